@@ -0,0 +1,200 @@
+//! 休息/睡眠判定模块
+//!
+//! 按趋势聚合段（1分钟）判定患者处于休息还是活动状态：心率较低、
+//! 心率波动小并且伪差少的时间段判定为休息。判定结果标记在该时间段的
+//! 趋势聚合段上，夜间报告据此分开统计静息心率和休息时段的血氧下降。
+
+use crate::types::{
+    ActivityPeriod, ActivityState, RestDetectionConfig, RestSummary, Spo2Dip, TrendBin,
+};
+use std::collections::VecDeque;
+
+/// 血氧比基线低该值（%）以上视为一次下降
+const SPO2_DIP_THRESHOLD: f64 = 3.0;
+
+/// 最多保留的时间段数量（24小时内每分钟切换一次的上限）
+const PERIOD_CAPACITY: usize = 24 * 60;
+
+/// 验证休息判定参数
+pub fn validate_config(config: &RestDetectionConfig) -> Result<(), String> {
+    if !config.max_heart_rate.is_finite() || !(30.0..=200.0).contains(&config.max_heart_rate) {
+        return Err("休息心率上限必须在30到200次/分之间".to_string());
+    }
+    if !config.max_heart_rate_range.is_finite() || config.max_heart_rate_range <= 0.0 {
+        return Err("心率波动上限必须大于0".to_string());
+    }
+    if !(0.0..=1.0).contains(&config.max_artifact_rate) {
+        return Err("伪差占比上限必须在0到1之间".to_string());
+    }
+    Ok(())
+}
+
+/// 休息/活动判定
+#[derive(Debug, Clone)]
+pub struct ActivityClassifier {
+    config: RestDetectionConfig,
+    /// 聚合段时长（毫秒），与趋势聚合一致
+    bin_ms: u64,
+    /// 正在累计的时间段起始时间戳
+    current_start: Option<u64>,
+    /// 当前时间段的采样数和伪差采样数
+    samples: u64,
+    artifacts: u64,
+    /// 按时间先后排列的休息和活动时间段，相邻的相同判定合并为一段
+    periods: VecDeque<ActivityPeriod>,
+}
+
+impl ActivityClassifier {
+    pub fn new(bin_ms: u64) -> Self {
+        Self {
+            config: RestDetectionConfig::default(),
+            bin_ms: bin_ms.max(1),
+            current_start: None,
+            samples: 0,
+            artifacts: 0,
+            periods: VecDeque::new(),
+        }
+    }
+
+    pub fn config(&self) -> RestDetectionConfig {
+        self.config.clone()
+    }
+
+    pub fn set_config(&mut self, config: RestDetectionConfig) {
+        self.config = config;
+    }
+
+    /// 累计一个采样
+    ///
+    /// # 返回值
+    /// 采样属于新的时间段时，返回上一个时间段的起始时间戳和伪差占比，由调用方判定
+    pub fn observe(&mut self, timestamp: u64, artifact: bool) -> Option<(u64, f64)> {
+        let start = timestamp - timestamp % self.bin_ms;
+        let finished = match self.current_start {
+            Some(current) if current == start => None,
+            Some(current) => {
+                let rate = self.artifacts as f64 / self.samples.max(1) as f64;
+                self.samples = 0;
+                self.artifacts = 0;
+                Some((current, rate))
+            }
+            None => None,
+        };
+        self.current_start = Some(start);
+        self.samples += 1;
+        if artifact {
+            self.artifacts += 1;
+        }
+        finished
+    }
+
+    /// 判定一个时间段并记录
+    ///
+    /// # 参数
+    /// * `start` - 时间段起始时间戳（毫秒）
+    /// * `artifact_rate` - 伪差采样占比
+    /// * `heart_rate` - 该时间段的心率聚合段，没有有效心率时无法判定
+    ///
+    /// # 返回值
+    /// 返回判定结果，无法判定时返回None
+    pub fn classify(
+        &mut self,
+        start: u64,
+        artifact_rate: f64,
+        heart_rate: Option<&TrendBin>,
+    ) -> Option<ActivityState> {
+        let heart_rate = heart_rate?;
+        let rest = heart_rate.mean <= self.config.max_heart_rate
+            && heart_rate.max - heart_rate.min <= self.config.max_heart_rate_range
+            && artifact_rate <= self.config.max_artifact_rate;
+        let state = if rest {
+            ActivityState::Rest
+        } else {
+            ActivityState::Active
+        };
+
+        let end = start + self.bin_ms;
+        match self.periods.back_mut() {
+            Some(period) if period.state == state && period.end_timestamp == start => {
+                period.end_timestamp = end;
+            }
+            _ => {
+                if self.periods.len() >= PERIOD_CAPACITY {
+                    self.periods.pop_front();
+                }
+                self.periods.push_back(ActivityPeriod {
+                    start_timestamp: start,
+                    end_timestamp: end,
+                    state,
+                });
+            }
+        }
+        Some(state)
+    }
+
+    /// 获取与时间范围有重叠的休息和活动时间段
+    pub fn periods(&self, start: u64, end: u64) -> Vec<ActivityPeriod> {
+        self.periods
+            .iter()
+            .filter(|p| p.end_timestamp > start && p.start_timestamp <= end)
+            .cloned()
+            .collect()
+    }
+}
+
+/// 已标记休息/活动的聚合段中指定状态的平均值
+fn mean_of(bins: &[TrendBin], state: ActivityState) -> Option<f64> {
+    let values: Vec<f64> = bins
+        .iter()
+        .filter(|b| b.activity == Some(state))
+        .map(|b| b.mean)
+        .collect();
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// 按休息和活动分开统计心率和血氧
+///
+/// # 参数
+/// * `heart_rate` - 时间范围内的心率聚合段
+/// * `spo2` - 时间范围内的血氧聚合段
+/// * `periods` - 时间范围内的休息和活动时间段
+pub fn rest_summary(
+    heart_rate: &[TrendBin],
+    spo2: &[TrendBin],
+    periods: Vec<ActivityPeriod>,
+) -> RestSummary {
+    let minutes = |state: ActivityState| {
+        heart_rate
+            .iter()
+            .filter(|b| b.activity == Some(state))
+            .count() as u64
+    };
+    let rest_spo2_baseline = mean_of(spo2, ActivityState::Rest);
+    let dips: Vec<&TrendBin> = match rest_spo2_baseline {
+        Some(baseline) => spo2
+            .iter()
+            .filter(|b| b.min <= baseline - SPO2_DIP_THRESHOLD)
+            .collect(),
+        None => Vec::new(),
+    };
+    RestSummary {
+        rest_minutes: minutes(ActivityState::Rest),
+        active_minutes: minutes(ActivityState::Active),
+        resting_heart_rate: mean_of(heart_rate, ActivityState::Rest),
+        active_heart_rate: mean_of(heart_rate, ActivityState::Active),
+        rest_spo2_baseline,
+        rest_spo2_dips: dips
+            .iter()
+            .filter(|b| b.activity == Some(ActivityState::Rest))
+            .map(|b| Spo2Dip {
+                timestamp: b.start_timestamp,
+                nadir: b.min,
+            })
+            .collect(),
+        active_spo2_dip_count: dips
+            .iter()
+            .filter(|b| b.activity == Some(ActivityState::Active))
+            .count(),
+        periods,
+    }
+}
@@ -0,0 +1,228 @@
+use crate::alarms::AlarmListener;
+use crate::ipc_schema;
+use crate::purge;
+use crate::types::{Alarm, AlarmPriority};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use tauri::Manager;
+
+/// 报警事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlarmEventKind {
+    /// 报警激活（包括优先级升高、静音结束后的重新通知）
+    Activated,
+    /// 报警解除
+    Resolved,
+}
+
+/// 持久化的报警事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+struct AlarmEvent {
+    session_id: String,
+    kind: AlarmEventKind,
+    timestamp: u64,
+    alarm: Alarm,
+}
+
+/// 报警历史记录（一次报警从激活到解除的完整过程）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct AlarmHistoryEntry {
+    /// 所属会话ID
+    pub session_id: String,
+    /// 报警ID（会话内唯一）
+    pub id: u64,
+    /// 报警条件标识
+    pub condition: String,
+    /// 相关体征
+    pub vital: String,
+    /// 最后记录的优先级
+    pub priority: AlarmPriority,
+    /// 报警描述
+    pub message: String,
+    /// 最后记录的数值
+    pub value: f64,
+    /// 报警阈值
+    pub limit: f64,
+    /// 激活时间戳（毫秒）
+    pub started_at: u64,
+    /// 解除时间戳（毫秒），尚未解除时为None
+    pub resolved_at: Option<u64>,
+    /// 持续时长（毫秒），尚未解除时为None
+    pub duration_ms: Option<u64>,
+    /// 是否已确认
+    pub acknowledged: bool,
+    /// 确认报警的操作员
+    pub acknowledged_by: Option<String>,
+}
+
+/// 报警历史存储，每个报警事件为一行JSON，只追加不修改
+#[derive(Debug, Clone)]
+pub struct AlarmHistoryStore {
+    data_file: PathBuf,
+}
+
+impl AlarmHistoryStore {
+    pub fn new(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("无法获取应用数据目录: {}", e))?;
+
+        let data_dir = app_data_dir.join("vital-signs");
+        if !data_dir.exists() {
+            fs::create_dir_all(&data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+        }
+
+        let data_file = data_dir.join("alarm_history.jsonl");
+
+        Ok(Self { data_file })
+    }
+
+    /// 追加一个报警事件
+    fn append(&self, event: &AlarmEvent) -> Result<(), String> {
+        let line =
+            serde_json::to_string(event).map_err(|e| format!("序列化报警事件失败: {}", e))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.data_file)
+            .map_err(|e| format!("打开报警历史失败: {}", e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("写入报警历史失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 不可恢复地删除指定会话的报警事件，其余事件保持原样
+    ///
+    /// # 返回值
+    /// 返回被删除的事件数
+    pub fn purge_sessions(&self, session_ids: &[String]) -> Result<usize, String> {
+        if session_ids.is_empty() || !self.data_file.exists() {
+            return Ok(0);
+        }
+
+        let content =
+            fs::read_to_string(&self.data_file).map_err(|e| format!("读取报警历史失败: {}", e))?;
+
+        let mut removed = 0;
+        let mut kept = String::new();
+        for line in content.lines() {
+            let purged = ipc_schema::from_stored_json::<AlarmEvent>(line)
+                .is_ok_and(|event| session_ids.contains(&event.session_id));
+            if purged {
+                removed += 1;
+            } else {
+                kept.push_str(line);
+                kept.push('\n');
+            }
+        }
+
+        if removed > 0 {
+            purge::secure_rewrite(&self.data_file, &kept)?;
+        }
+        Ok(removed)
+    }
+
+    /// 查询报警历史
+    ///
+    /// # 参数
+    /// * `start` - 起始时间戳（毫秒），与报警持续区间有重叠即返回
+    /// * `end` - 结束时间戳（毫秒）
+    /// * `session_id` - 只返回该会话的报警，省略时返回所有会话
+    ///
+    /// # 返回值
+    /// 返回按激活时间排列的报警记录
+    pub fn query(
+        &self,
+        start: Option<u64>,
+        end: Option<u64>,
+        session_id: Option<&str>,
+    ) -> Result<Vec<AlarmHistoryEntry>, String> {
+        if !self.data_file.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content =
+            fs::read_to_string(&self.data_file).map_err(|e| format!("读取报警历史失败: {}", e))?;
+
+        // 同一报警的多个事件合并为一条记录，后面的事件覆盖前面的状态
+        let mut entries: BTreeMap<(String, u64), AlarmHistoryEntry> = BTreeMap::new();
+        for event in content
+            .lines()
+            .filter_map(|line| ipc_schema::from_stored_json::<AlarmEvent>(line).ok())
+            .filter(|e| session_id.is_none_or(|id| e.session_id == id))
+        {
+            let resolved_at = (event.kind == AlarmEventKind::Resolved).then_some(event.timestamp);
+            let alarm = event.alarm;
+            entries.insert(
+                (event.session_id.clone(), alarm.id),
+                AlarmHistoryEntry {
+                    session_id: event.session_id,
+                    id: alarm.id,
+                    condition: alarm.condition,
+                    vital: alarm.vital,
+                    priority: alarm.priority,
+                    message: alarm.message,
+                    value: alarm.value,
+                    limit: alarm.limit,
+                    started_at: alarm.started_at,
+                    resolved_at,
+                    duration_ms: resolved_at.map(|r| r.saturating_sub(alarm.started_at)),
+                    acknowledged: alarm.acknowledged,
+                    acknowledged_by: alarm.acknowledged_by,
+                },
+            );
+        }
+
+        let mut entries: Vec<AlarmHistoryEntry> = entries
+            .into_values()
+            .filter(|e| end.is_none_or(|end| e.started_at <= end))
+            .filter(|e| start.is_none_or(|s| e.resolved_at.is_none_or(|r| r >= s)))
+            .collect();
+        entries.sort_by_key(|e| e.started_at);
+        Ok(entries)
+    }
+}
+
+/// 将报警激活/解除事件写入报警历史的监听器
+pub struct AlarmHistoryRecorder {
+    store: AlarmHistoryStore,
+    session_id: String,
+}
+
+impl AlarmHistoryRecorder {
+    pub fn new(store: AlarmHistoryStore, session_id: String) -> Self {
+        Self { store, session_id }
+    }
+
+    fn record(&self, kind: AlarmEventKind, alarm: &Alarm, timestamp: u64) {
+        let event = AlarmEvent {
+            session_id: self.session_id.clone(),
+            kind,
+            timestamp,
+            alarm: alarm.clone(),
+        };
+        if let Err(e) = self.store.append(&event) {
+            eprintln!("[AlarmHistory] {}", e);
+        }
+    }
+}
+
+impl AlarmListener for AlarmHistoryRecorder {
+    fn on_alarm_raised(&mut self, alarm: &Alarm) {
+        let timestamp = alarm.escalated_at.unwrap_or(alarm.started_at);
+        self.record(AlarmEventKind::Activated, alarm, timestamp);
+    }
+
+    fn on_alarm_cleared(&mut self, alarm: &Alarm) {
+        let timestamp = chrono::Utc::now().timestamp_millis() as u64;
+        self.record(AlarmEventKind::Resolved, alarm, timestamp);
+    }
+}
@@ -0,0 +1,968 @@
+//! 报警模块
+//!
+//! 报警引擎维护当前处于激活状态的报警，每个报警条件（例如 `bp_hypertension`）
+//! 同一时间最多只有一个激活的报警。各项体征的报警规则负责在条件满足时
+//! 调用 `raise`，条件解除时调用 `clear`。报警激活和解除会通知已注册的
+//! `AlarmListener`（例如桌面通知）。
+//!
+//! 静音期间激活或已激活的报警不通知监听器；静音到期，或报警数值相对静音时
+//! 明显恶化，报警会重新通知。
+
+use crate::types::{
+    Alarm, AlarmLevel, AlarmPriority, AlarmSuppression, BloodPressureReading, BpAlarmConfig,
+    CuffStatus, DerivedAlarmConfig, DerivedMetrics, EscalationConfig, LimitDirection,
+    MotionSuppressionConfig, MotionSuppressionMode, TemperatureAlarmConfig, VitalAlarmLimits,
+};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// 体温上升速率计算的采样间隔（毫秒），体温变化缓慢，无需逐个采样保存
+const TEMP_RISE_SAMPLE_INTERVAL_MS: u64 = 10_000;
+
+/// 支持分级限值报警的体征
+const LIMIT_VITALS: [&str; 4] = ["heart_rate", "spo2", "respiration_rate", "etco2"];
+
+/// 报警延迟上限（毫秒）
+const MAX_ALARM_DELAY_MS: u64 = 120_000;
+
+/// 静音时长上限（毫秒）
+const MAX_SILENCE_MS: u64 = 10 * 60_000;
+
+/// 运动抑制延迟上限（毫秒）
+const MAX_MOTION_DELAY_MS: u64 = 120_000;
+
+/// 最多保留的报警抑制记录数量
+const SUPPRESSION_LOG_CAPACITY: usize = 500;
+
+/// 静音中的报警数值偏离阈值的程度比静音时增加超过阈值的该比例，视为恶化
+const WORSENING_MARGIN: f64 = 0.1;
+
+/// 报警触发描述
+#[derive(Debug, Clone)]
+pub struct AlarmTrigger<'a> {
+    /// 报警条件标识
+    pub condition: &'a str,
+    /// 相关体征
+    pub vital: &'a str,
+    /// 优先级
+    pub priority: AlarmPriority,
+    /// 报警描述
+    pub message: String,
+    /// 触发报警的数值
+    pub value: f64,
+    /// 报警阈值
+    pub limit: f64,
+}
+
+/// 报警事件监听器
+///
+/// 在数据处理线程中被调用，实现应尽快返回。
+pub trait AlarmListener: Send {
+    /// 报警激活时调用，已激活报警的数值更新不会再次调用
+    fn on_alarm_raised(&mut self, alarm: &Alarm);
+
+    /// 报警解除时调用
+    fn on_alarm_cleared(&mut self, _alarm: &Alarm) {}
+}
+
+/// 体征的显示名称
+fn vital_label(vital: &str) -> &str {
+    match vital {
+        "heart_rate" => "心率",
+        "spo2" => "血氧",
+        "respiration_rate" => "呼吸频率",
+        "etco2" => "EtCO2",
+        other => other,
+    }
+}
+
+/// 默认的分级报警限值
+fn default_limits() -> Vec<VitalAlarmLimits> {
+    let limits = |vital: &str, direction, levels: &[(AlarmPriority, f64)]| VitalAlarmLimits {
+        vital: vital.to_string(),
+        direction,
+        levels: levels
+            .iter()
+            .map(|&(priority, threshold)| AlarmLevel {
+                priority,
+                threshold,
+            })
+            .collect(),
+    };
+    use AlarmPriority::{High, Medium};
+    use LimitDirection::{High as Above, Low as Below};
+
+    vec![
+        limits("spo2", Below, &[(Medium, 92.0), (High, 85.0)]),
+        limits("heart_rate", Above, &[(Medium, 120.0), (High, 150.0)]),
+        limits("heart_rate", Below, &[(Medium, 50.0), (High, 40.0)]),
+        limits("respiration_rate", Above, &[(Medium, 25.0), (High, 30.0)]),
+        limits("respiration_rate", Below, &[(Medium, 8.0), (High, 5.0)]),
+    ]
+}
+
+/// 默认的报警延迟（毫秒），用于过滤单个伪差采样或一次漏检心搏引起的报警
+fn default_delays() -> BTreeMap<String, u64> {
+    [
+        ("spo2_low", 10_000),
+        ("heart_rate_high", 5_000),
+        ("heart_rate_low", 5_000),
+        ("respiration_rate_high", 10_000),
+        ("respiration_rate_low", 10_000),
+        ("etco2_high", 10_000),
+        ("etco2_low", 10_000),
+    ]
+    .into_iter()
+    .map(|(condition, delay)| (condition.to_string(), delay))
+    .collect()
+}
+
+/// 验证静音时长（毫秒）
+pub fn validate_silence_duration(duration_ms: u64) -> Result<(), String> {
+    if duration_ms == 0 || duration_ms > MAX_SILENCE_MS {
+        return Err(format!("静音时长必须在1到{}秒之间", MAX_SILENCE_MS / 1000));
+    }
+    Ok(())
+}
+
+/// 验证分级报警限值
+fn validate_limits(limits: &[VitalAlarmLimits]) -> Result<(), String> {
+    for (i, entry) in limits.iter().enumerate() {
+        if !LIMIT_VITALS.contains(&entry.vital.as_str()) {
+            return Err(format!("体征不支持限值报警: {}", entry.vital));
+        }
+        if limits[..i]
+            .iter()
+            .any(|e| e.vital == entry.vital && e.direction == entry.direction)
+        {
+            return Err(format!("体征 {} 的同方向限值重复", entry.vital));
+        }
+        if entry.levels.is_empty() {
+            return Err(format!("体征 {} 至少需要一个报警级别", entry.vital));
+        }
+
+        let mut levels = entry.levels.clone();
+        levels.sort_by_key(|l| l.priority);
+        for pair in levels.windows(2) {
+            if pair[0].priority == pair[1].priority {
+                return Err(format!("体征 {} 的报警级别重复", entry.vital));
+            }
+            let ordered = match entry.direction {
+                LimitDirection::Low => pair[1].threshold < pair[0].threshold,
+                LimitDirection::High => pair[1].threshold > pair[0].threshold,
+            };
+            if !ordered {
+                return Err(format!(
+                    "体征 {} 的高优先级阈值必须比低优先级阈值更严格",
+                    entry.vital
+                ));
+            }
+        }
+        if levels.iter().any(|l| !l.threshold.is_finite()) {
+            return Err(format!("体征 {} 的阈值无效", entry.vital));
+        }
+    }
+    Ok(())
+}
+
+/// 报警引擎
+pub struct AlarmEngine {
+    /// 当前激活的报警，键为报警条件
+    active: BTreeMap<String, Alarm>,
+    /// 下一个报警ID
+    next_id: u64,
+    /// 血压报警配置
+    bp_config: BpAlarmConfig,
+    /// 连续高于高血压阈值的测量次数
+    bp_high_readings: u32,
+    /// 连续低于低血压阈值的测量次数
+    bp_low_readings: u32,
+    /// 衍生风险指标报警配置
+    derived_config: DerivedAlarmConfig,
+    /// 体温报警配置
+    temp_config: TemperatureAlarmConfig,
+    /// 用于计算体温上升速率的体温记录(时间戳, 体温)
+    temp_history: VecDeque<(u64, f64)>,
+    /// 报警事件监听器
+    listeners: Vec<Box<dyn AlarmListener>>,
+    /// 分级限值报警配置
+    limits: Vec<VitalAlarmLimits>,
+    /// 报警升级配置
+    escalation: EscalationConfig,
+    /// 各报警条件的延迟（毫秒），未配置的条件立即激活
+    delays: BTreeMap<String, u64>,
+    /// 延迟中的报警条件首次满足的时间戳（毫秒），键为报警条件
+    pending: BTreeMap<String, u64>,
+    /// 静音截止时间戳（毫秒），未静音时为None
+    silenced_until: Option<u64>,
+    /// 静音中报警的基准偏离量（数值超出阈值的程度），键为报警条件
+    silence_baselines: BTreeMap<String, f64>,
+    /// 运动感知报警抑制配置
+    motion_config: MotionSuppressionConfig,
+    /// 当前是否检测到运动/伪差
+    motion: bool,
+    /// 本次运动期间已抑制的报警条件，每个条件每次运动只记录一次
+    motion_suppressed: BTreeSet<String>,
+    /// 报警抑制记录
+    suppression_log: VecDeque<AlarmSuppression>,
+}
+
+impl Default for AlarmEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlarmEngine {
+    pub fn new() -> Self {
+        Self {
+            active: BTreeMap::new(),
+            next_id: 1,
+            bp_config: BpAlarmConfig::default(),
+            bp_high_readings: 0,
+            bp_low_readings: 0,
+            derived_config: DerivedAlarmConfig::default(),
+            temp_config: TemperatureAlarmConfig::default(),
+            temp_history: VecDeque::new(),
+            listeners: Vec::new(),
+            limits: default_limits(),
+            escalation: EscalationConfig::default(),
+            delays: default_delays(),
+            pending: BTreeMap::new(),
+            silenced_until: None,
+            silence_baselines: BTreeMap::new(),
+            motion_config: MotionSuppressionConfig::default(),
+            motion: false,
+            motion_suppressed: BTreeSet::new(),
+            suppression_log: VecDeque::new(),
+        }
+    }
+
+    /// 更新运动/伪差检测状态
+    pub fn set_motion(&mut self, motion: bool) {
+        if self.motion && !motion {
+            self.motion_suppressed.clear();
+        }
+        self.motion = motion;
+    }
+
+    /// 记录一次报警抑制，同一条件在一次运动期间只记录一次
+    fn log_suppression(&mut self, trigger: &AlarmTrigger, timestamp: u64) {
+        if !self.motion_suppressed.insert(trigger.condition.to_string()) {
+            return;
+        }
+        let mode = self.motion_config.mode;
+        println!(
+            "[AlarmEngine] 运动期间抑制报警: {} ({:?}, {:?})",
+            trigger.message, trigger.priority, mode
+        );
+        if self.suppression_log.len() >= SUPPRESSION_LOG_CAPACITY {
+            self.suppression_log.pop_front();
+        }
+        self.suppression_log.push_back(AlarmSuppression {
+            timestamp,
+            condition: trigger.condition.to_string(),
+            message: trigger.message.clone(),
+            priority: trigger.priority,
+            mode,
+        });
+    }
+
+    /// 运动期间是否抑制该报警
+    fn motion_suppresses(&self, trigger: &AlarmTrigger) -> bool {
+        self.motion_config.enabled && self.motion && trigger.priority < AlarmPriority::High
+    }
+
+    /// 激活报警，条件已激活时更新当前值和优先级
+    ///
+    /// 优先级升高时重新通知监听器；因未确认而升级的优先级不会被较低的触发降级。
+    ///
+    /// # 参数
+    /// * `trigger` - 报警触发描述
+    /// * `timestamp` - 当前时间戳（毫秒）
+    pub fn raise(&mut self, mut trigger: AlarmTrigger, timestamp: u64) {
+        let suppressed = self.motion_suppresses(&trigger);
+        if suppressed
+            && self.motion_config.mode == MotionSuppressionMode::Downgrade
+            && trigger.priority == AlarmPriority::Medium
+        {
+            self.log_suppression(&trigger, timestamp);
+            trigger.priority = AlarmPriority::Low;
+        }
+
+        if let Some(alarm) = self.active.get_mut(trigger.condition) {
+            alarm.value = trigger.value;
+            alarm.message = trigger.message;
+
+            let keep_escalated = alarm.escalated_at.is_some() && trigger.priority < alarm.priority;
+            if trigger.priority != alarm.priority && !keep_escalated {
+                let upgraded = trigger.priority > alarm.priority;
+                alarm.priority = trigger.priority;
+                alarm.limit = trigger.limit;
+                if upgraded && !alarm.silenced {
+                    println!(
+                        "[AlarmEngine] 报警优先级升高: {} ({:?})",
+                        alarm.message, alarm.priority
+                    );
+                    for listener in &mut self.listeners {
+                        listener.on_alarm_raised(alarm);
+                    }
+                }
+            }
+
+            // 静音中的报警数值明显恶化时重新报警
+            let worsened = alarm.silenced
+                && self
+                    .silence_baselines
+                    .get(trigger.condition)
+                    .is_some_and(|&baseline| {
+                        Self::deviation(alarm) > baseline + alarm.limit.abs() * WORSENING_MARGIN
+                    });
+            if worsened {
+                println!(
+                    "[AlarmEngine] 静音中的报警恶化，重新报警: {}",
+                    alarm.message
+                );
+                alarm.silenced = false;
+                self.silence_baselines.remove(trigger.condition);
+                for listener in &mut self.listeners {
+                    listener.on_alarm_raised(alarm);
+                }
+            }
+            return;
+        }
+
+        // 报警延迟：条件需持续满足 `delay_ms` 才激活，运动期间按配置额外延迟
+        let delay_ms = self.delays.get(trigger.condition).copied().unwrap_or(0);
+        let motion_delay_ms =
+            if suppressed && self.motion_config.mode == MotionSuppressionMode::Delay {
+                self.motion_config.extra_delay_ms
+            } else {
+                0
+            };
+        if delay_ms + motion_delay_ms > 0 {
+            let since = *self
+                .pending
+                .entry(trigger.condition.to_string())
+                .or_insert(timestamp);
+            let elapsed = timestamp.saturating_sub(since);
+            if elapsed < delay_ms + motion_delay_ms {
+                if elapsed >= delay_ms {
+                    self.log_suppression(&trigger, timestamp);
+                }
+                return;
+            }
+            self.pending.remove(trigger.condition);
+        }
+
+        println!(
+            "[AlarmEngine] 报警激活: {} ({}, {:?})",
+            trigger.message, trigger.condition, trigger.priority
+        );
+        let alarm = Alarm {
+            id: self.next_id,
+            condition: trigger.condition.to_string(),
+            vital: trigger.vital.to_string(),
+            priority: trigger.priority,
+            message: trigger.message,
+            value: trigger.value,
+            limit: trigger.limit,
+            started_at: timestamp,
+            acknowledged: false,
+            acknowledged_by: None,
+            acknowledged_at: None,
+            silenced: self.silenced_until.is_some_and(|until| timestamp < until),
+            escalated_at: None,
+            delay_ms,
+        };
+        self.next_id += 1;
+        if alarm.silenced {
+            self.silence_baselines
+                .insert(alarm.condition.clone(), Self::deviation(&alarm));
+        } else {
+            for listener in &mut self.listeners {
+                listener.on_alarm_raised(&alarm);
+            }
+        }
+        self.active.insert(alarm.condition.clone(), alarm);
+    }
+
+    /// 报警数值偏离阈值的程度
+    fn deviation(alarm: &Alarm) -> f64 {
+        (alarm.value - alarm.limit).abs()
+    }
+
+    /// 推进静音和升级计时
+    ///
+    /// 静音到期时重新通知仍处于激活状态的报警；未确认的报警持续超过
+    /// `unacknowledged_ms` 后优先级升高一级并重新通知。
+    ///
+    /// # 参数
+    /// * `timestamp` - 当前时间戳（毫秒）
+    pub fn tick(&mut self, timestamp: u64) {
+        if self.escalation.enabled {
+            self.escalate(timestamp);
+        }
+
+        let Some(until) = self.silenced_until else {
+            return;
+        };
+        if timestamp < until {
+            return;
+        }
+
+        println!("[AlarmEngine] 报警静音到期");
+        self.silenced_until = None;
+        self.silence_baselines.clear();
+        for alarm in self.active.values_mut().filter(|a| a.silenced) {
+            alarm.silenced = false;
+            for listener in &mut self.listeners {
+                listener.on_alarm_raised(alarm);
+            }
+        }
+    }
+
+    /// 升级长时间未确认的报警
+    fn escalate(&mut self, timestamp: u64) {
+        let threshold = self.escalation.unacknowledged_ms;
+        for alarm in self.active.values_mut() {
+            let since = alarm.escalated_at.unwrap_or(alarm.started_at);
+            if alarm.acknowledged
+                || alarm.priority == AlarmPriority::High
+                || timestamp.saturating_sub(since) < threshold
+            {
+                continue;
+            }
+
+            alarm.priority = match alarm.priority {
+                AlarmPriority::Low => AlarmPriority::Medium,
+                _ => AlarmPriority::High,
+            };
+            alarm.escalated_at = Some(timestamp);
+            println!(
+                "[AlarmEngine] 报警未确认，升级为 {:?}: {}",
+                alarm.priority, alarm.message
+            );
+            if !alarm.silenced {
+                for listener in &mut self.listeners {
+                    listener.on_alarm_raised(alarm);
+                }
+            }
+        }
+    }
+
+    /// 设置分级限值报警配置
+    pub fn set_limits(&mut self, limits: Vec<VitalAlarmLimits>) -> Result<(), String> {
+        validate_limits(&limits)?;
+        self.limits = limits;
+        Ok(())
+    }
+
+    /// 获取分级限值报警配置
+    pub fn get_limits(&self) -> Vec<VitalAlarmLimits> {
+        self.limits.clone()
+    }
+
+    /// 设置报警延迟
+    ///
+    /// # 参数
+    /// * `condition` - 报警条件标识，例如 `spo2_low`
+    /// * `delay_ms` - 条件需持续满足的时长（毫秒），0表示立即激活
+    pub fn set_delay(&mut self, condition: &str, delay_ms: u64) -> Result<(), String> {
+        if condition.trim().is_empty() {
+            return Err("报警条件不能为空".to_string());
+        }
+        if delay_ms > MAX_ALARM_DELAY_MS {
+            return Err(format!("报警延迟不能超过{}秒", MAX_ALARM_DELAY_MS / 1000));
+        }
+        if delay_ms == 0 {
+            self.delays.remove(condition);
+        } else {
+            self.delays.insert(condition.to_string(), delay_ms);
+        }
+        self.pending.remove(condition);
+        Ok(())
+    }
+
+    /// 获取各报警条件的延迟（毫秒）
+    pub fn get_delays(&self) -> BTreeMap<String, u64> {
+        self.delays.clone()
+    }
+
+    /// 设置运动感知报警抑制配置
+    pub fn set_motion_suppression(
+        &mut self,
+        config: MotionSuppressionConfig,
+    ) -> Result<(), String> {
+        if config.extra_delay_ms > MAX_MOTION_DELAY_MS {
+            return Err(format!(
+                "运动抑制延迟不能超过{}秒",
+                MAX_MOTION_DELAY_MS / 1000
+            ));
+        }
+        println!(
+            "[AlarmEngine] 运动感知报警抑制: 启用={}，方式={:?}",
+            config.enabled, config.mode
+        );
+        self.motion_config = config;
+        Ok(())
+    }
+
+    /// 获取运动感知报警抑制配置
+    pub fn get_motion_suppression(&self) -> MotionSuppressionConfig {
+        self.motion_config.clone()
+    }
+
+    /// 获取报警抑制记录，按时间先后排列
+    pub fn suppression_log(&self) -> Vec<AlarmSuppression> {
+        self.suppression_log.iter().cloned().collect()
+    }
+
+    /// 设置报警升级配置
+    pub fn set_escalation(&mut self, config: EscalationConfig) -> Result<(), String> {
+        if config.enabled && config.unacknowledged_ms < 10_000 {
+            return Err("升级等待时长不能短于10秒".to_string());
+        }
+        self.escalation = config;
+        Ok(())
+    }
+
+    /// 获取报警升级配置
+    pub fn get_escalation(&self) -> EscalationConfig {
+        self.escalation.clone()
+    }
+
+    /// 按分级限值评估体征报警
+    ///
+    /// 每项体征每个方向对应一个报警条件（例如 `spo2_low`），
+    /// 优先级取数值越过的最严格一级；数值不可用或回到限值内时解除报警。
+    ///
+    /// # 参数
+    /// * `values` - (体征名, 当前值)，无有效值时为None
+    /// * `timestamp` - 当前时间戳（毫秒）
+    pub fn evaluate_limits(&mut self, values: &[(&str, Option<f64>)], timestamp: u64) {
+        // 每个采样都会调用，暂时取出限值并复用条件名缓冲区，避免逐项分配
+        let limits = std::mem::take(&mut self.limits);
+        let mut condition = String::new();
+        for entry in &limits {
+            condition.clear();
+            condition.push_str(&entry.vital);
+            condition.push_str(match entry.direction {
+                LimitDirection::Low => "_low",
+                LimitDirection::High => "_high",
+            });
+            let value = values
+                .iter()
+                .find(|(vital, _)| *vital == entry.vital)
+                .and_then(|(_, value)| *value);
+            let Some(value) = value else {
+                self.clear(&condition);
+                continue;
+            };
+
+            let breached = entry
+                .levels
+                .iter()
+                .filter(|l| match entry.direction {
+                    LimitDirection::Low => value < l.threshold,
+                    LimitDirection::High => value > l.threshold,
+                })
+                .max_by_key(|l| l.priority);
+            let Some(level) = breached else {
+                self.clear(&condition);
+                continue;
+            };
+
+            let direction = match entry.direction {
+                LimitDirection::Low => "过低",
+                LimitDirection::High => "过高",
+            };
+            self.raise(
+                AlarmTrigger {
+                    condition: &condition,
+                    vital: &entry.vital,
+                    priority: level.priority,
+                    message: format!("{}{}: {:.1}", vital_label(&entry.vital), direction, value),
+                    value,
+                    limit: level.threshold,
+                },
+                timestamp,
+            );
+        }
+        self.limits = limits;
+    }
+
+    /// 静音报警
+    ///
+    /// 静音对当前激活的报警和静音期间新激活的报警都有效，到期后自动解除。
+    ///
+    /// # 参数
+    /// * `duration_ms` - 静音时长（毫秒）
+    /// * `timestamp` - 当前时间戳（毫秒）
+    ///
+    /// # 返回值
+    /// 返回被静音的报警
+    pub fn silence(&mut self, duration_ms: u64, timestamp: u64) -> Result<Vec<Alarm>, String> {
+        validate_silence_duration(duration_ms)?;
+
+        self.silenced_until = Some(timestamp + duration_ms);
+        for alarm in self.active.values_mut() {
+            if !alarm.silenced {
+                alarm.silenced = true;
+                self.silence_baselines
+                    .insert(alarm.condition.clone(), Self::deviation(alarm));
+            }
+        }
+        println!("[AlarmEngine] 报警静音 {} 秒", duration_ms / 1000);
+        Ok(self.active_alarms())
+    }
+
+    /// 获取静音截止时间戳（毫秒），未静音时返回None
+    pub fn silenced_until(&self) -> Option<u64> {
+        self.silenced_until
+    }
+
+    /// 确认报警
+    ///
+    /// # 参数
+    /// * `id` - 报警ID
+    /// * `operator` - 确认报警的操作员
+    /// * `timestamp` - 当前时间戳（毫秒）
+    ///
+    /// # 返回值
+    /// 返回确认后的报警，报警不存在或已解除时返回错误
+    pub fn acknowledge(
+        &mut self,
+        id: u64,
+        operator: &str,
+        timestamp: u64,
+    ) -> Result<Alarm, String> {
+        let alarm = self
+            .active
+            .values_mut()
+            .find(|a| a.id == id)
+            .ok_or_else(|| format!("报警不存在或已解除: {}", id))?;
+        alarm.acknowledged = true;
+        alarm.acknowledged_by = Some(operator.to_string());
+        alarm.acknowledged_at = Some(timestamp);
+        println!("[AlarmEngine] 报警已确认: {} ({})", alarm.message, operator);
+        Ok(alarm.clone())
+    }
+
+    /// 注册报警事件监听器
+    pub fn add_listener(&mut self, listener: Box<dyn AlarmListener>) {
+        self.listeners.push(listener);
+    }
+
+    /// 解除报警
+    ///
+    /// # 返回值
+    /// 返回被解除的报警，条件未激活时返回None
+    pub fn clear(&mut self, condition: &str) -> Option<Alarm> {
+        self.pending.remove(condition);
+        let alarm = self.active.remove(condition)?;
+        self.silence_baselines.remove(condition);
+        println!("[AlarmEngine] 报警解除: {} ({})", alarm.message, condition);
+        for listener in &mut self.listeners {
+            listener.on_alarm_cleared(&alarm);
+        }
+        Some(alarm)
+    }
+
+    /// 解除指定体征的所有报警，用于关闭通道时
+    pub fn clear_vitals(&mut self, vitals: &[&str]) {
+        let conditions: Vec<String> = self
+            .active
+            .values()
+            .filter(|alarm| vitals.contains(&alarm.vital.as_str()))
+            .map(|alarm| alarm.condition.clone())
+            .collect();
+        for condition in conditions {
+            self.clear(&condition);
+        }
+    }
+
+    /// 获取当前激活的报警，按优先级从高到低、激活时间先后排列
+    pub fn active_alarms(&self) -> Vec<Alarm> {
+        let mut alarms: Vec<Alarm> = self.active.values().cloned().collect();
+        alarms.sort_by_key(|a| (std::cmp::Reverse(a.priority), a.started_at));
+        alarms
+    }
+
+    /// 设置血压报警配置
+    pub fn set_bp_config(&mut self, config: BpAlarmConfig) -> Result<(), String> {
+        if config.sustained_readings == 0 {
+            return Err("持续测量次数必须大于0".to_string());
+        }
+        if config.hypotension_systolic >= config.hypertension_systolic {
+            return Err("低血压阈值必须低于高血压阈值".to_string());
+        }
+        self.bp_config = config;
+        self.bp_high_readings = 0;
+        self.bp_low_readings = 0;
+        Ok(())
+    }
+
+    /// 获取血压报警配置
+    pub fn get_bp_config(&self) -> BpAlarmConfig {
+        self.bp_config.clone()
+    }
+
+    /// 设置衍生风险指标报警配置
+    pub fn set_derived_config(&mut self, config: DerivedAlarmConfig) -> Result<(), String> {
+        let thresholds = [
+            config.shock_index_high,
+            config.modified_shock_index_high,
+            config.rate_pressure_product_high,
+        ];
+        if thresholds
+            .iter()
+            .flatten()
+            .any(|t| !t.is_finite() || *t <= 0.0)
+        {
+            return Err("衍生指标报警阈值必须大于0".to_string());
+        }
+        self.derived_config = config;
+        Ok(())
+    }
+
+    /// 获取衍生风险指标报警配置
+    pub fn get_derived_config(&self) -> DerivedAlarmConfig {
+        self.derived_config.clone()
+    }
+
+    /// 评估衍生风险指标报警
+    ///
+    /// # 参数
+    /// * `metrics` - 当前衍生指标，心率或血压不可用时为None（解除相关报警）
+    /// * `timestamp` - 当前时间戳（毫秒）
+    pub fn evaluate_derived_metrics(&mut self, metrics: Option<&DerivedMetrics>, timestamp: u64) {
+        let config = self.derived_config.clone();
+        let checks = [
+            (
+                "shock_index_high",
+                "shock_index",
+                "休克指数过高",
+                config.shock_index_high,
+                metrics.map(|m| m.shock_index),
+            ),
+            (
+                "modified_shock_index_high",
+                "modified_shock_index",
+                "改良休克指数过高",
+                config.modified_shock_index_high,
+                metrics.map(|m| m.modified_shock_index),
+            ),
+            (
+                "rate_pressure_product_high",
+                "rate_pressure_product",
+                "心率收缩压乘积过高",
+                config.rate_pressure_product_high,
+                metrics.map(|m| m.rate_pressure_product),
+            ),
+        ];
+
+        for (condition, vital, label, limit, value) in checks {
+            match (limit, value) {
+                (Some(limit), Some(value)) if value >= limit => self.raise(
+                    AlarmTrigger {
+                        condition,
+                        vital,
+                        priority: AlarmPriority::Medium,
+                        message: format!("{}: {:.2}", label, value),
+                        value,
+                        limit,
+                    },
+                    timestamp,
+                ),
+                _ => {
+                    self.clear(condition);
+                }
+            }
+        }
+    }
+
+    /// 按一次血压测量结果评估持续性高血压/低血压报警
+    ///
+    /// 连续 `sustained_readings` 次超出阈值才报警，单次异常不报警；
+    /// 一次正常测量即解除报警。袖带状态异常的测量不参与评估。
+    pub fn evaluate_blood_pressure(&mut self, reading: &BloodPressureReading) {
+        if reading.cuff_status != CuffStatus::Ok {
+            return;
+        }
+        let config = self.bp_config.clone();
+
+        let high = reading.systolic >= config.hypertension_systolic
+            || reading.diastolic >= config.hypertension_diastolic;
+        let low = reading.systolic <= config.hypotension_systolic
+            || reading.mean_arterial_pressure <= config.hypotension_map;
+
+        self.bp_high_readings = if high { self.bp_high_readings + 1 } else { 0 };
+        self.bp_low_readings = if low { self.bp_low_readings + 1 } else { 0 };
+
+        if self.bp_high_readings >= config.sustained_readings {
+            self.raise(
+                AlarmTrigger {
+                    condition: "bp_hypertension",
+                    vital: "blood_pressure",
+                    priority: AlarmPriority::Medium,
+                    message: format!(
+                        "持续性高血压: {}/{} mmHg",
+                        reading.systolic, reading.diastolic
+                    ),
+                    value: reading.systolic as f64,
+                    limit: config.hypertension_systolic as f64,
+                },
+                reading.timestamp,
+            );
+        } else if !high {
+            self.clear("bp_hypertension");
+        }
+
+        if self.bp_low_readings >= config.sustained_readings {
+            self.raise(
+                AlarmTrigger {
+                    condition: "bp_hypotension",
+                    vital: "blood_pressure",
+                    priority: AlarmPriority::Medium,
+                    message: format!(
+                        "持续性低血压: {}/{} mmHg (平均压 {:.0})",
+                        reading.systolic, reading.diastolic, reading.mean_arterial_pressure
+                    ),
+                    value: reading.systolic as f64,
+                    limit: config.hypotension_systolic as f64,
+                },
+                reading.timestamp,
+            );
+        } else if !low {
+            self.clear("bp_hypotension");
+        }
+    }
+
+    /// 设置体温报警配置
+    pub fn set_temp_config(&mut self, config: TemperatureAlarmConfig) -> Result<(), String> {
+        if !config.fever_trigger.is_finite() || !config.fever_clear.is_finite() {
+            return Err("发热阈值无效".to_string());
+        }
+        if config.fever_clear >= config.fever_trigger {
+            return Err("发热解除阈值必须低于触发阈值".to_string());
+        }
+        if !config.max_rise_per_hour.is_finite() || config.max_rise_per_hour <= 0.0 {
+            return Err("升温速率阈值必须大于0".to_string());
+        }
+        if config.rise_window_ms < 2 * TEMP_RISE_SAMPLE_INTERVAL_MS {
+            return Err(format!(
+                "升温速率窗口不能短于{}秒",
+                2 * TEMP_RISE_SAMPLE_INTERVAL_MS / 1000
+            ));
+        }
+        self.temp_config = config;
+        self.temp_history.clear();
+        Ok(())
+    }
+
+    /// 获取体温报警配置
+    pub fn get_temp_config(&self) -> TemperatureAlarmConfig {
+        self.temp_config.clone()
+    }
+
+    /// 评估发热和升温速率报警
+    ///
+    /// 发热报警带滞回：升至 `fever_trigger` 触发，降至 `fever_clear` 以下才解除，
+    /// 避免阶梯状的平滑体温在阈值附近反复触发。升温速率按窗口首尾体温计算，
+    /// 窗口内数据不足一半时不评估。
+    ///
+    /// # 参数
+    /// * `temperature` - 一个完整窗口的平滑体温（°C，去除极值后的均值），无有效体温时为None
+    /// * `timestamp` - 当前时间戳（毫秒）
+    pub fn evaluate_temperature(&mut self, temperature: Option<f64>, timestamp: u64) {
+        let Some(temperature) = temperature else {
+            self.temp_history.clear();
+            self.clear("fever");
+            self.clear("temperature_rising");
+            return;
+        };
+        let config = self.temp_config.clone();
+
+        if temperature >= config.fever_trigger {
+            self.raise(
+                AlarmTrigger {
+                    condition: "fever",
+                    vital: "temperature",
+                    priority: AlarmPriority::Medium,
+                    message: format!("发热: {:.1} °C", temperature),
+                    value: temperature,
+                    limit: config.fever_trigger,
+                },
+                timestamp,
+            );
+        } else if temperature < config.fever_clear {
+            self.clear("fever");
+        } else if let Some(alarm) = self.active.get_mut("fever") {
+            // 滞回区间内保持报警，只更新当前值
+            alarm.value = temperature;
+        }
+
+        let sample_due = self
+            .temp_history
+            .back()
+            .is_none_or(|&(ts, _)| timestamp.saturating_sub(ts) >= TEMP_RISE_SAMPLE_INTERVAL_MS);
+        if !sample_due {
+            return;
+        }
+        self.temp_history.push_back((timestamp, temperature));
+        while self
+            .temp_history
+            .front()
+            .is_some_and(|&(ts, _)| timestamp.saturating_sub(ts) > config.rise_window_ms)
+        {
+            self.temp_history.pop_front();
+        }
+
+        let (start_ts, start_temp) = self.temp_history[0];
+        let span_ms = timestamp.saturating_sub(start_ts);
+        if span_ms < config.rise_window_ms / 2 {
+            return;
+        }
+        let rise_per_hour = (temperature - start_temp) * 3_600_000.0 / span_ms as f64;
+        if rise_per_hour >= config.max_rise_per_hour {
+            self.raise(
+                AlarmTrigger {
+                    condition: "temperature_rising",
+                    vital: "temperature",
+                    priority: AlarmPriority::Low,
+                    message: format!("体温快速上升: {:.1} °C/小时", rise_per_hour),
+                    value: rise_per_hour,
+                    limit: config.max_rise_per_hour,
+                },
+                timestamp,
+            );
+        } else if rise_per_hour < config.max_rise_per_hour / 2.0 {
+            self.clear("temperature_rising");
+        }
+    }
+
+    /// 评估呼吸暂停报警（高优先级）
+    ///
+    /// # 参数
+    /// * `apnea_since` - 呼吸暂停开始时间戳（即最后一次呼吸），未处于呼吸暂停时为None
+    /// * `threshold_ms` - 呼吸暂停判定时长（毫秒）
+    /// * `timestamp` - 当前时间戳（毫秒）
+    pub fn evaluate_apnea(&mut self, apnea_since: Option<u64>, threshold_ms: u64, timestamp: u64) {
+        let Some(since) = apnea_since else {
+            self.clear("apnea");
+            return;
+        };
+        let seconds = timestamp.saturating_sub(since) as f64 / 1000.0;
+        self.raise(
+            AlarmTrigger {
+                condition: "apnea",
+                vital: "respiration",
+                priority: AlarmPriority::High,
+                message: format!("呼吸暂停: {:.0} 秒无呼吸", seconds),
+                value: seconds,
+                limit: threshold_ms as f64 / 1000.0,
+            },
+            timestamp,
+        );
+    }
+}
@@ -0,0 +1,418 @@
+//! 报警邮件/短信通知模块
+//!
+//! 高优先级报警激活后超过配置的时长仍未确认时，通过SMTP邮件或HTTP短信网关通知
+//! 值班人员，内容包括报警描述、体征数值和患者标识。每个报警只通知一次，
+//! 发送在后台线程中进行，结果记录在发送日志中。
+
+use crate::ipc_schema;
+use crate::types::{Alarm, AlarmPriority};
+use crate::webhooks::json_escape;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::Manager;
+
+/// 发送超时
+const SEND_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 发送日志保留的记录数
+const DISPATCH_LOG_CAPACITY: usize = 200;
+
+/// SMTP连接加密方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// 隐式TLS（通常为465端口）
+    Tls,
+    /// STARTTLS（通常为587端口）
+    StartTls,
+    /// 不加密，只用于内网中继
+    None,
+}
+
+/// SMTP邮件设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    /// 登录用户名，为空时不认证
+    #[serde(default)]
+    pub username: String,
+    /// 登录密码，读取配置时返回空字符串，保存时为空表示保持原密码
+    #[serde(default)]
+    pub password: String,
+    /// 发件人地址
+    pub from: String,
+    /// 收件人地址
+    pub to: Vec<String>,
+}
+
+/// HTTP短信网关设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SmsGatewaySettings {
+    /// 网关地址，每个收件人发送一次POST请求
+    pub url: String,
+    /// 附加的请求头，例如认证令牌
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// 请求体模板，`{{to}}` 和 `{{message}}` 替换为转义后的手机号和短信内容
+    #[serde(default = "default_sms_template")]
+    pub body_template: String,
+    /// 收件人手机号
+    pub recipients: Vec<String>,
+}
+
+fn default_sms_template() -> String {
+    r#"{"to": "{{to}}", "message": "{{message}}"}"#.to_string()
+}
+
+/// 报警邮件/短信通知配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct AlertDispatchConfig {
+    pub enabled: bool,
+    /// 高优先级报警未确认多长时间（秒）后发送通知
+    pub unacknowledged_secs: u64,
+    /// 通知中的患者标识（例如床号），为空时使用当前患者姓名
+    #[serde(default)]
+    pub patient_label: String,
+    #[serde(default)]
+    pub email: Option<SmtpSettings>,
+    #[serde(default)]
+    pub sms: Option<SmsGatewaySettings>,
+}
+
+impl Default for AlertDispatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            unacknowledged_secs: 120,
+            patient_label: String::new(),
+            email: None,
+            sms: None,
+        }
+    }
+}
+
+/// 通知渠道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertChannel {
+    Email,
+    Sms,
+}
+
+/// 一次通知的发送记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct AlertDispatch {
+    pub alarm_id: u64,
+    pub channel: AlertChannel,
+    pub recipient: String,
+    /// 发送时间戳（毫秒）
+    pub timestamp: u64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// 验证通知配置
+fn validate_config(config: &AlertDispatchConfig) -> Result<(), String> {
+    if !(10..=86_400).contains(&config.unacknowledged_secs) {
+        return Err("未确认时长必须在10到86400秒之间".to_string());
+    }
+    if let Some(email) = &config.email {
+        if email.host.trim().is_empty() || email.port == 0 {
+            return Err("SMTP服务器地址和端口不能为空".to_string());
+        }
+        for address in std::iter::once(&email.from).chain(&email.to) {
+            address
+                .parse::<lettre::message::Mailbox>()
+                .map_err(|e| format!("邮件地址无效 {}: {}", address, e))?;
+        }
+        if email.to.is_empty() {
+            return Err("至少填写一个收件人邮箱".to_string());
+        }
+    }
+    if let Some(sms) = &config.sms {
+        if !(sms.url.starts_with("http://") || sms.url.starts_with("https://")) {
+            return Err("短信网关地址必须以http://或https://开头".to_string());
+        }
+        if sms.recipients.is_empty() || sms.recipients.iter().any(|r| r.trim().is_empty()) {
+            return Err("至少填写一个收件人手机号，且不能为空".to_string());
+        }
+    }
+    if config.enabled && config.email.is_none() && config.sms.is_none() {
+        return Err("启用通知时至少配置邮件或短信之一".to_string());
+    }
+    Ok(())
+}
+
+/// 待发送的通知
+struct AlertJob {
+    alarm_id: u64,
+    subject: String,
+    message: String,
+    email: Option<SmtpSettings>,
+    sms: Option<SmsGatewaySettings>,
+}
+
+/// 报警邮件/短信通知分发器
+///
+/// 配置保存在数据目录的 `alert_dispatch.json` 中。由看门狗线程每秒调用 `check`，
+/// 传入当前的报警列表。
+pub struct AlertDispatcher {
+    config_file: PathBuf,
+    config: Mutex<AlertDispatchConfig>,
+    /// 已通知的报警ID，报警解除后移除
+    notified: Mutex<BTreeSet<u64>>,
+    log: Arc<Mutex<VecDeque<AlertDispatch>>>,
+    sender: Sender<AlertJob>,
+}
+
+impl AlertDispatcher {
+    pub fn new(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("无法获取应用数据目录: {}", e))?;
+
+        let data_dir = app_data_dir.join("vital-signs");
+        if !data_dir.exists() {
+            fs::create_dir_all(&data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+        }
+
+        let config_file = data_dir.join("alert_dispatch.json");
+        let config = if config_file.exists() {
+            let content = fs::read_to_string(&config_file)
+                .map_err(|e| format!("读取报警通知配置失败: {}", e))?;
+            ipc_schema::from_stored_json(&content)
+                .map_err(|e| format!("解析报警通知配置失败: {}", e))?
+        } else {
+            AlertDispatchConfig::default()
+        };
+
+        let client = reqwest::blocking::Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+        let log = Arc::new(Mutex::new(VecDeque::new()));
+        let (sender, receiver) = mpsc::channel();
+        let thread_log = log.clone();
+        thread::spawn(move || run_dispatch(client, receiver, thread_log));
+
+        Ok(Self {
+            config_file,
+            config: Mutex::new(config),
+            notified: Mutex::new(BTreeSet::new()),
+            log,
+            sender,
+        })
+    }
+
+    /// 保存通知配置，密码为空时保持原密码
+    pub fn set_config(&self, config: AlertDispatchConfig) -> Result<(), String> {
+        let mut config = config;
+        validate_config(&config)?;
+        let mut current = self.config.lock().unwrap();
+        if let (Some(email), Some(old)) = (config.email.as_mut(), current.email.as_ref()) {
+            if email.password.is_empty() {
+                email.password = old.password.clone();
+            }
+        }
+        let json = serde_json::to_string_pretty(&config)
+            .map_err(|e| format!("序列化报警通知配置失败: {}", e))?;
+        fs::write(&self.config_file, json).map_err(|e| format!("保存报警通知配置失败: {}", e))?;
+        *current = config;
+        Ok(())
+    }
+
+    /// 获取通知配置，不返回SMTP密码
+    pub fn get_config(&self) -> AlertDispatchConfig {
+        let mut config = self.config.lock().unwrap().clone();
+        if let Some(email) = config.email.as_mut() {
+            email.password.clear();
+        }
+        config
+    }
+
+    /// 获取最近的发送记录，最新的在前
+    pub fn get_log(&self, limit: usize) -> Vec<AlertDispatch> {
+        let log = self.log.lock().unwrap();
+        log.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// 检查报警，高优先级报警未确认超过配置时长时发送通知
+    ///
+    /// # 参数
+    /// * `alarms` - 当前激活的报警
+    /// * `now` - 当前时间戳（毫秒）
+    /// * `current_patient` - 获取当前患者姓名，只在需要发送且未配置患者标识时调用
+    pub fn check(&self, alarms: &[Alarm], now: u64, current_patient: impl FnOnce() -> String) {
+        let mut notified = self.notified.lock().unwrap();
+        notified.retain(|id| alarms.iter().any(|alarm| alarm.id == *id));
+
+        let config = self.config.lock().unwrap().clone();
+        if !config.enabled {
+            return;
+        }
+        let delay_ms = config.unacknowledged_secs * 1000;
+        let due: Vec<&Alarm> = alarms
+            .iter()
+            .filter(|alarm| {
+                alarm.priority == AlarmPriority::High
+                    && !alarm.acknowledged
+                    && now.saturating_sub(alarm.started_at) >= delay_ms
+                    && !notified.contains(&alarm.id)
+            })
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+
+        let label = if config.patient_label.trim().is_empty() {
+            current_patient()
+        } else {
+            config.patient_label.clone()
+        };
+        for alarm in due {
+            notified.insert(alarm.id);
+            let message = format!(
+                "【紧急报警】{}：{}，当前值 {:.1}（限值 {:.1}），已{}秒未确认",
+                label,
+                alarm.message,
+                alarm.value,
+                alarm.limit,
+                now.saturating_sub(alarm.started_at) / 1000
+            );
+            println!("[AlertDispatcher] 发送未确认报警通知: {}", message);
+            let _ = self.sender.send(AlertJob {
+                alarm_id: alarm.id,
+                subject: format!("紧急报警未确认 - {}", label),
+                message,
+                email: config.email.clone(),
+                sms: config.sms.clone(),
+            });
+        }
+    }
+}
+
+/// 发送邮件
+fn send_email(settings: &SmtpSettings, to: &str, subject: &str, body: &str) -> Result<(), String> {
+    use lettre::message::header::ContentType;
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{Message, SmtpTransport, Transport};
+
+    let parse = |address: &str| {
+        address
+            .parse()
+            .map_err(|e| format!("邮件地址无效 {}: {}", address, e))
+    };
+    let email = Message::builder()
+        .from(parse(&settings.from)?)
+        .to(parse(to)?)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(body.to_string())
+        .map_err(|e| format!("生成邮件失败: {}", e))?;
+    let builder = match settings.security {
+        SmtpSecurity::Tls => SmtpTransport::relay(&settings.host),
+        SmtpSecurity::StartTls => SmtpTransport::starttls_relay(&settings.host),
+        SmtpSecurity::None => Ok(SmtpTransport::builder_dangerous(&settings.host)),
+    }
+    .map_err(|e| format!("连接SMTP服务器失败: {}", e))?;
+    let mut builder = builder.port(settings.port).timeout(Some(SEND_TIMEOUT));
+    if !settings.username.is_empty() {
+        builder = builder.credentials(Credentials::new(
+            settings.username.clone(),
+            settings.password.clone(),
+        ));
+    }
+    builder
+        .build()
+        .send(&email)
+        .map(|_| ())
+        .map_err(|e| format!("发送邮件失败: {}", e))
+}
+
+/// 通过短信网关发送短信
+fn send_sms(
+    client: &reqwest::blocking::Client,
+    settings: &SmsGatewaySettings,
+    to: &str,
+    message: &str,
+) -> Result<(), String> {
+    let body = settings
+        .body_template
+        .replace("{{to}}", &json_escape(to))
+        .replace("{{message}}", &json_escape(message));
+    let mut request = client.post(&settings.url);
+    if !settings
+        .headers
+        .keys()
+        .any(|name| name.eq_ignore_ascii_case("content-type"))
+    {
+        request = request.header("Content-Type", "application/json");
+    }
+    for (name, value) in &settings.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = request
+        .body(body)
+        .send()
+        .map_err(|e| format!("请求短信网关失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("短信网关返回 {}", response.status()));
+    }
+    Ok(())
+}
+
+/// 发送线程：逐个收件人发送通知并记录结果
+fn run_dispatch(
+    client: reqwest::blocking::Client,
+    jobs: Receiver<AlertJob>,
+    log: Arc<Mutex<VecDeque<AlertDispatch>>>,
+) {
+    for job in jobs {
+        let mut results = Vec::new();
+        if let Some(email) = &job.email {
+            for to in &email.to {
+                let result = send_email(email, to, &job.subject, &job.message);
+                results.push((AlertChannel::Email, to.clone(), result));
+            }
+        }
+        if let Some(sms) = &job.sms {
+            for to in &sms.recipients {
+                let result = send_sms(&client, sms, to, &job.message);
+                results.push((AlertChannel::Sms, to.clone(), result));
+            }
+        }
+
+        let timestamp = chrono::Utc::now().timestamp_millis().max(0) as u64;
+        let mut log = log.lock().unwrap();
+        for (channel, recipient, result) in results {
+            if let Err(e) = &result {
+                eprintln!("[AlertDispatcher][线程] 通知 {} 失败: {}", recipient, e);
+            }
+            if log.len() >= DISPATCH_LOG_CAPACITY {
+                log.pop_front();
+            }
+            log.push_back(AlertDispatch {
+                alarm_id: job.alarm_id,
+                channel,
+                recipient,
+                timestamp,
+                success: result.is_ok(),
+                error: result.err(),
+            });
+        }
+    }
+    println!("[AlertDispatcher][线程] 发送线程安全退出");
+}
@@ -0,0 +1,50 @@
+//! 内存分配统计模块
+//!
+//! 计数分配器包装系统分配器，按线程统计分配次数和字节数，用于处理性能测试中
+//! 衡量热路径的内存分配。计数保存在线程局部变量中，不同线程之间互不影响，
+//! 开销只有一次线程局部变量的读写。
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    /// 当前线程的分配次数和分配字节数
+    static ALLOCATIONS: Cell<(u64, u64)> = const { Cell::new((0, 0)) };
+}
+
+/// 计数分配器，在 `main.rs` 中注册为全局分配器
+pub struct CountingAllocator;
+
+fn record(bytes: usize) {
+    // 线程退出时线程局部变量可能已销毁，此时不计数
+    let _ = ALLOCATIONS.try_with(|counts| {
+        let (count, total) = counts.get();
+        counts.set((count + 1, total + bytes as u64));
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// 当前线程累计的分配次数和分配字节数，未注册计数分配器时始终为0
+pub fn thread_allocations() -> (u64, u64) {
+    ALLOCATIONS.with(|counts| counts.get())
+}
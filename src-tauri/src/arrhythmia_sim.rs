@@ -0,0 +1,380 @@
+//! 心律失常模拟库
+//!
+//! 模拟数据源按选定的心律失常场景逐个心搏生成心电波形，每个心搏的R波位置和类别
+//! 作为标注（真值）与波形同时记录。评估命令把实时检测到的心搏与标注按时间配对，
+//! 给出检出率、阳性预测值和分类准确率，修改检测算法后可据此做回归测试。
+//!
+//! 心搏波形取自模拟数据源内置心电录制中的一个完整心动周期，RR间期长于该周期时
+//! 用等电位线补齐，短于该周期时截断T波之后的部分。
+
+use crate::stress_sim::{self, StressConfig};
+use crate::test_reader::ECG_DATA;
+use crate::types::{BeatClass, BeatRecord};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// 心搏模板在内置心电录制中的长度（采样点数）
+const TEMPLATE_LEN: usize = 165;
+
+/// 心搏模板中R波的位置（采样点数）
+const R_WAVE_OFFSET: usize = 39;
+
+/// 正常RR间期的随机抖动（采样点数）
+const RR_JITTER: i32 = 2;
+
+/// 保存的标注心搏数上限（约1小时的心搏）
+const GROUND_TRUTH_CAPACITY: usize = 4096;
+
+/// 心搏配对的默认容差（毫秒）
+const DEFAULT_TOLERANCE_MS: u64 = 150;
+
+/// 心搏配对容差范围（毫秒）
+const TOLERANCE_RANGE_MS: (u64, u64) = (20, 500);
+
+/// 心律失常场景
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArrhythmiaScenario {
+    /// 正常窦性心律，75次/分
+    NormalSinus,
+    /// 窦性心动过缓，45次/分
+    SinusBradycardia,
+    /// 窦性心动过速，120次/分
+    SinusTachycardia,
+    /// 偶发室性早搏：每6个心搏出现一次提前心搏，随后为代偿间歇
+    OccasionalPvc,
+    /// 二联律：正常心搏与提前心搏交替出现
+    Bigeminy,
+    /// 心房颤动：RR间期在44~136次/分之间随机变化
+    AtrialFibrillation,
+    /// 二度房室传导阻滞：每4个心搏脱落1个
+    SecondDegreeBlock,
+}
+
+impl ArrhythmiaScenario {
+    /// 场景库中的所有场景
+    pub const ALL: [ArrhythmiaScenario; 7] = [
+        ArrhythmiaScenario::NormalSinus,
+        ArrhythmiaScenario::SinusBradycardia,
+        ArrhythmiaScenario::SinusTachycardia,
+        ArrhythmiaScenario::OccasionalPvc,
+        ArrhythmiaScenario::Bigeminy,
+        ArrhythmiaScenario::AtrialFibrillation,
+        ArrhythmiaScenario::SecondDegreeBlock,
+    ];
+
+    /// 场景说明
+    pub fn description(self) -> &'static str {
+        match self {
+            ArrhythmiaScenario::NormalSinus => "正常窦性心律，75次/分",
+            ArrhythmiaScenario::SinusBradycardia => "窦性心动过缓，45次/分",
+            ArrhythmiaScenario::SinusTachycardia => "窦性心动过速，120次/分",
+            ArrhythmiaScenario::OccasionalPvc => "偶发室性早搏，每6个心搏1个，伴代偿间歇",
+            ArrhythmiaScenario::Bigeminy => "二联律，正常心搏与提前心搏交替",
+            ArrhythmiaScenario::AtrialFibrillation => "心房颤动，RR间期绝对不规则",
+            ArrhythmiaScenario::SecondDegreeBlock => "二度房室传导阻滞，每4个心搏脱落1个",
+        }
+    }
+}
+
+/// 场景库条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioInfo {
+    pub scenario: ArrhythmiaScenario,
+    pub description: String,
+}
+
+/// 获取场景库
+pub fn scenarios() -> Vec<ScenarioInfo> {
+    ArrhythmiaScenario::ALL
+        .iter()
+        .map(|&scenario| ScenarioInfo {
+            scenario,
+            description: scenario.description().to_string(),
+        })
+        .collect()
+}
+
+/// 模拟数据源配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SimulatorConfig {
+    /// 心律失常场景，为None时循环播放内置心电录制（没有标注）
+    #[serde(default)]
+    pub scenario: Option<ArrhythmiaScenario>,
+    /// 压力测试配置，设置时生成频率扫描波形并逐级提高采样率，不能与心律失常场景同时使用
+    #[serde(default)]
+    pub stress: Option<StressConfig>,
+}
+
+/// 验证模拟数据源配置
+pub fn validate_config(config: &SimulatorConfig) -> Result<(), String> {
+    if let Some(stress) = &config.stress {
+        if config.scenario.is_some() {
+            return Err("压力测试模式不能与心律失常场景同时使用".to_string());
+        }
+        stress_sim::validate_config(stress)?;
+    }
+    Ok(())
+}
+
+/// 一个标注心搏
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct GroundTruthBeat {
+    /// R波采样放入原始数据队列的时间戳（毫秒）
+    pub timestamp: u64,
+    /// 心搏类别
+    pub classification: BeatClass,
+}
+
+/// 模拟数据源记录的标注心搏，每次连接清空
+#[derive(Debug, Default)]
+pub struct GroundTruth {
+    beats: Mutex<VecDeque<GroundTruthBeat>>,
+}
+
+impl GroundTruth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一个标注心搏
+    pub fn record(&self, beat: GroundTruthBeat) {
+        let mut beats = self.beats.lock().unwrap();
+        if beats.len() >= GROUND_TRUTH_CAPACITY {
+            beats.pop_front();
+        }
+        beats.push_back(beat);
+    }
+
+    /// 获取全部标注心搏，按时间先后排列
+    pub fn beats(&self) -> Vec<GroundTruthBeat> {
+        self.beats.lock().unwrap().iter().copied().collect()
+    }
+
+    pub fn clear(&self) {
+        self.beats.lock().unwrap().clear();
+    }
+}
+
+/// 按场景逐个采样生成心电波形
+#[derive(Debug)]
+pub struct BeatGenerator {
+    scenario: ArrhythmiaScenario,
+    /// 已开始的心搏数
+    beat_index: u64,
+    /// 当前心搏的长度，即到下一个R波的间期（采样点数）
+    beat_len: usize,
+    /// 当前心搏中的位置（采样点数）
+    position: usize,
+    /// 当前心搏的类别
+    classification: BeatClass,
+    /// 下一个心搏是否为提前心搏
+    next_premature: bool,
+}
+
+impl BeatGenerator {
+    pub fn new(scenario: ArrhythmiaScenario) -> Self {
+        println!("[ArrhythmiaSim] 心律失常场景: {}", scenario.description());
+        Self {
+            scenario,
+            beat_index: 0,
+            beat_len: 0,
+            position: 0,
+            classification: BeatClass::Normal,
+            next_premature: false,
+        }
+    }
+
+    /// 生成下一个采样
+    ///
+    /// # 返回值
+    /// 返回（心电采样值, 本采样为R波时的心搏类别）
+    pub fn next_sample(&mut self, rng: &mut impl Rng) -> (i32, Option<BeatClass>) {
+        if self.position >= self.beat_len {
+            self.classification = if self.next_premature {
+                BeatClass::Premature
+            } else {
+                BeatClass::Normal
+            };
+            let (beat_len, next_premature) = self.next_interval(rng);
+            self.beat_len = beat_len;
+            self.next_premature = next_premature;
+            self.position = 0;
+            self.beat_index += 1;
+        }
+        let value = if self.position < TEMPLATE_LEN {
+            ECG_DATA[self.position]
+        } else {
+            ECG_DATA[0]
+        };
+        let beat = (self.position == R_WAVE_OFFSET).then_some(self.classification);
+        self.position += 1;
+        (value, beat)
+    }
+
+    /// 按场景决定当前心搏到下一个R波的间期
+    ///
+    /// # 返回值
+    /// 返回（间期采样点数, 下一个心搏是否为提前心搏）
+    fn next_interval(&self, rng: &mut impl Rng) -> (usize, bool) {
+        let mut jittered =
+            |samples: i32| (samples + rng.gen_range(-RR_JITTER..=RR_JITTER)) as usize;
+        let k = self.beat_index;
+        match self.scenario {
+            ArrhythmiaScenario::NormalSinus => (jittered(200), false),
+            ArrhythmiaScenario::SinusBradycardia => (jittered(333), false),
+            ArrhythmiaScenario::SinusTachycardia => (jittered(125), false),
+            ArrhythmiaScenario::OccasionalPvc => match k % 6 {
+                4 => (120, true),
+                // 代偿间歇：提前心搏前后两个间期之和为正常间期的两倍
+                5 => (280, false),
+                _ => (jittered(200), false),
+            },
+            ArrhythmiaScenario::Bigeminy => {
+                if k.is_multiple_of(2) {
+                    (140, true)
+                } else {
+                    (260, false)
+                }
+            }
+            ArrhythmiaScenario::AtrialFibrillation => (rng.gen_range(110..=340), false),
+            ArrhythmiaScenario::SecondDegreeBlock => {
+                if k % 4 == 3 {
+                    (jittered(200) * 2, false)
+                } else {
+                    (jittered(200), false)
+                }
+            }
+        }
+    }
+}
+
+/// 心搏检测评估结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct DetectorValidation {
+    /// 心搏配对容差（毫秒）
+    pub tolerance_ms: u64,
+    /// 参与评估的标注心搏数
+    pub truth_beats: usize,
+    /// 参与评估的检测心搏数
+    pub detected_beats: usize,
+    /// 与标注心搏配对的检测心搏数
+    pub true_positives: usize,
+    /// 没有对应标注心搏的检测心搏数（误检）
+    pub false_positives: usize,
+    /// 没有被检测到的标注心搏数（漏检）
+    pub false_negatives: usize,
+    /// 检出率（0~1）
+    pub sensitivity: f64,
+    /// 阳性预测值（0~1）
+    pub positive_predictivity: f64,
+    /// 配对心搏中类别与标注一致的比例（0~1）
+    pub classification_accuracy: f64,
+    /// 标注为提前心搏且被检测为提前心搏的比例（0~1），没有提前心搏标注时为None
+    pub premature_sensitivity: Option<f64>,
+    /// 配对心搏的平均时间偏差（检测到的R波采样接收时间减标注时间，毫秒）
+    pub mean_timing_error_ms: f64,
+}
+
+/// 用标注心搏评估心搏检测结果
+///
+/// # 参数
+/// * `truth` - 标注心搏，按时间先后排列
+/// * `detected` - 检测到的心搏，按时间先后排列，按R波采样的接收时间与标注心搏配对
+/// * `processed_until` - 最近处理的采样放入原始数据队列的时间戳（毫秒），
+///   之后的标注心搏尚未经过检测，不参与评估
+/// * `tolerance_ms` - 心搏配对容差（毫秒），省略时为 `DEFAULT_TOLERANCE_MS`
+pub fn validate(
+    truth: &[GroundTruthBeat],
+    detected: &[BeatRecord],
+    processed_until: u64,
+    tolerance_ms: Option<u64>,
+) -> Result<DetectorValidation, String> {
+    let tolerance = tolerance_ms.unwrap_or(DEFAULT_TOLERANCE_MS);
+    if !(TOLERANCE_RANGE_MS.0..=TOLERANCE_RANGE_MS.1).contains(&tolerance) {
+        return Err(format!(
+            "配对容差必须在{}到{}毫秒之间",
+            TOLERANCE_RANGE_MS.0, TOLERANCE_RANGE_MS.1
+        ));
+    }
+    let truth: Vec<&GroundTruthBeat> = truth
+        .iter()
+        .filter(|b| b.timestamp + tolerance <= processed_until)
+        .collect();
+    let (Some(first), Some(last)) = (truth.first(), truth.last()) else {
+        return Err("没有可用于评估的标注心搏，请先以心律失常场景启动模拟数据源".to_string());
+    };
+    let window_start = first.timestamp.saturating_sub(tolerance);
+    let window_end = last.timestamp + tolerance;
+    let detected: Vec<&BeatRecord> = detected
+        .iter()
+        .filter(|b| (window_start..=window_end).contains(&b.received_at))
+        .collect();
+
+    // 两个序列都按时间排列，逐个标注心搏配对容差内最早的未配对检测心搏
+    let mut true_positives = 0;
+    let mut false_positives = 0;
+    let mut false_negatives = 0;
+    let mut class_matches = 0;
+    let mut premature_truth = 0;
+    let mut premature_detected = 0;
+    let mut timing_error_sum = 0.0;
+    let mut j = 0;
+    for beat in &truth {
+        while j < detected.len() && detected[j].received_at + tolerance < beat.timestamp {
+            false_positives += 1;
+            j += 1;
+        }
+        if beat.classification == BeatClass::Premature {
+            premature_truth += 1;
+        }
+        if j < detected.len() && detected[j].received_at <= beat.timestamp + tolerance {
+            let matched = detected[j];
+            true_positives += 1;
+            timing_error_sum += matched.received_at as f64 - beat.timestamp as f64;
+            if matched.classification == beat.classification {
+                class_matches += 1;
+                if beat.classification == BeatClass::Premature {
+                    premature_detected += 1;
+                }
+            }
+            j += 1;
+        } else {
+            false_negatives += 1;
+        }
+    }
+    false_positives += detected.len() - j;
+
+    let ratio = |n: usize, d: usize| if d > 0 { n as f64 / d as f64 } else { 0.0 };
+    let validation = DetectorValidation {
+        tolerance_ms: tolerance,
+        truth_beats: truth.len(),
+        detected_beats: detected.len(),
+        true_positives,
+        false_positives,
+        false_negatives,
+        sensitivity: ratio(true_positives, truth.len()),
+        positive_predictivity: ratio(true_positives, detected.len()),
+        classification_accuracy: ratio(class_matches, true_positives),
+        premature_sensitivity: (premature_truth > 0)
+            .then(|| ratio(premature_detected, premature_truth)),
+        mean_timing_error_ms: if true_positives > 0 {
+            timing_error_sum / true_positives as f64
+        } else {
+            0.0
+        },
+    };
+    println!(
+        "[ArrhythmiaSim] 检测评估: 标注{}个, 检出率{:.3}, 阳性预测值{:.3}, 分类准确率{:.3}",
+        validation.truth_beats,
+        validation.sensitivity,
+        validation.positive_predictivity,
+        validation.classification_accuracy
+    );
+    Ok(validation)
+}
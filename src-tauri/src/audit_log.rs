@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use tauri::Manager;
+
+/// 审计日志条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// 记录时间（RFC 3339）
+    pub timestamp: String,
+    /// 操作员
+    pub operator: String,
+    /// 操作类型，例如 `acknowledge_alarm`
+    pub action: String,
+    /// 操作详情
+    pub details: String,
+}
+
+/// 审计日志存储，每条记录为一行JSON，只追加不修改
+pub struct AuditStore {
+    data_file: PathBuf,
+}
+
+impl AuditStore {
+    pub fn new(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("无法获取应用数据目录: {}", e))?;
+
+        let data_dir = app_data_dir.join("vital-signs");
+        if !data_dir.exists() {
+            fs::create_dir_all(&data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+        }
+
+        let data_file = data_dir.join("audit_log.jsonl");
+
+        Ok(Self { data_file })
+    }
+
+    /// 追加一条审计记录
+    ///
+    /// # 参数
+    /// * `operator` - 操作员，不能为空
+    /// * `action` - 操作类型
+    /// * `details` - 操作详情
+    pub fn append(&self, operator: &str, action: &str, details: String) -> Result<(), String> {
+        if operator.trim().is_empty() {
+            return Err("操作员不能为空".to_string());
+        }
+
+        let entry = AuditEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            operator: operator.trim().to_string(),
+            action: action.to_string(),
+            details,
+        };
+        let line =
+            serde_json::to_string(&entry).map_err(|e| format!("序列化审计记录失败: {}", e))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.data_file)
+            .map_err(|e| format!("打开审计日志失败: {}", e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("写入审计日志失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 读取审计记录
+    ///
+    /// # 参数
+    /// * `limit` - 只返回最近的若干条，省略时返回全部
+    ///
+    /// # 返回值
+    /// 返回按时间先后排列的审计记录，无法解析的行被跳过
+    pub fn load(&self, limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+        if !self.data_file.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content =
+            fs::read_to_string(&self.data_file).map_err(|e| format!("读取审计日志失败: {}", e))?;
+        let entries: Vec<AuditEntry> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+
+        let skip = limit.map_or(0, |n| entries.len().saturating_sub(n));
+        Ok(entries.into_iter().skip(skip).collect())
+    }
+}
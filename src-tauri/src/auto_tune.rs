@@ -0,0 +1,201 @@
+//! 缓冲区自动调节模块
+//!
+//! 开启自动调节后，后台线程定期观测数据源的采样率和处理延迟，按采样率换算出各缓冲区
+//! 应容纳的时长，在上下限内调整原始采样队列容量、处理后数据队列容量、ECG的LTTB缓冲区
+//! 大小和事件推送的每批采样数，使同一个程序在50Hz到1kHz的数据源上都能正常工作。
+//! 新值与当前值相差不到 `CHANGE_THRESHOLD` 时不调整，避免采样率抖动导致反复调整。
+
+use serde::{Deserialize, Serialize};
+
+/// 采样率低于该值（Hz）时视为没有数据，不做调整
+const MIN_SAMPLE_RATE: f64 = 10.0;
+
+/// 采样率和延迟的指数平滑系数
+const SMOOTHING: f64 = 0.3;
+
+/// 新值与当前值的相对差超过该比例才调整
+const CHANGE_THRESHOLD: f64 = 0.25;
+
+/// 原始采样队列容纳的时长（秒）
+const RAW_QUEUE_SECONDS: f64 = 4.0;
+
+/// 处理延迟超过该值（毫秒）时原始采样队列容纳的时长加倍
+const HIGH_LATENCY_MS: f64 = 100.0;
+
+/// 原始采样队列容量范围
+const RAW_QUEUE_RANGE: (usize, usize) = (250, 8000);
+
+/// 处理后数据队列容纳的时长（秒）
+const PROCESSED_QUEUE_SECONDS: f64 = 4.0;
+
+/// 处理后数据队列容量范围
+const PROCESSED_QUEUE_RANGE: (usize, usize) = (250, 4000);
+
+/// ECG波形显示窗口的时长（秒），即LTTB缓冲区容纳的时长
+const LTTB_WINDOW_SECONDS: f64 = 4.0;
+
+/// LTTB缓冲区大小范围
+const LTTB_BUFFER_RANGE: (usize, usize) = (500, 4000);
+
+/// 每批采样数相对于推送间隔内平均采样数的余量
+const BATCH_HEADROOM: f64 = 1.5;
+
+/// 每批采样数范围，与事件推送配置的校验范围一致
+const BATCH_SIZE_RANGE: (usize, usize) = (1, 250);
+
+/// 自动调节选出的缓冲区设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TunedSettings {
+    /// 原始采样队列容量，下次连接时生效
+    pub raw_queue_capacity: usize,
+    /// 处理后数据队列容量
+    pub processed_queue_capacity: usize,
+    /// ECG的LTTB缓冲区大小
+    pub lttb_buffer_size: usize,
+    /// 事件推送的每批采样数上限
+    pub event_batch_size: usize,
+}
+
+/// 自动调节状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct AutoTuneStatus {
+    /// 是否开启自动调节
+    pub enabled: bool,
+    /// 平滑后的采样率（Hz），尚未观测到数据时为None
+    pub sample_rate: Option<f64>,
+    /// 平滑后的平均处理延迟（毫秒）
+    pub average_latency_ms: Option<f64>,
+    /// 最近一次选出的设置，尚未调整过时为None
+    pub settings: Option<TunedSettings>,
+    /// 最近一次调整的时间戳（毫秒）
+    pub updated_at: Option<u64>,
+}
+
+/// 缓冲区自动调节器
+#[derive(Debug, Default)]
+pub struct AutoTuner {
+    enabled: bool,
+    sample_rate: Option<f64>,
+    latency_ms: Option<f64>,
+    settings: Option<TunedSettings>,
+    updated_at: Option<u64>,
+}
+
+impl AutoTuner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开启或关闭自动调节，关闭时清除观测值，已调整的设置保持不变
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.sample_rate = None;
+            self.latency_ms = None;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn status(&self) -> AutoTuneStatus {
+        AutoTuneStatus {
+            enabled: self.enabled,
+            sample_rate: self.sample_rate,
+            average_latency_ms: self.latency_ms,
+            settings: self.settings,
+            updated_at: self.updated_at,
+        }
+    }
+
+    /// 输入一次观测，需要调整时返回新的设置
+    ///
+    /// # 参数
+    /// * `sample_rate` - 数据源采样率（Hz）
+    /// * `latency_ms` - 平均处理延迟（毫秒）
+    /// * `event_rate_hz` - 事件推送频率（Hz）
+    /// * `compression_ratio` - LTTB压缩比，LTTB缓冲区大小取其整数倍
+    /// * `timestamp` - 当前时间戳（毫秒）
+    pub fn observe(
+        &mut self,
+        sample_rate: f64,
+        latency_ms: f64,
+        event_rate_hz: u32,
+        compression_ratio: usize,
+        timestamp: u64,
+    ) -> Option<TunedSettings> {
+        if !self.enabled || !sample_rate.is_finite() || sample_rate < MIN_SAMPLE_RATE {
+            return None;
+        }
+        let sample_rate = smooth(self.sample_rate, sample_rate);
+        let latency_ms = smooth(self.latency_ms, latency_ms.max(0.0));
+        self.sample_rate = Some(sample_rate);
+        self.latency_ms = Some(latency_ms);
+
+        let tuned = compute_settings(sample_rate, latency_ms, event_rate_hz, compression_ratio);
+        let changed = match self.settings {
+            None => true,
+            Some(current) => {
+                differs(current.raw_queue_capacity, tuned.raw_queue_capacity)
+                    || differs(
+                        current.processed_queue_capacity,
+                        tuned.processed_queue_capacity,
+                    )
+                    || differs(current.lttb_buffer_size, tuned.lttb_buffer_size)
+                    || differs(current.event_batch_size, tuned.event_batch_size)
+            }
+        };
+        if !changed {
+            return None;
+        }
+        self.settings = Some(tuned);
+        self.updated_at = Some(timestamp);
+        Some(tuned)
+    }
+}
+
+/// 按采样率和处理延迟计算缓冲区设置
+fn compute_settings(
+    sample_rate: f64,
+    latency_ms: f64,
+    event_rate_hz: u32,
+    compression_ratio: usize,
+) -> TunedSettings {
+    let raw_seconds = if latency_ms > HIGH_LATENCY_MS {
+        RAW_QUEUE_SECONDS * 2.0
+    } else {
+        RAW_QUEUE_SECONDS
+    };
+    let compression_ratio = compression_ratio.max(1);
+    let lttb_buffer_size = clamp(sample_rate * LTTB_WINDOW_SECONDS, LTTB_BUFFER_RANGE);
+    TunedSettings {
+        raw_queue_capacity: clamp(sample_rate * raw_seconds, RAW_QUEUE_RANGE),
+        processed_queue_capacity: clamp(
+            sample_rate * PROCESSED_QUEUE_SECONDS,
+            PROCESSED_QUEUE_RANGE,
+        ),
+        lttb_buffer_size: (lttb_buffer_size / compression_ratio).max(1) * compression_ratio,
+        event_batch_size: clamp(
+            sample_rate / event_rate_hz.max(1) as f64 * BATCH_HEADROOM,
+            BATCH_SIZE_RANGE,
+        ),
+    }
+}
+
+fn smooth(previous: Option<f64>, value: f64) -> f64 {
+    match previous {
+        Some(previous) => previous + (value - previous) * SMOOTHING,
+        None => value,
+    }
+}
+
+fn clamp(value: f64, (min, max): (usize, usize)) -> usize {
+    (value.ceil() as usize).clamp(min, max)
+}
+
+fn differs(current: usize, tuned: usize) -> bool {
+    current.abs_diff(tuned) as f64 > current as f64 * CHANGE_THRESHOLD
+}
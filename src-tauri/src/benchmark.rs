@@ -0,0 +1,86 @@
+//! 处理性能测试模块
+//!
+//! 在独立的处理器实例中同步解析和处理一批合成采样，测量处理速率以及每个采样的
+//! 内存分配次数，用于确认热路径（帧重组、行解析、LTTB压缩）没有多余的分配。
+//! 测试不影响正在进行的实时监护。
+
+use crate::alloc_stats;
+use crate::channels::ChannelRegistry;
+use crate::data_processor::DataProcessor;
+use crate::framing::{AssembledFrame, FrameAssembler, LineFrameParser};
+use crate::sample_ring::SampleRing;
+use crate::types::ProcessingBenchmark;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// 合成采样的采样率（Hz）
+const SAMPLE_RATE_HZ: u64 = 250;
+
+/// 预热采样数，预热期间缓冲区增长到稳定容量，不计入结果
+const WARMUP_SAMPLES: usize = 2000;
+
+/// 单次测试的采样数范围
+const SAMPLE_RANGE: (usize, usize) = (1000, 1_000_000);
+
+/// 运行处理性能测试
+///
+/// # 参数
+/// * `samples` - 计入结果的采样数
+pub fn run(samples: usize) -> Result<ProcessingBenchmark, String> {
+    if !(SAMPLE_RANGE.0..=SAMPLE_RANGE.1).contains(&samples) {
+        return Err(format!(
+            "采样数必须在{}到{}之间",
+            SAMPLE_RANGE.0, SAMPLE_RANGE.1
+        ));
+    }
+    let processor = DataProcessor::new(Arc::new(SampleRing::new(1)));
+    let registry = ChannelRegistry::new();
+    let mut assembler = FrameAssembler::new(Arc::new(LineFrameParser));
+    let mut line = String::with_capacity(128);
+    let start_timestamp = chrono::Utc::now().timestamp_millis().max(0) as u64;
+
+    let mut run_samples = |range: std::ops::Range<usize>| {
+        for i in range {
+            // 合成的心电近似为每秒一次的尖峰叠加缓慢的基线漂移
+            let phase = i as u64 % SAMPLE_RATE_HZ;
+            let ecg = 2048 + if phase < 5 { 1500 } else { 0 } + (i % 500) as i32 / 10;
+            line.clear();
+            let _ = writeln!(line, "A={},B=980,C=368,D={}", ecg, 2000 + (i % 1000) as i32);
+            assembler.push(line.as_bytes());
+            while let Some(unit) = assembler.next_frame() {
+                if let AssembledFrame::Frame(frame) = unit {
+                    let decoded = assembler.decode(&frame, &registry);
+                    assembler.recycle(frame);
+                    if let Some(vital_signs) = decoded.ok().and_then(|d| d.vital_signs) {
+                        let timestamp = start_timestamp + i as u64 * 1000 / SAMPLE_RATE_HZ;
+                        let processed = processor.process_sample(vital_signs, timestamp);
+                        processor.recycle(processed);
+                    }
+                }
+            }
+        }
+    };
+
+    run_samples(0..WARMUP_SAMPLES);
+    let (allocations_before, bytes_before) = alloc_stats::thread_allocations();
+    let started = Instant::now();
+    run_samples(WARMUP_SAMPLES..WARMUP_SAMPLES + samples);
+    let elapsed = started.elapsed().as_secs_f64();
+    let (allocations_after, bytes_after) = alloc_stats::thread_allocations();
+
+    let allocations = allocations_after - allocations_before;
+    let result = ProcessingBenchmark {
+        samples,
+        elapsed_ms: elapsed * 1000.0,
+        samples_per_second: samples as f64 / elapsed.max(f64::EPSILON),
+        allocations,
+        allocations_per_sample: allocations as f64 / samples as f64,
+        bytes_allocated_per_sample: (bytes_after - bytes_before) as f64 / samples as f64,
+    };
+    println!(
+        "[Benchmark] {}个采样，{:.0}采样/秒，每采样分配{:.2}次",
+        samples, result.samples_per_second, result.allocations_per_sample
+    );
+    Ok(result)
+}
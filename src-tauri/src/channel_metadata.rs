@@ -0,0 +1,265 @@
+//! 体征元数据目录模块
+//!
+//! 前端显示和导出时需要每项体征的单位、有效范围、采样率和显示精度，统一由后端给出，
+//! 避免各处硬编码的单位与通道注册表不一致。内置体征的单位和采样率取自其来源通道的
+//! 通道描述，扩展通道直接由通道描述生成，通道注册表变化后目录随之更新。
+
+use crate::channels::ChannelRegistry;
+use crate::data_processor::{DIASTOLIC_VALID_RANGE, GLUCOSE_VALID_RANGE, SYSTOLIC_VALID_RANGE};
+use crate::types::{ChannelDescriptor, ChannelKind};
+use serde::{Deserialize, Serialize};
+
+/// 心率有效范围（bpm）
+const HEART_RATE_RANGE: (f64, f64) = (0.0, 300.0);
+
+/// 血氧饱和度有效范围（%）
+const SPO2_RANGE: (f64, f64) = (0.0, 100.0);
+
+/// 体温有效范围（°C）
+const TEMPERATURE_RANGE: (f64, f64) = (25.0, 45.0);
+
+/// 呼吸频率有效范围（次/分）
+const RESP_RATE_RANGE: (f64, f64) = (0.0, 120.0);
+
+/// 二氧化碳分压有效范围（mmHg）
+const CO2_RANGE: (f64, f64) = (0.0, 150.0);
+
+/// 收缩压有效范围（mmHg）
+const SYSTOLIC_RANGE: (f64, f64) = (SYSTOLIC_VALID_RANGE.0 as f64, SYSTOLIC_VALID_RANGE.1 as f64);
+
+/// 舒张压有效范围（mmHg）
+const DIASTOLIC_RANGE: (f64, f64) = (
+    DIASTOLIC_VALID_RANGE.0 as f64,
+    DIASTOLIC_VALID_RANGE.1 as f64,
+);
+
+/// 扩展通道的默认显示精度（小数位数）
+const EXTENSION_DISPLAY_PRECISION: u32 = 2;
+
+/// 一项体征的元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ChannelMetadata {
+    /// 体征ID，与按体征订阅使用的ID相同
+    pub id: String,
+    /// 显示名称
+    pub label: String,
+    /// 单位，无量纲的数值为空字符串
+    pub unit: String,
+    /// 波形或数值
+    pub kind: ChannelKind,
+    /// 来源通道ID
+    pub source_channel: String,
+    /// 有效范围下限，未定义时为None
+    pub min: Option<f64>,
+    /// 有效范围上限，未定义时为None
+    pub max: Option<f64>,
+    /// 采样率（Hz），间歇性测量为0
+    pub sample_rate_hz: f64,
+    /// 显示精度（小数位数）
+    pub display_precision: u32,
+    /// 是否为扩展通道
+    pub extension: bool,
+}
+
+/// 内置体征的元数据定义
+struct BuiltinVital {
+    id: &'static str,
+    label: &'static str,
+    source: &'static str,
+    /// 单位，为None时使用来源通道的单位
+    unit: Option<&'static str>,
+    kind: ChannelKind,
+    range: Option<(f64, f64)>,
+    precision: u32,
+}
+
+/// 内置体征，顺序即目录中的顺序
+const BUILTIN_VITALS: &[BuiltinVital] = &[
+    BuiltinVital {
+        id: "ecg",
+        label: "心电",
+        source: "ecg",
+        unit: Some(""),
+        kind: ChannelKind::Waveform,
+        range: Some((-1.0, 1.0)),
+        precision: 3,
+    },
+    BuiltinVital {
+        id: "heart_rate",
+        label: "心率",
+        source: "ecg",
+        unit: Some("bpm"),
+        kind: ChannelKind::Numeric,
+        range: Some(HEART_RATE_RANGE),
+        precision: 0,
+    },
+    BuiltinVital {
+        id: "heart_rate_instant",
+        label: "瞬时心率",
+        source: "ecg",
+        unit: Some("bpm"),
+        kind: ChannelKind::Numeric,
+        range: Some(HEART_RATE_RANGE),
+        precision: 0,
+    },
+    BuiltinVital {
+        id: "rr_interval",
+        label: "RR间期",
+        source: "ecg",
+        unit: Some("s"),
+        kind: ChannelKind::Numeric,
+        range: Some((0.0, 3.0)),
+        precision: 3,
+    },
+    BuiltinVital {
+        id: "spo2",
+        label: "血氧饱和度",
+        source: "spo2",
+        unit: None,
+        kind: ChannelKind::Numeric,
+        range: Some(SPO2_RANGE),
+        precision: 0,
+    },
+    BuiltinVital {
+        id: "spo2_raw",
+        label: "未平均血氧饱和度",
+        source: "spo2",
+        unit: None,
+        kind: ChannelKind::Numeric,
+        range: Some(SPO2_RANGE),
+        precision: 0,
+    },
+    BuiltinVital {
+        id: "temperature",
+        label: "体温",
+        source: "temp",
+        unit: None,
+        kind: ChannelKind::Numeric,
+        range: Some(TEMPERATURE_RANGE),
+        precision: 1,
+    },
+    BuiltinVital {
+        id: "respiration",
+        label: "呼吸波形",
+        source: "resp",
+        unit: None,
+        kind: ChannelKind::Waveform,
+        range: None,
+        precision: 0,
+    },
+    BuiltinVital {
+        id: "respiration_rate",
+        label: "呼吸频率",
+        source: "resp",
+        unit: Some("次/分"),
+        kind: ChannelKind::Numeric,
+        range: Some(RESP_RATE_RANGE),
+        precision: 0,
+    },
+    BuiltinVital {
+        id: "co2",
+        label: "二氧化碳波形",
+        source: "co2",
+        unit: None,
+        kind: ChannelKind::Waveform,
+        range: Some(CO2_RANGE),
+        precision: 1,
+    },
+    BuiltinVital {
+        id: "etco2",
+        label: "呼气末二氧化碳",
+        source: "co2",
+        unit: None,
+        kind: ChannelKind::Numeric,
+        range: Some(CO2_RANGE),
+        precision: 0,
+    },
+    BuiltinVital {
+        id: "co2_respiration_rate",
+        label: "二氧化碳呼吸频率",
+        source: "co2",
+        unit: Some("次/分"),
+        kind: ChannelKind::Numeric,
+        range: Some(RESP_RATE_RANGE),
+        precision: 0,
+    },
+    BuiltinVital {
+        id: "systolic",
+        label: "收缩压",
+        source: "nibp",
+        unit: None,
+        kind: ChannelKind::Numeric,
+        range: Some(SYSTOLIC_RANGE),
+        precision: 0,
+    },
+    BuiltinVital {
+        id: "diastolic",
+        label: "舒张压",
+        source: "nibp",
+        unit: None,
+        kind: ChannelKind::Numeric,
+        range: Some(DIASTOLIC_RANGE),
+        precision: 0,
+    },
+    BuiltinVital {
+        id: "glucose",
+        label: "血糖",
+        source: "glucose",
+        unit: None,
+        kind: ChannelKind::Numeric,
+        range: Some(GLUCOSE_VALID_RANGE),
+        precision: 1,
+    },
+];
+
+/// 获取当前的体征元数据目录
+///
+/// # 参数
+/// * `registry` - 当前的通道注册表
+///
+/// # 返回值
+/// 先列出内置体征，再按注册顺序列出扩展通道；来源通道不在注册表中的内置体征不列出
+pub fn catalog(registry: &ChannelRegistry) -> Vec<ChannelMetadata> {
+    let mut catalog: Vec<ChannelMetadata> = BUILTIN_VITALS
+        .iter()
+        .filter_map(|vital| {
+            let source = registry.get(vital.source)?;
+            Some(ChannelMetadata {
+                id: vital.id.to_string(),
+                label: vital.label.to_string(),
+                unit: vital.unit.unwrap_or(source.unit.as_str()).to_string(),
+                kind: vital.kind,
+                source_channel: source.id.clone(),
+                min: vital.range.map(|r| r.0),
+                max: vital.range.map(|r| r.1),
+                sample_rate_hz: source.sample_rate_hz,
+                display_precision: vital.precision,
+                extension: false,
+            })
+        })
+        .collect();
+    catalog.extend(
+        registry
+            .descriptors()
+            .iter()
+            .filter(|d| !d.builtin)
+            .map(extension_metadata),
+    );
+    catalog
+}
+
+fn extension_metadata(descriptor: &ChannelDescriptor) -> ChannelMetadata {
+    ChannelMetadata {
+        id: descriptor.id.clone(),
+        label: descriptor.label.clone(),
+        unit: descriptor.unit.clone(),
+        kind: descriptor.kind,
+        source_channel: descriptor.id.clone(),
+        min: None,
+        max: None,
+        sample_rate_hz: descriptor.sample_rate_hz,
+        display_precision: EXTENSION_DISPLAY_PRECISION,
+        extension: true,
+    }
+}
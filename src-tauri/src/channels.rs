@@ -0,0 +1,154 @@
+//! 通道注册表模块
+//!
+//! 用元数据描述每个体征通道（ID、单位、波形/数值类型、采样率、协议键），
+//! 新传感器只需注册一个通道描述（解析映射）并可选地提供处理插件，
+//! 无需修改 `VitalSigns`/`ProcessedVitalSigns` 的结构。
+
+use crate::framing::LineFrameParser;
+use crate::types::{ChannelDescriptor, ChannelKind};
+use std::collections::VecDeque;
+
+/// 扩展通道的处理插件
+///
+/// 数据处理线程对每个扩展通道的每个采样调用一次 `process`，
+/// 未注册插件的通道按原值输出。
+pub trait ChannelPlugin: Send {
+    /// 处理单个采样
+    ///
+    /// # 参数
+    /// * `value` - 解析得到的原始值
+    /// * `timestamp` - 当前时间戳（毫秒）
+    ///
+    /// # 返回值
+    /// 返回处理后的值
+    fn process(&mut self, value: f64, timestamp: u64) -> f64;
+}
+
+/// 滑动平均插件，适用于噪声较大的数值通道
+pub struct MovingAveragePlugin {
+    window: usize,
+    values: VecDeque<f64>,
+}
+
+impl MovingAveragePlugin {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            values: VecDeque::with_capacity(window.max(1)),
+        }
+    }
+}
+
+impl ChannelPlugin for MovingAveragePlugin {
+    fn process(&mut self, value: f64, _timestamp: u64) -> f64 {
+        if self.values.len() >= self.window {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+        self.values.iter().sum::<f64>() / self.values.len() as f64
+    }
+}
+
+/// 通道注册表
+#[derive(Debug, Clone)]
+pub struct ChannelRegistry {
+    descriptors: Vec<ChannelDescriptor>,
+}
+
+impl Default for ChannelRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChannelRegistry {
+    /// 创建包含内置通道的注册表
+    pub fn new() -> Self {
+        let builtin =
+            |id: &str, label: &str, unit: &str, kind, rate, key: &str| ChannelDescriptor {
+                id: id.to_string(),
+                label: label.to_string(),
+                unit: unit.to_string(),
+                kind,
+                sample_rate_hz: rate,
+                protocol_key: key.to_string(),
+                builtin: true,
+            };
+
+        Self {
+            descriptors: vec![
+                builtin("ecg", "心电", "raw", ChannelKind::Waveform, 250.0, "A"),
+                builtin("spo2", "血氧饱和度", "%", ChannelKind::Numeric, 250.0, "B"),
+                builtin("temp", "体温", "°C", ChannelKind::Numeric, 250.0, "C"),
+                builtin("resp", "呼吸", "raw", ChannelKind::Waveform, 250.0, "D"),
+                builtin("co2", "二氧化碳", "mmHg", ChannelKind::Waveform, 250.0, "F"),
+                builtin("nibp", "无创血压", "mmHg", ChannelKind::Numeric, 0.0, "E"),
+                builtin("glucose", "血糖", "mmol/L", ChannelKind::Numeric, 0.0, "G"),
+            ],
+        }
+    }
+
+    /// 获取所有通道描述
+    pub fn descriptors(&self) -> &[ChannelDescriptor] {
+        &self.descriptors
+    }
+
+    /// 按通道ID查找通道描述
+    pub fn get(&self, id: &str) -> Option<&ChannelDescriptor> {
+        self.descriptors.iter().find(|d| d.id == id)
+    }
+
+    /// 按协议键查找扩展通道（内置通道由解析器直接映射到固定字段）
+    pub fn find_extension_by_key(&self, key: &str) -> Option<&ChannelDescriptor> {
+        self.descriptors
+            .iter()
+            .find(|d| !d.builtin && d.protocol_key == key)
+    }
+
+    /// 注册扩展通道
+    ///
+    /// # 参数
+    /// * `descriptor` - 通道描述，ID和协议键都不能与已有通道重复
+    pub fn register(&mut self, mut descriptor: ChannelDescriptor) -> Result<(), String> {
+        if descriptor.id.trim().is_empty() {
+            return Err("通道ID不能为空".to_string());
+        }
+        if descriptor.protocol_key.trim().is_empty() {
+            return Err("协议键不能为空".to_string());
+        }
+        if descriptor.protocol_key == LineFrameParser::SEQUENCE_KEY {
+            return Err(format!("协议键 {} 保留用于帧序号", descriptor.protocol_key));
+        }
+        if !descriptor.sample_rate_hz.is_finite() || descriptor.sample_rate_hz < 0.0 {
+            return Err("采样率无效".to_string());
+        }
+        if self.get(&descriptor.id).is_some() {
+            return Err(format!("通道已存在: {}", descriptor.id));
+        }
+        if self
+            .descriptors
+            .iter()
+            .any(|d| d.protocol_key == descriptor.protocol_key)
+        {
+            return Err(format!("协议键已被占用: {}", descriptor.protocol_key));
+        }
+
+        descriptor.builtin = false;
+        self.descriptors.push(descriptor);
+        Ok(())
+    }
+
+    /// 注销扩展通道，内置通道不能注销
+    pub fn unregister(&mut self, id: &str) -> Result<(), String> {
+        match self.descriptors.iter().position(|d| d.id == id) {
+            Some(index) if self.descriptors[index].builtin => {
+                Err(format!("内置通道不能注销: {}", id))
+            }
+            Some(index) => {
+                self.descriptors.remove(index);
+                Ok(())
+            }
+            None => Err(format!("通道不存在: {}", id)),
+        }
+    }
+}
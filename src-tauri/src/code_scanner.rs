@@ -0,0 +1,88 @@
+//! 条码扫描枪模块
+//!
+//! 串口扫描枪每扫描一次发送一行条码内容。扫描线程按行读取，把条码交给回调处理
+//! （识别患者并设为当前患者）。以键盘方式输入的扫码枪不经过本模块，由前端把
+//! 输入的内容交给 `identify_patient_from_code` 命令。
+
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// 读取超时，用于定期检查停止信号
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 条码最大长度，超过时视为噪声丢弃
+const MAX_CODE_LEN: usize = 256;
+
+/// 扫描到条码时的回调
+pub type CodeHandler = Box<dyn Fn(String) + Send>;
+
+pub struct CodeScanner {
+    port_name: String,
+    baud_rate: u32,
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl CodeScanner {
+    pub fn new(port_name: String, baud_rate: u32) -> Self {
+        println!(
+            "[CodeScanner] 初始化，串口={}，波特率={}",
+            port_name, baud_rate
+        );
+        Self {
+            port_name,
+            baud_rate,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 打开扫描枪串口并启动扫描线程
+    ///
+    /// # 参数
+    /// * `on_code` - 扫描到条码时调用，参数为去掉首尾空白的条码内容
+    pub fn start(&self, on_code: CodeHandler) -> Result<(), String> {
+        let port = serialport::new(&self.port_name, self.baud_rate)
+            .timeout(READ_TIMEOUT)
+            .open()
+            .map_err(|e| format!("无法打开扫描枪串口 {}: {}", self.port_name, e))?;
+        println!("[CodeScanner] 扫描枪串口 {} 已打开", self.port_name);
+
+        let stop_flag = self.stop_flag.clone();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(port);
+            let mut line = Vec::new();
+            while !stop_flag.load(Ordering::Relaxed) {
+                // 超时返回时已读到的部分保留在 `line` 中，下次继续读到行尾
+                match reader.read_until(b'\n', &mut line) {
+                    Ok(0) => thread::sleep(READ_TIMEOUT),
+                    Ok(_) if line.ends_with(b"\n") => {
+                        let code = String::from_utf8_lossy(&line).trim().to_string();
+                        line.clear();
+                        if !code.is_empty() && code.len() <= MAX_CODE_LEN {
+                            println!("[CodeScanner][线程] 扫描到条码: {}", code);
+                            on_code(code);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) if e.kind() == ErrorKind::TimedOut => {}
+                    Err(e) => {
+                        eprintln!("[CodeScanner][线程] 读取扫描枪失败: {}", e);
+                        break;
+                    }
+                }
+                if line.len() > MAX_CODE_LEN {
+                    line.clear();
+                }
+            }
+            println!("[CodeScanner][线程] 扫描线程安全退出");
+        });
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        println!("[CodeScanner] 停止扫描枪");
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
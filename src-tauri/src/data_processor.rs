@@ -2,22 +2,334 @@
 //!
 //! 本模块负责处理从串口接收到的原始体征数据，包括：
 //! - ECG（心电图）数据处理和LTTB压缩
+//! - 呼吸波形处理（呼吸频率、呼吸暂停检测）和LTTB压缩
+//! - 二氧化碳波形处理（EtCO2、FiCO2、呼吸频率）和LTTB压缩
+//! - 血糖等间歇性测量的验证和历史记录
 //! - 体温数据处理和滤波
 //! - 血氧数据处理
+//! - 运动/伪差检测
 //! - 心率和RR间隔计算
+//! - HRV分析（调用 `hrv` 模块）
 //! - 数据归一化和压缩算法
+//! - 通道增益/反相/偏移校正
 
+use crate::activity::{self, ActivityClassifier};
+use crate::alarm_history::AlarmHistoryEntry;
+use crate::alarms::{self, AlarmEngine, AlarmListener};
+use crate::channels::ChannelPlugin;
+use crate::diagnostics::hot_log;
+use crate::ecg_archive::EcgArchive;
+use crate::hl7_sender::{Hl7InterfaceStatus, Hl7Sender, Hl7SenderConfig};
+use crate::hr_recovery::{self, HrRecoverySession};
+use crate::hrv;
+use crate::kafka_sink::{KafkaAlarmForwarder, KafkaConfig, KafkaSink, KafkaStatus};
+use crate::lsl_outlet::{LslConfig, LslOutlet, LslStatus};
+use crate::lttb_worker::LttbWorker;
+use crate::orthostatic::{self, OrthostaticSession};
+use crate::osc_output::{OscConfig, OscOutput, OscStatus};
+use crate::pipeline::{self, PipelineRuntime, StageOutputs};
+use crate::sample_clock::{ClockJump, SampleClock};
+use crate::shared_memory::{SharedMemoryConfig, SharedMemoryStatus, SharedMemoryWriter};
+use crate::telemetry::{TelemetryConfig, TelemetryStatus, TelemetryUplink};
+use crate::thread_tuning::{self, ThreadRole};
+use crate::thresholds::{self, ThresholdMonitor};
+use crate::trends::{self, RollingStatistics, TrendAggregator};
 use crate::types::{
-    DataQueue, EcgProcessingState, LttbConfig, LttbDataPoint, LttbProcessingState,
-    ProcessedDataQueue, ProcessedVitalSigns, TemperatureProcessingState, VitalSigns,
+    Alarm, AlarmSuppression, AnnotationKind, ArtifactDetectionState, BackpressurePolicy, BeatClass,
+    BeatRecord, BloodPressureReading, BorgScore, BorgTiming, BpAlarmConfig, CapnographyData,
+    CapnographyProcessingState, ChannelAdjustment, ChannelPipeline, CuffStatus, CurrentVitalValue,
+    DataQueue, DerivedAlarmConfig, DerivedMetrics, DeviceCapabilities, EcgProcessingParams,
+    EcgProcessingState, EcgStatistics, EscalationConfig, HeartRateAveraging, HeartRateHistogram,
+    HeartRateZone, HourlySummary, HrRecoveryConfig, HrRecoveryStatus, HrvSpectrum, LttbConfig,
+    LttbDataPoint, LttbProcessingState, MeasurementDetails, MeasurementKind, MeasurementRecord,
+    MotionSuppressionConfig, NibpMeasurement, OrthostaticConfig, OrthostaticStatus,
+    PerformanceMetrics, PoincarePlot, ProcessedDataQueue, ProcessedVitalSigns, ProcessingStatus,
+    RespirationData, RespirationProcessingState, RestDetectionConfig, RestSummary, RrIntervalPoint,
+    SampleDropWarning, SessionAnnotation, SpectralMethod, Spo2Config, Spo2ProcessingState,
+    TemperatureAlarmConfig, TemperatureCalibration, TemperatureCalibrationPoint,
+    TemperatureProcessingState, ThreadTuningConfig, ThresholdConfig, ThresholdCrossing, TrendBin,
+    ValueQuality, VitalAlarmLimits, VitalSigns, VitalStatistics, WalkTestConfig, WalkTestStatus,
+    WalkTestSummary,
 };
-use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, Ordering};
+use crate::walk_test::{self, WalkTestSession};
+use crate::watchdog::{join_with_timeout, panic_message, Heartbeat};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread;
+use std::thread::{self, JoinHandle};
 use std::time::Duration;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
+/// 支持增益/反相/偏移设置的通道
+pub const ADJUSTABLE_CHANNELS: &[&str] = &["ecg", "spo2", "temp"];
+
+/// RR间期序列保留的最大心搏数（约1小时的心搏）
+const RR_HISTORY_CAPACITY: usize = 4096;
+
+/// 伪差检测的ECG方差窗口（250Hz下1秒）
+const ARTIFACT_WINDOW_SIZE: usize = 250;
+
+/// 起搏脉冲后判定为起搏心搏的时间窗口（250Hz下300ms）
+const PACED_BEAT_WINDOW_SAMPLES: u32 = 75;
+
+/// 判断提前心搏时参考的最近心搏数
+const PREMATURE_REFERENCE_BEATS: usize = 8;
+
+/// RR间期短于参考中位数的该比例视为提前心搏
+const PREMATURE_RR_RATIO: f64 = 0.8;
+
+/// 生理上可能的心率范围（bpm），超出范围的瞬时心率视为误检，不更新心率
+const HEART_RATE_PLAUSIBLE_RANGE: (f64, f64) = (20.0, 300.0);
+
+/// 间歇性测量历史记录的最大保存条数
+const MEASUREMENT_HISTORY_CAPACITY: usize = 1024;
+
+/// 血糖有效范围（mmol/L），超出范围的上报视为无效
+pub(crate) const GLUCOSE_VALID_RANGE: (f64, f64) = (1.0, 40.0);
+
+/// 校准时参与平均的最近体温读数数量（250Hz下2秒）
+const TEMP_CALIBRATION_WINDOW: usize = 500;
+
+/// 两个校准点的未校准读数至少相差的温度（摄氏度）
+const TEMP_CALIBRATION_MIN_SPAN: f64 = 0.5;
+
+/// 没有心搏时血氧平均的采样间隔（毫秒），例如ECG通道关闭时
+const SPO2_FALLBACK_INTERVAL_MS: u64 = 1500;
+
+/// 连续多少次偏离平均值后视为真实变化而不是尖峰
+const SPO2_MAX_CONSECUTIVE_SPIKES: u32 = 3;
+
+/// 没有有效血氧读数（探头脱落、读数缺失或伪差）时保持平均值的最长时间（毫秒），
+/// 超过后清空平均并输出0，由当前值的不可用/过期判断和报警接手
+const SPO2_HOLD_MAX_AGE_MS: u64 = 3_000;
+
+/// 无创血压历史记录的最大保存条数
+const BP_HISTORY_CAPACITY: usize = 1024;
+
+/// 趋势聚合段时长（1分钟）
+const TREND_BIN_MS: u64 = 60_000;
+
+/// 每项体征保留的趋势聚合段数量（24小时）
+const TREND_CAPACITY: usize = 24 * 60;
+
+/// 滚动统计的默认窗口时长（5分钟）
+const DEFAULT_STATISTICS_WINDOW_MS: u64 = 5 * 60_000;
+
+/// 滚动统计窗口时长的允许范围（秒）
+const STATISTICS_WINDOW_RANGE_SECS: (u64, u64) = (10, 3_600);
+
+/// 体征概览的默认时间跨度（毫秒）
+const VITAL_SUMMARY_SPAN_MS: u64 = 24 * 3_600_000;
+
+/// ECG波峰检测阈值范围
+const PEAK_THRESHOLD_RANGE: (f64, f64) = (0.1, 0.95);
+
+/// ECG动态范围更新间隔范围（采样点数）
+const THRESHOLD_UPDATE_INTERVAL_RANGE: (u32, u32) = (50, 5_000);
+
+/// ECG原始数据缓冲区大小范围（采样点数）
+const ECG_RAW_BUFFER_RANGE: (usize, usize) = (50, 5_000);
+
+/// 心率平均使用的心搏记录数范围
+const HEART_RATE_HISTORY_RANGE: (usize, usize) = (4, 1_024);
+
+/// 体征当前值的有效期，超过该时长（毫秒）未更新的读数视为过期
+const CURRENT_VALUE_MAX_AGE_MS: u64 = 5_000;
+
+/// 内置通道对应的报警体征和趋势体征，通道关闭时解除这些报警、保存会话时不包含这些趋势
+const CHANNEL_VITALS: &[(&str, &[&str])] = &[
+    (
+        "ecg",
+        &[
+            "heart_rate",
+            "shock_index",
+            "modified_shock_index",
+            "rate_pressure_product",
+        ],
+    ),
+    ("spo2", &["spo2"]),
+    ("temp", &["temperature"]),
+    ("resp", &["respiration", "respiration_rate"]),
+    ("co2", &["etco2"]),
+    (
+        "nibp",
+        &[
+            "blood_pressure",
+            "systolic",
+            "diastolic",
+            "mean_arterial_pressure",
+            "shock_index",
+            "modified_shock_index",
+            "rate_pressure_product",
+        ],
+    ),
+    ("glucose", &["glucose"]),
+];
+
+/// 通道对应的体征，扩展通道的体征ID与通道ID相同
+pub(crate) fn channel_vitals(channel: &str) -> Vec<&str> {
+    CHANNEL_VITALS
+        .iter()
+        .find(|(id, _)| *id == channel)
+        .map_or_else(|| vec![channel], |(_, vitals)| vitals.to_vec())
+}
+
+/// 血压测量用于计算衍生指标的有效期（15分钟）
+const BP_FRESHNESS_MS: u64 = 15 * 60_000;
+
+/// 收缩压有效范围（mmHg）
+pub(crate) const SYSTOLIC_VALID_RANGE: (i32, i32) = (40, 300);
+
+/// 舒张压有效范围（mmHg）
+pub(crate) const DIASTOLIC_VALID_RANGE: (i32, i32) = (20, 200);
+
+/// 呼吸暂停判定时长的允许范围（秒）
+const APNEA_THRESHOLD_RANGE_SECS: (u64, u64) = (5, 120);
+
+/// 会话标注的最大保存条数
+const ANNOTATION_CAPACITY: usize = 1024;
+
+/// 计算处理延迟使用的最近采样数（250Hz下1秒）
+const LATENCY_WINDOW: usize = 250;
+
+/// 呼吸波形LTTB缓冲区大小（250Hz下10秒）
+const RESPIRATION_BUFFER_SIZE: usize = 2500;
+
+/// 处理后数据队列的默认容量（采样数）
+const DEFAULT_PROCESSED_CAPACITY: usize = 1000;
+
+/// 最多保留的空闲LTTB数据点缓冲区数量
+const LTTB_POOL_CAPACITY: usize = 16;
+
+/// 原始数据队列积压超过容量的这一比例时启用反压策略
+const BACKPRESSURE_HIGH_WATER: f64 = 0.75;
+
+/// 积压回落到容量的这一比例以下时停止反压
+const BACKPRESSURE_LOW_WATER: f64 = 0.25;
+
+/// 停止时等待处理线程退出的最长时间，处理线程空闲时每轮最多休眠200毫秒
+const STOP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 处理线程与命令接口共享的各项处理状态
+#[derive(Clone)]
+struct ProcessingStates {
+    /// ECG数据处理状态，包含心率计算和波峰检测状态
+    ecg_state: Arc<Mutex<EcgProcessingState>>,
+    /// 体温数据处理状态，包含滤波和校准参数
+    temp_state: Arc<Mutex<TemperatureProcessingState>>,
+    /// LTTB算法处理状态，包含压缩缓冲区和配置
+    lttb_state: Arc<Mutex<LttbProcessingState>>,
+    /// 空闲的LTTB数据点缓冲区，被丢弃的处理结果交还后供新采样复用
+    lttb_pool: Arc<Mutex<Vec<Vec<LttbDataPoint>>>>,
+    /// 各波形共用的LTTB压缩工作线程
+    lttb_worker: Arc<LttbWorker>,
+    /// 运动/伪差检测状态
+    artifact_state: Arc<Mutex<ArtifactDetectionState>>,
+    /// 血氧验证和平均状态
+    spo2_state: Arc<Mutex<Spo2ProcessingState>>,
+    /// 呼吸波形处理状态
+    resp_state: Arc<Mutex<RespirationProcessingState>>,
+    /// 呼吸波形的LTTB处理状态
+    resp_lttb_state: Arc<Mutex<LttbProcessingState>>,
+    /// 二氧化碳波形处理状态
+    co2_state: Arc<Mutex<CapnographyProcessingState>>,
+    /// 二氧化碳波形的LTTB处理状态
+    co2_lttb_state: Arc<Mutex<LttbProcessingState>>,
+    /// 无创血压测量历史记录（包含袖带状态异常的测量）
+    bp_history: Arc<Mutex<VecDeque<BloodPressureReading>>>,
+    /// 体征趋势聚合
+    trends: Arc<Mutex<TrendAggregator>>,
+    /// 心率、血氧和体温的滚动统计
+    rolling_stats: Arc<Mutex<RollingStatistics>>,
+    /// 提示性阈值监测
+    thresholds: Arc<Mutex<ThresholdMonitor>>,
+    /// 休息/活动判定
+    activity: Arc<Mutex<ActivityClassifier>>,
+    /// 报警引擎
+    alarm_engine: Arc<Mutex<AlarmEngine>>,
+    /// 当前监护会话ID，每个数据处理器实例对应一个会话
+    session_id: String,
+    /// 当前会话的事件标注（例如呼吸暂停片段）
+    annotations: Arc<Mutex<VecDeque<SessionAnnotation>>>,
+    /// 体位性生命体征测试，完成后保留至下次开始或取消
+    orthostatic_test: Arc<Mutex<Option<OrthostaticSession>>>,
+    /// 心率恢复测试，完成后保留至下次开始或取消
+    hr_recovery_test: Arc<Mutex<Option<HrRecoverySession>>>,
+    /// 六分钟步行试验，结束后保留至下次开始或取消
+    walk_test: Arc<Mutex<Option<WalkTestSession>>>,
+    /// OSC输出，未启用时为None
+    osc_output: Arc<Mutex<Option<OscOutput>>>,
+    /// LSL输出，未启用时为None
+    lsl_outlet: Arc<Mutex<Option<LslOutlet>>>,
+    /// 共享内存输出，未启用时为None
+    shared_memory: Arc<Mutex<Option<SharedMemoryWriter>>>,
+    /// Kafka输出，未启用时为None
+    kafka_sink: Arc<Mutex<Option<KafkaSink>>>,
+    /// 云端遥测上报，未启用时为None
+    telemetry: Arc<Mutex<Option<TelemetryUplink>>>,
+    /// HL7结果发送，未启用时为None
+    hl7_sender: Arc<Mutex<Option<Hl7Sender>>>,
+    /// 原始心电存档，与应用共享
+    ecg_archive: Arc<Mutex<Option<Arc<EcgArchive>>>>,
+    /// 血糖等间歇性测量的历史记录
+    measurement_history: Arc<Mutex<VecDeque<MeasurementRecord>>>,
+    /// 各通道的处理流水线，键为通道ID
+    pipelines: Arc<Mutex<BTreeMap<String, PipelineRuntime>>>,
+    /// 已关闭的通道ID集合，关闭的通道不参与处理
+    disabled_channels: Arc<Mutex<BTreeSet<String>>>,
+    /// 按设备能力自动关闭的通道ID集合，只有这些通道会在设备能力变化时自动重新打开
+    auto_disabled_channels: Arc<Mutex<BTreeSet<String>>>,
+    /// 扩展通道的处理插件，键为通道ID
+    channel_plugins: Arc<Mutex<BTreeMap<String, Box<dyn ChannelPlugin>>>>,
+    /// LTTB算法配置参数
+    lttb_config: LttbConfig,
+    /// 心率平均策略，可在运行时调整
+    hr_averaging: Arc<Mutex<HeartRateAveraging>>,
+    /// 各通道的增益/反相/偏移设置，键为通道名
+    channel_adjustments: Arc<Mutex<BTreeMap<String, ChannelAdjustment>>>,
+}
+
+impl ProcessingStates {
+    /// 清除处理线程panic后各项状态的中毒标记，命令接口可以继续读取
+    fn clear_poison(&self) {
+        self.ecg_state.clear_poison();
+        self.temp_state.clear_poison();
+        self.lttb_state.clear_poison();
+        self.lttb_pool.clear_poison();
+        self.artifact_state.clear_poison();
+        self.spo2_state.clear_poison();
+        self.resp_state.clear_poison();
+        self.resp_lttb_state.clear_poison();
+        self.co2_state.clear_poison();
+        self.co2_lttb_state.clear_poison();
+        self.bp_history.clear_poison();
+        self.trends.clear_poison();
+        self.rolling_stats.clear_poison();
+        self.thresholds.clear_poison();
+        self.activity.clear_poison();
+        self.alarm_engine.clear_poison();
+        self.annotations.clear_poison();
+        self.orthostatic_test.clear_poison();
+        self.hr_recovery_test.clear_poison();
+        self.walk_test.clear_poison();
+        self.osc_output.clear_poison();
+        self.lsl_outlet.clear_poison();
+        self.shared_memory.clear_poison();
+        self.kafka_sink.clear_poison();
+        self.telemetry.clear_poison();
+        self.hl7_sender.clear_poison();
+        self.ecg_archive.clear_poison();
+        self.measurement_history.clear_poison();
+        self.pipelines.clear_poison();
+        self.disabled_channels.clear_poison();
+        self.auto_disabled_channels.clear_poison();
+        self.channel_plugins.clear_poison();
+        self.hr_averaging.clear_poison();
+        self.channel_adjustments.clear_poison();
+    }
+}
+
 /// 数据处理器主结构
 ///
 /// 负责管理所有体征数据的处理流程，包括原始数据队列、处理后数据队列、
@@ -27,18 +339,51 @@ pub struct DataProcessor {
     raw_data_queue: DataQueue,
     /// 处理后数据队列，存储经过算法处理的数据
     processed_data_queue: ProcessedDataQueue,
-    /// ECG数据处理状态，包含心率计算和波峰检测状态
-    ecg_state: Arc<Mutex<EcgProcessingState>>,
-    /// 体温数据处理状态，包含滤波和校准参数
-    temp_state: Arc<Mutex<TemperatureProcessingState>>,
-    /// LTTB算法处理状态，包含压缩缓冲区和配置
-    lttb_state: Arc<Mutex<LttbProcessingState>>,
-    /// LTTB算法配置参数
-    lttb_config: LttbConfig,
+    /// 放入处理后数据队列的采样总数，在持有队列锁时更新，用作增量读取的游标
+    processed_sequence: Arc<AtomicU64>,
+    /// 处理后数据队列的容量，可由缓冲区自动调节在运行中修改
+    processed_capacity: Arc<AtomicUsize>,
+    /// 采样时间轴，处理线程重启后沿用，保证时间戳单调递增
+    sample_clock: Arc<Mutex<SampleClock>>,
+    /// 各项处理状态
+    states: ProcessingStates,
+    /// 进行中的体温校准已采集的校准点，未在校准时为None
+    temp_calibration: Mutex<Option<Vec<TemperatureCalibrationPoint>>>,
     /// 数据处理线程运行状态标志
     is_running: Arc<AtomicBool>,
+    /// 处理线程代数，每次启动加一，旧线程发现代数变化后退出
+    generation: Arc<AtomicU64>,
+    /// 处理线程心跳，每轮循环更新一次
+    heartbeat: Heartbeat,
     /// 处理的数据点总数
     total_processed: Arc<Mutex<u64>>,
+    /// 处理速率和延迟统计
+    metrics: Arc<Mutex<ProcessingMetrics>>,
+    /// 处理跟不上采样速率时的反压策略
+    backpressure_policy: Arc<Mutex<BackpressurePolicy>>,
+    /// 处理线程的优先级和CPU亲和性配置
+    thread_tuning: Mutex<ThreadTuningConfig>,
+    /// 处理线程状态
+    status: Arc<Mutex<ProcessingStatus>>,
+    /// 当前处理线程的句柄，停止时等待线程退出
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// 处理线程的性能统计
+#[derive(Debug, Default)]
+struct ProcessingMetrics {
+    /// 最近采样的处理延迟（毫秒）
+    latencies_ms: VecDeque<u64>,
+    /// 最近一个统计周期的处理速率（点/秒）
+    processing_rate: f64,
+    /// 反压策略丢弃的采样数
+    dropped_samples: u64,
+    /// 处理器创建时原始数据队列的溢出计数，之前的溢出不计入本处理器
+    overflow_baseline: u64,
+    /// 已通过警告报告的丢弃采样数
+    reported_drops: u64,
+    /// 最近处理的采样放入原始数据队列的时间戳（毫秒）
+    last_received_at: Option<u64>,
 }
 
 impl DataProcessor {
@@ -50,10 +395,13 @@ impl DataProcessor {
     /// # 返回值
     /// 返回配置完成的DataProcessor实例
     pub fn new(raw_data_queue: DataQueue) -> Self {
-        // 初始化处理后数据队列，容量为1000个数据点
-        let processed_data_queue = Arc::new(Mutex::new(VecDeque::with_capacity(1000)));
+        // 初始化处理后数据队列
+        let processed_data_queue =
+            Arc::new(Mutex::new(VecDeque::with_capacity(DEFAULT_PROCESSED_CAPACITY)));
+        let overflow_baseline = raw_data_queue.overflowed();
 
         // 初始化ECG处理状态
+        let ecg_params = EcgProcessingParams::default();
         let ecg_state = Arc::new(Mutex::new(EcgProcessingState {
             last_heart_rate: 0.0,
             last_rr_interval: 0.0,
@@ -64,7 +412,18 @@ impl DataProcessor {
             ecg_points: VecDeque::with_capacity(3),
             peak_interval_num: 0,
             counter: 0,
-            ecg_data_original_list: Vec::with_capacity(250),
+            ecg_data_original_list: Vec::with_capacity(ecg_params.raw_buffer_size),
+            last_averaged_heart_rate: 0.0,
+            recent_heart_rates: VecDeque::with_capacity(ecg_params.heart_rate_history_size),
+            rr_history: VecDeque::with_capacity(RR_HISTORY_CAPACITY),
+            beats: VecDeque::with_capacity(RR_HISTORY_CAPACITY),
+            skip_next_beat: false,
+            last_received_at: 0,
+            samples_since_pace: None,
+            pace_spike_count: 0,
+            paced_beats: 0,
+            total_beats: 0,
+            params: ecg_params,
         }));
 
         // 初始化体温处理状态
@@ -72,8 +431,25 @@ impl DataProcessor {
             temperatures: Vec::with_capacity(70),
             scale_factor: 0.8,
             offset: 0.0,
-            max_temp: 37.2,
+            max_temp: 45.0,
             room_temperature: 23.2,
+            recent_raw_values: VecDeque::with_capacity(TEMP_CALIBRATION_WINDOW),
+        }));
+
+        // 初始化伪差检测状态
+        let artifact_state = Arc::new(Mutex::new(ArtifactDetectionState {
+            max_ecg_slope: 40000.0,
+            variance_ratio_threshold: 6.0,
+            max_spo2_jump: 8.0,
+            pace_slew_threshold: 20000.0,
+            hold_samples: 125,
+            prev_ecg: None,
+            last_ecg: None,
+            ecg_window: VecDeque::with_capacity(ARTIFACT_WINDOW_SIZE),
+            baseline_variance: 0.0,
+            ecg_hold_remaining: 0,
+            last_spo2: None,
+            spo2_hold_remaining: 0,
         }));
 
         // 初始化LTTB处理状态
@@ -90,18 +466,147 @@ impl DataProcessor {
             sample_counter: 0,
         }));
 
+        // 初始化呼吸波形处理状态
+        let resp_state = Arc::new(Mutex::new(RespirationProcessingState {
+            apnea_threshold_ms: 20_000,
+            min_breath_interval_ms: 1_000,
+            initialized: false,
+            smoothed: 0.0,
+            baseline: 0.0,
+            envelope: 0.0,
+            armed: false,
+            last_breath_timestamp: None,
+            breath_intervals: VecDeque::with_capacity(8),
+            respiration_rate: 0.0,
+            apnea: false,
+            apnea_since: None,
+        }));
+        let resp_lttb_state =
+            Self::new_waveform_lttb_state(RESPIRATION_BUFFER_SIZE, lttb_config.compression_ratio);
+
+        // 初始化二氧化碳波形处理状态
+        let co2_state = Arc::new(Mutex::new(CapnographyProcessingState {
+            expiration_threshold: 8.0,
+            inspiration_threshold: 4.0,
+            min_breath_interval_ms: 1_000,
+            no_breath_timeout_ms: 30_000,
+            in_expiration: false,
+            expiration_peak: 0.0,
+            inspiration_min: f64::INFINITY,
+            last_breath_timestamp: None,
+            breath_intervals: VecDeque::with_capacity(8),
+            etco2: 0.0,
+            fico2: 0.0,
+            respiration_rate: 0.0,
+        }));
+        let co2_lttb_state =
+            Self::new_waveform_lttb_state(RESPIRATION_BUFFER_SIZE, lttb_config.compression_ratio);
+        let kafka_sink = Arc::new(Mutex::new(None));
+        let mut alarm_engine = AlarmEngine::new();
+        alarm_engine.add_listener(Box::new(KafkaAlarmForwarder::new(kafka_sink.clone())));
+
         Self {
             raw_data_queue,
             processed_data_queue,
-            ecg_state,
-            temp_state,
-            lttb_state,
-            lttb_config,
+            processed_sequence: Arc::new(AtomicU64::new(0)),
+            processed_capacity: Arc::new(AtomicUsize::new(DEFAULT_PROCESSED_CAPACITY)),
+            sample_clock: Arc::new(Mutex::new(SampleClock::new())),
+            states: ProcessingStates {
+                ecg_state,
+                temp_state,
+                lttb_state,
+                lttb_pool: Arc::new(Mutex::new(Vec::with_capacity(LTTB_POOL_CAPACITY))),
+                lttb_worker: Arc::new(LttbWorker::spawn()),
+                artifact_state,
+                spo2_state: Arc::new(Mutex::new(Spo2ProcessingState {
+                    config: Spo2Config::default(),
+                    values: VecDeque::with_capacity(16),
+                    consecutive_spikes: 0,
+                    last_sample_timestamp: None,
+                })),
+                resp_state,
+                resp_lttb_state,
+                co2_state,
+                co2_lttb_state,
+                bp_history: Arc::new(Mutex::new(VecDeque::with_capacity(BP_HISTORY_CAPACITY))),
+                trends: Arc::new(Mutex::new(TrendAggregator::new(
+                    TREND_BIN_MS,
+                    TREND_CAPACITY,
+                ))),
+                rolling_stats: Arc::new(Mutex::new(RollingStatistics::new(
+                    DEFAULT_STATISTICS_WINDOW_MS,
+                ))),
+                thresholds: Arc::new(Mutex::new(ThresholdMonitor::new())),
+                activity: Arc::new(Mutex::new(ActivityClassifier::new(TREND_BIN_MS))),
+                alarm_engine: Arc::new(Mutex::new(alarm_engine)),
+                session_id: chrono::Local::now().format("%Y%m%d-%H%M%S").to_string(),
+                annotations: Arc::new(Mutex::new(VecDeque::new())),
+                orthostatic_test: Arc::new(Mutex::new(None)),
+                hr_recovery_test: Arc::new(Mutex::new(None)),
+                walk_test: Arc::new(Mutex::new(None)),
+                osc_output: Arc::new(Mutex::new(None)),
+                lsl_outlet: Arc::new(Mutex::new(None)),
+                shared_memory: Arc::new(Mutex::new(None)),
+                kafka_sink,
+                telemetry: Arc::new(Mutex::new(None)),
+                hl7_sender: Arc::new(Mutex::new(None)),
+                ecg_archive: Arc::new(Mutex::new(None)),
+                measurement_history: Arc::new(Mutex::new(VecDeque::with_capacity(
+                    MEASUREMENT_HISTORY_CAPACITY,
+                ))),
+                channel_plugins: Arc::new(Mutex::new(BTreeMap::new())),
+                pipelines: Arc::new(Mutex::new(
+                    pipeline::pipeline_channels()
+                        .map(|c| {
+                            (
+                                c.to_string(),
+                                PipelineRuntime::new(pipeline::default_pipeline(c)),
+                            )
+                        })
+                        .collect(),
+                )),
+                disabled_channels: Arc::new(Mutex::new(BTreeSet::new())),
+                auto_disabled_channels: Arc::new(Mutex::new(BTreeSet::new())),
+                lttb_config,
+                hr_averaging: Arc::new(Mutex::new(HeartRateAveraging::default())),
+                channel_adjustments: Arc::new(Mutex::new(BTreeMap::new())),
+            },
+            temp_calibration: Mutex::new(None),
             is_running: Arc::new(AtomicBool::new(false)),
+            generation: Arc::new(AtomicU64::new(0)),
+            heartbeat: Heartbeat::new(),
             total_processed: Arc::new(Mutex::new(0)),
+            metrics: Arc::new(Mutex::new(ProcessingMetrics {
+                overflow_baseline,
+                ..ProcessingMetrics::default()
+            })),
+            backpressure_policy: Arc::new(Mutex::new(BackpressurePolicy::default())),
+            thread_tuning: Mutex::new(ThreadTuningConfig::default()),
+            status: Arc::new(Mutex::new(ProcessingStatus::Idle)),
+            worker: Mutex::new(None),
         }
     }
 
+    /// 创建波形通道（呼吸、二氧化碳）使用的LTTB处理状态
+    ///
+    /// # 参数
+    /// * `buffer_size` - 原始数据缓冲区大小
+    /// * `compression_ratio` - 压缩比例
+    fn new_waveform_lttb_state(
+        buffer_size: usize,
+        compression_ratio: usize,
+    ) -> Arc<Mutex<LttbProcessingState>> {
+        Arc::new(Mutex::new(LttbProcessingState {
+            raw_buffer: Vec::with_capacity(buffer_size),
+            compressed_buffer: Vec::with_capacity(buffer_size / compression_ratio),
+            buffer_size,
+            compression_ratio,
+            global_min: f64::INFINITY,
+            global_max: f64::NEG_INFINITY,
+            sample_counter: 0,
+        }))
+    }
+
     /// 启动数据处理线程
     ///
     /// 创建一个后台线程持续处理原始数据队列中的数据，
@@ -112,187 +617,2622 @@ impl DataProcessor {
         // 克隆所有需要在线程中使用的Arc引用
         let raw_queue = self.raw_data_queue.clone();
         let processed_queue = self.processed_data_queue.clone();
-        let ecg_state = self.ecg_state.clone();
-        let temp_state = self.temp_state.clone();
-        let lttb_state = self.lttb_state.clone();
-        let lttb_config = self.lttb_config.clone();
+        let processed_sequence = self.processed_sequence.clone();
+        let processed_capacity = self.processed_capacity.clone();
+        let sample_clock = self.sample_clock.clone();
+        let states = self.states.clone();
         let is_running = self.is_running.clone();
         let total_processed = self.total_processed.clone();
+        let metrics = self.metrics.clone();
+        let backpressure_policy = self.backpressure_policy.clone();
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        let current_generation = self.generation.clone();
+        let heartbeat = self.heartbeat.clone();
+        let thread_tuning = self.thread_tuning.lock().unwrap().clone();
+        let status = self.status.clone();
+        *status.lock().unwrap() = ProcessingStatus::Processing;
+        heartbeat.beat();
 
-        thread::spawn(move || {
+        let handle = thread::spawn(move || {
             println!("[DataProcessor] 数据处理线程已启动（包含LTTB压缩算法）");
+            thread_tuning::apply(&thread_tuning, ThreadRole::Processor);
             let mut consecutive_empty_count = 0;
             let mut last_performance_log = Instant::now();
+            let mut last_logged_count = *total_processed.lock().unwrap();
+            let capacity = raw_queue.capacity() as f64;
+            let high_water = ((capacity * BACKPRESSURE_HIGH_WATER) as usize).max(1);
+            let low_water = (capacity * BACKPRESSURE_LOW_WATER) as usize;
+            let mut overloaded = false;
+            // 处理线程当前是否空闲，只在状态变化时更新共享的处理状态
+            let mut idle = false;
 
-            while is_running.load(Ordering::Relaxed) {
-                // 从原始数据队列获取数据
-                let raw_data = {
-                    let mut queue = raw_queue.lock().unwrap();
-                    queue.pop_front()
-                };
+            // 处理线程panic时不让共享状态保持中毒，标记错误后退出，由看门狗按配置重启
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                while is_running.load(Ordering::Relaxed)
+                    && current_generation.load(Ordering::Relaxed) == generation
+                {
+                    heartbeat.beat();
 
-                if let Some(vital_signs) = raw_data {
-                    consecutive_empty_count = 0;
-
-                    // 处理数据（包含LTTB压缩）
-                    let processed = Self::process_vital_signs(
-                        vital_signs,
-                        &ecg_state,
-                        &temp_state,
-                        &lttb_state,
-                        &lttb_config,
-                    );
-
-                    // 更新处理计数
-                    {
-                        let mut count = total_processed.lock().unwrap();
-                        *count += 1;
+                    // 积压超过高水位时按反压策略丢弃采样，回落到低水位后恢复正常处理
+                    let backlog = raw_queue.queued();
+                    if backlog >= high_water {
+                        overloaded = true;
+                    } else if backlog <= low_water {
+                        overloaded = false;
                     }
-
-                    // 定期输出性能信息（每5秒一次）
-                    if last_performance_log.elapsed() >= Duration::from_secs(5) {
-                        let count = *total_processed.lock().unwrap();
-                        let lttb_state_guard = lttb_state.lock().unwrap();
-                        println!("[DataProcessor] 性能统计: 已处理{}个数据点, LTTB缓冲区:{}/{}, 压缩数据点:{}", 
-                                 count,
-                                 lttb_state_guard.raw_buffer.len(),
-                                 lttb_state_guard.buffer_size,
-                                 lttb_state_guard.compressed_buffer.len());
-                        last_performance_log = Instant::now();
+                    if overloaded {
+                        let dropped = match *backpressure_policy.lock().unwrap() {
+                            BackpressurePolicy::DropOldest => {
+                                overloaded = false;
+                                raw_queue.discard(backlog.saturating_sub(low_water))
+                            }
+                            BackpressurePolicy::Downsample => raw_queue.discard(1),
+                        };
+                        metrics.lock().unwrap().dropped_samples += dropped;
                     }
 
-                    // 输出处理后的数据到控制台（简化版）
-                    // if consecutive_empty_count == 0 { // 只在重新开始处理时输出
-                    //     println!("[DataProcessor] ECG原始={}, 归一化={:.3}, 压缩点数={}, 体温={:.2}°C, 心率={:.1}bpm",
-                    //              processed.ecg_raw,
-                    //              processed.ecg_normalized,
-                    //              processed.ecg_lttb_compressed.len(),
-                    //              processed.body_temperature,
-                    //              processed.heart_rate);
-                    // }
-
-                    // 存储处理后的数据
-                    let mut processed_queue = processed_queue.lock().unwrap();
-                    if processed_queue.len() >= 1000 {
-                        processed_queue.pop_front();
-                    }
-                    processed_queue.push_back(processed);
-                } else {
-                    consecutive_empty_count += 1;
-                    // 动态调整休眠时间，避免过度占用CPU
-                    let sleep_time = if consecutive_empty_count < 10 {
-                        Duration::from_millis(50) // 短期无数据，短暂休眠
+                    // 从原始数据队列获取数据
+                    let raw_data = raw_queue.pop();
+
+                    if let Some(vital_signs) = raw_data {
+                        consecutive_empty_count = 0;
+                        if idle {
+                            idle = false;
+                            *status.lock().unwrap() = ProcessingStatus::Processing;
+                        }
+                        let received_at = vital_signs.received_at;
+
+                        // 先做通道校正，后续检测和归一化都基于校正后的数据
+                        let vital_signs =
+                            Self::apply_channel_adjustments(vital_signs, &states.channel_adjustments);
+
+                        // 采样时间戳取单调时间轴，系统时钟调整时记录标注
+                        let (timestamp, clock_jump) = sample_clock.lock().unwrap().now();
+                        if let Some(jump) = clock_jump {
+                            Self::record_clock_adjustment(jump, &states);
+                        }
+
+                        // 处理数据（包含LTTB压缩）
+                        let processed = Self::process_vital_signs(vital_signs, timestamp, &states);
+
+                        // 更新处理计数
+                        {
+                            let mut count = total_processed.lock().unwrap();
+                            *count += 1;
+                        }
+                        if received_at > 0 {
+                            let latency = Self::wall_millis().saturating_sub(received_at);
+                            let mut metrics = metrics.lock().unwrap();
+                            metrics.last_received_at = Some(received_at);
+                            if metrics.latencies_ms.len() >= LATENCY_WINDOW {
+                                metrics.latencies_ms.pop_front();
+                            }
+                            metrics.latencies_ms.push_back(latency);
+                        }
+
+                        // 定期输出性能信息（每5秒一次）
+                        if last_performance_log.elapsed() >= Duration::from_secs(5) {
+                            let count = *total_processed.lock().unwrap();
+                            metrics.lock().unwrap().processing_rate = (count - last_logged_count)
+                                as f64
+                                / last_performance_log.elapsed().as_secs_f64();
+                            last_logged_count = count;
+                            let lttb_state_guard = states.lttb_state.lock().unwrap();
+                            println!("[DataProcessor] 性能统计: 已处理{}个数据点, LTTB缓冲区:{}/{}, 压缩数据点:{}", 
+                                     count,
+                                     lttb_state_guard.raw_buffer.len(),
+                                     lttb_state_guard.buffer_size,
+                                     lttb_state_guard.compressed_buffer.len());
+                            last_performance_log = Instant::now();
+                        }
+
+                        hot_log!(
+                            "[DataProcessor] ECG原始={}, 归一化={:.3}, 压缩点数={}, 体温={:.2?}°C, 心率={:.1}bpm",
+                            processed.ecg_raw,
+                            processed.ecg_normalized,
+                            processed.ecg_lttb_compressed.len(),
+                            processed.body_temperature,
+                            processed.heart_rate
+                        );
+
+                        // 存储处理后的数据
+                        let mut processed_queue = processed_queue.lock().unwrap();
+                        let capacity = processed_capacity.load(Ordering::Relaxed);
+                        while processed_queue.len() >= capacity {
+                            match processed_queue.pop_front() {
+                                Some(old) => Self::recycle_into(&states.lttb_pool, old),
+                                None => break,
+                            }
+                        }
+                        processed_queue.push_back(processed);
+                        processed_sequence.fetch_add(1, Ordering::Relaxed);
                     } else {
-                        Duration::from_millis(200) // 长期无数据，较长休眠
-                    };
-                    thread::sleep(sleep_time);
+                        consecutive_empty_count += 1;
+                        // 动态调整休眠时间，避免过度占用CPU
+                        let sleep_time = if consecutive_empty_count < 10 {
+                            Duration::from_millis(50) // 短期无数据，短暂休眠
+                        } else {
+                            if !idle {
+                                idle = true;
+                                *status.lock().unwrap() = ProcessingStatus::Idle;
+                            }
+                            Duration::from_millis(200) // 长期无数据，较长休眠
+                        };
+                        thread::sleep(sleep_time);
+                    }
+                }
+            }));
+            match outcome {
+                Ok(()) => println!("[DataProcessor] 数据处理线程已停止"),
+                Err(payload) => {
+                    let message = panic_message(payload.as_ref());
+                    eprintln!("[DataProcessor] 数据处理线程异常退出: {}", message);
+                    states.clear_poison();
+                    processed_queue.clear_poison();
+                    total_processed.clear_poison();
+                    metrics.clear_poison();
+                    backpressure_policy.clear_poison();
+                    sample_clock.clear_poison();
+                    *status.lock().unwrap() = ProcessingStatus::Error(message);
                 }
             }
-
-            println!("[DataProcessor] 数据处理线程已停止");
         });
+        // 重启时停滞的旧线程不再等待，代数变化后自行退出
+        *self.worker.lock().unwrap() = Some(handle);
     }
 
-    /// 停止数据处理线程
-    pub fn stop(&self) {
+    /// 停止数据处理线程并等待其退出
+    ///
+    /// # 返回值
+    /// 处理线程在 `STOP_TIMEOUT` 内未退出时返回错误信息，此时线程留在后台，
+    /// 发现停止标志后自行退出
+    pub fn stop(&self) -> Result<(), String> {
         self.is_running.store(false, Ordering::Relaxed);
+        *self.status.lock().unwrap() = ProcessingStatus::Idle;
+        match self.worker.lock().unwrap().take() {
+            Some(handle) => join_with_timeout(handle, STOP_TIMEOUT, "数据处理线程"),
+            None => Ok(()),
+        }
+    }
+
+    /// 获取处理线程状态
+    ///
+    /// 处理线程持续没有数据时为 `Idle`，处理采样期间LTTB工作线程有压缩任务时为 `Compressing`，
+    /// 处理线程panic退出后为 `Error`，重启后恢复为 `Processing`
+    pub fn get_processing_status(&self) -> ProcessingStatus {
+        match self.status.lock().unwrap().clone() {
+            ProcessingStatus::Processing if self.states.lttb_worker.is_busy() => {
+                ProcessingStatus::Compressing
+            }
+            status => status,
+        }
+    }
+
+    /// 重启数据处理线程，处理状态保留，停滞的旧线程恢复后自行退出
+    pub fn restart(&self) {
+        println!("[DataProcessor] 重启数据处理线程");
+        self.start();
+    }
+
+    /// 重置处理状态，不断开数据源也不停止处理线程
+    ///
+    /// 清除ECG动态范围和波峰检测窗口、体温平滑历史、各波形的LTTB缓冲区和归一化范围
+    /// 以及滚动统计，用于更换电极后或归一化被早先的伪差尖峰卡住时重新开始。
+    /// 心搏记录、趋势、报警和会话标注保留，并在会话中记录一条重置标注。
+    pub fn reset_processing_state(&self) {
+        {
+            let mut ecg_state = self.states.ecg_state.lock().unwrap();
+            ecg_state.ecg_point_max = f64::NEG_INFINITY;
+            ecg_state.ecg_point_min = f64::INFINITY;
+            ecg_state.ecg_point_max_new = 0.0;
+            ecg_state.ecg_point_min_new = f64::INFINITY;
+            ecg_state.ecg_points.clear();
+            ecg_state.peak_interval_num = 0;
+            ecg_state.counter = 0;
+            ecg_state.ecg_data_original_list.clear();
+            // 重置前后的R波间期不可信，下一个R波只作为新的计时起点
+            ecg_state.skip_next_beat = true;
+        }
+        {
+            let mut temp_state = self.states.temp_state.lock().unwrap();
+            temp_state.temperatures.clear();
+            temp_state.recent_raw_values.clear();
+        }
+        for lttb_state in [
+            &self.states.lttb_state,
+            &self.states.resp_lttb_state,
+            &self.states.co2_lttb_state,
+        ] {
+            let mut lttb_state = lttb_state.lock().unwrap();
+            lttb_state.raw_buffer.clear();
+            lttb_state.compressed_buffer.clear();
+            lttb_state.global_min = f64::INFINITY;
+            lttb_state.global_max = f64::NEG_INFINITY;
+            lttb_state.sample_counter = 0;
+        }
+        {
+            let mut rolling_stats = self.states.rolling_stats.lock().unwrap();
+            *rolling_stats = RollingStatistics::new(rolling_stats.window_ms());
+        }
+
+        let timestamp = self.sample_time();
+        let mut annotations = self.states.annotations.lock().unwrap();
+        if annotations.len() >= ANNOTATION_CAPACITY {
+            annotations.pop_front();
+        }
+        annotations.push_back(SessionAnnotation {
+            session_id: self.states.session_id.clone(),
+            kind: AnnotationKind::ProcessingReset,
+            message: "处理状态已重置".to_string(),
+            start_timestamp: timestamp,
+            end_timestamp: timestamp,
+            duration_ms: 0,
+        });
+        println!("[DataProcessor] 处理状态已重置");
+    }
+
+    /// 获取最近处理的采样放入原始数据队列的时间戳（毫秒），尚未处理带接收时间的采样时返回None
+    pub fn last_received_at(&self) -> Option<u64> {
+        self.metrics.lock().unwrap().last_received_at
+    }
+
+    /// 获取处理线程的性能指标
+    ///
+    /// 串口吞吐量字段（`samples_per_second`、`bytes_per_second`）由串口管理器填写，
+    /// 内存和CPU使用率暂不采集，均为0
+    pub fn get_performance_metrics(&self) -> PerformanceMetrics {
+        let metrics = self.metrics.lock().unwrap();
+        let average_latency_ms = if metrics.latencies_ms.is_empty() {
+            0.0
+        } else {
+            metrics.latencies_ms.iter().sum::<u64>() as f64 / metrics.latencies_ms.len() as f64
+        };
+        let compression_ratio = self.states.lttb_config.compression_ratio.max(1) as f64;
+        PerformanceMetrics {
+            processing_rate: metrics.processing_rate,
+            memory_usage: 0.0,
+            cpu_usage: 0.0,
+            queue_length: self.raw_data_queue.queued(),
+            compression_ratio_achieved: (1.0 - 1.0 / compression_ratio) * 100.0,
+            samples_per_second: 0.0,
+            bytes_per_second: 0.0,
+            average_latency_ms,
+            max_latency_ms: metrics.latencies_ms.iter().copied().max().unwrap_or(0) as f64,
+            dropped_samples: Self::total_dropped(&metrics, &self.raw_data_queue),
+        }
+    }
+
+    /// 处理器创建以来丢弃的采样数（反压策略丢弃加上原始数据队列溢出）
+    fn total_dropped(metrics: &ProcessingMetrics, raw_queue: &DataQueue) -> u64 {
+        metrics.dropped_samples
+            + raw_queue
+                .overflowed()
+                .saturating_sub(metrics.overflow_baseline)
+    }
+
+    /// 设置处理后数据队列的容量，超出的旧数据在下一个采样处理完成时丢弃
+    pub fn set_processed_queue_capacity(&self, capacity: usize) {
+        self.processed_capacity.store(capacity.max(1), Ordering::Relaxed);
+    }
+
+    /// 设置ECG的LTTB缓冲区大小，缓冲区下次写满时按新大小压缩
+    pub fn set_lttb_buffer_size(&self, buffer_size: usize) {
+        let mut lttb_state = self.states.lttb_state.lock().unwrap();
+        lttb_state.buffer_size = buffer_size.max(lttb_state.compression_ratio);
+    }
+
+    /// LTTB压缩比
+    pub fn lttb_compression_ratio(&self) -> usize {
+        self.states.lttb_config.compression_ratio
+    }
+
+    /// 设置处理线程的优先级和CPU亲和性，下次调用 `start` 时生效
+    pub fn set_thread_tuning(&self, config: ThreadTuningConfig) {
+        *self.thread_tuning.lock().unwrap() = config;
+    }
+
+    /// 设置反压策略
+    pub fn set_backpressure_policy(&self, policy: BackpressurePolicy) {
+        *self.backpressure_policy.lock().unwrap() = policy;
+        println!("[DataProcessor] 反压策略: {:?}", policy);
+    }
+
+    /// 获取反压策略
+    pub fn get_backpressure_policy(&self) -> BackpressurePolicy {
+        *self.backpressure_policy.lock().unwrap()
+    }
+
+    /// 取出上次检查以来的采样丢弃警告，没有新的丢弃时返回None
+    pub fn take_sample_drop_warning(&self) -> Option<SampleDropWarning> {
+        let mut metrics = self.metrics.lock().unwrap();
+        let total_dropped = Self::total_dropped(&metrics, &self.raw_data_queue);
+        if total_dropped <= metrics.reported_drops {
+            return None;
+        }
+        let dropped = total_dropped - metrics.reported_drops;
+        metrics.reported_drops = total_dropped;
+        Some(SampleDropWarning {
+            policy: self.get_backpressure_policy(),
+            dropped,
+            total_dropped,
+            queue_length: self.raw_data_queue.queued(),
+            timestamp: self.sample_time(),
+        })
+    }
+
+    /// 获取处理线程的心跳
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
     }
 
     /// 获取最新的处理后数据
     ///
     /// # 参数
     /// * `count` - 要获取的数据点数量
+    /// * `since` - 起始时间戳（毫秒，包含）
+    /// * `until` - 结束时间戳（毫秒，包含）
     ///
     /// # 返回值
-    /// 返回最新的处理后数据向量，按时间倒序排列
-    pub fn get_processed_data(&self, count: usize) -> Vec<ProcessedVitalSigns> {
+    /// 返回时间范围内最新的处理后数据向量，按时间倒序排列
+    pub fn get_processed_data(
+        &self,
+        count: usize,
+        since: Option<u64>,
+        until: Option<u64>,
+    ) -> Vec<ProcessedVitalSigns> {
         let queue = self.processed_data_queue.lock().unwrap();
-        queue.iter().rev().take(count).cloned().collect()
+        queue
+            .iter()
+            .rev()
+            .skip_while(|d| until.is_some_and(|t| d.timestamp > t))
+            .take_while(|d| since.is_none_or(|t| d.timestamp >= t))
+            .take(count)
+            .cloned()
+            .collect()
     }
 
-    /// 获取LTTB压缩后的ECG数据
+    /// 获取当前的处理后数据游标，即已放入处理后数据队列的采样总数
+    pub fn processed_cursor(&self) -> u64 {
+        let _queue = self.processed_data_queue.lock().unwrap();
+        self.processed_sequence.load(Ordering::Relaxed)
+    }
+
+    /// 获取游标之后处理完成的数据
+    ///
+    /// # 参数
+    /// * `cursor` - 上次读取返回的游标，首次读取时传0
+    /// * `max_count` - 最多返回的采样数，超出时只返回最新的部分
     ///
     /// # 返回值
-    /// 返回当前LTTB压缩缓冲区中的所有数据点
-    pub fn get_lttb_compressed_data(&self) -> Vec<LttbDataPoint> {
-        let lttb_state = self.lttb_state.lock().unwrap();
-        lttb_state.compressed_buffer.clone()
+    /// (按处理先后排列的采样, 新游标, 因超出上限或已被处理队列覆盖而跳过的采样数)
+    pub fn get_processed_since(
+        &self,
+        cursor: u64,
+        max_count: usize,
+    ) -> (Vec<ProcessedVitalSigns>, u64, u64) {
+        let queue = self.processed_data_queue.lock().unwrap();
+        let total = self.processed_sequence.load(Ordering::Relaxed);
+        let pending = total.saturating_sub(cursor);
+        let count = pending.min(queue.len() as u64).min(max_count as u64) as usize;
+        let samples = queue.iter().skip(queue.len() - count).cloned().collect();
+        (samples, total, pending - count as u64)
     }
 
-    /// 处理单个体征数据点
+    /// 获取单项体征的当前值
     ///
-    /// 这是核心处理函数，集成了所有数据处理算法：
-    /// - ECG数据的LTTB压缩和归一化
-    /// - 体温数据的滤波和校准
-    /// - 血氧数据的验证
-    /// - 心率和RR间隔的计算
+    /// 从最新的处理后采样往前查找该体征最近一次的有效读数（大于0），
+    /// 设备以较低频率上报的体征不会因为最新采样中没有读数而显示为无效。
+    ///
+    /// # 参数
+    /// * `channel` - 来源通道ID，通道关闭时该体征不可用
+    /// * `value` - 从处理后采样中取该体征的数值
+    /// * `unit` - 单位
+    /// * `artifact_sensitive` - 伪差期间该体征是否保持上一有效值
+    fn current_value(
+        &self,
+        channel: &str,
+        value: impl Fn(&ProcessedVitalSigns) -> Option<f64>,
+        unit: &str,
+        artifact_sensitive: bool,
+    ) -> CurrentVitalValue {
+        let queue = self.processed_data_queue.lock().unwrap();
+        let latest_artifact = queue.back().is_some_and(|d| d.artifact);
+        let reading = queue
+            .iter()
+            .rev()
+            .filter_map(|d| value(d).map(|v| (v, d.timestamp)))
+            .find(|(v, _)| v.is_finite() && *v > 0.0)
+            .filter(|_| self.is_channel_enabled(channel));
+        drop(queue);
+
+        let Some((value, timestamp)) = reading else {
+            return CurrentVitalValue {
+                value: None,
+                unit: unit.to_string(),
+                timestamp: None,
+                age_ms: None,
+                quality: ValueQuality::Unavailable,
+            };
+        };
+        // 采样时间戳在采样时间轴上，数据年龄也按采样时间轴计算
+        let age_ms = self.sample_time().saturating_sub(timestamp);
+        let quality = if age_ms > CURRENT_VALUE_MAX_AGE_MS {
+            ValueQuality::Stale
+        } else if artifact_sensitive && latest_artifact {
+            ValueQuality::Artifact
+        } else {
+            ValueQuality::Good
+        };
+        CurrentVitalValue {
+            value: Some(value),
+            unit: unit.to_string(),
+            timestamp: Some(timestamp),
+            age_ms: Some(age_ms),
+            quality,
+        }
+    }
+
+    /// 获取当前心率（次/分）
+    pub fn get_current_heart_rate(&self) -> CurrentVitalValue {
+        self.current_value("ecg", |d| Some(d.heart_rate), "bpm", true)
+    }
+
+    /// 获取当前血氧饱和度（%）
+    pub fn get_current_spo2(&self) -> CurrentVitalValue {
+        self.current_value("spo2", |d| d.blood_oxygen, "%", true)
+    }
+
+    /// 获取当前体温（°C）
+    pub fn get_current_temperature(&self) -> CurrentVitalValue {
+        self.current_value("temp", |d| d.body_temperature, "°C", false)
+    }
+
+    /// 设置心率平均策略
+    ///
+    /// 切换策略后立即按新策略重新计算平均心率，不需要等待下一个心搏。
+    /// 按心搏数取中位数时心搏数不能超过心率记录数，否则只能取到记录中的心搏。
+    ///
+    /// # 参数
+    /// * `averaging` - 新的心率平均策略
+    pub fn set_heart_rate_averaging(&self, averaging: HeartRateAveraging) -> Result<(), String> {
+        let mut ecg_state = self.states.ecg_state.lock().unwrap();
+        match averaging {
+            HeartRateAveraging::MedianOfBeats(0) => {
+                return Err("心搏数量必须大于0".to_string());
+            }
+            HeartRateAveraging::MedianOfBeats(beats)
+                if beats > ecg_state.params.heart_rate_history_size =>
+            {
+                return Err(format!(
+                    "心搏数量不能超过心率记录数{}",
+                    ecg_state.params.heart_rate_history_size
+                ));
+            }
+            HeartRateAveraging::TimeWindowMean(0) => {
+                return Err("时间窗口必须大于0毫秒".to_string());
+            }
+            _ => {}
+        }
+
+        ecg_state.last_averaged_heart_rate = Self::average_heart_rate(&ecg_state, &averaging);
+        *self.states.hr_averaging.lock().unwrap() = averaging;
+        Ok(())
+    }
+
+    /// 设置ECG心搏检测参数
+    ///
+    /// 缓冲区缩小时立即丢弃超出的旧数据，其余参数从下一个采样起生效。
+    ///
+    /// # 参数
+    /// * `params` - 新的检测参数
+    pub fn set_ecg_processing_params(&self, params: EcgProcessingParams) -> Result<(), String> {
+        if !(PEAK_THRESHOLD_RANGE.0..=PEAK_THRESHOLD_RANGE.1).contains(&params.peak_threshold) {
+            return Err(format!(
+                "波峰检测阈值必须在{}到{}之间",
+                PEAK_THRESHOLD_RANGE.0, PEAK_THRESHOLD_RANGE.1
+            ));
+        }
+        if !(THRESHOLD_UPDATE_INTERVAL_RANGE.0..=THRESHOLD_UPDATE_INTERVAL_RANGE.1)
+            .contains(&params.threshold_update_interval)
+        {
+            return Err(format!(
+                "阈值更新间隔必须在{}到{}个采样点之间",
+                THRESHOLD_UPDATE_INTERVAL_RANGE.0, THRESHOLD_UPDATE_INTERVAL_RANGE.1
+            ));
+        }
+        if !(ECG_RAW_BUFFER_RANGE.0..=ECG_RAW_BUFFER_RANGE.1).contains(&params.raw_buffer_size) {
+            return Err(format!(
+                "原始数据缓冲区大小必须在{}到{}个采样点之间",
+                ECG_RAW_BUFFER_RANGE.0, ECG_RAW_BUFFER_RANGE.1
+            ));
+        }
+        if !(HEART_RATE_HISTORY_RANGE.0..=HEART_RATE_HISTORY_RANGE.1)
+            .contains(&params.heart_rate_history_size)
+        {
+            return Err(format!(
+                "心率记录数必须在{}到{}之间",
+                HEART_RATE_HISTORY_RANGE.0, HEART_RATE_HISTORY_RANGE.1
+            ));
+        }
+        // 与 `set_heart_rate_averaging` 一样在心电状态锁内读取心率平均方式，两者并发设置时不会绕过校验
+        let mut ecg_state = self.states.ecg_state.lock().unwrap();
+        if let HeartRateAveraging::MedianOfBeats(beats) = self.get_heart_rate_averaging() {
+            if params.heart_rate_history_size < beats {
+                return Err(format!("心率记录数不能少于心率平均使用的心搏数{}", beats));
+            }
+        }
+
+        println!(
+            "[DataProcessor] ECG检测参数已更新: 波峰阈值{}, 每{}个采样点更新阈值, 缓冲区{}, 心率记录{}",
+            params.peak_threshold,
+            params.threshold_update_interval,
+            params.raw_buffer_size,
+            params.heart_rate_history_size
+        );
+        if ecg_state.ecg_data_original_list.len() >= params.raw_buffer_size {
+            ecg_state.ecg_data_original_list.clear();
+        }
+        let excess = ecg_state
+            .recent_heart_rates
+            .len()
+            .saturating_sub(params.heart_rate_history_size);
+        ecg_state.recent_heart_rates.drain(..excess);
+        ecg_state.params = params;
+        Ok(())
+    }
+
+    /// 获取当前ECG心搏检测参数
+    pub fn get_ecg_processing_params(&self) -> EcgProcessingParams {
+        self.states.ecg_state.lock().unwrap().params.clone()
+    }
+
+    /// 设置血氧验证和平均配置
+    ///
+    /// # 参数
+    /// * `config` - 新配置，平均心搏数只能为4、8或16
+    pub fn set_spo2_config(&self, config: Spo2Config) -> Result<(), String> {
+        if ![4, 8, 16].contains(&config.averaging_beats) {
+            return Err("血氧平均心搏数只能为4、8或16".to_string());
+        }
+        if !config.max_spike.is_finite() || config.max_spike <= 0.0 {
+            return Err("血氧尖峰阈值必须大于0".to_string());
+        }
+
+        println!(
+            "[DataProcessor] 血氧配置已更新: 平均{}个心搏, 尖峰阈值{}%",
+            config.averaging_beats, config.max_spike
+        );
+        self.states.spo2_state.lock().unwrap().config = config;
+        Ok(())
+    }
+
+    /// 获取当前血氧验证和平均配置
+    pub fn get_spo2_config(&self) -> Spo2Config {
+        self.states.spo2_state.lock().unwrap().config.clone()
+    }
+
+    /// 获取当前心率平均策略
+    pub fn get_heart_rate_averaging(&self) -> HeartRateAveraging {
+        self.states.hr_averaging.lock().unwrap().clone()
+    }
+
+    /// 设置单个通道的增益/反相/偏移
+    ///
+    /// # 参数
+    /// * `channel` - 通道名，见 `ADJUSTABLE_CHANNELS`
+    /// * `adjustment` - 新的通道设置
+    pub fn set_channel_adjustment(
+        &self,
+        channel: &str,
+        adjustment: ChannelAdjustment,
+    ) -> Result<(), String> {
+        if !ADJUSTABLE_CHANNELS.contains(&channel) {
+            return Err(format!("不支持的通道: {}", channel));
+        }
+        if !adjustment.gain.is_finite() || adjustment.gain == 0.0 {
+            return Err("增益必须是非零有限值".to_string());
+        }
+        if !adjustment.offset.is_finite() {
+            return Err("偏移必须是有限值".to_string());
+        }
+
+        self.states
+            .channel_adjustments
+            .lock()
+            .unwrap()
+            .insert(channel.to_string(), adjustment);
+        Ok(())
+    }
+
+    /// 配置通道处理流水线
+    ///
+    /// 所有流水线先全部验证，全部有效后才一次性应用到运行中的处理器；
+    /// 被替换的流水线的滤波状态会重置，未包含在参数中的通道保持不变。
+    ///
+    /// # 参数
+    /// * `pipelines` - 各通道的流水线配置
+    pub fn configure_pipeline(&self, pipelines: Vec<ChannelPipeline>) -> Result<(), String> {
+        for config in &pipelines {
+            pipeline::validate(config)?;
+        }
+
+        let mut runtimes = self.states.pipelines.lock().unwrap();
+        for config in pipelines {
+            println!("[DataProcessor] 更新通道 {} 的处理流水线", config.channel);
+            runtimes.insert(config.channel.clone(), PipelineRuntime::new(config));
+        }
+        Ok(())
+    }
+
+    /// 获取当前的通道处理流水线配置
+    pub fn get_pipeline_config(&self) -> Vec<ChannelPipeline> {
+        self.states
+            .pipelines
+            .lock()
+            .unwrap()
+            .values()
+            .map(|p| p.config().clone())
+            .collect()
+    }
+
+    /// 打开或关闭通道
+    ///
+    /// 关闭的通道（例如未接体温探头时的体温通道）不再参与处理，
+    /// 输出值为0或None，并在通道状态中报告为关闭；该通道体征的激活报警随之解除，
+    /// 保存会话时不包含其趋势。手动设置的开关不再随设备能力自动改变。
+    ///
+    /// # 参数
+    /// * `channel` - 通道ID
+    /// * `enabled` - 是否打开
+    pub fn set_channel_enabled(&self, channel: &str, enabled: bool) {
+        let mut disabled = self.states.disabled_channels.lock().unwrap();
+        self.states
+            .auto_disabled_channels
+            .lock()
+            .unwrap()
+            .remove(channel);
+        if enabled {
+            disabled.remove(channel);
+        } else {
+            disabled.insert(channel.to_string());
+            self.clear_channel_alarms(channel);
+        }
+        println!(
+            "[DataProcessor] 通道 {} 已{}",
+            channel,
+            if enabled { "打开" } else { "关闭" }
+        );
+    }
+
+    /// 按设备能力关闭设备不支持的通道，避免显示一直为0的曲线；之前因设备不支持而
+    /// 自动关闭的通道在设备支持后重新打开，用户手动关闭的通道保持关闭；
+    /// 尚无法判断的通道保持原来的开关状态
+    pub fn apply_device_capabilities(&self, capabilities: &DeviceCapabilities) {
+        let mut disabled = self.states.disabled_channels.lock().unwrap();
+        let mut auto_disabled = self.states.auto_disabled_channels.lock().unwrap();
+        for channel in &capabilities.supported_channels {
+            if auto_disabled.remove(channel) {
+                disabled.remove(channel);
+            }
+        }
+        for channel in &capabilities.unsupported_channels {
+            if disabled.insert(channel.clone()) {
+                auto_disabled.insert(channel.clone());
+            }
+            self.clear_channel_alarms(channel);
+        }
+        println!(
+            "[DataProcessor] 已按设备能力关闭不支持的通道: {:?}",
+            capabilities.unsupported_channels
+        );
+    }
+
+    /// 解除通道对应体征的激活报警
+    fn clear_channel_alarms(&self, channel: &str) {
+        self.states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .clear_vitals(&channel_vitals(channel));
+    }
+
+    /// 获取关闭的通道ID
+    pub fn get_disabled_channels(&self) -> Vec<String> {
+        self.states
+            .disabled_channels
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// 判断通道是否打开
+    pub fn is_channel_enabled(&self, channel: &str) -> bool {
+        !self
+            .states
+            .disabled_channels
+            .lock()
+            .unwrap()
+            .contains(channel)
+    }
+
+    /// 为扩展通道注册处理插件，替换该通道已有的插件
+    ///
+    /// # 参数
+    /// * `channel_id` - 通道ID
+    /// * `plugin` - 处理插件
+    pub fn register_channel_plugin(&self, channel_id: &str, plugin: Box<dyn ChannelPlugin>) {
+        println!("[DataProcessor] 注册通道处理插件: {}", channel_id);
+        self.states
+            .channel_plugins
+            .lock()
+            .unwrap()
+            .insert(channel_id.to_string(), plugin);
+    }
+
+    /// 开始体温两点校准，清除之前采集的校准点
+    pub fn start_temp_calibration(&self) {
+        println!("[DataProcessor] 开始体温校准");
+        *self.temp_calibration.lock().unwrap() = Some(Vec::new());
+    }
+
+    /// 采集体温校准点
+    ///
+    /// 取最近约2秒未校准读数的平均值，与参考温度计读数配对。
+    ///
+    /// # 参数
+    /// * `reference_value` - 参考温度计读数（摄氏度）
+    ///
+    /// # 返回值
+    /// 返回采集到的校准点
+    pub fn capture_calibration_point(
+        &self,
+        reference_value: f64,
+    ) -> Result<TemperatureCalibrationPoint, String> {
+        if !(20.0..=45.0).contains(&reference_value) {
+            return Err("参考温度必须在20°C到45°C之间".to_string());
+        }
+
+        let mut calibration = self.temp_calibration.lock().unwrap();
+        let points = calibration
+            .as_mut()
+            .ok_or_else(|| "体温校准未开始".to_string())?;
+        if points.len() >= 2 {
+            return Err("已采集两个校准点，请完成校准".to_string());
+        }
+
+        let raw_value = {
+            let temp_state = self.states.temp_state.lock().unwrap();
+            if temp_state.recent_raw_values.is_empty() {
+                return Err("暂无体温数据".to_string());
+            }
+            temp_state.recent_raw_values.iter().sum::<f64>()
+                / temp_state.recent_raw_values.len() as f64
+        };
+
+        let point = TemperatureCalibrationPoint {
+            raw_value,
+            reference_value,
+        };
+        println!(
+            "[DataProcessor] 采集校准点: 读数={:.2}, 参考={:.2}°C",
+            raw_value, reference_value
+        );
+        points.push(point.clone());
+        Ok(point)
+    }
+
+    /// 完成体温校准
+    ///
+    /// 根据两个校准点计算比例系数和偏移量，并立即应用到体温处理。
+    ///
+    /// # 返回值
+    /// 返回计算得到的校准结果
+    pub fn finish_temp_calibration(&self) -> Result<TemperatureCalibration, String> {
+        let mut calibration = self.temp_calibration.lock().unwrap();
+        let points = calibration
+            .as_ref()
+            .ok_or_else(|| "体温校准未开始".to_string())?;
+        if points.len() != 2 {
+            return Err(format!("需要两个校准点，当前为{}个", points.len()));
+        }
+
+        let (p1, p2) = (&points[0], &points[1]);
+        let raw_span = p2.raw_value - p1.raw_value;
+        if raw_span.abs() < TEMP_CALIBRATION_MIN_SPAN {
+            return Err(format!(
+                "两个校准点的读数过于接近（至少相差{}°C）",
+                TEMP_CALIBRATION_MIN_SPAN
+            ));
+        }
+        let scale_factor = (p2.reference_value - p1.reference_value) / raw_span;
+        if !scale_factor.is_finite() || scale_factor <= 0.0 {
+            return Err("校准结果无效，请检查参考温度".to_string());
+        }
+        let offset = p1.reference_value - scale_factor * p1.raw_value;
+
+        let result = TemperatureCalibration {
+            scale_factor,
+            offset,
+            points: points.clone(),
+            calibrated_at: chrono::Utc::now().to_rfc3339(),
+        };
+        self.apply_temp_calibration(&result);
+        *calibration = None;
+        Ok(result)
+    }
+
+    /// 应用体温校准结果（完成校准或加载连接配置档案时调用）
+    pub fn apply_temp_calibration(&self, calibration: &TemperatureCalibration) {
+        let mut temp_state = self.states.temp_state.lock().unwrap();
+        temp_state.scale_factor = calibration.scale_factor;
+        temp_state.offset = calibration.offset;
+        temp_state.temperatures.clear();
+        println!(
+            "[DataProcessor] 体温校准已应用: 系数={:.4}, 偏移={:.3}",
+            calibration.scale_factor, calibration.offset
+        );
+    }
+
+    /// 移除扩展通道的处理插件
+    pub fn remove_channel_plugin(&self, channel_id: &str) {
+        self.states
+            .channel_plugins
+            .lock()
+            .unwrap()
+            .remove(channel_id);
+    }
+
+    /// 整体替换通道设置（用于加载连接配置档案）
+    pub fn set_channel_adjustments(&self, adjustments: BTreeMap<String, ChannelAdjustment>) {
+        *self.states.channel_adjustments.lock().unwrap() = adjustments;
+    }
+
+    /// 获取当前的通道设置
+    pub fn get_channel_adjustments(&self) -> BTreeMap<String, ChannelAdjustment> {
+        self.states.channel_adjustments.lock().unwrap().clone()
+    }
+
+    /// 获取RR间期序列（心搏间期图数据）
+    ///
+    /// # 参数
+    /// * `count` - 最多返回的心搏数量，从最新的心搏往前取
+    /// * `start` - 起始时间戳（毫秒，包含）
+    /// * `end` - 结束时间戳（毫秒，包含）
+    ///
+    /// # 返回值
+    /// 返回按时间先后排列的RR间期数据点
+    pub fn get_rr_tachogram(
+        &self,
+        count: Option<usize>,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> Vec<RrIntervalPoint> {
+        let ecg_state = self.states.ecg_state.lock().unwrap();
+        let in_range: Vec<&RrIntervalPoint> = ecg_state
+            .rr_history
+            .iter()
+            .filter(|p| start.is_none_or(|s| p.timestamp >= s))
+            .filter(|p| end.is_none_or(|e| p.timestamp <= e))
+            .collect();
+        let skip = count.map_or(0, |c| in_range.len().saturating_sub(c));
+        in_range.into_iter().skip(skip).cloned().collect()
+    }
+
+    /// 获取心搏检测结果
+    ///
+    /// # 参数
+    /// * `start` - 起始时间戳（毫秒，包含）
+    /// * `end` - 结束时间戳（毫秒，包含）
+    ///
+    /// # 返回值
+    /// 返回按时间先后排列的心搏（时间戳、RR间期、分类、幅度）
+    pub fn get_beats(&self, start: Option<u64>, end: Option<u64>) -> Vec<BeatRecord> {
+        self.states
+            .ecg_state
+            .lock()
+            .unwrap()
+            .beats
+            .iter()
+            .filter(|b| start.is_none_or(|s| b.timestamp >= s))
+            .filter(|b| end.is_none_or(|e| b.timestamp <= e))
+            .cloned()
+            .collect()
+    }
+
+    /// 获取最近一次成功的无创血压测量结果
+    pub fn get_latest_blood_pressure(&self) -> Option<BloodPressureReading> {
+        self.states
+            .bp_history
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|r| r.cuff_status == CuffStatus::Ok)
+            .cloned()
+    }
+
+    /// 获取无创血压测量历史
+    ///
+    /// # 参数
+    /// * `start` - 起始时间戳（毫秒，包含）
+    /// * `end` - 结束时间戳（毫秒，包含）
+    ///
+    /// # 返回值
+    /// 返回按时间先后排列的血压测量记录，包括袖带状态异常的测量
+    pub fn get_bp_history(
+        &self,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> Vec<BloodPressureReading> {
+        self.states
+            .bp_history
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| start.is_none_or(|s| r.timestamp >= s))
+            .filter(|r| end.is_none_or(|e| r.timestamp <= e))
+            .cloned()
+            .collect()
+    }
+
+    /// 获取体征趋势
+    ///
+    /// # 参数
+    /// * `vital` - 体征名，例如 `heart_rate`、`spo2`、`systolic`
+    /// * `start` - 起始时间戳（毫秒）
+    /// * `end` - 结束时间戳（毫秒）
+    ///
+    /// # 返回值
+    /// 返回按时间先后排列的趋势聚合段（每段1分钟）
+    pub fn get_trends(&self, vital: &str, start: Option<u64>, end: Option<u64>) -> Vec<TrendBin> {
+        self.states.trends.lock().unwrap().get(vital, start, end)
+    }
+
+    /// 获取所有体征的每小时概览（最小/最大/平均/最新值和报警次数）
+    ///
+    /// # 参数
+    /// * `start` - 起始时间戳（毫秒），省略时为结束时间前24小时
+    /// * `end` - 结束时间戳（毫秒），省略时为当前时间
+    /// * `alarms` - 报警历史，用于统计每小时的报警次数
+    pub fn get_vital_summary(
+        &self,
+        start: Option<u64>,
+        end: Option<u64>,
+        alarms: &[AlarmHistoryEntry],
+    ) -> BTreeMap<String, Vec<HourlySummary>> {
+        let end = end.unwrap_or_else(|| self.sample_time());
+        let start = start.unwrap_or(end.saturating_sub(VITAL_SUMMARY_SPAN_MS));
+        let alarms: Vec<(String, u64)> = alarms
+            .iter()
+            .map(|alarm| (alarm.vital.clone(), alarm.started_at))
+            .collect();
+        self.states
+            .trends
+            .lock()
+            .unwrap()
+            .hourly_summary(start, end, &alarms)
+    }
+
+    /// 获取体征的迷你趋势图数据
+    ///
+    /// 取最近一段时间的趋势聚合段平均值，再用LTTB降采样到固定点数。
+    ///
+    /// # 参数
+    /// * `vital` - 体征名，可以是任何有趋势数据的体征
+    /// * `duration_ms` - 时间跨度（毫秒），截止到当前时间
+    /// * `points` - 最多返回的点数
+    ///
+    /// # 返回值
+    /// 返回按时间先后排列的数据点，x为时间戳（毫秒），y为平均值
+    pub fn get_sparkline(
+        &self,
+        vital: &str,
+        duration_ms: u64,
+        points: usize,
+    ) -> Vec<LttbDataPoint> {
+        let end = self.sample_time();
+        let data: Vec<LttbDataPoint> = self
+            .get_trends(vital, Some(end.saturating_sub(duration_ms)), Some(end))
+            .iter()
+            .map(|bin| LttbDataPoint {
+                x: bin.start_timestamp as f64,
+                y: bin.mean,
+            })
+            .collect();
+        Self::lttb_downsample(&data, points)
+    }
+
+    /// 获取一段时间内的心率分布
+    ///
+    /// # 参数
+    /// * `start` - 起始时间戳（毫秒），省略时为结束时间前24小时
+    /// * `end` - 结束时间戳（毫秒），省略时为当前时间
+    /// * `bin_width` - 直方图柱宽（次/分）
+    /// * `zones` - 心率区间，省略时使用心动过缓/正常/心动过速三个区间
+    pub fn get_hr_histogram(
+        &self,
+        start: Option<u64>,
+        end: Option<u64>,
+        bin_width: f64,
+        zones: Option<Vec<HeartRateZone>>,
+    ) -> HeartRateHistogram {
+        let end = end.unwrap_or_else(|| self.sample_time());
+        let start = start.unwrap_or(end.saturating_sub(VITAL_SUMMARY_SPAN_MS));
+        let bins = self.get_trends("heart_rate", Some(start), Some(end));
+        trends::heart_rate_histogram(
+            &bins,
+            start,
+            end,
+            bin_width,
+            zones.unwrap_or_else(trends::default_heart_rate_zones),
+        )
+    }
+
+    /// 获取已有趋势数据的体征名
+    pub fn get_trend_vitals(&self) -> Vec<String> {
+        self.states.trends.lock().unwrap().vitals()
+    }
+
+    /// 开始体位性生命体征测试
+    ///
+    /// # 参数
+    /// * `config` - 测试配置
+    ///
+    /// # 返回值
+    /// 已有测试进行中或配置无效时返回错误
+    pub fn start_orthostatic_test(&self, config: OrthostaticConfig) -> Result<(), String> {
+        orthostatic::validate_config(&config)?;
+        let mut test = self.states.orthostatic_test.lock().unwrap();
+        if test.as_ref().is_some_and(|s| !s.is_complete()) {
+            return Err("体位性测试正在进行中".to_string());
+        }
+        *test = Some(OrthostaticSession::new(config));
+        println!("[DataProcessor] 体位性测试开始");
+        Ok(())
+    }
+
+    /// 取消体位性生命体征测试，同时清除上次测试的状态
+    pub fn cancel_orthostatic_test(&self) {
+        *self.states.orthostatic_test.lock().unwrap() = None;
+    }
+
+    /// 获取体位性生命体征测试状态，未开始测试时返回None
+    pub fn get_orthostatic_status(&self) -> Option<OrthostaticStatus> {
+        self.states
+            .orthostatic_test
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|s| s.status())
+    }
+
+    /// 开始心率恢复测试，运动期间开始，运动结束时调用 `mark_exercise_end`
+    ///
+    /// # 返回值
+    /// 已有测试进行中或配置无效时返回错误
+    pub fn start_hr_recovery_test(&self, config: HrRecoveryConfig) -> Result<(), String> {
+        hr_recovery::validate_config(&config)?;
+        let mut test = self.states.hr_recovery_test.lock().unwrap();
+        if test.as_ref().is_some_and(|s| !s.is_complete()) {
+            return Err("心率恢复测试正在进行中".to_string());
+        }
+        *test = Some(HrRecoverySession::new(config));
+        println!("[DataProcessor] 心率恢复测试开始");
+        Ok(())
+    }
+
+    /// 标记运动结束，开始记录恢复心率
+    pub fn mark_exercise_end(&self) -> Result<(), String> {
+        let mut test = self.states.hr_recovery_test.lock().unwrap();
+        match test.as_mut().filter(|s| !s.is_complete()) {
+            Some(session) => session.mark_exercise_end(),
+            None => Err("没有进行中的心率恢复测试".to_string()),
+        }
+    }
+
+    /// 取消心率恢复测试，同时清除上次测试的状态
+    pub fn cancel_hr_recovery_test(&self) {
+        *self.states.hr_recovery_test.lock().unwrap() = None;
+    }
+
+    /// 获取心率恢复测试状态，未开始测试时返回None
+    pub fn get_hr_recovery_status(&self) -> Option<HrRecoveryStatus> {
+        self.states
+            .hr_recovery_test
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|s| s.status())
+    }
+
+    /// 开始六分钟步行试验，收到下一个采样时开始计时
+    ///
+    /// # 参数
+    /// * `config` - 试验配置
+    /// * `borg_pre` - 试验前的Borg评分，可在开始后再录入
+    pub fn start_walk_test(
+        &self,
+        config: WalkTestConfig,
+        borg_pre: Option<BorgScore>,
+    ) -> Result<(), String> {
+        walk_test::validate_config(&config)?;
+        let mut test = self.states.walk_test.lock().unwrap();
+        if test.as_ref().is_some_and(|s| !s.is_complete()) {
+            return Err("步行试验正在进行中".to_string());
+        }
+        let mut session = WalkTestSession::new(config);
+        if let Some(score) = borg_pre {
+            session.record_borg(BorgTiming::Pre, score)?;
+        }
+        *test = Some(session);
+        println!("[DataProcessor] 六分钟步行试验开始");
+        Ok(())
+    }
+
+    /// 对进行中或已结束的步行试验执行操作
+    fn with_walk_test<T>(
+        &self,
+        action: impl FnOnce(&mut WalkTestSession) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut test = self.states.walk_test.lock().unwrap();
+        match test.as_mut() {
+            Some(session) => action(session),
+            None => Err("没有步行试验".to_string()),
+        }
+    }
+
+    /// 标记患者完成一圈
+    pub fn record_walk_lap(&self) -> Result<(), String> {
+        self.with_walk_test(|session| session.record_lap())
+    }
+
+    /// 录入步行试验前或试验后的Borg评分
+    pub fn record_borg_score(&self, timing: BorgTiming, score: BorgScore) -> Result<(), String> {
+        self.with_walk_test(|session| session.record_borg(timing, score))
+    }
+
+    /// 提前结束步行试验
+    pub fn stop_walk_test(&self, reason: &str) -> Result<(), String> {
+        self.with_walk_test(|session| session.stop(reason))
+    }
+
+    /// 取消步行试验，同时清除上次试验的状态
+    pub fn cancel_walk_test(&self) {
+        *self.states.walk_test.lock().unwrap() = None;
+    }
+
+    /// 获取步行试验状态，未开始试验时返回None
+    pub fn get_walk_test_status(&self) -> Option<WalkTestStatus> {
+        self.states
+            .walk_test
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|s| s.status())
+    }
+
+    /// 生成已结束步行试验的结果
+    ///
+    /// # 参数
+    /// * `partial_distance_m` - 最后不足一圈的距离（米）
+    pub fn get_walk_test_summary(
+        &self,
+        partial_distance_m: f64,
+    ) -> Result<WalkTestSummary, String> {
+        if !partial_distance_m.is_finite() || partial_distance_m < 0.0 {
+            return Err("最后一圈的距离不能为负数".to_string());
+        }
+        self.with_walk_test(|session| {
+            session
+                .summary(partial_distance_m)
+                .ok_or_else(|| "步行试验尚未结束".to_string())
+        })
+    }
+
+    /// 启用OSC输出，已启用时按新配置重新创建
+    pub fn start_osc_output(&self, config: OscConfig) -> Result<(), String> {
+        let output = OscOutput::new(config)?;
+        *self.states.osc_output.lock().unwrap() = Some(output);
+        Ok(())
+    }
+
+    /// 停止OSC输出
+    pub fn stop_osc_output(&self) {
+        if self.states.osc_output.lock().unwrap().take().is_some() {
+            println!("[DataProcessor] OSC输出已停止");
+        }
+    }
+
+    /// 获取OSC输出状态，未启用时返回None
+    pub fn get_osc_status(&self) -> Option<OscStatus> {
+        self.states
+            .osc_output
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|output| output.status())
+    }
+
+    /// 启用LSL输出，已启用时先注销原有的流再按新配置发布
+    pub fn start_lsl_outlet(&self, config: LslConfig) -> Result<(), String> {
+        let mut outlet = self.states.lsl_outlet.lock().unwrap();
+        *outlet = None;
+        *outlet = Some(LslOutlet::new(config)?);
+        Ok(())
+    }
+
+    /// 停止LSL输出
+    pub fn stop_lsl_outlet(&self) {
+        if self.states.lsl_outlet.lock().unwrap().take().is_some() {
+            println!("[DataProcessor] LSL输出已停止");
+        }
+    }
+
+    /// 获取LSL输出状态，未启用时返回None
+    pub fn get_lsl_status(&self) -> Option<LslStatus> {
+        self.states
+            .lsl_outlet
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|outlet| outlet.status())
+    }
+
+    /// 启用共享内存输出，已启用时按新配置重新创建环形缓冲文件
+    pub fn start_shared_memory(&self, config: SharedMemoryConfig) -> Result<(), String> {
+        let writer = SharedMemoryWriter::new(config)?;
+        *self.states.shared_memory.lock().unwrap() = Some(writer);
+        Ok(())
+    }
+
+    /// 停止共享内存输出，文件保留最后写入的内容
+    pub fn stop_shared_memory(&self) {
+        if self.states.shared_memory.lock().unwrap().take().is_some() {
+            println!("[DataProcessor] 共享内存输出已停止");
+        }
+    }
+
+    /// 获取共享内存输出状态，未启用时返回None
+    pub fn get_shared_memory_status(&self) -> Option<SharedMemoryStatus> {
+        self.states
+            .shared_memory
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|writer| writer.status())
+    }
+
+    /// 启用Kafka输出，已启用时先发送完原有的消息再按新配置连接
+    pub fn start_kafka_sink(&self, config: KafkaConfig) -> Result<(), String> {
+        let sink = KafkaSink::new(config)?;
+        *self.states.kafka_sink.lock().unwrap() = Some(sink);
+        Ok(())
+    }
+
+    /// 停止Kafka输出
+    pub fn stop_kafka_sink(&self) {
+        if self.states.kafka_sink.lock().unwrap().take().is_some() {
+            println!("[DataProcessor] Kafka输出已停止");
+        }
+    }
+
+    /// 获取Kafka输出状态，未启用时返回None
+    pub fn get_kafka_status(&self) -> Option<KafkaStatus> {
+        self.states
+            .kafka_sink
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|sink| sink.status())
+    }
+
+    /// 启用云端遥测上报，已启用时按新配置重新连接
+    ///
+    /// # 参数
+    /// * `config` - 遥测配置
+    /// * `outbox_dir` - 本地发件箱目录，离线期间的批次保存在这里
+    pub fn start_telemetry(
+        &self,
+        config: TelemetryConfig,
+        outbox_dir: std::path::PathBuf,
+    ) -> Result<(), String> {
+        let mut telemetry = self.states.telemetry.lock().unwrap();
+        *telemetry = None;
+        *telemetry = Some(TelemetryUplink::new(config, outbox_dir)?);
+        Ok(())
+    }
+
+    /// 停止云端遥测上报，未上传的批次保留在发件箱中
+    pub fn stop_telemetry(&self) {
+        if self.states.telemetry.lock().unwrap().take().is_some() {
+            println!("[DataProcessor] 云端遥测已停止");
+        }
+    }
+
+    /// 获取云端遥测状态，未启用时返回None
+    pub fn get_telemetry_status(&self) -> Option<TelemetryStatus> {
+        self.states
+            .telemetry
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|uplink| uplink.status())
+    }
+
+    /// 设置原始心电存档，之后每个采样的原始ECG值都写入存档
+    pub fn set_ecg_archive(&self, archive: Arc<EcgArchive>) {
+        *self.states.ecg_archive.lock().unwrap() = Some(archive);
+    }
+
+    /// 启用HL7结果发送，已启用时按新配置重新连接
+    ///
+    /// # 参数
+    /// * `config` - 发送配置
+    /// * `queue_dir` - 本地发送队列目录，未确认的消息保存在这里
+    pub fn start_hl7_sender(
+        &self,
+        config: Hl7SenderConfig,
+        queue_dir: std::path::PathBuf,
+    ) -> Result<(), String> {
+        let mut sender = self.states.hl7_sender.lock().unwrap();
+        *sender = None;
+        *sender = Some(Hl7Sender::new(config, queue_dir)?);
+        Ok(())
+    }
+
+    /// 停止HL7结果发送，未确认的消息保留在发送队列中
+    pub fn stop_hl7_sender(&self) {
+        if self.states.hl7_sender.lock().unwrap().take().is_some() {
+            println!("[DataProcessor] HL7结果发送已停止");
+        }
+    }
+
+    /// 获取HL7接口状态，未启用时返回None
+    pub fn get_hl7_interface_status(&self) -> Option<Hl7InterfaceStatus> {
+        self.states
+            .hl7_sender
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|sender| sender.status())
+    }
+
+    /// 静音报警
+    ///
+    /// 静音生效前先以当前激活的报警调用 `audit` 记录操作，记录失败时不静音。
+    ///
+    /// # 参数
+    /// * `duration_secs` - 静音时长（秒）
+    /// * `audit` - 记录审计日志
+    ///
+    /// # 返回值
+    /// 返回被静音的报警
+    pub fn silence_alarms(
+        &self,
+        duration_secs: u64,
+        audit: impl FnOnce(&[Alarm]) -> Result<(), String>,
+    ) -> Result<Vec<Alarm>, String> {
+        let duration_ms = duration_secs.saturating_mul(1000);
+        alarms::validate_silence_duration(duration_ms)?;
+        let mut alarm_engine = self.states.alarm_engine.lock().unwrap();
+        audit(&alarm_engine.active_alarms())?;
+        alarm_engine.silence(duration_ms, self.sample_time())
+    }
+
+    /// 获取报警静音截止时间戳（毫秒），未静音时返回None
+    pub fn get_alarm_silence(&self) -> Option<u64> {
+        self.states.alarm_engine.lock().unwrap().silenced_until()
+    }
+
+    /// 确认报警
+    ///
+    /// # 参数
+    /// * `id` - 报警ID
+    /// * `operator` - 确认报警的操作员
+    pub fn acknowledge_alarm(
+        &self,
+        id: u64,
+        operator: &str,
+        audit: impl FnOnce(&Alarm) -> Result<(), String>,
+    ) -> Result<Alarm, String> {
+        let mut alarm_engine = self.states.alarm_engine.lock().unwrap();
+        let alarm = alarm_engine
+            .active_alarms()
+            .into_iter()
+            .find(|a| a.id == id)
+            .ok_or_else(|| format!("报警不存在或已解除: {}", id))?;
+        audit(&alarm)?;
+        alarm_engine.acknowledge(id, operator, self.sample_time())
+    }
+
+    /// 采样时间轴的当前时间（毫秒），与处理线程生成的采样时间戳同源，
+    /// 静音到期、统计窗口等与采样时间戳的比较都使用该时间
+    pub fn sample_time(&self) -> u64 {
+        self.sample_clock.lock().unwrap().peek()
+    }
+
+    /// 系统时间（毫秒），只用于和串口接收时间比较计算处理延迟
+    fn wall_millis() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    /// 注册报警事件监听器（例如桌面通知）
+    pub fn add_alarm_listener(&self, listener: Box<dyn AlarmListener>) {
+        self.states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .add_listener(listener);
+    }
+
+    /// 设置分级限值报警配置
+    pub fn set_alarm_limits(&self, limits: Vec<VitalAlarmLimits>) -> Result<(), String> {
+        self.states.alarm_engine.lock().unwrap().set_limits(limits)
+    }
+
+    /// 获取分级限值报警配置
+    pub fn get_alarm_limits(&self) -> Vec<VitalAlarmLimits> {
+        self.states.alarm_engine.lock().unwrap().get_limits()
+    }
+
+    /// 设置报警延迟
+    ///
+    /// # 参数
+    /// * `condition` - 报警条件标识，例如 `spo2_low`
+    /// * `seconds` - 条件需持续满足的秒数，0表示立即激活
+    pub fn set_alarm_delay(&self, condition: &str, seconds: u64) -> Result<(), String> {
+        self.states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .set_delay(condition, seconds.saturating_mul(1000))
+    }
+
+    /// 获取各报警条件的延迟（毫秒）
+    pub fn get_alarm_delays(&self) -> BTreeMap<String, u64> {
+        self.states.alarm_engine.lock().unwrap().get_delays()
+    }
+
+    /// 设置运动感知报警抑制配置
+    pub fn set_motion_suppression(&self, config: MotionSuppressionConfig) -> Result<(), String> {
+        self.states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .set_motion_suppression(config)
+    }
+
+    /// 获取运动感知报警抑制配置
+    pub fn get_motion_suppression(&self) -> MotionSuppressionConfig {
+        self.states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .get_motion_suppression()
+    }
+
+    /// 获取运动期间的报警抑制记录
+    pub fn get_alarm_suppressions(&self) -> Vec<AlarmSuppression> {
+        self.states.alarm_engine.lock().unwrap().suppression_log()
+    }
+
+    /// 设置报警升级配置
+    pub fn set_escalation_config(&self, config: EscalationConfig) -> Result<(), String> {
+        self.states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .set_escalation(config)
+    }
+
+    /// 获取报警升级配置
+    pub fn get_escalation_config(&self) -> EscalationConfig {
+        self.states.alarm_engine.lock().unwrap().get_escalation()
+    }
+
+    /// 设置体温报警配置
+    pub fn set_temp_alarm_config(&self, config: TemperatureAlarmConfig) -> Result<(), String> {
+        self.states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .set_temp_config(config)
+    }
+
+    /// 获取体温报警配置
+    pub fn get_temp_alarm_config(&self) -> TemperatureAlarmConfig {
+        self.states.alarm_engine.lock().unwrap().get_temp_config()
+    }
+
+    /// 设置衍生风险指标报警配置
+    pub fn set_derived_alarm_config(&self, config: DerivedAlarmConfig) -> Result<(), String> {
+        self.states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .set_derived_config(config)
+    }
+
+    /// 获取衍生风险指标报警配置
+    pub fn get_derived_alarm_config(&self) -> DerivedAlarmConfig {
+        self.states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .get_derived_config()
+    }
+
+    /// 获取当前激活的报警
+    pub fn get_active_alarms(&self) -> Vec<Alarm> {
+        self.states.alarm_engine.lock().unwrap().active_alarms()
+    }
+
+    /// 设置血压报警配置
+    pub fn set_bp_alarm_config(&self, config: BpAlarmConfig) -> Result<(), String> {
+        self.states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .set_bp_config(config)
+    }
+
+    /// 获取血压报警配置
+    pub fn get_bp_alarm_config(&self) -> BpAlarmConfig {
+        self.states.alarm_engine.lock().unwrap().get_bp_config()
+    }
+
+    /// 获取间歇性测量历史记录
+    ///
+    /// # 参数
+    /// * `kind` - 测量类型，省略时返回所有类型
+    /// * `start` - 起始时间戳（毫秒，包含）
+    /// * `end` - 结束时间戳（毫秒，包含）
+    ///
+    /// # 返回值
+    /// 返回按时间先后排列的测量记录
+    pub fn get_measurement_history(
+        &self,
+        kind: Option<MeasurementKind>,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> Vec<MeasurementRecord> {
+        self.states
+            .measurement_history
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| kind.is_none_or(|k| m.kind == k))
+            .filter(|m| start.is_none_or(|s| m.timestamp >= s))
+            .filter(|m| end.is_none_or(|e| m.timestamp <= e))
+            .cloned()
+            .collect()
+    }
+
+    /// 清除间歇性测量历史和血压测量历史，用于清除患者数据
+    ///
+    /// # 返回值
+    /// 返回被清除的记录数
+    pub fn clear_measurements(&self) -> usize {
+        let mut measurements = self.states.measurement_history.lock().unwrap();
+        let mut bp_history = self.states.bp_history.lock().unwrap();
+        let count = measurements.len() + bp_history.len();
+        measurements.clear();
+        bp_history.clear();
+        count
+    }
+
+    /// 获取Poincaré散点图数据
+    ///
+    /// # 参数
+    /// * `window` - 参与计算的最近RR间期数量
+    ///
+    /// # 返回值
+    /// 返回 (RRn, RRn+1) 点对以及SD1/SD2椭圆参数
+    pub fn get_poincare_plot(&self, window: usize) -> PoincarePlot {
+        let rr_intervals: Vec<f64> = self
+            .get_rr_tachogram(Some(window), None, None)
+            .iter()
+            .map(|p| p.rr_interval_ms)
+            .collect();
+        hrv::poincare_plot(&rr_intervals)
+    }
+
+    /// 获取HRV频域分析结果
+    ///
+    /// # 参数
+    /// * `window_seconds` - 分析窗口长度（秒），取最近该时长内的心搏
+    /// * `method` - 功率谱估计方法
+    ///
+    /// # 返回值
+    /// 返回LF、HF、LF/HF及总功率等频域指标
+    pub fn get_hrv_spectrum(&self, window_seconds: f64, method: SpectralMethod) -> HrvSpectrum {
+        let rr_intervals: Vec<f64> = {
+            let ecg_state = self.states.ecg_state.lock().unwrap();
+            let latest = ecg_state.rr_history.back().map_or(0, |p| p.timestamp);
+            let window_start = latest.saturating_sub((window_seconds * 1000.0) as u64);
+            ecg_state
+                .rr_history
+                .iter()
+                .filter(|p| p.timestamp >= window_start)
+                .map(|p| p.rr_interval_ms)
+                .collect()
+        };
+        hrv::spectrum(&rr_intervals, method, window_seconds)
+    }
+
+    /// 获取ECG统计信息
+    ///
+    /// 心率统计基于最近的心搏记录，信号质量按最近处理数据中
+    /// 无伪差采样的比例计算。
+    pub fn get_ecg_statistics(&self) -> EcgStatistics {
+        let (current_heart_rate, heart_rates, rr_intervals, paced_beat_percentage) = {
+            let ecg_state = self.states.ecg_state.lock().unwrap();
+            let heart_rates: Vec<f64> = ecg_state
+                .recent_heart_rates
+                .iter()
+                .map(|(_, hr)| *hr)
+                .collect();
+            let rr_intervals: Vec<f64> = ecg_state
+                .rr_history
+                .iter()
+                .rev()
+                .take(64)
+                .rev()
+                .map(|p| p.rr_interval_ms)
+                .collect();
+            let paced = if ecg_state.total_beats > 0 {
+                ecg_state.paced_beats as f64 / ecg_state.total_beats as f64 * 100.0
+            } else {
+                0.0
+            };
+            (
+                ecg_state.last_averaged_heart_rate,
+                heart_rates,
+                rr_intervals,
+                paced,
+            )
+        };
+
+        let signal_quality = {
+            let queue = self.processed_data_queue.lock().unwrap();
+            if queue.is_empty() {
+                0.0
+            } else {
+                let clean = queue.iter().filter(|p| !p.artifact).count();
+                clean as f64 / queue.len() as f64 * 100.0
+            }
+        };
+
+        let compression_efficiency = {
+            let lttb_state = self.states.lttb_state.lock().unwrap();
+            if lttb_state.compressed_buffer.is_empty() {
+                0.0
+            } else {
+                lttb_state.buffer_size as f64 / lttb_state.compressed_buffer.len() as f64
+            }
+        };
+
+        let rolling_heart_rate = self.states.rolling_stats.lock().unwrap().get("heart_rate");
+        let (average_heart_rate, max_heart_rate, min_heart_rate) =
+            if let Some(stats) = rolling_heart_rate {
+                (stats.mean, stats.max, stats.min)
+            } else if heart_rates.is_empty() {
+                (current_heart_rate, current_heart_rate, current_heart_rate)
+            } else {
+                (
+                    heart_rates.iter().sum::<f64>() / heart_rates.len() as f64,
+                    heart_rates
+                        .iter()
+                        .cloned()
+                        .fold(f64::NEG_INFINITY, f64::max),
+                    heart_rates.iter().cloned().fold(f64::INFINITY, f64::min),
+                )
+            };
+
+        EcgStatistics {
+            current_heart_rate,
+            average_heart_rate,
+            max_heart_rate,
+            min_heart_rate,
+            rr_variability: hrv::rmssd(&rr_intervals),
+            signal_quality,
+            compression_efficiency,
+            paced_beat_percentage,
+        }
+    }
+
+    /// 获取呼吸数据
+    ///
+    /// # 返回值
+    /// 返回呼吸频率、呼吸暂停标记以及LTTB压缩后的呼吸波形
+    pub fn get_respiration_data(&self) -> RespirationData {
+        let (respiration_rate, apnea, last_breath_timestamp) = {
+            let resp_state = self.states.resp_state.lock().unwrap();
+            (
+                resp_state.respiration_rate,
+                resp_state.apnea,
+                resp_state.last_breath_timestamp,
+            )
+        };
+        let waveform = self
+            .states
+            .resp_lttb_state
+            .lock()
+            .unwrap()
+            .compressed_buffer
+            .clone();
+
+        RespirationData {
+            respiration_rate,
+            apnea,
+            last_breath_timestamp,
+            waveform,
+        }
+    }
+
+    /// 设置呼吸暂停判定时长
+    ///
+    /// # 参数
+    /// * `seconds` - 无呼吸超过该秒数判定为呼吸暂停
+    pub fn set_apnea_threshold(&self, seconds: u64) -> Result<(), String> {
+        let (min, max) = APNEA_THRESHOLD_RANGE_SECS;
+        if !(min..=max).contains(&seconds) {
+            return Err(format!("呼吸暂停判定时长必须在{}到{}秒之间", min, max));
+        }
+        self.states.resp_state.lock().unwrap().apnea_threshold_ms = seconds * 1000;
+        println!("[DataProcessor] 呼吸暂停判定时长设置为 {} 秒", seconds);
+        Ok(())
+    }
+
+    /// 获取呼吸暂停判定时长（秒）
+    pub fn get_apnea_threshold(&self) -> u64 {
+        self.states.resp_state.lock().unwrap().apnea_threshold_ms / 1000
+    }
+
+    /// 设置滚动统计窗口时长
+    ///
+    /// # 参数
+    /// * `seconds` - 窗口时长（秒）
+    pub fn set_statistics_window(&self, seconds: u64) -> Result<(), String> {
+        let (min, max) = STATISTICS_WINDOW_RANGE_SECS;
+        if !(min..=max).contains(&seconds) {
+            return Err(format!("统计窗口时长必须在{}到{}秒之间", min, max));
+        }
+        self.states
+            .rolling_stats
+            .lock()
+            .unwrap()
+            .set_window_ms(seconds * 1000);
+        println!("[DataProcessor] 滚动统计窗口设置为 {} 秒", seconds);
+        Ok(())
+    }
+
+    /// 获取心率、血氧和体温在滚动窗口内的统计值
+    pub fn get_vital_statistics(&self) -> VitalStatistics {
+        let rolling_stats = self.states.rolling_stats.lock().unwrap();
+        VitalStatistics {
+            window_secs: rolling_stats.window_ms() / 1000,
+            heart_rate: rolling_stats.get("heart_rate"),
+            spo2: rolling_stats.get("spo2"),
+            temperature: rolling_stats.get("temperature"),
+        }
+    }
+
+    /// 设置提示性阈值配置
+    pub fn set_threshold_config(&self, configs: Vec<ThresholdConfig>) -> Result<(), String> {
+        thresholds::validate_thresholds(&configs)?;
+        println!(
+            "[DataProcessor] 提示性阈值已更新，共 {} 项体征",
+            configs.len()
+        );
+        self.states.thresholds.lock().unwrap().set_configs(configs);
+        Ok(())
+    }
+
+    /// 获取提示性阈值配置
+    pub fn get_threshold_config(&self) -> Vec<ThresholdConfig> {
+        self.states.thresholds.lock().unwrap().configs()
+    }
+
+    /// 查询越过提示性阈值的事件
+    ///
+    /// # 参数
+    /// * `start` - 起始时间戳（毫秒）
+    /// * `end` - 结束时间戳（毫秒）
+    pub fn get_threshold_events(
+        &self,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> Vec<ThresholdCrossing> {
+        self.states.thresholds.lock().unwrap().events(start, end)
+    }
+
+    /// 取出尚未推送的提示性阈值事件
+    pub fn take_threshold_events(&self) -> Vec<ThresholdCrossing> {
+        self.states.thresholds.lock().unwrap().take_pending()
+    }
+
+    /// 设置休息/睡眠判定参数，从下一个趋势聚合段开始生效
+    pub fn set_rest_detection_config(&self, config: RestDetectionConfig) -> Result<(), String> {
+        activity::validate_config(&config)?;
+        self.states.activity.lock().unwrap().set_config(config);
+        Ok(())
+    }
+
+    /// 获取休息/睡眠判定参数
+    pub fn get_rest_detection_config(&self) -> RestDetectionConfig {
+        self.states.activity.lock().unwrap().config()
+    }
+
+    /// 按休息和活动分开统计一段时间内的心率和血氧
+    ///
+    /// # 参数
+    /// * `start` - 起始时间戳（毫秒），省略时为结束时间前24小时
+    /// * `end` - 结束时间戳（毫秒），省略时为当前时间
+    pub fn get_rest_summary(&self, start: Option<u64>, end: Option<u64>) -> RestSummary {
+        let end = end.unwrap_or_else(|| self.sample_time());
+        let start = start.unwrap_or(end.saturating_sub(VITAL_SUMMARY_SPAN_MS));
+        let periods = self.states.activity.lock().unwrap().periods(start, end);
+        activity::rest_summary(
+            &self.get_trends("heart_rate", Some(start), Some(end)),
+            &self.get_trends("spo2", Some(start), Some(end)),
+            periods,
+        )
+    }
+
+    /// 复制另一个处理器中影响处理结果的配置（流水线、通道校正、通道开关、
+    /// 心率平均、血氧配置、ECG检测参数、滚动统计窗口、提示性阈值、休息判定参数
+    /// 和呼吸暂停判定时长），用于离线重新处理录制数据
+    ///
+    /// # 参数
+    /// * `source` - 配置来源，通常为正在运行的处理器
+    pub fn copy_processing_config(&self, source: &DataProcessor) -> Result<(), String> {
+        self.configure_pipeline(source.get_pipeline_config())?;
+        self.set_channel_adjustments(source.get_channel_adjustments());
+        *self.states.disabled_channels.lock().unwrap() =
+            source.states.disabled_channels.lock().unwrap().clone();
+        *self.states.auto_disabled_channels.lock().unwrap() =
+            source.states.auto_disabled_channels.lock().unwrap().clone();
+        self.set_heart_rate_averaging(source.get_heart_rate_averaging())?;
+        self.set_spo2_config(source.get_spo2_config())?;
+        self.set_ecg_processing_params(source.get_ecg_processing_params())?;
+        self.set_statistics_window(source.get_vital_statistics().window_secs)?;
+        self.set_threshold_config(source.get_threshold_config())?;
+        self.set_rest_detection_config(source.get_rest_detection_config())?;
+        self.set_apnea_threshold(source.get_apnea_threshold())
+    }
+
+    /// 同步处理一个采样，不经过原始数据队列和处理线程
+    ///
+    /// 用于离线重新处理录制数据，时间戳由调用方按录制时间给出，
+    /// 处理结果同样进入趋势、报警和会话标注。
+    ///
+    /// # 参数
+    /// * `vital_signs` - 原始采样
+    /// * `timestamp` - 采样时间戳（毫秒）
+    pub fn process_sample(&self, vital_signs: VitalSigns, timestamp: u64) -> ProcessedVitalSigns {
+        let vital_signs =
+            Self::apply_channel_adjustments(vital_signs, &self.states.channel_adjustments);
+        *self.total_processed.lock().unwrap() += 1;
+        Self::process_vital_signs(vital_signs, timestamp, &self.states)
+    }
+
+    /// 交还不再使用的处理结果，其LTTB数据点缓冲区供后续采样复用
+    pub fn recycle(&self, processed: ProcessedVitalSigns) {
+        Self::recycle_into(&self.states.lttb_pool, processed);
+    }
+
+    fn recycle_into(pool: &Mutex<Vec<Vec<LttbDataPoint>>>, processed: ProcessedVitalSigns) {
+        let buffer = processed.ecg_lttb_compressed;
+        if buffer.capacity() == 0 {
+            return;
+        }
+        let mut pool = pool.lock().unwrap();
+        if pool.len() < LTTB_POOL_CAPACITY {
+            pool.push(buffer);
+        }
+    }
+
+    /// 获取已处理的采样总数
+    pub fn total_processed(&self) -> u64 {
+        *self.total_processed.lock().unwrap()
+    }
+
+    /// 获取当前监护会话ID
+    pub fn session_id(&self) -> &str {
+        &self.states.session_id
+    }
+
+    /// 获取当前会话的事件标注，按时间先后排列
+    pub fn get_session_annotations(&self) -> Vec<SessionAnnotation> {
+        self.states
+            .annotations
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// 获取二氧化碳监测数据
+    ///
+    /// # 返回值
+    /// 返回EtCO2、FiCO2、呼吸频率以及LTTB压缩后的二氧化碳波形
+    pub fn get_capnography_data(&self) -> CapnographyData {
+        let (etco2, fico2, respiration_rate, last_breath_timestamp) = {
+            let co2_state = self.states.co2_state.lock().unwrap();
+            (
+                co2_state.etco2,
+                co2_state.fico2,
+                co2_state.respiration_rate,
+                co2_state.last_breath_timestamp,
+            )
+        };
+        let waveform = self
+            .states
+            .co2_lttb_state
+            .lock()
+            .unwrap()
+            .compressed_buffer
+            .clone();
+
+        CapnographyData {
+            etco2,
+            fico2,
+            respiration_rate,
+            last_breath_timestamp,
+            waveform,
+        }
+    }
+
+    /// 获取LTTB压缩后的ECG数据
+    ///
+    /// # 返回值
+    /// 返回当前LTTB压缩缓冲区中的所有数据点
+    pub fn get_lttb_compressed_data(&self) -> Vec<LttbDataPoint> {
+        let lttb_state = self.states.lttb_state.lock().unwrap();
+        lttb_state.compressed_buffer.clone()
+    }
+
+    /// 对原始体征数据应用通道增益/反相/偏移
+    ///
+    /// # 参数
+    /// * `vital_signs` - 原始体征数据
+    /// * `channel_adjustments` - 通道设置引用
+    ///
+    /// # 返回值
+    /// 返回校正后的体征数据，未配置的通道保持不变
+    fn apply_channel_adjustments(
+        mut vital_signs: VitalSigns,
+        channel_adjustments: &Arc<Mutex<BTreeMap<String, ChannelAdjustment>>>,
+    ) -> VitalSigns {
+        let adjustments = channel_adjustments.lock().unwrap();
+        if adjustments.is_empty() {
+            return vital_signs;
+        }
+
+        let apply = |channel: &str, raw: i32| -> i32 {
+            match adjustments.get(channel) {
+                Some(adj) => {
+                    let sign = if adj.invert { -1.0 } else { 1.0 };
+                    (raw as f64 * adj.gain * sign + adj.offset).round() as i32
+                }
+                None => raw,
+            }
+        };
+
+        vital_signs.ecg = apply("ecg", vital_signs.ecg);
+        vital_signs.spo2 = vital_signs.spo2.map(|raw| apply("spo2", raw));
+        vital_signs.temp = vital_signs.temp.map(|raw| apply("temp", raw));
+        vital_signs
+    }
+
+    /// 处理单个体征数据点
+    ///
+    /// 这是核心处理函数，集成了所有数据处理算法：
+    /// - ECG数据的LTTB压缩和归一化
+    /// - 体温数据的滤波和校准
+    /// - 血氧数据的验证
+    /// - 运动/伪差检测（伪差期间保持心率和血氧不更新）
+    /// - 心率和RR间隔的计算
+    /// - 通道开关（关闭的通道跳过处理）
+    /// - 按通道流水线配置进行滤波，并决定检测和压缩阶段的输入
+    ///
+    /// # 参数
+    /// * `vital_signs` - 原始体征数据
+    /// * `states` - 各项处理状态引用
+    ///
+    /// # 返回值
+    /// 返回处理后的体征数据，包含所有计算结果和压缩数据
+    fn process_vital_signs(
+        vital_signs: VitalSigns,
+        timestamp: u64,
+        states: &ProcessingStates,
+    ) -> ProcessedVitalSigns {
+        let received_at = vital_signs.received_at;
+
+        // 读取通道开关，关闭或缺失的通道不参与处理，输出为None
+        let (ecg_on, spo2_on, temp_on, resp_on, co2_on, nibp_on, glucose_on, extension_channels) = {
+            let disabled = states.disabled_channels.lock().unwrap();
+            let mut extension_channels = vital_signs.channels;
+            extension_channels.retain(|id, _| !disabled.contains(id));
+            (
+                !disabled.contains("ecg"),
+                !disabled.contains("spo2"),
+                !disabled.contains("temp"),
+                !disabled.contains("resp"),
+                !disabled.contains("co2"),
+                !disabled.contains("nibp"),
+                !disabled.contains("glucose"),
+                extension_channels,
+            )
+        };
+
+        // 各通道依次通过配置的流水线，得到检测阶段和压缩阶段的输入；
+        // 关闭的通道和本次采样缺失的通道不经过流水线
+        let (ecg_in, spo2_in, temp_in, resp_in, co2_in) = {
+            let mut pipelines = states.pipelines.lock().unwrap();
+            let mut run = |channel: &str, on: bool, value: Option<i32>| match value {
+                Some(value) if on => pipelines
+                    .get_mut(channel)
+                    .map(|p| p.run(value))
+                    .unwrap_or_default(),
+                _ => StageOutputs::default(),
+            };
+            (
+                run("ecg", ecg_on, Some(vital_signs.ecg)),
+                run("spo2", spo2_on, vital_signs.spo2),
+                run("temp", temp_on, vital_signs.temp),
+                run("resp", resp_on, vital_signs.resp),
+                run("co2", co2_on, vital_signs.co2),
+            )
+        };
+
+        // 处理体温数据，发热报警只按完成的窗口的平滑体温评估
+        let (body_temperature, smoothed_temperature) = match temp_in.detection {
+            Some(temp) => {
+                let (value, smoothed) = Self::process_body_temperature(temp, &states.temp_state);
+                (Some(value), smoothed)
+            }
+            None => (None, None),
+        };
+
+        // 处理血氧数据
+        let raw_blood_oxygen = match spo2_in.detection {
+            Some(spo2) => Self::process_blood_oxygen(spo2),
+            None => 0.0,
+        };
+
+        // 伪差检测，伪差期间血氧保持上一有效值
+        let (ecg_artifact, spo2_artifact, pace_spike) = Self::detect_artifacts(
+            ecg_in.detection.unwrap_or(vital_signs.ecg),
+            raw_blood_oxygen,
+            &states.artifact_state,
+        );
+        let ecg_detecting = ecg_in.detection.is_some();
+        let (ecg_artifact, spo2_artifact, pace_spike) = (
+            ecg_artifact && ecg_detecting,
+            spo2_artifact && spo2_in.detection.is_some(),
+            pace_spike && ecg_detecting,
+        );
+
+        // 处理心电数据
+        let beats_before = states.ecg_state.lock().unwrap().total_beats;
+        let (heart_rate, heart_rate_instant, rr_interval) = match ecg_in.detection {
+            Some(ecg) => {
+                let averaging = states.hr_averaging.lock().unwrap().clone();
+                Self::process_ecg_data(
+                    ecg,
+                    timestamp,
+                    received_at,
+                    ecg_artifact,
+                    pace_spike,
+                    &states.ecg_state,
+                    &averaging,
+                )
+            }
+            None => (0.0, 0.0, 0.0),
+        };
+        let beat_detected = states.ecg_state.lock().unwrap().total_beats > beats_before;
+
+        // 血氧按心搏滑动平均，伪差或读数缺失期间短时保持当前平均值；通道关闭时立即清空
+        let blood_oxygen = if spo2_on {
+            Self::process_spo2_averaging(
+                raw_blood_oxygen,
+                spo2_artifact,
+                beat_detected,
+                timestamp,
+                &states.spo2_state,
+            )
+        } else {
+            states.spo2_state.lock().unwrap().values.clear();
+            0.0
+        };
+        // 血氧内部以0表示无效读数，输出时转换为None
+        let valid_spo2 = |value: f64| (value > 0.0).then_some(value);
+
+        // LTTB处理和归一化
+        let (ecg_normalized, ecg_lttb_compressed) = match ecg_in.compression {
+            Some(ecg) => {
+                let mut compressed = states.lttb_pool.lock().unwrap().pop().unwrap_or_default();
+                let normalized = Self::process_waveform_lttb(
+                    ecg,
+                    timestamp,
+                    &states.lttb_state,
+                    &states.lttb_config,
+                    &states.lttb_worker,
+                    Some(&mut compressed),
+                );
+                (normalized, compressed)
+            }
+            None => (0.0, Vec::new()),
+        };
+
+        // 处理二氧化碳波形
+        let co2_waveform = vital_signs
+            .co2
+            .filter(|_| co2_on)
+            .map(|co2| co2 as f64 / 10.0);
+        let (etco2, co2_respiration_rate) = match co2_in.detection {
+            Some(co2) => {
+                let (etco2, rate) =
+                    Self::process_capnography(co2 as f64 / 10.0, timestamp, &states.co2_state);
+                (Some(etco2), Some(rate))
+            }
+            None => (None, None),
+        };
+        if let Some(co2) = co2_in.compression {
+            Self::process_waveform_lttb(
+                co2,
+                timestamp,
+                &states.co2_lttb_state,
+                &states.lttb_config,
+                &states.lttb_worker,
+                None,
+            );
+        }
+
+        // 处理无创血压测量（间歇性，仅在有新结果时记录）
+        let blood_pressure = if nibp_on {
+            vital_signs
+                .blood_pressure
+                .and_then(|m| Self::process_blood_pressure(m, timestamp, states))
+        } else {
+            None
+        };
+
+        // 处理血糖测量（间歇性，仅在有新结果时记录）
+        let glucose = if glucose_on {
+            Self::process_glucose(vital_signs.glucose, timestamp, &states.measurement_history)
+        } else {
+            None
+        };
+
+        // 处理扩展通道
+        let channels = Self::process_extension_channels(
+            extension_channels,
+            timestamp,
+            &states.channel_plugins,
+        );
+
+        // 处理呼吸波形
+        let respiration_raw = vital_signs.resp.filter(|_| resp_on);
+        let (respiration_rate, apnea_since, apnea_episode) = match resp_in.detection {
+            Some(resp) => {
+                let (rate, apnea_since, apnea_episode) =
+                    Self::process_respiration(resp, timestamp, &states.resp_state);
+                (Some(rate), apnea_since, apnea_episode)
+            }
+            None => (None, None, None),
+        };
+        Self::handle_apnea(apnea_since, apnea_episode, timestamp, states);
+        let apnea = apnea_since.is_some();
+        if let Some(resp) = resp_in.compression {
+            Self::process_waveform_lttb(
+                resp,
+                timestamp,
+                &states.resp_lttb_state,
+                &states.lttb_config,
+                &states.lttb_worker,
+                None,
+            );
+        }
+
+        {
+            let mut alarm_engine = states.alarm_engine.lock().unwrap();
+            alarm_engine.set_motion(ecg_artifact || spo2_artifact);
+            alarm_engine.tick(timestamp);
+            // 窗口未完成时保持上一个窗口的评估结果；体温通道关闭或缺失时解除报警
+            if body_temperature.is_none() || smoothed_temperature.is_some() {
+                alarm_engine.evaluate_temperature(
+                    smoothed_temperature.filter(|temp| *temp > 0.0),
+                    timestamp,
+                );
+            }
+        }
+
+        // 心率和血压均有效且心电流水线启用衍生指标阶段时计算衍生风险指标
+        let heart_rate_valid = ecg_on && !ecg_artifact && heart_rate > 0.0;
+        let derived_metrics = if heart_rate_valid && nibp_on && ecg_in.derived {
+            Self::compute_derived_metrics(heart_rate, timestamp, &states.bp_history)
+        } else {
+            None
+        };
+        states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .evaluate_derived_metrics(derived_metrics.as_ref(), timestamp);
+
+        // 分级限值报警，数值缺失或为0（无效）时不参与评估
+        let positive = |value: f64| (value > 0.0).then_some(value);
+        states.alarm_engine.lock().unwrap().evaluate_limits(
+            &[
+                (
+                    "heart_rate",
+                    positive(heart_rate).filter(|_| heart_rate_valid),
+                ),
+                (
+                    "spo2",
+                    positive(blood_oxygen).filter(|_| spo2_on && !spo2_artifact),
+                ),
+                (
+                    "respiration_rate",
+                    respiration_rate.and_then(positive).filter(|_| !apnea),
+                ),
+                ("etco2", etco2.and_then(positive)),
+            ],
+            timestamp,
+        );
+
+        let processed = ProcessedVitalSigns {
+            ecg_raw: if ecg_on { vital_signs.ecg } else { 0 },
+            ecg_normalized,
+            ecg_lttb_compressed,
+            body_temperature,
+            blood_oxygen: valid_spo2(blood_oxygen),
+            blood_oxygen_raw: valid_spo2(raw_blood_oxygen),
+            heart_rate,
+            heart_rate_instant,
+            rr_interval,
+            respiration_raw,
+            respiration_rate,
+            apnea,
+            co2_waveform,
+            etco2,
+            co2_respiration_rate,
+            glucose,
+            channels,
+            derived_metrics,
+            artifact: ecg_artifact || spo2_artifact,
+            timestamp,
+        };
+
+        Self::update_trends(&processed, &states.trends);
+        Self::update_rolling_stats(&processed, &states.rolling_stats);
+        Self::update_thresholds(&processed, &states.thresholds);
+        Self::update_activity(&processed, states);
+        Self::update_orthostatic_test(&processed, blood_pressure.as_ref(), states);
+        Self::update_hr_recovery_test(&processed, states);
+        Self::update_walk_test(&processed, states);
+        if let Some(output) = states.osc_output.lock().unwrap().as_mut() {
+            output.push(&processed);
+        }
+        if let Some(outlet) = states.lsl_outlet.lock().unwrap().as_mut() {
+            outlet.push(&processed);
+        }
+        if let Some(writer) = states.shared_memory.lock().unwrap().as_mut() {
+            writer.push(&processed);
+        }
+        if let Some(sink) = states.kafka_sink.lock().unwrap().as_mut() {
+            sink.push(&processed);
+        }
+        if let Some(uplink) = states.telemetry.lock().unwrap().as_mut() {
+            uplink.push(&processed);
+        }
+        if let Some(sender) = states.hl7_sender.lock().unwrap().as_mut() {
+            sender.push(&processed);
+        }
+        if let Some(archive) = states.ecg_archive.lock().unwrap().as_ref() {
+            archive.push(processed.timestamp, processed.ecg_raw);
+        }
+        processed
+    }
+
+    /// 处理呼吸波形数据
+    ///
+    /// 基于阻抗呼吸波形的简单呼吸检测：
+    /// - 低通平滑去除高频噪声
+    /// - 慢速基线跟踪和幅度包络估计
+    /// - 带滞回的基线上穿检测，每次上穿记为一次呼吸
+    /// - 超过 `apnea_threshold_ms` 无呼吸时标记呼吸暂停
+    ///
+    /// # 参数
+    /// * `resp_value` - 原始呼吸波形值
+    /// * `timestamp` - 当前时间戳（毫秒）
+    /// * `resp_state` - 呼吸处理状态引用
+    ///
+    /// # 返回值
+    /// 返回元组：(呼吸频率 次/分, 呼吸暂停开始时间戳, 本采样结束的呼吸暂停片段(开始, 结束))
+    fn process_respiration(
+        resp_value: i32,
+        timestamp: u64,
+        resp_state: &Arc<Mutex<RespirationProcessingState>>,
+    ) -> (f64, Option<u64>, Option<(u64, u64)>) {
+        let mut state = resp_state.lock().unwrap();
+        let value = resp_value as f64;
+
+        if !state.initialized {
+            state.smoothed = value;
+            state.baseline = value;
+            state.initialized = true;
+            return (state.respiration_rate, state.apnea_since, None);
+        }
+
+        // 低通平滑（约0.8Hz截止）和慢速基线（约4秒时间常数）
+        state.smoothed += (value - state.smoothed) * 0.02;
+        state.baseline += (state.smoothed - state.baseline) * 0.001;
+
+        // 幅度包络：跟随峰值，缓慢衰减
+        let deviation = state.smoothed - state.baseline;
+        state.envelope = deviation.abs().max(state.envelope * 0.9995);
+
+        // 带滞回的上穿检测
+        let hysteresis = state.envelope * 0.3;
+        if hysteresis > 0.0 {
+            if deviation < -hysteresis {
+                state.armed = true;
+            } else if state.armed && deviation > hysteresis {
+                state.armed = false;
+
+                let interval = state
+                    .last_breath_timestamp
+                    .map(|last| timestamp.saturating_sub(last));
+                match interval {
+                    Some(interval) if interval < state.min_breath_interval_ms => {
+                        // 间隔过短，视为噪声
+                    }
+                    Some(interval) => {
+                        state.breath_intervals.push_back(interval as f64);
+                        if state.breath_intervals.len() > 4 {
+                            state.breath_intervals.pop_front();
+                        }
+                        let mean_interval = state.breath_intervals.iter().sum::<f64>()
+                            / state.breath_intervals.len() as f64;
+                        state.respiration_rate = 60_000.0 / mean_interval;
+                        state.last_breath_timestamp = Some(timestamp);
+                    }
+                    None => state.last_breath_timestamp = Some(timestamp),
+                }
+            }
+        }
+
+        // 呼吸暂停判断：曾检测到呼吸，且距上次呼吸超过阈值
+        state.apnea = state
+            .last_breath_timestamp
+            .is_some_and(|last| timestamp.saturating_sub(last) > state.apnea_threshold_ms);
+        let mut ended_episode = None;
+        if state.apnea {
+            state.respiration_rate = 0.0;
+            state.breath_intervals.clear();
+            if state.apnea_since.is_none() {
+                state.apnea_since = state.last_breath_timestamp;
+            }
+        } else if let Some(since) = state.apnea_since.take() {
+            ended_episode = Some((since, timestamp));
+        }
+
+        (state.respiration_rate, state.apnea_since, ended_episode)
+    }
+
+    /// 更新呼吸暂停报警，并将结束的呼吸暂停片段记录为会话标注
+    ///
+    /// # 参数
+    /// * `apnea_since` - 当前呼吸暂停开始时间戳，未处于呼吸暂停时为None
+    /// * `ended_episode` - 本采样结束的呼吸暂停片段(开始, 结束)
+    /// * `timestamp` - 当前时间戳（毫秒）
+    /// * `states` - 各项处理状态引用
+    fn handle_apnea(
+        apnea_since: Option<u64>,
+        ended_episode: Option<(u64, u64)>,
+        timestamp: u64,
+        states: &ProcessingStates,
+    ) {
+        let threshold_ms = states.resp_state.lock().unwrap().apnea_threshold_ms;
+        states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .evaluate_apnea(apnea_since, threshold_ms, timestamp);
+
+        let Some((start, end)) = ended_episode else {
+            return;
+        };
+        let duration_ms = end.saturating_sub(start);
+        println!(
+            "[DataProcessor] 呼吸暂停结束，持续 {:.1} 秒",
+            duration_ms as f64 / 1000.0
+        );
+
+        let mut annotations = states.annotations.lock().unwrap();
+        if annotations.len() >= ANNOTATION_CAPACITY {
+            annotations.pop_front();
+        }
+        annotations.push_back(SessionAnnotation {
+            session_id: states.session_id.clone(),
+            kind: AnnotationKind::Apnea,
+            message: format!("呼吸暂停 {:.1} 秒", duration_ms as f64 / 1000.0),
+            start_timestamp: start,
+            end_timestamp: end,
+            duration_ms,
+        });
+    }
+
+    /// 记录系统时钟调整标注
+    ///
+    /// 采样时间轴不随系统时钟跳变，标注中记录调整量和调整后系统时钟相对时间轴的偏差，
+    /// 导出数据时可据此换算回系统时间
+    fn record_clock_adjustment(jump: ClockJump, states: &ProcessingStates) {
+        println!(
+            "[DataProcessor] 检测到系统时钟调整 {:+.1} 秒，当前偏差 {:+.1} 秒",
+            jump.adjustment_ms as f64 / 1000.0,
+            jump.offset_ms as f64 / 1000.0
+        );
+
+        let mut annotations = states.annotations.lock().unwrap();
+        if annotations.len() >= ANNOTATION_CAPACITY {
+            annotations.pop_front();
+        }
+        annotations.push_back(SessionAnnotation {
+            session_id: states.session_id.clone(),
+            kind: AnnotationKind::ClockAdjustment,
+            message: format!(
+                "系统时钟调整 {:+.1} 秒，系统时间比采样时间轴{} {:.1} 秒",
+                jump.adjustment_ms as f64 / 1000.0,
+                if jump.offset_ms >= 0 { "快" } else { "慢" },
+                jump.offset_ms.unsigned_abs() as f64 / 1000.0
+            ),
+            start_timestamp: jump.timestamp,
+            end_timestamp: jump.timestamp,
+            duration_ms: 0,
+        });
+    }
+
+    /// 运动/伪差及起搏脉冲检测
+    ///
+    /// ECG通道检测两类伪差：
+    /// - 相邻采样变化量超过生理上可能的斜率
+    /// - 最近1秒的方差相对基线方差突然放大
+    ///
+    /// 陡升后立即回落的单点窄脉冲判定为起搏脉冲而非伪差。由于需要
+    /// 看到回落沿，斜率判断针对上一个采样点进行（延迟一个采样）。
+    ///
+    /// 血氧通道检测相邻采样间不可能的跳变。检测到伪差后，
+    /// 标记会保持 `hold_samples` 个采样点，避免信号恢复瞬间的误判。
     ///
     /// # 参数
-    /// * `vital_signs` - 原始体征数据
-    /// * `ecg_state` - ECG处理状态引用
-    /// * `temp_state` - 体温处理状态引用
-    /// * `lttb_state` - LTTB处理状态引用
-    /// * `lttb_config` - LTTB配置参数引用
+    /// * `ecg_value` - 当前ECG原始值
+    /// * `spo2_value` - 当前血氧值（百分比）
+    /// * `artifact_state` - 伪差检测状态引用
     ///
     /// # 返回值
-    /// 返回处理后的体征数据，包含所有计算结果和压缩数据
-    fn process_vital_signs(
-        vital_signs: VitalSigns,
-        ecg_state: &Arc<Mutex<EcgProcessingState>>,
-        temp_state: &Arc<Mutex<TemperatureProcessingState>>,
-        lttb_state: &Arc<Mutex<LttbProcessingState>>,
-        lttb_config: &LttbConfig,
-    ) -> ProcessedVitalSigns {
-        // 生成时间戳
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
+    /// 返回元组：(ECG是否为伪差, 血氧是否为伪差, 上一个采样点是否为起搏脉冲)
+    fn detect_artifacts(
+        ecg_value: i32,
+        spo2_value: f64,
+        artifact_state: &Arc<Mutex<ArtifactDetectionState>>,
+    ) -> (bool, bool, bool) {
+        let mut state = artifact_state.lock().unwrap();
+        let ecg_f64 = ecg_value as f64;
 
-        // 处理体温数据
-        let body_temperature = Self::process_body_temperature(vital_signs.temp, temp_state);
+        // ---------- ECG：起搏脉冲与斜率检测 ----------
+        let mut pace_spike = false;
+        let mut slope_exceeded = false;
+        let before_spike = state.prev_ecg;
+        if let (Some(prev2), Some(prev1)) = (state.prev_ecg, state.last_ecg) {
+            let rise = prev1 as f64 - prev2 as f64;
+            let fall = ecg_f64 - prev1 as f64;
+            let narrow = rise.signum() != fall.signum()
+                && fall.abs() > rise.abs() * 0.5
+                && (ecg_f64 - prev2 as f64).abs() < rise.abs() * 0.3;
 
-        // 处理血氧数据
-        let blood_oxygen = Self::process_blood_oxygen(vital_signs.spo2);
+            if rise.abs() > state.pace_slew_threshold && narrow {
+                pace_spike = true;
+            } else if rise.abs() > state.max_ecg_slope {
+                slope_exceeded = true;
+            }
+        }
+        state.prev_ecg = state.last_ecg;
+        state.last_ecg = Some(ecg_value);
 
-        // 处理心电数据
-        let (heart_rate, rr_interval) = Self::process_ecg_data(vital_signs.ecg, ecg_state);
+        // ---------- ECG：短时方差检测 ----------
+        // 起搏脉冲在方差窗口中用插值替换
+        if pace_spike {
+            let interpolated = (before_spike.unwrap_or(ecg_value) as f64 + ecg_f64) / 2.0;
+            if let Some(last) = state.ecg_window.back_mut() {
+                *last = interpolated;
+            }
+        }
+        state.ecg_window.push_back(ecg_f64);
+        if state.ecg_window.len() > ARTIFACT_WINDOW_SIZE {
+            state.ecg_window.pop_front();
+        }
 
-        // LTTB处理和归一化
-        let (ecg_normalized, ecg_lttb_compressed) =
-            Self::process_ecg_lttb(vital_signs.ecg, timestamp, lttb_state, lttb_config);
+        let mut variance_exceeded = false;
+        if state.ecg_window.len() == ARTIFACT_WINDOW_SIZE {
+            let n = state.ecg_window.len() as f64;
+            let mean = state.ecg_window.iter().sum::<f64>() / n;
+            let variance = state
+                .ecg_window
+                .iter()
+                .map(|v| (v - mean).powi(2))
+                .sum::<f64>()
+                / n;
 
-        ProcessedVitalSigns {
-            ecg_raw: vital_signs.ecg,
-            ecg_normalized,
-            ecg_lttb_compressed,
-            body_temperature,
-            blood_oxygen,
-            heart_rate,
-            rr_interval,
-            timestamp,
+            if state.baseline_variance <= 0.0 {
+                state.baseline_variance = variance;
+            } else if variance > state.baseline_variance * state.variance_ratio_threshold {
+                variance_exceeded = true;
+            } else if state.ecg_hold_remaining == 0 {
+                // 基线只在信号干净时缓慢跟随
+                state.baseline_variance = state.baseline_variance * 0.998 + variance * 0.002;
+            }
+        }
+
+        if slope_exceeded || variance_exceeded {
+            state.ecg_hold_remaining = state.hold_samples;
+        } else if state.ecg_hold_remaining > 0 {
+            state.ecg_hold_remaining -= 1;
+        }
+
+        // ---------- 血氧：跳变检测 ----------
+        let spo2_jump = spo2_value > 0.0
+            && state
+                .last_spo2
+                .is_some_and(|last| last > 0.0 && (spo2_value - last).abs() > state.max_spo2_jump);
+        state.last_spo2 = Some(spo2_value);
+
+        if spo2_jump {
+            state.spo2_hold_remaining = state.hold_samples;
+        } else if state.spo2_hold_remaining > 0 {
+            state.spo2_hold_remaining -= 1;
         }
+
+        (
+            state.ecg_hold_remaining > 0,
+            state.spo2_hold_remaining > 0,
+            pace_spike,
+        )
     }
 
-    /// ECG数据的LTTB压缩和归一化处理
+    /// 波形数据（ECG、呼吸、二氧化碳）的LTTB压缩和归一化处理
     ///
     /// 实现Largest Triangle Three Buckets算法进行数据压缩，
-    /// 同时将ECG数据归一化到-1到1的范围。
+    /// 同时将波形数据归一化到-1到1的范围。
     ///
     /// # 参数
-    /// * `ecg_value` - 原始ECG数据值
+    /// * `ecg_value` - 原始波形数据值
     /// * `timestamp` - 当前时间戳
     /// * `lttb_state` - LTTB处理状态引用
     /// * `lttb_config` - LTTB配置参数引用
+    /// * `lttb_worker` - 缓冲区写满时接收快照的压缩工作线程
+    /// * `output` - 需要压缩数据时传入，压缩后的数据点复制到其中（复用已有容量）
     ///
     /// # 返回值
-    /// 返回元组：(归一化ECG值, 压缩后的数据点向量)
-    fn process_ecg_lttb(
+    /// 返回归一化后的波形值
+    fn process_waveform_lttb(
         ecg_value: i32,
         timestamp: u64,
         lttb_state: &Arc<Mutex<LttbProcessingState>>,
         lttb_config: &LttbConfig,
-    ) -> (f64, Vec<LttbDataPoint>) {
+        lttb_worker: &LttbWorker,
+        output: Option<&mut Vec<LttbDataPoint>>,
+    ) -> f64 {
         let mut state = lttb_state.lock().unwrap();
 
         let ecg_f64 = ecg_value as f64;
@@ -328,33 +3268,24 @@ impl DataProcessor {
             Self::recalculate_global_range(&mut state);
         }
 
-        let compressed_data = if state.raw_buffer.len() >= state.buffer_size {
+        if state.raw_buffer.len() >= state.buffer_size {
+            // 压缩交给工作线程，压缩结果在工作线程完成后写回compressed_buffer
             let target_points = state.buffer_size / state.compression_ratio;
-            // 用 block 临时作用域确保不可变引用提前结束
-            let compressed = { Self::lttb_downsample(&state.raw_buffer, target_points) };
-            // 这里 compressed 已经是新 Vec，不再引用 raw_buffer
-
-            state.compressed_buffer = compressed.clone();
+            if !lttb_worker.submit(lttb_state, &state.raw_buffer, target_points) {
+                hot_log!("[LTTB] 压缩线程积压，跳过本次压缩");
+            }
 
             // 修复借用冲突：先计算keep_size和drain范围
             let keep_size = state.buffer_size / 4;
             let buffer_len = state.raw_buffer.len();
             let drain_end = buffer_len - keep_size;
             state.raw_buffer.drain(0..drain_end);
+        }
 
-            println!(
-                "[LTTB] 压缩完成: {} -> {} 数据点，压缩比: {:.1}:1",
-                state.buffer_size,
-                target_points,
-                state.buffer_size as f64 / target_points as f64
-            );
-
-            compressed
-        } else {
-            state.compressed_buffer.clone()
-        };
-
-        (ecg_normalized, compressed_data)
+        if let Some(output) = output {
+            output.clone_from(&state.compressed_buffer);
+        }
+        ecg_normalized
     }
 
     /// LTTB降采样算法实现
@@ -368,7 +3299,7 @@ impl DataProcessor {
     ///
     /// # 返回值
     /// 返回降采样后的数据点向量
-    fn lttb_downsample(data: &[LttbDataPoint], threshold: usize) -> Vec<LttbDataPoint> {
+    pub(crate) fn lttb_downsample(data: &[LttbDataPoint], threshold: usize) -> Vec<LttbDataPoint> {
         if data.len() <= threshold {
             return data.to_vec();
         }
@@ -476,7 +3407,7 @@ impl DataProcessor {
         state.global_max = state.global_max * (1.0 - alpha) + new_max * alpha;
         state.global_min = state.global_min * (1.0 - alpha) + new_min * alpha;
 
-        println!(
+        hot_log!(
             "[LTTB] 动态范围更新: [{:.2}, {:.2}]",
             state.global_min, state.global_max
         );
@@ -495,20 +3426,24 @@ impl DataProcessor {
     /// * `temp_state` - 体温处理状态引用
     ///
     /// # 返回值
-    /// 返回处理后的体温值（摄氏度）
+    /// 返回元组：(处理后的体温值, 本采样完成一个窗口时的平滑体温)，单位摄氏度
     fn process_body_temperature(
         raw_temp: i32,
         temp_state: &Arc<Mutex<TemperatureProcessingState>>,
-    ) -> f64 {
+    ) -> (f64, Option<f64>) {
         let mut state = temp_state.lock().unwrap();
 
         // 转换原始温度值（假设原始值需要除以10）
         let raw_temp_value = raw_temp as f64 / 10.0;
+        if state.recent_raw_values.len() >= TEMP_CALIBRATION_WINDOW {
+            state.recent_raw_values.pop_front();
+        }
+        state.recent_raw_values.push_back(raw_temp_value);
         let temp_value = raw_temp_value * state.scale_factor + state.offset;
 
         // 异常值检测：如果温度值异常低，可能是传感器问题
         let adjusted_temp = if temp_value < state.room_temperature - 10.0 {
-            println!(
+            hot_log!(
                 "[DataProcessor] 检测到异常低温度值 {:.2}°C，使用室温 {:.2}°C 作为基准",
                 temp_value, state.room_temperature
             );
@@ -539,70 +3474,610 @@ impl DataProcessor {
                 // 清空历史数据，准备下一轮统计
                 state.temperatures.clear();
 
-                // 应用最大温度限制
-                if average_temp > state.max_temp {
-                    state.max_temp
-                } else {
-                    average_temp
-                }
+                // 应用最大温度限制（超出人体可能范围的读数）
+                let smoothed = average_temp.min(state.max_temp);
+                (smoothed, Some(smoothed))
             } else {
-                adjusted_temp
+                (adjusted_temp, None)
             }
         } else {
-            adjusted_temp
+            (adjusted_temp, None)
         }
     }
 
     /// 处理血氧数据
     ///
-    /// 简单的血氧数据验证和处理。
+    /// 血氧数据的范围验证，超出 0–100% 的读数视为无效。
     ///
     /// # 参数
     /// * `raw_spo2` - 原始血氧数据
     ///
     /// # 返回值
-    /// 返回处理后的血氧值（百分比）
+    /// 返回处理后的血氧值（百分比），无效时返回0
     fn process_blood_oxygen(raw_spo2: i32) -> f64 {
-        // 简单的数据验证：小于1的值视为无效
-        if raw_spo2 < 1 {
+        let spo2 = (raw_spo2 as f64) / 10.0;
+        if spo2 > 0.0 && spo2 <= 100.0 {
+            spo2
+        } else {
             0.0
+        }
+    }
+
+    /// 血氧尖峰剔除和滑动平均
+    ///
+    /// 每个心搏采样一次血氧读数（没有心搏时按固定间隔采样），
+    /// 对最近N个读数求平均作为显示值。相对平均值偏差过大的读数视为尖峰被丢弃，
+    /// 连续多次偏离时视为真实变化，以新的水平重新开始平均。
+    /// 没有有效读数时最多保持平均值 `SPO2_HOLD_MAX_AGE_MS`，之后视为信号丢失，清空平均并返回0。
+    ///
+    /// # 参数
+    /// * `spo2` - 经范围验证的血氧读数，无效时为0
+    /// * `artifact` - 血氧信号是否受伪差干扰
+    /// * `beat_detected` - 本采样是否检测到心搏
+    /// * `timestamp` - 当前时间戳（毫秒）
+    /// * `spo2_state` - 血氧处理状态引用
+    ///
+    /// # 返回值
+    /// 返回平均后的血氧值，尚无有效读数或信号丢失时返回0
+    fn process_spo2_averaging(
+        spo2: f64,
+        artifact: bool,
+        beat_detected: bool,
+        timestamp: u64,
+        spo2_state: &Arc<Mutex<Spo2ProcessingState>>,
+    ) -> f64 {
+        let mut state = spo2_state.lock().unwrap();
+        let average = |values: &VecDeque<f64>| {
+            if values.is_empty() {
+                0.0
+            } else {
+                values.iter().sum::<f64>() / values.len() as f64
+            }
+        };
+
+        let sample_due = beat_detected
+            || state
+                .last_sample_timestamp
+                .is_none_or(|t| timestamp.saturating_sub(t) >= SPO2_FALLBACK_INTERVAL_MS);
+        if spo2 <= 0.0 || artifact || !sample_due {
+            let signal_lost = state
+                .last_sample_timestamp
+                .is_none_or(|t| timestamp.saturating_sub(t) > SPO2_HOLD_MAX_AGE_MS);
+            if signal_lost && !state.values.is_empty() {
+                println!("[DataProcessor] 血氧信号丢失，清空血氧平均");
+                state.values.clear();
+                state.consecutive_spikes = 0;
+            }
+            return average(&state.values);
+        }
+        state.last_sample_timestamp = Some(timestamp);
+
+        let current = average(&state.values);
+        if !state.values.is_empty() && (spo2 - current).abs() > state.config.max_spike {
+            state.consecutive_spikes += 1;
+            if state.consecutive_spikes < SPO2_MAX_CONSECUTIVE_SPIKES {
+                return current;
+            }
+            // 连续多次偏离，视为真实变化
+            state.values.clear();
+        }
+        state.consecutive_spikes = 0;
+
+        state.values.push_back(spo2);
+        while state.values.len() > state.config.averaging_beats {
+            state.values.pop_front();
+        }
+        average(&state.values)
+    }
+
+    /// 处理二氧化碳波形数据
+    ///
+    /// 基于阈值和滞回的呼吸相检测：
+    /// - 波形上穿呼气阈值时进入呼气相，记为一次呼吸
+    /// - 波形下穿吸气阈值时结束呼气相，呼气相内的峰值即为EtCO2
+    /// - 吸气相内的最小值作为FiCO2
+    /// - 超过 `no_breath_timeout_ms` 无呼吸时频率和EtCO2归零
+    ///
+    /// # 参数
+    /// * `co2` - 二氧化碳波形值（mmHg）
+    /// * `timestamp` - 当前时间戳（毫秒）
+    /// * `co2_state` - 二氧化碳处理状态引用
+    ///
+    /// # 返回值
+    /// 返回元组：(EtCO2 mmHg, 呼吸频率 次/分)
+    fn process_capnography(
+        co2: f64,
+        timestamp: u64,
+        co2_state: &Arc<Mutex<CapnographyProcessingState>>,
+    ) -> (f64, f64) {
+        let mut state = co2_state.lock().unwrap();
+
+        if state.in_expiration {
+            state.expiration_peak = state.expiration_peak.max(co2);
+            if co2 < state.inspiration_threshold {
+                // 呼气结束，记录呼气末值
+                state.in_expiration = false;
+                state.etco2 = state.expiration_peak;
+                state.inspiration_min = co2;
+            }
+        } else {
+            state.inspiration_min = state.inspiration_min.min(co2);
+            if co2 > state.expiration_threshold {
+                // 进入呼气相
+                state.in_expiration = true;
+                state.expiration_peak = co2;
+                if state.inspiration_min.is_finite() {
+                    state.fico2 = state.inspiration_min;
+                }
+
+                let interval = state
+                    .last_breath_timestamp
+                    .map(|last| timestamp.saturating_sub(last));
+                match interval {
+                    Some(interval) if interval < state.min_breath_interval_ms => {
+                        // 间隔过短，视为噪声
+                    }
+                    Some(interval) => {
+                        state.breath_intervals.push_back(interval as f64);
+                        if state.breath_intervals.len() > 4 {
+                            state.breath_intervals.pop_front();
+                        }
+                        let mean_interval = state.breath_intervals.iter().sum::<f64>()
+                            / state.breath_intervals.len() as f64;
+                        state.respiration_rate = 60_000.0 / mean_interval;
+                        state.last_breath_timestamp = Some(timestamp);
+                    }
+                    None => state.last_breath_timestamp = Some(timestamp),
+                }
+            }
+        }
+
+        // 长时间无呼吸时清除频率和EtCO2
+        let no_breath = state
+            .last_breath_timestamp
+            .is_some_and(|last| timestamp.saturating_sub(last) > state.no_breath_timeout_ms);
+        if no_breath {
+            state.respiration_rate = 0.0;
+            state.etco2 = 0.0;
+            state.breath_intervals.clear();
+        }
+
+        (state.etco2, state.respiration_rate)
+    }
+
+    /// 处理扩展通道数据
+    ///
+    /// 已注册插件的通道交由插件处理，其余通道按原值输出。
+    ///
+    /// # 参数
+    /// * `channels` - 解析得到的扩展通道数据
+    /// * `timestamp` - 当前时间戳（毫秒）
+    /// * `channel_plugins` - 扩展通道插件引用
+    ///
+    /// # 返回值
+    /// 返回处理后的扩展通道数据
+    fn process_extension_channels(
+        mut channels: BTreeMap<String, f64>,
+        timestamp: u64,
+        channel_plugins: &Arc<Mutex<BTreeMap<String, Box<dyn ChannelPlugin>>>>,
+    ) -> BTreeMap<String, f64> {
+        if channels.is_empty() {
+            return channels;
+        }
+
+        let mut plugins = channel_plugins.lock().unwrap();
+        for (id, value) in channels.iter_mut() {
+            if let Some(plugin) = plugins.get_mut(id) {
+                *value = plugin.process(*value, timestamp);
+            }
+        }
+        channels
+    }
+
+    /// 处理无创血压测量结果
+    ///
+    /// 每次测量都写入血压历史（包括袖带状态异常的测量）；
+    /// 测量成功且数值有效时计入趋势并评估持续性高血压/低血压报警。
+    ///
+    /// # 参数
+    /// * `measurement` - 设备上报的测量结果
+    /// * `timestamp` - 当前时间戳（毫秒）
+    /// * `states` - 各项处理状态引用
+    ///
+    /// # 返回值
+    /// 返回写入历史的测量记录，数值无效被丢弃时返回None
+    fn process_blood_pressure(
+        measurement: NibpMeasurement,
+        timestamp: u64,
+        states: &ProcessingStates,
+    ) -> Option<BloodPressureReading> {
+        let NibpMeasurement {
+            systolic,
+            diastolic,
+            cuff_status,
+        } = measurement;
+
+        if cuff_status == CuffStatus::Ok {
+            let (sys_min, sys_max) = SYSTOLIC_VALID_RANGE;
+            let (dia_min, dia_max) = DIASTOLIC_VALID_RANGE;
+            if !(sys_min..=sys_max).contains(&systolic)
+                || !(dia_min..=dia_max).contains(&diastolic)
+                || systolic <= diastolic
+            {
+                hot_log!("[DataProcessor] 丢弃无效血压值: {}/{}", systolic, diastolic);
+                return None;
+            }
         } else {
-            (raw_spo2 as f64) / 10.0
+            hot_log!("[DataProcessor] 血压测量失败，袖带状态: {:?}", cuff_status);
+        }
+
+        let reading = BloodPressureReading {
+            systolic,
+            diastolic,
+            mean_arterial_pressure: (systolic as f64 + 2.0 * diastolic as f64) / 3.0,
+            cuff_status,
+            timestamp,
+        };
+
+        {
+            let mut history = states.bp_history.lock().unwrap();
+            if history.len() >= BP_HISTORY_CAPACITY {
+                history.pop_front();
+            }
+            history.push_back(reading.clone());
+        }
+
+        if cuff_status == CuffStatus::Ok {
+            let mut trends = states.trends.lock().unwrap();
+            trends.add("systolic", systolic as f64, timestamp);
+            trends.add("diastolic", diastolic as f64, timestamp);
+            trends.add(
+                "mean_arterial_pressure",
+                reading.mean_arterial_pressure,
+                timestamp,
+            );
+        }
+
+        states
+            .alarm_engine
+            .lock()
+            .unwrap()
+            .evaluate_blood_pressure(&reading);
+        Some(reading)
+    }
+
+    /// 计算休克指数、改良休克指数和心率收缩压乘积
+    ///
+    /// 使用最近一次成功且未超过有效期的血压测量。
+    ///
+    /// # 参数
+    /// * `heart_rate` - 当前有效心率（次/分）
+    /// * `timestamp` - 当前时间戳（毫秒）
+    /// * `bp_history` - 血压测量历史引用
+    ///
+    /// # 返回值
+    /// 返回衍生指标，无有效血压时返回None
+    fn compute_derived_metrics(
+        heart_rate: f64,
+        timestamp: u64,
+        bp_history: &Arc<Mutex<VecDeque<BloodPressureReading>>>,
+    ) -> Option<DerivedMetrics> {
+        let history = bp_history.lock().unwrap();
+        let reading = history
+            .iter()
+            .rev()
+            .find(|r| r.cuff_status == CuffStatus::Ok)?;
+        if timestamp.saturating_sub(reading.timestamp) > BP_FRESHNESS_MS {
+            return None;
+        }
+
+        let systolic = reading.systolic as f64;
+        Some(DerivedMetrics {
+            shock_index: heart_rate / systolic,
+            modified_shock_index: heart_rate / reading.mean_arterial_pressure,
+            rate_pressure_product: heart_rate * systolic,
+            blood_pressure_timestamp: reading.timestamp,
+        })
+    }
+
+    /// 推进进行中的体位性生命体征测试
+    ///
+    /// 测试完成时将结果写入测量历史。
+    ///
+    /// # 参数
+    /// * `processed` - 处理后的体征数据
+    /// * `blood_pressure` - 本采样完成的血压测量
+    /// * `states` - 各项处理状态引用
+    fn update_orthostatic_test(
+        processed: &ProcessedVitalSigns,
+        blood_pressure: Option<&BloodPressureReading>,
+        states: &ProcessingStates,
+    ) {
+        let mut test = states.orthostatic_test.lock().unwrap();
+        let Some(session) = test.as_mut() else {
+            return;
+        };
+        let heart_rate = (!processed.artifact).then_some(processed.heart_rate);
+        let Some(result) = session.update(heart_rate, blood_pressure, processed.timestamp) else {
+            return;
+        };
+
+        println!(
+            "[DataProcessor] 体位性测试完成: {}",
+            if result.passed { "通过" } else { "未通过" }
+        );
+        let mut history = states.measurement_history.lock().unwrap();
+        if history.len() >= MEASUREMENT_HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        history.push_back(MeasurementRecord {
+            kind: MeasurementKind::Orthostatic,
+            value: if result.passed { 1.0 } else { 0.0 },
+            unit: String::new(),
+            timestamp: result.completed_at,
+            details: Some(MeasurementDetails::Orthostatic(result)),
+        });
+    }
+
+    /// 推进进行中的心率恢复测试，完成时将结果写入测量历史
+    fn update_hr_recovery_test(processed: &ProcessedVitalSigns, states: &ProcessingStates) {
+        let mut test = states.hr_recovery_test.lock().unwrap();
+        let Some(session) = test.as_mut() else {
+            return;
+        };
+        let heart_rate = (!processed.artifact).then_some(processed.heart_rate);
+        let Some(result) = session.update(heart_rate, processed.timestamp) else {
+            return;
+        };
+
+        let one_minute = result.points.first().and_then(|p| p.recovery);
+        println!(
+            "[DataProcessor] 心率恢复测试完成: 1分钟下降={:?}",
+            one_minute
+        );
+        let mut history = states.measurement_history.lock().unwrap();
+        if history.len() >= MEASUREMENT_HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        history.push_back(MeasurementRecord {
+            kind: MeasurementKind::HeartRateRecovery,
+            value: one_minute.unwrap_or(0.0),
+            unit: "bpm".to_string(),
+            timestamp: result.completed_at,
+            details: Some(MeasurementDetails::HeartRateRecovery(result)),
+        });
+    }
+
+    /// 推进进行中的六分钟步行试验，伪差期间的心率和血氧不计入
+    fn update_walk_test(processed: &ProcessedVitalSigns, states: &ProcessingStates) {
+        let mut test = states.walk_test.lock().unwrap();
+        let Some(session) = test.as_mut() else {
+            return;
+        };
+        let valid = !processed.artifact;
+        session.update(
+            valid.then_some(processed.heart_rate),
+            processed.blood_oxygen.filter(|_| valid),
+            processed.timestamp,
+        );
+    }
+
+    /// 将处理结果中的连续体征数值计入趋势
+    ///
+    /// 数值为0（无效或通道关闭）以及伪差期间的心率和血氧不计入。
+    ///
+    /// # 参数
+    /// * `processed` - 处理后的体征数据
+    /// * `trends` - 趋势聚合引用
+    fn update_trends(processed: &ProcessedVitalSigns, trends: &Arc<Mutex<TrendAggregator>>) {
+        let timestamp = processed.timestamp;
+        let mut trends = trends.lock().unwrap();
+        let mut add = |vital: &str, value: Option<f64>| {
+            if let Some(value) = value.filter(|value| *value > 0.0) {
+                trends.add(vital, value, timestamp);
+            }
+        };
+
+        if !processed.artifact {
+            add("heart_rate", Some(processed.heart_rate));
+            add("spo2", processed.blood_oxygen);
+        }
+        add("temperature", processed.body_temperature);
+        add("respiration_rate", processed.respiration_rate);
+        add("etco2", processed.etco2);
+        add("glucose", processed.glucose);
+    }
+
+    /// 将心率、血氧和体温计入滚动统计，无效数值和伪差期间的心率、血氧不计入
+    fn update_rolling_stats(
+        processed: &ProcessedVitalSigns,
+        rolling_stats: &Arc<Mutex<RollingStatistics>>,
+    ) {
+        let timestamp = processed.timestamp;
+        let mut rolling_stats = rolling_stats.lock().unwrap();
+        let mut add = |vital: &str, value: Option<f64>| {
+            if let Some(value) = value.filter(|value| *value > 0.0) {
+                rolling_stats.add(vital, value, timestamp);
+            }
+        };
+
+        if !processed.artifact {
+            add("heart_rate", Some(processed.heart_rate));
+            add("spo2", processed.blood_oxygen);
+        }
+        add("temperature", processed.body_temperature);
+    }
+
+    /// 检查连续体征是否越过提示性阈值，无效数值和伪差期间的心率、血氧不检查
+    fn update_thresholds(
+        processed: &ProcessedVitalSigns,
+        thresholds: &Arc<Mutex<ThresholdMonitor>>,
+    ) {
+        let timestamp = processed.timestamp;
+        let mut thresholds = thresholds.lock().unwrap();
+        let mut observe = |vital: &str, value: Option<f64>| {
+            if let Some(value) = value.filter(|value| *value > 0.0) {
+                thresholds.observe(vital, value, timestamp);
+            }
+        };
+
+        if !processed.artifact {
+            observe("heart_rate", Some(processed.heart_rate));
+            observe("spo2", processed.blood_oxygen);
+        }
+        observe("temperature", processed.body_temperature);
+        observe("respiration_rate", processed.respiration_rate);
+        observe("etco2", processed.etco2);
+    }
+
+    /// 累计伪差占比，每个趋势聚合段结束时判定休息/活动并标记到趋势上
+    fn update_activity(processed: &ProcessedVitalSigns, states: &ProcessingStates) {
+        let mut classifier = states.activity.lock().unwrap();
+        let Some((start, artifact_rate)) =
+            classifier.observe(processed.timestamp, processed.artifact)
+        else {
+            return;
+        };
+        let mut trends = states.trends.lock().unwrap();
+        let heart_rate = trends.get("heart_rate", Some(start), Some(start)).pop();
+        if let Some(state) = classifier.classify(start, artifact_rate, heart_rate.as_ref()) {
+            trends.tag_activity(start, state);
+        }
+    }
+
+    /// 处理血糖测量结果
+    ///
+    /// 血糖为间歇性测量，只有设备上报新结果时才存在。
+    /// 有效结果写入测量历史，超出有效范围的结果被丢弃。
+    ///
+    /// # 参数
+    /// * `raw_glucose` - 本采样携带的血糖值（mmol/L）
+    /// * `timestamp` - 当前时间戳（毫秒）
+    /// * `measurement_history` - 测量历史记录引用
+    ///
+    /// # 返回值
+    /// 返回有效的血糖值，无新测量或无效时返回None
+    fn process_glucose(
+        raw_glucose: Option<f64>,
+        timestamp: u64,
+        measurement_history: &Arc<Mutex<VecDeque<MeasurementRecord>>>,
+    ) -> Option<f64> {
+        let value = raw_glucose?;
+        let (min, max) = GLUCOSE_VALID_RANGE;
+        if !(min..=max).contains(&value) {
+            hot_log!("[DataProcessor] 丢弃无效血糖值: {}", value);
+            return None;
+        }
+
+        let mut history = measurement_history.lock().unwrap();
+        if history.len() >= MEASUREMENT_HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        history.push_back(MeasurementRecord {
+            kind: MeasurementKind::Glucose,
+            value,
+            unit: "mmol/L".to_string(),
+            timestamp,
+            details: None,
+        });
+
+        Some(value)
+    }
+
+    /// RR间期是否明显短于最近心搏（提前心搏）
+    ///
+    /// # 参数
+    /// * `rr_history` - 之前的RR间期序列
+    /// * `rr_interval_ms` - 当前心搏的RR间期（毫秒）
+    fn is_premature(rr_history: &VecDeque<RrIntervalPoint>, rr_interval_ms: f64) -> bool {
+        let mut recent: Vec<f64> = rr_history
+            .iter()
+            .rev()
+            .take(PREMATURE_REFERENCE_BEATS)
+            .map(|p| p.rr_interval_ms)
+            .collect();
+        if recent.len() < PREMATURE_REFERENCE_BEATS {
+            return false;
         }
+        recent.sort_by(f64::total_cmp);
+        rr_interval_ms < recent[recent.len() / 2] * PREMATURE_RR_RATIO
     }
 
     /// 处理ECG数据（传统算法）
     ///
     /// 实现基于滑动窗口的R波检测算法，包括：
     /// - 动态阈值更新
-    /// - 3点滑动窗口波峰检测
+    /// - 3点滑动窗口波峰检测（剔除起搏脉冲）
+    /// - 起搏心搏统计
     /// - 心率和RR间隔计算
+    /// - 按平均策略平滑心率
     /// - 数据缓冲区管理
     ///
     /// # 参数
     /// * `ecg_value` - 当前ECG数据值
+    /// * `timestamp` - 当前时间戳（毫秒）
+    /// * `received_at` - 当前采样放入原始数据队列的时间戳（毫秒）
+    /// * `artifact` - 当前采样是否处于伪差期间，伪差期间不更新阈值和心率
+    /// * `pace_spike` - 上一个采样点是否为起搏脉冲
     /// * `ecg_state` - ECG处理状态引用
+    /// * `averaging` - 心率平均策略
     ///
     /// # 返回值
-    /// 返回元组：(心率, RR间隔)
-    fn process_ecg_data(ecg_value: i32, ecg_state: &Arc<Mutex<EcgProcessingState>>) -> (f64, f64) {
+    /// 返回元组：(平均心率, 瞬时心率, RR间隔)
+    fn process_ecg_data(
+        ecg_value: i32,
+        timestamp: u64,
+        received_at: u64,
+        artifact: bool,
+        pace_spike: bool,
+        ecg_state: &Arc<Mutex<EcgProcessingState>>,
+        averaging: &HeartRateAveraging,
+    ) -> (f64, f64, f64) {
         let mut state = ecg_state.lock().unwrap();
 
+        // 伪差期间暂停检测，避免伪差尖峰污染阈值或被误判为R波
+        if artifact {
+            state.ecg_points.clear();
+            state.peak_interval_num = 0;
+            state.skip_next_beat = true;
+            return (
+                state.last_averaged_heart_rate,
+                state.last_heart_rate,
+                state.last_rr_interval,
+            );
+        }
+
         // 添加到原始数据列表
         state.ecg_data_original_list.push(ecg_value);
-        let ecg_value_f64 = ecg_value as f64;
 
-        // 更新动态最大最小值（用于阈值计算）
-        if ecg_value_f64 > state.ecg_point_max_new {
-            state.ecg_point_max_new = ecg_value_f64;
+        // 距上一个起搏脉冲的采样点数
+        if let Some(samples) = state.samples_since_pace.as_mut() {
+            *samples += 1;
         }
-        if ecg_value_f64 < state.ecg_point_min_new {
-            state.ecg_point_min_new = ecg_value_f64;
+
+        // 窗口中间点被识别为起搏脉冲时，用两侧点插值替换，避免被当作R波
+        let window_full = state.ecg_points.len() == 3;
+        if pace_spike && window_full {
+            state.ecg_points[1] = (state.ecg_points[0] + ecg_value) / 2;
+            state.pace_spike_count += 1;
+            state.samples_since_pace = Some(1);
+        }
+
+        // 更新动态最大最小值（用于阈值计算）
+        // 使用窗口中间的已确认采样点，确保起搏脉冲不会抬高阈值
+        if window_full {
+            let confirmed_f64 = state.ecg_points[1] as f64;
+            if confirmed_f64 > state.ecg_point_max_new {
+                state.ecg_point_max_new = confirmed_f64;
+            }
+            if confirmed_f64 < state.ecg_point_min_new {
+                state.ecg_point_min_new = confirmed_f64;
+            }
         }
 
-        // 每300个数据点更新一次全局阈值
+        // 每隔设定的采样点数更新一次全局阈值
         state.counter += 1;
-        if state.counter >= 300 {
+        if state.counter >= state.params.threshold_update_interval {
             state.ecg_point_max = state.ecg_point_max_new;
             state.ecg_point_min = state.ecg_point_min_new;
             state.ecg_point_max_new = 0.0;
@@ -610,7 +4085,8 @@ impl DataProcessor {
             state.counter = 0;
         }
 
-        // 3点滑动窗口波峰检测
+        // 3点滑动窗口波峰检测，波峰为窗口中间点，即上一个采样
+        let peak_received_at = std::mem::replace(&mut state.last_received_at, received_at);
         if state.ecg_points.len() < 3 {
             state.ecg_points.push_back(ecg_value);
         } else {
@@ -619,7 +4095,7 @@ impl DataProcessor {
 
             if state.ecg_points.len() == 3 {
                 let points: Vec<i32> = state.ecg_points.iter().cloned().collect();
-                let peak_detection_threshold = 0.6; // 波峰检测阈值
+                let peak_detection_threshold = state.params.peak_threshold;
 
                 // 检测波峰：中间点大于两侧点
                 if points[0] < points[1] && points[1] > points[2] {
@@ -628,23 +4104,79 @@ impl DataProcessor {
 
                     // 检查波峰是否超过动态阈值
                     if (points[1] as f64 - state.ecg_point_min) > threshold_value {
-                        if state.peak_interval_num != 0 {
-                            // 计算心率（基于250Hz采样率）
-                            let mut heart_rate =
-                                60.0 / (1.0 / 250.0 * state.peak_interval_num as f64);
-
-                            // 心率限制（防止异常值）
-                            if heart_rate > 100.0 {
-                                heart_rate = 100.0;
+                        if state.skip_next_beat {
+                            // 伪差后的第一个R波只作为新的计时起点
+                            state.skip_next_beat = false;
+                            state.peak_interval_num = 0;
+                        } else if state.peak_interval_num != 0 {
+                            // 起搏脉冲后300ms内出现的R波视为起搏心搏
+                            state.total_beats += 1;
+                            let paced = state
+                                .samples_since_pace
+                                .is_some_and(|s| s <= PACED_BEAT_WINDOW_SAMPLES);
+                            if paced {
+                                state.paced_beats += 1;
                             }
 
-                            // 计算RR间隔
-                            let rr_interval = 60.0 / heart_rate;
+                            // 记录真实的RR间期（基于250Hz采样率，每个采样点4ms）
+                            let rr_interval_ms = state.peak_interval_num as f64 * 4.0;
+                            let classification = if paced {
+                                BeatClass::Paced
+                            } else if Self::is_premature(&state.rr_history, rr_interval_ms) {
+                                BeatClass::Premature
+                            } else {
+                                BeatClass::Normal
+                            };
+                            let amplitude = points[1] as f64 - state.ecg_point_min;
+                            state.beats.push_back(BeatRecord {
+                                timestamp,
+                                received_at: peak_received_at,
+                                rr_interval_ms,
+                                classification,
+                                amplitude,
+                            });
+                            if state.beats.len() > RR_HISTORY_CAPACITY {
+                                state.beats.pop_front();
+                            }
+                            state.rr_history.push_back(RrIntervalPoint {
+                                timestamp,
+                                rr_interval_ms,
+                            });
+                            if state.rr_history.len() > RR_HISTORY_CAPACITY {
+                                state.rr_history.pop_front();
+                            }
 
-                            // 更新状态
-                            state.last_heart_rate = heart_rate;
-                            state.last_rr_interval = rr_interval;
+                            // 计算心率（基于250Hz采样率）
+                            let heart_rate = 60.0 / (1.0 / 250.0 * state.peak_interval_num as f64);
                             state.peak_interval_num = 0;
+
+                            // 超出生理范围的心率（误检或漏检）丢弃，保持上一个有效心率
+                            let plausible = (HEART_RATE_PLAUSIBLE_RANGE.0
+                                ..=HEART_RATE_PLAUSIBLE_RANGE.1)
+                                .contains(&heart_rate);
+                            if plausible {
+                                // 计算RR间隔
+                                let rr_interval = 60.0 / heart_rate;
+
+                                // 更新状态
+                                state.last_heart_rate = heart_rate;
+                                state.last_rr_interval = rr_interval;
+
+                                // 记录心搏并更新平均心率
+                                state.recent_heart_rates.push_back((timestamp, heart_rate));
+                                if state.recent_heart_rates.len()
+                                    > state.params.heart_rate_history_size
+                                {
+                                    state.recent_heart_rates.pop_front();
+                                }
+                                state.last_averaged_heart_rate =
+                                    Self::average_heart_rate(&state, averaging);
+                            } else {
+                                hot_log!(
+                                    "[DataProcessor] 心率 {:.1} bpm 超出生理范围，已丢弃",
+                                    heart_rate
+                                );
+                            }
                         }
                     } else {
                         state.peak_interval_num += 1;
@@ -656,11 +4188,66 @@ impl DataProcessor {
         }
 
         // 管理原始数据缓冲区大小
-        if state.ecg_data_original_list.len() >= 250 {
+        if state.ecg_data_original_list.len() >= state.params.raw_buffer_size {
             state.ecg_data_original_list.clear();
         }
 
         // 返回最近一次检测到的有效心率和RR间期
-        (state.last_heart_rate, state.last_rr_interval)
+        (
+            state.last_averaged_heart_rate,
+            state.last_heart_rate,
+            state.last_rr_interval,
+        )
+    }
+
+    /// 按平均策略计算心率
+    ///
+    /// # 参数
+    /// * `state` - ECG处理状态，包含最近心搏的心率记录
+    /// * `averaging` - 心率平均策略
+    ///
+    /// # 返回值
+    /// 返回平均后的心率；没有可用心搏时返回最近一次的瞬时心率
+    fn average_heart_rate(state: &EcgProcessingState, averaging: &HeartRateAveraging) -> f64 {
+        let rates: Vec<f64> = match averaging {
+            HeartRateAveraging::Instant => return state.last_heart_rate,
+            HeartRateAveraging::MedianOfBeats(beats) => state
+                .recent_heart_rates
+                .iter()
+                .rev()
+                .take(*beats)
+                .map(|(_, hr)| *hr)
+                .collect(),
+            HeartRateAveraging::TimeWindowMean(window_ms) => {
+                let latest = match state.recent_heart_rates.back() {
+                    Some((ts, _)) => *ts,
+                    None => return state.last_heart_rate,
+                };
+                state
+                    .recent_heart_rates
+                    .iter()
+                    .filter(|(ts, _)| latest - ts <= *window_ms)
+                    .map(|(_, hr)| *hr)
+                    .collect()
+            }
+        };
+
+        if rates.is_empty() {
+            return state.last_heart_rate;
+        }
+
+        match averaging {
+            HeartRateAveraging::MedianOfBeats(_) => {
+                let mut sorted = rates;
+                sorted.sort_by(f64::total_cmp);
+                let mid = sorted.len() / 2;
+                if sorted.len() % 2 == 1 {
+                    sorted[mid]
+                } else {
+                    (sorted[mid - 1] + sorted[mid]) / 2.0
+                }
+            }
+            _ => rates.iter().sum::<f64>() / rates.len() as f64,
+        }
     }
 }
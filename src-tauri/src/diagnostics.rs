@@ -0,0 +1,135 @@
+//! 诊断输出模块
+//!
+//! 处理热路径上可能每个采样都触发的输出（解析失败、异常体温、LTTB压缩等）统一通过
+//! `hot_log!` 输出。Windows控制台输出很慢，逐采样打印会明显拖慢处理，因此：
+//! - 只有启用 `diagnostics` 特性编译时才会输出，否则宏展开为空
+//! - 运行时还需打开详细输出开关，默认关闭
+//! - 每个输出位置按配置的最小间隔限流，间隔内被抑制的条数附在下一条输出后
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 同一输出位置两次输出的最小间隔范围（毫秒）
+const INTERVAL_RANGE_MS: (u64, u64) = (100, 600_000);
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+static MIN_INTERVAL_MS: AtomicU64 = AtomicU64::new(5000);
+
+/// 诊断输出配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct DiagnosticsConfig {
+    /// 是否输出热路径上的详细诊断信息
+    pub verbose: bool,
+    /// 同一输出位置两次输出的最小间隔（毫秒）
+    pub min_interval_ms: u64,
+    /// 当前程序是否以 `diagnostics` 特性编译，只读，设置时忽略
+    #[serde(default)]
+    pub compiled_in: bool,
+}
+
+/// 设置诊断输出配置
+pub fn set_config(config: &DiagnosticsConfig) -> Result<(), String> {
+    if !(INTERVAL_RANGE_MS.0..=INTERVAL_RANGE_MS.1).contains(&config.min_interval_ms) {
+        return Err(format!(
+            "最小输出间隔必须在{}到{}毫秒之间",
+            INTERVAL_RANGE_MS.0, INTERVAL_RANGE_MS.1
+        ));
+    }
+    MIN_INTERVAL_MS.store(config.min_interval_ms, Ordering::Relaxed);
+    VERBOSE.store(config.verbose, Ordering::Relaxed);
+    if config.verbose && !cfg!(feature = "diagnostics") {
+        println!("[Diagnostics] 未启用diagnostics特性编译，详细输出不会生效");
+    }
+    Ok(())
+}
+
+/// 获取诊断输出配置
+pub fn get_config() -> DiagnosticsConfig {
+    DiagnosticsConfig {
+        verbose: VERBOSE.load(Ordering::Relaxed),
+        min_interval_ms: MIN_INTERVAL_MS.load(Ordering::Relaxed),
+        compiled_in: cfg!(feature = "diagnostics"),
+    }
+}
+
+/// 是否打开了详细输出
+#[cfg_attr(not(feature = "diagnostics"), allow(dead_code))]
+pub fn verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// 单个输出位置的限流器
+#[cfg_attr(not(feature = "diagnostics"), allow(dead_code))]
+pub struct RateLimiter {
+    /// 上次输出的时间戳（毫秒）
+    last_ms: AtomicU64,
+    /// 上次输出以来被抑制的条数
+    suppressed: AtomicU64,
+}
+
+#[cfg_attr(not(feature = "diagnostics"), allow(dead_code))]
+impl RateLimiter {
+    pub const fn new() -> Self {
+        Self {
+            last_ms: AtomicU64::new(0),
+            suppressed: AtomicU64::new(0),
+        }
+    }
+
+    /// 本次是否允许输出，允许时返回上次输出以来被抑制的条数
+    pub fn check(&self) -> Option<u64> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let last = self.last_ms.load(Ordering::Relaxed);
+        if now.saturating_sub(last) < MIN_INTERVAL_MS.load(Ordering::Relaxed)
+            || self
+                .last_ms
+                .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_err()
+        {
+            self.suppressed.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        Some(self.suppressed.swap(0, Ordering::Relaxed))
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 热路径诊断输出，参数同 `println!`
+///
+/// 未启用 `diagnostics` 特性时不输出，参数只做类型检查不求值
+macro_rules! hot_log {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "diagnostics")]
+        {
+            static LIMITER: $crate::diagnostics::RateLimiter =
+                $crate::diagnostics::RateLimiter::new();
+            if $crate::diagnostics::verbose() {
+                if let Some(suppressed) = LIMITER.check() {
+                    if suppressed > 0 {
+                        println!("{}（间隔内另有{}条被抑制）", format_args!($($arg)*), suppressed);
+                    } else {
+                        println!($($arg)*);
+                    }
+                }
+            }
+        }
+        #[cfg(not(feature = "diagnostics"))]
+        {
+            if false {
+                let _ = format_args!($($arg)*);
+            }
+        }
+    }};
+}
+
+pub(crate) use hot_log;
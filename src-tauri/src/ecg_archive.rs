@@ -0,0 +1,252 @@
+//! 原始心电存档模块
+//!
+//! 把每个原始ECG采样写入一个固定大小的内存映射环形缓冲文件，保留最近几个小时的
+//! 原始心电，用于按时间范围查看波形和事后导出心电图条带，不需要把全部采样保存在
+//! 内存中，也不需要事先开始录制。文件在应用重启后继续使用，容量不变时保留已有数据。
+//!
+//! 文件布局（小端序）：
+//! * 0..64字节为文件头：
+//!   * 0 `u32` 魔数 `0x47434556`（"VECG"），4 `u32` 版本号
+//!   * 8 `u64` 容量（采样数）
+//!   * 16 `u64` 已写入的采样总数，最新采样位于第 `(总数 - 1) % 容量` 个槽位
+//! * 之后为 `容量` 个槽位，每个槽位为 `u64` 时间戳（毫秒）加 `i32` 原始值
+
+use memmap2::{MmapMut, MmapOptions};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+/// 文件魔数 "VECG"
+const MAGIC: u32 = 0x4743_4556;
+
+/// 文件格式版本
+const VERSION: u32 = 1;
+
+/// 文件头长度（字节）
+const HEADER_LEN: usize = 64;
+
+/// 每个槽位的字节数
+const SLOT_LEN: usize = 12;
+
+/// 保留时长（小时），按250Hz计算容量，采样率更高时保留时长相应缩短
+const RETENTION_HOURS: u64 = 4;
+
+/// 存档容量（采样数）
+const CAPACITY: u64 = RETENTION_HOURS * 3600 * 250;
+
+/// 每写入多少个采样异步刷新一次映射，其余时间由系统页缓存决定写回时机
+const FLUSH_INTERVAL: u64 = 2500;
+
+/// 单次查询最多返回的点数
+const MAX_QUERY_POINTS: usize = 100_000;
+
+/// 一个原始ECG采样
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EcgSample {
+    pub timestamp: u64,
+    pub value: i32,
+}
+
+/// 存档状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct EcgArchiveStatus {
+    pub path: String,
+    /// 容量（采样数）
+    pub capacity: u64,
+    /// 存档中的采样数
+    pub samples: u64,
+    /// 最早采样的时间戳（毫秒），存档为空时为None
+    pub start_timestamp: Option<u64>,
+    /// 最新采样的时间戳（毫秒），存档为空时为None
+    pub end_timestamp: Option<u64>,
+}
+
+struct ArchiveInner {
+    map: MmapMut,
+    /// 已写入的采样总数
+    written: u64,
+}
+
+impl ArchiveInner {
+    fn read_u64(&self, offset: usize) -> u64 {
+        u64::from_le_bytes(self.map[offset..offset + 8].try_into().unwrap())
+    }
+
+    /// 存档中的采样数
+    fn len(&self) -> u64 {
+        self.written.min(CAPACITY)
+    }
+
+    /// 按时间顺序的第 `index` 个采样
+    fn get(&self, index: u64) -> EcgSample {
+        let slot = (self.written - self.len() + index) % CAPACITY;
+        let offset = HEADER_LEN + slot as usize * SLOT_LEN;
+        EcgSample {
+            timestamp: self.read_u64(offset),
+            value: i32::from_le_bytes(self.map[offset + 8..offset + 12].try_into().unwrap()),
+        }
+    }
+
+    /// 第一个时间戳不早于 `timestamp` 的采样序号
+    fn lower_bound(&self, timestamp: u64) -> u64 {
+        let (mut lo, mut hi) = (0, self.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.get(mid).timestamp < timestamp {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+}
+
+/// 原始心电存档，由处理线程写入、命令线程查询
+pub struct EcgArchive {
+    path: PathBuf,
+    inner: Mutex<ArchiveInner>,
+}
+
+impl EcgArchive {
+    /// 打开存档文件，文件不存在或格式、容量不符时重新创建
+    pub fn new(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("无法获取应用数据目录: {}", e))?
+            .join("vital-signs");
+        fs::create_dir_all(&data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+        let path = data_dir.join("ecg_archive.bin");
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|e| format!("打开心电存档失败: {}", e))?;
+        let file_len = (HEADER_LEN + CAPACITY as usize * SLOT_LEN) as u64;
+        let existing = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if existing != file_len {
+            file.set_len(file_len)
+                .map_err(|e| format!("分配心电存档空间失败: {}", e))?;
+        }
+        // 文件由本进程独占使用，映射期间不会被截断
+        let map = unsafe { MmapOptions::new().map_mut(&file) }
+            .map_err(|e| format!("映射心电存档失败: {}", e))?;
+
+        let mut inner = ArchiveInner { map, written: 0 };
+        let valid = existing == file_len
+            && inner.map[0..4] == MAGIC.to_le_bytes()
+            && inner.map[4..8] == VERSION.to_le_bytes()
+            && inner.read_u64(8) == CAPACITY;
+        if valid {
+            inner.written = inner.read_u64(16);
+        } else {
+            inner.map[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+            inner.map[4..8].copy_from_slice(&VERSION.to_le_bytes());
+            inner.map[8..16].copy_from_slice(&CAPACITY.to_le_bytes());
+            inner.map[16..24].copy_from_slice(&0u64.to_le_bytes());
+        }
+        println!(
+            "[EcgArchive] 心电存档 {}，容量={}，已有采样={}",
+            path.display(),
+            CAPACITY,
+            inner.len()
+        );
+        Ok(Self {
+            path,
+            inner: Mutex::new(inner),
+        })
+    }
+
+    /// 写入一个采样，时间戳早于最新采样时（时钟回拨或回放）丢弃该采样，保证存档时间有序且已有数据不丢失
+    pub fn push(&self, timestamp: u64, value: i32) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.len() > 0 && timestamp < inner.get(inner.len() - 1).timestamp {
+            return;
+        }
+        let slot = (inner.written % CAPACITY) as usize;
+        let offset = HEADER_LEN + slot * SLOT_LEN;
+        inner.map[offset..offset + 8].copy_from_slice(&timestamp.to_le_bytes());
+        inner.map[offset + 8..offset + 12].copy_from_slice(&value.to_le_bytes());
+        inner.written += 1;
+        let written = inner.written;
+        inner.map[16..24].copy_from_slice(&written.to_le_bytes());
+        if written.is_multiple_of(FLUSH_INTERVAL) {
+            let _ = inner.map.flush_async();
+        }
+    }
+
+    /// 查询时间范围内的原始采样
+    ///
+    /// # 参数
+    /// * `start` - 起始时间戳（毫秒，包含）
+    /// * `end` - 结束时间戳（毫秒，不包含）
+    /// * `max_points` - 最多返回的点数，采样更多时等间隔抽取
+    pub fn range(&self, start: u64, end: u64, max_points: Option<usize>) -> Vec<EcgSample> {
+        let inner = self.inner.lock().unwrap();
+        let first = inner.lower_bound(start);
+        let last = inner.lower_bound(end).max(first);
+        let count = (last - first) as usize;
+        let max_points = max_points
+            .unwrap_or(MAX_QUERY_POINTS)
+            .clamp(1, MAX_QUERY_POINTS);
+        let step = count.div_ceil(max_points).max(1) as u64;
+        (first..last)
+            .step_by(step as usize)
+            .map(|index| inner.get(index))
+            .collect()
+    }
+
+    /// 把时间范围内的原始采样导出为CSV心电图条带
+    ///
+    /// # 返回值
+    /// 返回导出的采样数
+    pub fn export_strip(&self, start: u64, end: u64, path: &str) -> Result<usize, String> {
+        if path.trim().is_empty() {
+            return Err("导出文件路径不能为空".to_string());
+        }
+        // 只在锁内复制采样，格式化和写文件在释放锁后进行，不阻塞实时写入
+        let samples: Vec<EcgSample> = {
+            let inner = self.inner.lock().unwrap();
+            let first = inner.lower_bound(start);
+            let last = inner.lower_bound(end).max(first);
+            (first..last).map(|index| inner.get(index)).collect()
+        };
+        if samples.is_empty() {
+            return Err("所选时间范围内没有心电数据".to_string());
+        }
+        let mut csv = String::from("timestamp,ecg_raw\n");
+        for sample in &samples {
+            let _ = writeln!(csv, "{},{}", sample.timestamp, sample.value);
+        }
+        fs::write(path, csv).map_err(|e| format!("写入导出文件失败: {}", e))?;
+        println!("[EcgArchive] 已导出 {} 个采样到 {}", samples.len(), path);
+        Ok(samples.len())
+    }
+
+    /// 获取存档状态
+    pub fn status(&self) -> EcgArchiveStatus {
+        let inner = self.inner.lock().unwrap();
+        let samples = inner.len();
+        EcgArchiveStatus {
+            path: self.path.display().to_string(),
+            capacity: CAPACITY,
+            samples,
+            start_timestamp: (samples > 0).then(|| inner.get(0).timestamp),
+            end_timestamp: (samples > 0).then(|| inner.get(samples - 1).timestamp),
+        }
+    }
+}
+
+impl Drop for EcgArchive {
+    fn drop(&mut self) {
+        let _ = self.inner.lock().unwrap().map.flush();
+    }
+}
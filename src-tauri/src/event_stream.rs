@@ -0,0 +1,135 @@
+//! 实时数据事件推送模块
+//!
+//! 前端除了轮询 `get_processed_data`，也可以开启事件推送：后台线程以固定的界面刷新率
+//! 把这段时间内处理完成的采样合并成一个 `vital-signs-batch` 事件推送，而不是每个采样
+//! 推送一次，避免250Hz以上的数据源占满webview的IPC通道。
+
+use crate::data_processor::DataProcessor;
+use crate::types::{ProcessedVitalSigns, ProcessingStatus};
+use serde::{Deserialize, Serialize};
+
+/// 推送频率范围（Hz）
+const RATE_RANGE_HZ: (u32, u32) = (1, 60);
+
+/// 每批采样数上限的范围
+const BATCH_SIZE_RANGE: (usize, usize) = (1, 250);
+
+/// 事件推送配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct EventStreamConfig {
+    /// 是否推送实时数据事件，关闭时前端需轮询获取数据
+    pub enabled: bool,
+    /// 推送频率（Hz），即每秒推送的批次数
+    pub rate_hz: u32,
+    /// 每批最多包含的采样数，超出时只推送最新的部分
+    pub max_batch_size: usize,
+}
+
+impl Default for EventStreamConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rate_hz: 20,
+            max_batch_size: 25,
+        }
+    }
+}
+
+/// 一批实时数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ProcessedBatch {
+    /// 批次序号，从1开始递增，前端可据此发现丢失的批次
+    pub sequence: u64,
+    /// 上一批以来处理完成的采样，按处理先后排列。LTTB压缩波形是整个显示窗口的快照，
+    /// 只保留在最后一个采样中，其余采样的 `ecg_lttb_compressed` 为空
+    pub samples: Vec<ProcessedVitalSigns>,
+    /// 因超出每批上限或已被处理队列覆盖而没有推送的采样数
+    pub skipped: u64,
+    /// 推送时的处理状态
+    pub processing_status: ProcessingStatus,
+    /// 时间戳（毫秒）
+    pub timestamp: u64,
+}
+
+/// 校验事件推送配置
+pub fn validate_config(config: &EventStreamConfig) -> Result<(), String> {
+    if !(RATE_RANGE_HZ.0..=RATE_RANGE_HZ.1).contains(&config.rate_hz) {
+        return Err(format!(
+            "推送频率必须在{}到{}Hz之间",
+            RATE_RANGE_HZ.0, RATE_RANGE_HZ.1
+        ));
+    }
+    if !(BATCH_SIZE_RANGE.0..=BATCH_SIZE_RANGE.1).contains(&config.max_batch_size) {
+        return Err(format!(
+            "每批采样数必须在{}到{}之间",
+            BATCH_SIZE_RANGE.0, BATCH_SIZE_RANGE.1
+        ));
+    }
+    Ok(())
+}
+
+/// 推送进度，记录已推送到的位置
+#[derive(Debug, Default)]
+pub struct EventStream {
+    /// 正在推送的处理器的会话ID，处理器重建后游标从头开始
+    session_id: String,
+    /// 处理后数据的读取游标，首次推送前为None，从当时的最新数据开始推送
+    cursor: Option<u64>,
+    /// 已推送的批次数
+    sequence: u64,
+}
+
+impl EventStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取出上一批以来的新采样组成一个批次，没有新采样时返回None
+    ///
+    /// # 参数
+    /// * `processor` - 当前的数据处理器
+    /// * `max_batch_size` - 每批最多包含的采样数
+    pub fn next_batch(
+        &mut self,
+        processor: &DataProcessor,
+        max_batch_size: usize,
+    ) -> Option<ProcessedBatch> {
+        if processor.session_id() != self.session_id {
+            self.session_id = processor.session_id().to_string();
+            // 推送期间处理器重建，新处理器的数据都还没有推送过
+            if self.cursor.is_some() {
+                self.cursor = Some(0);
+            }
+        }
+        let Some(cursor) = self.cursor else {
+            // 开启推送时跳过已有数据，前端需要历史数据时通过查询命令获取
+            self.cursor = Some(processor.processed_cursor());
+            return None;
+        };
+        let (mut samples, next_cursor, skipped) =
+            processor.get_processed_since(cursor, max_batch_size);
+        self.cursor = Some(next_cursor);
+        if samples.is_empty() {
+            return None;
+        }
+        let last = samples.len() - 1;
+        for sample in &mut samples[..last] {
+            sample.ecg_lttb_compressed = Vec::new();
+        }
+        self.sequence += 1;
+        Some(ProcessedBatch {
+            sequence: self.sequence,
+            samples,
+            skipped,
+            processing_status: processor.get_processing_status(),
+            timestamp: chrono::Utc::now().timestamp_millis().max(0) as u64,
+        })
+    }
+
+    /// 推送关闭或处理器停止时调用，重新开启后从最新数据开始推送
+    pub fn reset(&mut self) {
+        self.cursor = None;
+    }
+}
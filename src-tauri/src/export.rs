@@ -0,0 +1,171 @@
+//! 会话导出模块
+//!
+//! 把已保存的会话连同患者信息导出为JSON、CSV或openEHR COMPOSITION文件。去标识化模式下患者姓名
+//! 和会话记录的患者ID替换为稳定的假名ID，电话、地址、紧急联系人和档案时间被清除，
+//! 用于向研究合作方提供数据。
+
+use crate::openehr;
+use crate::patient_store::{ConsentScope, PatientInfo, PatientStore};
+use crate::session_store::StoredSession;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::fs;
+
+/// 导出文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// 包含患者信息、会话概要、趋势和事件标注的JSON文件
+    Json,
+    /// 每行一个趋势聚合段的CSV文件，文件头以 `#` 注释行记录患者和会话信息
+    Csv,
+    /// openEHR COMPOSITION（canonical JSON），包含心率、体温、血氧和血压观察
+    #[serde(rename = "openehr")]
+    OpenEhr,
+}
+
+/// 导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// 是否去除患者身份信息
+    #[serde(default)]
+    pub anonymize: bool,
+    /// 缺少知情同意时强制导出的管理员，强制导出会记入审计日志
+    #[serde(default)]
+    pub override_operator: Option<String>,
+    /// 强制导出时管理员输入的患者数据PIN，用于确认管理员身份
+    #[serde(default)]
+    pub override_pin: Option<String>,
+}
+
+/// JSON导出文件的内容
+#[derive(Debug, Serialize)]
+struct ExportDocument<'a> {
+    patient: &'a PatientInfo,
+    /// 是否已去标识化
+    anonymized: bool,
+    #[serde(flatten)]
+    session: &'a StoredSession,
+}
+
+/// 转义CSV单元格
+fn csv_cell(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_json(document: &ExportDocument) -> Result<String, String> {
+    serde_json::to_string_pretty(document).map_err(|e| format!("序列化导出数据失败: {}", e))
+}
+
+fn to_csv(document: &ExportDocument) -> String {
+    let patient = document.patient;
+    let metadata = &document.session.metadata;
+    let mut csv = String::new();
+    let _ = writeln!(csv, "# patient={}", csv_cell(&patient.name));
+    if !document.anonymized {
+        let _ = writeln!(csv, "# phone={}", csv_cell(&patient.phone));
+        let _ = writeln!(csv, "# address={}", csv_cell(&patient.address));
+    }
+    let _ = writeln!(
+        csv,
+        "# gender={},age={}",
+        csv_cell(&patient.gender),
+        patient.age
+    );
+    let _ = writeln!(csv, "# session={}", metadata.session_id);
+    csv.push_str("vital,start_timestamp,end_timestamp,min,max,mean,last,count\n");
+    for (vital, bins) in &document.session.trends {
+        for bin in bins {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{}",
+                vital,
+                bin.start_timestamp,
+                bin.end_timestamp,
+                bin.min,
+                bin.max,
+                bin.mean,
+                bin.last,
+                bin.count
+            );
+        }
+    }
+    csv
+}
+
+/// 找出导出会话所需、但会话所属患者今天没有有效知情同意的数据类型
+///
+/// 知情同意按会话记录的患者ID核对，与当前选中的患者无关；
+/// 会话未关联患者时所有数据类型都视为缺少同意。
+pub fn missing_consents(
+    session: &StoredSession,
+    patient_store: &PatientStore,
+) -> Result<Vec<ConsentScope>, String> {
+    let mut scopes = vec![ConsentScope::Demographics];
+    if !session.trends.is_empty() {
+        scopes.push(ConsentScope::Trends);
+    }
+    if !session.annotations.is_empty() {
+        scopes.push(ConsentScope::Annotations);
+    }
+    match session.metadata.patient_id.as_deref() {
+        Some(patient_id) => patient_store.missing_consents(patient_id, &scopes),
+        None => Ok(scopes),
+    }
+}
+
+/// 导出会话，调用前须用 `missing_consents` 核对知情同意
+///
+/// # 参数
+/// * `session` - 要导出的会话
+/// * `patient` - 会话所属患者的信息
+/// * `patient_store` - 患者存储，去标识化时用于生成假名ID
+/// * `options` - 导出选项
+/// * `path` - 导出文件路径
+pub fn export_session(
+    session: &StoredSession,
+    patient: &PatientInfo,
+    patient_store: &PatientStore,
+    options: &ExportOptions,
+    path: &str,
+) -> Result<(), String> {
+    if path.trim().is_empty() {
+        return Err("导出文件路径不能为空".to_string());
+    }
+
+    let (patient, session) = if options.anonymize {
+        let patient = patient_store.anonymize(patient)?;
+        let mut session = session.clone();
+        session.metadata.patient_id = Some(patient.id.clone());
+        (patient, Cow::Owned(session))
+    } else {
+        (patient.clone(), Cow::Borrowed(session))
+    };
+    let session = session.as_ref();
+    let document = ExportDocument {
+        patient: &patient,
+        anonymized: options.anonymize,
+        session,
+    };
+    let content = match options.format {
+        ExportFormat::Json => to_json(&document)?,
+        ExportFormat::Csv => to_csv(&document),
+        ExportFormat::OpenEhr => {
+            serde_json::to_string_pretty(&openehr::to_composition(session, &patient))
+                .map_err(|e| format!("序列化导出数据失败: {}", e))?
+        }
+    };
+    fs::write(path, content).map_err(|e| format!("写入导出文件失败: {}", e))?;
+    println!(
+        "[Export] 已导出会话 {} 到 {}，格式={:?}，去标识化={}",
+        session.metadata.session_id, path, options.format, options.anonymize
+    );
+    Ok(())
+}
@@ -0,0 +1,303 @@
+//! 记录文件数据源模块
+//!
+//! 跟踪其他采集程序持续写入的文本或CSV文件（类似 `tail -f`），把新追加的行
+//! 解析后放入数据队列，用于对接只能输出记录文件的旧式记录仪。CSV行按列映射
+//! 转换为串口协议的键值对，之后与串口数据使用同一个解析器。
+//!
+//! 文件被截断或轮转为更短的新文件时从新文件开头重新读取。
+
+use crate::channels::ChannelRegistry;
+use crate::framing::{deliver_frame, FrameParser, LineFrameParser};
+use crate::serial_manager::StreamCounters;
+use crate::types::{DataQueue, FileTailConfig, FileTailFormat, SerialErrorKind, SerialStatus};
+use crate::watchdog::Heartbeat;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// 每次从文件读取的最大字节数
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// CSV列映射必须包含的通道
+const REQUIRED_CHANNELS: [&str; 3] = ["ecg", "spo2", "temp"];
+
+/// 验证记录文件数据源配置
+pub fn validate_config(config: &FileTailConfig) -> Result<(), String> {
+    if config.path.trim().is_empty() {
+        return Err("文件路径不能为空".to_string());
+    }
+    if !(10..=5000).contains(&config.poll_interval_ms) {
+        return Err("检查间隔必须在10到5000毫秒之间".to_string());
+    }
+    if config.format == FileTailFormat::Csv {
+        if matches!(config.delimiter, '\n' | '\r' | '"') {
+            return Err("无效的CSV分隔符".to_string());
+        }
+        if let Some(missing) = REQUIRED_CHANNELS
+            .iter()
+            .find(|id| !config.columns.contains_key(**id))
+        {
+            return Err(format!("CSV列映射缺少通道: {}", missing));
+        }
+        if !config.has_header {
+            if let Some((id, column)) = config
+                .columns
+                .iter()
+                .find(|(_, column)| column.trim().parse::<usize>().is_err())
+            {
+                return Err(format!(
+                    "文件没有表头，通道 {} 必须使用列序号: {}",
+                    id, column
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 把CSV列映射解析为（协议键，列序号）
+///
+/// # 参数
+/// * `config` - 记录文件数据源配置
+/// * `header` - 表头行，文件没有表头时为None
+/// * `registry` - 通道注册表，用于查找通道的协议键
+fn resolve_columns(
+    config: &FileTailConfig,
+    header: Option<&str>,
+    registry: &ChannelRegistry,
+) -> Result<Vec<(String, usize)>, String> {
+    let header_cells: Vec<&str> = header
+        .map(|line| split_row(line, config.delimiter))
+        .unwrap_or_default();
+
+    config
+        .columns
+        .iter()
+        .map(|(id, column)| {
+            let descriptor = registry
+                .get(id)
+                .ok_or_else(|| format!("CSV列映射中的通道不存在: {}", id))?;
+            let column = column.trim();
+            let index = match header_cells.iter().position(|cell| *cell == column) {
+                Some(index) => index,
+                None => column
+                    .parse()
+                    .map_err(|_| format!("表头中没有列: {}", column))?,
+            };
+            Ok((descriptor.protocol_key.clone(), index))
+        })
+        .collect()
+}
+
+/// 拆分一行CSV，去掉单元格两侧的空白和引号
+fn split_row(line: &str, delimiter: char) -> Vec<&str> {
+    line.split(delimiter)
+        .map(|cell| cell.trim().trim_matches('"'))
+        .collect()
+}
+
+/// 把一行CSV转换为串口协议的键值对行
+fn csv_to_line(row: &str, delimiter: char, columns: &[(String, usize)]) -> String {
+    let cells = split_row(row, delimiter);
+    columns
+        .iter()
+        .filter_map(|(key, index)| cells.get(*index).map(|cell| format!("{}={}", key, cell)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// 读取文件的第一行，文件为空时返回None
+fn read_first_line(path: &str) -> Result<Option<String>, String> {
+    let file = File::open(path).map_err(|e| format!("无法打开文件 {}: {}", path, e))?;
+    let mut line = String::new();
+    let len = BufReader::new(file)
+        .read_line(&mut line)
+        .map_err(|e| format!("无法读取文件 {}: {}", path, e))?;
+    Ok((len > 0 && line.ends_with('\n')).then_some(line))
+}
+
+/// 打开文件并定位到开始读取的位置
+///
+/// # 参数
+/// * `path` - 文件路径
+/// * `skip_header` - 是否跳过第一行表头
+/// * `from_end` - 是否从文件末尾开始读取
+///
+/// # 返回值
+/// 返回文件和读取位置
+fn open_at(path: &str, skip_header: bool, from_end: bool) -> Result<(File, u64), String> {
+    let mut file = File::open(path).map_err(|e| format!("无法打开文件 {}: {}", path, e))?;
+    let position = if from_end {
+        file.metadata()
+            .map_err(|e| format!("无法读取文件信息 {}: {}", path, e))?
+            .len()
+    } else if skip_header {
+        read_first_line(path)?.map_or(0, |line| line.len() as u64)
+    } else {
+        0
+    };
+    file.seek(SeekFrom::Start(position))
+        .map_err(|e| format!("无法定位文件 {}: {}", path, e))?;
+    Ok((file, position))
+}
+
+pub struct FileTailReader {
+    config: FileTailConfig,
+    data_queue: DataQueue,
+    channel_registry: Arc<Mutex<ChannelRegistry>>,
+    counters: Arc<StreamCounters>,
+    status: Arc<Mutex<SerialStatus>>,
+    stop_flag: Arc<AtomicBool>,
+    heartbeat: Heartbeat,
+}
+
+impl FileTailReader {
+    pub fn new(
+        config: FileTailConfig,
+        data_queue: DataQueue,
+        channel_registry: Arc<Mutex<ChannelRegistry>>,
+        counters: Arc<StreamCounters>,
+        status: Arc<Mutex<SerialStatus>>,
+    ) -> Self {
+        println!(
+            "[FileTailReader] 初始化，文件={}，格式={:?}",
+            config.path, config.format
+        );
+        Self {
+            config,
+            data_queue,
+            channel_registry,
+            counters,
+            status,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            heartbeat: Heartbeat::new(),
+        }
+    }
+
+    /// 读取线程的心跳，每次检查文件时更新
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
+
+    pub fn start(&self) -> Result<(), String> {
+        validate_config(&self.config)?;
+        let csv = self.config.format == FileTailFormat::Csv;
+        let skip_header = csv && self.config.has_header;
+
+        // CSV列映射在启动时按表头解析一次
+        let columns = if csv {
+            let header = if skip_header {
+                Some(
+                    read_first_line(&self.config.path)?
+                        .ok_or_else(|| format!("文件 {} 中没有表头", self.config.path))?,
+                )
+            } else {
+                None
+            };
+            let registry = self.channel_registry.lock().unwrap();
+            resolve_columns(&self.config, header.as_deref(), &registry)?
+        } else {
+            Vec::new()
+        };
+
+        let (file, position) =
+            open_at(&self.config.path, skip_header, !self.config.from_beginning)?;
+        println!(
+            "[FileTailReader] 开始跟踪文件 {}，起始位置={}",
+            self.config.path, position
+        );
+
+        let config = self.config.clone();
+        let stop_flag = self.stop_flag.clone();
+        let data_queue = self.data_queue.clone();
+        let channel_registry = self.channel_registry.clone();
+        let counters = self.counters.clone();
+        let status = self.status.clone();
+        let heartbeat = self.heartbeat.clone();
+        heartbeat.beat();
+
+        thread::spawn(move || {
+            let mut file = file;
+            let mut position = position;
+            let poll_interval = Duration::from_millis(config.poll_interval_ms);
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            // 尚未以换行结束的内容
+            let mut pending: Vec<u8> = Vec::new();
+            // 上一帧的序号，用于统计丢帧
+            let mut last_sequence: Option<u64> = None;
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                heartbeat.beat();
+                let len = match file.read(&mut chunk) {
+                    Ok(len) => len,
+                    Err(e) => {
+                        eprintln!("[FileTailReader][线程] 读取文件失败: {}", e);
+                        counters.record_error(SerialErrorKind::Other, &e.to_string());
+                        thread::sleep(poll_interval);
+                        continue;
+                    }
+                };
+
+                if len == 0 {
+                    // 文件变短说明被截断或轮转，从新文件开头重新读取
+                    let truncated =
+                        fs::metadata(&config.path).is_ok_and(|meta| meta.len() < position);
+                    if truncated {
+                        match open_at(&config.path, skip_header, false) {
+                            Ok((new_file, new_position)) => {
+                                println!("[FileTailReader][线程] 文件已被截断或轮转，重新读取");
+                                file = new_file;
+                                position = new_position;
+                                pending.clear();
+                                last_sequence = None;
+                                counters.record_reconnect();
+                            }
+                            Err(e) => {
+                                eprintln!("[FileTailReader][线程] {}", e);
+                                *status.lock().unwrap() = SerialStatus::Error(e);
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+                    thread::sleep(poll_interval);
+                    continue;
+                }
+
+                position += len as u64;
+                counters.record_bytes(len);
+                pending.extend_from_slice(&chunk[..len]);
+                let Some(end) = pending.iter().rposition(|&b| b == b'\n') else {
+                    continue;
+                };
+                let complete: Vec<u8> = pending.drain(..=end).collect();
+
+                let registry = channel_registry.lock().unwrap();
+                for line in String::from_utf8_lossy(&complete).lines() {
+                    let line = line.trim_end_matches('\r');
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let line = if csv {
+                        csv_to_line(line, config.delimiter, &columns)
+                    } else {
+                        line.to_string()
+                    };
+                    let parsed = LineFrameParser.decode(line.as_bytes(), &registry);
+                    deliver_frame(parsed, &counters, &mut last_sequence, &data_queue);
+                }
+            }
+            println!("[FileTailReader][线程] 读取线程安全退出");
+        });
+
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        println!("[FileTailReader] 停止跟踪文件");
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,154 @@
+//! 设备固件版本模块
+//!
+//! 连接支持双向通信的数据源（串口、蓝牙SPP、远程串口）后发送固件版本查询命令，
+//! 设备以单独一行 `VER=<版本号>` 应答。兼容性表记录每种帧解析器已知可用的最低固件版本，
+//! 设备固件低于该版本时在串口状态中给出警告。兼容性表保存在数据目录的
+//! `firmware_compatibility.json` 中。
+
+use crate::types::FrameParserKind;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// 固件版本查询命令
+pub const QUERY_COMMAND: &str = "VER?\n";
+
+/// 固件版本应答的协议键
+pub const REPLY_KEY: &str = "VER";
+
+/// 一种帧解析器要求的最低固件版本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct FirmwareRequirement {
+    /// 帧解析器类型
+    pub parser: FrameParserKind,
+    /// 已知可用的最低固件版本，例如 `1.2.0`
+    pub min_version: String,
+}
+
+/// 当前设备的固件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct FirmwareInfo {
+    /// 设备应答的固件版本，尚未应答时为None
+    pub version: Option<String>,
+    /// 当前数据源使用的帧解析器
+    pub parser: FrameParserKind,
+    /// 兼容性表中该解析器要求的最低版本，表中没有该解析器时为None
+    pub min_version: Option<String>,
+    /// 固件版本是否兼容，版本未知或无法识别时为None
+    pub compatible: Option<bool>,
+    /// 固件版本过低时的警告信息
+    pub warning: Option<String>,
+}
+
+/// 默认的兼容性表
+pub fn default_compatibility() -> Vec<FirmwareRequirement> {
+    vec![FirmwareRequirement {
+        parser: FrameParserKind::Line,
+        min_version: "1.0.0".to_string(),
+    }]
+}
+
+/// 获取兼容性表文件的路径，数据目录不存在时创建
+pub fn compatibility_file(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("无法获取应用数据目录: {}", e))?;
+
+    let data_dir = app_data_dir.join("vital-signs");
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    }
+    Ok(data_dir.join("firmware_compatibility.json"))
+}
+
+/// 读取兼容性表，文件不存在时返回默认表
+pub fn load_compatibility(path: &Path) -> Result<Vec<FirmwareRequirement>, String> {
+    if !path.exists() {
+        return Ok(default_compatibility());
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("读取固件兼容性表失败: {}", e))?;
+    let table: Vec<FirmwareRequirement> =
+        serde_json::from_str(&content).map_err(|e| format!("解析固件兼容性表失败: {}", e))?;
+    validate_compatibility(&table)?;
+    Ok(table)
+}
+
+/// 保存兼容性表
+pub fn save_compatibility(path: &Path, table: &[FirmwareRequirement]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(table)
+        .map_err(|e| format!("序列化固件兼容性表失败: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("保存固件兼容性表失败: {}", e))
+}
+
+/// 验证兼容性表：版本号可以识别，且每种解析器最多一条
+pub fn validate_compatibility(table: &[FirmwareRequirement]) -> Result<(), String> {
+    for (i, requirement) in table.iter().enumerate() {
+        if parse_version(&requirement.min_version).is_none() {
+            return Err(format!("无法识别的固件版本号: {}", requirement.min_version));
+        }
+        if table[..i].iter().any(|r| r.parser == requirement.parser) {
+            return Err(format!(
+                "解析器 {:?} 重复设置了最低版本",
+                requirement.parser
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 把版本号解析为数字序列，例如 `v1.2.3` 解析为 `[1, 2, 3]`
+fn parse_version(version: &str) -> Option<Vec<u32>> {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// 按数字逐段比较两个版本号，缺少的段视为0
+fn compare_versions(a: &[u32], b: &[u32]) -> Ordering {
+    let len = a.len().max(b.len());
+    let segment = |v: &[u32], i: usize| v.get(i).copied().unwrap_or(0);
+    (0..len)
+        .map(|i| segment(a, i).cmp(&segment(b, i)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// 按兼容性表检查设备固件版本
+///
+/// # 参数
+/// * `version` - 设备应答的固件版本，尚未应答时为None
+/// * `parser` - 当前数据源使用的帧解析器
+/// * `table` - 兼容性表
+pub fn check(
+    version: Option<&str>,
+    parser: FrameParserKind,
+    table: &[FirmwareRequirement],
+) -> FirmwareInfo {
+    let min_version = table
+        .iter()
+        .find(|r| r.parser == parser)
+        .map(|r| r.min_version.clone());
+    let compatible = version
+        .and_then(parse_version)
+        .zip(min_version.as_deref().and_then(parse_version))
+        .map(|(actual, min)| compare_versions(&actual, &min).is_ge());
+    let warning = match (version, &min_version) {
+        (Some(version), Some(min_version)) if compatible == Some(false) => Some(format!(
+            "设备固件版本 {} 低于 {:?} 解析器已知可用的最低版本 {}",
+            version, parser, min_version
+        )),
+        _ => None,
+    };
+    FirmwareInfo {
+        version: version.map(str::to_string),
+        parser,
+        min_version,
+        compatible,
+        warning,
+    }
+}
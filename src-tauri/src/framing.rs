@@ -0,0 +1,359 @@
+//! 串口帧重组模块
+//!
+//! 读取线程按字节块读取串口数据并放入重组缓冲区，由可替换的帧解析器
+//! 在缓冲区中查找完整帧。跨越多次读取的半帧保留在缓冲区中等待后续字节，
+//! 无法识别的字节被丢弃，直到解析器重新找到帧起点（重新同步）。
+
+use crate::channels::ChannelRegistry;
+use crate::diagnostics::hot_log;
+use crate::firmware;
+use crate::serial_manager::StreamCounters;
+use crate::types::{CuffStatus, DataQueue, FrameParserKind, NibpMeasurement, VitalSigns};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// 重组缓冲区的最大长度，缓冲区超过该长度仍没有完整帧时整体丢弃（字节）
+const MAX_BUFFER_LEN: usize = 64 * 1024;
+
+/// 重组器最多保留的空闲帧缓冲区数量
+const FRAME_POOL_CAPACITY: usize = 8;
+
+/// 解析器在缓冲区开头的查找结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameScan {
+    /// 缓冲区开头是一个完整帧，长度为 `len` 字节
+    Complete { len: usize },
+    /// 缓冲区开头可能是帧的一部分，需要更多字节
+    Incomplete,
+    /// 缓冲区开头的 `skip` 字节不属于任何帧，应丢弃后重新查找
+    Garbage { skip: usize },
+}
+
+/// 帧解析器，负责帧的切分和解码
+///
+/// 新的设备协议（二进制帧、带校验的帧等）只需实现该特征，
+/// 并在 `SerialManager` 中替换默认的行协议解析器。
+pub trait FrameParser: Send + Sync {
+    /// 在缓冲区开头查找帧
+    ///
+    /// # 参数
+    /// * `buffer` - 重组缓冲区中尚未处理的字节
+    fn scan(&self, buffer: &[u8]) -> FrameScan;
+
+    /// 将一个完整帧解码为体征数据或设备应答
+    ///
+    /// # 参数
+    /// * `frame` - `scan` 返回的完整帧
+    /// * `registry` - 通道注册表，用于解析扩展通道
+    ///
+    /// # 返回值
+    /// 返回解码结果，帧内容无效或校验失败时返回错误
+    fn decode(&self, frame: &[u8], registry: &ChannelRegistry) -> Result<DecodedFrame, FrameError>;
+}
+
+/// 解码后的帧
+#[derive(Debug, Clone)]
+pub struct DecodedFrame {
+    /// 体征数据，设备应答帧中为None
+    pub vital_signs: Option<VitalSigns>,
+    /// 帧序号，协议不带序号时为None
+    pub sequence: Option<u64>,
+    /// 设备应答的固件版本，仅在固件版本应答帧中存在
+    pub firmware_version: Option<String>,
+}
+
+/// 帧解码错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// 帧格式错误或缺少必需字段
+    Malformed,
+    /// 校验和不匹配
+    ChecksumMismatch,
+}
+
+/// 按类型创建帧解析器
+pub fn create_parser(kind: FrameParserKind) -> Arc<dyn FrameParser> {
+    match kind {
+        FrameParserKind::Line => Arc::new(LineFrameParser),
+    }
+}
+
+/// 重组得到的单元
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssembledFrame {
+    /// 完整帧
+    Frame(Vec<u8>),
+    /// 被丢弃的无效字节数
+    Discarded(usize),
+}
+
+/// 帧重组器
+///
+/// 取出的帧使用完后通过 `recycle` 交还，下一帧复用其缓冲区，避免每帧分配内存。
+pub struct FrameAssembler {
+    parser: Arc<dyn FrameParser>,
+    buffer: Vec<u8>,
+    /// 已交还的空闲帧缓冲区
+    frame_pool: Vec<Vec<u8>>,
+}
+
+impl FrameAssembler {
+    pub fn new(parser: Arc<dyn FrameParser>) -> Self {
+        Self {
+            parser,
+            buffer: Vec::with_capacity(4096),
+            frame_pool: Vec::with_capacity(FRAME_POOL_CAPACITY),
+        }
+    }
+
+    /// 交还用完的帧缓冲区，供下一帧复用
+    pub fn recycle(&mut self, frame: Vec<u8>) {
+        if self.frame_pool.len() < FRAME_POOL_CAPACITY {
+            self.frame_pool.push(frame);
+        }
+    }
+
+    /// 追加新读取的字节
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// 丢弃缓冲区中的所有字节，用于重新连接后重新同步
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// 取出缓冲区中剩余的全部字节，用于数据报等自带边界的传输方式，
+    /// 数据报末尾没有帧结束标记时剩余字节即为最后一帧
+    pub fn take_remaining(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buffer)
+    }
+
+    /// 取出下一个完整帧或一段被丢弃的无效字节
+    ///
+    /// # 返回值
+    /// 缓冲区中没有完整帧时返回None，剩余字节保留到下次读取
+    pub fn next_frame(&mut self) -> Option<AssembledFrame> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        match self.parser.scan(&self.buffer) {
+            FrameScan::Complete { len } => {
+                let len = len.clamp(1, self.buffer.len());
+                let mut frame = self.frame_pool.pop().unwrap_or_default();
+                frame.clear();
+                frame.extend(self.buffer.drain(..len));
+                Some(AssembledFrame::Frame(frame))
+            }
+            FrameScan::Garbage { skip } => {
+                let skip = skip.clamp(1, self.buffer.len());
+                self.buffer.drain(..skip);
+                Some(AssembledFrame::Discarded(skip))
+            }
+            FrameScan::Incomplete if self.buffer.len() > MAX_BUFFER_LEN => {
+                let discarded = self.buffer.len();
+                self.buffer.clear();
+                Some(AssembledFrame::Discarded(discarded))
+            }
+            FrameScan::Incomplete => None,
+        }
+    }
+
+    /// 解码一个完整帧
+    pub fn decode(
+        &self,
+        frame: &[u8],
+        registry: &ChannelRegistry,
+    ) -> Result<DecodedFrame, FrameError> {
+        self.parser.decode(frame, registry)
+    }
+}
+
+/// 处理一个帧的解码结果：更新统计、检查帧序号并把采样放入数据队列
+///
+/// # 参数
+/// * `parsed` - 帧解码结果
+/// * `counters` - 当前连接的数据流计数器
+/// * `last_sequence` - 上一帧的序号，由调用方在一次连接期间保存
+/// * `data_queue` - 原始数据队列
+pub fn deliver_frame(
+    parsed: Result<DecodedFrame, FrameError>,
+    counters: &StreamCounters,
+    last_sequence: &mut Option<u64>,
+    data_queue: &DataQueue,
+) {
+    let decoded = match parsed {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            counters.record_rejected(e);
+            hot_log!("[Framing] 解析失败，无效数据帧: {:?}", e);
+            return;
+        }
+    };
+    let Some(mut vital_signs) = decoded.vital_signs else {
+        // 设备应答帧不含体征数据，不计入采样
+        if let Some(version) = decoded.firmware_version {
+            counters.record_firmware_version(version);
+        }
+        return;
+    };
+    counters.record_accepted(&vital_signs);
+
+    if let Some(sequence) = decoded.sequence {
+        if let Some(last) = *last_sequence {
+            if sequence != last.wrapping_add(1) {
+                // 序号回退（设备重启）时无法推算丢失的帧数
+                let missed = sequence.saturating_sub(last).saturating_sub(1);
+                counters.record_sequence_gap(missed);
+            }
+        }
+        *last_sequence = Some(sequence);
+    }
+
+    vital_signs.received_at = chrono::Utc::now().timestamp_millis() as u64;
+    data_queue.push(vital_signs);
+}
+
+/// 以换行结尾的文本行协议解析器，例如 `A=123456,B=980,C=368`
+///
+/// 可选的 `S=<序号>` 字段为帧序号；可选的 `*XX` 后缀为校验和，
+/// 取 `*` 之前所有字节的异或值，以两位十六进制表示（与NMEA相同）。
+/// 单独一行的 `VER=<版本号>` 为设备对固件版本查询的应答。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineFrameParser;
+
+impl LineFrameParser {
+    /// 单行的最大长度（字节），超过后视为无效数据
+    const MAX_LINE_LEN: usize = 1024;
+
+    /// 帧序号字段的协议键
+    pub const SEQUENCE_KEY: &'static str = "S";
+
+    /// 校验并去掉行尾的 `*XX` 校验和，没有校验和的行原样返回
+    fn verify_checksum(line: &str) -> Result<&str, FrameError> {
+        let Some((body, checksum)) = line.rsplit_once('*') else {
+            return Ok(line);
+        };
+        let expected =
+            u8::from_str_radix(checksum.trim(), 16).map_err(|_| FrameError::Malformed)?;
+        let actual = body.bytes().fold(0u8, |acc, b| acc ^ b);
+        if actual == expected {
+            Ok(body)
+        } else {
+            Err(FrameError::ChecksumMismatch)
+        }
+    }
+
+    /// 解析帧序号字段
+    fn parse_sequence(line: &str) -> Option<u64> {
+        line.split(',')
+            .filter_map(|part| part.split_once('='))
+            .find(|(key, _)| key.trim() == Self::SEQUENCE_KEY)
+            .and_then(|(_, value)| value.trim().parse().ok())
+    }
+
+    /// 解析固件版本应答行，不是应答行时返回None
+    fn parse_firmware_reply(line: &str) -> Option<String> {
+        let (key, version) = line.split_once('=')?;
+        let version = version.trim();
+        (key.trim() == firmware::REPLY_KEY && !version.is_empty()).then(|| version.to_string())
+    }
+
+    fn parse_data_line(line: &str, registry: &ChannelRegistry) -> Option<VitalSigns> {
+        let mut ecg = None;
+        let mut spo2 = None;
+        let mut temp = None;
+        let mut resp = None;
+        let mut glucose = None;
+        let mut co2 = None;
+        let mut blood_pressure = None;
+        let mut channels = BTreeMap::new();
+
+        for part in line.split(',') {
+            let Some((key, value)) = part.split_once('=') else {
+                continue;
+            };
+            if value.contains('=') {
+                continue;
+            }
+            let key = key.trim();
+            match key {
+                "A" => ecg = value.trim().parse().ok(),
+                "B" => spo2 = value.trim().parse().ok(),
+                "C" => temp = value.trim().parse().ok(),
+                "D" => resp = value.trim().parse().ok(),
+                "E" => blood_pressure = Self::parse_blood_pressure(value),
+                "F" => co2 = value.trim().parse().ok(),
+                "G" => glucose = value.trim().parse().ok(),
+                _ => {
+                    // 扩展通道：按注册表中的协议键映射
+                    if let Some(descriptor) = registry.find_extension_by_key(key) {
+                        if let Ok(value) = value.trim().parse::<f64>() {
+                            channels.insert(descriptor.id.clone(), value);
+                        }
+                    }
+                }
+            }
+        }
+
+        // 心电为必需通道，其余通道缺失时保持为None，与真实的0区分
+        Some(VitalSigns {
+            ecg: ecg?,
+            spo2,
+            temp,
+            blood_pressure, // 血压仅在无创血压测量完成时出现
+            resp,
+            co2,
+            glucose, // 血糖为间歇性测量，仅在有新结果时出现
+            channels,
+            received_at: 0,
+        })
+    }
+
+    /// 解析无创血压字段，格式为 `收缩压/舒张压[/袖带状态码]`，例如 `E=120/80/0`，
+    /// 省略状态码时视为测量成功
+    fn parse_blood_pressure(value: &str) -> Option<NibpMeasurement> {
+        let mut parts = value.trim().split('/');
+        let systolic = parts.next()?.trim().parse().ok()?;
+        let diastolic = parts.next()?.trim().parse().ok()?;
+        let cuff_status = match parts.next() {
+            Some(code) => CuffStatus::from_code(code.trim().parse().ok()?),
+            None => CuffStatus::Ok,
+        };
+        Some(NibpMeasurement {
+            systolic,
+            diastolic,
+            cuff_status,
+        })
+    }
+}
+
+impl FrameParser for LineFrameParser {
+    fn scan(&self, buffer: &[u8]) -> FrameScan {
+        match buffer.iter().position(|&b| b == b'\n') {
+            Some(pos) if pos < Self::MAX_LINE_LEN => FrameScan::Complete { len: pos + 1 },
+            // 过长的行丢弃到换行符为止，从下一行重新同步
+            Some(pos) => FrameScan::Garbage { skip: pos + 1 },
+            None if buffer.len() >= Self::MAX_LINE_LEN => FrameScan::Garbage { skip: buffer.len() },
+            None => FrameScan::Incomplete,
+        }
+    }
+
+    fn decode(&self, frame: &[u8], registry: &ChannelRegistry) -> Result<DecodedFrame, FrameError> {
+        let line = std::str::from_utf8(frame).map_err(|_| FrameError::Malformed)?;
+        let line = Self::verify_checksum(line.trim_end_matches(['\r', '\n']))?;
+        if let Some(version) = Self::parse_firmware_reply(line) {
+            return Ok(DecodedFrame {
+                vital_signs: None,
+                sequence: None,
+                firmware_version: Some(version),
+            });
+        }
+        let vital_signs = Self::parse_data_line(line, registry).ok_or(FrameError::Malformed)?;
+        Ok(DecodedFrame {
+            vital_signs: Some(vital_signs),
+            sequence: Self::parse_sequence(line),
+            firmware_version: None,
+        })
+    }
+}
@@ -0,0 +1,269 @@
+//! USB HID数据源模块
+//!
+//! 部分传感器适配器枚举为HID设备而不是CDC串口，串口数据承载在厂商自定义的
+//! 输入报告或特征报告中。读取线程读取报告，从中取出数据字节交给帧解析器，
+//! 之后的处理与串口数据相同。
+
+use crate::channels::ChannelRegistry;
+use crate::framing::{self, deliver_frame, AssembledFrame, FrameAssembler};
+use crate::serial_manager::StreamCounters;
+use crate::types::{
+    DataQueue, HidConfig, HidDeviceInfo, HidReportMode, SerialErrorKind, SerialResilienceConfig,
+    SerialStatus,
+};
+use crate::watchdog::Heartbeat;
+use hidapi::{HidApi, HidDevice};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// 报告缓冲区长度，全速USB的报告最长64字节，另加1字节报告ID
+const REPORT_BUFFER_LEN: usize = 65;
+
+/// 读取输入报告的超时（毫秒），用于定期检查停止信号
+const INPUT_REPORT_TIMEOUT_MS: i32 = 500;
+
+/// 验证HID数据源配置
+pub fn validate_config(config: &HidConfig) -> Result<(), String> {
+    if config.vendor_id == 0 && config.product_id == 0 {
+        return Err("必须指定HID设备的厂商ID和产品ID".to_string());
+    }
+    if config.report_mode == HidReportMode::Feature
+        && !(1..=1000).contains(&config.poll_interval_ms)
+    {
+        return Err("特征报告读取间隔必须在1到1000毫秒之间".to_string());
+    }
+    Ok(())
+}
+
+/// 列出已连接的HID设备
+pub fn list_devices() -> Result<Vec<HidDeviceInfo>, String> {
+    let api = HidApi::new().map_err(|e| format!("无法初始化HID: {}", e))?;
+    let mut devices: Vec<HidDeviceInfo> = api
+        .device_list()
+        .map(|info| HidDeviceInfo {
+            vendor_id: info.vendor_id(),
+            product_id: info.product_id(),
+            product: info.product_string().map(str::to_string),
+            manufacturer: info.manufacturer_string().map(str::to_string),
+            serial_number: info.serial_number().map(str::to_string),
+        })
+        .collect();
+    // 同一设备的多个接口会重复出现
+    devices.dedup_by(|a, b| {
+        a.vendor_id == b.vendor_id
+            && a.product_id == b.product_id
+            && a.serial_number == b.serial_number
+    });
+    Ok(devices)
+}
+
+/// 打开配置指定的HID设备
+fn open_device(config: &HidConfig) -> Result<HidDevice, String> {
+    let api = HidApi::new().map_err(|e| format!("无法初始化HID: {}", e))?;
+    let device = match &config.serial_number {
+        Some(serial_number) => api.open_serial(config.vendor_id, config.product_id, serial_number),
+        None => api.open(config.vendor_id, config.product_id),
+    };
+    device.map_err(|e| {
+        format!(
+            "无法打开HID设备 {:04x}:{:04x}: {}",
+            config.vendor_id, config.product_id, e
+        )
+    })
+}
+
+/// 从报告中取出数据字节
+///
+/// # 参数
+/// * `report` - 读取到的报告，编号报告和特征报告的第一个字节为报告ID
+/// * `config` - HID数据源配置
+fn report_payload<'a>(report: &'a [u8], config: &HidConfig) -> &'a [u8] {
+    let has_report_id = config.report_mode == HidReportMode::Feature || config.report_id.is_some();
+    let data = if has_report_id {
+        report.get(1..).unwrap_or_default()
+    } else {
+        report
+    };
+
+    if config.length_prefixed {
+        let Some((&len, rest)) = data.split_first() else {
+            return &[];
+        };
+        &rest[..(len as usize).min(rest.len())]
+    } else {
+        let end = data.iter().rposition(|&b| b != 0).map_or(0, |pos| pos + 1);
+        &data[..end]
+    }
+}
+
+/// 读取一个报告
+///
+/// # 返回值
+/// 返回报告长度，输入报告超时时返回0
+fn read_report(device: &HidDevice, config: &HidConfig, buffer: &mut [u8]) -> Result<usize, String> {
+    match config.report_mode {
+        HidReportMode::Input => device
+            .read_timeout(buffer, INPUT_REPORT_TIMEOUT_MS)
+            .map_err(|e| e.to_string()),
+        HidReportMode::Feature => {
+            thread::sleep(Duration::from_millis(config.poll_interval_ms));
+            buffer[0] = config.report_id.unwrap_or(0);
+            device.get_feature_report(buffer).map_err(|e| e.to_string())
+        }
+    }
+}
+
+pub struct HidReader {
+    config: HidConfig,
+    data_queue: DataQueue,
+    channel_registry: Arc<Mutex<ChannelRegistry>>,
+    counters: Arc<StreamCounters>,
+    status: Arc<Mutex<SerialStatus>>,
+    resilience: SerialResilienceConfig,
+    stop_flag: Arc<AtomicBool>,
+    heartbeat: Heartbeat,
+}
+
+impl HidReader {
+    pub fn new(
+        config: HidConfig,
+        data_queue: DataQueue,
+        channel_registry: Arc<Mutex<ChannelRegistry>>,
+        counters: Arc<StreamCounters>,
+        status: Arc<Mutex<SerialStatus>>,
+        resilience: SerialResilienceConfig,
+    ) -> Self {
+        println!(
+            "[HidReader] 初始化，设备={:04x}:{:04x}，报告类型={:?}",
+            config.vendor_id, config.product_id, config.report_mode
+        );
+        Self {
+            config,
+            data_queue,
+            channel_registry,
+            counters,
+            status,
+            resilience,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            heartbeat: Heartbeat::new(),
+        }
+    }
+
+    /// 读取线程的心跳，每轮读取（包括超时）更新一次
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
+
+    /// 按错误恢复策略重新打开设备
+    ///
+    /// # 返回值
+    /// 重新打开成功时返回设备，停止信号到达时返回None
+    fn reconnect(
+        config: &HidConfig,
+        resilience: &SerialResilienceConfig,
+        stop_flag: &AtomicBool,
+    ) -> Result<Option<HidDevice>, String> {
+        for attempt in 1..=resilience.max_reconnect_attempts {
+            thread::sleep(Duration::from_millis(resilience.reconnect_interval_ms));
+            if stop_flag.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            println!(
+                "[HidReader][线程] 尝试重新打开 {:04x}:{:04x} (第{}次)",
+                config.vendor_id, config.product_id, attempt
+            );
+            match open_device(config) {
+                Ok(device) => return Ok(Some(device)),
+                Err(e) => eprintln!("[HidReader][线程] 重新打开失败: {}", e),
+            }
+        }
+        Err(format!(
+            "HID设备 {:04x}:{:04x} 已断开，{}次重新打开均失败",
+            config.vendor_id, config.product_id, resilience.max_reconnect_attempts
+        ))
+    }
+
+    pub fn start(&self) -> Result<(), String> {
+        validate_config(&self.config)?;
+        let device = open_device(&self.config)?;
+        println!(
+            "[HidReader] 已打开HID设备 {:04x}:{:04x}",
+            self.config.vendor_id, self.config.product_id
+        );
+
+        let config = self.config.clone();
+        let resilience = self.resilience.clone();
+        let mut assembler = FrameAssembler::new(framing::create_parser(config.parser));
+        let stop_flag = self.stop_flag.clone();
+        let data_queue = self.data_queue.clone();
+        let channel_registry = self.channel_registry.clone();
+        let counters = self.counters.clone();
+        let status = self.status.clone();
+        let heartbeat = self.heartbeat.clone();
+        heartbeat.beat();
+
+        thread::spawn(move || {
+            let mut device = device;
+            let mut report = [0u8; REPORT_BUFFER_LEN];
+            // 上一帧的序号，用于统计丢帧
+            let mut last_sequence: Option<u64> = None;
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                heartbeat.beat();
+                let len = match read_report(&device, &config, &mut report) {
+                    Ok(len) => len,
+                    Err(message) => {
+                        eprintln!("[HidReader][线程] 读取HID报告失败: {}", message);
+                        counters.record_error(SerialErrorKind::DeviceGone, &message);
+                        match Self::reconnect(&config, &resilience, &stop_flag) {
+                            Ok(Some(new_device)) => {
+                                device = new_device;
+                                counters.record_reconnect();
+                                // 重新打开后从帧边界重新同步
+                                assembler.clear();
+                                last_sequence = None;
+                                println!("[HidReader][线程] HID设备重新打开成功");
+                            }
+                            Ok(None) => break,
+                            Err(e) => {
+                                eprintln!("[HidReader][线程] {}", e);
+                                *status.lock().unwrap() = SerialStatus::Error(e);
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+                };
+
+                let payload = report_payload(&report[..len], &config);
+                if payload.is_empty() {
+                    continue;
+                }
+                counters.record_bytes(payload.len());
+                assembler.push(payload);
+                while let Some(unit) = assembler.next_frame() {
+                    match unit {
+                        AssembledFrame::Frame(frame) => {
+                            let registry = channel_registry.lock().unwrap();
+                            let parsed = assembler.decode(&frame, &registry);
+                            drop(registry);
+                            assembler.recycle(frame);
+                            deliver_frame(parsed, &counters, &mut last_sequence, &data_queue);
+                        }
+                        AssembledFrame::Discarded(len) => counters.record_resync(len),
+                    }
+                }
+            }
+            println!("[HidReader][线程] 读取线程安全退出");
+        });
+
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        println!("[HidReader] 停止信号已发出");
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,693 @@
+//! HL7结果发送模块
+//!
+//! 把体征打包为HL7 v2.5.1 ORU^R01结果消息，通过MLLP（TCP）发送到医院接口引擎。
+//! 支持两种批次规则：
+//!
+//! * 逐次测量：每隔固定时间发送一次当前体征，血糖等间歇性测量出结果时立即单独发送
+//! * 周期汇总：每个周期发送一条消息，包含周期内各项体征的平均值
+//!
+//! 每条消息先写入本地发送队列目录再发送，收到AA/CA确认后删除；连接失败、确认超时
+//! 或收到否定确认（AE/AR）时稍后重发，超过重试次数的消息移入 `failed` 子目录，
+//! 需要人工处理。队列超过容量时删除最早的消息。
+
+use crate::types::ProcessedVitalSigns;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// MLLP帧起始字节
+const MLLP_START: u8 = 0x0B;
+/// MLLP帧结束字节（后跟回车）
+const MLLP_END: u8 = 0x1C;
+/// 确认消息的最大长度，超过时视为接口异常
+const MAX_ACK_LEN: usize = 64 * 1024;
+/// 连接超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// 第一次重发前的等待时长，之后每次失败翻倍
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(5);
+/// 重发等待时长上限
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(300);
+
+/// 批次规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hl7BatchMode {
+    /// 每隔 `interval_secs` 发送一次当前体征，间歇性测量立即发送
+    PerMeasurement,
+    /// 每 `interval_secs` 发送一次周期内的平均值
+    PeriodicSummary,
+}
+
+/// HL7发送配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct Hl7SenderConfig {
+    /// 接口引擎主机名或IP
+    pub host: String,
+    /// 接口引擎MLLP端口
+    pub port: u16,
+    /// MSH-4 发送机构
+    #[serde(default)]
+    pub sending_facility: String,
+    /// MSH-5 接收应用
+    #[serde(default)]
+    pub receiving_application: String,
+    /// MSH-6 接收机构
+    #[serde(default)]
+    pub receiving_facility: String,
+    pub batch_mode: Hl7BatchMode,
+    /// 发送间隔或汇总周期（秒）
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// PID-3 患者ID，省略时使用当前患者
+    #[serde(default)]
+    pub patient_id: Option<String>,
+    /// PID-5 患者姓名，省略时使用当前患者
+    #[serde(default)]
+    pub patient_name: Option<String>,
+    /// 等待确认的超时（秒）
+    #[serde(default = "default_ack_timeout_secs")]
+    pub ack_timeout_secs: u64,
+    /// 每条消息的最大重发次数
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// 发送队列最多保留的消息数
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+fn default_interval_secs() -> u64 {
+    60
+}
+
+fn default_ack_timeout_secs() -> u64 {
+    10
+}
+
+fn default_max_retries() -> u32 {
+    5
+}
+
+fn default_queue_capacity() -> usize {
+    10_000
+}
+
+/// 验证HL7发送配置
+pub fn validate_config(config: &Hl7SenderConfig) -> Result<(), String> {
+    if config.host.trim().is_empty() {
+        return Err("接口引擎地址不能为空".to_string());
+    }
+    if config.port == 0 {
+        return Err("接口引擎端口无效".to_string());
+    }
+    if !(5..=3600).contains(&config.interval_secs) {
+        return Err("发送间隔必须在5到3600秒之间".to_string());
+    }
+    if !(1..=120).contains(&config.ack_timeout_secs) {
+        return Err("确认超时必须在1到120秒之间".to_string());
+    }
+    if config.max_retries > 100 {
+        return Err("重发次数不能超过100".to_string());
+    }
+    if !(10..=1_000_000).contains(&config.queue_capacity) {
+        return Err("发送队列容量必须在10到1000000条之间".to_string());
+    }
+    Ok(())
+}
+
+/// 与接口引擎的连接状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hl7ConnectionState {
+    #[default]
+    Disconnected,
+    Connected,
+    /// 上次发送失败，等待重发
+    Retrying,
+}
+
+/// 接口引擎返回的确认
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct Hl7Ack {
+    /// 被确认消息的控制ID（MSA-2）
+    pub control_id: String,
+    /// 确认代码（MSA-1）：AA/CA为接受，AE/CE为错误，AR/CR为拒绝
+    pub code: String,
+    /// 确认说明（MSA-3或ERR段）
+    pub text: String,
+    /// 收到确认的时间（RFC 3339）
+    pub received_at: String,
+}
+
+impl Hl7Ack {
+    fn accepted(&self) -> bool {
+        matches!(self.code.as_str(), "AA" | "CA")
+    }
+}
+
+/// HL7接口统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct Hl7Stats {
+    pub connection: Hl7ConnectionState,
+    /// 已被接受的消息数
+    pub sent: u64,
+    /// 队列中等待发送的消息数
+    pub pending: usize,
+    /// 超过重试次数移入 `failed` 目录的消息数
+    pub failed: u64,
+    /// 队列溢出删除的消息数
+    pub dropped: u64,
+    /// 最近一次肯定确认
+    pub last_ack: Option<Hl7Ack>,
+    /// 最近一次否定确认
+    pub last_nak: Option<Hl7Ack>,
+    /// 最近一次连接或发送失败的原因
+    pub last_error: Option<String>,
+}
+
+/// HL7接口状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct Hl7InterfaceStatus {
+    pub config: Hl7SenderConfig,
+    #[serde(flatten)]
+    pub stats: Hl7Stats,
+}
+
+/// ORU消息中的一项观察
+struct Observation {
+    /// LOINC代码
+    code: &'static str,
+    name: &'static str,
+    /// UCUM单位
+    units: &'static str,
+    value: f64,
+}
+
+/// 各项连续体征的LOINC代码、名称和单位
+const VITAL_CODES: [(&str, &str, &str, &str); 5] = [
+    ("heart_rate", "8867-4", "Heart rate", "/min"),
+    (
+        "spo2",
+        "59408-5",
+        "Oxygen saturation in Arterial blood by Pulse oximetry",
+        "%",
+    ),
+    ("temperature", "8310-5", "Body temperature", "Cel"),
+    ("respiration_rate", "9279-1", "Respiratory rate", "/min"),
+    (
+        "etco2",
+        "19889-5",
+        "Carbon dioxide [Partial pressure] in Exhaled gas --at end expiration",
+        "mm[Hg]",
+    ),
+];
+
+/// 转义HL7字段中的分隔符
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\E\\"),
+            '|' => out.push_str("\\F\\"),
+            '^' => out.push_str("\\S\\"),
+            '~' => out.push_str("\\R\\"),
+            '&' => out.push_str("\\T\\"),
+            '\r' | '\n' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// HL7时间格式（本地时间，带时区）
+fn hl7_time(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp as i64)
+        .unwrap_or_default()
+        .with_timezone(&chrono::Local)
+        .format("%Y%m%d%H%M%S%z")
+        .to_string()
+}
+
+/// 生成ORU^R01消息，段之间以回车分隔
+///
+/// # 参数
+/// * `config` - 发送配置（MSH和PID字段）
+/// * `control_id` - 消息控制ID
+/// * `start` - 观察起始时间戳（毫秒）
+/// * `end` - 观察结束时间戳（毫秒），单次测量时与起始相同
+/// * `observations` - 观察结果
+fn build_oru(
+    config: &Hl7SenderConfig,
+    control_id: &str,
+    start: u64,
+    end: u64,
+    observations: &[Observation],
+) -> String {
+    let mut segments = vec![
+        format!(
+            "MSH|^~\\&|VitalSigns|{}|{}|{}|{}||ORU^R01^ORU_R01|{}|P|2.5.1",
+            escape(&config.sending_facility),
+            escape(&config.receiving_application),
+            escape(&config.receiving_facility),
+            hl7_time(chrono::Utc::now().timestamp_millis().max(0) as u64),
+            control_id
+        ),
+        format!(
+            "PID|1||{}||{}",
+            escape(config.patient_id.as_deref().unwrap_or_default()),
+            escape(config.patient_name.as_deref().unwrap_or_default())
+        ),
+        format!(
+            "OBR|1|||VITALS^Vital signs^L|||{}|{}",
+            hl7_time(start),
+            if end > start {
+                hl7_time(end)
+            } else {
+                String::new()
+            }
+        ),
+    ];
+    for (i, obs) in observations.iter().enumerate() {
+        segments.push(format!(
+            "OBX|{}|NM|{}^{}^LN||{}|{}^{}^UCUM|||||F|||{}",
+            i + 1,
+            obs.code,
+            obs.name,
+            (obs.value * 10.0).round() / 10.0,
+            obs.units,
+            obs.units,
+            hl7_time(end)
+        ));
+    }
+    let mut message = segments.join("\r");
+    message.push('\r');
+    message
+}
+
+/// 解析确认消息的MSA段（以及可选的ERR段）
+fn parse_ack(message: &str) -> Option<Hl7Ack> {
+    let segments: Vec<&str> = message.split(['\r', '\n']).collect();
+    let msa: Vec<&str> = segments
+        .iter()
+        .find(|segment| segment.starts_with("MSA|"))?
+        .split('|')
+        .collect();
+    let err_text = segments
+        .iter()
+        .find(|segment| segment.starts_with("ERR|"))
+        .and_then(|segment| segment.split('|').nth(8))
+        .unwrap_or_default();
+    let text = msa.get(3).copied().filter(|text| !text.is_empty());
+    Some(Hl7Ack {
+        code: msa.get(1).copied().unwrap_or_default().to_string(),
+        control_id: msa.get(2).copied().unwrap_or_default().to_string(),
+        text: text.unwrap_or(err_text).to_string(),
+        received_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// 本地发送队列，每条消息一个 `<控制ID>.hl7` 文件，控制ID按生成时间排序
+struct Hl7Queue {
+    dir: PathBuf,
+    capacity: usize,
+}
+
+impl Hl7Queue {
+    fn new(dir: PathBuf, capacity: usize) -> Result<Self, String> {
+        fs::create_dir_all(dir.join("failed"))
+            .map_err(|e| format!("创建HL7发送队列失败: {}", e))?;
+        Ok(Self { dir, capacity })
+    }
+
+    /// 按时间顺序列出待发送的消息文件
+    fn pending(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "hl7"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+
+    /// 写入一条消息，超过容量时删除最早的消息
+    ///
+    /// # 返回值
+    /// 返回删除的消息数
+    fn store(&self, control_id: &str, message: &str) -> Result<u64, String> {
+        let path = self.dir.join(format!("{}.hl7", control_id));
+        fs::write(&path, message).map_err(|e| format!("写入HL7发送队列失败: {}", e))?;
+        let pending = self.pending();
+        let excess = pending.len().saturating_sub(self.capacity);
+        for old in &pending[..excess] {
+            let _ = fs::remove_file(old);
+        }
+        Ok(excess as u64)
+    }
+
+    /// 把消息移入 `failed` 子目录
+    fn fail(&self, path: &Path) {
+        if let Some(name) = path.file_name() {
+            let _ = fs::rename(path, self.dir.join("failed").join(name));
+        }
+    }
+}
+
+/// 周期汇总中单项体征的累计值
+#[derive(Debug, Clone, Copy, Default)]
+struct Accumulator {
+    sum: f64,
+    count: u64,
+}
+
+/// HL7结果发送，由处理线程在每个采样后调用 `push`
+///
+/// 消息在处理线程中生成后交给发送线程写入队列；发送被丢弃时通道关闭，
+/// 发送线程随之退出，未发送的消息保留在队列中，下次启动时补发。
+pub struct Hl7Sender {
+    config: Hl7SenderConfig,
+    sender: Sender<(String, String)>,
+    stats: Arc<Mutex<Hl7Stats>>,
+    period_start: Option<u64>,
+    totals: BTreeMap<&'static str, Accumulator>,
+    sequence: u32,
+}
+
+impl Hl7Sender {
+    /// 启动发送线程
+    ///
+    /// # 参数
+    /// * `config` - 发送配置
+    /// * `queue_dir` - 本地发送队列目录
+    pub fn new(config: Hl7SenderConfig, queue_dir: PathBuf) -> Result<Self, String> {
+        validate_config(&config)?;
+        let queue = Hl7Queue::new(queue_dir, config.queue_capacity)?;
+        let stats = Arc::new(Mutex::new(Hl7Stats {
+            pending: queue.pending().len(),
+            ..Hl7Stats::default()
+        }));
+        let (sender, receiver) = mpsc::channel();
+        let thread_config = config.clone();
+        let thread_stats = stats.clone();
+        thread::spawn(move || run_sender(thread_config, queue, receiver, thread_stats));
+        println!(
+            "[HL7] 发送到 {}:{}，批次规则={:?}，间隔={}秒",
+            config.host, config.port, config.batch_mode, config.interval_secs
+        );
+        Ok(Self {
+            config,
+            sender,
+            stats,
+            period_start: None,
+            totals: BTreeMap::new(),
+            sequence: 0,
+        })
+    }
+
+    /// 生成消息控制ID（时间戳加序号，不超过20个字符）
+    fn next_control_id(&mut self, timestamp: u64) -> String {
+        self.sequence = (self.sequence + 1) % 10_000;
+        format!("{:013}{:04}", timestamp, self.sequence)
+    }
+
+    fn send(&mut self, start: u64, end: u64, observations: &[Observation]) {
+        if observations.is_empty() {
+            return;
+        }
+        let control_id = self.next_control_id(end);
+        let message = build_oru(&self.config, &control_id, start, end, observations);
+        let _ = self.sender.send((control_id, message));
+    }
+
+    /// 处理一个采样，按批次规则生成消息
+    pub fn push(&mut self, processed: &ProcessedVitalSigns) {
+        let timestamp = processed.timestamp;
+        if let Some(glucose) = processed.glucose {
+            if self.config.batch_mode == Hl7BatchMode::PerMeasurement {
+                let observation = Observation {
+                    code: "15074-8",
+                    name: "Glucose [Moles/volume] in Blood",
+                    units: "mmol/L",
+                    value: glucose,
+                };
+                self.send(timestamp, timestamp, &[observation]);
+            }
+        }
+
+        let values = [
+            (
+                "heart_rate",
+                (!processed.artifact).then_some(processed.heart_rate),
+            ),
+            (
+                "spo2",
+                processed.blood_oxygen.filter(|_| !processed.artifact),
+            ),
+            ("temperature", processed.body_temperature),
+            ("respiration_rate", processed.respiration_rate),
+            ("etco2", processed.etco2),
+        ];
+        if self.config.batch_mode == Hl7BatchMode::PeriodicSummary {
+            for (vital, value) in values {
+                if let Some(value) = value.filter(|value| *value > 0.0) {
+                    let total = self.totals.entry(vital).or_default();
+                    total.sum += value;
+                    total.count += 1;
+                }
+            }
+        }
+
+        let period_start = *self.period_start.get_or_insert(timestamp);
+        if timestamp >= period_start && timestamp - period_start < self.config.interval_secs * 1000
+        {
+            return;
+        }
+        self.period_start = Some(timestamp);
+
+        let observations: Vec<Observation> = match self.config.batch_mode {
+            Hl7BatchMode::PerMeasurement => values
+                .into_iter()
+                .filter_map(|(vital, value)| {
+                    observation(vital, value.filter(|value| *value > 0.0)?)
+                })
+                .collect(),
+            Hl7BatchMode::PeriodicSummary => std::mem::take(&mut self.totals)
+                .into_iter()
+                .filter_map(|(vital, total)| observation(vital, total.sum / total.count as f64))
+                .collect(),
+        };
+        match self.config.batch_mode {
+            Hl7BatchMode::PerMeasurement => self.send(timestamp, timestamp, &observations),
+            Hl7BatchMode::PeriodicSummary => self.send(period_start, timestamp, &observations),
+        }
+    }
+
+    /// 获取接口状态
+    pub fn status(&self) -> Hl7InterfaceStatus {
+        Hl7InterfaceStatus {
+            config: self.config.clone(),
+            stats: self.stats.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// 按体征名查找LOINC代码生成观察
+fn observation(vital: &str, value: f64) -> Option<Observation> {
+    VITAL_CODES
+        .iter()
+        .find(|(name, ..)| *name == vital)
+        .map(|&(_, code, name, units)| Observation {
+            code,
+            name,
+            units,
+            value,
+        })
+}
+
+/// 以MLLP帧发送一条消息并读取确认
+fn exchange(stream: &mut TcpStream, message: &str) -> Result<Hl7Ack, String> {
+    let mut frame = Vec::with_capacity(message.len() + 3);
+    frame.push(MLLP_START);
+    frame.extend_from_slice(message.as_bytes());
+    frame.extend_from_slice(&[MLLP_END, b'\r']);
+    stream
+        .write_all(&frame)
+        .map_err(|e| format!("发送HL7消息失败: {}", e))?;
+
+    let mut response = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let n = stream
+            .read(&mut buf)
+            .map_err(|e| format!("等待HL7确认失败: {}", e))?;
+        if n == 0 {
+            return Err("接口引擎关闭了连接".to_string());
+        }
+        response.extend_from_slice(&buf[..n]);
+        if let Some(end) = response.iter().position(|&b| b == MLLP_END) {
+            let start = response
+                .iter()
+                .position(|&b| b == MLLP_START)
+                .map_or(0, |i| i + 1);
+            let ack = String::from_utf8_lossy(&response[start.min(end)..end]);
+            return parse_ack(&ack).ok_or_else(|| "确认消息缺少MSA段".to_string());
+        }
+        if response.len() > MAX_ACK_LEN {
+            return Err("确认消息过长".to_string());
+        }
+    }
+}
+
+/// 连接接口引擎
+fn connect(config: &Hl7SenderConfig) -> Result<TcpStream, String> {
+    let addr = (config.host.as_str(), config.port)
+        .to_socket_addrs()
+        .map_err(|e| format!("解析接口引擎地址失败: {}", e))?
+        .next()
+        .ok_or_else(|| "接口引擎地址无效".to_string())?;
+    let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+        .map_err(|e| format!("连接接口引擎失败: {}", e))?;
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(config.ack_timeout_secs)));
+    let _ = stream.set_write_timeout(Some(Duration::from_secs(config.ack_timeout_secs)));
+    println!("[HL7][线程] 已连接到 {}:{}", config.host, config.port);
+    Ok(stream)
+}
+
+/// 发送线程：把新消息写入队列，按顺序逐条发送并等待确认
+fn run_sender(
+    config: Hl7SenderConfig,
+    queue: Hl7Queue,
+    messages: Receiver<(String, String)>,
+    stats: Arc<Mutex<Hl7Stats>>,
+) {
+    let mut stream: Option<TcpStream> = None;
+    // 每条消息已失败的次数
+    let mut attempts: HashMap<PathBuf, u32> = HashMap::new();
+    let mut retry_at: Option<Instant> = None;
+    let mut failures: u32 = 0;
+    loop {
+        let pending = queue.pending();
+        let wait = match retry_at {
+            Some(at) => at.saturating_duration_since(Instant::now()),
+            None if pending.is_empty() => Duration::from_secs(1),
+            None => Duration::ZERO,
+        };
+        let mut closed = false;
+        let mut received = match messages.recv_timeout(wait) {
+            Ok(message) => vec![message],
+            Err(RecvTimeoutError::Timeout) => Vec::new(),
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        loop {
+            match messages.try_recv() {
+                Ok(message) => received.push(message),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    closed = true;
+                    break;
+                }
+            }
+        }
+        let mut dropped = 0;
+        for (control_id, message) in &received {
+            match queue.store(control_id, message) {
+                Ok(removed) => dropped += removed,
+                Err(e) => eprintln!("[HL7][线程] {}", e),
+            }
+        }
+        stats.lock().unwrap().dropped += dropped;
+        if closed {
+            break;
+        }
+
+        if retry_at.is_some_and(|at| Instant::now() < at) {
+            continue;
+        }
+        retry_at = None;
+        let Some(path) = queue.pending().into_iter().next() else {
+            stats.lock().unwrap().pending = 0;
+            continue;
+        };
+
+        let result = (|| {
+            let message =
+                fs::read_to_string(&path).map_err(|e| format!("读取HL7消息失败: {}", e))?;
+            if stream.is_none() {
+                stream = Some(connect(&config)?);
+            }
+            let result = exchange(stream.as_mut().unwrap(), &message);
+            if result.is_err() {
+                stream = None;
+            }
+            result
+        })();
+
+        let mut stats = stats.lock().unwrap();
+        let failed = match result {
+            Ok(ack) if ack.accepted() => {
+                let _ = fs::remove_file(&path);
+                attempts.remove(&path);
+                stats.sent += 1;
+                stats.last_ack = Some(ack);
+                stats.last_error = None;
+                false
+            }
+            Ok(nak) => {
+                eprintln!(
+                    "[HL7][线程] 消息 {} 被拒绝: {} {}",
+                    nak.control_id, nak.code, nak.text
+                );
+                stats.last_nak = Some(nak);
+                true
+            }
+            Err(e) => {
+                if stats.last_error.as_deref() != Some(e.as_str()) {
+                    eprintln!("[HL7][线程] {}", e);
+                }
+                stats.last_error = Some(e);
+                true
+            }
+        };
+        if failed {
+            let count = attempts.entry(path.clone()).or_insert(0);
+            *count += 1;
+            if *count > config.max_retries {
+                eprintln!(
+                    "[HL7][线程] 消息 {} 超过重试次数，移入failed目录",
+                    path.display()
+                );
+                queue.fail(&path);
+                attempts.remove(&path);
+                stats.failed += 1;
+            }
+            failures = failures.saturating_add(1);
+            let delay = RETRY_BASE_DELAY
+                .saturating_mul(1 << (failures - 1).min(6))
+                .min(RETRY_MAX_DELAY);
+            retry_at = Some(Instant::now() + delay);
+        } else {
+            failures = 0;
+        }
+        stats.connection = match (stream.is_some(), failed) {
+            (true, _) => Hl7ConnectionState::Connected,
+            (false, true) => Hl7ConnectionState::Retrying,
+            (false, false) => Hl7ConnectionState::Disconnected,
+        };
+        stats.pending = queue.pending().len();
+    }
+    println!("[HL7][线程] 发送线程安全退出");
+}
@@ -0,0 +1,170 @@
+//! 心率恢复测试模块
+//!
+//! 运动期间开始测试，运动结束时由操作员标记，之后自动记录运动结束后1、2、3分钟
+//! 的心率（每个时间点前一段时间内的平均值），计算相对运动结束时心率的下降值。
+//! 1分钟心率下降过小提示自主神经调节异常。
+
+use crate::types::{
+    HrRecoveryConfig, HrRecoveryPhase, HrRecoveryPoint, HrRecoveryResult, HrRecoveryStatus,
+};
+use std::collections::VecDeque;
+
+/// 记录心率的时间点（距运动结束的分钟数）
+const CHECKPOINT_MINUTES: [u32; 3] = [1, 2, 3];
+
+/// 验证心率恢复测试配置
+pub fn validate_config(config: &HrRecoveryConfig) -> Result<(), String> {
+    if !(1_000..=30_000).contains(&config.heart_rate_window_ms) {
+        return Err("心率采集窗口必须在1到30秒之间".to_string());
+    }
+    if !config.abnormal_recovery_threshold.is_finite() || config.abnormal_recovery_threshold <= 0.0
+    {
+        return Err("恢复异常阈值必须大于0".to_string());
+    }
+    Ok(())
+}
+
+/// 一次心率恢复测试的运行状态
+#[derive(Debug, Clone)]
+pub struct HrRecoverySession {
+    config: HrRecoveryConfig,
+    /// 测试开始时间戳，收到第一个采样时确定
+    started_at: Option<u64>,
+    /// 已请求标记运动结束，下一个采样的时间戳作为运动结束时间
+    end_requested: bool,
+    /// 运动结束时间戳
+    exercise_ended_at: Option<u64>,
+    /// 运动结束前的平均心率
+    exercise_heart_rate: Option<f64>,
+    /// 最近一个采样的时间戳
+    last_timestamp: u64,
+    /// 采集窗口内的心率记录
+    window: VecDeque<(u64, f64)>,
+    /// 已记录的时间点
+    points: Vec<HrRecoveryPoint>,
+    /// 测试结果，全部时间点记录后生成
+    result: Option<HrRecoveryResult>,
+}
+
+impl HrRecoverySession {
+    /// 创建测试，配置需先通过 `validate_config` 验证
+    pub fn new(config: HrRecoveryConfig) -> Self {
+        Self {
+            config,
+            started_at: None,
+            end_requested: false,
+            exercise_ended_at: None,
+            exercise_heart_rate: None,
+            last_timestamp: 0,
+            window: VecDeque::new(),
+            points: Vec::new(),
+            result: None,
+        }
+    }
+
+    /// 测试是否已完成
+    pub fn is_complete(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// 标记运动结束
+    pub fn mark_exercise_end(&mut self) -> Result<(), String> {
+        if self.exercise_ended_at.is_some() || self.end_requested {
+            return Err("已标记运动结束".to_string());
+        }
+        self.end_requested = true;
+        Ok(())
+    }
+
+    /// 采集窗口内的平均心率
+    fn window_mean(&self) -> Option<f64> {
+        (!self.window.is_empty())
+            .then(|| self.window.iter().map(|(_, hr)| hr).sum::<f64>() / self.window.len() as f64)
+    }
+
+    /// 处理一个采样
+    ///
+    /// # 参数
+    /// * `heart_rate` - 当前有效心率，无效（伪差、通道关闭）时为None
+    /// * `timestamp` - 当前时间戳（毫秒）
+    ///
+    /// # 返回值
+    /// 测试在本采样完成时返回测试结果
+    pub fn update(&mut self, heart_rate: Option<f64>, timestamp: u64) -> Option<HrRecoveryResult> {
+        if self.is_complete() {
+            return None;
+        }
+        self.last_timestamp = timestamp;
+        self.started_at.get_or_insert(timestamp);
+
+        if self.end_requested {
+            self.end_requested = false;
+            self.exercise_ended_at = Some(timestamp);
+            self.exercise_heart_rate = self.window_mean();
+            self.window.clear();
+            println!("[HrRecovery] 运动结束，心率={:?}", self.exercise_heart_rate);
+        }
+
+        if let Some(hr) = heart_rate.filter(|&hr| hr > 0.0) {
+            self.window.push_back((timestamp, hr));
+        }
+        while self
+            .window
+            .front()
+            .is_some_and(|(t, _)| *t + self.config.heart_rate_window_ms < timestamp)
+        {
+            self.window.pop_front();
+        }
+
+        let ended_at = self.exercise_ended_at?;
+        let minute = CHECKPOINT_MINUTES[self.points.len()];
+        if timestamp < ended_at + minute as u64 * 60_000 {
+            return None;
+        }
+        let heart_rate = self.window_mean();
+        self.points.push(HrRecoveryPoint {
+            minute,
+            heart_rate,
+            recovery: heart_rate
+                .zip(self.exercise_heart_rate)
+                .map(|(hr, exercise)| exercise - hr),
+            captured_at: timestamp,
+        });
+        println!("[HrRecovery] 运动结束后{}分钟心率={:?}", minute, heart_rate);
+        if self.points.len() < CHECKPOINT_MINUTES.len() {
+            return None;
+        }
+
+        let result = HrRecoveryResult {
+            exercise_heart_rate: self.exercise_heart_rate,
+            points: self.points.clone(),
+            abnormal: self.points[0]
+                .recovery
+                .map(|recovery| recovery <= self.config.abnormal_recovery_threshold),
+            config: self.config.clone(),
+            started_at: self.started_at.unwrap_or(ended_at),
+            exercise_ended_at: ended_at,
+            completed_at: timestamp,
+        };
+        self.result = Some(result.clone());
+        Some(result)
+    }
+
+    /// 获取测试的当前状态
+    pub fn status(&self) -> HrRecoveryStatus {
+        let phase = match (self.is_complete(), self.exercise_ended_at) {
+            (true, _) => HrRecoveryPhase::Complete,
+            (false, Some(_)) => HrRecoveryPhase::Recovery,
+            (false, None) => HrRecoveryPhase::Exercise,
+        };
+        HrRecoveryStatus {
+            phase,
+            recovery_elapsed_ms: self
+                .exercise_ended_at
+                .map_or(0, |ended| self.last_timestamp.saturating_sub(ended)),
+            exercise_heart_rate: self.exercise_heart_rate,
+            points: self.points.clone(),
+            result: self.result.clone(),
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! 心率变异性（HRV）分析模块
+//!
+//! 本模块基于RR间期序列计算HRV相关指标，包括：
+//! - Poincaré散点图数据（RRn, RRn+1）
+//! - Poincaré椭圆参数 SD1/SD2
+//! - 时域指标 RMSSD
+//! - 频域分析：RR序列重采样 + Welch / Lomb-Scargle 功率谱估计，
+//!   输出 VLF、LF、HF 频段功率和 LF/HF 比值
+
+use crate::types::{HrvSpectrum, PoincarePlot, PoincarePoint, SpectralMethod};
+use std::f64::consts::PI;
+
+/// VLF频段 (Hz)
+const VLF_BAND: (f64, f64) = (0.0033, 0.04);
+/// LF频段 (Hz)
+const LF_BAND: (f64, f64) = (0.04, 0.15);
+/// HF频段 (Hz)
+const HF_BAND: (f64, f64) = (0.15, 0.4);
+/// Welch法重采样频率 (Hz)
+const RESAMPLE_RATE_HZ: f64 = 4.0;
+/// Welch法每段的采样点数（4Hz下约64秒）
+const WELCH_SEGMENT_LEN: usize = 256;
+/// Lomb-Scargle频率网格步长 (Hz)
+const LOMB_FREQ_STEP: f64 = 0.001;
+/// 频域分析所需的最少RR间期数量
+const MIN_SPECTRUM_BEATS: usize = 16;
+
+/// 生成Poincaré散点图数据
+///
+/// 将相邻的RR间期组成 (RRn, RRn+1) 点对，并计算椭圆拟合参数。
+///
+/// # 参数
+/// * `rr_intervals` - 按时间先后排列的RR间期（毫秒）
+///
+/// # 返回值
+/// 返回散点数据和SD1/SD2参数；RR间期少于3个时SD1/SD2为0
+pub fn poincare_plot(rr_intervals: &[f64]) -> PoincarePlot {
+    let points: Vec<PoincarePoint> = rr_intervals
+        .windows(2)
+        .map(|pair| PoincarePoint {
+            rr_n: pair[0],
+            rr_n1: pair[1],
+        })
+        .collect();
+
+    let (sd1, sd2) = poincare_sd1_sd2(rr_intervals);
+    let mean_rr = mean(rr_intervals);
+
+    PoincarePlot {
+        points,
+        sd1,
+        sd2,
+        sd1_sd2_ratio: if sd2 > 0.0 { sd1 / sd2 } else { 0.0 },
+        center_x: mean_rr,
+        center_y: mean_rr,
+    }
+}
+
+/// 计算Poincaré椭圆的SD1和SD2
+///
+/// - SD1 = sqrt(0.5 * SDSD²)，反映短期变异性
+/// - SD2 = sqrt(2 * SDNN² - 0.5 * SDSD²)，反映长期变异性
+///
+/// # 参数
+/// * `rr_intervals` - RR间期序列（毫秒）
+///
+/// # 返回值
+/// 返回元组：(SD1, SD2)，单位毫秒
+pub fn poincare_sd1_sd2(rr_intervals: &[f64]) -> (f64, f64) {
+    if rr_intervals.len() < 3 {
+        return (0.0, 0.0);
+    }
+
+    let successive_diffs: Vec<f64> = rr_intervals.windows(2).map(|p| p[1] - p[0]).collect();
+    let sdsd_sq = variance(&successive_diffs);
+    let sdnn_sq = variance(rr_intervals);
+
+    let sd1 = (0.5 * sdsd_sq).sqrt();
+    let sd2 = (2.0 * sdnn_sq - 0.5 * sdsd_sq).max(0.0).sqrt();
+    (sd1, sd2)
+}
+
+/// 计算HRV频域指标
+///
+/// # 参数
+/// * `rr_intervals` - 按时间先后排列的RR间期（毫秒）
+/// * `method` - 功率谱估计方法
+/// * `window_seconds` - 分析窗口长度（秒），仅用于结果记录
+///
+/// # 返回值
+/// 返回各频段功率（ms²）及功率谱；RR间期不足时各功率为0
+pub fn spectrum(rr_intervals: &[f64], method: SpectralMethod, window_seconds: f64) -> HrvSpectrum {
+    let (frequencies, psd) = if rr_intervals.len() < MIN_SPECTRUM_BEATS {
+        (Vec::new(), Vec::new())
+    } else {
+        match method {
+            SpectralMethod::Welch => welch_psd(rr_intervals),
+            SpectralMethod::LombScargle => lomb_scargle_psd(rr_intervals),
+        }
+    };
+
+    let vlf_power = band_power(&frequencies, &psd, VLF_BAND);
+    let lf_power = band_power(&frequencies, &psd, LF_BAND);
+    let hf_power = band_power(&frequencies, &psd, HF_BAND);
+    let total_power = vlf_power + lf_power + hf_power;
+
+    HrvSpectrum {
+        method,
+        window_seconds,
+        beat_count: rr_intervals.len(),
+        vlf_power,
+        lf_power,
+        hf_power,
+        total_power,
+        lf_hf_ratio: if hf_power > 0.0 {
+            lf_power / hf_power
+        } else {
+            0.0
+        },
+        lf_nu: normalized_unit(lf_power, lf_power + hf_power),
+        hf_nu: normalized_unit(hf_power, lf_power + hf_power),
+        frequencies,
+        psd,
+    }
+}
+
+/// Welch法功率谱估计
+///
+/// 先将RR序列按心搏时刻线性插值重采样为4Hz等间隔序列，去均值后
+/// 分段（50%重叠）加Hann窗计算周期图并求平均。
+///
+/// # 返回值
+/// 返回元组：(频率向量Hz, 单边功率谱密度 ms²/Hz)
+fn welch_psd(rr_intervals: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let mut signal = resample_rr(rr_intervals, RESAMPLE_RATE_HZ);
+    if signal.len() < MIN_SPECTRUM_BEATS {
+        return (Vec::new(), Vec::new());
+    }
+    let signal_mean = mean(&signal);
+    signal.iter_mut().for_each(|v| *v -= signal_mean);
+
+    let segment_len = WELCH_SEGMENT_LEN.min(signal.len());
+    let step = (segment_len / 2).max(1);
+    let window: Vec<f64> = (0..segment_len)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / (segment_len - 1) as f64).cos())
+        .collect();
+    let window_power: f64 = window.iter().map(|w| w * w).sum();
+
+    let bins = segment_len / 2 + 1;
+    let mut psd = vec![0.0; bins];
+    let mut segments = 0;
+
+    let mut start = 0;
+    while start + segment_len <= signal.len() {
+        let segment = &signal[start..start + segment_len];
+        for (k, value) in psd.iter_mut().enumerate() {
+            let (mut re, mut im) = (0.0, 0.0);
+            for (j, x) in segment.iter().enumerate() {
+                let angle = 2.0 * PI * (j * k) as f64 / segment_len as f64;
+                let xw = x * window[j];
+                re += xw * angle.cos();
+                im -= xw * angle.sin();
+            }
+            let mut p = (re * re + im * im) / (RESAMPLE_RATE_HZ * window_power);
+            // 单边谱：除直流和奈奎斯特频点外功率加倍
+            if k != 0 && 2 * k != segment_len {
+                p *= 2.0;
+            }
+            *value += p;
+        }
+        segments += 1;
+        start += step;
+    }
+
+    psd.iter_mut().for_each(|p| *p /= segments as f64);
+    let frequencies = (0..bins)
+        .map(|k| k as f64 * RESAMPLE_RATE_HZ / segment_len as f64)
+        .collect();
+    (frequencies, psd)
+}
+
+/// Lomb-Scargle功率谱估计
+///
+/// 直接在不等间隔的心搏时刻上计算周期图，无需重采样，
+/// 并按序列方差对结果进行缩放，使谱积分与方差一致。
+///
+/// # 返回值
+/// 返回元组：(频率向量Hz, 功率谱密度 ms²/Hz)
+fn lomb_scargle_psd(rr_intervals: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let times = beat_times(rr_intervals);
+    let rr_mean = mean(rr_intervals);
+    let values: Vec<f64> = rr_intervals.iter().map(|v| v - rr_mean).collect();
+
+    let steps = ((HF_BAND.1 - VLF_BAND.0) / LOMB_FREQ_STEP).round() as usize;
+    let frequencies: Vec<f64> = (0..=steps)
+        .map(|i| VLF_BAND.0 + i as f64 * LOMB_FREQ_STEP)
+        .collect();
+
+    let mut psd: Vec<f64> = frequencies
+        .iter()
+        .map(|f| {
+            let omega = 2.0 * PI * f;
+            let (sin_sum, cos_sum) = times.iter().fold((0.0, 0.0), |(s, c), t| {
+                (s + (2.0 * omega * t).sin(), c + (2.0 * omega * t).cos())
+            });
+            let tau = sin_sum.atan2(cos_sum) / (2.0 * omega);
+
+            let (mut yc, mut ys, mut cc, mut ss) = (0.0, 0.0, 0.0, 0.0);
+            for (t, y) in times.iter().zip(&values) {
+                let arg = omega * (t - tau);
+                let (s, c) = arg.sin_cos();
+                yc += y * c;
+                ys += y * s;
+                cc += c * c;
+                ss += s * s;
+            }
+            let cos_term = if cc > 0.0 { yc * yc / cc } else { 0.0 };
+            let sin_term = if ss > 0.0 { ys * ys / ss } else { 0.0 };
+            0.5 * (cos_term + sin_term)
+        })
+        .collect();
+
+    let integral: f64 = psd.iter().sum::<f64>() * LOMB_FREQ_STEP;
+    let total_variance = variance(rr_intervals);
+    if integral > 0.0 {
+        let scale = total_variance / integral;
+        psd.iter_mut().for_each(|p| *p *= scale);
+    }
+
+    (frequencies, psd)
+}
+
+/// 计算每个心搏相对第一个心搏的时刻（秒）
+fn beat_times(rr_intervals: &[f64]) -> Vec<f64> {
+    let mut elapsed = 0.0;
+    rr_intervals
+        .iter()
+        .map(|rr| {
+            elapsed += rr / 1000.0;
+            elapsed
+        })
+        .collect()
+}
+
+/// 将RR序列按心搏时刻线性插值为等间隔序列
+///
+/// # 参数
+/// * `rr_intervals` - RR间期（毫秒）
+/// * `rate_hz` - 重采样频率
+fn resample_rr(rr_intervals: &[f64], rate_hz: f64) -> Vec<f64> {
+    let times = beat_times(rr_intervals);
+    let (first, last) = match (times.first(), times.last()) {
+        (Some(first), Some(last)) if last > first => (*first, *last),
+        _ => return Vec::new(),
+    };
+
+    let sample_count = ((last - first) * rate_hz).floor() as usize + 1;
+    let mut resampled = Vec::with_capacity(sample_count);
+    let mut idx = 0;
+    for n in 0..sample_count {
+        let t = first + n as f64 / rate_hz;
+        while idx + 1 < times.len() - 1 && times[idx + 1] < t {
+            idx += 1;
+        }
+        let (t0, t1) = (times[idx], times[idx + 1]);
+        let (v0, v1) = (rr_intervals[idx], rr_intervals[idx + 1]);
+        let ratio = if t1 > t0 { (t - t0) / (t1 - t0) } else { 0.0 };
+        resampled.push(v0 + (v1 - v0) * ratio.clamp(0.0, 1.0));
+    }
+    resampled
+}
+
+/// 对功率谱在频段内积分（矩形法）
+fn band_power(frequencies: &[f64], psd: &[f64], band: (f64, f64)) -> f64 {
+    if frequencies.len() < 2 {
+        return 0.0;
+    }
+    let df = frequencies[1] - frequencies[0];
+    frequencies
+        .iter()
+        .zip(psd)
+        .filter(|(f, _)| **f >= band.0 && **f < band.1)
+        .map(|(_, p)| p * df)
+        .sum()
+}
+
+/// 计算归一化单位（n.u.），总功率为0时返回0
+fn normalized_unit(power: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        power / total * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// 计算RMSSD（相邻RR间期差值的均方根）
+///
+/// # 参数
+/// * `rr_intervals` - RR间期序列（毫秒）
+///
+/// # 返回值
+/// 返回RMSSD（毫秒），RR间期少于2个时返回0
+pub fn rmssd(rr_intervals: &[f64]) -> f64 {
+    if rr_intervals.len() < 2 {
+        return 0.0;
+    }
+    let sum_sq: f64 = rr_intervals.windows(2).map(|p| (p[1] - p[0]).powi(2)).sum();
+    (sum_sq / (rr_intervals.len() - 1) as f64).sqrt()
+}
+
+/// 计算均值，空序列返回0
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// 计算样本方差（n-1），少于2个值时返回0
+fn variance(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let m = mean(values);
+    values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+}
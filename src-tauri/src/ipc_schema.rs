@@ -0,0 +1,117 @@
+//! 前端接口数据格式模块
+//!
+//! 通过命令返回或事件推送传给前端的结构体字段统一以camelCase命名。
+//! 迁移期间可以用 `legacy-field-names` 特性编译，保留原来的snake_case字段名。
+//!
+//! 推送给前端的事件统一包装为 `{ schemaVersion, payload }`，前端按版本号判断字段格式。
+//! 磁盘上旧版本保存的文件使用snake_case字段名，读取时先把字段名转换为camelCase再解析。
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// 当前数据格式版本，1为snake_case字段名，2为camelCase字段名
+pub const SCHEMA_VERSION: u32 = if cfg!(feature = "legacy-field-names") {
+    1
+} else {
+    2
+};
+
+/// 键为数据（通道名、体征名、请求头名等）而不是字段名的映射字段，读取旧文件时键保持不变
+const DATA_KEYED_FIELDS: &[&str] = &[
+    "channels",
+    "columns",
+    "counts",
+    "trends",
+    "channelAdjustments",
+    "headers",
+];
+
+/// 数据格式信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct IpcSchema {
+    /// 数据格式版本
+    pub schema_version: u32,
+    /// 字段命名方式，`camelCase` 或 `snake_case`
+    pub field_naming: String,
+}
+
+/// 获取当前的数据格式信息
+pub fn schema() -> IpcSchema {
+    IpcSchema {
+        schema_version: SCHEMA_VERSION,
+        field_naming: if cfg!(feature = "legacy-field-names") {
+            "snake_case"
+        } else {
+            "camelCase"
+        }
+        .to_string(),
+    }
+}
+
+/// 带数据格式版本的事件负载
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct Versioned<T> {
+    pub schema_version: u32,
+    pub payload: T,
+}
+
+/// 为事件负载附上数据格式版本
+pub fn versioned<T: Serialize>(payload: T) -> Versioned<T> {
+    Versioned {
+        schema_version: SCHEMA_VERSION,
+        payload,
+    }
+}
+
+/// 解析磁盘上保存的JSON，兼容旧版本的snake_case字段名
+pub fn from_stored_json<T: DeserializeOwned>(content: &str) -> Result<T, serde_json::Error> {
+    let mut value: Value = serde_json::from_str(content)?;
+    if !cfg!(feature = "legacy-field-names") {
+        upgrade_field_names(&mut value);
+    }
+    serde_json::from_value(value)
+}
+
+/// 把JSON中snake_case的字段名转换为camelCase，已是camelCase的字段名不变
+fn upgrade_field_names(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            let entries = std::mem::take(object);
+            *object = entries
+                .into_iter()
+                .map(|(key, mut field)| {
+                    let key = to_camel_case(&key);
+                    if DATA_KEYED_FIELDS.contains(&key.as_str()) {
+                        if let Value::Object(map) = &mut field {
+                            map.values_mut().for_each(upgrade_field_names);
+                        }
+                    } else {
+                        upgrade_field_names(&mut field);
+                    }
+                    (key, field)
+                })
+                .collect::<Map<String, Value>>();
+        }
+        Value::Array(items) => items.iter_mut().for_each(upgrade_field_names),
+        _ => {}
+    }
+}
+
+fn to_camel_case(key: &str) -> String {
+    let mut result = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' && !result.is_empty() {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
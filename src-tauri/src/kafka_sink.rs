@@ -0,0 +1,350 @@
+//! Kafka输出模块
+//!
+//! 把处理后的体征和报警事件发布到Kafka主题，供医院的流式分析平台消费。
+//! 消息键为 `<患者ID>/<设备ID>`，同一患者和设备的消息进入同一分区并保持顺序；
+//! 消息内容为JSON。Broker不可用时消息在本地缓存，恢复后按顺序补发，
+//! 缓存超过容量时丢弃最早的消息并计数。
+//!
+//! Kafka客户端依赖librdkafka，需要用 `--features kafka` 编译；未启用时启动输出返回错误。
+
+use crate::alarms::AlarmListener;
+use crate::types::{Alarm, ProcessedVitalSigns};
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Kafka输出配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct KafkaConfig {
+    /// Broker地址列表，逗号分隔，例如 `kafka1:9092,kafka2:9092`
+    pub brokers: String,
+    /// 体征数据主题
+    pub vitals_topic: String,
+    /// 报警事件主题
+    pub alarms_topic: String,
+    /// 设备ID，写入消息键
+    pub device_id: String,
+    /// 患者ID，写入消息键，省略时使用当前患者
+    #[serde(default)]
+    pub patient_id: Option<String>,
+    /// 体征数据的发布间隔（毫秒）
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    /// Broker不可用时本地最多缓存的消息数
+    #[serde(default = "default_buffer_capacity")]
+    pub buffer_capacity: usize,
+}
+
+fn default_interval_ms() -> u64 {
+    1000
+}
+
+fn default_buffer_capacity() -> usize {
+    100_000
+}
+
+/// Kafka发送统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct KafkaStats {
+    /// 已交给Kafka客户端发送的消息数
+    pub produced: u64,
+    /// 本地缓存中等待发送的消息数
+    pub buffered: usize,
+    /// Kafka客户端中尚未被Broker确认的消息数
+    pub in_flight: usize,
+    /// 缓存溢出丢弃的消息数
+    pub dropped: u64,
+    /// 发送失败的消息数
+    pub errors: u64,
+    /// 最近一次失败的原因
+    pub last_error: Option<String>,
+}
+
+/// Kafka输出状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KafkaStatus {
+    pub config: KafkaConfig,
+    #[serde(flatten)]
+    pub stats: KafkaStats,
+}
+
+/// 验证Kafka输出配置
+pub fn validate_config(config: &KafkaConfig) -> Result<(), String> {
+    if config.brokers.trim().is_empty() {
+        return Err("Kafka Broker地址不能为空".to_string());
+    }
+    if config.vitals_topic.trim().is_empty() || config.alarms_topic.trim().is_empty() {
+        return Err("Kafka主题不能为空".to_string());
+    }
+    if config.device_id.trim().is_empty() {
+        return Err("设备ID不能为空".to_string());
+    }
+    if !(100..=60_000).contains(&config.interval_ms) {
+        return Err("发布间隔必须在100到60000毫秒之间".to_string());
+    }
+    if !(100..=10_000_000).contains(&config.buffer_capacity) {
+        return Err("本地缓存容量必须在100到10000000条之间".to_string());
+    }
+    Ok(())
+}
+
+/// 体征数据消息
+#[derive(Debug, Serialize)]
+struct VitalsMessage<'a> {
+    patient_id: Option<&'a str>,
+    device_id: &'a str,
+    timestamp: u64,
+    heart_rate: f64,
+    spo2: Option<f64>,
+    temperature: Option<f64>,
+    respiration_rate: Option<f64>,
+    etco2: Option<f64>,
+    artifact: bool,
+}
+
+/// 报警事件消息
+#[derive(Debug, Serialize)]
+struct AlarmMessage<'a> {
+    patient_id: Option<&'a str>,
+    device_id: &'a str,
+    /// `raised` 或 `cleared`
+    event: &'a str,
+    alarm: &'a Alarm,
+}
+
+/// 交给发送线程的消息
+#[cfg_attr(not(feature = "kafka"), allow(dead_code))]
+struct KafkaMessage {
+    topic: String,
+    key: String,
+    payload: String,
+}
+
+/// Kafka输出，由处理线程在每个采样后调用 `push`
+///
+/// Kafka客户端在发送线程中创建和使用，处理线程只把消息放入通道；
+/// 输出被丢弃时通道关闭，发送线程尽量发送完剩余消息后退出。
+pub struct KafkaSink {
+    config: KafkaConfig,
+    key: String,
+    sender: Sender<KafkaMessage>,
+    stats: Arc<Mutex<KafkaStats>>,
+    last_published: Option<u64>,
+}
+
+impl KafkaSink {
+    /// 连接Kafka并启动发送线程
+    pub fn new(config: KafkaConfig) -> Result<Self, String> {
+        validate_config(&config)?;
+        let (sender, receiver) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::sync_channel(1);
+        let stats = Arc::new(Mutex::new(KafkaStats::default()));
+        let thread_config = config.clone();
+        let thread_stats = stats.clone();
+        thread::spawn(move || run_producer(thread_config, receiver, thread_stats, ready_sender));
+        ready
+            .recv()
+            .map_err(|_| "Kafka发送线程意外退出".to_string())??;
+        println!(
+            "[Kafka] 输出到 {}，体征主题={}，报警主题={}",
+            config.brokers, config.vitals_topic, config.alarms_topic
+        );
+        let key = format!(
+            "{}/{}",
+            config.patient_id.as_deref().unwrap_or("unknown"),
+            config.device_id
+        );
+        Ok(Self {
+            config,
+            key,
+            sender,
+            stats,
+            last_published: None,
+        })
+    }
+
+    fn send(&self, topic: &str, payload: Result<String, serde_json::Error>) {
+        match payload {
+            Ok(payload) => {
+                let _ = self.sender.send(KafkaMessage {
+                    topic: topic.to_string(),
+                    key: self.key.clone(),
+                    payload,
+                });
+            }
+            Err(e) => eprintln!("[Kafka] 序列化消息失败: {}", e),
+        }
+    }
+
+    /// 处理一个采样，到达发布间隔时发布体征数据
+    pub fn push(&mut self, processed: &ProcessedVitalSigns) {
+        let timestamp = processed.timestamp;
+        let due = self
+            .last_published
+            .is_none_or(|last| timestamp < last || timestamp - last >= self.config.interval_ms);
+        if !due {
+            return;
+        }
+        self.last_published = Some(timestamp);
+        let message = VitalsMessage {
+            patient_id: self.config.patient_id.as_deref(),
+            device_id: &self.config.device_id,
+            timestamp,
+            heart_rate: processed.heart_rate,
+            spo2: processed.blood_oxygen,
+            temperature: processed.body_temperature,
+            respiration_rate: processed.respiration_rate,
+            etco2: processed.etco2,
+            artifact: processed.artifact,
+        };
+        self.send(&self.config.vitals_topic, serde_json::to_string(&message));
+    }
+
+    /// 发布报警事件
+    fn push_alarm(&self, event: &str, alarm: &Alarm) {
+        let message = AlarmMessage {
+            patient_id: self.config.patient_id.as_deref(),
+            device_id: &self.config.device_id,
+            event,
+            alarm,
+        };
+        self.send(&self.config.alarms_topic, serde_json::to_string(&message));
+    }
+
+    /// 获取输出状态
+    pub fn status(&self) -> KafkaStatus {
+        KafkaStatus {
+            config: self.config.clone(),
+            stats: self.stats.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// 把报警事件转发到Kafka输出，输出未启用时忽略
+pub struct KafkaAlarmForwarder {
+    sink: Arc<Mutex<Option<KafkaSink>>>,
+}
+
+impl KafkaAlarmForwarder {
+    pub fn new(sink: Arc<Mutex<Option<KafkaSink>>>) -> Self {
+        Self { sink }
+    }
+}
+
+impl AlarmListener for KafkaAlarmForwarder {
+    fn on_alarm_raised(&mut self, alarm: &Alarm) {
+        if let Some(sink) = self.sink.lock().unwrap().as_ref() {
+            sink.push_alarm("raised", alarm);
+        }
+    }
+
+    fn on_alarm_cleared(&mut self, alarm: &Alarm) {
+        if let Some(sink) = self.sink.lock().unwrap().as_ref() {
+            sink.push_alarm("cleared", alarm);
+        }
+    }
+}
+
+/// 发送线程：把通道中的消息放入本地缓存，再按顺序交给Kafka客户端
+#[cfg(feature = "kafka")]
+fn run_producer(
+    config: KafkaConfig,
+    messages: Receiver<KafkaMessage>,
+    stats: Arc<Mutex<KafkaStats>>,
+    ready: SyncSender<Result<(), String>>,
+) {
+    use rdkafka::config::ClientConfig;
+    use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+    use rdkafka::producer::{BaseProducer, BaseRecord, Producer};
+    use std::collections::VecDeque;
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
+
+    // message.timeout.ms=0：Broker不可用期间客户端持续重试，不会丢弃已交给它的消息
+    let producer: BaseProducer = match ClientConfig::new()
+        .set("bootstrap.servers", &config.brokers)
+        .set("client.id", &config.device_id)
+        .set("message.timeout.ms", "0")
+        .set("enable.idempotence", "true")
+        .create()
+    {
+        Ok(producer) => {
+            let _ = ready.send(Ok(()));
+            producer
+        }
+        Err(e) => {
+            let _ = ready.send(Err(format!("创建Kafka客户端失败: {}", e)));
+            return;
+        }
+    };
+
+    let mut backlog: VecDeque<KafkaMessage> = VecDeque::new();
+    let mut closed = false;
+    while !closed {
+        match messages.recv_timeout(Duration::from_millis(100)) {
+            Ok(message) => backlog.push_back(message),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => closed = true,
+        }
+        backlog.extend(messages.try_iter());
+        let mut dropped = 0;
+        while backlog.len() > config.buffer_capacity {
+            backlog.pop_front();
+            dropped += 1;
+        }
+
+        let mut produced = 0;
+        let mut error = None;
+        while let Some(message) = backlog.front() {
+            let record = BaseRecord::to(&message.topic)
+                .key(message.key.as_str())
+                .payload(message.payload.as_str());
+            match producer.send(record) {
+                Ok(()) => produced += 1,
+                // 客户端队列已满（Broker长时间不可用），留在本地缓存稍后重试
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), _)) => break,
+                Err((e, _)) => error = Some(e.to_string()),
+            }
+            backlog.pop_front();
+        }
+        producer.poll(Duration::ZERO);
+
+        let mut stats = stats.lock().unwrap();
+        stats.produced += produced;
+        stats.dropped += dropped;
+        if let Some(e) = error {
+            if stats.last_error.is_none() {
+                eprintln!("[Kafka][线程] 发送消息失败: {}", e);
+            }
+            stats.errors += 1;
+            stats.last_error = Some(e);
+        }
+        stats.buffered = backlog.len();
+        stats.in_flight = producer.in_flight_count().max(0) as usize;
+    }
+
+    // 停止后最多等待5秒让客户端把已接收的消息发给Broker
+    if producer.flush(Duration::from_secs(5)).is_err() || !backlog.is_empty() {
+        eprintln!(
+            "[Kafka][线程] 停止时仍有{}条消息未发送",
+            backlog.len() + producer.in_flight_count().max(0) as usize
+        );
+    }
+    println!("[Kafka][线程] 发送线程安全退出");
+}
+
+/// 未启用Kafka支持时无法创建客户端
+#[cfg(not(feature = "kafka"))]
+fn run_producer(
+    _config: KafkaConfig,
+    _messages: Receiver<KafkaMessage>,
+    _stats: Arc<Mutex<KafkaStats>>,
+    ready: SyncSender<Result<(), String>>,
+) {
+    let _ = ready.send(Err(
+        "当前版本未包含Kafka支持，请使用 --features kafka 重新编译".to_string(),
+    ));
+}
@@ -9,9 +9,67 @@ macro_rules! undefined_Send_for_SerialManager {
 }
 
 // 导出模块
+pub mod activity;
+pub mod alarm_history;
+pub mod alarms;
+pub mod alert_dispatcher;
+pub mod alloc_stats;
+pub mod arrhythmia_sim;
+pub mod audit_log;
+pub mod auto_tune;
+pub mod benchmark;
+pub mod channel_metadata;
+pub mod channels;
+pub mod code_scanner;
 pub mod data_processor;
+pub mod diagnostics;
+pub mod ecg_archive;
+pub mod event_stream;
+pub mod export;
+pub mod file_tail_reader;
+pub mod firmware;
+pub mod framing;
+pub mod hid_reader;
+pub mod hl7_sender;
+pub mod hr_recovery;
+pub mod hrv;
+pub mod ipc_schema;
+pub mod kafka_sink;
+pub mod lsl_outlet;
+pub mod lttb_worker;
+pub mod patient_lock;
+pub mod patient_merge;
 pub mod patient_store;
+pub mod pdf_report;
+pub mod notifier;
+pub mod openehr;
+pub mod orthostatic;
+pub mod osc_output;
+pub mod pagination;
+pub mod pipeline;
+pub mod playback_reader;
+pub mod profile_store;
+pub mod purge;
+pub mod reprocess;
+pub mod sample_clock;
+pub mod sample_ring;
 pub mod serial_manager;
 pub mod serial_reader;
+pub mod session_store;
+pub mod shared_memory;
+pub mod snapshot;
+pub mod spp_reader;
+pub mod stress_sim;
+pub mod subscriptions;
+pub mod tcp_reader;
+pub mod telemetry;
 pub mod test_reader;
+pub mod thread_tuning;
+pub mod thresholds;
+pub mod time_sync;
+pub mod trends;
 pub mod types; // 新增患者存储模块
+pub mod udp_reader;
+pub mod walk_test;
+pub mod watchdog;
+pub mod webhooks;
@@ -0,0 +1,268 @@
+//! LSL输出模块
+//!
+//! 把ECG波形和数值体征发布为Lab Streaming Layer（LSL）数据流，供研究人员与已使用LSL的
+//! 脑电、眼动等设备同步采集。发布两个流：
+//! * `<名称> ECG` - 类型 `ECG`，1个通道（归一化ECG），按ECG采样率发送每个采样
+//! * `<名称> Vitals` - 类型 `VitalSigns`，心率、血氧、体温、呼吸频率和RR间期，每秒一个采样
+//!
+//! 采样时间戳由采样时间换算到LSL本地时钟，处理延迟不影响与其他设备的同步。
+//! LSL依赖liblsl，需要用 `--features lsl` 编译；未启用时启动输出返回错误。
+
+use crate::types::ProcessedVitalSigns;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::thread;
+
+/// 数值体征流的发送间隔（毫秒）
+const VITALS_INTERVAL_MS: u64 = 1000;
+
+/// 数值体征流的通道：标签和单位
+#[cfg(feature = "lsl")]
+const VITAL_CHANNELS: [(&str, &str); 5] = [
+    ("HeartRate", "bpm"),
+    ("SpO2", "percent"),
+    ("Temperature", "celsius"),
+    ("RespirationRate", "breaths/min"),
+    ("RRInterval", "ms"),
+];
+
+/// LSL输出配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct LslConfig {
+    /// 流名称前缀
+    pub stream_name: String,
+    /// 数据源ID，接收端断线重连时据此找回同一设备的流
+    pub source_id: String,
+    /// ECG采样率（Hz），写入流的标称采样率
+    pub ecg_sample_rate_hz: f64,
+    /// 是否发布ECG波形流
+    pub ecg: bool,
+    /// 是否发布数值体征流
+    pub vitals: bool,
+}
+
+impl Default for LslConfig {
+    fn default() -> Self {
+        Self {
+            stream_name: "VitalSigns".to_string(),
+            source_id: "tauri-vital-signs".to_string(),
+            ecg_sample_rate_hz: 250.0,
+            ecg: true,
+            vitals: true,
+        }
+    }
+}
+
+/// LSL输出状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct LslStatus {
+    pub config: LslConfig,
+    /// 已发送的ECG采样数
+    pub ecg_samples: u64,
+    /// 已发送的数值体征采样数
+    pub vital_samples: u64,
+}
+
+/// 验证LSL输出配置
+pub fn validate_config(config: &LslConfig) -> Result<(), String> {
+    if config.stream_name.trim().is_empty() {
+        return Err("LSL流名称不能为空".to_string());
+    }
+    if config.source_id.trim().is_empty() {
+        return Err("LSL数据源ID不能为空".to_string());
+    }
+    if !(1.0..=10_000.0).contains(&config.ecg_sample_rate_hz) {
+        return Err("ECG采样率必须在1到10000Hz之间".to_string());
+    }
+    if !config.ecg && !config.vitals {
+        return Err("至少选择一个要发布的流".to_string());
+    }
+    Ok(())
+}
+
+/// 交给发送线程的采样，时间戳为采样时间（毫秒）
+#[cfg_attr(not(feature = "lsl"), allow(dead_code))]
+enum LslSample {
+    Ecg(f32, u64),
+    Vitals([f32; 5], u64),
+}
+
+/// LSL输出，由处理线程在每个采样后调用 `push`
+///
+/// liblsl的流对象在发送线程中创建和使用，处理线程只把采样放入通道；
+/// 输出被丢弃时通道关闭，发送线程随之退出并注销流。
+pub struct LslOutlet {
+    config: LslConfig,
+    sender: Sender<LslSample>,
+    last_vitals: Option<u64>,
+    ecg_samples: u64,
+    vital_samples: u64,
+}
+
+impl LslOutlet {
+    /// 创建LSL流并启动发送线程
+    pub fn new(config: LslConfig) -> Result<Self, String> {
+        validate_config(&config)?;
+        let (sender, receiver) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::sync_channel(1);
+        let thread_config = config.clone();
+        thread::spawn(move || run_outlets(thread_config, receiver, ready_sender));
+        ready
+            .recv()
+            .map_err(|_| "LSL发送线程意外退出".to_string())??;
+        println!(
+            "[LSL] 已发布数据流 {}，ECG={}，数值体征={}",
+            config.stream_name, config.ecg, config.vitals
+        );
+        Ok(Self {
+            config,
+            sender,
+            last_vitals: None,
+            ecg_samples: 0,
+            vital_samples: 0,
+        })
+    }
+
+    /// 处理一个采样
+    pub fn push(&mut self, processed: &ProcessedVitalSigns) {
+        let timestamp = processed.timestamp;
+        if self.config.ecg {
+            let sample = LslSample::Ecg(processed.ecg_normalized as f32, timestamp);
+            if self.sender.send(sample).is_ok() {
+                self.ecg_samples += 1;
+            }
+        }
+        let due = self
+            .last_vitals
+            .is_none_or(|last| timestamp < last || timestamp - last >= VITALS_INTERVAL_MS);
+        if self.config.vitals && due {
+            self.last_vitals = Some(timestamp);
+            // 设备未上报的体征按LSL惯例发送NaN
+            let values = [
+                processed.heart_rate,
+                processed.blood_oxygen.unwrap_or(f64::NAN),
+                processed.body_temperature.unwrap_or(f64::NAN),
+                processed.respiration_rate.unwrap_or(f64::NAN),
+                processed.rr_interval,
+            ]
+            .map(|value| value as f32);
+            if self
+                .sender
+                .send(LslSample::Vitals(values, timestamp))
+                .is_ok()
+            {
+                self.vital_samples += 1;
+            }
+        }
+    }
+
+    /// 获取输出状态
+    pub fn status(&self) -> LslStatus {
+        LslStatus {
+            config: self.config.clone(),
+            ecg_samples: self.ecg_samples,
+            vital_samples: self.vital_samples,
+        }
+    }
+}
+
+/// 创建一个LSL流，写入通道标签、单位和类型等元数据
+#[cfg(feature = "lsl")]
+fn create_outlet(
+    config: &LslConfig,
+    suffix: &str,
+    kind: &str,
+    channels: &[(&str, &str)],
+    rate: f64,
+) -> Result<lsl::StreamOutlet, String> {
+    let mut info = lsl::StreamInfo::new(
+        &format!("{} {}", config.stream_name, suffix),
+        kind,
+        channels.len() as u32,
+        rate,
+        lsl::ChannelFormat::Float32,
+        &format!("{}-{}", config.source_id, suffix.to_lowercase()),
+    )
+    .map_err(|e| format!("创建LSL流信息失败: {:?}", e))?;
+    let mut desc = info.desc();
+    desc.append_child_value("manufacturer", "tauri-vital-signs");
+    let mut channel_list = desc.append_child("channels");
+    for (label, unit) in channels {
+        channel_list
+            .append_child("channel")
+            .append_child_value("label", label)
+            .append_child_value("unit", unit)
+            .append_child_value("type", kind);
+    }
+    lsl::StreamOutlet::new(&info, 0, 360).map_err(|e| format!("创建LSL流失败: {:?}", e))
+}
+
+/// 发送线程：创建流，把收到的采样推送到LSL，直到通道关闭
+#[cfg(feature = "lsl")]
+fn run_outlets(
+    config: LslConfig,
+    samples: Receiver<LslSample>,
+    ready: SyncSender<Result<(), String>>,
+) {
+    use lsl::ExPushable;
+
+    let ecg = config
+        .ecg
+        .then(|| {
+            let channels = [("ECG", "normalized")];
+            create_outlet(&config, "ECG", "ECG", &channels, config.ecg_sample_rate_hz)
+        })
+        .transpose();
+    let vitals = config
+        .vitals
+        .then(|| {
+            let rate = 1000.0 / VITALS_INTERVAL_MS as f64;
+            create_outlet(&config, "Vitals", "VitalSigns", &VITAL_CHANNELS, rate)
+        })
+        .transpose();
+    let (ecg, vitals) = match (ecg, vitals) {
+        (Ok(ecg), Ok(vitals)) => {
+            let _ = ready.send(Ok(()));
+            (ecg, vitals)
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            let _ = ready.send(Err(e));
+            return;
+        }
+    };
+
+    // 采样时间换算为LSL本地时钟：当前LSL时间减去采样距今的时长
+    let lsl_time = |timestamp: u64| {
+        let now = chrono::Utc::now().timestamp_millis().max(0) as u64;
+        lsl::local_clock() - now.saturating_sub(timestamp) as f64 / 1000.0
+    };
+    for sample in samples {
+        let result = match (&sample, &ecg, &vitals) {
+            (LslSample::Ecg(value, timestamp), Some(outlet), _) => {
+                outlet.push_sample_ex(&vec![*value], lsl_time(*timestamp), true)
+            }
+            (LslSample::Vitals(values, timestamp), _, Some(outlet)) => {
+                outlet.push_sample_ex(&values.to_vec(), lsl_time(*timestamp), true)
+            }
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            eprintln!("[LSL][线程] 推送采样失败: {:?}", e);
+        }
+    }
+    println!("[LSL][线程] 数据流已关闭");
+}
+
+/// 未启用LSL支持时无法创建流
+#[cfg(not(feature = "lsl"))]
+fn run_outlets(
+    _config: LslConfig,
+    _samples: Receiver<LslSample>,
+    ready: SyncSender<Result<(), String>>,
+) {
+    let _ = ready.send(Err(
+        "当前版本未包含LSL支持，请使用 --features lsl 重新编译".to_string()
+    ));
+}
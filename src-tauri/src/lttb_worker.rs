@@ -0,0 +1,139 @@
+//! LTTB压缩工作线程模块
+//!
+//! 波形缓冲区写满时，处理线程只把缓冲区快照交给工作线程，由工作线程完成整个缓冲区的
+//! LTTB压缩并写回压缩结果，处理线程不会因为压缩而停顿（高采样率下的停顿会导致漏检心搏）。
+//! 工作线程以较低优先级运行，快照缓冲区循环复用，处理线程提交快照时不分配内存。
+
+use crate::data_processor::DataProcessor;
+use crate::diagnostics::hot_log;
+use crate::types::{LttbDataPoint, LttbProcessingState};
+use crate::watchdog::panic_message;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// 等待压缩的快照数上限，工作线程跟不上时丢弃新的快照，下次写满时再压缩
+const QUEUE_DEPTH: usize = 4;
+
+/// 工作线程的nice值增量
+#[cfg(target_os = "linux")]
+const WORKER_NICENESS: i32 = 10;
+
+/// 一个压缩任务
+struct LttbJob {
+    /// 压缩结果写回的波形状态
+    state: Arc<Mutex<LttbProcessingState>>,
+    /// 原始缓冲区快照
+    points: Vec<LttbDataPoint>,
+    /// 压缩后的数据点数
+    target_points: usize,
+}
+
+/// LTTB压缩工作线程，处理器析构后线程自行退出
+pub struct LttbWorker {
+    sender: SyncSender<LttbJob>,
+    /// 空闲的快照缓冲区
+    pool: Arc<Mutex<Vec<Vec<LttbDataPoint>>>>,
+    /// 已提交但尚未完成的压缩任务数
+    pending: Arc<AtomicUsize>,
+}
+
+impl LttbWorker {
+    /// 启动工作线程
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_DEPTH);
+        let pool = Arc::new(Mutex::new(Vec::with_capacity(QUEUE_DEPTH + 1)));
+        let pending = Arc::new(AtomicUsize::new(0));
+        let worker_pool = pool.clone();
+        let worker_pending = pending.clone();
+        thread::Builder::new()
+            .name("lttb-worker".to_string())
+            .spawn(move || Self::run(receiver, worker_pool, worker_pending))
+            .expect("无法启动LTTB压缩线程");
+        Self {
+            sender,
+            pool,
+            pending,
+        }
+    }
+
+    fn run(
+        receiver: Receiver<LttbJob>,
+        pool: Arc<Mutex<Vec<Vec<LttbDataPoint>>>>,
+        pending: Arc<AtomicUsize>,
+    ) {
+        #[cfg(target_os = "linux")]
+        // Linux下nice只影响调用线程
+        unsafe {
+            libc::nice(WORKER_NICENESS);
+        }
+        for job in receiver {
+            // 压缩出错只丢弃本次任务，工作线程继续运行
+            let compressed = match panic::catch_unwind(|| {
+                DataProcessor::lttb_downsample(&job.points, job.target_points)
+            }) {
+                Ok(compressed) => compressed,
+                Err(payload) => {
+                    eprintln!("[LTTB] 压缩任务异常: {}", panic_message(payload.as_ref()));
+                    pending.fetch_sub(1, Ordering::Relaxed);
+                    continue;
+                }
+            };
+            let compressed_len = compressed.len();
+            job.state.lock().unwrap().compressed_buffer = compressed;
+            hot_log!(
+                "[LTTB] 压缩完成: {} -> {} 数据点，压缩比: {:.1}:1",
+                job.points.len(),
+                compressed_len,
+                job.points.len() as f64 / compressed_len.max(1) as f64
+            );
+            let mut points = job.points;
+            points.clear();
+            pool.lock().unwrap().push(points);
+            pending.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 是否有正在进行或等待中的压缩任务
+    pub fn is_busy(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// 提交一个压缩任务，复制缓冲区快照后立即返回
+    ///
+    /// # 参数
+    /// * `state` - 压缩结果写回的波形状态
+    /// * `points` - 原始缓冲区
+    /// * `target_points` - 压缩后的数据点数
+    ///
+    /// # 返回值
+    /// 工作线程积压过多而丢弃该任务时返回false
+    pub fn submit(
+        &self,
+        state: &Arc<Mutex<LttbProcessingState>>,
+        points: &[LttbDataPoint],
+        target_points: usize,
+    ) -> bool {
+        let mut snapshot = self.pool.lock().unwrap().pop().unwrap_or_default();
+        snapshot.extend_from_slice(points);
+        let job = LttbJob {
+            state: state.clone(),
+            points: snapshot,
+            target_points,
+        };
+        // 先计数再发送，避免工作线程在计数前完成任务导致计数下溢
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        match self.sender.try_send(job) {
+            Ok(()) => true,
+            Err(TrySendError::Full(job) | TrySendError::Disconnected(job)) => {
+                self.pending.fetch_sub(1, Ordering::Relaxed);
+                let mut points = job.points;
+                points.clear();
+                self.pool.lock().unwrap().push(points);
+                false
+            }
+        }
+    }
+}
@@ -3,19 +3,109 @@
     windows_subsystem = "windows"
 )]
 
+mod activity;
+mod alarm_history;
+mod alarms;
+mod alert_dispatcher;
+mod alloc_stats;
+mod arrhythmia_sim;
+mod audit_log;
+mod auto_tune;
+mod benchmark;
+mod channel_metadata;
+mod channels;
+mod code_scanner;
 mod data_processor;
+mod diagnostics;
+mod ecg_archive;
+mod event_stream;
+mod export;
+mod file_tail_reader;
+mod firmware;
+mod framing;
+mod hid_reader;
+mod hl7_sender;
+mod hr_recovery;
+mod hrv;
+mod ipc_schema;
+mod kafka_sink;
+mod lsl_outlet;
+mod lttb_worker;
+mod notifier;
+mod openehr;
+mod orthostatic;
+mod osc_output;
+mod pagination;
+mod patient_lock;
+mod patient_merge;
 mod patient_store;
+mod pdf_report;
+mod pipeline;
+mod playback_reader;
+mod profile_store;
+mod purge;
+mod reprocess;
+mod sample_clock;
+mod sample_ring;
 mod serial_manager;
 mod serial_reader;
+mod session_store;
+mod shared_memory;
+mod snapshot;
+mod spp_reader;
+mod stress_sim;
+mod subscriptions;
+mod tcp_reader;
+mod telemetry;
 mod test_reader;  // 新增
+mod thread_tuning;
+mod thresholds;
+mod time_sync;
+mod trends;
 mod types;
+mod udp_reader;
+mod walk_test;
+mod watchdog;
+mod webhooks;
 
+use alarm_history::{AlarmHistoryEntry, AlarmHistoryRecorder, AlarmHistoryStore};
+use alert_dispatcher::AlertDispatcher;
+use audit_log::{AuditEntry, AuditStore};
+use auto_tune::{AutoTuneStatus, AutoTuner};
+use code_scanner::CodeScanner;
 use data_processor::DataProcessor;
-use patient_store::{PatientInfo, PatientStore};
+use ecg_archive::EcgArchive;
+use event_stream::{EventStream, EventStreamConfig};
+use notifier::DesktopNotifier;
+use pagination::Page;
+use patient_lock::{PatientLock, PatientLockStatus};
+use patient_store::{
+    ClinicalFlags, CodedEntry, ConsentRecord, ConsentScope, EntryList, Medication, PatientInfo,
+    PatientStore,
+};
+use profile_store::{ConnectionProfile, ProfileStore};
+use std::collections::BTreeMap;
 use serial_manager::SerialManager;
-use std::sync::Mutex;
-use tauri::{Manager, State}; // 添加 Manager 导入
-use types::{DataSourceType, ProcessedVitalSigns, SerialConfig, SerialStatus, VitalSigns};
+use session_store::{
+    ExportRecord, SessionAlignment, SessionComparison, SessionMetadata, SessionStore,
+};
+use subscriptions::{SubscriptionInfo, SubscriptionManager};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State}; // 添加 Manager 导入
+use time_sync::{TimeSync, TimeSyncConfig, TimeSyncStatus};
+use types::{
+    ChannelAdjustment, DataSourceType, HeartRateAveraging, NotificationSettings,
+    PerformanceMetrics, ProcessedVitalSigns, ProcessingStatus, SerialConfig, SerialStatusReport,
+    VitalSigns, WatchdogComponent, WatchdogConfig,
+};
+use watchdog::Watchdog;
+use webhooks::{WebhookAlarmListener, WebhookDispatcher, WebhookEvent};
+
+/// 按线程统计内存分配，供处理性能测试使用
+#[global_allocator]
+static GLOBAL_ALLOCATOR: alloc_stats::CountingAllocator = alloc_stats::CountingAllocator;
 
 /// 全局串口管理器状态
 struct SerialManagerState(Mutex<SerialManager>);
@@ -23,213 +113,3578 @@ struct SerialManagerState(Mutex<SerialManager>);
 /// 全局数据处理器状态
 struct DataProcessorState(Mutex<Option<DataProcessor>>);
 
-/// 全局患者存储状态
-struct PatientStoreState(Mutex<Option<PatientStore>>);
+impl DataProcessorState {
+    /// 获取数据处理器，持有锁的命令发生panic后清除中毒标记继续使用，不让后续命令连带崩溃
+    fn lock(&self) -> MutexGuard<'_, Option<DataProcessor>> {
+        self.0.lock().unwrap_or_else(|poisoned| {
+            eprintln!("[Main] 数据处理器锁已中毒，清除后继续使用");
+            self.0.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+}
+
+/// 全局患者存储状态
+struct PatientStoreState(Mutex<Option<PatientStore>>);
+
+/// 患者数据锁状态
+struct PatientLockState(Mutex<Option<PatientLock>>);
+
+/// 全局连接配置档案存储状态
+struct ProfileStoreState(Mutex<Option<ProfileStore>>);
+
+/// 全局报警历史存储状态
+struct AlarmHistoryStoreState(Mutex<Option<AlarmHistoryStore>>);
+
+/// 全局审计日志存储状态
+struct AuditStoreState(Mutex<Option<AuditStore>>);
+
+/// 会话存储状态
+struct SessionStoreState(Mutex<Option<SessionStore>>);
+
+/// 串口条码扫描枪状态
+struct CodeScannerState(Mutex<Option<CodeScanner>>);
+
+/// Webhook分发器状态，由报警监听器共享
+struct WebhookState(Mutex<Option<Arc<WebhookDispatcher>>>);
+
+/// 报警邮件/短信通知状态
+struct AlertDispatcherState(Mutex<Option<AlertDispatcher>>);
+
+/// 原始心电存档状态，由数据处理器共享
+struct EcgArchiveState(Mutex<Option<Arc<EcgArchive>>>);
+
+/// 全局报警通知设置，由桌面通知监听器共享
+struct NotificationSettingsState(Arc<Mutex<NotificationSettings>>);
+
+/// 全局看门狗配置
+struct WatchdogConfigState(Mutex<WatchdogConfig>);
+
+/// 全局实时数据事件推送配置
+struct EventStreamConfigState(Mutex<EventStreamConfig>);
+
+/// 全局按体征订阅
+struct SubscriptionState(Mutex<SubscriptionManager>);
+
+/// 全局缓冲区自动调节器
+struct AutoTuneState(Mutex<AutoTuner>);
+
+/// 全局时间同步配置和最近一次查询结果
+struct TimeSyncState(Mutex<TimeSyncConfig>, Mutex<TimeSync>);
+
+/// 按数量或时间范围查询采样时单次最多返回的采样数
+const MAX_QUERY_COUNT: usize = 5000;
+
+/// 按体征订阅的检查间隔，对应最高推送频率
+const SUBSCRIPTION_TICK: Duration = Duration::from_millis(16);
+
+/// 没有订阅时的检查间隔
+const SUBSCRIPTION_IDLE_TICK: Duration = Duration::from_millis(200);
+
+/// 缓冲区自动调节的观测间隔
+const AUTO_TUNE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 看门狗停滞事件名
+const WATCHDOG_EVENT: &str = "watchdog-error";
+
+/// 数据流健康状态变化事件名
+const STREAM_HEALTH_EVENT: &str = "serial-status";
+
+/// 处理状态变化事件名
+const PROCESSING_STATUS_EVENT: &str = "processing-status";
+
+/// 连接中切换数据源类型事件名
+const DATA_SOURCE_SWITCH_EVENT: &str = "data-source-switched";
+
+/// 性能指标事件名
+const PERFORMANCE_EVENT: &str = "performance-metrics";
+
+/// 提示性阈值事件名
+const THRESHOLD_EVENT: &str = "threshold-crossing";
+
+/// 采样丢弃警告事件名
+const SAMPLE_DROP_EVENT: &str = "sample-drop-warning";
+
+/// 批量实时数据事件名
+const VITAL_SIGNS_BATCH_EVENT: &str = "vital-signs-batch";
+
+/// 时钟偏差超过阈值的警告事件名
+const TIME_SYNC_WARNING_EVENT: &str = "time-sync-warning";
+
+/// 设备能力检测完成或变化事件名
+const DEVICE_CAPABILITIES_EVENT: &str = "device-capabilities";
+
+/// 扫码识别患者成功事件名
+const PATIENT_IDENTIFIED_EVENT: &str = "patient-identified";
+
+/// 扫码识别患者失败事件名
+const PATIENT_CODE_ERROR_EVENT: &str = "patient-code-error";
+
+/// 汇总数据处理器的性能指标和串口吞吐量，数据处理器未启动时返回None
+fn collect_performance_metrics(
+    serial_state: &SerialManagerState,
+    processor_state: &DataProcessorState,
+) -> Option<PerformanceMetrics> {
+    let mut metrics = processor_state.lock().as_ref()?.get_performance_metrics();
+    let (samples_per_second, bytes_per_second) = serial_state.0.lock().unwrap().get_throughput();
+    metrics.samples_per_second = samples_per_second;
+    metrics.bytes_per_second = bytes_per_second;
+    Some(metrics)
+}
+
+/// 启动看门狗线程，每秒检查一次串口读取线程和数据处理线程的心跳
+///
+/// 组件停滞时推送 `watchdog-error` 事件，配置了自动重启时先重启该组件；
+/// 同时更新数据流健康状态和固件版本警告，变化时推送 `serial-status` 事件；
+/// 数据处理器运行时每秒推送一次 `performance-metrics` 事件，
+/// 有新的提示性阈值事件时推送 `threshold-crossing` 事件，
+/// 处理跟不上采样速率而丢弃采样时推送 `sample-drop-warning` 事件并计入压力测试报告，
+/// 处理状态变化时推送 `processing-status` 事件，
+/// 设备能力检测完成或变化时按设备能力开关通道并推送 `device-capabilities` 事件，
+/// 高优先级报警长时间未确认时发送邮件/短信通知
+fn spawn_watchdog(app: AppHandle) {
+    thread::spawn(move || {
+        println!("[Watchdog] 看门狗线程已启动");
+        let mut watchdog = Watchdog::new();
+        let mut last_processing_status = None;
+        loop {
+            thread::sleep(Duration::from_secs(1));
+            let config = app.state::<WatchdogConfigState>().0.lock().unwrap().clone();
+
+            let serial_state = app.state::<SerialManagerState>();
+            let mut serial_manager = serial_state.0.lock().unwrap();
+            if let Some(report) = serial_manager.update_stream_health() {
+                if let Err(e) = app.emit(STREAM_HEALTH_EVENT, ipc_schema::versioned(report)) {
+                    eprintln!("[Watchdog] 推送事件失败: {}", e);
+                }
+            }
+            let capabilities = serial_manager.take_capability_change();
+            let heartbeat = serial_manager.get_reader_heartbeat();
+            let event =
+                watchdog.check(WatchdogComponent::SerialReader, heartbeat.as_ref(), &config);
+            if let Some(mut event) = event {
+                if config.auto_restart {
+                    match serial_manager.restart_reader() {
+                        Ok(()) => event.restarted = true,
+                        Err(e) => event.message = format!("{}，重启失败: {}", event.message, e),
+                    }
+                }
+                drop(serial_manager);
+                eprintln!("[Watchdog] {}", event.message);
+                if let Err(e) = app.emit(WATCHDOG_EVENT, ipc_schema::versioned(event)) {
+                    eprintln!("[Watchdog] 推送事件失败: {}", e);
+                }
+            } else {
+                drop(serial_manager);
+            }
+
+            let processor_state = app.state::<DataProcessorState>();
+            let processor_guard = processor_state.lock();
+            if let Some(capabilities) = capabilities {
+                if let Some(processor) = processor_guard.as_ref() {
+                    processor.apply_device_capabilities(&capabilities);
+                }
+                if let Err(e) = app.emit(
+                    DEVICE_CAPABILITIES_EVENT,
+                    ipc_schema::versioned(capabilities),
+                ) {
+                    eprintln!("[Watchdog] 推送事件失败: {}", e);
+                }
+            }
+            let heartbeat = processor_guard.as_ref().map(|p| p.heartbeat());
+            let threshold_events = processor_guard
+                .as_ref()
+                .map(|p| p.take_threshold_events())
+                .unwrap_or_default();
+            let drop_warning = processor_guard
+                .as_ref()
+                .and_then(|p| p.take_sample_drop_warning());
+            let active_alarms = processor_guard
+                .as_ref()
+                .map(|p| p.get_active_alarms())
+                .unwrap_or_default();
+            // 报警时间戳在采样时间轴上，未确认时长也按采样时间轴计算
+            let sample_time = processor_guard.as_ref().map(|p| p.sample_time());
+            let processing_status = processor_guard.as_ref().map(|p| p.get_processing_status());
+            let event =
+                watchdog.check(WatchdogComponent::DataProcessor, heartbeat.as_ref(), &config);
+            if let Some(mut event) = event {
+                if let Some(ProcessingStatus::Error(message)) =
+                    processor_guard.as_ref().map(|p| p.get_processing_status())
+                {
+                    event.message = format!("{}（处理线程异常退出: {}）", event.message, message);
+                }
+                if let Some(processor) = processor_guard.as_ref().filter(|_| config.auto_restart) {
+                    processor.restart();
+                    event.restarted = true;
+                }
+                drop(processor_guard);
+                eprintln!("[Watchdog] {}", event.message);
+                if let Err(e) = app.emit(WATCHDOG_EVENT, ipc_schema::versioned(event)) {
+                    eprintln!("[Watchdog] 推送事件失败: {}", e);
+                }
+            } else {
+                drop(processor_guard);
+            }
+
+            if processing_status != last_processing_status {
+                if let Some(status) = &processing_status {
+                    if let Err(e) = app.emit(PROCESSING_STATUS_EVENT, ipc_schema::versioned(status))
+                    {
+                        eprintln!("[Watchdog] 推送事件失败: {}", e);
+                    }
+                }
+                last_processing_status = processing_status;
+            }
+
+            if !threshold_events.is_empty() {
+                if let Err(e) =
+                    app.emit(THRESHOLD_EVENT, ipc_schema::versioned(threshold_events))
+                {
+                    eprintln!("[Watchdog] 推送事件失败: {}", e);
+                }
+            }
+
+            if let Some(warning) = drop_warning {
+                eprintln!(
+                    "[Watchdog] 数据处理跟不上采样速率，丢弃 {} 个采样（累计 {}）",
+                    warning.dropped, warning.total_dropped
+                );
+                app.state::<SerialManagerState>()
+                    .0
+                    .lock()
+                    .unwrap()
+                    .record_stress_drops(warning.dropped);
+                if let Err(e) = app.emit(SAMPLE_DROP_EVENT, ipc_schema::versioned(warning)) {
+                    eprintln!("[Watchdog] 推送事件失败: {}", e);
+                }
+            }
+
+            if let Some(dispatcher) = app.state::<AlertDispatcherState>().0.lock().unwrap().as_ref() {
+                // 没有处理器时也没有报警，时间不参与比较
+                let now = sample_time.unwrap_or_default();
+                dispatcher.check(&active_alarms, now, || {
+                    let patient_state = app.state::<PatientStoreState>();
+                    let patient_guard = patient_state.0.lock().unwrap();
+                    patient_guard
+                        .as_ref()
+                        .and_then(|store| store.load_patient_info().ok())
+                        .map(|info| info.name)
+                        .unwrap_or_default()
+                });
+            }
+
+            if let Some(metrics) = collect_performance_metrics(&serial_state, &processor_state) {
+                if let Err(e) = app.emit(PERFORMANCE_EVENT, ipc_schema::versioned(metrics)) {
+                    eprintln!("[Watchdog] 推送事件失败: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// 启动实时数据推送线程，开启推送时按配置的频率推送 `vital-signs-batch` 批量事件
+fn spawn_event_stream(app: AppHandle) {
+    thread::spawn(move || {
+        println!("[EventStream] 实时数据推送线程已启动");
+        let mut stream = EventStream::new();
+        loop {
+            let config = app.state::<EventStreamConfigState>().0.lock().unwrap().clone();
+            let interval = Duration::from_secs(1) / config.rate_hz;
+            let started = std::time::Instant::now();
+
+            let batch = if config.enabled {
+                let processor_state = app.state::<DataProcessorState>();
+                let processor_guard = processor_state.lock();
+                match processor_guard.as_ref() {
+                    Some(processor) => stream.next_batch(processor, config.max_batch_size),
+                    None => {
+                        stream.reset();
+                        None
+                    }
+                }
+            } else {
+                stream.reset();
+                None
+            };
+            if let Some(batch) = batch {
+                if let Err(e) =
+                    app.emit(VITAL_SIGNS_BATCH_EVENT, ipc_schema::versioned(batch))
+                {
+                    eprintln!("[EventStream] 推送事件失败: {}", e);
+                }
+            }
+
+            // 按固定频率推送，扣除本轮取数和推送的耗时
+            thread::sleep(interval.saturating_sub(started.elapsed()));
+        }
+    });
+}
+
+/// 启动按体征订阅的推送线程，按各订阅的频率推送订阅的体征
+fn spawn_subscriptions(app: AppHandle) {
+    thread::spawn(move || {
+        println!("[Subscriptions] 订阅推送线程已启动");
+        loop {
+            let subscription_state = app.state::<SubscriptionState>();
+            if subscription_state.0.lock().unwrap().is_empty() {
+                thread::sleep(SUBSCRIPTION_IDLE_TICK);
+                continue;
+            }
+            let latest = app
+                .state::<DataProcessorState>()
+                .lock()
+                .as_ref()
+                .and_then(|processor| processor.get_processed_data(1, None, None).pop());
+            if let Some(latest) = latest {
+                let now = chrono::Utc::now().timestamp_millis().max(0) as u64;
+                let updates = subscription_state.0.lock().unwrap().poll(&latest, now);
+                for update in updates {
+                    let event = subscriptions::event_name(update.subscription_id);
+                    if let Err(e) = app.emit(&event, ipc_schema::versioned(update)) {
+                        eprintln!("[Subscriptions] 推送事件失败: {}", e);
+                    }
+                }
+            }
+            thread::sleep(SUBSCRIPTION_TICK);
+        }
+    });
+}
+
+/// 启动时间同步线程，开启时间同步时按配置的间隔查询时钟偏差，
+/// 偏差超过阈值时推送 `time-sync-warning` 事件
+fn spawn_time_sync(app: AppHandle) {
+    thread::spawn(move || {
+        println!("[TimeSync] 时间同步线程已启动");
+        loop {
+            thread::sleep(Duration::from_secs(1));
+            let state = app.state::<TimeSyncState>();
+            let config = state.0.lock().unwrap().clone();
+            let now = chrono::Utc::now().timestamp_millis().max(0) as u64;
+            if !state.1.lock().unwrap().is_due(&config, now) {
+                continue;
+            }
+
+            // 查询期间不持有锁，避免网络超时阻塞状态查询
+            let result = time_sync::query_offset(&config.server);
+            let warning = state.1.lock().unwrap().record(&config, result, now);
+            if let Some(warning) = warning {
+                eprintln!(
+                    "[TimeSync] 本机时钟偏差 {:.1} 毫秒，超过阈值 {:.1} 毫秒",
+                    warning.offset_ms, warning.threshold_ms
+                );
+                if let Err(e) =
+                    app.emit(TIME_SYNC_WARNING_EVENT, ipc_schema::versioned(warning))
+                {
+                    eprintln!("[TimeSync] 推送事件失败: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// 启动缓冲区自动调节线程，开启自动调节时按观测到的采样率和处理延迟调整缓冲区
+///
+/// 处理后数据队列、LTTB缓冲区和每批推送采样数立即生效，原始采样队列容量在下次连接时生效
+fn spawn_auto_tune(app: AppHandle) {
+    thread::spawn(move || {
+        println!("[AutoTune] 缓冲区自动调节线程已启动");
+        loop {
+            thread::sleep(AUTO_TUNE_INTERVAL);
+            if !app.state::<AutoTuneState>().0.lock().unwrap().is_enabled() {
+                continue;
+            }
+
+            let serial_state = app.state::<SerialManagerState>();
+            let processor_state = app.state::<DataProcessorState>();
+            let Some(metrics) = collect_performance_metrics(&serial_state, &processor_state) else {
+                continue;
+            };
+            let event_rate_hz = app.state::<EventStreamConfigState>().0.lock().unwrap().rate_hz;
+            let compression_ratio = match processor_state.lock().as_ref() {
+                Some(processor) => processor.lttb_compression_ratio(),
+                None => continue,
+            };
+
+            let auto_tune_state = app.state::<AutoTuneState>();
+            let (changed, settings) = {
+                let mut tuner = auto_tune_state.0.lock().unwrap();
+                let changed = tuner.observe(
+                    metrics.samples_per_second,
+                    metrics.average_latency_ms,
+                    event_rate_hz,
+                    compression_ratio,
+                    chrono::Utc::now().timestamp_millis().max(0) as u64,
+                );
+                (changed, tuner.status().settings)
+            };
+            if let Some(settings) = changed {
+                println!(
+                    "[AutoTune] 采样率约{:.0}Hz，调整缓冲区: {:?}",
+                    metrics.samples_per_second, settings
+                );
+            }
+
+            // 数据处理器可能已重建，每轮都重新应用当前设置
+            let Some(settings) = settings else {
+                continue;
+            };
+            if let Some(processor) = processor_state.lock().as_ref() {
+                processor.set_processed_queue_capacity(settings.processed_queue_capacity);
+                processor.set_lttb_buffer_size(settings.lttb_buffer_size);
+            }
+            serial_state
+                .0
+                .lock()
+                .unwrap()
+                .set_queue_capacity(settings.raw_queue_capacity);
+            app.state::<EventStreamConfigState>().0.lock().unwrap().max_batch_size =
+                settings.event_batch_size;
+        }
+    });
+}
+
+/// 将指定串口的配置档案应用到数据处理器
+fn apply_connection_profile(
+    processor: &DataProcessor,
+    port_name: &str,
+    profile_state: &ProfileStoreState,
+) {
+    let store_guard = profile_state.0.lock().unwrap();
+    if let Some(store) = store_guard.as_ref() {
+        match store.get_profile(port_name) {
+            Ok(Some(profile)) => {
+                processor.set_channel_adjustments(profile.channel_adjustments);
+                if let Some(calibration) = &profile.temperature_calibration {
+                    processor.apply_temp_calibration(calibration);
+                }
+                if let Some(params) = profile.ecg_processing_params {
+                    if let Err(e) = processor.set_ecg_processing_params(params) {
+                        eprintln!("[Main] 连接配置中的ECG检测参数无效: {}", e);
+                    }
+                }
+                println!("[Main] 已加载串口 {} 的连接配置", port_name);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("[Main] 加载连接配置失败: {}", e),
+        }
+    }
+}
+
+/// 为新建的数据处理器挂接报警输出（系统通知、报警历史、Webhook）和原始心电存档，并发送会话开始Webhook
+///
+/// `connect_serial` 和 `start_data_processing` 都会新建数据处理器，均通过这里挂接，
+/// 两种启动方式的报警输出和心电存档保持一致。
+fn attach_outputs(processor: &DataProcessor, app: &AppHandle) {
+    let notification_settings = app.state::<NotificationSettingsState>().0.clone();
+    processor.add_alarm_listener(Box::new(DesktopNotifier::new(
+        app.clone(),
+        notification_settings,
+    )));
+    let alarm_history_state = app.state::<AlarmHistoryStoreState>();
+    if let Some(store) = alarm_history_state.0.lock().unwrap().as_ref() {
+        let session_id = processor.session_id().to_string();
+        processor.add_alarm_listener(Box::new(AlarmHistoryRecorder::new(
+            store.clone(),
+            session_id,
+        )));
+    }
+    let ecg_archive_state = app.state::<EcgArchiveState>();
+    if let Some(archive) = ecg_archive_state.0.lock().unwrap().as_ref() {
+        processor.set_ecg_archive(archive.clone());
+    }
+    let dispatcher = app.state::<WebhookState>().0.lock().unwrap().clone();
+    if let Some(dispatcher) = dispatcher {
+        let session_id = processor.session_id().to_string();
+        dispatcher.fire(WebhookEvent::SessionStarted, Some(&session_id), None);
+        processor.add_alarm_listener(Box::new(WebhookAlarmListener::new(dispatcher, session_id)));
+    }
+}
+
+/// 停止数据处理器并发送会话结束Webhook，在后台线程中调用，等待处理线程退出
+fn stop_processor(processor: DataProcessor, dispatcher: Option<Arc<WebhookDispatcher>>) {
+    match processor.stop() {
+        Ok(()) => println!("[Main] 数据处理已停止"),
+        Err(e) => eprintln!("[Main] {}", e),
+    }
+    if let Some(dispatcher) = dispatcher {
+        dispatcher.fire(
+            WebhookEvent::SessionStopped,
+            Some(processor.session_id()),
+            None,
+        );
+    }
+}
+
+/// 修改当前串口的连接配置档案并保存，未连接串口时不做处理
+fn update_current_profile(
+    serial_state: &SerialManagerState,
+    profile_state: &ProfileStoreState,
+    update: impl FnOnce(&mut ConnectionProfile),
+) -> Result<(), String> {
+    let current_config = serial_state.0.lock().unwrap().get_current_config();
+    if let Some(config) = current_config {
+        let store_guard = profile_state.0.lock().unwrap();
+        if let Some(store) = store_guard.as_ref() {
+            let mut profile = store
+                .get_profile(&config.port_name)?
+                .unwrap_or_else(|| ConnectionProfile::new(&config.port_name, config.baud_rate));
+            profile.baud_rate = config.baud_rate;
+            update(&mut profile);
+            store.save_profile(&profile)?;
+        }
+    }
+    Ok(())
+}
+
+/// 获取可用串口列表
+#[tauri::command]
+fn get_available_ports() -> Vec<(String, String)> {
+    SerialManager::get_available_ports()
+}
+
+/// 测试串口连接，串口正被当前连接或其他程序占用时返回 `busy`
+#[tauri::command]
+fn test_serial_connection(
+    port_name: String,
+    baud_rate: u32,
+    state: State<SerialManagerState>,
+) -> Result<types::PortTestStatus, String> {
+    let config = SerialConfig {
+        port_name,
+        baud_rate,
+    };
+    state.0.lock().unwrap().test_connection(config)
+}
+
+/// 串口回环测试（收发短接），测量往返时间和数据完整性
+#[tauri::command]
+fn run_loopback_test(
+    port_name: String,
+    baud_rate: u32,
+    state: State<SerialManagerState>,
+) -> Result<types::LoopbackTestResult, String> {
+    let config = SerialConfig {
+        port_name,
+        baud_rate,
+    };
+    state.0.lock().unwrap().run_loopback_test(config)
+}
+
+/// 连接串口
+#[tauri::command]
+fn connect_serial(
+    app: AppHandle,
+    port_name: String,
+    baud_rate: u32,
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+    profile_state: State<ProfileStoreState>,
+) -> Result<(), String> {
+    let config = SerialConfig {
+        port_name: port_name.clone(),
+        baud_rate,
+    };
+
+    // 连接串口，数据处理器随后重建，可以换用自动调节选出的队列容量
+    {
+        let mut serial_manager = serial_state.0.lock().unwrap();
+        serial_manager.apply_queue_capacity();
+        serial_manager.connect(config)?;
+    }
+
+    // 自动启动数据处理
+    let serial_manager = serial_state.0.lock().unwrap();
+    let data_queue = serial_manager.get_data_queue();
+    let thread_tuning = serial_manager.get_thread_tuning();
+    drop(serial_manager); // 释放锁
+
+    let processor = DataProcessor::new(data_queue);
+    processor.set_thread_tuning(thread_tuning);
+    apply_connection_profile(&processor, &port_name, &profile_state);
+    attach_outputs(&processor, &app);
+    processor.start();
+
+    let mut processor_guard = processor_state.lock();
+    *processor_guard = Some(processor);
+
+    println!("[Main] 串口连接成功，数据处理已自动启动");
+    Ok(())
+}
+
+/// 断开串口连接
+///
+/// 处理器和读取线程先从共享状态中取出，再在后台线程中等待其退出，
+/// 等待期间不阻塞主线程，也不占用状态锁。
+#[tauri::command]
+async fn disconnect_serial(
+    serial_state: State<'_, SerialManagerState>,
+    processor_state: State<'_, DataProcessorState>,
+    webhook_state: State<'_, WebhookState>,
+) -> Result<(), String> {
+    let processor = processor_state.lock().take();
+    let readers = serial_state.0.lock().unwrap().detach();
+    let dispatcher = webhook_state.0.lock().unwrap().as_ref().cloned();
+    tauri::async_runtime::spawn_blocking(move || {
+        // 停止数据处理
+        if let Some(processor) = processor {
+            stop_processor(processor, dispatcher);
+        }
+
+        // 断开串口连接
+        readers.stop();
+        println!("[Main] 串口连接已断开");
+    })
+    .await
+    .map_err(|e| format!("断开串口连接失败: {}", e))
+}
+
+/// 发送数据到串口
+#[tauri::command]
+fn send_serial_data(data: String, state: State<SerialManagerState>) -> Result<(), String> {
+    state.0.lock().unwrap().send_data(data)
+}
+
+/// 获取最新的N组原始数据，可按接收时间戳（毫秒）限定范围，最多返回 `MAX_QUERY_COUNT` 组
+#[tauri::command]
+fn get_latest_data(
+    count: usize,
+    since_timestamp: Option<u64>,
+    until_timestamp: Option<u64>,
+    state: State<SerialManagerState>,
+) -> Vec<VitalSigns> {
+    state.0.lock().unwrap().get_latest_data(
+        count.min(MAX_QUERY_COUNT),
+        since_timestamp,
+        until_timestamp,
+    )
+}
+
+/// 获取当前串口状态及数据流健康状态
+#[tauri::command]
+fn get_serial_status(state: State<SerialManagerState>) -> SerialStatusReport {
+    state.0.lock().unwrap().get_status_report()
+}
+
+/// 获取当前连接按类别统计的串口错误次数
+#[tauri::command]
+fn get_serial_error_counts(state: State<SerialManagerState>) -> types::SerialErrorCounts {
+    state.0.lock().unwrap().get_error_counts()
+}
+
+/// 获取性能指标（处理速率、串口吞吐量、队列到处理的延迟）
+#[tauri::command]
+fn get_performance_metrics(
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+) -> Result<PerformanceMetrics, String> {
+    collect_performance_metrics(&serial_state, &processor_state)
+        .ok_or_else(|| "数据处理器未启动".to_string())
+}
+
+/// 获取数据处理状态（空闲、处理中、压缩中或异常退出的错误信息）
+#[tauri::command]
+fn get_processing_status(state: State<DataProcessorState>) -> Result<ProcessingStatus, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_processing_status())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取实时数据包（最新的处理后数据、ECG统计、处理状态和性能指标）
+#[tauri::command]
+fn get_realtime_data(
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+) -> Result<types::RealtimeDataPacket, String> {
+    let performance_metrics = collect_performance_metrics(&serial_state, &processor_state)
+        .ok_or_else(|| "数据处理器未启动".to_string())?;
+    let processor_guard = processor_state.lock();
+    let processor = processor_guard
+        .as_ref()
+        .ok_or_else(|| "数据处理器未启动".to_string())?;
+    let vital_signs = processor
+        .get_processed_data(1, None, None)
+        .pop()
+        .ok_or_else(|| "没有可用的处理后数据".to_string())?;
+    Ok(types::RealtimeDataPacket {
+        vital_signs,
+        ecg_statistics: processor.get_ecg_statistics(),
+        processing_status: processor.get_processing_status(),
+        performance_metrics,
+    })
+}
+
+/// 设置数据处理跟不上采样速率时的反压策略
+#[tauri::command]
+fn set_backpressure_policy(
+    policy: types::BackpressurePolicy,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_backpressure_policy(policy);
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取反压策略
+#[tauri::command]
+fn get_backpressure_policy(
+    state: State<DataProcessorState>,
+) -> Result<types::BackpressurePolicy, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_backpressure_policy())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 运行处理性能测试，返回处理速率和每个采样的内存分配次数
+#[tauri::command]
+fn run_processing_benchmark(samples: Option<usize>) -> Result<types::ProcessingBenchmark, String> {
+    benchmark::run(samples.unwrap_or(10_000))
+}
+
+/// 获取当前连接的帧完整性统计（格式错误、校验失败、重新同步、丢帧）
+#[tauri::command]
+fn get_link_statistics(state: State<SerialManagerState>) -> types::LinkStatistics {
+    state.0.lock().unwrap().get_link_statistics()
+}
+
+/// 获取当前数据源的连接统计（连接时长、重连次数、采样总数、解析失败率、吞吐量和最近错误）
+#[tauri::command]
+fn get_connection_statistics(state: State<SerialManagerState>) -> types::ConnectionStatistics {
+    state.0.lock().unwrap().get_connection_statistics()
+}
+
+/// 获取当前连接的设备能力（设备支持、不支持和尚待确定的通道）
+#[tauri::command]
+fn get_device_capabilities(state: State<SerialManagerState>) -> types::DeviceCapabilities {
+    state.0.lock().unwrap().get_device_capabilities()
+}
+
+/// 获取设备固件版本及其与当前帧解析器的兼容性
+#[tauri::command]
+fn get_firmware_info(state: State<SerialManagerState>) -> firmware::FirmwareInfo {
+    state.0.lock().unwrap().get_firmware_info()
+}
+
+/// 设置固件兼容性表（各帧解析器要求的最低固件版本）
+#[tauri::command]
+fn set_firmware_compatibility(
+    table: Vec<firmware::FirmwareRequirement>,
+    state: State<SerialManagerState>,
+) -> Result<(), String> {
+    state.0.lock().unwrap().set_firmware_compatibility(table)
+}
+
+/// 获取固件兼容性表
+#[tauri::command]
+fn get_firmware_compatibility(
+    state: State<SerialManagerState>,
+) -> Vec<firmware::FirmwareRequirement> {
+    state.0.lock().unwrap().get_firmware_compatibility()
+}
+
+/// 设置串口错误恢复策略（读取超时、退避和重新连接），下次连接时生效
+#[tauri::command]
+fn set_serial_resilience_config(
+    config: types::SerialResilienceConfig,
+    state: State<SerialManagerState>,
+) -> Result<(), String> {
+    state.0.lock().unwrap().set_resilience_config(config)
+}
+
+/// 获取串口错误恢复策略
+#[tauri::command]
+fn get_serial_resilience_config(
+    state: State<SerialManagerState>,
+) -> types::SerialResilienceConfig {
+    state.0.lock().unwrap().get_resilience_config()
+}
+
+/// 设置串口读取线程和数据处理线程的优先级和CPU亲和性，下次连接或启动处理时生效
+#[tauri::command]
+fn set_thread_tuning_config(
+    config: types::ThreadTuningConfig,
+    state: State<SerialManagerState>,
+) -> Result<(), String> {
+    state.0.lock().unwrap().set_thread_tuning(config)
+}
+
+/// 获取线程调度配置
+#[tauri::command]
+fn get_thread_tuning_config(state: State<SerialManagerState>) -> types::ThreadTuningConfig {
+    state.0.lock().unwrap().get_thread_tuning()
+}
+
+/// 设置UDP数据源配置（绑定地址、组播组和帧解析器），下次连接时生效
+#[tauri::command]
+fn set_udp_config(
+    config: types::UdpConfig,
+    state: State<SerialManagerState>,
+) -> Result<(), String> {
+    state.0.lock().unwrap().set_udp_config(config)
+}
+
+/// 获取UDP数据源配置
+#[tauri::command]
+fn get_udp_config(state: State<SerialManagerState>) -> types::UdpConfig {
+    state.0.lock().unwrap().get_udp_config()
+}
+
+/// 设置模拟数据源配置（心律失常场景或压力测试），下次连接时生效
+#[tauri::command]
+fn set_simulator_config(
+    config: arrhythmia_sim::SimulatorConfig,
+    state: State<SerialManagerState>,
+) -> Result<(), String> {
+    state.0.lock().unwrap().set_simulator_config(config)
+}
+
+/// 获取模拟数据源配置
+#[tauri::command]
+fn get_simulator_config(state: State<SerialManagerState>) -> arrhythmia_sim::SimulatorConfig {
+    state.0.lock().unwrap().get_simulator_config()
+}
+
+/// 获取心律失常场景库
+#[tauri::command]
+fn list_arrhythmia_scenarios() -> Vec<arrhythmia_sim::ScenarioInfo> {
+    arrhythmia_sim::scenarios()
+}
+
+/// 获取模拟数据源本次连接记录的标注心搏（R波时间戳和类别）
+#[tauri::command]
+fn get_simulation_ground_truth(
+    state: State<SerialManagerState>,
+) -> Vec<arrhythmia_sim::GroundTruthBeat> {
+    state.0.lock().unwrap().get_ground_truth()
+}
+
+/// 用模拟数据源的标注心搏评估实时心搏检测（检出率、阳性预测值和分类准确率）
+#[tauri::command]
+fn validate_beat_detection(
+    tolerance_ms: Option<u64>,
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+) -> Result<arrhythmia_sim::DetectorValidation, String> {
+    let truth = serial_state.0.lock().unwrap().get_ground_truth();
+    let processor_guard = processor_state.lock();
+    let processor = processor_guard
+        .as_ref()
+        .ok_or_else(|| "数据处理器未启动".to_string())?;
+    let processed_until = processor
+        .last_received_at()
+        .ok_or_else(|| "没有可用的心电数据".to_string())?;
+    let detected = processor.get_beats(None, None);
+    arrhythmia_sim::validate(&truth, &detected, processed_until, tolerance_ms)
+}
+
+/// 获取模拟数据源最近一次压力测试的报告（各采样率台阶的生成数、丢弃数和开始丢弃时的采样率）
+#[tauri::command]
+fn get_stress_report(state: State<SerialManagerState>) -> stress_sim::StressReport {
+    state.0.lock().unwrap().get_stress_report()
+}
+
+/// 设置远程串口配置（ser2net主机、端口和是否使用RFC2217），下次连接时生效
+#[tauri::command]
+fn set_tcp_serial_config(
+    config: types::TcpSerialConfig,
+    state: State<SerialManagerState>,
+) -> Result<(), String> {
+    state.0.lock().unwrap().set_tcp_serial_config(config)
+}
+
+/// 获取远程串口配置
+#[tauri::command]
+fn get_tcp_serial_config(state: State<SerialManagerState>) -> types::TcpSerialConfig {
+    state.0.lock().unwrap().get_tcp_serial_config()
+}
+
+/// 列出已连接的USB HID设备
+#[tauri::command]
+fn get_available_hid_devices() -> Result<Vec<types::HidDeviceInfo>, String> {
+    hid_reader::list_devices()
+}
+
+/// 设置USB HID数据源配置（厂商ID、产品ID和报告格式），下次连接时生效
+#[tauri::command]
+fn set_hid_config(
+    config: types::HidConfig,
+    state: State<SerialManagerState>,
+) -> Result<(), String> {
+    state.0.lock().unwrap().set_hid_config(config)
+}
+
+/// 获取USB HID数据源配置
+#[tauri::command]
+fn get_hid_config(state: State<SerialManagerState>) -> types::HidConfig {
+    state.0.lock().unwrap().get_hid_config()
+}
+
+/// 设置记录文件数据源配置（文件路径、格式和CSV列映射），下次连接时生效
+#[tauri::command]
+fn set_file_tail_config(
+    config: types::FileTailConfig,
+    state: State<SerialManagerState>,
+) -> Result<(), String> {
+    state.0.lock().unwrap().set_file_tail_config(config)
+}
+
+/// 获取记录文件数据源配置
+#[tauri::command]
+fn get_file_tail_config(state: State<SerialManagerState>) -> types::FileTailConfig {
+    state.0.lock().unwrap().get_file_tail_config()
+}
+
+/// 设置回放数据源配置（录制文件、采样率和速度），下次连接时生效
+#[tauri::command]
+fn set_playback_config(
+    config: types::PlaybackConfig,
+    state: State<SerialManagerState>,
+) -> Result<(), String> {
+    state.0.lock().unwrap().set_playback_config(config)
+}
+
+/// 获取回放数据源配置
+#[tauri::command]
+fn get_playback_config(state: State<SerialManagerState>) -> types::PlaybackConfig {
+    state.0.lock().unwrap().get_playback_config()
+}
+
+/// 设置回放速度倍数（0.1到64倍），正在回放时立即生效
+#[tauri::command]
+fn set_playback_speed(speed: f64, state: State<SerialManagerState>) -> Result<(), String> {
+    state.0.lock().unwrap().set_playback_speed(speed)
+}
+
+/// 跳转到录制中的指定时间（距录制开始的毫秒数）
+#[tauri::command]
+fn seek_playback(timestamp: u64, state: State<SerialManagerState>) -> Result<(), String> {
+    state.0.lock().unwrap().seek_playback(timestamp)
+}
+
+/// 获取当前回放进度
+#[tauri::command]
+fn get_playback_position(
+    state: State<SerialManagerState>,
+) -> Result<types::PlaybackPosition, String> {
+    state.0.lock().unwrap().get_playback_position()
+}
+
+/// 获取处理后的最新数据，可按时间戳（毫秒）限定范围，最多返回 `MAX_QUERY_COUNT` 组
+#[tauri::command]
+fn get_processed_data(
+    count: usize,
+    since_timestamp: Option<u64>,
+    until_timestamp: Option<u64>,
+    state: State<DataProcessorState>,
+) -> Vec<ProcessedVitalSigns> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_processed_data(
+            count.min(MAX_QUERY_COUNT),
+            since_timestamp,
+            until_timestamp,
+        )
+    } else {
+        Vec::new()
+    }
+}
+
+/// 获取当前心率（含采样时间戳和读数质量）
+#[tauri::command]
+fn get_current_heart_rate(
+    state: State<DataProcessorState>,
+) -> Result<types::CurrentVitalValue, String> {
+    let processor_guard = state.lock();
+    let processor = processor_guard
+        .as_ref()
+        .ok_or_else(|| "数据处理器未启动".to_string())?;
+    Ok(processor.get_current_heart_rate())
+}
+
+/// 获取当前血氧饱和度（含采样时间戳和读数质量）
+#[tauri::command]
+fn get_current_spo2(state: State<DataProcessorState>) -> Result<types::CurrentVitalValue, String> {
+    let processor_guard = state.lock();
+    let processor = processor_guard
+        .as_ref()
+        .ok_or_else(|| "数据处理器未启动".to_string())?;
+    Ok(processor.get_current_spo2())
+}
+
+/// 获取当前体温（含采样时间戳和读数质量）
+#[tauri::command]
+fn get_current_temperature(
+    state: State<DataProcessorState>,
+) -> Result<types::CurrentVitalValue, String> {
+    let processor_guard = state.lock();
+    let processor = processor_guard
+        .as_ref()
+        .ok_or_else(|| "数据处理器未启动".to_string())?;
+    Ok(processor.get_current_temperature())
+}
+
+/// 启动数据处理
+#[tauri::command]
+fn start_data_processing(
+    app: AppHandle,
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+    profile_state: State<ProfileStoreState>,
+) -> Result<(), String> {
+    let serial_manager = serial_state.0.lock().unwrap();
+    let data_queue = serial_manager.get_data_queue();
+    let current_config = serial_manager.get_current_config();
+    let thread_tuning = serial_manager.get_thread_tuning();
+    let capabilities = serial_manager.get_device_capabilities();
+    drop(serial_manager);
+
+    let processor = DataProcessor::new(data_queue);
+    processor.set_thread_tuning(thread_tuning);
+    if let Some(config) = current_config {
+        apply_connection_profile(&processor, &config.port_name, &profile_state);
+    }
+    if capabilities.discovery_complete {
+        processor.apply_device_capabilities(&capabilities);
+    }
+    attach_outputs(&processor, &app);
+    processor.start();
+
+    let mut processor_guard = processor_state.lock();
+    *processor_guard = Some(processor);
+
+    Ok(())
+}
+
+/// 设置报警系统通知（按优先级配置是否通知、是否播放提示音）
+#[tauri::command]
+fn set_notification_settings(
+    settings: NotificationSettings,
+    state: State<NotificationSettingsState>,
+) {
+    *state.0.lock().unwrap() = settings;
+}
+
+/// 获取报警系统通知设置
+#[tauri::command]
+fn get_notification_settings(state: State<NotificationSettingsState>) -> NotificationSettings {
+    state.0.lock().unwrap().clone()
+}
+
+/// 设置看门狗配置（停滞超时和是否自动重启）
+#[tauri::command]
+fn set_watchdog_config(
+    config: WatchdogConfig,
+    state: State<WatchdogConfigState>,
+) -> Result<(), String> {
+    watchdog::validate_config(&config)?;
+    *state.0.lock().unwrap() = config;
+    Ok(())
+}
+
+/// 获取看门狗配置
+#[tauri::command]
+fn get_watchdog_config(state: State<WatchdogConfigState>) -> WatchdogConfig {
+    state.0.lock().unwrap().clone()
+}
+
+/// 设置时间同步（NTP服务器、查询间隔和偏差警告阈值）
+#[tauri::command]
+fn set_time_sync_config(
+    config: TimeSyncConfig,
+    state: State<TimeSyncState>,
+) -> Result<(), String> {
+    time_sync::validate_config(&config)?;
+    *state.0.lock().unwrap() = config;
+    Ok(())
+}
+
+/// 获取时间同步配置
+#[tauri::command]
+fn get_time_sync_config(state: State<TimeSyncState>) -> TimeSyncConfig {
+    state.0.lock().unwrap().clone()
+}
+
+/// 获取时间同步状态（最近一次测得的时钟偏差和往返时延）
+#[tauri::command]
+fn get_time_sync_status(state: State<TimeSyncState>) -> TimeSyncStatus {
+    state.1.lock().unwrap().status()
+}
+
+/// 开启或关闭缓冲区自动调节，开启期间事件推送的每批采样数由自动调节决定
+#[tauri::command]
+fn set_auto_tune_enabled(enabled: bool, state: State<AutoTuneState>) {
+    state.0.lock().unwrap().set_enabled(enabled);
+    println!("[AutoTune] 自动调节已{}", if enabled { "开启" } else { "关闭" });
+}
+
+/// 获取缓冲区自动调节状态（观测值和选出的设置）
+#[tauri::command]
+fn get_auto_tune_status(state: State<AutoTuneState>) -> AutoTuneStatus {
+    state.0.lock().unwrap().status()
+}
+
+/// 设置诊断输出（热路径详细输出开关和限流间隔）
+#[tauri::command]
+fn set_diagnostics_config(config: diagnostics::DiagnosticsConfig) -> Result<(), String> {
+    diagnostics::set_config(&config)
+}
+
+/// 获取诊断输出配置
+#[tauri::command]
+fn get_diagnostics_config() -> diagnostics::DiagnosticsConfig {
+    diagnostics::get_config()
+}
+
+/// 获取前端接口的数据格式版本和字段命名方式
+#[tauri::command]
+fn get_ipc_schema() -> ipc_schema::IpcSchema {
+    ipc_schema::schema()
+}
+
+/// 设置实时数据事件推送（开关、推送频率、每批采样数上限）
+#[tauri::command]
+fn set_event_stream_config(
+    config: EventStreamConfig,
+    state: State<EventStreamConfigState>,
+) -> Result<(), String> {
+    event_stream::validate_config(&config)?;
+    *state.0.lock().unwrap() = config;
+    Ok(())
+}
+
+/// 获取实时数据事件推送配置
+#[tauri::command]
+fn get_event_stream_config(state: State<EventStreamConfigState>) -> EventStreamConfig {
+    state.0.lock().unwrap().clone()
+}
+
+/// 订阅指定体征，后台按 `rate_hz` 推送到事件 `vital-subscription-<订阅ID>`
+///
+/// 可订阅的内置体征见 `subscriptions::BUILTIN_VITALS`，扩展通道用通道ID
+#[tauri::command]
+fn subscribe_vitals(
+    vitals: Vec<String>,
+    rate_hz: u32,
+    serial_state: State<SerialManagerState>,
+    subscription_state: State<SubscriptionState>,
+) -> Result<u64, String> {
+    let extension_channels: Vec<String> = serial_state
+        .0
+        .lock()
+        .unwrap()
+        .get_channel_descriptors()
+        .into_iter()
+        .map(|descriptor| descriptor.id)
+        .collect();
+    subscription_state
+        .0
+        .lock()
+        .unwrap()
+        .subscribe(vitals, rate_hz, &extension_channels)
+}
+
+/// 取消体征订阅
+#[tauri::command]
+fn unsubscribe_vitals(subscription_id: u64, state: State<SubscriptionState>) -> Result<(), String> {
+    state.0.lock().unwrap().unsubscribe(subscription_id)
+}
+
+/// 获取当前的体征订阅
+#[tauri::command]
+fn get_vital_subscriptions(state: State<SubscriptionState>) -> Vec<SubscriptionInfo> {
+    state.0.lock().unwrap().list()
+}
+
+/// 停止数据处理，处理器取出后在后台线程中等待处理线程退出
+#[tauri::command]
+async fn stop_data_processing(
+    state: State<'_, DataProcessorState>,
+    webhook_state: State<'_, WebhookState>,
+) -> Result<(), String> {
+    let Some(processor) = state.lock().take() else {
+        return Ok(());
+    };
+    let dispatcher = webhook_state.0.lock().unwrap().as_ref().cloned();
+    tauri::async_runtime::spawn_blocking(move || stop_processor(processor, dispatcher))
+        .await
+        .map_err(|e| format!("停止数据处理失败: {}", e))
+}
+
+/// 重置处理状态（ECG动态范围、体温历史、LTTB缓冲区和滚动统计），数据源保持连接
+#[tauri::command]
+fn reset_processing_state(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.reset_processing_state();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 设置心率平均策略
+#[tauri::command]
+fn set_heart_rate_averaging(
+    averaging: HeartRateAveraging,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_heart_rate_averaging(averaging)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取当前心率平均策略
+#[tauri::command]
+fn get_heart_rate_averaging(state: State<DataProcessorState>) -> Result<HeartRateAveraging, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_heart_rate_averaging())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 设置血氧验证和平均配置
+#[tauri::command]
+fn set_spo2_config(
+    config: types::Spo2Config,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_spo2_config(config)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取当前血氧验证和平均配置
+#[tauri::command]
+fn get_spo2_config(state: State<DataProcessorState>) -> Result<types::Spo2Config, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_spo2_config())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取RR间期序列（心搏间期图）
+///
+/// `count` 限制返回的心搏数量，`start`/`end` 为毫秒时间戳范围，均可省略。
+#[tauri::command]
+fn get_rr_tachogram(
+    count: Option<usize>,
+    start: Option<u64>,
+    end: Option<u64>,
+    state: State<DataProcessorState>,
+) -> Vec<types::RrIntervalPoint> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_rr_tachogram(count, start, end)
+    } else {
+        Vec::new()
+    }
+}
+
+/// 获取心搏检测结果（时间戳、RR间期、分类、幅度），`start`/`end` 为毫秒时间戳范围
+#[tauri::command]
+fn get_beats(
+    start: Option<u64>,
+    end: Option<u64>,
+    state: State<DataProcessorState>,
+) -> Vec<types::BeatRecord> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_beats(start, end)
+    } else {
+        Vec::new()
+    }
+}
+
+/// 获取间歇性测量（血糖等）历史记录
+///
+/// `kind` 为测量类型，`start`/`end` 为毫秒时间戳范围，均可省略。
+#[tauri::command]
+fn get_measurement_history(
+    kind: Option<types::MeasurementKind>,
+    start: Option<u64>,
+    end: Option<u64>,
+    state: State<DataProcessorState>,
+) -> Vec<types::MeasurementRecord> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_measurement_history(kind, start, end)
+    } else {
+        Vec::new()
+    }
+}
+
+/// 获取Poincaré散点图数据
+///
+/// `window` 为参与计算的最近RR间期数量。
+#[tauri::command]
+fn get_poincare_points(
+    window: usize,
+    state: State<DataProcessorState>,
+) -> Result<types::PoincarePlot, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_poincare_plot(window))
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取HRV频域分析结果
+///
+/// `window_seconds` 为分析窗口长度（默认300秒），`method` 默认使用Welch法。
+#[tauri::command]
+fn get_hrv_spectrum(
+    window_seconds: Option<f64>,
+    method: Option<types::SpectralMethod>,
+    state: State<DataProcessorState>,
+) -> Result<types::HrvSpectrum, String> {
+    let window_seconds = window_seconds.unwrap_or(300.0);
+    if window_seconds <= 0.0 {
+        return Err("分析窗口必须大于0秒".to_string());
+    }
+
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_hrv_spectrum(window_seconds, method.unwrap_or_default()))
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取ECG统计信息（心率统计、RR变异性、信号质量、起搏心搏占比）
+#[tauri::command]
+fn get_ecg_statistics(state: State<DataProcessorState>) -> Result<types::EcgStatistics, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_ecg_statistics())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 设置通道增益/反相/偏移
+///
+/// 立即作用于正在运行的数据处理器，并保存到当前串口的连接配置档案。
+#[tauri::command]
+fn set_channel_adjustment(
+    channel: String,
+    adjustment: ChannelAdjustment,
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+    profile_state: State<ProfileStoreState>,
+) -> Result<(), String> {
+    let adjustments = {
+        let processor_guard = processor_state.lock();
+        let processor = processor_guard
+            .as_ref()
+            .ok_or_else(|| "数据处理器未启动".to_string())?;
+        processor.set_channel_adjustment(&channel, adjustment)?;
+        processor.get_channel_adjustments()
+    };
+
+    // 保存到当前连接的配置档案
+    update_current_profile(&serial_state, &profile_state, |profile| {
+        profile.channel_adjustments = adjustments;
+    })
+}
+
+/// 设置ECG心搏检测参数（波峰阈值、阈值更新间隔和缓冲区大小），并保存到当前串口的连接配置
+#[tauri::command]
+fn set_ecg_processing_params(
+    params: types::EcgProcessingParams,
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+    profile_state: State<ProfileStoreState>,
+) -> Result<(), String> {
+    {
+        let processor_guard = processor_state.lock();
+        let processor = processor_guard
+            .as_ref()
+            .ok_or_else(|| "数据处理器未启动".to_string())?;
+        processor.set_ecg_processing_params(params.clone())?;
+    }
+
+    // 保存到当前连接的配置档案
+    update_current_profile(&serial_state, &profile_state, |profile| {
+        profile.ecg_processing_params = Some(params);
+    })
+}
+
+/// 获取当前ECG心搏检测参数
+#[tauri::command]
+fn get_ecg_processing_params(
+    state: State<DataProcessorState>,
+) -> Result<types::EcgProcessingParams, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_ecg_processing_params())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 开始体温两点校准
+#[tauri::command]
+fn start_temp_calibration(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.start_temp_calibration();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 采集体温校准点，`reference_value` 为参考温度计读数（摄氏度）
+#[tauri::command]
+fn capture_calibration_point(
+    reference_value: f64,
+    state: State<DataProcessorState>,
+) -> Result<types::TemperatureCalibrationPoint, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.capture_calibration_point(reference_value)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 完成体温校准
+///
+/// 计算新的比例系数和偏移量，立即应用并保存到当前串口的连接配置档案。
+#[tauri::command]
+fn finish_temp_calibration(
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+    profile_state: State<ProfileStoreState>,
+) -> Result<types::TemperatureCalibration, String> {
+    let calibration = {
+        let processor_guard = processor_state.lock();
+        let processor = processor_guard
+            .as_ref()
+            .ok_or_else(|| "数据处理器未启动".to_string())?;
+        processor.finish_temp_calibration()?
+    };
+
+    update_current_profile(&serial_state, &profile_state, |profile| {
+        profile.temperature_calibration = Some(calibration.clone());
+    })?;
+
+    Ok(calibration)
+}
+
+/// 获取当前的通道增益/反相/偏移设置
+#[tauri::command]
+fn get_channel_adjustments(
+    state: State<DataProcessorState>,
+) -> Result<BTreeMap<String, ChannelAdjustment>, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_channel_adjustments())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取所有连接配置档案
+#[tauri::command]
+fn get_connection_profiles(
+    state: State<ProfileStoreState>,
+) -> Result<Vec<ConnectionProfile>, String> {
+    let store_guard = state.0.lock().unwrap();
+    if let Some(store) = store_guard.as_ref() {
+        store.load_profiles()
+    } else {
+        Err("连接配置存储未初始化".to_string())
+    }
+}
+
+/// 删除指定串口的连接配置档案
+#[tauri::command]
+fn delete_connection_profile(
+    port_name: String,
+    state: State<ProfileStoreState>,
+) -> Result<(), String> {
+    let store_guard = state.0.lock().unwrap();
+    if let Some(store) = store_guard.as_ref() {
+        store.delete_profile(&port_name)
+    } else {
+        Err("连接配置存储未初始化".to_string())
+    }
+}
+
+/// 获取呼吸数据（呼吸频率、呼吸暂停标记和压缩后的呼吸波形）
+#[tauri::command]
+fn get_respiration_data(
+    state: State<DataProcessorState>,
+) -> Result<types::RespirationData, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_respiration_data())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取二氧化碳监测数据（EtCO2、FiCO2、呼吸频率和压缩后的二氧化碳波形）
+#[tauri::command]
+fn get_capnography_data(
+    state: State<DataProcessorState>,
+) -> Result<types::CapnographyData, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_capnography_data())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 添加过敏或病史条目
+#[tauri::command]
+fn add_patient_entry(
+    list: EntryList,
+    entry: CodedEntry,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<CodedEntry, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.add_entry(list, entry)
+}
+
+/// 按ID更新过敏或病史条目
+#[tauri::command]
+fn update_patient_entry(
+    list: EntryList,
+    entry: CodedEntry,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<(), String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.update_entry(list, entry)
+}
+
+/// 按ID删除过敏或病史条目
+#[tauri::command]
+fn remove_patient_entry(
+    list: EntryList,
+    id: String,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<(), String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.remove_entry(list, &id)
+}
+
+/// 添加用药条目
+#[tauri::command]
+fn add_medication(
+    medication: Medication,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<Medication, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.add_medication(medication)
+}
+
+/// 按ID更新用药条目
+#[tauri::command]
+fn update_medication(
+    medication: Medication,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<(), String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.update_medication(medication)
+}
+
+/// 按ID删除用药条目
+#[tauri::command]
+fn remove_medication(
+    id: String,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<(), String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.remove_medication(&id)
+}
+
+/// 获取由过敏史和用药推导的临床标记（如β受体阻滞剂）
+#[tauri::command]
+fn get_clinical_flags(
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<ClinicalFlags, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.load_patient_info().map(|info| info.clinical_flags())
+}
+
+/// 列出所有患者记录
+#[tauri::command]
+fn list_patients(
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<Vec<PatientInfo>, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.list_patients()
+}
+
+/// 添加一个患者记录，不改变当前患者
+#[tauri::command]
+fn add_patient(
+    patient_info: PatientInfo,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<PatientInfo, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.add_patient(&patient_info)
+}
+
+/// 按姓名、出生日期和电话查找可能重复的患者
+#[tauri::command]
+fn find_duplicate_patients(
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<Vec<patient_merge::DuplicateCandidate>, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    Ok(patient_merge::find_duplicates(&store.list_patients()?))
+}
+
+/// 把患者 `merge_id` 合并到患者 `keep_id`，`dry_run` 为真时只预览结果；实际合并写入审计日志
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn merge_patients(
+    keep_id: String,
+    merge_id: String,
+    dry_run: bool,
+    operator: String,
+    session_state: State<SessionStoreState>,
+    patient_state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+    audit_state: State<AuditStoreState>,
+) -> Result<patient_merge::MergeReport, String> {
+    check_patient_lock(&lock_state)?;
+    if !dry_run && operator.trim().is_empty() {
+        return Err("操作员不能为空".to_string());
+    }
+    let session_guard = session_state.0.lock().unwrap();
+    let session_store = session_guard.as_ref().ok_or("会话存储未初始化")?;
+    let patient_guard = patient_state.0.lock().unwrap();
+    let patient_store = patient_guard.as_ref().ok_or("患者存储未初始化")?;
+    let report =
+        patient_merge::merge_patients(&keep_id, &merge_id, dry_run, patient_store, session_store)?;
+
+    if !dry_run {
+        let audit_guard = audit_state.0.lock().unwrap();
+        let audit_store = audit_guard.as_ref().ok_or("审计日志未初始化")?;
+        audit_store.append(&operator, "merge_patients", report.summary())?;
+    }
+    Ok(report)
+}
+
+/// 生成患者腕带二维码的内容
+#[tauri::command]
+fn get_patient_code(
+    patient_id: String,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<String, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.patient_code_payload(&patient_id)
+}
+
+/// 按扫描到的腕带条码识别患者并设为当前患者，也用于键盘方式输入的扫码枪
+#[tauri::command]
+fn identify_patient_from_code(
+    payload: String,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<PatientInfo, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.identify_from_code(&payload)
+}
+
+/// 处理串口扫描枪扫描到的条码
+fn identify_scanned_code(app: &AppHandle, code: &str) -> Result<PatientInfo, String> {
+    check_patient_lock(&app.state::<PatientLockState>())?;
+    let patient_state = app.state::<PatientStoreState>();
+    let store_guard = patient_state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.identify_from_code(code)
+}
+
+/// 启动串口条码扫描枪，识别结果通过 `patient-identified` / `patient-code-error` 事件推送
+#[tauri::command]
+fn start_code_scanner(
+    port_name: String,
+    baud_rate: u32,
+    app: AppHandle,
+    state: State<CodeScannerState>,
+) -> Result<(), String> {
+    let mut scanner_guard = state.0.lock().unwrap();
+    if let Some(scanner) = scanner_guard.take() {
+        scanner.stop();
+    }
+    let scanner = CodeScanner::new(port_name, baud_rate);
+    scanner.start(Box::new(move |code| {
+        let emitted = match identify_scanned_code(&app, &code) {
+            Ok(info) => app.emit(PATIENT_IDENTIFIED_EVENT, ipc_schema::versioned(info)),
+            Err(e) => {
+                eprintln!("[Main] 扫码识别患者失败: {}", e);
+                app.emit(PATIENT_CODE_ERROR_EVENT, ipc_schema::versioned(e))
+            }
+        };
+        if let Err(e) = emitted {
+            eprintln!("[Main] 推送扫码结果失败: {}", e);
+        }
+    }))?;
+    *scanner_guard = Some(scanner);
+    Ok(())
+}
+
+/// 停止串口条码扫描枪
+#[tauri::command]
+fn stop_code_scanner(state: State<CodeScannerState>) {
+    if let Some(scanner) = state.0.lock().unwrap().take() {
+        scanner.stop();
+    }
+}
+
+/// 保存患者照片（PNG或JPEG），照片会被缩小后保存
+#[tauri::command]
+fn set_patient_photo(
+    patient_id: String,
+    bytes: Vec<u8>,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<(), String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.set_photo(&patient_id, &bytes)
+}
+
+/// 获取患者照片（PNG），没有照片时返回None
+#[tauri::command]
+fn get_patient_photo(
+    patient_id: String,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<Option<Vec<u8>>, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.get_photo(&patient_id)
+}
+
+/// 为当前患者记录一条知情同意
+#[tauri::command]
+fn record_consent(
+    consent: ConsentRecord,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<ConsentRecord, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.record_consent(consent)
+}
+
+/// 获取当前患者的知情同意记录
+#[tauri::command]
+fn get_consents(
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<Vec<ConsentRecord>, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.get_consents()
+}
+
+/// 获取当前患者的ID，用于标记会话归属
+fn current_patient_id(patient_state: &State<PatientStoreState>) -> Option<String> {
+    let store_guard = patient_state.0.lock().unwrap();
+    store_guard.as_ref()?.current_patient_id().ok().flatten()
+}
+
+/// 找出当前患者缺少有效知情同意的数据类型，未选择患者时所有数据类型都视为缺少同意
+fn current_patient_missing_consents(
+    patient_state: &State<PatientStoreState>,
+    scopes: &[ConsentScope],
+) -> Result<Vec<ConsentScope>, String> {
+    let store_guard = patient_state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    match store.current_patient_id()? {
+        Some(patient_id) => store.missing_consents(&patient_id, scopes),
+        None => Ok(scopes.to_vec()),
+    }
+}
+
+/// 检查患者数据是否已解锁，已解锁时刷新空闲计时
+fn check_patient_lock(lock_state: &State<PatientLockState>) -> Result<(), String> {
+    let mut lock_guard = lock_state.0.lock().unwrap();
+    let lock = lock_guard.as_mut().ok_or("患者数据锁未初始化")?;
+    lock.check()
+}
+
+/// PIN验证失败时写入审计日志，未提供操作员时记为 `unknown`
+fn audit_pin_failure(
+    audit_state: &State<AuditStoreState>,
+    operator: Option<&str>,
+    action: &str,
+    error: &str,
+) {
+    let operator = operator
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+        .unwrap_or("unknown");
+    let audit_guard = audit_state.0.lock().unwrap();
+    let result = match audit_guard.as_ref() {
+        Some(audit_store) => audit_store.append(operator, action, error.to_string()),
+        None => Err("审计日志未初始化".to_string()),
+    };
+    if let Err(e) = result {
+        eprintln!("[Main] 记录PIN验证失败到审计日志失败: {}", e);
+    }
+}
+
+/// 导出前核对知情同意，缺少同意时须由管理员输入患者数据PIN强制导出
+///
+/// PIN验证失败和强制导出都记入审计日志，强制导出在写出文件前记录。
+///
+/// # 参数
+/// * `missing` - 缺少有效知情同意的数据类型
+/// * `override_operator` - 强制导出的管理员
+/// * `override_pin` - 管理员输入的患者数据PIN
+/// * `detail` - 审计日志中记录的导出内容
+fn authorize_export(
+    missing: &[ConsentScope],
+    override_operator: Option<&str>,
+    override_pin: Option<&str>,
+    detail: &str,
+    lock_state: &State<PatientLockState>,
+    audit_state: &State<AuditStoreState>,
+) -> Result<(), String> {
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let scopes: Vec<&str> = missing.iter().map(|scope| scope.label()).collect();
+    let operator = override_operator
+        .map(str::trim)
+        .filter(|operator| !operator.is_empty())
+        .ok_or_else(|| format!("缺少有效的知情同意: {}", scopes.join("、")))?;
+    let pin = override_pin.ok_or("强制导出需要输入管理员PIN")?;
+    let authorized = {
+        let mut lock_guard = lock_state.0.lock().unwrap();
+        let lock = lock_guard.as_mut().ok_or("患者数据锁未初始化")?;
+        lock.authorize(pin)
+    };
+    if let Err(e) = authorized {
+        audit_pin_failure(
+            audit_state,
+            Some(operator),
+            "export_consent_override_failed",
+            &e,
+        );
+        return Err(format!("管理员PIN验证失败: {}", e));
+    }
+
+    let audit_guard = audit_state.0.lock().unwrap();
+    let audit_store = audit_guard.as_ref().ok_or("审计日志未初始化")?;
+    audit_store.append(
+        operator,
+        "export_consent_override",
+        format!("{}，缺少同意: {}", detail, scopes.join("、")),
+    )
+}
+
+/// 用PIN解锁患者数据，验证失败写入审计日志
+#[tauri::command]
+fn unlock_patient_data(
+    pin: String,
+    operator: Option<String>,
+    state: State<PatientLockState>,
+    audit_state: State<AuditStoreState>,
+) -> Result<(), String> {
+    let mut lock_guard = state.0.lock().unwrap();
+    let lock = lock_guard.as_mut().ok_or("患者数据锁未初始化")?;
+    let result = lock.unlock(&pin);
+    drop(lock_guard);
+    if let Err(e) = &result {
+        audit_pin_failure(
+            &audit_state,
+            operator.as_deref(),
+            "unlock_patient_data_failed",
+            e,
+        );
+    }
+    result
+}
+
+/// 设置文件无法读取时重置患者数据锁，重置后需要重新设置PIN；操作写入审计日志
+#[tauri::command]
+fn reset_patient_data_lock(
+    operator: String,
+    state: State<PatientLockState>,
+    audit_state: State<AuditStoreState>,
+) -> Result<(), String> {
+    if operator.trim().is_empty() {
+        return Err("操作员不能为空".to_string());
+    }
+    let audit_guard = audit_state.0.lock().unwrap();
+    let audit_store = audit_guard.as_ref().ok_or("审计日志未初始化")?;
+    let mut lock_guard = state.0.lock().unwrap();
+    let lock = lock_guard.as_mut().ok_or("患者数据锁未初始化")?;
+    let settings_error = lock
+        .status()
+        .settings_error
+        .ok_or("患者数据锁设置正常，不需要重置")?;
+    audit_store.append(
+        &operator,
+        "reset_patient_data_lock",
+        format!("重置无法读取的患者数据锁设置: {}", settings_error),
+    )?;
+    lock.reset_unreadable()
+}
+
+/// 立即锁定患者数据
+#[tauri::command]
+fn lock_patient_data(state: State<PatientLockState>) {
+    if let Some(lock) = state.0.lock().unwrap().as_mut() {
+        lock.lock();
+    }
+}
+
+/// 设置、修改或取消（`new_pin` 为空）患者数据PIN，失败写入审计日志
+#[tauri::command]
+fn set_patient_data_pin(
+    current_pin: Option<String>,
+    new_pin: Option<String>,
+    operator: Option<String>,
+    state: State<PatientLockState>,
+    audit_state: State<AuditStoreState>,
+) -> Result<(), String> {
+    let mut lock_guard = state.0.lock().unwrap();
+    let lock = lock_guard.as_mut().ok_or("患者数据锁未初始化")?;
+    let result = lock.set_pin(current_pin.as_deref(), new_pin.as_deref());
+    drop(lock_guard);
+    if let Err(e) = &result {
+        audit_pin_failure(
+            &audit_state,
+            operator.as_deref(),
+            "set_patient_data_pin_failed",
+            e,
+        );
+    }
+    result
+}
+
+/// 设置患者数据空闲自动锁定时长（秒）
+#[tauri::command]
+fn set_patient_data_lock_timeout(
+    seconds: u64,
+    state: State<PatientLockState>,
+) -> Result<(), String> {
+    let mut lock_guard = state.0.lock().unwrap();
+    let lock = lock_guard.as_mut().ok_or("患者数据锁未初始化")?;
+    lock.set_idle_timeout(seconds)
+}
+
+/// 获取患者数据锁状态
+#[tauri::command]
+fn get_patient_data_lock_status(
+    state: State<PatientLockState>,
+) -> Result<PatientLockStatus, String> {
+    let lock_guard = state.0.lock().unwrap();
+    let lock = lock_guard.as_ref().ok_or("患者数据锁未初始化")?;
+    Ok(lock.status())
+}
+
+/// 保存患者信息
+#[tauri::command]
+fn save_patient_info(
+    patient_info: PatientInfo,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<(), String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    if let Some(store) = store_guard.as_ref() {
+        store.save_patient_info(&patient_info)
+    } else {
+        Err("患者存储未初始化".to_string())
+    }
+}
+
+/// 加载患者信息
+#[tauri::command]
+fn load_patient_info(
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<PatientInfo, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    if let Some(store) = store_guard.as_ref() {
+        store.load_patient_info()
+    } else {
+        Err("患者存储未初始化".to_string())
+    }
+}
+
+/// 删除患者信息
+#[tauri::command]
+fn delete_patient_info(
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<(), String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    if let Some(store) = store_guard.as_ref() {
+        store.delete_patient_info()
+    } else {
+        Err("患者存储未初始化".to_string())
+    }
+}
+
+/// 获取LTTB压缩后的ECG数据
+#[tauri::command]
+fn get_lttb_compressed_data(state: State<DataProcessorState>) -> Vec<types::LttbDataPoint> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_lttb_compressed_data()
+    } else {
+        Vec::new()
+    }
+}
+
+/// 获取最近一次无创血压测量结果（含测量时间戳）
+#[tauri::command]
+fn get_blood_pressure(
+    state: State<DataProcessorState>,
+) -> Result<types::BloodPressureReading, String> {
+    let processor_guard = state.lock();
+    let processor = processor_guard
+        .as_ref()
+        .ok_or_else(|| "数据处理器未启动".to_string())?;
+    processor
+        .get_latest_blood_pressure()
+        .ok_or_else(|| "没有可用的血压数据".to_string())
+}
+
+/// 获取无创血压测量历史，`start`/`end` 为毫秒时间戳范围，均可省略
+#[tauri::command]
+fn get_bp_history(
+    start: Option<u64>,
+    end: Option<u64>,
+    state: State<DataProcessorState>,
+) -> Vec<types::BloodPressureReading> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_bp_history(start, end)
+    } else {
+        Vec::new()
+    }
+}
+
+/// 获取体征趋势（每分钟的最小/最大/平均/最新值）
+#[tauri::command]
+fn get_trends(
+    vital: String,
+    start: Option<u64>,
+    end: Option<u64>,
+    state: State<DataProcessorState>,
+) -> Vec<types::TrendBin> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_trends(&vital, start, end)
+    } else {
+        Vec::new()
+    }
+}
+
+/// 分页获取体征趋势，`cursor` 为上一页返回的游标，读取第一页时省略
+#[tauri::command]
+fn get_trends_page(
+    vital: String,
+    start: Option<u64>,
+    end: Option<u64>,
+    cursor: Option<String>,
+    page_size: Option<usize>,
+    state: State<DataProcessorState>,
+) -> Result<Page<types::TrendBin>, String> {
+    let trends = state
+        .lock()
+        .as_ref()
+        .map(|processor| processor.get_trends(&vital, start, end))
+        .unwrap_or_default();
+    pagination::paginate(
+        trends,
+        |bin| bin.start_timestamp,
+        cursor.as_deref(),
+        page_size,
+    )
+}
+
+/// 获取各项体征的每小时概览（最小/最大/平均/最新值和报警次数），用于24小时概览表
+///
+/// 时间范围（毫秒时间戳）省略时为最近24小时。
+#[tauri::command]
+fn get_vital_summary(
+    start: Option<u64>,
+    end: Option<u64>,
+    state: State<DataProcessorState>,
+    alarm_history_state: State<AlarmHistoryStoreState>,
+) -> Result<BTreeMap<String, Vec<types::HourlySummary>>, String> {
+    let alarms = match alarm_history_state.0.lock().unwrap().as_ref() {
+        Some(store) => store.query(start, end, None)?,
+        None => Vec::new(),
+    };
+    let processor_guard = state.lock();
+    let processor = processor_guard.as_ref().ok_or("数据处理器未启动")?;
+    Ok(processor.get_vital_summary(start, end, &alarms))
+}
+
+/// 获取体征的迷你趋势图数据（最近 `duration` 秒，降采样到最多 `points` 个点）
+#[tauri::command]
+fn get_sparkline(
+    vital: String,
+    duration: u64,
+    points: usize,
+    state: State<DataProcessorState>,
+) -> Result<Vec<types::LttbDataPoint>, String> {
+    if !(1..=86_400).contains(&duration) {
+        return Err("时间跨度必须在1到86400秒之间".to_string());
+    }
+    if !(2..=500).contains(&points) {
+        return Err("点数必须在2到500之间".to_string());
+    }
+    let processor_guard = state.lock();
+    let processor = processor_guard.as_ref().ok_or("数据处理器未启动")?;
+    Ok(processor.get_sparkline(&vital, duration * 1000, points))
+}
+
+/// 获取一段时间内的心率分布直方图和各心率区间的时间占比
+///
+/// 时间范围（毫秒时间戳）省略时为最近24小时。
+#[tauri::command]
+fn get_hr_histogram(
+    start: Option<u64>,
+    end: Option<u64>,
+    bin_width: f64,
+    zones: Option<Vec<types::HeartRateZone>>,
+    state: State<DataProcessorState>,
+) -> Result<types::HeartRateHistogram, String> {
+    if !bin_width.is_finite() || !(1.0..=50.0).contains(&bin_width) {
+        return Err("柱宽必须在1到50次/分之间".to_string());
+    }
+    if let Some(zone) = zones.iter().flatten().find(|zone| zone.min.is_nan() || zone.min >= zone.max) {
+        return Err(format!("心率区间 {} 的下限必须小于上限", zone.name));
+    }
+    let processor_guard = state.lock();
+    let processor = processor_guard.as_ref().ok_or("数据处理器未启动")?;
+    Ok(processor.get_hr_histogram(start, end, bin_width, zones))
+}
+
+/// 获取已有趋势数据的体征名
+#[tauri::command]
+fn get_trend_vitals(state: State<DataProcessorState>) -> Vec<String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_trend_vitals()
+    } else {
+        Vec::new()
+    }
+}
+
+/// 开始体位性生命体征测试，省略配置时使用默认时长和判定标准
+#[tauri::command]
+fn start_orthostatic_test(
+    config: Option<types::OrthostaticConfig>,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.start_orthostatic_test(config.unwrap_or_default())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 取消体位性生命体征测试
+#[tauri::command]
+fn cancel_orthostatic_test(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.cancel_orthostatic_test();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取体位性生命体征测试状态（当前体位、剩余时间和结果）
+#[tauri::command]
+fn get_orthostatic_status(
+    state: State<DataProcessorState>,
+) -> Option<types::OrthostaticStatus> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().and_then(|p| p.get_orthostatic_status())
+}
+
+/// 开始心率恢复测试（运动期间开始），省略配置时使用默认采集窗口和异常阈值
+#[tauri::command]
+fn start_hr_recovery_test(
+    config: Option<types::HrRecoveryConfig>,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.start_hr_recovery_test(config.unwrap_or_default())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 标记运动结束，之后自动记录1、2、3分钟的恢复心率
+#[tauri::command]
+fn mark_exercise_end(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.mark_exercise_end()
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 取消心率恢复测试
+#[tauri::command]
+fn cancel_hr_recovery_test(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.cancel_hr_recovery_test();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取心率恢复测试状态（阶段、已记录的时间点和结果）
+#[tauri::command]
+fn get_hr_recovery_status(state: State<DataProcessorState>) -> Option<types::HrRecoveryStatus> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().and_then(|p| p.get_hr_recovery_status())
+}
+
+/// 开始六分钟步行试验，省略配置时使用6分钟、每圈30米
+#[tauri::command]
+fn start_walk_test(
+    config: Option<types::WalkTestConfig>,
+    borg_pre: Option<types::BorgScore>,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.start_walk_test(config.unwrap_or_default(), borg_pre)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 标记患者完成一圈
+#[tauri::command]
+fn record_walk_lap(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.record_walk_lap()
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 录入步行试验前（pre）或试验后（post）的Borg呼吸困难和疲劳评分
+#[tauri::command]
+fn record_borg_score(
+    timing: types::BorgTiming,
+    score: types::BorgScore,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.record_borg_score(timing, score)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 提前结束步行试验，必须填写原因
+#[tauri::command]
+fn stop_walk_test(reason: String, state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.stop_walk_test(&reason)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 取消步行试验
+#[tauri::command]
+fn cancel_walk_test(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.cancel_walk_test();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取步行试验状态（计时、圈数、血氧最低值和结束后的结果）
+#[tauri::command]
+fn get_walk_test_status(state: State<DataProcessorState>) -> Option<types::WalkTestStatus> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().and_then(|p| p.get_walk_test_status())
+}
+
+/// 把已结束的步行试验结果保存到当前患者
+///
+/// # 参数
+/// * `partial_distance_m` - 最后不足一圈的距离（米）
+#[tauri::command]
+fn save_walk_test(
+    partial_distance_m: Option<f64>,
+    state: State<DataProcessorState>,
+    patient_state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<types::WalkTestSummary, String> {
+    check_patient_lock(&lock_state)?;
+    let processor_guard = state.lock();
+    let processor = processor_guard.as_ref().ok_or("数据处理器未启动")?;
+    let mut summary = processor.get_walk_test_summary(partial_distance_m.unwrap_or(0.0))?;
+    drop(processor_guard);
+
+    let patient_guard = patient_state.0.lock().unwrap();
+    let patient_store = patient_guard.as_ref().ok_or("患者存储未初始化")?;
+    summary.patient_id = Some(patient_store.current_patient_id()?.ok_or("没有当前患者")?);
+    patient_store.save_walk_test(&summary)?;
+    Ok(summary)
+}
+
+/// 获取患者已保存的六分钟步行试验结果
+#[tauri::command]
+fn get_walk_tests(
+    patient_id: String,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+) -> Result<Vec<types::WalkTestSummary>, String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    store.get_walk_tests(&patient_id)
+}
+
+/// 把已保存的步行试验结果导出为PDF报告
+///
+/// 报告包含患者ID和体征数据，缺少知情同意时须由管理员输入患者数据PIN强制导出。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn export_walk_test_pdf(
+    patient_id: String,
+    test_id: String,
+    path: String,
+    override_operator: Option<String>,
+    override_pin: Option<String>,
+    state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+    audit_state: State<AuditStoreState>,
+) -> Result<(), String> {
+    check_patient_lock(&lock_state)?;
+    let store_guard = state.0.lock().unwrap();
+    let store = store_guard.as_ref().ok_or("患者存储未初始化")?;
+    let summary = store.get_walk_test(&patient_id, &test_id)?;
+    authorize_export(
+        &store.missing_consents(
+            &patient_id,
+            &[ConsentScope::Demographics, ConsentScope::Trends],
+        )?,
+        override_operator.as_deref(),
+        override_pin.as_deref(),
+        &format!("步行试验={}，患者={}，文件={}", test_id, patient_id, path),
+        &lock_state,
+        &audit_state,
+    )?;
+    pdf_report::write_text_pdf(
+        &path,
+        "Six-Minute Walk Test",
+        &walk_test::report_lines(&summary),
+    )?;
+    println!("[PDF] 已导出步行试验 {} 到 {}", test_id, path);
+    Ok(())
+}
+
+/// 启用OSC输出，按配置的频率向目标主机发送心率、RR间期和归一化ECG
+#[tauri::command]
+fn start_osc_output(
+    config: osc_output::OscConfig,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.start_osc_output(config)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 停止OSC输出
+#[tauri::command]
+fn stop_osc_output(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.stop_osc_output();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取OSC输出状态（配置、目标地址和发送统计），未启用时返回None
+#[tauri::command]
+fn get_osc_status(state: State<DataProcessorState>) -> Option<osc_output::OscStatus> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().and_then(|p| p.get_osc_status())
+}
+
+/// 启用LSL输出，把ECG波形和数值体征发布为LSL数据流
+#[tauri::command]
+fn start_lsl_outlet(
+    config: Option<lsl_outlet::LslConfig>,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.start_lsl_outlet(config.unwrap_or_default())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 停止LSL输出
+#[tauri::command]
+fn stop_lsl_outlet(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.stop_lsl_outlet();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取LSL输出状态，未启用时返回None
+#[tauri::command]
+fn get_lsl_status(state: State<DataProcessorState>) -> Option<lsl_outlet::LslStatus> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().and_then(|p| p.get_lsl_status())
+}
+
+/// 启用共享内存输出，把最新采样持续写入供本机程序映射读取的环形缓冲文件
+#[tauri::command]
+fn start_shared_memory(
+    config: shared_memory::SharedMemoryConfig,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.start_shared_memory(config)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 停止共享内存输出
+#[tauri::command]
+fn stop_shared_memory(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.stop_shared_memory();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取共享内存输出状态（文件布局信息和已写入的采样数），未启用时返回None
+#[tauri::command]
+fn get_shared_memory_status(
+    state: State<DataProcessorState>,
+) -> Option<shared_memory::SharedMemoryStatus> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().and_then(|p| p.get_shared_memory_status())
+}
+
+/// 启用Kafka输出，把体征和报警事件发布到配置的主题，省略患者ID时使用当前患者
+#[tauri::command]
+fn start_kafka_sink(
+    mut config: kafka_sink::KafkaConfig,
+    state: State<DataProcessorState>,
+    patient_state: State<PatientStoreState>,
+) -> Result<(), String> {
+    if config.patient_id.is_none() {
+        if let Some(store) = patient_state.0.lock().unwrap().as_ref() {
+            config.patient_id = store.current_patient_id()?;
+        }
+    }
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.start_kafka_sink(config)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 停止Kafka输出
+#[tauri::command]
+fn stop_kafka_sink(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.stop_kafka_sink();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取Kafka输出状态（发送、缓存和丢弃的消息数），未启用时返回None
+#[tauri::command]
+fn get_kafka_status(state: State<DataProcessorState>) -> Option<kafka_sink::KafkaStatus> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().and_then(|p| p.get_kafka_status())
+}
+
+/// 启用云端遥测，按批通过MQTT over TLS上报到AWS IoT Core或Azure IoT Hub，
+/// 省略患者ID时使用当前患者
+#[tauri::command]
+fn start_telemetry(
+    app: AppHandle,
+    mut config: telemetry::TelemetryConfig,
+    state: State<DataProcessorState>,
+    patient_state: State<PatientStoreState>,
+) -> Result<(), String> {
+    if config.patient_id.is_none() {
+        if let Some(store) = patient_state.0.lock().unwrap().as_ref() {
+            config.patient_id = store.current_patient_id()?;
+        }
+    }
+    let outbox_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("无法获取应用数据目录: {}", e))?
+        .join("vital-signs")
+        .join("telemetry_outbox");
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.start_telemetry(config, outbox_dir)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 停止云端遥测
+#[tauri::command]
+fn stop_telemetry(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.stop_telemetry();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取云端遥测状态（连接状态、已上传和待上传的批次数），未启用时返回None
+#[tauri::command]
+fn get_telemetry_status(state: State<DataProcessorState>) -> Option<telemetry::TelemetryStatus> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().and_then(|p| p.get_telemetry_status())
+}
+
+/// 启用HL7 ORU结果发送，通过MLLP发送到接口引擎，省略患者ID和姓名时使用当前患者
+#[tauri::command]
+fn start_hl7_sender(
+    app: AppHandle,
+    mut config: hl7_sender::Hl7SenderConfig,
+    state: State<DataProcessorState>,
+    patient_state: State<PatientStoreState>,
+) -> Result<(), String> {
+    if let Some(store) = patient_state.0.lock().unwrap().as_ref() {
+        if config.patient_id.is_none() {
+            config.patient_id = store.current_patient_id()?;
+        }
+        if config.patient_name.is_none() {
+            config.patient_name = store.load_patient_info().ok().map(|info| info.name);
+        }
+    }
+    let queue_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("无法获取应用数据目录: {}", e))?
+        .join("vital-signs")
+        .join("hl7_outbound");
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.start_hl7_sender(config, queue_dir)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 停止HL7结果发送
+#[tauri::command]
+fn stop_hl7_sender(state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.stop_hl7_sender();
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取HL7接口状态（连接状态、待发送消息数、最近一次ACK/NAK），未启用时返回None
+#[tauri::command]
+fn get_hl7_interface_status(
+    state: State<DataProcessorState>,
+) -> Option<hl7_sender::Hl7InterfaceStatus> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().and_then(|p| p.get_hl7_interface_status())
+}
+
+/// 设置分级限值报警（例如血氧低于92为中优先级、低于85为高优先级）
+#[tauri::command]
+fn set_alarm_limits(
+    limits: Vec<types::VitalAlarmLimits>,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_alarm_limits(limits)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取分级限值报警配置
+#[tauri::command]
+fn get_alarm_limits(state: State<DataProcessorState>) -> Vec<types::VitalAlarmLimits> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_alarm_limits()
+    } else {
+        Vec::new()
+    }
+}
+
+/// 设置报警延迟：报警条件需持续满足 `seconds` 秒才激活，0表示立即激活
+#[tauri::command]
+fn set_alarm_delay(
+    condition: String,
+    seconds: u64,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_alarm_delay(&condition, seconds)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取各报警条件的延迟（毫秒）
+#[tauri::command]
+fn get_alarm_delays(state: State<DataProcessorState>) -> BTreeMap<String, u64> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_alarm_delays()
+    } else {
+        BTreeMap::new()
+    }
+}
+
+/// 设置报警升级配置（未确认多久后升高优先级）
+#[tauri::command]
+fn set_escalation_config(
+    config: types::EscalationConfig,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_escalation_config(config)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 设置运动感知报警抑制（运动/伪差期间延迟或降级中、低优先级报警）
+#[tauri::command]
+fn set_motion_suppression(
+    config: types::MotionSuppressionConfig,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_motion_suppression(config)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取运动感知报警抑制配置
+#[tauri::command]
+fn get_motion_suppression(
+    state: State<DataProcessorState>,
+) -> Result<types::MotionSuppressionConfig, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_motion_suppression())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取运动期间被延迟或降级的报警记录
+#[tauri::command]
+fn get_alarm_suppressions(
+    state: State<DataProcessorState>,
+) -> Result<Vec<types::AlarmSuppression>, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_alarm_suppressions())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取报警升级配置
+#[tauri::command]
+fn get_escalation_config(
+    state: State<DataProcessorState>,
+) -> Result<types::EscalationConfig, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_escalation_config())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 设置体温报警配置（发热触发/解除阈值和升温速率）
+#[tauri::command]
+fn set_temp_alarm_config(
+    config: types::TemperatureAlarmConfig,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_temp_alarm_config(config)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取体温报警配置
+#[tauri::command]
+fn get_temp_alarm_config(
+    state: State<DataProcessorState>,
+) -> Result<types::TemperatureAlarmConfig, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_temp_alarm_config())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 设置衍生风险指标（休克指数等）报警配置
+#[tauri::command]
+fn set_derived_alarm_config(
+    config: types::DerivedAlarmConfig,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_derived_alarm_config(config)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取衍生风险指标报警配置
+#[tauri::command]
+fn get_derived_alarm_config(
+    state: State<DataProcessorState>,
+) -> Result<types::DerivedAlarmConfig, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_derived_alarm_config())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取心率、血氧和体温在滚动窗口（默认5分钟）内的最小/最大/平均值
+#[tauri::command]
+fn get_vital_statistics(
+    state: State<DataProcessorState>,
+) -> Result<types::VitalStatistics, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_vital_statistics())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 设置滚动统计窗口时长（秒）
+#[tauri::command]
+fn set_statistics_window(seconds: u64, state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_statistics_window(seconds)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 设置提示性阈值（与报警无关，例如心率区间分界值）
+#[tauri::command]
+fn set_threshold_config(
+    configs: Vec<types::ThresholdConfig>,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_threshold_config(configs)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取提示性阈值配置
+#[tauri::command]
+fn get_threshold_config(
+    state: State<DataProcessorState>,
+) -> Result<Vec<types::ThresholdConfig>, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_threshold_config())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 查询体征越过提示性阈值的事件，可按时间范围（毫秒时间戳）过滤
+#[tauri::command]
+fn get_threshold_events(
+    start: Option<u64>,
+    end: Option<u64>,
+    state: State<DataProcessorState>,
+) -> Vec<types::ThresholdCrossing> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_threshold_events(start, end)
+    } else {
+        Vec::new()
+    }
+}
+
+/// 设置休息/睡眠判定参数
+#[tauri::command]
+fn set_rest_detection_config(
+    config: types::RestDetectionConfig,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_rest_detection_config(config)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取休息/睡眠判定参数
+#[tauri::command]
+fn get_rest_detection_config(
+    state: State<DataProcessorState>,
+) -> Result<types::RestDetectionConfig, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_rest_detection_config())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取休息与活动时段分开统计的心率和血氧概要，时间范围省略时为最近24小时
+#[tauri::command]
+fn get_rest_summary(
+    start: Option<u64>,
+    end: Option<u64>,
+    state: State<DataProcessorState>,
+) -> Result<types::RestSummary, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_rest_summary(start, end))
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 设置呼吸暂停判定时长（秒）
+#[tauri::command]
+fn set_apnea_threshold(seconds: u64, state: State<DataProcessorState>) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_apnea_threshold(seconds)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取呼吸暂停判定时长（秒）
+#[tauri::command]
+fn get_apnea_threshold(state: State<DataProcessorState>) -> Result<u64, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_apnea_threshold())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取当前监护会话的事件标注（例如呼吸暂停片段及其持续时长）
+#[tauri::command]
+fn get_session_annotations(state: State<DataProcessorState>) -> Vec<types::SessionAnnotation> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_session_annotations()
+    } else {
+        Vec::new()
+    }
+}
 
-/// 获取可用串口列表
+/// 静音报警，到期自动解除，静音中的报警恶化时重新报警；先写入审计日志再静音，写入失败时不静音
 #[tauri::command]
-fn get_available_ports() -> Vec<(String, String)> {
-    SerialManager::get_available_ports()
+fn silence_alarms(
+    duration_secs: u64,
+    operator: String,
+    processor_state: State<DataProcessorState>,
+    audit_state: State<AuditStoreState>,
+) -> Result<Vec<types::Alarm>, String> {
+    if operator.trim().is_empty() {
+        return Err("操作员不能为空".to_string());
+    }
+    let audit_guard = audit_state.0.lock().unwrap();
+    let audit_store = audit_guard.as_ref().ok_or("审计日志未初始化")?;
+
+    let processor_guard = processor_state.lock();
+    let processor = processor_guard.as_ref().ok_or("数据处理器未启动")?;
+    processor.silence_alarms(duration_secs, |active| {
+        let conditions: Vec<&str> = active.iter().map(|a| a.condition.as_str()).collect();
+        audit_store.append(
+            &operator,
+            "silence_alarms",
+            format!("静音 {} 秒，当前报警: {:?}", duration_secs, conditions),
+        )
+    })
 }
 
-/// 测试串口连接
+/// 获取报警静音截止时间戳（毫秒），未静音时返回None
 #[tauri::command]
-fn test_serial_connection(
-    port_name: String,
-    baud_rate: u32,
-    state: State<SerialManagerState>,
-) -> Result<(), String> {
-    let config = SerialConfig {
-        port_name,
-        baud_rate,
-    };
-    state.0.lock().unwrap().test_connection(config)
+fn get_alarm_silence(state: State<DataProcessorState>) -> Option<u64> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().and_then(|p| p.get_alarm_silence())
 }
 
-/// 连接串口
+/// 确认报警，先写入审计日志再确认，写入失败时不确认
 #[tauri::command]
-fn connect_serial(
-    port_name: String,
-    baud_rate: u32,
-    serial_state: State<SerialManagerState>,
+fn acknowledge_alarm(
+    id: u64,
+    operator: String,
     processor_state: State<DataProcessorState>,
-) -> Result<(), String> {
-    let config = SerialConfig {
-        port_name,
-        baud_rate,
-    };
+    audit_state: State<AuditStoreState>,
+) -> Result<types::Alarm, String> {
+    if operator.trim().is_empty() {
+        return Err("操作员不能为空".to_string());
+    }
+    let audit_guard = audit_state.0.lock().unwrap();
+    let audit_store = audit_guard.as_ref().ok_or("审计日志未初始化")?;
 
-    // 连接串口
-    serial_state.0.lock().unwrap().connect(config)?;
+    let processor_guard = processor_state.lock();
+    let processor = processor_guard.as_ref().ok_or("数据处理器未启动")?;
+    processor.acknowledge_alarm(id, operator.trim(), |alarm| {
+        audit_store.append(
+            &operator,
+            "acknowledge_alarm",
+            format!(
+                "确认报警 #{} ({}): {}",
+                alarm.id, alarm.condition, alarm.message
+            ),
+        )
+    })
+}
 
-    // 自动启动数据处理
-    let serial_manager = serial_state.0.lock().unwrap();
-    let data_queue = serial_manager.get_data_queue();
-    drop(serial_manager); // 释放锁
+/// 查询报警历史，可按时间范围（毫秒时间戳）和会话ID过滤
+#[tauri::command]
+fn get_alarm_history(
+    start: Option<u64>,
+    end: Option<u64>,
+    session_id: Option<String>,
+    state: State<AlarmHistoryStoreState>,
+) -> Result<Vec<AlarmHistoryEntry>, String> {
+    let store_guard = state.0.lock().unwrap();
+    if let Some(store) = store_guard.as_ref() {
+        store.query(start, end, session_id.as_deref())
+    } else {
+        Err("报警历史未初始化".to_string())
+    }
+}
 
-    let processor = DataProcessor::new(data_queue);
-    processor.start();
+/// 替换全部Webhook配置，返回补充了ID的配置
+#[tauri::command]
+fn set_webhooks(
+    webhooks: Vec<webhooks::WebhookConfig>,
+    state: State<WebhookState>,
+) -> Result<Vec<webhooks::WebhookConfig>, String> {
+    let dispatcher_guard = state.0.lock().unwrap();
+    let dispatcher = dispatcher_guard.as_ref().ok_or("Webhook未初始化")?;
+    dispatcher.set_webhooks(webhooks)
+}
 
-    let mut processor_guard = processor_state.0.lock().unwrap();
-    *processor_guard = Some(processor);
+/// 获取全部Webhook配置
+#[tauri::command]
+fn get_webhooks(state: State<WebhookState>) -> Result<Vec<webhooks::WebhookConfig>, String> {
+    let dispatcher_guard = state.0.lock().unwrap();
+    let dispatcher = dispatcher_guard.as_ref().ok_or("Webhook未初始化")?;
+    Ok(dispatcher.get_webhooks())
+}
 
-    println!("[Main] 串口连接成功，数据处理已自动启动");
-    Ok(())
+/// 获取最近的Webhook投递记录（状态、尝试次数、HTTP状态码），最新的在前
+#[tauri::command]
+fn get_webhook_deliveries(
+    limit: Option<usize>,
+    state: State<WebhookState>,
+) -> Result<Vec<webhooks::WebhookDelivery>, String> {
+    let dispatcher_guard = state.0.lock().unwrap();
+    let dispatcher = dispatcher_guard.as_ref().ok_or("Webhook未初始化")?;
+    Ok(dispatcher.get_deliveries(limit.unwrap_or(50)))
 }
 
-/// 断开串口连接
+/// 查询原始心电存档中时间范围内的采样，采样多于 `max_points` 时等间隔抽取
 #[tauri::command]
-fn disconnect_serial(
-    serial_state: State<SerialManagerState>,
+fn get_ecg_range(
+    start_timestamp: u64,
+    end_timestamp: u64,
+    max_points: Option<usize>,
+    state: State<EcgArchiveState>,
+) -> Result<Vec<ecg_archive::EcgSample>, String> {
+    let archive_guard = state.0.lock().unwrap();
+    let archive = archive_guard.as_ref().ok_or("心电存档未初始化")?;
+    Ok(archive.range(start_timestamp, end_timestamp, max_points))
+}
+
+/// 把原始心电存档中时间范围内的采样导出为CSV心电图条带，返回导出的采样数
+///
+/// 心电属于当前患者的体征数据，缺少知情同意时须由管理员输入患者数据PIN强制导出。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn export_ecg_strip(
+    start_timestamp: u64,
+    end_timestamp: u64,
+    path: String,
+    override_operator: Option<String>,
+    override_pin: Option<String>,
+    state: State<EcgArchiveState>,
+    patient_state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+    audit_state: State<AuditStoreState>,
+) -> Result<usize, String> {
+    check_patient_lock(&lock_state)?;
+    authorize_export(
+        &current_patient_missing_consents(&patient_state, &[ConsentScope::Trends])?,
+        override_operator.as_deref(),
+        override_pin.as_deref(),
+        &format!(
+            "心电条带={}~{}，文件={}",
+            start_timestamp, end_timestamp, path
+        ),
+        &lock_state,
+        &audit_state,
+    )?;
+    let archive_guard = state.0.lock().unwrap();
+    let archive = archive_guard.as_ref().ok_or("心电存档未初始化")?;
+    archive.export_strip(start_timestamp, end_timestamp, &path)
+}
+
+/// 把最近 `duration_secs` 秒的心电波形（原始和压缩）及当前体征数值保存为快照文件，返回原始采样数
+///
+/// 缺少当前患者的知情同意时须由管理员输入患者数据PIN强制导出。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn snapshot_current_waveform(
+    duration_secs: u64,
+    path: String,
+    format: snapshot::SnapshotFormat,
+    override_operator: Option<String>,
+    override_pin: Option<String>,
     processor_state: State<DataProcessorState>,
-) {
-    // 停止数据处理
-    let mut processor_guard = processor_state.0.lock().unwrap();
-    if let Some(processor) = processor_guard.as_ref() {
-        processor.stop();
-        println!("[Main] 数据处理已停止");
-    }
-    *processor_guard = None;
+    archive_state: State<EcgArchiveState>,
+    patient_state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+    audit_state: State<AuditStoreState>,
+) -> Result<usize, String> {
+    check_patient_lock(&lock_state)?;
+    authorize_export(
+        &current_patient_missing_consents(&patient_state, &[ConsentScope::Trends])?,
+        override_operator.as_deref(),
+        override_pin.as_deref(),
+        &format!("波形快照={}秒，文件={}", duration_secs, path),
+        &lock_state,
+        &audit_state,
+    )?;
+    let archive = archive_state.0.lock().unwrap().clone();
+    let processor_guard = processor_state.lock();
+    let processor = processor_guard
+        .as_ref()
+        .ok_or_else(|| "数据处理器未启动".to_string())?;
+    let snapshot = snapshot::capture(processor, archive.as_deref(), duration_secs)?;
     drop(processor_guard);
+    snapshot::write(&snapshot, &path, format)?;
+    Ok(snapshot.raw.len())
+}
 
-    // 断开串口连接
-    serial_state.0.lock().unwrap().disconnect();
-    println!("[Main] 串口连接已断开");
+/// 获取原始心电存档状态（容量、采样数和覆盖的时间范围）
+#[tauri::command]
+fn get_ecg_archive_status(
+    state: State<EcgArchiveState>,
+) -> Result<ecg_archive::EcgArchiveStatus, String> {
+    let archive_guard = state.0.lock().unwrap();
+    let archive = archive_guard.as_ref().ok_or("心电存档未初始化")?;
+    Ok(archive.status())
 }
 
-/// 发送数据到串口
+/// 保存报警邮件/短信通知配置，SMTP密码留空时保持原密码
 #[tauri::command]
-fn send_serial_data(data: String, state: State<SerialManagerState>) -> Result<(), String> {
-    state.0.lock().unwrap().send_data(data)
+fn set_alert_dispatch_config(
+    config: alert_dispatcher::AlertDispatchConfig,
+    state: State<AlertDispatcherState>,
+) -> Result<(), String> {
+    let dispatcher_guard = state.0.lock().unwrap();
+    let dispatcher = dispatcher_guard.as_ref().ok_or("报警通知未初始化")?;
+    dispatcher.set_config(config)
 }
 
-/// 获取最新的N组数据
+/// 获取报警邮件/短信通知配置（不含SMTP密码）
 #[tauri::command]
-fn get_latest_data(count: usize, state: State<SerialManagerState>) -> Vec<VitalSigns> {
-    state.0.lock().unwrap().get_latest_data(count)
+fn get_alert_dispatch_config(
+    state: State<AlertDispatcherState>,
+) -> Result<alert_dispatcher::AlertDispatchConfig, String> {
+    let dispatcher_guard = state.0.lock().unwrap();
+    let dispatcher = dispatcher_guard.as_ref().ok_or("报警通知未初始化")?;
+    Ok(dispatcher.get_config())
 }
 
-/// 获取当前串口状态
+/// 获取最近的报警邮件/短信发送记录，最新的在前
 #[tauri::command]
-fn get_serial_status(state: State<SerialManagerState>) -> SerialStatus {
-    state.0.lock().unwrap().get_status()
+fn get_alert_dispatch_log(
+    limit: Option<usize>,
+    state: State<AlertDispatcherState>,
+) -> Result<Vec<alert_dispatcher::AlertDispatch>, String> {
+    let dispatcher_guard = state.0.lock().unwrap();
+    let dispatcher = dispatcher_guard.as_ref().ok_or("报警通知未初始化")?;
+    Ok(dispatcher.get_log(limit.unwrap_or(50)))
 }
 
-/// 获取处理后的最新数据
+/// 获取当前监护会话ID，数据处理未启动时返回None
 #[tauri::command]
-fn get_processed_data(count: usize, state: State<DataProcessorState>) -> Vec<ProcessedVitalSigns> {
-    let processor_guard = state.0.lock().unwrap();
-    if let Some(processor) = processor_guard.as_ref() {
-        processor.get_processed_data(count)
-    } else {
-        Vec::new()
-    }
+fn get_current_session_id(state: State<DataProcessorState>) -> Option<String> {
+    let processor_guard = state.lock();
+    processor_guard.as_ref().map(|p| p.session_id().to_string())
 }
 
-/// 启动数据处理
+/// 保存当前监护会话的趋势和事件标注
 #[tauri::command]
-fn start_data_processing(
-    serial_state: State<SerialManagerState>,
+fn save_current_session(
     processor_state: State<DataProcessorState>,
-) -> Result<(), String> {
-    let serial_manager = serial_state.0.lock().unwrap();
-    let data_queue = serial_manager.get_data_queue();
-    drop(serial_manager);
+    session_state: State<SessionStoreState>,
+    patient_state: State<PatientStoreState>,
+    time_sync_state: State<TimeSyncState>,
+) -> Result<SessionMetadata, String> {
+    let processor_guard = processor_state.lock();
+    let processor = processor_guard.as_ref().ok_or("数据处理未启动")?;
+    let mut session = session_store::capture(processor);
+    drop(processor_guard);
+    session.metadata.patient_id = current_patient_id(&patient_state);
+    session.metadata.clock_offset_ms = time_sync_state.1.lock().unwrap().offset_ms();
 
-    let processor = DataProcessor::new(data_queue);
-    processor.start();
+    let session_guard = session_state.0.lock().unwrap();
+    let session_store = session_guard.as_ref().ok_or("会话存储未初始化")?;
+    session_store.save(&session)?;
+    println!("[Main] 已保存会话: {}", session.metadata.session_id);
+    Ok(session.metadata)
+}
 
-    let mut processor_guard = processor_state.0.lock().unwrap();
-    *processor_guard = Some(processor);
+/// 按当前处理配置重新处理录制的原始数据，结果保存为衍生会话
+///
+/// 只在复制处理配置时持有处理器状态锁，录制数据在后台线程中由离线处理器处理。
+#[tauri::command]
+async fn reprocess_recording(
+    config: types::ReprocessConfig,
+    serial_state: State<'_, SerialManagerState>,
+    processor_state: State<'_, DataProcessorState>,
+    session_state: State<'_, SessionStoreState>,
+    patient_state: State<'_, PatientStoreState>,
+) -> Result<SessionMetadata, String> {
+    reprocess::validate_config(&config)?;
+    let registry = serial_state.0.lock().unwrap().get_channel_registry();
+    let processor = {
+        let processor_guard = processor_state.lock();
+        let live = processor_guard.as_ref().ok_or("数据处理未启动")?;
+        reprocess::offline_processor(live)?
+    };
+    let replay_config = config.clone();
+    let mut session = tauri::async_runtime::spawn_blocking(move || {
+        reprocess::reprocess_recording(&replay_config, &processor, &registry)
+    })
+    .await
+    .map_err(|e| format!("重新处理录制数据失败: {}", e))??;
 
-    Ok(())
+    let session_guard = session_state.0.lock().unwrap();
+    let session_store = session_guard.as_ref().ok_or("会话存储未初始化")?;
+    // 衍生会话与原会话属于同一患者，没有原会话时归属当前患者
+    let source_patient = config
+        .source_session_id
+        .as_deref()
+        .and_then(|id| session_store.load(id).ok())
+        .and_then(|source| source.metadata.patient_id);
+    session.metadata.patient_id = source_patient.or_else(|| current_patient_id(&patient_state));
+    session_store.save(&session)?;
+    Ok(session.metadata)
 }
 
-/// 停止数据处理
+/// 列出已保存的会话
 #[tauri::command]
-fn stop_data_processing(state: State<DataProcessorState>) {
-    let mut processor_guard = state.0.lock().unwrap();
-    if let Some(processor) = processor_guard.as_ref() {
-        processor.stop();
+fn list_sessions(session_state: State<SessionStoreState>) -> Result<Vec<SessionMetadata>, String> {
+    let session_guard = session_state.0.lock().unwrap();
+    if let Some(session_store) = session_guard.as_ref() {
+        session_store.list()
+    } else {
+        Err("会话存储未初始化".to_string())
     }
-    *processor_guard = None;
 }
 
-/// 保存患者信息
+/// 分页列出已保存的会话，按开始时间先后排列
 #[tauri::command]
-fn save_patient_info(
-    patient_info: PatientInfo,
-    state: State<PatientStoreState>,
+fn list_sessions_page(
+    cursor: Option<String>,
+    page_size: Option<usize>,
+    session_state: State<SessionStoreState>,
+) -> Result<Page<SessionMetadata>, String> {
+    let session_guard = session_state.0.lock().unwrap();
+    let session_store = session_guard.as_ref().ok_or("会话存储未初始化")?;
+    pagination::paginate(
+        session_store.list()?,
+        |metadata| metadata.start_timestamp,
+        cursor.as_deref(),
+        page_size,
+    )
+}
+
+/// 分页获取已保存会话中某项体征的趋势
+#[tauri::command]
+fn get_session_trends_page(
+    session_id: String,
+    vital: String,
+    cursor: Option<String>,
+    page_size: Option<usize>,
+    session_state: State<SessionStoreState>,
+) -> Result<Page<types::TrendBin>, String> {
+    let session_guard = session_state.0.lock().unwrap();
+    let session_store = session_guard.as_ref().ok_or("会话存储未初始化")?;
+    let mut session = session_store.load(&session_id)?;
+    let trends = session.trends.remove(&vital).unwrap_or_default();
+    pagination::paginate(
+        trends,
+        |bin| bin.start_timestamp,
+        cursor.as_deref(),
+        page_size,
+    )
+}
+
+/// 分页获取已保存会话中的心搏检测结果
+#[tauri::command]
+fn get_session_beats_page(
+    session_id: String,
+    cursor: Option<String>,
+    page_size: Option<usize>,
+    session_state: State<SessionStoreState>,
+) -> Result<Page<types::BeatRecord>, String> {
+    let session_guard = session_state.0.lock().unwrap();
+    let session_store = session_guard.as_ref().ok_or("会话存储未初始化")?;
+    let session = session_store.load(&session_id)?;
+    pagination::paginate(
+        session.beats,
+        |beat| beat.timestamp,
+        cursor.as_deref(),
+        page_size,
+    )
+}
+
+/// 对比两个已保存会话中的同一项体征
+#[tauri::command]
+fn compare_sessions(
+    a: String,
+    b: String,
+    vital: String,
+    alignment: SessionAlignment,
+    session_state: State<SessionStoreState>,
+) -> Result<SessionComparison, String> {
+    let session_guard = session_state.0.lock().unwrap();
+    let session_store = session_guard.as_ref().ok_or("会话存储未初始化")?;
+    let session_a = session_store.load(&a)?;
+    let session_b = session_store.load(&b)?;
+    session_store::compare(&session_a, &session_b, &vital, alignment)
+}
+
+/// 导出已保存的会话及其所属患者的信息，`options.anonymize` 为真时去除患者身份信息
+///
+/// 缺少知情同意时须由管理员输入患者数据PIN强制导出，强制导出在写出文件前记入审计日志。
+#[tauri::command]
+fn export_session(
+    session_id: String,
+    path: String,
+    options: export::ExportOptions,
+    session_state: State<SessionStoreState>,
+    patient_state: State<PatientStoreState>,
+    lock_state: State<PatientLockState>,
+    audit_state: State<AuditStoreState>,
 ) -> Result<(), String> {
-    let store_guard = state.0.lock().unwrap();
-    if let Some(store) = store_guard.as_ref() {
-        store.save_patient_info(&patient_info)
-    } else {
-        Err("患者存储未初始化".to_string())
+    check_patient_lock(&lock_state)?;
+    let session_guard = session_state.0.lock().unwrap();
+    let session_store = session_guard.as_ref().ok_or("会话存储未初始化")?;
+    let session = session_store.load(&session_id)?;
+
+    let patient_guard = patient_state.0.lock().unwrap();
+    let patient_store = patient_guard.as_ref().ok_or("患者存储未初始化")?;
+    let patient = match session.metadata.patient_id.as_deref() {
+        Some(patient_id) => patient_store.load_record(patient_id)?,
+        None => PatientInfo::default(),
+    };
+    authorize_export(
+        &export::missing_consents(&session, patient_store)?,
+        options.override_operator.as_deref(),
+        options.override_pin.as_deref(),
+        &format!("会话={}，文件={}", session_id, path),
+        &lock_state,
+        &audit_state,
+    )?;
+    export::export_session(&session, &patient, patient_store, &options, &path)?;
+    drop(patient_guard);
+
+    session_store.record_export(&ExportRecord {
+        session_id,
+        patient_id: session.metadata.patient_id,
+        path,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// 不可恢复地清除患者的信息、测量记录、知情同意、报警历史、导出文件以及（可选）会话，
+/// 并写入审计日志，部分步骤失败时也记录已清除的内容
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn purge_patient_data(
+    patient_id: String,
+    include_sessions: bool,
+    operator: String,
+    processor_state: State<DataProcessorState>,
+    session_state: State<SessionStoreState>,
+    patient_state: State<PatientStoreState>,
+    alarm_history_state: State<AlarmHistoryStoreState>,
+    lock_state: State<PatientLockState>,
+    audit_state: State<AuditStoreState>,
+) -> Result<purge::PurgeReport, String> {
+    check_patient_lock(&lock_state)?;
+    if operator.trim().is_empty() {
+        return Err("操作员不能为空".to_string());
+    }
+    let processor_guard = processor_state.lock();
+    let session_guard = session_state.0.lock().unwrap();
+    let session_store = session_guard.as_ref().ok_or("会话存储未初始化")?;
+    let patient_guard = patient_state.0.lock().unwrap();
+    let patient_store = patient_guard.as_ref().ok_or("患者存储未初始化")?;
+    let alarm_history_guard = alarm_history_state.0.lock().unwrap();
+    let report = purge::purge_patient_data(
+        &patient_id,
+        include_sessions,
+        patient_store,
+        session_store,
+        alarm_history_guard.as_ref(),
+        processor_guard.as_ref(),
+    )?;
+
+    // 部分步骤失败时同样记录审计日志，已经删除的内容不可恢复
+    let audit_guard = audit_state.0.lock().unwrap();
+    let audit_store = audit_guard.as_ref().ok_or("审计日志未初始化")?;
+    audit_store.append(&operator, "purge_patient_data", report.summary())?;
+    if !report.errors.is_empty() {
+        return Err(format!(
+            "部分患者数据清除失败: {}",
+            report.errors.join("；")
+        ));
     }
+    Ok(report)
 }
 
-/// 加载患者信息
+/// 获取审计日志，`limit` 为只返回最近的条数
 #[tauri::command]
-fn load_patient_info(state: State<PatientStoreState>) -> Result<PatientInfo, String> {
-    let store_guard = state.0.lock().unwrap();
-    if let Some(store) = store_guard.as_ref() {
-        store.load_patient_info()
+fn get_audit_log(
+    limit: Option<usize>,
+    state: State<AuditStoreState>,
+) -> Result<Vec<AuditEntry>, String> {
+    let audit_guard = state.0.lock().unwrap();
+    if let Some(audit_store) = audit_guard.as_ref() {
+        audit_store.load(limit)
     } else {
-        Err("患者存储未初始化".to_string())
+        Err("审计日志未初始化".to_string())
     }
 }
 
-/// 删除患者信息
+/// 获取当前激活的报警
 #[tauri::command]
-fn delete_patient_info(state: State<PatientStoreState>) -> Result<(), String> {
-    let store_guard = state.0.lock().unwrap();
-    if let Some(store) = store_guard.as_ref() {
-        store.delete_patient_info()
+fn get_active_alarms(state: State<DataProcessorState>) -> Vec<types::Alarm> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_active_alarms()
     } else {
-        Err("患者存储未初始化".to_string())
+        Vec::new()
     }
 }
 
-/// 获取LTTB压缩后的ECG数据
+/// 设置血压报警配置
 #[tauri::command]
-fn get_lttb_compressed_data(state: State<DataProcessorState>) -> Vec<types::LttbDataPoint> {
-    let processor_guard = state.0.lock().unwrap();
+fn set_bp_alarm_config(
+    config: types::BpAlarmConfig,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
     if let Some(processor) = processor_guard.as_ref() {
-        processor.get_lttb_compressed_data()
+        processor.set_bp_alarm_config(config)
     } else {
-        Vec::new()
+        Err("数据处理器未启动".to_string())
     }
 }
 
+/// 获取血压报警配置
 #[tauri::command]
-fn get_blood_pressure(state: State<SerialManagerState>) -> Result<(i32, i32), String> {
-    let manager = state.0.lock().unwrap();
-    let latest_data = manager.get_latest_data(1);
-    
-    if let Some(data) = latest_data.first() {
-        Ok((data.systolic, data.diastolic))
+fn get_bp_alarm_config(state: State<DataProcessorState>) -> Result<types::BpAlarmConfig, String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        Ok(processor.get_bp_alarm_config())
     } else {
-        Err("没有可用的血压数据".to_string())
+        Err("数据处理器未启动".to_string())
     }
 }
 
 
-/// 设置数据源类型
+/// 设置数据源类型，已连接时立即切换到新的数据源
+///
+/// 原数据源的读取线程在后台线程中停止，等待期间不占用串口管理器的锁。
 #[tauri::command]
-fn set_data_source_type(
+async fn set_data_source_type(
+    app: AppHandle,
     source_type: String,
-    state: State<SerialManagerState>,
+    state: State<'_, SerialManagerState>,
 ) -> Result<(), String> {
     let source_type = match source_type.as_str() {
         "real" => DataSourceType::RealSerial,
         "test" => DataSourceType::TestSimulation,
-        _ => return Err("无效的数据源类型，请使用 'real' 或 'test'".to_string()),
+        "udp" => DataSourceType::Udp,
+        "tcp" => DataSourceType::RemoteSerial,
+        "hid" => DataSourceType::Hid,
+        "file" => DataSourceType::FileTail,
+        "playback" => DataSourceType::Playback,
+        _ => {
+            return Err(
+                "无效的数据源类型，请使用 'real'、'test'、'udp'、'tcp'、'hid'、'file' 或 'playback'"
+                    .to_string(),
+            )
+        }
     };
     
-    let mut manager = state.0.lock().unwrap();
-    manager.set_data_source_type(source_type);
-    Ok(())
+    let switch = state.0.lock().unwrap().set_data_source_type(source_type);
+    let Some((readers, switch)) = switch else {
+        return Ok(());
+    };
+    tauri::async_runtime::spawn_blocking(move || readers.stop())
+        .await
+        .map_err(|e| format!("停止原数据源失败: {}", e))?;
+    let event = state.0.lock().unwrap().complete_data_source_switch(switch);
+    let Some(event) = event else {
+        return Ok(());
+    };
+    if let Err(e) = app.emit(DATA_SOURCE_SWITCH_EVENT, ipc_schema::versioned(&event)) {
+        eprintln!("[Main] 推送数据源切换事件失败: {}", e);
+    }
+    if event.success {
+        Ok(())
+    } else {
+        Err(event.message)
+    }
 }
 
 /// 获取当前数据源类型
@@ -239,7 +3694,146 @@ fn get_data_source_type(state: State<SerialManagerState>) -> String {
     match manager.get_data_source_type() {
         DataSourceType::RealSerial => "real".to_string(),
         DataSourceType::TestSimulation => "test".to_string(),
+        DataSourceType::Udp => "udp".to_string(),
+        DataSourceType::RemoteSerial => "tcp".to_string(),
+        DataSourceType::Hid => "hid".to_string(),
+        DataSourceType::FileTail => "file".to_string(),
+        DataSourceType::Playback => "playback".to_string(),
+    }
+}
+
+/// 获取通道注册表（内置通道和已注册的扩展通道）
+#[tauri::command]
+fn get_channel_registry(state: State<SerialManagerState>) -> Vec<types::ChannelDescriptor> {
+    let manager = state.0.lock().unwrap();
+    manager.get_channel_descriptors()
+}
+
+/// 获取体征元数据目录（单位、有效范围、采样率和显示精度），前端和导出不再硬编码单位
+#[tauri::command]
+fn get_channel_metadata(
+    state: State<SerialManagerState>,
+) -> Vec<channel_metadata::ChannelMetadata> {
+    let registry = state.0.lock().unwrap().get_channel_registry();
+    channel_metadata::catalog(&registry)
+}
+
+/// 打开或关闭通道，关闭的通道不参与处理
+#[tauri::command]
+fn set_channel_enabled(
+    channel: String,
+    enabled: bool,
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let known = serial_state
+        .0
+        .lock()
+        .unwrap()
+        .get_channel_descriptors()
+        .iter()
+        .any(|d| d.id == channel);
+    if !known {
+        return Err(format!("未知通道: {}", channel));
+    }
+
+    let processor_guard = processor_state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.set_channel_enabled(&channel, enabled);
+        Ok(())
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 配置通道处理流水线（阶段顺序、开关和参数），验证通过后应用到运行中的处理器
+#[tauri::command]
+fn configure_pipeline(
+    pipelines: Vec<types::ChannelPipeline>,
+    state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.configure_pipeline(pipelines)
+    } else {
+        Err("数据处理器未启动".to_string())
+    }
+}
+
+/// 获取当前的通道处理流水线配置
+#[tauri::command]
+fn get_pipeline_config(state: State<DataProcessorState>) -> Vec<types::ChannelPipeline> {
+    let processor_guard = state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.get_pipeline_config()
+    } else {
+        Vec::new()
+    }
+}
+
+/// 获取各通道的开关状态
+#[tauri::command]
+fn get_channel_status(
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+) -> BTreeMap<String, types::ChannelStatus> {
+    let descriptors = serial_state.0.lock().unwrap().get_channel_descriptors();
+    let processor_guard = processor_state.lock();
+    descriptors
+        .into_iter()
+        .map(|d| {
+            let enabled = processor_guard
+                .as_ref()
+                .is_some_and(|p| p.is_channel_enabled(&d.id));
+            let status = if enabled {
+                types::ChannelStatus::On
+            } else {
+                types::ChannelStatus::Off
+            };
+            (d.id, status)
+        })
+        .collect()
+}
+
+/// 注册扩展通道
+///
+/// `smoothing_window` 非空时为该通道在当前数据处理器上注册滑动平均插件。
+#[tauri::command]
+fn register_channel(
+    descriptor: types::ChannelDescriptor,
+    smoothing_window: Option<usize>,
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+) -> Result<(), String> {
+    let channel_id = descriptor.id.clone();
+    serial_state.0.lock().unwrap().register_channel(descriptor)?;
+
+    if let Some(window) = smoothing_window {
+        let processor_guard = processor_state.lock();
+        if let Some(processor) = processor_guard.as_ref() {
+            processor.register_channel_plugin(
+                &channel_id,
+                Box::new(channels::MovingAveragePlugin::new(window)),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 注销扩展通道
+#[tauri::command]
+fn unregister_channel(
+    channel_id: String,
+    serial_state: State<SerialManagerState>,
+    processor_state: State<DataProcessorState>,
+) -> Result<(), String> {
+    serial_state.0.lock().unwrap().unregister_channel(&channel_id)?;
+
+    let processor_guard = processor_state.lock();
+    if let Some(processor) = processor_guard.as_ref() {
+        processor.remove_channel_plugin(&channel_id);
     }
+    Ok(())
 }
 
 fn main() {
@@ -248,27 +3842,254 @@ fn main() {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
         .manage(SerialManagerState(Mutex::new(serial_manager)))
         .manage(DataProcessorState(Mutex::new(None)))
         .manage(PatientStoreState(Mutex::new(None)))
+        .manage(ProfileStoreState(Mutex::new(None)))
+        .manage(AuditStoreState(Mutex::new(None)))
+        .manage(SessionStoreState(Mutex::new(None)))
+        .manage(CodeScannerState(Mutex::new(None)))
+        .manage(PatientLockState(Mutex::new(None)))
+        .manage(AlarmHistoryStoreState(Mutex::new(None)))
+        .manage(WebhookState(Mutex::new(None)))
+        .manage(AlertDispatcherState(Mutex::new(None)))
+        .manage(EcgArchiveState(Mutex::new(None)))
+        .manage(NotificationSettingsState(Arc::new(Mutex::new(
+            NotificationSettings::default(),
+        ))))
+        .manage(WatchdogConfigState(Mutex::new(WatchdogConfig::default())))
+        .manage(EventStreamConfigState(Mutex::new(EventStreamConfig::default())))
+        .manage(SubscriptionState(Mutex::new(SubscriptionManager::new())))
+        .manage(AutoTuneState(Mutex::new(AutoTuner::new())))
+        .manage(TimeSyncState(
+            Mutex::new(TimeSyncConfig::default()),
+            Mutex::new(TimeSync::new()),
+        ))
         .invoke_handler(tauri::generate_handler![
             get_available_ports,
             test_serial_connection,
+            run_loopback_test,
             connect_serial,
             disconnect_serial,
             send_serial_data,
             get_latest_data,
             get_serial_status,
+            get_serial_error_counts,
+            get_link_statistics,
+            get_connection_statistics,
+            get_device_capabilities,
+            get_firmware_info,
+            set_firmware_compatibility,
+            get_firmware_compatibility,
+            get_performance_metrics,
+            get_processing_status,
+            get_realtime_data,
+            set_serial_resilience_config,
+            get_serial_resilience_config,
+            set_thread_tuning_config,
+            get_thread_tuning_config,
+            set_udp_config,
+            get_udp_config,
+            set_simulator_config,
+            get_simulator_config,
+            list_arrhythmia_scenarios,
+            get_simulation_ground_truth,
+            validate_beat_detection,
+            get_stress_report,
+            set_tcp_serial_config,
+            get_tcp_serial_config,
+            get_available_hid_devices,
+            set_hid_config,
+            get_hid_config,
+            set_file_tail_config,
+            get_file_tail_config,
+            set_playback_config,
+            get_playback_config,
+            set_playback_speed,
+            seek_playback,
+            get_playback_position,
             get_processed_data,
+            get_current_heart_rate,
+            get_current_spo2,
+            get_current_temperature,
             get_lttb_compressed_data,
+            get_respiration_data,
+            get_capnography_data,
             start_data_processing,
             stop_data_processing,
+            reset_processing_state,
+            set_notification_settings,
+            get_notification_settings,
+            set_watchdog_config,
+            get_watchdog_config,
+            set_heart_rate_averaging,
+            get_heart_rate_averaging,
+            set_spo2_config,
+            get_spo2_config,
+            get_rr_tachogram,
+            get_beats,
+            get_measurement_history,
+            get_poincare_points,
+            get_hrv_spectrum,
+            get_ecg_statistics,
+            set_channel_adjustment,
+            set_ecg_processing_params,
+            get_ecg_processing_params,
+            get_channel_adjustments,
+            start_temp_calibration,
+            capture_calibration_point,
+            finish_temp_calibration,
+            get_connection_profiles,
+            delete_connection_profile,
             save_patient_info,
             load_patient_info,
             delete_patient_info,
+            add_patient_entry,
+            update_patient_entry,
+            remove_patient_entry,
+            add_medication,
+            update_medication,
+            remove_medication,
+            get_clinical_flags,
+            list_patients,
+            add_patient,
+            find_duplicate_patients,
+            merge_patients,
+            get_patient_code,
+            identify_patient_from_code,
+            start_code_scanner,
+            stop_code_scanner,
+            set_patient_photo,
+            get_patient_photo,
+            record_consent,
+            get_consents,
+            unlock_patient_data,
+            reset_patient_data_lock,
+            lock_patient_data,
+            set_patient_data_pin,
+            set_patient_data_lock_timeout,
+            get_patient_data_lock_status,
             set_data_source_type,
             get_data_source_type,
-            get_blood_pressure  // 添加新的API函数
+            get_channel_registry,
+            get_channel_metadata,
+            set_channel_enabled,
+            get_channel_status,
+            configure_pipeline,
+            get_pipeline_config,
+            register_channel,
+            unregister_channel,
+            get_blood_pressure,  // 添加新的API函数
+            get_bp_history,
+            get_trends,
+            get_trends_page,
+            get_trend_vitals,
+            get_vital_summary,
+            get_vital_statistics,
+            set_statistics_window,
+            set_threshold_config,
+            get_threshold_config,
+            get_threshold_events,
+            set_rest_detection_config,
+            get_rest_detection_config,
+            get_rest_summary,
+            get_sparkline,
+            get_hr_histogram,
+            get_active_alarms,
+            silence_alarms,
+            get_alarm_silence,
+            acknowledge_alarm,
+            get_audit_log,
+            save_current_session,
+            reprocess_recording,
+            list_sessions,
+            list_sessions_page,
+            get_session_trends_page,
+            get_session_beats_page,
+            compare_sessions,
+            export_session,
+            purge_patient_data,
+            get_alarm_history,
+            get_current_session_id,
+            set_bp_alarm_config,
+            get_bp_alarm_config,
+            set_apnea_threshold,
+            get_apnea_threshold,
+            get_session_annotations,
+            set_alarm_limits,
+            get_alarm_limits,
+            set_alarm_delay,
+            get_alarm_delays,
+            set_escalation_config,
+            get_escalation_config,
+            set_motion_suppression,
+            get_motion_suppression,
+            get_alarm_suppressions,
+            set_temp_alarm_config,
+            get_temp_alarm_config,
+            set_derived_alarm_config,
+            get_derived_alarm_config,
+            start_orthostatic_test,
+            cancel_orthostatic_test,
+            get_orthostatic_status,
+            start_hr_recovery_test,
+            mark_exercise_end,
+            cancel_hr_recovery_test,
+            get_hr_recovery_status,
+            start_walk_test,
+            record_walk_lap,
+            record_borg_score,
+            stop_walk_test,
+            cancel_walk_test,
+            get_walk_test_status,
+            save_walk_test,
+            get_walk_tests,
+            export_walk_test_pdf,
+            start_osc_output,
+            stop_osc_output,
+            get_osc_status,
+            start_lsl_outlet,
+            stop_lsl_outlet,
+            get_lsl_status,
+            start_shared_memory,
+            stop_shared_memory,
+            get_shared_memory_status,
+            start_kafka_sink,
+            stop_kafka_sink,
+            get_kafka_status,
+            start_telemetry,
+            stop_telemetry,
+            get_telemetry_status,
+            set_webhooks,
+            get_webhooks,
+            get_webhook_deliveries,
+            set_alert_dispatch_config,
+            get_alert_dispatch_config,
+            get_alert_dispatch_log,
+            start_hl7_sender,
+            stop_hl7_sender,
+            get_hl7_interface_status,
+            get_ecg_range,
+            export_ecg_strip,
+            snapshot_current_waveform,
+            get_ecg_archive_status,
+            run_processing_benchmark,
+            set_backpressure_policy,
+            get_backpressure_policy,
+            set_event_stream_config,
+            get_event_stream_config,
+            subscribe_vitals,
+            unsubscribe_vitals,
+            get_vital_subscriptions,
+            set_diagnostics_config,
+            get_diagnostics_config,
+            get_ipc_schema,
+            set_auto_tune_enabled,
+            get_auto_tune_status,
+            set_time_sync_config,
+            get_time_sync_config,
+            get_time_sync_status
         ])
         .setup(|app| {
             // 在 setup 中初始化 PatientStore，这时可以访问 AppHandle
@@ -284,6 +4105,111 @@ fn main() {
                     // 可以选择继续运行或者退出应用
                 }
             }
+
+            match ProfileStore::new(app.handle()) {
+                Ok(profile_store) => {
+                    let profile_store_state = app.state::<ProfileStoreState>();
+                    *profile_store_state.0.lock().unwrap() = Some(profile_store);
+                    println!("[Main] 连接配置存储初始化成功");
+                }
+                Err(e) => {
+                    eprintln!("[Main] 连接配置存储初始化失败: {}", e);
+                }
+            }
+
+            match AuditStore::new(app.handle()) {
+                Ok(audit_store) => {
+                    let audit_state = app.state::<AuditStoreState>();
+                    *audit_state.0.lock().unwrap() = Some(audit_store);
+                    println!("[Main] 审计日志初始化成功");
+                }
+                Err(e) => {
+                    eprintln!("[Main] 审计日志初始化失败: {}", e);
+                }
+            }
+
+            match PatientLock::new(app.handle()) {
+                Ok(patient_lock) => {
+                    let lock_state = app.state::<PatientLockState>();
+                    *lock_state.0.lock().unwrap() = Some(patient_lock);
+                    println!("[Main] 患者数据锁初始化成功");
+                }
+                Err(e) => {
+                    eprintln!("[Main] 患者数据锁初始化失败: {}", e);
+                }
+            }
+
+            match SessionStore::new(app.handle()) {
+                Ok(session_store) => {
+                    let session_state = app.state::<SessionStoreState>();
+                    *session_state.0.lock().unwrap() = Some(session_store);
+                    println!("[Main] 会话存储初始化成功");
+                }
+                Err(e) => {
+                    eprintln!("[Main] 会话存储初始化失败: {}", e);
+                }
+            }
+
+            match AlarmHistoryStore::new(app.handle()) {
+                Ok(store) => {
+                    let alarm_history_state = app.state::<AlarmHistoryStoreState>();
+                    *alarm_history_state.0.lock().unwrap() = Some(store);
+                    println!("[Main] 报警历史初始化成功");
+                }
+                Err(e) => {
+                    eprintln!("[Main] 报警历史初始化失败: {}", e);
+                }
+            }
+
+            match WebhookDispatcher::new(app.handle()) {
+                Ok(dispatcher) => {
+                    let webhook_state = app.state::<WebhookState>();
+                    *webhook_state.0.lock().unwrap() = Some(Arc::new(dispatcher));
+                    println!("[Main] Webhook初始化成功");
+                }
+                Err(e) => {
+                    eprintln!("[Main] Webhook初始化失败: {}", e);
+                }
+            }
+
+            match EcgArchive::new(app.handle()) {
+                Ok(archive) => {
+                    let archive_state = app.state::<EcgArchiveState>();
+                    *archive_state.0.lock().unwrap() = Some(Arc::new(archive));
+                    println!("[Main] 心电存档初始化成功");
+                }
+                Err(e) => {
+                    eprintln!("[Main] 心电存档初始化失败: {}", e);
+                }
+            }
+
+            let serial_state = app.state::<SerialManagerState>();
+            match serial_state
+                .0
+                .lock()
+                .unwrap()
+                .load_firmware_compatibility(app.handle())
+            {
+                Ok(()) => println!("[Main] 固件兼容性表加载成功"),
+                Err(e) => eprintln!("[Main] 固件兼容性表加载失败: {}", e),
+            }
+
+            match AlertDispatcher::new(app.handle()) {
+                Ok(dispatcher) => {
+                    let dispatcher_state = app.state::<AlertDispatcherState>();
+                    *dispatcher_state.0.lock().unwrap() = Some(dispatcher);
+                    println!("[Main] 报警通知初始化成功");
+                }
+                Err(e) => {
+                    eprintln!("[Main] 报警通知初始化失败: {}", e);
+                }
+            }
+
+            spawn_watchdog(app.handle().clone());
+            spawn_event_stream(app.handle().clone());
+            spawn_subscriptions(app.handle().clone());
+            spawn_auto_tune(app.handle().clone());
+            spawn_time_sync(app.handle().clone());
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -0,0 +1,65 @@
+//! 报警桌面通知模块
+//!
+//! 通过Tauri通知插件把新激活的报警推送为系统通知，
+//! 应用窗口最小化时也能看到报警。是否通知、是否播放提示音按报警优先级分别配置。
+
+use crate::alarms::AlarmListener;
+use crate::types::{Alarm, AlarmPriority, NotificationSettings};
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// 系统通知使用的提示音名称
+const NOTIFICATION_SOUND: &str = "default";
+
+/// 桌面通知报警监听器
+pub struct DesktopNotifier {
+    app: AppHandle,
+    settings: Arc<Mutex<NotificationSettings>>,
+}
+
+impl DesktopNotifier {
+    /// 创建桌面通知监听器
+    ///
+    /// # 参数
+    /// * `app` - 应用句柄
+    /// * `settings` - 通知设置引用，修改后立即生效
+    pub fn new(app: AppHandle, settings: Arc<Mutex<NotificationSettings>>) -> Self {
+        Self { app, settings }
+    }
+}
+
+impl AlarmListener for DesktopNotifier {
+    fn on_alarm_raised(&mut self, alarm: &Alarm) {
+        let settings = self.settings.lock().unwrap().clone();
+        if !settings.enabled {
+            return;
+        }
+        let level = match alarm.priority {
+            AlarmPriority::Low => settings.low,
+            AlarmPriority::Medium => settings.medium,
+            AlarmPriority::High => settings.high,
+        };
+        if !level.notify {
+            return;
+        }
+
+        let title = match alarm.priority {
+            AlarmPriority::Low => "生命体征提示",
+            AlarmPriority::Medium => "生命体征报警",
+            AlarmPriority::High => "紧急报警",
+        };
+        let mut builder = self
+            .app
+            .notification()
+            .builder()
+            .title(title)
+            .body(&alarm.message);
+        if level.sound {
+            builder = builder.sound(NOTIFICATION_SOUND);
+        }
+        if let Err(e) = builder.show() {
+            eprintln!("[Notifier] 发送系统通知失败: {}", e);
+        }
+    }
+}
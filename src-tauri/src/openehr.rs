@@ -0,0 +1,300 @@
+//! openEHR导出模块
+//!
+//! 把会话的趋势映射为openEHR规范JSON格式（canonical JSON）的COMPOSITION，
+//! 用于对接区域电子健康档案。每个趋势聚合段对应观察中的一个时间点事件，
+//! 取该段的平均值：
+//!
+//! * 心率 → `openEHR-EHR-OBSERVATION.pulse.v2`
+//! * 体温 → `openEHR-EHR-OBSERVATION.body_temperature.v2`
+//! * 血氧 → `openEHR-EHR-OBSERVATION.pulse_oximetry.v1`
+//! * 血压 → `openEHR-EHR-OBSERVATION.blood_pressure.v2`（收缩压、舒张压按聚合段起始时间对齐）
+
+use crate::patient_store::PatientInfo;
+use crate::session_store::StoredSession;
+use crate::types::TrendBin;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// 参考模型版本
+const RM_VERSION: &str = "1.0.4";
+
+/// 导出时使用的模板ID，接收方需已上传同名模板
+const TEMPLATE_ID: &str = "vital_signs_monitoring.v1";
+
+/// 把毫秒时间戳转换为DV_DATE_TIME
+fn date_time(timestamp: u64) -> Value {
+    let value = chrono::DateTime::from_timestamp_millis(timestamp as i64)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    json!({ "_type": "DV_DATE_TIME", "value": value })
+}
+
+fn text(value: &str) -> Value {
+    json!({ "_type": "DV_TEXT", "value": value })
+}
+
+fn code_phrase(terminology: &str, code: &str) -> Value {
+    json!({
+        "_type": "CODE_PHRASE",
+        "terminology_id": { "_type": "TERMINOLOGY_ID", "value": terminology },
+        "code_string": code,
+    })
+}
+
+fn coded_text(value: &str, terminology: &str, code: &str) -> Value {
+    json!({
+        "_type": "DV_CODED_TEXT",
+        "value": value,
+        "defining_code": code_phrase(terminology, code),
+    })
+}
+
+fn quantity(magnitude: f64, units: &str) -> Value {
+    json!({
+        "_type": "DV_QUANTITY",
+        "magnitude": (magnitude * 10.0).round() / 10.0,
+        "units": units,
+        "precision": 1,
+    })
+}
+
+/// 百分比（openEHR PROPORTION_KIND 2）
+fn percent(value: f64) -> Value {
+    json!({
+        "_type": "DV_PROPORTION",
+        "numerator": (value * 10.0).round() / 10.0,
+        "denominator": 100.0,
+        "type": 2,
+        "precision": 1,
+    })
+}
+
+fn element(node_id: &str, name: &str, value: Value) -> Value {
+    json!({
+        "_type": "ELEMENT",
+        "archetype_node_id": node_id,
+        "name": text(name),
+        "value": value,
+    })
+}
+
+/// 观察中各层节点的archetype节点ID
+struct ObservationNodes {
+    archetype_id: &'static str,
+    name: &'static str,
+    history: &'static str,
+    event: &'static str,
+    tree: &'static str,
+}
+
+const PULSE: ObservationNodes = ObservationNodes {
+    archetype_id: "openEHR-EHR-OBSERVATION.pulse.v2",
+    name: "Pulse/Heart beat",
+    history: "at0002",
+    event: "at0003",
+    tree: "at0001",
+};
+
+const BODY_TEMPERATURE: ObservationNodes = ObservationNodes {
+    archetype_id: "openEHR-EHR-OBSERVATION.body_temperature.v2",
+    name: "Body temperature",
+    history: "at0002",
+    event: "at0003",
+    tree: "at0001",
+};
+
+const PULSE_OXIMETRY: ObservationNodes = ObservationNodes {
+    archetype_id: "openEHR-EHR-OBSERVATION.pulse_oximetry.v1",
+    name: "Pulse oximetry",
+    history: "at0001",
+    event: "at0002",
+    tree: "at0003",
+};
+
+const BLOOD_PRESSURE: ObservationNodes = ObservationNodes {
+    archetype_id: "openEHR-EHR-OBSERVATION.blood_pressure.v2",
+    name: "Blood pressure",
+    history: "at0001",
+    event: "at0006",
+    tree: "at0003",
+};
+
+/// 生成一个观察，没有事件时返回None
+///
+/// # 参数
+/// * `nodes` - archetype节点ID
+/// * `subject` - 观察对象（患者）
+/// * `origin` - 历史的起点（毫秒）
+/// * `events` - 每个时间点事件的时间戳和数据元素
+fn observation(
+    nodes: &ObservationNodes,
+    subject: &Value,
+    origin: u64,
+    events: Vec<(u64, Vec<Value>)>,
+) -> Option<Value> {
+    if events.is_empty() {
+        return None;
+    }
+    let events: Vec<Value> = events
+        .into_iter()
+        .map(|(timestamp, items)| {
+            json!({
+                "_type": "POINT_EVENT",
+                "archetype_node_id": nodes.event,
+                "name": text("Any event"),
+                "time": date_time(timestamp),
+                "data": {
+                    "_type": "ITEM_TREE",
+                    "archetype_node_id": nodes.tree,
+                    "name": text("Tree"),
+                    "items": items,
+                },
+            })
+        })
+        .collect();
+    Some(json!({
+        "_type": "OBSERVATION",
+        "archetype_node_id": nodes.archetype_id,
+        "name": text(nodes.name),
+        "archetype_details": {
+            "archetype_id": { "value": nodes.archetype_id },
+            "rm_version": RM_VERSION,
+        },
+        "language": code_phrase("ISO_639-1", "en"),
+        "encoding": code_phrase("IANA_character-sets", "UTF-8"),
+        "subject": subject,
+        "data": {
+            "_type": "HISTORY",
+            "archetype_node_id": nodes.history,
+            "name": text("History"),
+            "origin": date_time(origin),
+            "events": events,
+        },
+    }))
+}
+
+/// 单个数值的观察，每个趋势聚合段一个事件
+fn single_value_observation(
+    nodes: &ObservationNodes,
+    subject: &Value,
+    origin: u64,
+    bins: Option<&Vec<TrendBin>>,
+    to_element: impl Fn(f64) -> Value,
+) -> Option<Value> {
+    let events = bins
+        .map(|bins| {
+            bins.iter()
+                .map(|bin| (bin.start_timestamp, vec![to_element(bin.mean)]))
+                .collect()
+        })
+        .unwrap_or_default();
+    observation(nodes, subject, origin, events)
+}
+
+/// 血压观察，收缩压和舒张压都存在的聚合段才生成事件，平均动脉压可选
+fn blood_pressure_observation(
+    subject: &Value,
+    origin: u64,
+    trends: &BTreeMap<String, Vec<TrendBin>>,
+) -> Option<Value> {
+    let means = |vital: &str| -> BTreeMap<u64, f64> {
+        trends
+            .get(vital)
+            .map(|bins| {
+                bins.iter()
+                    .map(|bin| (bin.start_timestamp, bin.mean))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let diastolic = means("diastolic");
+    let mean_arterial = means("mean_arterial_pressure");
+    let events = means("systolic")
+        .into_iter()
+        .filter_map(|(timestamp, systolic)| {
+            let diastolic = diastolic.get(&timestamp)?;
+            let mut items = vec![
+                element("at0004", "Systolic", quantity(systolic, "mm[Hg]")),
+                element("at0005", "Diastolic", quantity(*diastolic, "mm[Hg]")),
+            ];
+            if let Some(map) = mean_arterial.get(&timestamp) {
+                items.push(element(
+                    "at1006",
+                    "Mean arterial pressure",
+                    quantity(*map, "mm[Hg]"),
+                ));
+            }
+            Some((timestamp, items))
+        })
+        .collect();
+    observation(&BLOOD_PRESSURE, subject, origin, events)
+}
+
+/// 把会话转换为openEHR COMPOSITION
+///
+/// # 参数
+/// * `session` - 要导出的会话
+/// * `patient` - 患者信息（去标识化时已替换为假名ID）
+///
+/// # 返回值
+/// canonical JSON格式的COMPOSITION，各观察的患者通过 `PARTY_SELF.external_ref` 关联
+pub fn to_composition(session: &StoredSession, patient: &PatientInfo) -> Value {
+    let metadata = &session.metadata;
+    let origin = metadata.start_timestamp;
+    let trends = &session.trends;
+    let subject = json!({
+        "_type": "PARTY_SELF",
+        "external_ref": {
+            "_type": "PARTY_REF",
+            "id": { "_type": "GENERIC_ID", "value": patient.id, "scheme": "local" },
+            "namespace": "DEMOGRAPHIC",
+            "type": "PERSON",
+        },
+    });
+
+    let content: Vec<Value> = [
+        single_value_observation(&PULSE, &subject, origin, trends.get("heart_rate"), |rate| {
+            element("at0004", "Rate", quantity(rate, "/min"))
+        }),
+        single_value_observation(
+            &BODY_TEMPERATURE,
+            &subject,
+            origin,
+            trends.get("temperature"),
+            |temp| element("at0004", "Temperature", quantity(temp, "Cel")),
+        ),
+        single_value_observation(
+            &PULSE_OXIMETRY,
+            &subject,
+            origin,
+            trends.get("spo2"),
+            |spo2| element("at0006", "SpO₂", percent(spo2)),
+        ),
+        blood_pressure_observation(&subject, origin, trends),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    json!({
+        "_type": "COMPOSITION",
+        "archetype_node_id": "openEHR-EHR-COMPOSITION.encounter.v1",
+        "name": text("Vital signs"),
+        "archetype_details": {
+            "archetype_id": { "value": "openEHR-EHR-COMPOSITION.encounter.v1" },
+            "template_id": { "value": TEMPLATE_ID },
+            "rm_version": RM_VERSION,
+        },
+        "language": code_phrase("ISO_639-1", "en"),
+        "territory": code_phrase("ISO_3166-1", "CN"),
+        "category": coded_text("event", "openehr", "433"),
+        "composer": { "_type": "PARTY_SELF" },
+        "context": {
+            "_type": "EVENT_CONTEXT",
+            "start_time": date_time(metadata.start_timestamp),
+            "end_time": date_time(metadata.end_timestamp),
+            "setting": coded_text("other care", "openehr", "238"),
+        },
+        "content": content,
+    })
+}
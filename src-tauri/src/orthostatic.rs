@@ -0,0 +1,222 @@
+//! 体位性生命体征测试模块
+//!
+//! 引导患者依次保持卧位、坐位、立位，每个体位按配置的时长计时，
+//! 体位结束时记录心率（体位末尾一段时间内的平均值）和该体位期间最近一次的血压，
+//! 全部体位完成后计算相对卧位的变化量并按配置的标准判定是否通过。
+
+use crate::types::{
+    BloodPressureReading, CuffStatus, OrthostaticConfig, OrthostaticDelta, OrthostaticPhaseResult,
+    OrthostaticPosition, OrthostaticResult, OrthostaticStatus,
+};
+
+/// 测试的体位顺序
+const POSITIONS: [OrthostaticPosition; 3] = [
+    OrthostaticPosition::Supine,
+    OrthostaticPosition::Sitting,
+    OrthostaticPosition::Standing,
+];
+
+/// 验证体位性测试配置
+pub fn validate_config(config: &OrthostaticConfig) -> Result<(), String> {
+    let durations = [
+        config.supine_duration_ms,
+        config.sitting_duration_ms,
+        config.standing_duration_ms,
+    ];
+    if durations.contains(&0) {
+        return Err("各体位的持续时间必须大于0".to_string());
+    }
+    if config.heart_rate_window_ms == 0
+        || durations.iter().any(|&d| config.heart_rate_window_ms > d)
+    {
+        return Err("心率采集窗口必须大于0且不超过任一体位的持续时间".to_string());
+    }
+    if config.max_systolic_drop <= 0
+        || config.max_diastolic_drop <= 0
+        || !config.max_heart_rate_rise.is_finite()
+        || config.max_heart_rate_rise <= 0.0
+    {
+        return Err("判定标准必须大于0".to_string());
+    }
+    Ok(())
+}
+
+/// 一次体位性测试的运行状态
+#[derive(Debug, Clone)]
+pub struct OrthostaticSession {
+    config: OrthostaticConfig,
+    /// 当前体位在 `POSITIONS` 中的下标
+    position_index: usize,
+    /// 测试开始时间戳，收到第一个采样时确定
+    started_at: Option<u64>,
+    /// 当前体位开始时间戳
+    phase_started_at: Option<u64>,
+    /// 最近一个采样的时间戳
+    last_timestamp: u64,
+    /// 采集窗口内的心率累加值
+    heart_rate_sum: f64,
+    /// 采集窗口内的心率数量
+    heart_rate_count: u32,
+    /// 当前体位期间最近一次成功的血压测量
+    blood_pressure: Option<BloodPressureReading>,
+    /// 已完成体位的记录
+    phases: Vec<OrthostaticPhaseResult>,
+    /// 测试结果，全部体位完成后生成
+    result: Option<OrthostaticResult>,
+}
+
+impl OrthostaticSession {
+    /// 创建测试，配置需先通过 `validate_config` 验证
+    pub fn new(config: OrthostaticConfig) -> Self {
+        Self {
+            config,
+            position_index: 0,
+            started_at: None,
+            phase_started_at: None,
+            last_timestamp: 0,
+            heart_rate_sum: 0.0,
+            heart_rate_count: 0,
+            blood_pressure: None,
+            phases: Vec::new(),
+            result: None,
+        }
+    }
+
+    /// 测试是否已完成
+    pub fn is_complete(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// 当前体位的持续时间（毫秒）
+    fn phase_duration(&self) -> u64 {
+        match POSITIONS[self.position_index] {
+            OrthostaticPosition::Supine => self.config.supine_duration_ms,
+            OrthostaticPosition::Sitting => self.config.sitting_duration_ms,
+            OrthostaticPosition::Standing => self.config.standing_duration_ms,
+        }
+    }
+
+    /// 处理一个采样
+    ///
+    /// # 参数
+    /// * `heart_rate` - 当前有效心率，无效（伪差、通道关闭）时为None
+    /// * `blood_pressure` - 本采样完成的血压测量
+    /// * `timestamp` - 当前时间戳（毫秒）
+    ///
+    /// # 返回值
+    /// 测试在本采样完成时返回测试结果
+    pub fn update(
+        &mut self,
+        heart_rate: Option<f64>,
+        blood_pressure: Option<&BloodPressureReading>,
+        timestamp: u64,
+    ) -> Option<OrthostaticResult> {
+        if self.is_complete() {
+            return None;
+        }
+        self.last_timestamp = timestamp;
+        self.started_at.get_or_insert(timestamp);
+        let phase_start = *self.phase_started_at.get_or_insert(timestamp);
+        let phase_end = phase_start + self.phase_duration();
+
+        if let Some(reading) = blood_pressure.filter(|r| r.cuff_status == CuffStatus::Ok) {
+            self.blood_pressure = Some(reading.clone());
+        }
+        if let Some(hr) = heart_rate.filter(|&hr| hr > 0.0) {
+            if timestamp + self.config.heart_rate_window_ms >= phase_end {
+                self.heart_rate_sum += hr;
+                self.heart_rate_count += 1;
+            }
+        }
+
+        if timestamp < phase_end {
+            return None;
+        }
+
+        let position = POSITIONS[self.position_index];
+        let bp = self.blood_pressure.take();
+        self.phases.push(OrthostaticPhaseResult {
+            position,
+            heart_rate: (self.heart_rate_count > 0)
+                .then(|| self.heart_rate_sum / self.heart_rate_count as f64),
+            systolic: bp.as_ref().map(|r| r.systolic),
+            diastolic: bp.as_ref().map(|r| r.diastolic),
+            captured_at: timestamp,
+        });
+        println!("[Orthostatic] {:?} 体位记录完成", position);
+        self.heart_rate_sum = 0.0;
+        self.heart_rate_count = 0;
+
+        if self.position_index + 1 < POSITIONS.len() {
+            self.position_index += 1;
+            self.phase_started_at = Some(timestamp);
+            return None;
+        }
+
+        let result = self.evaluate(timestamp);
+        self.result = Some(result.clone());
+        Some(result)
+    }
+
+    /// 计算相对卧位的变化量并判定是否通过
+    ///
+    /// 每项标准取坐位和立位中变化最大的一次；缺少数据的标准不参与判定。
+    fn evaluate(&self, completed_at: u64) -> OrthostaticResult {
+        let supine = &self.phases[0];
+        let deltas: Vec<OrthostaticDelta> = self.phases[1..]
+            .iter()
+            .map(|phase| OrthostaticDelta {
+                position: phase.position,
+                heart_rate_change: phase.heart_rate.zip(supine.heart_rate).map(|(a, b)| a - b),
+                systolic_change: phase.systolic.zip(supine.systolic).map(|(a, b)| a - b),
+                diastolic_change: phase.diastolic.zip(supine.diastolic).map(|(a, b)| a - b),
+            })
+            .collect();
+
+        let systolic_drop = deltas.iter().filter_map(|d| d.systolic_change).min();
+        let diastolic_drop = deltas.iter().filter_map(|d| d.diastolic_change).min();
+        let heart_rate_rise = deltas
+            .iter()
+            .filter_map(|d| d.heart_rate_change)
+            .reduce(f64::max);
+
+        let systolic_passed = systolic_drop.map(|d| -d < self.config.max_systolic_drop);
+        let diastolic_passed = diastolic_drop.map(|d| -d < self.config.max_diastolic_drop);
+        let heart_rate_passed = heart_rate_rise.map(|d| d < self.config.max_heart_rate_rise);
+        let passed = [systolic_passed, diastolic_passed, heart_rate_passed]
+            .iter()
+            .all(|p| p.unwrap_or(true));
+
+        OrthostaticResult {
+            phases: self.phases.clone(),
+            deltas,
+            systolic_passed,
+            diastolic_passed,
+            heart_rate_passed,
+            passed,
+            config: self.config.clone(),
+            started_at: self.started_at.unwrap_or(completed_at),
+            completed_at,
+        }
+    }
+
+    /// 获取测试的当前状态
+    pub fn status(&self) -> OrthostaticStatus {
+        let (position, phase_remaining_ms) = if self.is_complete() {
+            (None, 0)
+        } else {
+            let remaining = match self.phase_started_at {
+                Some(start) => (start + self.phase_duration()).saturating_sub(self.last_timestamp),
+                None => self.phase_duration(),
+            };
+            (Some(POSITIONS[self.position_index]), remaining)
+        };
+        OrthostaticStatus {
+            active: !self.is_complete(),
+            position,
+            phase_remaining_ms,
+            phases: self.phases.clone(),
+            result: self.result.clone(),
+        }
+    }
+}
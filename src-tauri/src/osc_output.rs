@@ -0,0 +1,255 @@
+//! OSC输出模块
+//!
+//! 通过UDP以OSC（Open Sound Control）协议向研究和生物反馈软件（Max/MSP、Unity等）
+//! 发送选定的体征数据。每个发送周期发送一个OSC包（bundle），包含：
+//! * `<prefix>/hr` - 当前心率（float）
+//! * `<prefix>/rr` - 上次发送后新检测到的RR间期（毫秒，float），每个间期一条消息
+//! * `<prefix>/ecg` - 上次发送后的归一化ECG采样（-1到1，多个float参数）
+
+use crate::types::ProcessedVitalSigns;
+use serde::{Deserialize, Serialize};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// 两次发送之间最多缓存的ECG采样数，超过时丢弃最早的采样
+const MAX_ECG_SAMPLES: usize = 512;
+
+/// OSC时间标签“立即执行”
+const TIMETAG_IMMEDIATELY: u64 = 1;
+
+/// 要发送的数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct OscStreams {
+    pub heart_rate: bool,
+    pub rr_interval: bool,
+    pub ecg: bool,
+}
+
+/// OSC输出配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct OscConfig {
+    /// 目标主机名或IP地址
+    pub host: String,
+    /// 目标UDP端口
+    pub port: u16,
+    /// 每秒发送次数
+    pub rate_hz: f64,
+    /// OSC地址前缀，以 `/` 开头
+    pub address_prefix: String,
+    pub streams: OscStreams,
+}
+
+impl Default for OscConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 9000,
+            rate_hz: 30.0,
+            address_prefix: "/vitals".to_string(),
+            streams: OscStreams {
+                heart_rate: true,
+                rr_interval: true,
+                ecg: true,
+            },
+        }
+    }
+}
+
+/// OSC输出状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct OscStatus {
+    pub config: OscConfig,
+    /// 目标地址解析结果
+    pub target: String,
+    /// 已发送的包数
+    pub packets_sent: u64,
+    /// 发送失败次数
+    pub send_errors: u64,
+    /// 最近一次发送失败的原因
+    pub last_error: Option<String>,
+}
+
+/// 验证OSC输出配置
+pub fn validate_config(config: &OscConfig) -> Result<(), String> {
+    if config.host.trim().is_empty() {
+        return Err("OSC目标主机不能为空".to_string());
+    }
+    if config.port == 0 {
+        return Err("OSC目标端口不能为0".to_string());
+    }
+    if !(1.0..=250.0).contains(&config.rate_hz) {
+        return Err("OSC发送频率必须在1到250Hz之间".to_string());
+    }
+    let prefix = &config.address_prefix;
+    if !prefix.starts_with('/') || prefix.ends_with('/') || prefix.contains([' ', '#', '*', ',']) {
+        return Err("OSC地址前缀必须以/开头，不能以/结尾且不能包含空格或#*,".to_string());
+    }
+    let streams = &config.streams;
+    if !(streams.heart_rate || streams.rr_interval || streams.ecg) {
+        return Err("至少选择一项要发送的数据".to_string());
+    }
+    Ok(())
+}
+
+/// 写入OSC字符串：以0结尾并补齐到4字节边界
+fn write_string(buf: &mut Vec<u8>, value: &str) {
+    buf.extend_from_slice(value.as_bytes());
+    buf.push(0);
+    buf.resize(buf.len().next_multiple_of(4), 0);
+}
+
+/// 编码一条参数全部为float的OSC消息
+fn encode_message(address: &str, args: &[f32]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(address.len() + args.len() * 5 + 8);
+    write_string(&mut buf, address);
+    let type_tags: String = std::iter::once(',')
+        .chain(std::iter::repeat_n('f', args.len()))
+        .collect();
+    write_string(&mut buf, &type_tags);
+    for arg in args {
+        buf.extend_from_slice(&arg.to_be_bytes());
+    }
+    buf
+}
+
+/// 把多条消息编码为一个OSC包
+fn encode_bundle(messages: &[Vec<u8>]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_string(&mut buf, "#bundle");
+    buf.extend_from_slice(&TIMETAG_IMMEDIATELY.to_be_bytes());
+    for message in messages {
+        buf.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        buf.extend_from_slice(message);
+    }
+    buf
+}
+
+/// OSC发送器，由处理线程在每个采样后调用 `push`
+pub struct OscOutput {
+    config: OscConfig,
+    socket: UdpSocket,
+    target: SocketAddr,
+    /// 发送间隔（毫秒）
+    interval_ms: f64,
+    last_sent: Option<u64>,
+    heart_rate: f64,
+    /// 最近一个RR间期，变化时视为新的心搏
+    last_rr: f64,
+    pending_rr: Vec<f32>,
+    pending_ecg: Vec<f32>,
+    packets_sent: u64,
+    send_errors: u64,
+    last_error: Option<String>,
+}
+
+impl OscOutput {
+    /// 解析目标地址并创建UDP套接字
+    pub fn new(config: OscConfig) -> Result<Self, String> {
+        validate_config(&config)?;
+        let target = (config.host.trim(), config.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("无法解析OSC目标地址 {}: {}", config.host, e))?
+            .next()
+            .ok_or_else(|| format!("无法解析OSC目标地址 {}", config.host))?;
+        let bind_addr = if target.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind_addr).map_err(|e| format!("创建OSC套接字失败: {}", e))?;
+        println!(
+            "[OSC] 输出到 {}，频率={}Hz，前缀={}",
+            target, config.rate_hz, config.address_prefix
+        );
+        Ok(Self {
+            interval_ms: 1000.0 / config.rate_hz,
+            config,
+            socket,
+            target,
+            last_sent: None,
+            heart_rate: 0.0,
+            last_rr: 0.0,
+            pending_rr: Vec::new(),
+            pending_ecg: Vec::new(),
+            packets_sent: 0,
+            send_errors: 0,
+            last_error: None,
+        })
+    }
+
+    /// 处理一个采样，到达发送间隔时发送缓存的数据
+    pub fn push(&mut self, processed: &ProcessedVitalSigns) {
+        let streams = &self.config.streams;
+        self.heart_rate = processed.heart_rate;
+        if streams.rr_interval
+            && processed.rr_interval > 0.0
+            && processed.rr_interval != self.last_rr
+        {
+            self.pending_rr.push(processed.rr_interval as f32);
+        }
+        self.last_rr = processed.rr_interval;
+        if streams.ecg {
+            if self.pending_ecg.len() >= MAX_ECG_SAMPLES {
+                self.pending_ecg.remove(0);
+            }
+            self.pending_ecg.push(processed.ecg_normalized as f32);
+        }
+
+        let due = self.last_sent.is_none_or(|last| {
+            processed.timestamp < last || (processed.timestamp - last) as f64 >= self.interval_ms
+        });
+        if due {
+            self.last_sent = Some(processed.timestamp);
+            self.send();
+        }
+    }
+
+    fn send(&mut self) {
+        let prefix = &self.config.address_prefix;
+        let mut messages = Vec::new();
+        if self.config.streams.heart_rate && self.heart_rate > 0.0 {
+            messages.push(encode_message(
+                &format!("{}/hr", prefix),
+                &[self.heart_rate as f32],
+            ));
+        }
+        for rr in self.pending_rr.drain(..) {
+            messages.push(encode_message(&format!("{}/rr", prefix), &[rr]));
+        }
+        if !self.pending_ecg.is_empty() {
+            messages.push(encode_message(
+                &format!("{}/ecg", prefix),
+                &self.pending_ecg,
+            ));
+            self.pending_ecg.clear();
+        }
+        if messages.is_empty() {
+            return;
+        }
+
+        match self.socket.send_to(&encode_bundle(&messages), self.target) {
+            Ok(_) => self.packets_sent += 1,
+            Err(e) => {
+                // 接收端未启动时每次发送都会失败，只在第一次失败时输出日志
+                if self.last_error.is_none() {
+                    eprintln!("[OSC] 发送到 {} 失败: {}", self.target, e);
+                }
+                self.send_errors += 1;
+                self.last_error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// 获取输出状态
+    pub fn status(&self) -> OscStatus {
+        OscStatus {
+            config: self.config.clone(),
+            target: self.target.to_string(),
+            packets_sent: self.packets_sent,
+            send_errors: self.send_errors,
+            last_error: self.last_error.clone(),
+        }
+    }
+}
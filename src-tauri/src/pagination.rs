@@ -0,0 +1,117 @@
+//! 历史数据分页模块
+//!
+//! 长时间记录的趋势、心搏和会话列表一次返回可能有数MB，分页查询每次只返回一页。
+//! 游标是不透明的字符串，记录上一页最后一项的排序键以及同一排序键已返回的项数，
+//! 两次查询之间有新数据追加时不会重复或遗漏。
+
+use serde::{Deserialize, Serialize};
+
+/// 每页项数范围
+const PAGE_SIZE_RANGE: (usize, usize) = (1, 5000);
+
+/// 省略每页项数时的默认值
+const DEFAULT_PAGE_SIZE: usize = 500;
+
+/// 一页查询结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct Page<T> {
+    /// 本页的项，按排序键先后排列
+    pub items: Vec<T>,
+    /// 读取下一页时传入的游标，已是最后一页时为None
+    pub next_cursor: Option<String>,
+}
+
+/// 解析后的游标
+#[derive(Debug, Clone, Copy)]
+struct Cursor {
+    /// 上一页最后一项的排序键
+    key: u64,
+    /// 排序键等于 `key` 的项中已返回的项数
+    returned: u64,
+}
+
+impl Cursor {
+    fn encode(self) -> String {
+        format!("{:x}.{:x}", self.key, self.returned)
+    }
+
+    fn decode(cursor: &str) -> Result<Self, String> {
+        let invalid = || "无效的分页游标".to_string();
+        let (key, returned) = cursor.split_once('.').ok_or_else(invalid)?;
+        Ok(Self {
+            key: u64::from_str_radix(key, 16).map_err(|_| invalid())?,
+            returned: u64::from_str_radix(returned, 16).map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// 从按排序键升序排列的数据中取出游标之后的一页
+///
+/// # 参数
+/// * `items` - 按排序键升序排列的数据
+/// * `key` - 取排序键（通常为时间戳）
+/// * `cursor` - 上一页返回的游标，读取第一页时为None
+/// * `page_size` - 每页项数，省略时为 `DEFAULT_PAGE_SIZE`
+pub fn paginate<T>(
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> u64,
+    cursor: Option<&str>,
+    page_size: Option<usize>,
+) -> Result<Page<T>, String> {
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+    if !(PAGE_SIZE_RANGE.0..=PAGE_SIZE_RANGE.1).contains(&page_size) {
+        return Err(format!(
+            "每页项数必须在{}到{}之间",
+            PAGE_SIZE_RANGE.0, PAGE_SIZE_RANGE.1
+        ));
+    }
+    let cursor = cursor.map(Cursor::decode).transpose()?;
+
+    let mut page = Vec::with_capacity(page_size);
+    let mut skipped = 0;
+    let mut has_more = false;
+    for item in items {
+        if let Some(cursor) = cursor {
+            let item_key = key(&item);
+            if item_key < cursor.key {
+                continue;
+            }
+            if item_key == cursor.key && skipped < cursor.returned {
+                skipped += 1;
+                continue;
+            }
+        }
+        if page.len() == page_size {
+            has_more = true;
+            break;
+        }
+        page.push(item);
+    }
+
+    let next_cursor = match page.last() {
+        Some(last) if has_more => {
+            let last_key = key(last);
+            let mut returned = page
+                .iter()
+                .rev()
+                .take_while(|item| key(item) == last_key)
+                .count() as u64;
+            if let Some(cursor) = cursor.filter(|c| c.key == last_key) {
+                returned += cursor.returned;
+            }
+            Some(
+                Cursor {
+                    key: last_key,
+                    returned,
+                }
+                .encode(),
+            )
+        }
+        _ => None,
+    };
+    Ok(Page {
+        items: page,
+        next_cursor,
+    })
+}
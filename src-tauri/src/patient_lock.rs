@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+/// PIN散列的迭代次数
+const HASH_ROUNDS: u32 = 10_000;
+
+/// 默认空闲自动锁定时长（秒）
+const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 300;
+
+/// 连续输入错误多少次后暂停验证PIN
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+
+/// 首次暂停验证的时长（秒），之后每次错误加倍
+const LOCKOUT_BASE_SECS: u64 = 30;
+
+/// 暂停验证的最长时长（秒）
+const LOCKOUT_MAX_SECS: u64 = 900;
+
+/// 患者数据锁的持久化设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LockSettings {
+    /// PIN的散列值（十六进制），未设置PIN时为None，此时患者数据不加锁
+    pin_hash: Option<String>,
+    /// 散列盐值
+    salt: String,
+    /// 空闲自动锁定时长（秒）
+    idle_timeout_secs: u64,
+}
+
+impl Default for LockSettings {
+    fn default() -> Self {
+        Self {
+            pin_hash: None,
+            salt: format!("{:032x}", rand::random::<u128>()),
+            idle_timeout_secs: DEFAULT_IDLE_TIMEOUT_SECS,
+        }
+    }
+}
+
+/// 患者数据锁状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct PatientLockStatus {
+    /// 是否已设置PIN
+    pub pin_set: bool,
+    /// 当前是否已解锁
+    pub unlocked: bool,
+    /// 空闲自动锁定时长（秒）
+    pub idle_timeout_secs: u64,
+    /// 连续输入错误PIN的次数
+    pub failed_attempts: u32,
+    /// 暂停验证PIN的剩余时长（秒），未暂停时为None
+    pub lockout_remaining_secs: Option<u64>,
+    /// 设置文件无法读取时的错误，此时患者数据保持锁定，只能重置患者数据锁
+    pub settings_error: Option<String>,
+}
+
+/// 验证PIN格式：4到12位数字
+fn validate_pin(pin: &str) -> Result<(), String> {
+    if !(4..=12).contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err("PIN必须为4到12位数字".to_string());
+    }
+    Ok(())
+}
+
+/// 计算PIN的迭代SHA-256散列
+fn hash_pin(pin: &str, salt: &str) -> String {
+    let mut digest = Sha256::digest(format!("{}:{}", salt, pin).as_bytes());
+    for _ in 1..HASH_ROUNDS {
+        digest = Sha256::digest(digest);
+    }
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 患者数据锁
+///
+/// 设置PIN后，读写患者数据前必须先用PIN解锁；解锁后超过空闲时长没有访问
+/// 患者数据则自动重新锁定。设置保存在数据目录的 `patient_lock.json` 中。
+///
+/// 连续输入错误PIN `MAX_FAILED_ATTEMPTS` 次后暂停验证，暂停时长随错误次数加倍。
+/// 设置文件损坏无法读取时患者数据保持锁定，由管理员重置后重新设置PIN。
+pub struct PatientLock {
+    settings_file: PathBuf,
+    settings: LockSettings,
+    /// 最近一次解锁或访问患者数据的时间，锁定时为None
+    last_activity: Option<Instant>,
+    /// 连续输入错误PIN的次数
+    failed_attempts: u32,
+    /// 暂停验证PIN的截止时间
+    lockout_until: Option<Instant>,
+    /// 设置文件无法读取时的错误
+    settings_error: Option<String>,
+}
+
+impl PatientLock {
+    pub fn new(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("无法获取应用数据目录: {}", e))?;
+
+        let data_dir = app_data_dir.join("vital-signs");
+        if !data_dir.exists() {
+            fs::create_dir_all(&data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+        }
+
+        let settings_file = data_dir.join("patient_lock.json");
+        let loaded = if settings_file.exists() {
+            fs::read_to_string(&settings_file)
+                .map_err(|e| format!("读取患者数据锁设置失败: {}", e))
+                .and_then(|content| {
+                    serde_json::from_str(&content)
+                        .map_err(|e| format!("解析患者数据锁设置失败: {}", e))
+                })
+        } else {
+            Ok(LockSettings::default())
+        };
+        // 设置无法读取时不能判断是否设置过PIN，按已锁定处理
+        let (settings, settings_error) = match loaded {
+            Ok(settings) => (settings, None),
+            Err(e) => {
+                eprintln!("[PatientLock] {}，患者数据保持锁定", e);
+                (LockSettings::default(), Some(e))
+            }
+        };
+
+        Ok(Self {
+            settings_file,
+            settings,
+            last_activity: None,
+            failed_attempts: 0,
+            lockout_until: None,
+            settings_error,
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(&self.settings)
+            .map_err(|e| format!("序列化患者数据锁设置失败: {}", e))?;
+        fs::write(&self.settings_file, json).map_err(|e| format!("保存患者数据锁设置失败: {}", e))
+    }
+
+    fn pin_matches(&self, pin: &str) -> bool {
+        self.settings
+            .pin_hash
+            .as_ref()
+            .is_some_and(|hash| *hash == hash_pin(pin, &self.settings.salt))
+    }
+
+    /// 设置文件无法读取时返回错误
+    fn check_settings(&self) -> Result<(), String> {
+        match &self.settings_error {
+            Some(e) => Err(format!("{}，请重置患者数据锁", e)),
+            None => Ok(()),
+        }
+    }
+
+    /// 暂停验证PIN的剩余时长
+    fn lockout_remaining(&self) -> Option<Duration> {
+        self.lockout_until
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// 验证PIN，连续输入错误过多时暂停验证
+    fn verify_pin(&mut self, pin: &str) -> Result<(), String> {
+        if let Some(remaining) = self.lockout_remaining() {
+            return Err(format!(
+                "PIN连续输入错误次数过多，请在{}秒后重试",
+                remaining.as_secs() + 1
+            ));
+        }
+        if self.pin_matches(pin) {
+            self.failed_attempts = 0;
+            self.lockout_until = None;
+            return Ok(());
+        }
+
+        self.failed_attempts += 1;
+        eprintln!(
+            "[PatientLock] PIN验证失败，已连续错误 {} 次",
+            self.failed_attempts
+        );
+        if self.failed_attempts < MAX_FAILED_ATTEMPTS {
+            return Err("PIN不正确".to_string());
+        }
+        let doublings = (self.failed_attempts - MAX_FAILED_ATTEMPTS).min(10);
+        let lockout_secs = (LOCKOUT_BASE_SECS << doublings).min(LOCKOUT_MAX_SECS);
+        self.lockout_until = Some(Instant::now() + Duration::from_secs(lockout_secs));
+        Err(format!(
+            "PIN不正确，已连续错误{}次，请在{}秒后重试",
+            self.failed_attempts, lockout_secs
+        ))
+    }
+
+    fn is_unlocked(&self) -> bool {
+        let idle_timeout = Duration::from_secs(self.settings.idle_timeout_secs);
+        self.settings_error.is_none()
+            && (self.settings.pin_hash.is_none()
+                || self
+                    .last_activity
+                    .is_some_and(|time| time.elapsed() < idle_timeout))
+    }
+
+    /// 设置或修改PIN
+    ///
+    /// # 参数
+    /// * `current_pin` - 当前PIN，已设置PIN时必须提供
+    /// * `new_pin` - 新PIN，为None时取消PIN
+    pub fn set_pin(
+        &mut self,
+        current_pin: Option<&str>,
+        new_pin: Option<&str>,
+    ) -> Result<(), String> {
+        self.check_settings()?;
+        if let Some(pin) = new_pin {
+            validate_pin(pin)?;
+        }
+        if self.settings.pin_hash.is_some() {
+            let current_pin = current_pin.ok_or("请输入当前PIN")?;
+            self.verify_pin(current_pin)
+                .map_err(|e| format!("当前PIN验证失败: {}", e))?;
+        }
+        self.settings.pin_hash = new_pin.map(|pin| hash_pin(pin, &self.settings.salt));
+        self.save()?;
+        self.last_activity = None;
+        let action = if new_pin.is_some() {
+            "更新"
+        } else {
+            "取消"
+        };
+        println!("[PatientLock] PIN已{}", action);
+        Ok(())
+    }
+
+    /// 设置空闲自动锁定时长（秒）
+    pub fn set_idle_timeout(&mut self, seconds: u64) -> Result<(), String> {
+        self.check_settings()?;
+        if !(10..=86_400).contains(&seconds) {
+            return Err("自动锁定时长必须在10到86400秒之间".to_string());
+        }
+        self.settings.idle_timeout_secs = seconds;
+        self.save()
+    }
+
+    /// 用PIN解锁患者数据
+    pub fn unlock(&mut self, pin: &str) -> Result<(), String> {
+        self.check_settings()?;
+        if self.settings.pin_hash.is_none() {
+            return Ok(());
+        }
+        self.verify_pin(pin)?;
+        self.last_activity = Some(Instant::now());
+        println!("[PatientLock] 患者数据已解锁");
+        Ok(())
+    }
+
+    /// 用PIN授权管理员操作（如缺少知情同意时强制导出），未设置PIN时无法授权
+    pub fn authorize(&mut self, pin: &str) -> Result<(), String> {
+        self.check_settings()?;
+        if self.settings.pin_hash.is_none() {
+            return Err("未设置患者数据PIN，无法授权管理员操作".to_string());
+        }
+        self.verify_pin(pin)
+    }
+
+    /// 立即锁定患者数据
+    pub fn lock(&mut self) {
+        self.last_activity = None;
+        println!("[PatientLock] 患者数据已锁定");
+    }
+
+    /// 设置文件无法读取时重置患者数据锁
+    ///
+    /// 损坏的设置文件改名保留，重置后没有PIN，需要重新设置。
+    /// 设置文件正常时不允许重置，避免绕过PIN。
+    pub fn reset_unreadable(&mut self) -> Result<(), String> {
+        if self.settings_error.is_none() {
+            return Err("患者数据锁设置正常，不需要重置".to_string());
+        }
+        if self.settings_file.exists() {
+            let backup = self.settings_file.with_extension(format!(
+                "json.corrupt-{}",
+                chrono::Utc::now().format("%Y%m%d%H%M%S")
+            ));
+            fs::rename(&self.settings_file, &backup)
+                .map_err(|e| format!("保留损坏的患者数据锁设置失败: {}", e))?;
+            println!("[PatientLock] 损坏的设置文件已改名为 {}", backup.display());
+        }
+        self.settings = LockSettings::default();
+        self.save()?;
+        self.settings_error = None;
+        self.failed_attempts = 0;
+        self.lockout_until = None;
+        self.last_activity = None;
+        println!("[PatientLock] 患者数据锁已重置，请重新设置PIN");
+        Ok(())
+    }
+
+    /// 检查患者数据是否已解锁，已解锁时刷新空闲计时
+    pub fn check(&mut self) -> Result<(), String> {
+        self.check_settings()?;
+        if !self.is_unlocked() {
+            self.last_activity = None;
+            return Err("患者数据已锁定，请先输入PIN解锁".to_string());
+        }
+        if self.last_activity.is_some() {
+            self.last_activity = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// 获取患者数据锁状态
+    pub fn status(&self) -> PatientLockStatus {
+        PatientLockStatus {
+            pin_set: self.settings.pin_hash.is_some() || self.settings_error.is_some(),
+            unlocked: self.is_unlocked(),
+            idle_timeout_secs: self.settings.idle_timeout_secs,
+            failed_attempts: self.failed_attempts,
+            lockout_remaining_secs: self.lockout_remaining().map(|r| r.as_secs() + 1),
+            settings_error: self.settings_error.clone(),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! 患者合并模块
+//!
+//! 按姓名、出生日期和电话查找可能重复的患者记录，并把一个患者合并到另一个患者：
+//! 会话、导出记录、知情同意和照片改为属于保留的患者，过敏、病史和用药条目取并集，
+//! 保留患者缺少的基本信息由被合并患者补充，最后删除被合并的记录。
+
+use crate::patient_store::{PatientInfo, PatientStore};
+use crate::session_store::SessionStore;
+use serde::{Deserialize, Serialize};
+
+/// 可能重复的一对患者
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct DuplicateCandidate {
+    pub patient_a: String,
+    pub patient_b: String,
+    /// 患者A的姓名
+    pub name_a: String,
+    /// 患者B的姓名
+    pub name_b: String,
+    /// 判定为重复的依据
+    pub reasons: Vec<String>,
+}
+
+/// 患者合并报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct MergeReport {
+    /// 保留的患者ID
+    pub keep_id: String,
+    /// 被合并的患者ID
+    pub merge_id: String,
+    /// 是否只是预览，预览时没有修改任何数据
+    pub dry_run: bool,
+    /// 改为属于保留患者的会话ID
+    pub sessions_relinked: Vec<String>,
+    /// 改为属于保留患者的导出记录数
+    pub exports_relinked: usize,
+    /// 改为属于保留患者的知情同意记录数
+    pub consents_relinked: usize,
+    /// 是否转移了照片
+    pub photo_moved: bool,
+    /// 新增到保留患者的过敏、病史和用药条目数
+    pub entries_added: usize,
+    /// 由被合并患者补充的基本信息字段
+    pub fields_filled: Vec<String>,
+}
+
+impl MergeReport {
+    /// 合并内容的文字描述，写入审计日志
+    pub fn summary(&self) -> String {
+        format!(
+            "保留={}，合并={}，会话{}个[{}]，导出记录{}条，知情同意{}条，照片={}，条目{}个，补充字段[{}]",
+            self.keep_id,
+            self.merge_id,
+            self.sessions_relinked.len(),
+            self.sessions_relinked.join(","),
+            self.exports_relinked,
+            self.consents_relinked,
+            if self.photo_moved { "已转移" } else { "无" },
+            self.entries_added,
+            self.fields_filled.join(",")
+        )
+    }
+}
+
+/// 未填写的字段值
+fn is_unset(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || value == "未设置"
+}
+
+/// 规范化姓名：去掉空白并转为小写，未填写时返回None
+fn normalized_name(info: &PatientInfo) -> Option<String> {
+    (!is_unset(&info.name)).then(|| {
+        info.name
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect()
+    })
+}
+
+/// 规范化电话：只保留数字，少于7位时视为无效
+fn normalized_phone(info: &PatientInfo) -> Option<String> {
+    let digits: String = info.phone.chars().filter(char::is_ascii_digit).collect();
+    (digits.len() >= 7).then_some(digits)
+}
+
+/// 查找可能重复的患者
+///
+/// 姓名相同且出生日期或电话之一相同，或者出生日期和电话都相同时判定为可能重复。
+pub fn find_duplicates(patients: &[PatientInfo]) -> Vec<DuplicateCandidate> {
+    let mut candidates = Vec::new();
+    for (i, a) in patients.iter().enumerate() {
+        for b in &patients[i + 1..] {
+            let same_name = normalized_name(a).is_some_and(|name| Some(name) == normalized_name(b));
+            let same_birth = a.birth_date.is_some() && a.birth_date == b.birth_date;
+            let same_phone =
+                normalized_phone(a).is_some_and(|phone| Some(phone) == normalized_phone(b));
+            let matches = [same_name, same_birth, same_phone]
+                .iter()
+                .filter(|m| **m)
+                .count();
+            if matches < 2 {
+                continue;
+            }
+            let reasons = [
+                (same_name, "姓名相同"),
+                (same_birth, "出生日期相同"),
+                (same_phone, "电话相同"),
+            ]
+            .iter()
+            .filter(|(matched, _)| *matched)
+            .map(|(_, reason)| reason.to_string())
+            .collect();
+            candidates.push(DuplicateCandidate {
+                patient_a: a.id.clone(),
+                patient_b: b.id.clone(),
+                name_a: a.name.clone(),
+                name_b: b.name.clone(),
+                reasons,
+            });
+        }
+    }
+    candidates
+}
+
+/// 合并两个患者的过敏、病史、用药和基本信息
+///
+/// # 返回值
+/// 返回新增的条目数和补充的字段名
+fn merge_records(keep: &mut PatientInfo, merge: &PatientInfo) -> (usize, Vec<String>) {
+    let same = |a: &str, b: &str| a.trim().eq_ignore_ascii_case(b.trim());
+    let mut added = 0;
+    for (target, source) in [
+        (&mut keep.allergies, &merge.allergies),
+        (&mut keep.medical_history, &merge.medical_history),
+    ] {
+        for entry in source {
+            if !target.iter().any(|e| same(&e.display, &entry.display)) {
+                target.push(entry.clone());
+                added += 1;
+            }
+        }
+    }
+    for medication in &merge.medications {
+        if !keep
+            .medications
+            .iter()
+            .any(|m| same(&m.display, &medication.display))
+        {
+            keep.medications.push(medication.clone());
+            added += 1;
+        }
+    }
+
+    let mut filled = Vec::new();
+    if keep.birth_date.is_none() && merge.birth_date.is_some() {
+        keep.birth_date = merge.birth_date.clone();
+        filled.push("birth_date".to_string());
+    }
+    for (name, target, source) in [
+        ("phone", &mut keep.phone, &merge.phone),
+        ("address", &mut keep.address, &merge.address),
+        (
+            "emergency_contact",
+            &mut keep.emergency_contact,
+            &merge.emergency_contact,
+        ),
+    ] {
+        if is_unset(target) && !is_unset(source) {
+            *target = source.clone();
+            filled.push(name.to_string());
+        }
+    }
+    (added, filled)
+}
+
+/// 把患者 `merge_id` 合并到患者 `keep_id`
+///
+/// # 参数
+/// * `keep_id` - 保留的患者ID
+/// * `merge_id` - 被合并并删除的患者ID
+/// * `dry_run` - 为真时只预览合并结果，不修改任何数据
+/// * `patient_store` - 患者存储
+/// * `session_store` - 会话存储
+pub fn merge_patients(
+    keep_id: &str,
+    merge_id: &str,
+    dry_run: bool,
+    patient_store: &PatientStore,
+    session_store: &SessionStore,
+) -> Result<MergeReport, String> {
+    if keep_id == merge_id {
+        return Err("不能把患者合并到自身".to_string());
+    }
+    let mut keep = patient_store.load_record(keep_id)?;
+    let merge = patient_store.load_record(merge_id)?;
+    let (entries_added, fields_filled) = merge_records(&mut keep, &merge);
+
+    let report = MergeReport {
+        keep_id: keep_id.to_string(),
+        merge_id: merge_id.to_string(),
+        dry_run,
+        sessions_relinked: session_store.relink_sessions(merge_id, keep_id, dry_run)?,
+        exports_relinked: session_store.relink_exports(merge_id, keep_id, dry_run)?,
+        consents_relinked: patient_store.relink_consents(merge_id, keep_id, dry_run)?,
+        photo_moved: patient_store.move_photo(merge_id, keep_id, dry_run)?,
+        entries_added,
+        fields_filled,
+    };
+    if dry_run {
+        return Ok(report);
+    }
+
+    // 被合并的是当前患者时，保留的患者成为当前患者
+    if patient_store.current_patient_id()?.as_deref() == Some(merge_id) {
+        patient_store.save_patient_info(&keep)?;
+    } else {
+        patient_store.update_record(&keep)?;
+    }
+    patient_store.remove_record(merge_id)?;
+    println!("[PatientMerge] 已合并患者: {}", report.summary());
+    Ok(report)
+}
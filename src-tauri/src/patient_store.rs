@@ -1,12 +1,184 @@
+use crate::ipc_schema;
+use crate::purge;
+use crate::types::WalkTestSummary;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
 use tauri::Manager;
 
+/// 过敏或病史条目的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntrySeverity {
+    Mild,
+    Moderate,
+    Severe,
+}
+
+/// 编码的过敏或病史条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct CodedEntry {
+    /// 条目ID，添加时自动生成
+    #[serde(default)]
+    pub id: String,
+    /// 编码系统，例如 `SNOMED-CT`、`ICD-10`
+    #[serde(default)]
+    pub code_system: Option<String>,
+    /// 编码系统中的代码
+    #[serde(default)]
+    pub code: Option<String>,
+    /// 显示文本
+    pub display: String,
+    /// 发生日期（YYYY-MM-DD）
+    #[serde(default)]
+    pub onset: Option<String>,
+    /// 严重程度
+    #[serde(default)]
+    pub severity: Option<EntrySeverity>,
+}
+
+/// 用药条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct Medication {
+    /// 条目ID，添加时自动生成
+    #[serde(default)]
+    pub id: String,
+    /// 编码系统，例如 `ATC`、`RxNorm`
+    #[serde(default)]
+    pub code_system: Option<String>,
+    /// 编码系统中的代码
+    #[serde(default)]
+    pub code: Option<String>,
+    /// 药品名称
+    pub display: String,
+    /// 剂量和用法，例如 `25mg 每日两次`
+    #[serde(default)]
+    pub dose: Option<String>,
+    /// 开始用药日期（YYYY-MM-DD）
+    #[serde(default)]
+    pub started_on: Option<String>,
+}
+
+/// β受体阻滞剂的ATC代码前缀
+const ATC_BETA_BLOCKER_PREFIX: &str = "C07";
+
+impl Medication {
+    /// 是否为β受体阻滞剂：ATC代码以C07开头，或未编码时药名以“洛尔”/“-olol”结尾
+    pub fn is_beta_blocker(&self) -> bool {
+        let atc = self
+            .code_system
+            .as_deref()
+            .is_some_and(|system| system.eq_ignore_ascii_case("ATC"));
+        match (&self.code, atc) {
+            (Some(code), true) => code
+                .to_ascii_uppercase()
+                .starts_with(ATC_BETA_BLOCKER_PREFIX),
+            _ => {
+                let name = self.display.trim().to_lowercase();
+                name.ends_with("洛尔") || name.ends_with("olol") || name.ends_with("ilol")
+            }
+        }
+    }
+}
+
+/// 由过敏史和用药推导的临床标记，供报警和预警评分逻辑使用
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ClinicalFlags {
+    /// 正在使用β受体阻滞剂（心率反应可能被抑制）
+    pub beta_blocker: bool,
+    /// 存在严重过敏
+    pub severe_allergy: bool,
+}
+
+/// 患者信息中的编码条目列表
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryList {
+    Allergies,
+    MedicalHistory,
+}
+
+/// 旧版本的条目为纯文本，读取时转换为只有显示文本的编码条目
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredEntry {
+    Text(String),
+    Entry(CodedEntry),
+}
+
+fn deserialize_entries<'de, D>(deserializer: D) -> Result<Vec<CodedEntry>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let entries = Vec::<StoredEntry>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| !matches!(entry, StoredEntry::Text(text) if text.trim().is_empty()))
+        .map(|entry| match entry {
+            StoredEntry::Text(display) => CodedEntry {
+                id: String::new(),
+                code_system: None,
+                code: None,
+                display,
+                onset: None,
+                severity: None,
+            },
+            StoredEntry::Entry(entry) => entry,
+        })
+        .collect())
+}
+
+/// 验证患者ID，ID用作文件名，只能包含字母和数字
+fn validate_patient_id(patient_id: &str) -> Result<(), String> {
+    if patient_id.is_empty() || !patient_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("无效的患者ID: {}", patient_id));
+    }
+    Ok(())
+}
+
+/// 生成条目ID
+fn new_entry_id() -> String {
+    format!("{:012x}", rand::random::<u64>() >> 16)
+}
+
+/// 验证可选的日期字段
+fn validate_optional_date(date: &Option<String>) -> Result<(), String> {
+    match date.as_deref() {
+        Some(date) if !date.trim().is_empty() => parse_date(date).map(|_| ()),
+        _ => Ok(()),
+    }
+}
+
+fn validate_entry(entry: &CodedEntry) -> Result<(), String> {
+    if entry.display.trim().is_empty() {
+        return Err("条目显示文本不能为空".to_string());
+    }
+    validate_optional_date(&entry.onset)
+}
+
+fn validate_medication(medication: &Medication) -> Result<(), String> {
+    if medication.display.trim().is_empty() {
+        return Err("药品名称不能为空".to_string());
+    }
+    validate_optional_date(&medication.started_on)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
 pub struct PatientInfo {
+    /// 患者ID，首次保存时生成，用于关联会话和导出记录
+    #[serde(default)]
+    pub id: String,
     pub name: String,
     pub gender: String,
+    /// 出生日期（YYYY-MM-DD）
+    #[serde(default)]
+    pub birth_date: Option<String>,
     pub age: u32,
     pub height: f32,
     pub weight: f32,
@@ -14,18 +186,45 @@ pub struct PatientInfo {
     pub address: String,
     pub emergency_contact: String,
     pub blood_type: String,
-    pub allergies: Vec<String>,
-    pub medical_history: Vec<String>,
+    #[serde(deserialize_with = "deserialize_entries")]
+    pub allergies: Vec<CodedEntry>,
+    #[serde(deserialize_with = "deserialize_entries")]
+    pub medical_history: Vec<CodedEntry>,
+    /// 当前用药
+    #[serde(default)]
+    pub medications: Vec<Medication>,
     pub last_checkup: String,
     pub created_at: String,
     pub updated_at: String,
 }
 
+impl PatientInfo {
+    fn entries_mut(&mut self, list: EntryList) -> &mut Vec<CodedEntry> {
+        match list {
+            EntryList::Allergies => &mut self.allergies,
+            EntryList::MedicalHistory => &mut self.medical_history,
+        }
+    }
+
+    /// 推导临床标记
+    pub fn clinical_flags(&self) -> ClinicalFlags {
+        ClinicalFlags {
+            beta_blocker: self.medications.iter().any(Medication::is_beta_blocker),
+            severe_allergy: self
+                .allergies
+                .iter()
+                .any(|entry| entry.severity == Some(EntrySeverity::Severe)),
+        }
+    }
+}
+
 impl Default for PatientInfo {
     fn default() -> Self {
         Self {
+            id: String::new(),
             name: "未设置".to_string(),
             gender: "男".to_string(),
+            birth_date: None,
             age: 0,
             height: 0.0,
             weight: 0.0,
@@ -35,6 +234,7 @@ impl Default for PatientInfo {
             blood_type: "未知".to_string(),
             allergies: Vec::new(),
             medical_history: Vec::new(),
+            medications: Vec::new(),
             last_checkup: "未记录".to_string(),
             created_at: chrono::Utc::now().to_rfc3339(),
             updated_at: chrono::Utc::now().to_rfc3339(),
@@ -42,8 +242,130 @@ impl Default for PatientInfo {
     }
 }
 
+/// 知情同意涵盖的数据类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentScope {
+    /// 患者基本信息
+    Demographics,
+    /// 体征趋势数据
+    Trends,
+    /// 事件标注
+    Annotations,
+}
+
+impl ConsentScope {
+    /// 数据类型的中文名称
+    pub fn label(self) -> &'static str {
+        match self {
+            ConsentScope::Demographics => "患者基本信息",
+            ConsentScope::Trends => "体征趋势",
+            ConsentScope::Annotations => "事件标注",
+        }
+    }
+}
+
+/// 知情同意记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ConsentRecord {
+    /// 所属患者ID，记录时自动填写
+    #[serde(default)]
+    pub patient_id: String,
+    /// 同意涵盖的数据类型
+    pub scope: ConsentScope,
+    /// 签署日期（YYYY-MM-DD）
+    pub signed_on: String,
+    /// 签署人
+    pub signer: String,
+    /// 失效日期（YYYY-MM-DD，包含），省略时长期有效
+    #[serde(default)]
+    pub expires_on: Option<String>,
+    /// 记录时间（RFC 3339），记录时自动填写
+    #[serde(default)]
+    pub recorded_at: String,
+}
+
+fn parse_date(date: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("日期格式无效，应为YYYY-MM-DD: {}", date))
+}
+
+impl ConsentRecord {
+    /// 同意在指定日期是否有效
+    pub fn is_valid_on(&self, date: chrono::NaiveDate) -> bool {
+        let signed = parse_date(&self.signed_on).is_ok_and(|signed| signed <= date);
+        let expired = self
+            .expires_on
+            .as_deref()
+            .is_some_and(|expires| parse_date(expires).map_or(true, |expires| date > expires));
+        signed && !expired
+    }
+}
+
+/// 腕带条码内容的前缀和版本
+const PATIENT_CODE_PREFIX: &str = "VSP1";
+
+/// 腕带条码内容的校验码：`前缀:患者ID` 的SHA-256前4字节
+fn patient_code_check(patient_id: &str) -> String {
+    let digest = Sha256::digest(format!("{}:{}", PATIENT_CODE_PREFIX, patient_id).as_bytes());
+    digest[..4].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 解析腕带条码内容
+///
+/// 支持 `VSP1:<患者ID>:<校验码>` 格式和只打印了患者ID的旧腕带。
+/// 扫码枪以键盘方式输入时末尾的回车换行会被忽略。
+///
+/// # 返回值
+/// 返回患者ID，格式或校验码错误时返回错误
+pub fn parse_patient_code(payload: &str) -> Result<String, String> {
+    let payload = payload.trim();
+    let mut parts = payload.split(':');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(prefix), Some(patient_id), Some(check), None) if prefix == PATIENT_CODE_PREFIX => {
+            validate_patient_id(patient_id)?;
+            if !check.eq_ignore_ascii_case(&patient_code_check(patient_id)) {
+                return Err("条码校验失败，请重新扫描".to_string());
+            }
+            Ok(patient_id.to_string())
+        }
+        (Some(patient_id), None, None, None) => {
+            validate_patient_id(patient_id).map_err(|_| format!("无法识别的条码: {}", payload))?;
+            Ok(patient_id.to_string())
+        }
+        _ => Err(format!("无法识别的条码: {}", payload)),
+    }
+}
+
+/// 患者照片缩小后的最大边长（像素）
+const MAX_PHOTO_DIMENSION: u32 = 512;
+
+/// 上传照片文件的最大字节数
+const MAX_PHOTO_BYTES: usize = 10 * 1024 * 1024;
+
+/// 患者假名ID的前缀
+const PSEUDONYM_PREFIX: &str = "P-";
+
+/// 64位FNV-1a哈希，用于生成跨版本稳定的假名ID
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
 pub struct PatientStore {
     data_file: PathBuf,
+    /// 生成假名ID的本机密钥文件
+    salt_file: PathBuf,
+    /// 知情同意记录文件
+    consents_file: PathBuf,
+    /// 患者照片目录
+    photos_dir: PathBuf,
+    /// 患者列表目录，每个患者一个JSON文件
+    patients_dir: PathBuf,
+    /// 六分钟步行试验结果目录，每个患者一个JSON文件
+    walk_tests_dir: PathBuf,
 }
 
 impl PatientStore {
@@ -59,37 +381,632 @@ impl PatientStore {
         }
 
         let data_file = data_dir.join("patient_info.json");
+        let salt_file = data_dir.join("pseudonym_salt");
+        let consents_file = data_dir.join("consents.json");
+        let photos_dir = data_dir.join("photos");
+        let patients_dir = data_dir.join("patients");
+        let walk_tests_dir = data_dir.join("walk_tests");
 
-        Ok(Self { data_file })
+        Ok(Self {
+            data_file,
+            salt_file,
+            consents_file,
+            photos_dir,
+            patients_dir,
+            walk_tests_dir,
+        })
     }
 
-    pub fn save_patient_info(&self, patient_info: &PatientInfo) -> Result<(), String> {
+    /// 验证患者记录并补充缺少的ID和更新时间
+    fn prepare_record(patient_info: &PatientInfo) -> Result<PatientInfo, String> {
         let mut info = patient_info.clone();
+        if info.id.is_empty() {
+            info.id = format!("{:016x}", rand::random::<u64>());
+        }
+        validate_optional_date(&info.birth_date)?;
+        for entry in info
+            .allergies
+            .iter_mut()
+            .chain(info.medical_history.iter_mut())
+        {
+            validate_entry(entry)?;
+            if entry.id.is_empty() {
+                entry.id = new_entry_id();
+            }
+        }
+        for medication in &mut info.medications {
+            validate_medication(medication)?;
+            if medication.id.is_empty() {
+                medication.id = new_entry_id();
+            }
+        }
         info.updated_at = chrono::Utc::now().to_rfc3339();
+        Ok(info)
+    }
+
+    fn write_json(path: &Path, info: &PatientInfo) -> Result<(), String> {
+        let json_data =
+            serde_json::to_string_pretty(info).map_err(|e| format!("序列化患者信息失败: {}", e))?;
+        fs::write(path, json_data).map_err(|e| format!("保存患者信息失败: {}", e))
+    }
 
-        let json_data = serde_json::to_string_pretty(&info)
-            .map_err(|e| format!("序列化患者信息失败: {}", e))?;
+    /// 保存当前患者的信息，同时更新患者列表中的记录
+    pub fn save_patient_info(&self, patient_info: &PatientInfo) -> Result<(), String> {
+        let mut info = patient_info.clone();
+        if info.id.is_empty() {
+            if let Some(stored) = self.stored_patient_info()? {
+                info.id = stored.id;
+            }
+        }
+        let info = Self::prepare_record(&info)?;
+        Self::write_json(&self.data_file, &info)?;
+        self.write_record(&info)
+    }
 
-        fs::write(&self.data_file, json_data).map_err(|e| format!("保存患者信息失败: {}", e))?;
+    fn patient_file(&self, patient_id: &str) -> Result<PathBuf, String> {
+        validate_patient_id(patient_id)?;
+        Ok(self.patients_dir.join(format!("{}.json", patient_id)))
+    }
+
+    /// 把患者记录写入患者列表
+    fn write_record(&self, info: &PatientInfo) -> Result<(), String> {
+        if !self.patients_dir.exists() {
+            fs::create_dir_all(&self.patients_dir)
+                .map_err(|e| format!("创建患者目录失败: {}", e))?;
+        }
+        Self::write_json(&self.patient_file(&info.id)?, info)
+    }
+
+    /// 按ID读取患者记录
+    pub fn load_record(&self, patient_id: &str) -> Result<PatientInfo, String> {
+        if self.current_patient_id()?.as_deref() == Some(patient_id) {
+            return self.load_patient_info();
+        }
+        let path = self.patient_file(patient_id)?;
+        if !path.exists() {
+            return Err(format!("患者不存在: {}", patient_id));
+        }
+        let json_data =
+            fs::read_to_string(&path).map_err(|e| format!("读取患者信息失败: {}", e))?;
+        ipc_schema::from_stored_json(&json_data).map_err(|e| format!("解析患者信息失败: {}", e))
+    }
+
+    /// 列出所有患者记录，按创建时间先后排列
+    pub fn list_patients(&self) -> Result<Vec<PatientInfo>, String> {
+        let mut patients: Vec<PatientInfo> = Vec::new();
+        if self.patients_dir.exists() {
+            let entries =
+                fs::read_dir(&self.patients_dir).map_err(|e| format!("读取患者目录失败: {}", e))?;
+            patients = entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+                .filter_map(|content| ipc_schema::from_stored_json(&content).ok())
+                .collect();
+        }
+        // 旧版本只保存了当前患者
+        if self.stored_patient_info()?.is_some() {
+            let current = self.load_patient_info()?;
+            if !patients.iter().any(|p| p.id == current.id) {
+                patients.push(current);
+            }
+        }
+        patients.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(patients)
+    }
+
+    /// 添加一个患者记录，不改变当前患者
+    ///
+    /// # 返回值
+    /// 返回生成了ID的患者记录
+    pub fn add_patient(&self, patient_info: &PatientInfo) -> Result<PatientInfo, String> {
+        let mut info = patient_info.clone();
+        info.id = String::new();
+        info.created_at = chrono::Utc::now().to_rfc3339();
+        let info = Self::prepare_record(&info)?;
+        self.write_record(&info)?;
+        println!("[PatientStore] 已添加患者记录: {}", info.id);
+        Ok(info)
+    }
+
+    /// 更新患者列表中的记录，当前患者同时更新
+    pub fn update_record(&self, patient_info: &PatientInfo) -> Result<(), String> {
+        if self.current_patient_id()?.as_deref() == Some(patient_info.id.as_str()) {
+            return self.save_patient_info(patient_info);
+        }
+        self.ensure_patient(&patient_info.id)?;
+        self.write_record(&Self::prepare_record(patient_info)?)
+    }
 
+    /// 从患者列表中删除记录（不能删除当前患者）
+    pub fn remove_record(&self, patient_id: &str) -> Result<(), String> {
+        if self.current_patient_id()?.as_deref() == Some(patient_id) {
+            return Err("不能删除当前患者的记录".to_string());
+        }
+        let path = self.patient_file(patient_id)?;
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("删除患者记录失败: {}", e))?;
+        }
         Ok(())
     }
 
-    pub fn load_patient_info(&self) -> Result<PatientInfo, String> {
+    /// 生成患者腕带二维码或条码的内容
+    pub fn patient_code_payload(&self, patient_id: &str) -> Result<String, String> {
+        self.ensure_patient(patient_id)?;
+        Ok(format!(
+            "{}:{}:{}",
+            PATIENT_CODE_PREFIX,
+            patient_id,
+            patient_code_check(patient_id)
+        ))
+    }
+
+    /// 把患者列表中的某个患者设为当前患者
+    ///
+    /// # 返回值
+    /// 返回该患者的信息
+    pub fn activate_patient(&self, patient_id: &str) -> Result<PatientInfo, String> {
+        let info = self.load_record(patient_id)?;
+        if self.current_patient_id()?.as_deref() != Some(patient_id) {
+            self.save_patient_info(&info)?;
+            println!("[PatientStore] 当前患者已切换为: {}", patient_id);
+        }
+        self.load_patient_info()
+    }
+
+    /// 按扫描到的腕带条码识别患者并设为当前患者
+    pub fn identify_from_code(&self, payload: &str) -> Result<PatientInfo, String> {
+        let patient_id = parse_patient_code(payload)?;
+        self.activate_patient(&patient_id)
+    }
+
+    /// 修改知情同意记录的所属患者
+    ///
+    /// # 返回值
+    /// 返回涉及的记录数，`dry_run` 为真时只统计不修改
+    pub fn relink_consents(&self, from: &str, to: &str, dry_run: bool) -> Result<usize, String> {
+        let mut consents = self.load_all_consents()?;
+        let mut count = 0;
+        for consent in consents.iter_mut().filter(|c| c.patient_id == from) {
+            consent.patient_id = to.to_string();
+            count += 1;
+        }
+        if count > 0 && !dry_run {
+            let json_data = serde_json::to_string_pretty(&consents)
+                .map_err(|e| format!("序列化知情同意记录失败: {}", e))?;
+            fs::write(&self.consents_file, json_data)
+                .map_err(|e| format!("保存知情同意记录失败: {}", e))?;
+        }
+        Ok(count)
+    }
+
+    /// 不可恢复地删除某患者的知情同意记录
+    ///
+    /// # 返回值
+    /// 返回被删除的记录数
+    pub fn purge_consents(&self, patient_id: &str) -> Result<usize, String> {
+        let (purged, kept): (Vec<ConsentRecord>, Vec<ConsentRecord>) = self
+            .load_all_consents()?
+            .into_iter()
+            .partition(|consent| consent.patient_id == patient_id);
+        if !purged.is_empty() {
+            let json_data = serde_json::to_string_pretty(&kept)
+                .map_err(|e| format!("序列化知情同意记录失败: {}", e))?;
+            purge::secure_rewrite(&self.consents_file, &json_data)?;
+        }
+        Ok(purged.len())
+    }
+
+    /// 把患者照片转移给另一个患者，目标患者已有照片时不转移
+    ///
+    /// # 返回值
+    /// 返回是否转移了照片，`dry_run` 为真时只判断不修改
+    pub fn move_photo(&self, from: &str, to: &str, dry_run: bool) -> Result<bool, String> {
+        let (source, target) = (self.photo_file(from)?, self.photo_file(to)?);
+        if !source.exists() || target.exists() {
+            return Ok(false);
+        }
+        if !dry_run {
+            fs::rename(&source, &target).map_err(|e| format!("转移患者照片失败: {}", e))?;
+        }
+        Ok(true)
+    }
+
+    /// 读取已保存的患者信息，尚未保存时返回None
+    fn stored_patient_info(&self) -> Result<Option<PatientInfo>, String> {
         if !self.data_file.exists() {
-            return Ok(PatientInfo::default());
+            return Ok(None);
         }
 
         let json_data =
             fs::read_to_string(&self.data_file).map_err(|e| format!("读取患者信息失败: {}", e))?;
 
         let patient_info: PatientInfo =
-            serde_json::from_str(&json_data).map_err(|e| format!("解析患者信息失败: {}", e))?;
+            ipc_schema::from_stored_json(&json_data).map_err(|e| format!("解析患者信息失败: {}", e))?;
 
+        Ok(Some(patient_info))
+    }
+
+    pub fn load_patient_info(&self) -> Result<PatientInfo, String> {
+        let Some(patient_info) = self.stored_patient_info()? else {
+            return Ok(PatientInfo::default());
+        };
+        // 旧版本保存的患者信息和条目没有ID，补充后重新保存
+        let missing_ids = patient_info.id.is_empty()
+            || patient_info
+                .allergies
+                .iter()
+                .chain(&patient_info.medical_history)
+                .any(|entry| entry.id.is_empty());
+        if missing_ids {
+            self.save_patient_info(&patient_info)?;
+            return self.load_patient_info();
+        }
         Ok(patient_info)
     }
 
+    /// 读取并修改已保存的患者信息，尚未保存患者信息时返回错误
+    fn modify<T>(
+        &self,
+        change: impl FnOnce(&mut PatientInfo) -> Result<T, String>,
+    ) -> Result<T, String> {
+        if self.stored_patient_info()?.is_none() {
+            return Err("请先保存患者信息".to_string());
+        }
+        let mut info = self.load_patient_info()?;
+        let result = change(&mut info)?;
+        self.save_patient_info(&info)?;
+        Ok(result)
+    }
+
+    /// 添加过敏或病史条目
+    ///
+    /// # 返回值
+    /// 返回生成了ID的条目
+    pub fn add_entry(&self, list: EntryList, entry: CodedEntry) -> Result<CodedEntry, String> {
+        validate_entry(&entry)?;
+        let mut entry = entry;
+        entry.id = new_entry_id();
+        self.modify(|info| {
+            info.entries_mut(list).push(entry.clone());
+            Ok(entry)
+        })
+    }
+
+    /// 按ID更新过敏或病史条目
+    pub fn update_entry(&self, list: EntryList, entry: CodedEntry) -> Result<(), String> {
+        validate_entry(&entry)?;
+        self.modify(|info| {
+            let existing = info
+                .entries_mut(list)
+                .iter_mut()
+                .find(|e| e.id == entry.id)
+                .ok_or_else(|| format!("条目不存在: {}", entry.id))?;
+            *existing = entry;
+            Ok(())
+        })
+    }
+
+    /// 按ID删除过敏或病史条目
+    pub fn remove_entry(&self, list: EntryList, id: &str) -> Result<(), String> {
+        self.modify(|info| {
+            let entries = info.entries_mut(list);
+            let len = entries.len();
+            entries.retain(|e| e.id != id);
+            if entries.len() == len {
+                return Err(format!("条目不存在: {}", id));
+            }
+            Ok(())
+        })
+    }
+
+    /// 添加用药条目
+    ///
+    /// # 返回值
+    /// 返回生成了ID的条目
+    pub fn add_medication(&self, medication: Medication) -> Result<Medication, String> {
+        validate_medication(&medication)?;
+        let mut medication = medication;
+        medication.id = new_entry_id();
+        self.modify(|info| {
+            info.medications.push(medication.clone());
+            Ok(medication)
+        })
+    }
+
+    /// 按ID更新用药条目
+    pub fn update_medication(&self, medication: Medication) -> Result<(), String> {
+        validate_medication(&medication)?;
+        self.modify(|info| {
+            let existing = info
+                .medications
+                .iter_mut()
+                .find(|m| m.id == medication.id)
+                .ok_or_else(|| format!("用药条目不存在: {}", medication.id))?;
+            *existing = medication;
+            Ok(())
+        })
+    }
+
+    /// 按ID删除用药条目
+    pub fn remove_medication(&self, id: &str) -> Result<(), String> {
+        self.modify(|info| {
+            let len = info.medications.len();
+            info.medications.retain(|m| m.id != id);
+            if info.medications.len() == len {
+                return Err(format!("用药条目不存在: {}", id));
+            }
+            Ok(())
+        })
+    }
+
+    /// 获取已保存患者的ID，尚未保存患者信息时返回None
+    pub fn current_patient_id(&self) -> Result<Option<String>, String> {
+        if self.stored_patient_info()?.is_none() {
+            return Ok(None);
+        }
+        self.load_patient_info().map(|info| Some(info.id))
+    }
+
+    fn load_all_consents(&self) -> Result<Vec<ConsentRecord>, String> {
+        if !self.consents_file.exists() {
+            return Ok(Vec::new());
+        }
+        let json_data = fs::read_to_string(&self.consents_file)
+            .map_err(|e| format!("读取知情同意记录失败: {}", e))?;
+        ipc_schema::from_stored_json(&json_data).map_err(|e| format!("解析知情同意记录失败: {}", e))
+    }
+
+    /// 为当前患者添加一条知情同意记录
+    ///
+    /// # 返回值
+    /// 返回补充了患者ID和记录时间的记录，尚未保存患者信息时返回错误
+    pub fn record_consent(&self, consent: ConsentRecord) -> Result<ConsentRecord, String> {
+        let patient_id = self
+            .current_patient_id()?
+            .ok_or("请先保存患者信息再记录知情同意")?;
+        if consent.signer.trim().is_empty() {
+            return Err("签署人不能为空".to_string());
+        }
+        let signed = parse_date(&consent.signed_on)?;
+        if let Some(expires) = consent.expires_on.as_deref() {
+            if parse_date(expires)? < signed {
+                return Err("失效日期不能早于签署日期".to_string());
+            }
+        }
+
+        let mut consent = consent;
+        consent.patient_id = patient_id;
+        consent.signer = consent.signer.trim().to_string();
+        consent.recorded_at = chrono::Utc::now().to_rfc3339();
+
+        let mut consents = self.load_all_consents()?;
+        consents.push(consent.clone());
+        let json_data = serde_json::to_string_pretty(&consents)
+            .map_err(|e| format!("序列化知情同意记录失败: {}", e))?;
+        fs::write(&self.consents_file, json_data)
+            .map_err(|e| format!("保存知情同意记录失败: {}", e))?;
+        Ok(consent)
+    }
+
+    /// 获取当前患者的知情同意记录，按记录先后排列
+    pub fn get_consents(&self) -> Result<Vec<ConsentRecord>, String> {
+        let Some(patient_id) = self.current_patient_id()? else {
+            return Ok(Vec::new());
+        };
+        self.consents_of(&patient_id)
+    }
+
+    fn consents_of(&self, patient_id: &str) -> Result<Vec<ConsentRecord>, String> {
+        Ok(self
+            .load_all_consents()?
+            .into_iter()
+            .filter(|consent| consent.patient_id == patient_id)
+            .collect())
+    }
+
+    /// 找出某患者在今天没有有效知情同意的数据类型
+    pub fn missing_consents(
+        &self,
+        patient_id: &str,
+        scopes: &[ConsentScope],
+    ) -> Result<Vec<ConsentScope>, String> {
+        let today = chrono::Local::now().date_naive();
+        let consents = self.consents_of(patient_id)?;
+        Ok(scopes
+            .iter()
+            .copied()
+            .filter(|scope| {
+                !consents
+                    .iter()
+                    .any(|consent| consent.scope == *scope && consent.is_valid_on(today))
+            })
+            .collect())
+    }
+
+    fn photo_file(&self, patient_id: &str) -> Result<PathBuf, String> {
+        validate_patient_id(patient_id)?;
+        Ok(self.photos_dir.join(format!("{}.png", patient_id)))
+    }
+
+    /// 检查患者ID是否为已保存的患者
+    fn ensure_patient(&self, patient_id: &str) -> Result<(), String> {
+        let exists = self.current_patient_id()?.as_deref() == Some(patient_id)
+            || self.patient_file(patient_id)?.exists();
+        if !exists {
+            return Err(format!("患者不存在: {}", patient_id));
+        }
+        Ok(())
+    }
+
+    /// 保存患者照片，照片缩小到不超过 `MAX_PHOTO_DIMENSION` 像素后以PNG格式保存
+    ///
+    /// # 参数
+    /// * `patient_id` - 患者ID
+    /// * `bytes` - 照片文件内容，支持PNG和JPEG
+    pub fn set_photo(&self, patient_id: &str, bytes: &[u8]) -> Result<(), String> {
+        self.ensure_patient(patient_id)?;
+        if bytes.len() > MAX_PHOTO_BYTES {
+            return Err(format!("照片不能超过{}MB", MAX_PHOTO_BYTES / 1024 / 1024));
+        }
+        let photo = image::load_from_memory(bytes).map_err(|e| format!("无法解析照片: {}", e))?;
+        let photo = if photo.width().max(photo.height()) > MAX_PHOTO_DIMENSION {
+            photo.thumbnail(MAX_PHOTO_DIMENSION, MAX_PHOTO_DIMENSION)
+        } else {
+            photo
+        };
+        let mut png = Cursor::new(Vec::new());
+        photo
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| format!("编码照片失败: {}", e))?;
+
+        if !self.photos_dir.exists() {
+            fs::create_dir_all(&self.photos_dir).map_err(|e| format!("创建照片目录失败: {}", e))?;
+        }
+        fs::write(self.photo_file(patient_id)?, png.into_inner())
+            .map_err(|e| format!("保存患者照片失败: {}", e))
+    }
+
+    /// 读取患者照片（PNG），没有照片时返回None
+    pub fn get_photo(&self, patient_id: &str) -> Result<Option<Vec<u8>>, String> {
+        self.ensure_patient(patient_id)?;
+        let photo_file = self.photo_file(patient_id)?;
+        if !photo_file.exists() {
+            return Ok(None);
+        }
+        fs::read(&photo_file)
+            .map(Some)
+            .map_err(|e| format!("读取患者照片失败: {}", e))
+    }
+
+    fn walk_test_file(&self, patient_id: &str) -> Result<PathBuf, String> {
+        validate_patient_id(patient_id)?;
+        Ok(self.walk_tests_dir.join(format!("{}.json", patient_id)))
+    }
+
+    /// 读取患者的六分钟步行试验结果，按开始时间排序
+    pub fn get_walk_tests(&self, patient_id: &str) -> Result<Vec<WalkTestSummary>, String> {
+        let path = self.walk_test_file(patient_id)?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let json_data =
+            fs::read_to_string(&path).map_err(|e| format!("读取步行试验结果失败: {}", e))?;
+        ipc_schema::from_stored_json(&json_data).map_err(|e| format!("解析步行试验结果失败: {}", e))
+    }
+
+    /// 按试验ID读取患者的一次六分钟步行试验结果
+    pub fn get_walk_test(
+        &self,
+        patient_id: &str,
+        test_id: &str,
+    ) -> Result<WalkTestSummary, String> {
+        self.get_walk_tests(patient_id)?
+            .into_iter()
+            .find(|summary| summary.test_id == test_id)
+            .ok_or_else(|| format!("步行试验结果不存在: {}", test_id))
+    }
+
+    /// 保存六分钟步行试验结果到所属患者，同一试验ID的结果被替换
+    pub fn save_walk_test(&self, summary: &WalkTestSummary) -> Result<(), String> {
+        let patient_id = summary
+            .patient_id
+            .as_deref()
+            .ok_or("步行试验结果没有关联患者")?;
+        self.ensure_patient(patient_id)?;
+        let mut tests = self.get_walk_tests(patient_id)?;
+        tests.retain(|test| test.test_id != summary.test_id);
+        tests.push(summary.clone());
+        tests.sort_by_key(|test| test.started_at);
+
+        if !self.walk_tests_dir.exists() {
+            fs::create_dir_all(&self.walk_tests_dir)
+                .map_err(|e| format!("创建步行试验目录失败: {}", e))?;
+        }
+        let json_data = serde_json::to_string_pretty(&tests)
+            .map_err(|e| format!("序列化步行试验结果失败: {}", e))?;
+        fs::write(self.walk_test_file(patient_id)?, json_data)
+            .map_err(|e| format!("保存步行试验结果失败: {}", e))?;
+        println!(
+            "[PatientStore] 已保存患者 {} 的步行试验 {}",
+            patient_id, summary.test_id
+        );
+        Ok(())
+    }
+
+    /// 读取本机假名密钥，不存在时随机生成
+    fn pseudonym_salt(&self) -> Result<String, String> {
+        if self.salt_file.exists() {
+            return fs::read_to_string(&self.salt_file)
+                .map_err(|e| format!("读取假名密钥失败: {}", e));
+        }
+        let salt = format!("{:032x}", rand::random::<u128>());
+        fs::write(&self.salt_file, &salt).map_err(|e| format!("保存假名密钥失败: {}", e))?;
+        Ok(salt)
+    }
+
+    /// 生成患者的假名ID
+    ///
+    /// 由本机密钥和患者档案的创建时间计算，同一患者每次导出得到相同的ID，
+    /// 没有密钥无法从ID反推患者身份。
+    pub fn pseudonym_id(&self, patient_info: &PatientInfo) -> Result<String, String> {
+        let salt = self.pseudonym_salt()?;
+        let input = format!("{}:{}", salt.trim(), patient_info.created_at);
+        Ok(format!(
+            "{}{:016x}",
+            PSEUDONYM_PREFIX,
+            fnv1a(input.as_bytes())
+        ))
+    }
+
+    /// 生成去标识化的患者信息：姓名和ID替换为假名ID，清除电话、地址、紧急联系人，
+    /// 以及作为假名输入的档案创建和更新时间
+    pub fn anonymize(&self, patient_info: &PatientInfo) -> Result<PatientInfo, String> {
+        let mut info = patient_info.clone();
+        info.name = self.pseudonym_id(patient_info)?;
+        info.id = info.name.clone();
+        info.phone = String::new();
+        info.address = String::new();
+        info.emergency_contact = String::new();
+        info.created_at = String::new();
+        info.updated_at = String::new();
+        Ok(info)
+    }
+
+    /// 不可恢复地删除患者信息，文件内容先被覆盖再删除
+    ///
+    /// # 返回值
+    /// 已保存的患者ID与 `patient_id` 一致并被删除时返回true
+    pub fn purge_patient_info(&self, patient_id: &str) -> Result<bool, String> {
+        let mut removed = false;
+        if self.current_patient_id()?.as_deref() == Some(patient_id) {
+            purge::secure_delete(&self.data_file)?;
+            removed = true;
+        }
+        for path in [
+            self.patient_file(patient_id)?,
+            self.photo_file(patient_id)?,
+            self.walk_test_file(patient_id)?,
+        ] {
+            if path.exists() {
+                purge::secure_delete(&path)?;
+                removed = true;
+            }
+        }
+        Ok(removed)
+    }
+
     pub fn delete_patient_info(&self) -> Result<(), String> {
+        if let Some(patient_id) = self.current_patient_id()? {
+            let photo_file = self.photo_file(&patient_id)?;
+            if photo_file.exists() {
+                fs::remove_file(&photo_file).map_err(|e| format!("删除患者照片失败: {}", e))?;
+            }
+            let record_file = self.patient_file(&patient_id)?;
+            if record_file.exists() {
+                fs::remove_file(&record_file).map_err(|e| format!("删除患者记录失败: {}", e))?;
+            }
+        }
         if self.data_file.exists() {
             fs::remove_file(&self.data_file).map_err(|e| format!("删除患者信息失败: {}", e))?;
         }
@@ -0,0 +1,112 @@
+//! PDF报告模块
+//!
+//! 不依赖外部库，生成只包含文字的A4页面PDF文件。PDF标准字体不含中文字形，
+//! 非ASCII字符会被替换为 `?`，报告内容应使用英文。
+
+use std::fmt::Write as _;
+use std::fs;
+
+/// 每页的行数
+const LINES_PER_PAGE: usize = 48;
+
+/// 转义PDF字符串中的特殊字符
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() {
+                c
+            } else {
+                '?'
+            }
+        })
+        .fold(String::new(), |mut out, c| {
+            if matches!(c, '(' | ')' | '\\') {
+                out.push('\\');
+            }
+            out.push(c);
+            out
+        })
+}
+
+/// 一页的内容流
+fn page_content(title: &str, lines: &[String], page: usize, pages: usize) -> String {
+    let mut content = String::new();
+    let _ = writeln!(content, "BT /F1 16 Tf 50 790 Td ({}) Tj ET", escape(title));
+    content.push_str("BT /F1 10 Tf 50 760 Td 14 TL\n");
+    for line in lines {
+        let _ = writeln!(content, "({}) Tj T*", escape(line));
+    }
+    content.push_str("ET\n");
+    let _ = writeln!(
+        content,
+        "BT /F1 8 Tf 500 40 Td (Page {} / {}) Tj ET",
+        page, pages
+    );
+    content
+}
+
+/// 把标题和文字行写入PDF文件，超过一页时自动分页
+///
+/// # 参数
+/// * `path` - 文件路径
+/// * `title` - 每页顶部的标题
+/// * `lines` - 正文行
+pub fn write_text_pdf(path: &str, title: &str, lines: &[String]) -> Result<(), String> {
+    if path.trim().is_empty() {
+        return Err("导出文件路径不能为空".to_string());
+    }
+    let chunks: Vec<&[String]> = if lines.is_empty() {
+        vec![&[]]
+    } else {
+        lines.chunks(LINES_PER_PAGE).collect()
+    };
+    let pages = chunks.len();
+
+    // 对象1为目录，2为页面树，3为字体，之后每页依次为页面对象和内容流
+    let page_ids: Vec<usize> = (0..pages).map(|i| 4 + i * 2).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{} 0 R", id))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+    ];
+    for (i, chunk) in chunks.iter().enumerate() {
+        let content = page_content(title, chunk, i + 1, pages);
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] \
+             /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            page_ids[i] + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = writeln!(pdf, "{} 0 obj\n{}\nendobj", i + 1, object);
+    }
+    let xref = pdf.len();
+    let _ = writeln!(pdf, "xref\n0 {}\n0000000000 65535 f ", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    );
+    fs::write(path, pdf).map_err(|e| format!("写入PDF文件失败: {}", e))
+}
@@ -0,0 +1,258 @@
+//! 处理流水线模块
+//!
+//! 每个通道的处理过程被描述为一组有序的阶段：
+//! 滤波阶段（滑动平均、低通、限幅）逐个变换采样值，
+//! 检测阶段（心搏/呼吸检测、数值验证）和压缩阶段（LTTB）
+//! 使用流水线中它们所处位置上的值。
+//! 例如把低通滤波放在检测之后，就只平滑显示用的压缩波形，不影响检测。
+//! 心电通道的衍生指标阶段决定是否由检测结果计算衍生风险指标，关闭后不再计算，
+//! 相关报警随之解除。
+
+use crate::types::{ChannelPipeline, PipelineStage, PipelineStageConfig};
+use std::collections::VecDeque;
+
+/// 支持配置流水线的波形通道（包含压缩阶段）
+const WAVEFORM_CHANNELS: [&str; 3] = ["ecg", "resp", "co2"];
+
+/// 支持配置流水线的数值通道（没有压缩阶段）
+const NUMERIC_CHANNELS: [&str; 2] = ["spo2", "temp"];
+
+/// 有衍生指标阶段的通道
+const DERIVED_CHANNELS: [&str; 1] = ["ecg"];
+
+/// 滑动平均窗口的最大长度
+const MAX_MOVING_AVERAGE_WINDOW: usize = 1000;
+
+/// 单个采样经过流水线后的输出
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StageOutputs {
+    /// 送入检测阶段的值，检测阶段关闭时为None
+    pub detection: Option<i32>,
+    /// 送入压缩阶段的值，压缩阶段关闭或不存在时为None
+    pub compression: Option<i32>,
+    /// 是否由检测结果计算衍生指标
+    pub derived: bool,
+}
+
+/// 滤波阶段的运行状态
+#[derive(Debug, Clone)]
+enum StageState {
+    MovingAverage { values: VecDeque<f64>, sum: f64 },
+    LowPass { last: Option<f64> },
+    None,
+}
+
+/// 单个通道流水线的运行实例
+#[derive(Debug, Clone)]
+pub struct PipelineRuntime {
+    config: ChannelPipeline,
+    states: Vec<StageState>,
+    /// 配置中是否有衍生指标阶段，没有时（衍生指标阶段加入之前保存的配置）检测后总是计算衍生指标
+    has_derived_stage: bool,
+}
+
+impl PipelineRuntime {
+    /// 根据已验证的配置创建运行实例
+    pub fn new(config: ChannelPipeline) -> Self {
+        let states = config
+            .stages
+            .iter()
+            .map(|s| match s.stage {
+                PipelineStage::MovingAverage { window } => StageState::MovingAverage {
+                    values: VecDeque::with_capacity(window),
+                    sum: 0.0,
+                },
+                PipelineStage::LowPass { .. } => StageState::LowPass { last: None },
+                _ => StageState::None,
+            })
+            .collect();
+        let has_derived_stage = config
+            .stages
+            .iter()
+            .any(|s| matches!(s.stage, PipelineStage::DerivedMetrics));
+        Self {
+            config,
+            states,
+            has_derived_stage,
+        }
+    }
+
+    /// 获取该流水线的配置
+    pub fn config(&self) -> &ChannelPipeline {
+        &self.config
+    }
+
+    /// 让一个采样依次通过各阶段
+    ///
+    /// # 参数
+    /// * `value` - 原始采样值
+    ///
+    /// # 返回值
+    /// 返回检测阶段和压缩阶段各自的输入值，以及是否计算衍生指标
+    pub fn run(&mut self, value: i32) -> StageOutputs {
+        let mut current = value as f64;
+        let mut outputs = StageOutputs::default();
+
+        for (config, state) in self.config.stages.iter().zip(self.states.iter_mut()) {
+            if !config.enabled {
+                continue;
+            }
+            current = match (&config.stage, state) {
+                (
+                    PipelineStage::MovingAverage { window },
+                    StageState::MovingAverage { values, sum },
+                ) => {
+                    if values.len() >= *window {
+                        *sum -= values.pop_front().unwrap_or(0.0);
+                    }
+                    values.push_back(current);
+                    *sum += current;
+                    *sum / values.len() as f64
+                }
+                (PipelineStage::LowPass { alpha }, StageState::LowPass { last }) => {
+                    let filtered = match last {
+                        Some(prev) => *prev + (current - *prev) * alpha,
+                        None => current,
+                    };
+                    *last = Some(filtered);
+                    filtered
+                }
+                (PipelineStage::Clamp { min, max }, _) => current.clamp(*min, *max),
+                (PipelineStage::Detection, _) => {
+                    outputs.detection = Some(current.round() as i32);
+                    current
+                }
+                (PipelineStage::DerivedMetrics, _) => {
+                    outputs.derived = outputs.detection.is_some();
+                    current
+                }
+                (PipelineStage::Compression, _) => {
+                    outputs.compression = Some(current.round() as i32);
+                    current
+                }
+                _ => current,
+            };
+        }
+        if !self.has_derived_stage {
+            outputs.derived = outputs.detection.is_some();
+        }
+
+        outputs
+    }
+}
+
+/// 获取通道的默认流水线（与固定处理流程一致：检测 → 衍生指标 → 压缩）
+pub fn default_pipeline(channel: &str) -> ChannelPipeline {
+    let mut stages = vec![PipelineStageConfig {
+        stage: PipelineStage::Detection,
+        enabled: true,
+    }];
+    if DERIVED_CHANNELS.contains(&channel) {
+        stages.push(PipelineStageConfig {
+            stage: PipelineStage::DerivedMetrics,
+            enabled: true,
+        });
+    }
+    if WAVEFORM_CHANNELS.contains(&channel) {
+        stages.push(PipelineStageConfig {
+            stage: PipelineStage::Compression,
+            enabled: true,
+        });
+    }
+    ChannelPipeline {
+        channel: channel.to_string(),
+        stages,
+    }
+}
+
+/// 所有支持配置流水线的通道
+pub fn pipeline_channels() -> impl Iterator<Item = &'static str> {
+    WAVEFORM_CHANNELS.into_iter().chain(NUMERIC_CHANNELS)
+}
+
+/// 验证通道流水线配置
+///
+/// # 参数
+/// * `pipeline` - 待验证的流水线配置
+///
+/// # 返回值
+/// 配置有效返回Ok，否则返回错误描述
+pub fn validate(pipeline: &ChannelPipeline) -> Result<(), String> {
+    let channel = pipeline.channel.as_str();
+    let is_waveform = WAVEFORM_CHANNELS.contains(&channel);
+    if !is_waveform && !NUMERIC_CHANNELS.contains(&channel) {
+        return Err(format!("通道不支持流水线配置: {}", channel));
+    }
+
+    let count = |target: &PipelineStage| {
+        pipeline
+            .stages
+            .iter()
+            .filter(|s| std::mem::discriminant(&s.stage) == std::mem::discriminant(target))
+            .count()
+    };
+    if count(&PipelineStage::Detection) != 1 {
+        return Err(format!(
+            "通道 {} 的流水线必须包含且仅包含一个检测阶段",
+            channel
+        ));
+    }
+    match (is_waveform, count(&PipelineStage::Compression)) {
+        (true, 1) | (false, 0) => {}
+        (true, _) => {
+            return Err(format!(
+                "通道 {} 的流水线必须包含且仅包含一个压缩阶段",
+                channel
+            ));
+        }
+        (false, _) => return Err(format!("数值通道 {} 不支持压缩阶段", channel)),
+    }
+    match count(&PipelineStage::DerivedMetrics) {
+        0 => {}
+        1 if DERIVED_CHANNELS.contains(&channel) => {
+            let position = |target: &PipelineStage| {
+                pipeline.stages.iter().position(|s| {
+                    std::mem::discriminant(&s.stage) == std::mem::discriminant(target)
+                })
+            };
+            if position(&PipelineStage::DerivedMetrics) < position(&PipelineStage::Detection) {
+                return Err(format!(
+                    "通道 {} 的衍生指标阶段必须位于检测阶段之后",
+                    channel
+                ));
+            }
+        }
+        1 => return Err(format!("通道 {} 没有衍生指标阶段", channel)),
+        _ => {
+            return Err(format!("通道 {} 的流水线最多包含一个衍生指标阶段", channel));
+        }
+    }
+
+    for stage in &pipeline.stages {
+        match stage.stage {
+            PipelineStage::MovingAverage { window } => {
+                if window == 0 || window > MAX_MOVING_AVERAGE_WINDOW {
+                    return Err(format!(
+                        "滑动平均窗口必须在1到{}之间",
+                        MAX_MOVING_AVERAGE_WINDOW
+                    ));
+                }
+            }
+            PipelineStage::LowPass { alpha } => {
+                if !(alpha > 0.0 && alpha <= 1.0) {
+                    return Err("低通滤波系数必须在(0, 1]范围内".to_string());
+                }
+            }
+            PipelineStage::Clamp { min, max } => {
+                if !min.is_finite() || !max.is_finite() || min >= max {
+                    return Err("限幅范围无效，要求min < max".to_string());
+                }
+            }
+            PipelineStage::Detection
+            | PipelineStage::DerivedMetrics
+            | PipelineStage::Compression => {}
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,281 @@
+//! 回放数据源模块
+//!
+//! 按录制时的采样率回放原始数据文件，每行一个串口协议采样。启动时为文件建立
+//! 行偏移索引，用于按时间定位；回放线程按当前速度倍数计算应输出的采样数，
+//! 可在回放过程中调整速度或跳转到指定时间，便于快速浏览整晚的记录并定位报警时刻。
+
+use crate::channels::ChannelRegistry;
+use crate::framing::{deliver_frame, FrameParser, LineFrameParser};
+use crate::serial_manager::StreamCounters;
+use crate::types::{DataQueue, PlaybackConfig, PlaybackPosition};
+use crate::watchdog::Heartbeat;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 回放线程的调度间隔
+const TICK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 回放速度倍数的范围
+const SPEED_RANGE: std::ops::RangeInclusive<f64> = 0.1..=64.0;
+
+/// 验证回放速度倍数
+pub fn validate_speed(speed: f64) -> Result<(), String> {
+    if !speed.is_finite() || !SPEED_RANGE.contains(&speed) {
+        return Err(format!(
+            "回放速度必须在{}到{}倍之间",
+            SPEED_RANGE.start(),
+            SPEED_RANGE.end()
+        ));
+    }
+    Ok(())
+}
+
+/// 验证回放数据源配置
+pub fn validate_config(config: &PlaybackConfig) -> Result<(), String> {
+    if config.path.trim().is_empty() {
+        return Err("录制文件路径不能为空".to_string());
+    }
+    if !config.sample_rate_hz.is_finite() || !(1.0..=10_000.0).contains(&config.sample_rate_hz) {
+        return Err("采样率必须在1到10000Hz之间".to_string());
+    }
+    validate_speed(config.speed)
+}
+
+/// 为录制文件建立行偏移索引，跳过空行
+fn build_index(path: &str) -> Result<Vec<u64>, String> {
+    let file = File::open(path).map_err(|e| format!("无法打开录制文件 {}: {}", path, e))?;
+    let mut reader = BufReader::new(file);
+    let mut offsets = Vec::new();
+    let mut offset = 0u64;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let len = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| format!("无法读取录制文件 {}: {}", path, e))?;
+        if len == 0 {
+            break;
+        }
+        if line.iter().any(|b| !b.is_ascii_whitespace()) {
+            offsets.push(offset);
+        }
+        offset += len as u64;
+    }
+    Ok(offsets)
+}
+
+/// 回放控制状态，在命令线程和回放线程之间共享
+#[derive(Debug)]
+struct PlaybackControl {
+    /// 速度倍数（f64的位表示）
+    speed_bits: AtomicU64,
+    /// 下一个要输出的采样序号
+    position: AtomicUsize,
+    /// 待执行的跳转目标采样序号
+    seek_to: Mutex<Option<usize>>,
+    /// 是否已回放到末尾
+    finished: AtomicBool,
+}
+
+impl PlaybackControl {
+    fn speed(&self) -> f64 {
+        f64::from_bits(self.speed_bits.load(Ordering::Relaxed))
+    }
+}
+
+pub struct PlaybackReader {
+    config: PlaybackConfig,
+    data_queue: DataQueue,
+    channel_registry: Arc<Mutex<ChannelRegistry>>,
+    counters: Arc<StreamCounters>,
+    control: Arc<PlaybackControl>,
+    /// 录制文件的行偏移索引
+    offsets: Arc<Vec<u64>>,
+    stop_flag: Arc<AtomicBool>,
+    heartbeat: Heartbeat,
+}
+
+impl PlaybackReader {
+    pub fn new(
+        config: PlaybackConfig,
+        data_queue: DataQueue,
+        channel_registry: Arc<Mutex<ChannelRegistry>>,
+        counters: Arc<StreamCounters>,
+    ) -> Self {
+        println!(
+            "[PlaybackReader] 初始化，文件={}，速度={}倍",
+            config.path, config.speed
+        );
+        let control = PlaybackControl {
+            speed_bits: AtomicU64::new(config.speed.to_bits()),
+            position: AtomicUsize::new(0),
+            seek_to: Mutex::new(None),
+            finished: AtomicBool::new(false),
+        };
+        Self {
+            config,
+            data_queue,
+            channel_registry,
+            counters,
+            control: Arc::new(control),
+            offsets: Arc::new(Vec::new()),
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            heartbeat: Heartbeat::new(),
+        }
+    }
+
+    /// 回放线程的心跳，每个调度周期更新一次
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
+
+    /// 采样序号对应的时间（距录制开始的毫秒数）
+    fn index_to_ms(&self, index: usize) -> u64 {
+        (index as f64 * 1000.0 / self.config.sample_rate_hz) as u64
+    }
+
+    /// 设置回放速度倍数，立即生效
+    pub fn set_speed(&self, speed: f64) -> Result<(), String> {
+        validate_speed(speed)?;
+        self.control
+            .speed_bits
+            .store(speed.to_bits(), Ordering::Relaxed);
+        println!("[PlaybackReader] 回放速度已设置为{}倍", speed);
+        Ok(())
+    }
+
+    /// 跳转到指定时间
+    ///
+    /// # 参数
+    /// * `timestamp_ms` - 距录制开始的毫秒数
+    pub fn seek(&self, timestamp_ms: u64) -> Result<(), String> {
+        let duration_ms = self.index_to_ms(self.offsets.len());
+        if timestamp_ms > duration_ms {
+            return Err(format!(
+                "跳转位置{}毫秒超出录制时长{}毫秒",
+                timestamp_ms, duration_ms
+            ));
+        }
+        let index = (timestamp_ms as f64 * self.config.sample_rate_hz / 1000.0) as usize;
+        *self.control.seek_to.lock().unwrap() = Some(index.min(self.offsets.len()));
+        println!("[PlaybackReader] 跳转到{}毫秒", timestamp_ms);
+        Ok(())
+    }
+
+    /// 获取当前回放进度
+    pub fn position(&self) -> PlaybackPosition {
+        PlaybackPosition {
+            position_ms: self.index_to_ms(self.control.position.load(Ordering::Relaxed)),
+            duration_ms: self.index_to_ms(self.offsets.len()),
+            speed: self.control.speed(),
+            finished: self.control.finished.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn start(&mut self) -> Result<(), String> {
+        validate_config(&self.config)?;
+        let offsets = Arc::new(build_index(&self.config.path)?);
+        if offsets.is_empty() {
+            return Err(format!("录制文件 {} 中没有数据", self.config.path));
+        }
+        self.offsets = offsets.clone();
+        let file = File::open(&self.config.path)
+            .map_err(|e| format!("无法打开录制文件 {}: {}", self.config.path, e))?;
+        println!(
+            "[PlaybackReader] 开始回放，共{}个采样，时长{}秒",
+            offsets.len(),
+            self.index_to_ms(offsets.len()) / 1000
+        );
+
+        let sample_rate_hz = self.config.sample_rate_hz;
+        let repeat = self.config.repeat;
+        let control = self.control.clone();
+        let stop_flag = self.stop_flag.clone();
+        let data_queue = self.data_queue.clone();
+        let channel_registry = self.channel_registry.clone();
+        let counters = self.counters.clone();
+        let heartbeat = self.heartbeat.clone();
+        heartbeat.beat();
+
+        thread::spawn(move || {
+            let mut reader = BufReader::new(file);
+            let mut line = String::new();
+            let mut index = 0usize;
+            // 上一帧的序号，用于统计丢帧
+            let mut last_sequence: Option<u64> = None;
+            // 自 `base_time` 起已按当前速度输出的采样数，速度或位置变化时重新计时
+            let mut base_time = Instant::now();
+            let mut base_speed = control.speed();
+            let mut emitted = 0u64;
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                heartbeat.beat();
+
+                let seek_to = control.seek_to.lock().unwrap().take();
+                if let Some(target) = seek_to {
+                    index = target;
+                    control.finished.store(false, Ordering::Relaxed);
+                    last_sequence = None;
+                    base_time = Instant::now();
+                    emitted = 0;
+                    if let Some(&offset) = offsets.get(index) {
+                        if let Err(e) = reader.seek(SeekFrom::Start(offset)) {
+                            eprintln!("[PlaybackReader][线程] 定位录制文件失败: {}", e);
+                        }
+                    }
+                }
+                let speed = control.speed();
+                if speed != base_speed {
+                    base_speed = speed;
+                    base_time = Instant::now();
+                    emitted = 0;
+                }
+
+                let due = (base_time.elapsed().as_secs_f64() * sample_rate_hz * speed) as u64;
+                let registry = channel_registry.lock().unwrap();
+                while emitted < due && index < offsets.len() {
+                    line.clear();
+                    match reader.read_line(&mut line) {
+                        Ok(0) => break,
+                        Ok(_) if line.trim().is_empty() => continue,
+                        Ok(len) => {
+                            counters.record_bytes(len);
+                            let parsed = LineFrameParser.decode(line.as_bytes(), &registry);
+                            deliver_frame(parsed, &counters, &mut last_sequence, &data_queue);
+                        }
+                        Err(e) => {
+                            eprintln!("[PlaybackReader][线程] 读取录制文件失败: {}", e);
+                            break;
+                        }
+                    }
+                    index += 1;
+                    emitted += 1;
+                }
+                drop(registry);
+                control.position.store(index, Ordering::Relaxed);
+
+                if index >= offsets.len() && !control.finished.load(Ordering::Relaxed) {
+                    if repeat {
+                        *control.seek_to.lock().unwrap() = Some(0);
+                    } else {
+                        println!("[PlaybackReader][线程] 已回放到末尾");
+                        control.finished.store(true, Ordering::Relaxed);
+                    }
+                }
+                thread::sleep(TICK_INTERVAL);
+            }
+            println!("[PlaybackReader][线程] 回放线程安全退出");
+        });
+
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        println!("[PlaybackReader] 停止回放");
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
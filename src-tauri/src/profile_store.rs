@@ -0,0 +1,111 @@
+use crate::ipc_schema;
+use crate::types::{ChannelAdjustment, EcgProcessingParams, TemperatureCalibration};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::Manager;
+
+/// 连接配置档案，按串口名保存设备相关的设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ConnectionProfile {
+    pub port_name: String,
+    pub baud_rate: u32,
+    /// 各通道的增益/反相/偏移设置，键为通道名
+    #[serde(default)]
+    pub channel_adjustments: BTreeMap<String, ChannelAdjustment>,
+    /// 该设备的体温校准结果
+    #[serde(default)]
+    pub temperature_calibration: Option<TemperatureCalibration>,
+    /// 该设备的ECG心搏检测参数，未调整时为None
+    #[serde(default)]
+    pub ecg_processing_params: Option<EcgProcessingParams>,
+    pub updated_at: String,
+}
+
+impl ConnectionProfile {
+    pub fn new(port_name: &str, baud_rate: u32) -> Self {
+        Self {
+            port_name: port_name.to_string(),
+            baud_rate,
+            channel_adjustments: BTreeMap::new(),
+            temperature_calibration: None,
+            ecg_processing_params: None,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+pub struct ProfileStore {
+    data_file: PathBuf,
+}
+
+impl ProfileStore {
+    pub fn new(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("无法获取应用数据目录: {}", e))?;
+
+        let data_dir = app_data_dir.join("vital-signs");
+        if !data_dir.exists() {
+            fs::create_dir_all(&data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+        }
+
+        let data_file = data_dir.join("connection_profiles.json");
+
+        Ok(Self { data_file })
+    }
+
+    pub fn load_profiles(&self) -> Result<Vec<ConnectionProfile>, String> {
+        if !self.data_file.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json_data =
+            fs::read_to_string(&self.data_file).map_err(|e| format!("读取连接配置失败: {}", e))?;
+
+        ipc_schema::from_stored_json(&json_data).map_err(|e| format!("解析连接配置失败: {}", e))
+    }
+
+    pub fn get_profile(&self, port_name: &str) -> Result<Option<ConnectionProfile>, String> {
+        Ok(self
+            .load_profiles()?
+            .into_iter()
+            .find(|p| p.port_name == port_name))
+    }
+
+    pub fn save_profile(&self, profile: &ConnectionProfile) -> Result<(), String> {
+        let mut profiles = self.load_profiles()?;
+        let mut profile = profile.clone();
+        profile.updated_at = chrono::Utc::now().to_rfc3339();
+
+        match profiles
+            .iter_mut()
+            .find(|p| p.port_name == profile.port_name)
+        {
+            Some(existing) => *existing = profile,
+            None => profiles.push(profile),
+        }
+
+        let json_data = serde_json::to_string_pretty(&profiles)
+            .map_err(|e| format!("序列化连接配置失败: {}", e))?;
+
+        fs::write(&self.data_file, json_data).map_err(|e| format!("保存连接配置失败: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn delete_profile(&self, port_name: &str) -> Result<(), String> {
+        let mut profiles = self.load_profiles()?;
+        profiles.retain(|p| p.port_name != port_name);
+
+        let json_data = serde_json::to_string_pretty(&profiles)
+            .map_err(|e| format!("序列化连接配置失败: {}", e))?;
+
+        fs::write(&self.data_file, json_data).map_err(|e| format!("保存连接配置失败: {}", e))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//! 患者数据清除模块
+//!
+//! 按患者ID不可恢复地删除患者信息、测量记录、知情同意、报警历史、会话和导出文件，
+//! 并报告实际删除的内容。文件在删除前先用零覆盖，使删除后无法从磁盘上直接恢复原内容。
+//! 某一步失败时继续执行其余步骤，失败原因记入报告，保证审计日志总能记录实际清除的内容。
+
+use crate::alarm_history::AlarmHistoryStore;
+use crate::data_processor::DataProcessor;
+use crate::patient_store::PatientStore;
+use crate::session_store::SessionStore;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// 患者数据清除报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct PurgeReport {
+    /// 被清除的患者ID
+    pub patient_id: String,
+    /// 是否删除了患者信息
+    pub demographics_removed: bool,
+    /// 被清除的测量记录数
+    pub measurements_removed: usize,
+    /// 被删除的知情同意记录数
+    pub consents_removed: usize,
+    /// 被删除的报警历史事件数
+    pub alarm_events_removed: usize,
+    /// 被删除的会话ID
+    pub sessions_removed: Vec<String>,
+    /// 被删除的导出文件路径
+    pub exports_removed: Vec<String>,
+    /// 失败步骤的错误信息，为空表示全部清除成功
+    pub errors: Vec<String>,
+}
+
+impl PurgeReport {
+    /// 清除内容的文字描述，写入审计日志
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "患者={}，患者信息={}，测量记录{}条，知情同意{}条，报警历史{}条，会话{}个[{}]，导出文件{}个[{}]",
+            self.patient_id,
+            if self.demographics_removed {
+                "已删除"
+            } else {
+                "无"
+            },
+            self.measurements_removed,
+            self.consents_removed,
+            self.alarm_events_removed,
+            self.sessions_removed.len(),
+            self.sessions_removed.join(","),
+            self.exports_removed.len(),
+            self.exports_removed.join(",")
+        );
+        if !self.errors.is_empty() {
+            summary.push_str(&format!("，部分清除失败: {}", self.errors.join("；")));
+        }
+        summary
+    }
+}
+
+/// 先用零覆盖文件内容再删除文件
+pub fn secure_delete(path: &Path) -> Result<(), String> {
+    let len = fs::metadata(path)
+        .map_err(|e| format!("无法读取文件信息 {}: {}", path.display(), e))?
+        .len();
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("无法打开文件 {}: {}", path.display(), e))?;
+    let zeros = [0u8; 4096];
+    let mut remaining = len;
+    while remaining > 0 {
+        let chunk = remaining.min(zeros.len() as u64) as usize;
+        file.write_all(&zeros[..chunk])
+            .map_err(|e| format!("覆盖文件失败 {}: {}", path.display(), e))?;
+        remaining -= chunk as u64;
+    }
+    file.sync_all()
+        .map_err(|e| format!("覆盖文件失败 {}: {}", path.display(), e))?;
+    drop(file);
+    fs::remove_file(path).map_err(|e| format!("删除文件失败 {}: {}", path.display(), e))
+}
+
+/// 用新内容替换文件，旧文件先覆盖删除，避免被移除的内容残留在磁盘上
+pub fn secure_rewrite(path: &Path, content: &str) -> Result<(), String> {
+    let temp = path.with_extension("purge-tmp");
+    fs::write(&temp, content).map_err(|e| format!("写入文件失败 {}: {}", temp.display(), e))?;
+    if path.exists() {
+        secure_delete(path)?;
+    }
+    fs::rename(&temp, path).map_err(|e| format!("替换文件失败 {}: {}", path.display(), e))
+}
+
+/// 清除患者数据
+///
+/// 各步骤互不依赖，某一步失败时继续执行其余步骤，失败原因记入报告的 `errors`。
+///
+/// # 参数
+/// * `patient_id` - 患者ID
+/// * `include_sessions` - 是否同时删除该患者的会话
+/// * `patient_store` - 患者存储
+/// * `session_store` - 会话存储
+/// * `alarm_history` - 报警历史存储，删除该患者所有会话的报警事件
+/// * `processor` - 正在运行的处理器，清除当前患者时一并清除其测量记录
+pub fn purge_patient_data(
+    patient_id: &str,
+    include_sessions: bool,
+    patient_store: &PatientStore,
+    session_store: &SessionStore,
+    alarm_history: Option<&AlarmHistoryStore>,
+    processor: Option<&DataProcessor>,
+) -> Result<PurgeReport, String> {
+    if patient_id.trim().is_empty() {
+        return Err("患者ID不能为空".to_string());
+    }
+    let mut errors = Vec::new();
+    let mut check = |step: &str, result: Result<(), String>| {
+        if let Err(e) = result {
+            eprintln!("[Purge] 清除{}失败: {}", step, e);
+            errors.push(format!("{}: {}", step, e));
+        }
+    };
+
+    let mut measurements_removed = 0;
+    match patient_store.current_patient_id() {
+        Ok(current) if current.as_deref() == Some(patient_id) => {
+            measurements_removed = processor.map_or(0, DataProcessor::clear_measurements);
+        }
+        Ok(_) => {}
+        Err(e) => check("测量记录", Err(e)),
+    }
+
+    // 会话被删除前先记下会话ID，用于清除这些会话的报警历史
+    let mut session_ids = Vec::new();
+    check(
+        "会话列表",
+        session_store.list().map(|sessions| {
+            session_ids = sessions
+                .into_iter()
+                .filter(|metadata| metadata.patient_id.as_deref() == Some(patient_id))
+                .map(|metadata| metadata.session_id)
+                .collect();
+        }),
+    );
+
+    let mut demographics_removed = false;
+    check(
+        "患者信息",
+        patient_store
+            .purge_patient_info(patient_id)
+            .map(|removed| demographics_removed = removed),
+    );
+    let mut consents_removed = 0;
+    check(
+        "知情同意",
+        patient_store
+            .purge_consents(patient_id)
+            .map(|count| consents_removed = count),
+    );
+    let mut alarm_events_removed = 0;
+    if let Some(alarm_history) = alarm_history {
+        check(
+            "报警历史",
+            alarm_history
+                .purge_sessions(&session_ids)
+                .map(|count| alarm_events_removed = count),
+        );
+    }
+    let mut exports_removed = Vec::new();
+    check(
+        "导出文件",
+        session_store
+            .purge_exports(patient_id)
+            .map(|removed| exports_removed = removed),
+    );
+    let mut sessions_removed = Vec::new();
+    if include_sessions {
+        check(
+            "会话",
+            session_store
+                .purge_sessions(patient_id)
+                .map(|removed| sessions_removed = removed),
+        );
+    }
+
+    let report = PurgeReport {
+        patient_id: patient_id.to_string(),
+        demographics_removed,
+        measurements_removed,
+        consents_removed,
+        alarm_events_removed,
+        sessions_removed,
+        exports_removed,
+        errors,
+    };
+    println!("[Purge] 已清除患者数据: {}", report.summary());
+    Ok(report)
+}
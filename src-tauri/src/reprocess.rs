@@ -0,0 +1,129 @@
+//! 录制数据重新处理模块
+//!
+//! 把录制的原始数据按当前的处理配置（滤波流水线、通道校正、检测参数）重新
+//! 处理一遍，得到的趋势和事件标注保存为衍生会话，便于把算法改进应用到旧数据上。
+//! 处理在独立的处理器实例中同步进行，只在复制处理配置时访问正在运行的处理器，
+//! 不影响正在进行的实时监护。
+
+use crate::channels::ChannelRegistry;
+use crate::data_processor::DataProcessor;
+use crate::framing::{FrameParser, LineFrameParser};
+use crate::sample_ring::SampleRing;
+use crate::session_store::{self, StoredSession};
+use crate::types::ReprocessConfig;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+/// 验证重新处理配置
+pub fn validate_config(config: &ReprocessConfig) -> Result<(), String> {
+    if config.recording_path.trim().is_empty() {
+        return Err("录制文件路径不能为空".to_string());
+    }
+    if !config.sample_rate_hz.is_finite() || !(1.0..=10_000.0).contains(&config.sample_rate_hz) {
+        return Err("采样率必须在1到10000Hz之间".to_string());
+    }
+    Ok(())
+}
+
+/// 统计录制文件中的采样行数，跳过空行
+fn count_samples(reader: impl BufRead) -> Result<u64, String> {
+    let mut count = 0;
+    for line in reader.lines() {
+        let line = line.map_err(|e| format!("无法读取录制文件: {}", e))?;
+        if !line.trim().is_empty() {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// 录制文件第一个采样的时间戳：按文件修改时间（即录制结束时间）减去录制时长
+fn recording_start(path: &str, samples: u64, sample_rate_hz: f64) -> Result<u64, String> {
+    let modified = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .map_err(|e| format!("无法读取录制文件信息 {}: {}", path, e))?;
+    let end = modified
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("录制文件修改时间无效: {}", e))?
+        .as_millis() as u64;
+    Ok(end.saturating_sub((samples as f64 * 1000.0 / sample_rate_hz) as u64))
+}
+
+/// 创建用于重新处理的离线处理器，复制正在运行的处理器的处理配置
+///
+/// # 参数
+/// * `live` - 正在运行的处理器
+pub fn offline_processor(live: &DataProcessor) -> Result<DataProcessor, String> {
+    let processor = DataProcessor::new(Arc::new(SampleRing::new(1)));
+    processor.copy_processing_config(live)?;
+    Ok(processor)
+}
+
+/// 用离线处理器重新处理录制文件
+///
+/// # 参数
+/// * `config` - 重新处理配置
+/// * `processor` - 由 `offline_processor` 创建的离线处理器
+/// * `registry` - 通道注册表，用于解析录制的采样
+///
+/// # 返回值
+/// 返回衍生会话，由调用方保存
+pub fn reprocess_recording(
+    config: &ReprocessConfig,
+    processor: &DataProcessor,
+    registry: &ChannelRegistry,
+) -> Result<StoredSession, String> {
+    validate_config(config)?;
+    let path = config.recording_path.as_str();
+    let open = || File::open(path).map_err(|e| format!("无法打开录制文件 {}: {}", path, e));
+
+    let start_timestamp = match config.start_timestamp {
+        Some(timestamp) => timestamp,
+        None => {
+            let samples = count_samples(BufReader::new(open()?))?;
+            recording_start(path, samples, config.sample_rate_hz)?
+        }
+    };
+    println!(
+        "[Reprocess] 开始重新处理录制文件 {}，起始时间戳={}",
+        path, start_timestamp
+    );
+
+    let mut index = 0u64;
+    let mut rejected = 0u64;
+    let mut timestamp = start_timestamp;
+    for line in BufReader::new(open()?).lines() {
+        let line = line.map_err(|e| format!("无法读取录制文件 {}: {}", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        timestamp = start_timestamp + (index as f64 * 1000.0 / config.sample_rate_hz) as u64;
+        index += 1;
+        match LineFrameParser.decode(line.as_bytes(), registry) {
+            Ok(frame) => {
+                // 录制中的设备应答行没有体征数据
+                if let Some(vital_signs) = frame.vital_signs {
+                    processor.process_sample(vital_signs, timestamp);
+                }
+            }
+            Err(_) => rejected += 1,
+        }
+    }
+    if index == rejected {
+        return Err(format!("录制文件 {} 中没有有效数据", path));
+    }
+
+    let mut session = session_store::capture(processor);
+    session.metadata.derived_from = config.source_session_id.clone();
+    session.metadata.source_recording = Some(path.to_string());
+    session.metadata.start_timestamp = start_timestamp;
+    session.metadata.end_timestamp = timestamp;
+    session.metadata.rejected_samples = rejected;
+    println!(
+        "[Reprocess] 重新处理完成，衍生会话={}，采样{}个，跳过{}个",
+        session.metadata.session_id, session.metadata.sample_count, rejected
+    );
+    Ok(session)
+}
@@ -0,0 +1,85 @@
+//! 采样时间轴模块
+//!
+//! 采样时间戳原先直接取系统时钟，NTP校时或手动修改时间会让记录的时间戳跳变甚至倒退。
+//! 采样时间轴改为以处理器创建时的系统时间为起点、按单调时钟推进，系统时钟跳变不影响
+//! 时间轴；同时比较系统时钟与时间轴的偏差，偏差突变时报告一次时钟调整，由处理器记录为
+//! 会话标注，导出时可据此换算回系统时间。
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// 系统时钟与时间轴的偏差变化超过该值（毫秒）视为时钟调整
+const JUMP_THRESHOLD_MS: i64 = 1000;
+
+/// 一次系统时钟调整
+#[derive(Debug, Clone, Copy)]
+pub struct ClockJump {
+    /// 发现调整时的时间轴时间戳（毫秒）
+    pub timestamp: u64,
+    /// 系统时钟的调整量（毫秒），正数表示向前调整
+    pub adjustment_ms: i64,
+    /// 调整后系统时钟相对时间轴的偏差（毫秒）
+    pub offset_ms: i64,
+}
+
+/// 单调递增的采样时间轴
+#[derive(Debug)]
+pub struct SampleClock {
+    /// 起点的系统时间（毫秒）
+    origin_ms: u64,
+    /// 起点的单调时钟
+    origin: Instant,
+    /// 上次返回的时间戳
+    last_ms: u64,
+    /// 系统时钟相对时间轴的当前偏差（毫秒）
+    offset_ms: i64,
+}
+
+impl SampleClock {
+    pub fn new() -> Self {
+        let origin_ms = wall_millis();
+        Self {
+            origin_ms,
+            origin: Instant::now(),
+            last_ms: origin_ms,
+            offset_ms: 0,
+        }
+    }
+
+    /// 取当前采样时间戳（毫秒），系统时钟发生调整时一并返回调整信息
+    pub fn now(&mut self) -> (u64, Option<ClockJump>) {
+        let timestamp = self.peek();
+        self.last_ms = timestamp;
+
+        let offset_ms = wall_millis() as i64 - timestamp as i64;
+        let adjustment_ms = offset_ms - self.offset_ms;
+        if adjustment_ms.abs() < JUMP_THRESHOLD_MS {
+            return (timestamp, None);
+        }
+        self.offset_ms = offset_ms;
+        let jump = ClockJump {
+            timestamp,
+            adjustment_ms,
+            offset_ms,
+        };
+        (timestamp, Some(jump))
+    }
+
+    /// 读取时间轴的当前时间（毫秒），不推进时间轴也不检查系统时钟调整，
+    /// 供处理线程之外与采样时间戳比较使用
+    pub fn peek(&self) -> u64 {
+        (self.origin_ms + self.origin.elapsed().as_millis() as u64).max(self.last_ms)
+    }
+}
+
+impl Default for SampleClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn wall_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
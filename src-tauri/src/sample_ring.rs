@@ -0,0 +1,119 @@
+//! 原始采样环形队列模块
+//!
+//! 每个数据源只有一个读取线程写入，数据处理线程是唯一的读取方，因此原始采样队列使用
+//! 单生产者单消费者的无等待环形缓冲区（rtrb），替代原先两端共用一把锁的 `VecDeque`，
+//! 250Hz以上采样率下读取线程不会因为处理线程持有锁而等待。
+//!
+//! 生产端和消费端各由一把锁保护，只用于重连或重启处理线程时新旧线程交接，正常运行时
+//! 每把锁只有一个线程使用，不会发生竞争。`get_latest_data` 读取单独的小快照缓冲区，
+//! 读取线程只在快照空闲时更新快照，查询命令不会阻塞数据采集。
+
+use crate::types::VitalSigns;
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// 原始采样队列的默认容量（采样数）
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+/// 快照缓冲区保留的最新采样数（250Hz下约1秒）
+const SNAPSHOT_CAPACITY: usize = 250;
+
+/// 读取线程与处理线程之间的原始采样队列
+pub struct SampleRing {
+    producer: Mutex<Producer<VitalSigns>>,
+    consumer: Mutex<Consumer<VitalSigns>>,
+    /// 最近收到的采样，供 `get_latest_data` 查询
+    snapshot: Mutex<VecDeque<VitalSigns>>,
+    capacity: usize,
+    /// 队列已满时被丢弃的采样总数
+    overflowed: AtomicU64,
+}
+
+impl SampleRing {
+    /// 创建队列
+    ///
+    /// # 参数
+    /// * `capacity` - 队列容量（采样数）
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (producer, consumer) = RingBuffer::new(capacity);
+        Self {
+            producer: Mutex::new(producer),
+            consumer: Mutex::new(consumer),
+            snapshot: Mutex::new(VecDeque::with_capacity(SNAPSHOT_CAPACITY)),
+            capacity,
+            overflowed: AtomicU64::new(0),
+        }
+    }
+
+    /// 放入一个采样，由数据源读取线程调用
+    ///
+    /// # 返回值
+    /// 队列已满时丢弃该采样并返回false
+    pub fn push(&self, sample: VitalSigns) -> bool {
+        if let Ok(mut snapshot) = self.snapshot.try_lock() {
+            if snapshot.len() >= SNAPSHOT_CAPACITY {
+                snapshot.pop_front();
+            }
+            snapshot.push_back(sample.clone());
+        }
+        let pushed = self.producer.lock().unwrap().push(sample).is_ok();
+        if !pushed {
+            self.overflowed.fetch_add(1, Ordering::Relaxed);
+        }
+        pushed
+    }
+
+    /// 取出最早的采样，由数据处理线程调用
+    pub fn pop(&self) -> Option<VitalSigns> {
+        self.consumer.lock().unwrap().pop().ok()
+    }
+
+    /// 丢弃最早的至多 `count` 个采样，由数据处理线程按反压策略调用
+    ///
+    /// # 返回值
+    /// 返回实际丢弃的采样数
+    pub fn discard(&self, count: usize) -> u64 {
+        let mut consumer = self.consumer.lock().unwrap();
+        let mut discarded = 0;
+        while discarded < count && consumer.pop().is_ok() {
+            discarded += 1;
+        }
+        discarded as u64
+    }
+
+    /// 队列中等待处理的采样数
+    pub fn queued(&self) -> usize {
+        self.consumer.lock().unwrap().slots()
+    }
+
+    /// 队列容量（采样数）
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 队列已满时被丢弃的采样总数
+    pub fn overflowed(&self) -> u64 {
+        self.overflowed.load(Ordering::Relaxed)
+    }
+
+    /// 获取最近收到的N个采样，最新的在前
+    ///
+    /// # 参数
+    /// * `count` - 最多返回的采样数
+    /// * `since` - 起始接收时间戳（毫秒，包含）
+    /// * `until` - 结束接收时间戳（毫秒，包含）
+    pub fn latest(&self, count: usize, since: Option<u64>, until: Option<u64>) -> Vec<VitalSigns> {
+        let snapshot = self.snapshot.lock().unwrap();
+        snapshot
+            .iter()
+            .rev()
+            .filter(|s| since.is_none_or(|t| s.received_at >= t))
+            .filter(|s| until.is_none_or(|t| s.received_at <= t))
+            .take(count)
+            .cloned()
+            .collect()
+    }
+}
@@ -1,9 +1,248 @@
-use crate::serial_reader::SerialReader;
+use crate::arrhythmia_sim::{self, GroundTruth, GroundTruthBeat, SimulatorConfig};
+use crate::channels::ChannelRegistry;
+use crate::file_tail_reader::{self, FileTailReader};
+use crate::firmware::{self, FirmwareInfo, FirmwareRequirement};
+use crate::framing::{FrameError, FrameParser, LineFrameParser};
+use crate::hid_reader::{self, HidReader};
+use crate::playback_reader::{self, PlaybackReader};
+use crate::sample_ring::{self, SampleRing};
+use crate::serial_reader::{validate_resilience_config, SerialReader};
+use crate::spp_reader::{self, SppReader};
+use crate::stress_sim::{StressMonitor, StressReport};
+use crate::tcp_reader::{self, TcpReader};
 use crate::test_reader::TestReader;
-use crate::types::{DataQueue, DataSourceType, SerialConfig, SerialStatus, VitalSigns};
+use crate::thread_tuning;
+use crate::types::{
+    ChannelDescriptor, ConnectionStatistics, DataQueue, DataSourceSwitchEvent, DataSourceType,
+    DeviceCapabilities, FileTailConfig, FrameParserKind, HidConfig, LinkStatistics,
+    LoopbackTestResult, PlaybackConfig, PlaybackPosition, PortTestStatus, SerialConfig,
+    SerialErrorCounts, SerialErrorKind, SerialResilienceConfig, SerialStatus, SerialStatusReport,
+    StreamHealth, TcpSerialConfig, ThreadTuningConfig, UdpConfig, VitalSigns,
+};
+use crate::udp_reader::{self, UdpReader};
+use crate::watchdog::Heartbeat;
 use serialport::SerialPortType;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 超过该时长没有有效采样视为数据流停滞（毫秒）
+const STALL_THRESHOLD_MS: u64 = 3000;
+/// 计算解析拒绝率的时间窗口（毫秒）
+const REJECTION_WINDOW_MS: u64 = 5000;
+/// 拒绝率达到该比例视为解析器持续拒绝数据
+const REJECTION_RATE_THRESHOLD: f64 = 0.2;
+/// 设备能力检测需要的采样数（250Hz下2秒），期间未上报的连续通道视为设备不支持
+const CAPABILITY_DISCOVERY_SAMPLES: u64 = 500;
+
+/// 当前时间戳（毫秒）
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// 数据流计数器，由读取线程更新，每次连接重新创建
+#[derive(Debug)]
+pub struct StreamCounters {
+    /// 解析成功的采样数
+    accepted: AtomicU64,
+    /// 无法解析的数据帧数（格式错误和校验失败之和）
+    rejected: AtomicU64,
+    /// 帧完整性统计
+    link: Mutex<LinkStatistics>,
+    /// 最近一次解析成功的时间戳（毫秒），尚无采样时为0
+    last_sample_at: AtomicU64,
+    /// 按类别统计的串口错误次数
+    errors: Mutex<BTreeMap<SerialErrorKind, u64>>,
+    /// 设备断开后成功重新连接的次数
+    reconnects: AtomicU64,
+    /// 接收的字节数
+    bytes: AtomicU64,
+    /// 计数器创建（即开始连接）的时间戳（毫秒）
+    started_at: u64,
+    /// 最近一次错误及其时间戳（毫秒），读取超时不计入
+    last_error: Mutex<Option<(String, u64)>>,
+    /// 设备上报过的通道ID
+    seen_channels: Mutex<BTreeSet<String>>,
+    /// 设备应答的固件版本
+    firmware_version: Mutex<Option<String>>,
+}
+
+impl StreamCounters {
+    pub fn new() -> Self {
+        Self {
+            accepted: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            link: Mutex::new(LinkStatistics::default()),
+            last_sample_at: AtomicU64::new(0),
+            errors: Mutex::new(BTreeMap::new()),
+            reconnects: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            started_at: now_millis(),
+            last_error: Mutex::new(None),
+            seen_channels: Mutex::new(BTreeSet::new()),
+            firmware_version: Mutex::new(None),
+        }
+    }
+
+    /// 记录一个解析成功的采样
+    pub fn record_accepted(&self, sample: &VitalSigns) {
+        self.accepted.fetch_add(1, Ordering::Relaxed);
+        self.last_sample_at.store(now_millis(), Ordering::Relaxed);
+        self.link.lock().unwrap().frames_received += 1;
+        let mut seen = self.seen_channels.lock().unwrap();
+        for channel in sample.present_channels() {
+            if !seen.contains(channel) {
+                seen.insert(channel.to_string());
+            }
+        }
+    }
+
+    /// 记录一个无法解码的数据帧
+    pub fn record_rejected(&self, error: FrameError) {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+        let mut link = self.link.lock().unwrap();
+        match error {
+            FrameError::Malformed => link.malformed_frames += 1,
+            FrameError::ChecksumMismatch => link.checksum_failures += 1,
+        }
+    }
+
+    /// 记录一次丢弃无效字节后的重新同步
+    pub fn record_resync(&self, discarded_bytes: usize) {
+        self.record_error(
+            SerialErrorKind::Framing,
+            &format!("丢弃{}字节无效数据", discarded_bytes),
+        );
+        let mut link = self.link.lock().unwrap();
+        link.resyncs += 1;
+        link.discarded_bytes += discarded_bytes as u64;
+    }
+
+    /// 记录一次帧序号不连续
+    ///
+    /// # 参数
+    /// * `missed` - 按序号推算丢失的帧数
+    pub fn record_sequence_gap(&self, missed: u64) {
+        let mut link = self.link.lock().unwrap();
+        link.sequence_gaps += 1;
+        link.missed_frames += missed;
+    }
+
+    /// 记录一次串口错误
+    ///
+    /// # 参数
+    /// * `kind` - 错误类别
+    /// * `message` - 错误信息
+    pub fn record_error(&self, kind: SerialErrorKind, message: &str) {
+        *self.errors.lock().unwrap().entry(kind).or_insert(0) += 1;
+        if kind != SerialErrorKind::Timeout {
+            *self.last_error.lock().unwrap() = Some((message.to_string(), now_millis()));
+        }
+    }
+
+    /// 记录从串口读取的字节数
+    pub fn record_bytes(&self, count: usize) {
+        self.bytes.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// 记录设备应答的固件版本
+    pub fn record_firmware_version(&self, version: String) {
+        println!("[SerialManager] 设备固件版本: {}", version);
+        *self.firmware_version.lock().unwrap() = Some(version);
+    }
+
+    /// 记录一次成功的重新连接
+    pub fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Default for StreamCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 某一时刻的数据流计数器快照
+#[derive(Debug, Clone, Copy)]
+struct CounterSnapshot {
+    timestamp: u64,
+    accepted: u64,
+    rejected: u64,
+    bytes: u64,
+}
+
+/// 连接中切换数据源类型时，等待原读取线程停止后才能完成的切换
+pub struct PendingSourceSwitch {
+    /// 切换前的数据源类型
+    from: DataSourceType,
+    /// 沿用的连接配置
+    config: SerialConfig,
+}
+
+/// 从串口管理器取出、尚未停止的读取线程
+pub struct DetachedReaders {
+    reader: Option<SerialReader>,
+    test_reader: Option<TestReader>,
+    udp_reader: Option<UdpReader>,
+    tcp_reader: Option<TcpReader>,
+    spp_reader: Option<SppReader>,
+    hid_reader: Option<HidReader>,
+    file_tail_reader: Option<FileTailReader>,
+    playback_reader: Option<PlaybackReader>,
+}
+
+impl DetachedReaders {
+    /// 停止各读取线程，串口读取线程等待其退出
+    pub fn stop(self) {
+        // 停止串口读取器
+        if let Some(reader) = self.reader {
+            if let Err(e) = reader.stop() {
+                eprintln!("[SerialManager] {}", e);
+            }
+        }
+
+        // 停止测试数据生成器
+        if let Some(test_reader) = self.test_reader {
+            test_reader.stop();
+        }
+
+        // 停止UDP数据接收器
+        if let Some(udp_reader) = self.udp_reader {
+            udp_reader.stop();
+        }
+
+        // 停止远程串口读取器
+        if let Some(tcp_reader) = self.tcp_reader {
+            tcp_reader.stop();
+        }
+
+        // 停止蓝牙SPP读取器
+        if let Some(spp_reader) = self.spp_reader {
+            spp_reader.stop();
+        }
+
+        // 停止HID读取器
+        if let Some(hid_reader) = self.hid_reader {
+            hid_reader.stop();
+        }
+
+        // 停止记录文件读取器
+        if let Some(file_tail_reader) = self.file_tail_reader {
+            file_tail_reader.stop();
+        }
+
+        // 停止回放
+        if let Some(playback_reader) = self.playback_reader {
+            playback_reader.stop();
+        }
+    }
+}
 
 /// 串口管理器结构体
 pub struct SerialManager {
@@ -11,12 +250,66 @@ pub struct SerialManager {
     reader: Option<SerialReader>,
     /// 测试数据生成器
     test_reader: Option<TestReader>,
+    /// UDP数据接收器
+    udp_reader: Option<UdpReader>,
+    /// 远程串口读取器
+    tcp_reader: Option<TcpReader>,
+    /// 蓝牙SPP读取器
+    spp_reader: Option<SppReader>,
+    /// USB HID读取器
+    hid_reader: Option<HidReader>,
+    /// 记录文件读取器
+    file_tail_reader: Option<FileTailReader>,
+    /// 录制文件回放器
+    playback_reader: Option<PlaybackReader>,
     /// 数据队列
     data_queue: DataQueue,
     /// 串口状态
     status: Arc<Mutex<SerialStatus>>,
     /// 当前数据源类型
     data_source_type: Arc<Mutex<DataSourceType>>,
+    /// 当前连接使用的串口配置
+    current_config: Option<SerialConfig>,
+    /// 通道注册表（协议键到通道的映射）
+    channel_registry: Arc<Mutex<ChannelRegistry>>,
+    /// 串口数据的帧解析器，默认为换行结尾的文本行协议
+    frame_parser: Arc<dyn FrameParser>,
+    /// 当前连接的数据流计数器
+    stream_counters: Arc<StreamCounters>,
+    /// 计数器快照，用于计算时间窗口内的拒绝率和吞吐量
+    counter_snapshots: VecDeque<CounterSnapshot>,
+    /// 最近一次计算的数据流健康状态，未连接时为None
+    stream_health: Option<StreamHealth>,
+    /// 最近一次报告的设备能力，未连接或检测未完成时为None
+    reported_capabilities: Option<DeviceCapabilities>,
+    /// 各帧解析器要求的最低固件版本
+    firmware_compatibility: Vec<FirmwareRequirement>,
+    /// 固件兼容性表的保存路径，加载之前为None，此时设置只在内存中生效
+    firmware_compatibility_file: Option<PathBuf>,
+    /// 最近一次报告的固件版本警告
+    firmware_warning: Option<String>,
+    /// 串口错误恢复策略配置
+    resilience_config: SerialResilienceConfig,
+    /// UDP数据源配置
+    udp_config: UdpConfig,
+    /// 远程串口配置
+    tcp_config: TcpSerialConfig,
+    /// USB HID数据源配置
+    hid_config: HidConfig,
+    /// 记录文件数据源配置
+    file_tail_config: FileTailConfig,
+    /// 回放数据源配置
+    playback_config: PlaybackConfig,
+    /// 模拟数据源配置
+    simulator_config: SimulatorConfig,
+    /// 模拟数据源记录的标注心搏
+    ground_truth: Arc<GroundTruth>,
+    /// 模拟数据源压力测试的统计
+    stress_monitor: Arc<StressMonitor>,
+    /// 串口读取线程和数据处理线程的调度配置
+    thread_tuning: ThreadTuningConfig,
+    /// 原始采样队列的目标容量，与当前队列不同时在下次连接前重建队列
+    queue_capacity: usize,
 }
 
 impl SerialManager {
@@ -25,14 +318,44 @@ impl SerialManager {
         Self {
             reader: None,
             test_reader: None,
-            data_queue: Arc::new(Mutex::new(VecDeque::with_capacity(1000))),
+            udp_reader: None,
+            tcp_reader: None,
+            spp_reader: None,
+            hid_reader: None,
+            file_tail_reader: None,
+            playback_reader: None,
+            data_queue: Arc::new(SampleRing::new(sample_ring::DEFAULT_CAPACITY)),
             status: Arc::new(Mutex::new(SerialStatus::Disconnected)),
             data_source_type: Arc::new(Mutex::new(DataSourceType::RealSerial)),
+            current_config: None,
+            channel_registry: Arc::new(Mutex::new(ChannelRegistry::new())),
+            frame_parser: Arc::new(LineFrameParser),
+            stream_counters: Arc::new(StreamCounters::new()),
+            counter_snapshots: VecDeque::new(),
+            stream_health: None,
+            reported_capabilities: None,
+            firmware_compatibility: firmware::default_compatibility(),
+            firmware_compatibility_file: None,
+            firmware_warning: None,
+            resilience_config: SerialResilienceConfig::default(),
+            udp_config: UdpConfig::default(),
+            tcp_config: TcpSerialConfig::default(),
+            hid_config: HidConfig::default(),
+            file_tail_config: FileTailConfig::default(),
+            playback_config: PlaybackConfig::default(),
+            simulator_config: SimulatorConfig::default(),
+            ground_truth: Arc::new(GroundTruth::new()),
+            stress_monitor: Arc::new(StressMonitor::new()),
+            thread_tuning: ThreadTuningConfig::default(),
+            queue_capacity: sample_ring::DEFAULT_CAPACITY,
         }
     }
 
     /// 获取可用串口列表
     pub fn get_available_ports() -> Vec<(String, String)> {
+        let spp_devices = spp_reader::discover_paired_devices()
+            .into_iter()
+            .map(|device| (device.port_name(), format!("蓝牙SPP设备 ({})", device.name)));
         serialport::available_ports()
             .unwrap_or_default()
             .into_iter()
@@ -48,19 +371,68 @@ impl SerialManager {
                 };
                 Some((port_name, port_type))
             })
+            .chain(spp_devices)
             .collect()
     }
 
+    /// 串口是否正被当前连接占用
+    fn is_port_in_use(&self, port_name: &str) -> bool {
+        (self.reader.is_some() || self.spp_reader.is_some())
+            && self
+                .current_config
+                .as_ref()
+                .is_some_and(|c| c.port_name == port_name)
+    }
+
     /// 测试串口连接
-    pub fn test_connection(&self, config: SerialConfig) -> Result<(), String> {
-        let reader = SerialReader::new(config.clone(), self.data_queue.clone());
+    ///
+    /// 测试的串口正被当前连接使用时不再另外打开，直接返回 `Busy`
+    pub fn test_connection(&self, config: SerialConfig) -> Result<PortTestStatus, String> {
+        if self.is_port_in_use(&config.port_name) {
+            println!("[SerialManager] 串口 {} 正在使用中", config.port_name);
+            return Ok(PortTestStatus::Busy);
+        }
+        if spp_reader::is_spp_port(&config.port_name) {
+            spp_reader::test_connection(&config.port_name)?;
+            return Ok(PortTestStatus::Available);
+        }
+        let reader = SerialReader::new(
+            config.clone(),
+            self.data_queue.clone(),
+            self.channel_registry.clone(),
+            self.frame_parser.clone(),
+            Arc::new(StreamCounters::new()),
+            self.status.clone(),
+            self.resilience_config.clone(),
+        );
         reader.test_connection()
     }
 
+    /// 对指定串口运行回环测试，测试的串口不能是当前已连接的串口
+    pub fn run_loopback_test(&self, config: SerialConfig) -> Result<LoopbackTestResult, String> {
+        if self.is_port_in_use(&config.port_name) {
+            return Err("串口正在使用中，请先断开连接".to_string());
+        }
+        let reader = SerialReader::new(
+            config,
+            self.data_queue.clone(),
+            self.channel_registry.clone(),
+            self.frame_parser.clone(),
+            Arc::new(StreamCounters::new()),
+            Arc::new(Mutex::new(SerialStatus::Disconnected)),
+            self.resilience_config.clone(),
+        );
+        reader.run_loopback_test()
+    }
+
     /// 发送数据到串口
     pub fn send_data(&self, data: String) -> Result<(), String> {
         if let Some(reader) = &self.reader {
             reader.send_data(&data)
+        } else if let Some(tcp_reader) = &self.tcp_reader {
+            tcp_reader.send_data(&data)
+        } else if let Some(spp_reader) = &self.spp_reader {
+            spp_reader.send_data(&data)
         } else {
             Err("串口未连接".to_string())
         }
@@ -70,12 +442,41 @@ impl SerialManager {
     pub fn connect(&mut self, config: SerialConfig) -> Result<(), String> {
         // 先断开现有连接
         self.disconnect();
+        self.stream_counters = Arc::new(StreamCounters::new());
 
         // 根据数据源类型选择连接方式
         match self.get_data_source_type() {
+            DataSourceType::RealSerial if spp_reader::is_spp_port(&config.port_name) => {
+                // 直接连接蓝牙SPP设备
+                let spp_reader = SppReader::new(
+                    &config.port_name,
+                    self.data_queue.clone(),
+                    self.channel_registry.clone(),
+                    self.frame_parser.clone(),
+                    self.stream_counters.clone(),
+                    self.status.clone(),
+                    self.resilience_config.clone(),
+                )?;
+
+                // 建立RFCOMM连接
+                spp_reader.start()?;
+
+                // 更新状态
+                *self.status.lock().unwrap() = SerialStatus::Connected(config.port_name.clone());
+                self.spp_reader = Some(spp_reader);
+            },
             DataSourceType::RealSerial => {
                 // 创建新的串口读取器
-                let reader = SerialReader::new(config.clone(), self.data_queue.clone());
+                let mut reader = SerialReader::new(
+                    config.clone(),
+                    self.data_queue.clone(),
+                    self.channel_registry.clone(),
+                    self.frame_parser.clone(),
+                    self.stream_counters.clone(),
+                    self.status.clone(),
+                    self.resilience_config.clone(),
+                );
+                reader.set_thread_tuning(self.thread_tuning.clone());
                 
                 // 启动串口读取
                 reader.start()?;
@@ -86,7 +487,15 @@ impl SerialManager {
             },
             DataSourceType::TestSimulation => {
                 // 创建测试数据生成器
-                let test_reader = TestReader::new(self.data_queue.clone());
+                self.ground_truth.clear();
+                self.stress_monitor.reset();
+                let test_reader = TestReader::new(
+                    self.data_queue.clone(),
+                    self.stream_counters.clone(),
+                    self.simulator_config.clone(),
+                    self.ground_truth.clone(),
+                    self.stress_monitor.clone(),
+                );
                 
                 // 启动测试数据生成
                 test_reader.start()?;
@@ -94,31 +503,180 @@ impl SerialManager {
                 // 更新状态
                 *self.status.lock().unwrap() = SerialStatus::Connected("TEST_MODE".to_string());
                 self.test_reader = Some(test_reader);
+            },
+            DataSourceType::Udp => {
+                // 创建UDP数据接收器
+                let udp_reader = UdpReader::new(
+                    self.udp_config.clone(),
+                    self.data_queue.clone(),
+                    self.channel_registry.clone(),
+                    self.stream_counters.clone(),
+                    self.status.clone(),
+                );
+
+                // 启动UDP接收
+                udp_reader.start()?;
+
+                // 更新状态
+                *self.status.lock().unwrap() =
+                    SerialStatus::Connected(format!("UDP {}", self.udp_config.bind_address));
+                self.udp_reader = Some(udp_reader);
+            },
+            DataSourceType::RemoteSerial => {
+                // 创建远程串口读取器，波特率通过RFC2217协商
+                let tcp_reader = TcpReader::new(
+                    self.tcp_config.clone(),
+                    config.baud_rate,
+                    self.data_queue.clone(),
+                    self.channel_registry.clone(),
+                    self.stream_counters.clone(),
+                    self.status.clone(),
+                    self.resilience_config.clone(),
+                );
+
+                // 连接远程串口
+                tcp_reader.start()?;
+
+                // 更新状态
+                *self.status.lock().unwrap() = SerialStatus::Connected(format!(
+                    "{}:{}",
+                    self.tcp_config.host, self.tcp_config.port
+                ));
+                self.tcp_reader = Some(tcp_reader);
+            },
+            DataSourceType::Hid => {
+                // 创建HID读取器
+                let hid_reader = HidReader::new(
+                    self.hid_config.clone(),
+                    self.data_queue.clone(),
+                    self.channel_registry.clone(),
+                    self.stream_counters.clone(),
+                    self.status.clone(),
+                    self.resilience_config.clone(),
+                );
+
+                // 打开HID设备
+                hid_reader.start()?;
+
+                // 更新状态
+                *self.status.lock().unwrap() = SerialStatus::Connected(format!(
+                    "HID {:04x}:{:04x}",
+                    self.hid_config.vendor_id, self.hid_config.product_id
+                ));
+                self.hid_reader = Some(hid_reader);
+            },
+            DataSourceType::FileTail => {
+                // 创建记录文件读取器
+                let file_tail_reader = FileTailReader::new(
+                    self.file_tail_config.clone(),
+                    self.data_queue.clone(),
+                    self.channel_registry.clone(),
+                    self.stream_counters.clone(),
+                    self.status.clone(),
+                );
+
+                // 开始跟踪文件
+                file_tail_reader.start()?;
+
+                // 更新状态
+                *self.status.lock().unwrap() =
+                    SerialStatus::Connected(format!("FILE {}", self.file_tail_config.path));
+                self.file_tail_reader = Some(file_tail_reader);
+            },
+            DataSourceType::Playback => {
+                // 创建录制文件回放器
+                let mut playback_reader = PlaybackReader::new(
+                    self.playback_config.clone(),
+                    self.data_queue.clone(),
+                    self.channel_registry.clone(),
+                    self.stream_counters.clone(),
+                );
+
+                // 开始回放
+                playback_reader.start()?;
+
+                // 更新状态
+                *self.status.lock().unwrap() =
+                    SerialStatus::Connected(format!("PLAYBACK {}", self.playback_config.path));
+                self.playback_reader = Some(playback_reader);
             }
         }
+
+        self.current_config = Some(config);
+        self.stream_health = Some(StreamHealth::NoData);
+        self.query_firmware_version();
         
         Ok(())
     }
 
-    /// 断开当前串口连接
+    /// 断开当前串口连接，等待读取线程退出
     pub fn disconnect(&mut self) {
-        // 停止串口读取器
-        if let Some(reader) = self.reader.take() {
-            reader.stop();
-        }
-        
-        // 停止测试数据生成器
-        if let Some(test_reader) = self.test_reader.take() {
-            test_reader.stop();
-        }
-        
+        self.detach().stop();
+    }
+
+    /// 断开连接但不等待读取线程退出
+    ///
+    /// 串口读取线程退出最长要等待数秒，命令在释放串口管理器的锁之后再调用
+    /// `DetachedReaders::stop`，等待期间不阻塞其他命令。
+    pub fn detach(&mut self) -> DetachedReaders {
+        let readers = DetachedReaders {
+            reader: self.reader.take(),
+            test_reader: self.test_reader.take(),
+            udp_reader: self.udp_reader.take(),
+            tcp_reader: self.tcp_reader.take(),
+            spp_reader: self.spp_reader.take(),
+            hid_reader: self.hid_reader.take(),
+            file_tail_reader: self.file_tail_reader.take(),
+            playback_reader: self.playback_reader.take(),
+        };
+
         *self.status.lock().unwrap() = SerialStatus::Disconnected;
+        self.current_config = None;
+        self.counter_snapshots.clear();
+        self.stream_health = None;
+        self.reported_capabilities = None;
+        self.firmware_warning = None;
+        readers
+    }
+
+    /// 获取当前运行中的读取线程（各类数据源）的心跳，未连接时返回None
+    pub fn get_reader_heartbeat(&self) -> Option<Heartbeat> {
+        self.reader
+            .as_ref()
+            .map(|r| r.heartbeat())
+            .or_else(|| self.test_reader.as_ref().map(|r| r.heartbeat()))
+            .or_else(|| self.udp_reader.as_ref().map(|r| r.heartbeat()))
+            .or_else(|| self.tcp_reader.as_ref().map(|r| r.heartbeat()))
+            .or_else(|| self.spp_reader.as_ref().map(|r| r.heartbeat()))
+            .or_else(|| self.hid_reader.as_ref().map(|r| r.heartbeat()))
+            .or_else(|| self.file_tail_reader.as_ref().map(|r| r.heartbeat()))
+            .or_else(|| self.playback_reader.as_ref().map(|r| r.heartbeat()))
+    }
+
+    /// 按当前配置重新建立连接，用于读取线程停滞后的恢复
+    pub fn restart_reader(&mut self) -> Result<(), String> {
+        let config = self
+            .current_config
+            .clone()
+            .ok_or_else(|| "串口未连接".to_string())?;
+        println!("[SerialManager] 重启读取线程: {}", config.port_name);
+        let result = self.connect(config.clone());
+        if let Err(e) = &result {
+            *self.status.lock().unwrap() = SerialStatus::Error(e.clone());
+            // 保留配置以便再次尝试重启
+            self.current_config = Some(config);
+        }
+        result
     }
 
-    /// 获取最新的N组数据
-    pub fn get_latest_data(&self, count: usize) -> Vec<VitalSigns> {
-        let queue = self.data_queue.lock().unwrap();
-        queue.iter().rev().take(count).cloned().collect()
+    /// 获取最新的N组数据，可按接收时间戳（毫秒，包含两端）限定范围
+    pub fn get_latest_data(
+        &self,
+        count: usize,
+        since: Option<u64>,
+        until: Option<u64>,
+    ) -> Vec<VitalSigns> {
+        self.data_queue.latest(count, since, until)
     }
 
     /// 获取当前串口状态
@@ -126,21 +684,543 @@ impl SerialManager {
         self.status.lock().unwrap().clone()
     }
 
+    /// 获取串口状态及数据流健康状态
+    pub fn get_status_report(&self) -> SerialStatusReport {
+        SerialStatusReport {
+            status: self.get_status(),
+            stream_health: self.stream_health.clone(),
+            firmware_warning: self.firmware_warning.clone(),
+        }
+    }
+
+    /// 获取当前连接按类别统计的串口错误次数
+    pub fn get_error_counts(&self) -> SerialErrorCounts {
+        SerialErrorCounts {
+            counts: self.stream_counters.errors.lock().unwrap().clone(),
+            reconnects: self.stream_counters.reconnects.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 获取当前连接的帧完整性统计
+    pub fn get_link_statistics(&self) -> LinkStatistics {
+        self.stream_counters.link.lock().unwrap().clone()
+    }
+
+    /// 获取当前数据源的连接统计
+    pub fn get_connection_statistics(&self) -> ConnectionStatistics {
+        let counters = &self.stream_counters;
+        let port_name = match self.get_status() {
+            SerialStatus::Connected(port_name) => Some(port_name),
+            _ => None,
+        };
+        let uptime_ms = port_name
+            .as_ref()
+            .map(|_| now_millis().saturating_sub(counters.started_at));
+        let samples_received = counters.accepted.load(Ordering::Relaxed);
+        let parse_failures = counters.rejected.load(Ordering::Relaxed);
+        let frames = samples_received + parse_failures;
+        let (samples_per_second, bytes_per_second) = self.get_throughput();
+        let last_error = counters.last_error.lock().unwrap().clone();
+        ConnectionStatistics {
+            source_type: self.get_data_source_type(),
+            port_name,
+            uptime_ms,
+            reconnects: counters.reconnects.load(Ordering::Relaxed),
+            samples_received,
+            parse_failures,
+            parse_failure_rate: if frames > 0 {
+                parse_failures as f64 / frames as f64
+            } else {
+                0.0
+            },
+            samples_per_second,
+            bytes_per_second,
+            last_error_at: last_error.as_ref().map(|(_, at)| *at),
+            last_error: last_error.map(|(message, _)| message),
+        }
+    }
+
+    /// 当前数据源使用的帧解析器类型
+    fn current_parser_kind(&self) -> FrameParserKind {
+        match self.get_data_source_type() {
+            DataSourceType::Udp => self.udp_config.parser,
+            DataSourceType::RemoteSerial => self.tcp_config.parser,
+            DataSourceType::Hid => self.hid_config.parser,
+            _ => FrameParserKind::Line,
+        }
+    }
+
+    /// 获取设备固件版本及其与当前帧解析器的兼容性
+    pub fn get_firmware_info(&self) -> FirmwareInfo {
+        let counters = &self.stream_counters;
+        let version = counters.firmware_version.lock().unwrap().clone();
+        firmware::check(
+            version.as_deref(),
+            self.current_parser_kind(),
+            &self.firmware_compatibility,
+        )
+    }
+
+    /// 查询设备固件版本，只对支持双向通信的数据源发送，应答由读取线程解析
+    ///
+    /// 连接、重启读取线程和连接中切换数据源都经过 `connect`，新数据源启动后各查询一次。
+    fn query_firmware_version(&self) {
+        if self.reader.is_none() && self.tcp_reader.is_none() && self.spp_reader.is_none() {
+            return;
+        }
+        if let Err(e) = self.send_data(firmware::QUERY_COMMAND.to_string()) {
+            eprintln!("[SerialManager] 查询固件版本失败: {}", e);
+        }
+    }
+
+    /// 从数据目录加载固件兼容性表，之后设置的兼容性表保存到该文件
+    pub fn load_firmware_compatibility(
+        &mut self,
+        app_handle: &tauri::AppHandle,
+    ) -> Result<(), String> {
+        let path = firmware::compatibility_file(app_handle)?;
+        self.firmware_compatibility_file = Some(path.clone());
+        self.firmware_compatibility = firmware::load_compatibility(&path)?;
+        Ok(())
+    }
+
+    /// 设置固件兼容性表（各帧解析器要求的最低固件版本）并保存
+    pub fn set_firmware_compatibility(
+        &mut self,
+        table: Vec<FirmwareRequirement>,
+    ) -> Result<(), String> {
+        firmware::validate_compatibility(&table)?;
+        if let Some(path) = &self.firmware_compatibility_file {
+            firmware::save_compatibility(path, &table)?;
+        }
+        self.firmware_compatibility = table;
+        Ok(())
+    }
+
+    /// 获取固件兼容性表
+    pub fn get_firmware_compatibility(&self) -> Vec<FirmwareRequirement> {
+        self.firmware_compatibility.clone()
+    }
+
+    /// 获取当前连接的设备能力
+    ///
+    /// 设备没有能力查询命令，连接后先观察 `CAPABILITY_DISCOVERY_SAMPLES` 个采样，
+    /// 期间上报过的通道为设备支持的通道。间歇性测量通道（采样率为0）只在测量完成时上报，
+    /// 未上报过时保持为待定。
+    pub fn get_device_capabilities(&self) -> DeviceCapabilities {
+        let samples_observed = self.stream_counters.accepted.load(Ordering::Relaxed);
+        let discovery_complete = samples_observed >= CAPABILITY_DISCOVERY_SAMPLES;
+        let seen = self.stream_counters.seen_channels.lock().unwrap();
+        let mut capabilities = DeviceCapabilities {
+            discovery_complete,
+            samples_observed,
+            supported_channels: Vec::new(),
+            unsupported_channels: Vec::new(),
+            pending_channels: Vec::new(),
+        };
+        for descriptor in self.channel_registry.lock().unwrap().descriptors() {
+            let channels = if seen.contains(&descriptor.id) {
+                &mut capabilities.supported_channels
+            } else if discovery_complete && descriptor.sample_rate_hz > 0.0 {
+                &mut capabilities.unsupported_channels
+            } else {
+                &mut capabilities.pending_channels
+            };
+            channels.push(descriptor.id.clone());
+        }
+        capabilities
+    }
+
+    /// 检测设备能力的变化，应定期调用（例如每秒一次）
+    ///
+    /// # 返回值
+    /// 检测完成时，以及之后设备开始上报新的通道（例如接上体温探头）时返回新的设备能力
+    pub fn take_capability_change(&mut self) -> Option<DeviceCapabilities> {
+        if !matches!(self.get_status(), SerialStatus::Connected(_)) {
+            return None;
+        }
+        let capabilities = self.get_device_capabilities();
+        if !capabilities.discovery_complete {
+            return None;
+        }
+        let unchanged = self.reported_capabilities.as_ref().is_some_and(|reported| {
+            reported.supported_channels == capabilities.supported_channels
+                && reported.unsupported_channels == capabilities.unsupported_channels
+        });
+        if unchanged {
+            return None;
+        }
+        println!(
+            "[SerialManager] 设备能力: 支持 {:?}，不支持 {:?}",
+            capabilities.supported_channels, capabilities.unsupported_channels
+        );
+        self.reported_capabilities = Some(capabilities.clone());
+        Some(capabilities)
+    }
+
+    /// 根据数据流计数器重新计算健康状态，应定期调用（例如每秒一次）
+    ///
+    /// # 返回值
+    /// 健康状态发生变化时返回新的状态报告
+    pub fn update_stream_health(&mut self) -> Option<SerialStatusReport> {
+        // 未连接时不计算
+        self.stream_health.as_ref()?;
+
+        let now = now_millis();
+        let accepted = self.stream_counters.accepted.load(Ordering::Relaxed);
+        let rejected = self.stream_counters.rejected.load(Ordering::Relaxed);
+        let last_sample_at = self.stream_counters.last_sample_at.load(Ordering::Relaxed);
+        self.counter_snapshots.push_back(CounterSnapshot {
+            timestamp: now,
+            accepted,
+            rejected,
+            bytes: self.stream_counters.bytes.load(Ordering::Relaxed),
+        });
+        while self
+            .counter_snapshots
+            .front()
+            .is_some_and(|s| now.saturating_sub(s.timestamp) > REJECTION_WINDOW_MS)
+        {
+            self.counter_snapshots.pop_front();
+        }
+
+        // 窗口内的拒绝率，窗口内没有数据行时为None
+        let first = self.counter_snapshots[0];
+        let window_accepted = accepted - first.accepted;
+        let window_rejected = rejected - first.rejected;
+        let window_total = window_accepted + window_rejected;
+        let rejection_rate =
+            (window_total > 0).then(|| window_rejected as f64 / window_total as f64);
+
+        let health = match rejection_rate {
+            Some(rate) if rate >= REJECTION_RATE_THRESHOLD => {
+                StreamHealth::ParserRejectingData { rate }
+            }
+            _ if accepted == 0 => StreamHealth::NoData,
+            _ if now.saturating_sub(last_sample_at) >= STALL_THRESHOLD_MS => {
+                StreamHealth::Stalled {
+                    seconds: now.saturating_sub(last_sample_at) / 1000,
+                }
+            }
+            _ => StreamHealth::StreamingOk,
+        };
+
+        let firmware_warning = self.get_firmware_info().warning;
+        let firmware_changed = firmware_warning != self.firmware_warning;
+        if firmware_changed {
+            if let Some(warning) = &firmware_warning {
+                eprintln!("[SerialManager] {}", warning);
+                self.stream_counters
+                    .record_error(SerialErrorKind::Firmware, warning);
+            }
+            self.firmware_warning = firmware_warning;
+        }
+
+        if self.stream_health.as_ref() == Some(&health) && !firmware_changed {
+            return None;
+        }
+        self.stream_health = Some(health);
+        Some(self.get_status_report())
+    }
+
+    /// 获取最近时间窗口内的吞吐量
+    ///
+    /// # 返回值
+    /// 返回（采样/秒, 字节/秒），快照不足两个时均为0
+    pub fn get_throughput(&self) -> (f64, f64) {
+        let (Some(first), Some(last)) =
+            (self.counter_snapshots.front(), self.counter_snapshots.back())
+        else {
+            return (0.0, 0.0);
+        };
+        let elapsed_secs = last.timestamp.saturating_sub(first.timestamp) as f64 / 1000.0;
+        if elapsed_secs <= 0.0 {
+            return (0.0, 0.0);
+        }
+        (
+            (last.accepted - first.accepted) as f64 / elapsed_secs,
+            (last.bytes - first.bytes) as f64 / elapsed_secs,
+        )
+    }
+
+    /// 获取当前连接的串口配置，未连接时返回None
+    pub fn get_current_config(&self) -> Option<SerialConfig> {
+        self.current_config.clone()
+    }
+
     /// 获取数据队列的引用 - 新增方法
     pub fn get_data_queue(&self) -> DataQueue {
         self.data_queue.clone()
     }
 
     /// 设置数据源类型
-    pub fn set_data_source_type(&mut self, source_type: DataSourceType) {
+    ///
+    /// 已连接时若类型发生变化，取出当前数据源的读取线程并记下连接配置；命令在释放
+    /// 串口管理器的锁之后停止这些读取线程，再调用 `complete_data_source_switch`
+    /// 按原连接配置启动新的数据源。未连接时在下次连接时生效。
+    ///
+    /// # 返回值
+    /// 连接中需要切换时返回待停止的读取线程和待完成的切换，否则返回None
+    pub fn set_data_source_type(
+        &mut self,
+        source_type: DataSourceType,
+    ) -> Option<(DetachedReaders, PendingSourceSwitch)> {
         println!("[SerialManager] 数据源类型已设置为: {:?}", source_type);
-        *self.data_source_type.lock().unwrap() = source_type;
+        let previous = std::mem::replace(
+            &mut *self.data_source_type.lock().unwrap(),
+            source_type.clone(),
+        );
+        if previous == source_type {
+            return None;
+        }
+        let config = self.current_config.clone()?;
+        println!(
+            "[SerialManager] 连接中切换数据源: {:?} -> {:?}",
+            previous, source_type
+        );
+        let readers = self.detach();
+        Some((
+            readers,
+            PendingSourceSwitch {
+                from: previous,
+                config,
+            },
+        ))
+    }
+
+    /// 原数据源的读取线程停止后，按原连接配置启动新的数据源
+    ///
+    /// 新数据源支持双向通信时重新查询设备固件版本。停止期间已重新连接时不再启动，
+    /// 停止期间再次切换了类型时启动最新设置的类型。
+    ///
+    /// # 返回值
+    /// 返回切换结果，已重新连接时返回None
+    pub fn complete_data_source_switch(
+        &mut self,
+        switch: PendingSourceSwitch,
+    ) -> Option<DataSourceSwitchEvent> {
+        if self.current_config.is_some() {
+            println!("[SerialManager] 切换数据源期间已重新连接，不再启动新的数据源");
+            return None;
+        }
+        let source_type = self.get_data_source_type();
+        println!(
+            "[SerialManager] 启动新的数据源: {:?} ({})",
+            source_type, switch.config.port_name
+        );
+        let result = self.connect(switch.config.clone());
+        if let Err(e) = &result {
+            *self.status.lock().unwrap() = SerialStatus::Error(e.clone());
+            // 保留配置以便再次尝试重启
+            self.current_config = Some(switch.config.clone());
+        }
+        let message = match &result {
+            Ok(()) => format!("数据源已从 {:?} 切换为 {:?}", switch.from, source_type),
+            Err(e) => format!("切换到 {:?} 失败: {}", source_type, e),
+        };
+        Some(DataSourceSwitchEvent {
+            from: switch.from,
+            to: source_type,
+            port_name: switch.config.port_name,
+            success: result.is_ok(),
+            message,
+            timestamp: now_millis(),
+        })
     }
     
     /// 获取当前数据源类型
     pub fn get_data_source_type(&self) -> DataSourceType {
         self.data_source_type.lock().unwrap().clone()
     }
+
+    /// 设置串口错误恢复策略，下次连接时生效
+    pub fn set_resilience_config(&mut self, config: SerialResilienceConfig) -> Result<(), String> {
+        validate_resilience_config(&config)?;
+        println!("[SerialManager] 错误恢复策略已更新: {:?}", config);
+        self.resilience_config = config;
+        Ok(())
+    }
+
+    /// 获取串口错误恢复策略
+    pub fn get_resilience_config(&self) -> SerialResilienceConfig {
+        self.resilience_config.clone()
+    }
+
+    /// 设置原始采样队列的容量，调用 `apply_queue_capacity` 后生效
+    pub fn set_queue_capacity(&mut self, capacity: usize) {
+        self.queue_capacity = capacity.max(1);
+    }
+
+    /// 容量有变化时重建原始采样队列
+    ///
+    /// 正在运行的读取线程和数据处理线程仍持有旧队列，只能在重新连接并重建数据处理器之前调用
+    pub fn apply_queue_capacity(&mut self) {
+        if self.data_queue.capacity() != self.queue_capacity {
+            println!(
+                "[SerialManager] 原始采样队列容量: {} -> {}",
+                self.data_queue.capacity(),
+                self.queue_capacity
+            );
+            self.data_queue = Arc::new(SampleRing::new(self.queue_capacity));
+        }
+    }
+
+    /// 设置串口读取线程和数据处理线程的调度配置，下次连接或启动处理时生效
+    pub fn set_thread_tuning(&mut self, config: ThreadTuningConfig) -> Result<(), String> {
+        thread_tuning::validate_config(&config)?;
+        println!("[SerialManager] 线程调度配置已更新: {:?}", config);
+        self.thread_tuning = config;
+        Ok(())
+    }
+
+    /// 获取线程调度配置
+    pub fn get_thread_tuning(&self) -> ThreadTuningConfig {
+        self.thread_tuning.clone()
+    }
+
+    /// 设置UDP数据源配置，下次连接时生效
+    pub fn set_udp_config(&mut self, config: UdpConfig) -> Result<(), String> {
+        udp_reader::validate_config(&config)?;
+        println!("[SerialManager] UDP配置已更新: {:?}", config);
+        self.udp_config = config;
+        Ok(())
+    }
+
+    /// 获取UDP数据源配置
+    pub fn get_udp_config(&self) -> UdpConfig {
+        self.udp_config.clone()
+    }
+
+    /// 设置远程串口配置，下次连接时生效
+    pub fn set_tcp_serial_config(&mut self, config: TcpSerialConfig) -> Result<(), String> {
+        tcp_reader::validate_config(&config)?;
+        println!("[SerialManager] 远程串口配置已更新: {:?}", config);
+        self.tcp_config = config;
+        Ok(())
+    }
+
+    /// 获取远程串口配置
+    pub fn get_tcp_serial_config(&self) -> TcpSerialConfig {
+        self.tcp_config.clone()
+    }
+
+    /// 设置USB HID数据源配置，下次连接时生效
+    pub fn set_hid_config(&mut self, config: HidConfig) -> Result<(), String> {
+        hid_reader::validate_config(&config)?;
+        println!("[SerialManager] HID配置已更新: {:?}", config);
+        self.hid_config = config;
+        Ok(())
+    }
+
+    /// 获取USB HID数据源配置
+    pub fn get_hid_config(&self) -> HidConfig {
+        self.hid_config.clone()
+    }
+
+    /// 设置记录文件数据源配置，下次连接时生效
+    pub fn set_file_tail_config(&mut self, config: FileTailConfig) -> Result<(), String> {
+        file_tail_reader::validate_config(&config)?;
+        println!("[SerialManager] 记录文件配置已更新: {:?}", config);
+        self.file_tail_config = config;
+        Ok(())
+    }
+
+    /// 获取记录文件数据源配置
+    pub fn get_file_tail_config(&self) -> FileTailConfig {
+        self.file_tail_config.clone()
+    }
+
+    /// 设置回放数据源配置，下次连接时生效
+    pub fn set_playback_config(&mut self, config: PlaybackConfig) -> Result<(), String> {
+        playback_reader::validate_config(&config)?;
+        println!("[SerialManager] 回放配置已更新: {:?}", config);
+        self.playback_config = config;
+        Ok(())
+    }
+
+    /// 获取回放数据源配置
+    pub fn get_playback_config(&self) -> PlaybackConfig {
+        self.playback_config.clone()
+    }
+
+    /// 设置回放速度倍数，正在回放时立即生效
+    pub fn set_playback_speed(&mut self, speed: f64) -> Result<(), String> {
+        playback_reader::validate_speed(speed)?;
+        if let Some(playback_reader) = &self.playback_reader {
+            playback_reader.set_speed(speed)?;
+        }
+        self.playback_config.speed = speed;
+        Ok(())
+    }
+
+    /// 跳转到录制中的指定时间（距录制开始的毫秒数）
+    pub fn seek_playback(&self, timestamp_ms: u64) -> Result<(), String> {
+        self.playback_reader
+            .as_ref()
+            .ok_or_else(|| "回放未启动".to_string())?
+            .seek(timestamp_ms)
+    }
+
+    /// 获取当前回放进度
+    pub fn get_playback_position(&self) -> Result<PlaybackPosition, String> {
+        self.playback_reader
+            .as_ref()
+            .map(|r| r.position())
+            .ok_or_else(|| "回放未启动".to_string())
+    }
+
+    /// 获取通道注册表中的所有通道描述
+    pub fn get_channel_descriptors(&self) -> Vec<ChannelDescriptor> {
+        self.channel_registry.lock().unwrap().descriptors().to_vec()
+    }
+
+    /// 设置模拟数据源配置，下次连接时生效
+    pub fn set_simulator_config(&mut self, config: SimulatorConfig) -> Result<(), String> {
+        arrhythmia_sim::validate_config(&config)?;
+        println!("[SerialManager] 模拟数据源配置已更新: {:?}", config);
+        self.simulator_config = config;
+        Ok(())
+    }
+
+    /// 获取模拟数据源配置
+    pub fn get_simulator_config(&self) -> SimulatorConfig {
+        self.simulator_config.clone()
+    }
+
+    /// 获取模拟数据源本次连接记录的标注心搏
+    pub fn get_ground_truth(&self) -> Vec<GroundTruthBeat> {
+        self.ground_truth.beats()
+    }
+
+    /// 把数据处理器丢弃的采样计入压力测试当前台阶
+    pub fn record_stress_drops(&self, count: u64) {
+        self.stress_monitor.record_dropped(count);
+    }
+
+    /// 获取模拟数据源最近一次压力测试的报告
+    pub fn get_stress_report(&self) -> StressReport {
+        self.stress_monitor.report()
+    }
+
+    /// 获取通道注册表的副本，用于在数据源之外解析录制数据
+    pub fn get_channel_registry(&self) -> ChannelRegistry {
+        self.channel_registry.lock().unwrap().clone()
+    }
+
+    /// 注册扩展通道，注册后解析器立即按其协议键解析数据
+    pub fn register_channel(&self, descriptor: ChannelDescriptor) -> Result<(), String> {
+        println!(
+            "[SerialManager] 注册扩展通道: {} ({}=)",
+            descriptor.id, descriptor.protocol_key
+        );
+        self.channel_registry.lock().unwrap().register(descriptor)
+    }
+
+    /// 注销扩展通道
+    pub fn unregister_channel(&self, id: &str) -> Result<(), String> {
+        println!("[SerialManager] 注销扩展通道: {}", id);
+        self.channel_registry.lock().unwrap().unregister(id)
+    }
 }
 
 // 为了线程安全实现必要的特征
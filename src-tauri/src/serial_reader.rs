@@ -1,17 +1,83 @@
-use crate::types::{DataQueue, SerialConfig, VitalSigns};
-use std::io::{BufRead, BufReader, Write};
+use crate::channels::ChannelRegistry;
+use crate::diagnostics::hot_log;
+use crate::framing::{deliver_frame, AssembledFrame, FrameAssembler, FrameParser};
+use crate::serial_manager::StreamCounters;
+use crate::thread_tuning::{self, ThreadRole};
+use crate::types::{
+    DataQueue, LoopbackTestResult, PortTestStatus, RecoveryAction, SerialConfig, SerialErrorKind,
+    SerialResilienceConfig, SerialStatus, ThreadTuningConfig,
+};
+use crate::watchdog::{join_with_timeout, Heartbeat};
+use serialport::{ClearBuffer, SerialPort};
+use std::io::{ErrorKind, Read, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// 每次从串口读取的最大字节数
+const READ_CHUNK_SIZE: usize = 1024;
+
+/// 回环测试的轮数
+const LOOPBACK_ROUNDS: u32 = 20;
+
+/// 回环测试每轮发送的字节数
+const LOOPBACK_PATTERN_LEN: usize = 64;
+
+/// 回环测试每轮等待回显的超时
+const LOOPBACK_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// 停止时等待读取线程退出的时间，在读取超时之外额外等待的部分
+const STOP_GRACE: Duration = Duration::from_secs(2);
+
+/// 验证串口错误恢复策略配置
+pub fn validate_resilience_config(config: &SerialResilienceConfig) -> Result<(), String> {
+    if !(100..=60_000).contains(&config.read_timeout_ms) {
+        return Err("读取超时必须在100到60000毫秒之间".to_string());
+    }
+    if config.max_consecutive_errors == 0 {
+        return Err("最大连续错误次数必须大于0".to_string());
+    }
+    if config.initial_backoff_ms == 0 || config.max_backoff_ms < config.initial_backoff_ms {
+        return Err("退避等待时间必须大于0，且上限不小于首次等待时间".to_string());
+    }
+    if !config.backoff_multiplier.is_finite() || config.backoff_multiplier < 1.0 {
+        return Err("退避增长倍数不能小于1".to_string());
+    }
+    if config.reconnect_interval_ms == 0 {
+        return Err("重新连接间隔必须大于0".to_string());
+    }
+    Ok(())
+}
 
 pub struct SerialReader {
     config: SerialConfig,
     data_queue: DataQueue,
+    channel_registry: Arc<Mutex<ChannelRegistry>>,
+    parser: Arc<dyn FrameParser>,
+    counters: Arc<StreamCounters>,
+    status: Arc<Mutex<SerialStatus>>,
+    resilience: SerialResilienceConfig,
+    /// 读取线程的优先级和CPU亲和性配置
+    thread_tuning: ThreadTuningConfig,
     stop_flag: Arc<AtomicBool>,
+    heartbeat: Heartbeat,
+    /// 读取线程所用串口句柄的副本，用于发送数据，未连接时为None
+    writer: Arc<Mutex<Option<Box<dyn SerialPort>>>>,
+    /// 读取线程的句柄，停止时等待线程退出
+    worker: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl SerialReader {
-    pub fn new(config: SerialConfig, data_queue: DataQueue) -> Self {
+    pub fn new(
+        config: SerialConfig,
+        data_queue: DataQueue,
+        channel_registry: Arc<Mutex<ChannelRegistry>>,
+        parser: Arc<dyn FrameParser>,
+        counters: Arc<StreamCounters>,
+        status: Arc<Mutex<SerialStatus>>,
+        resilience: SerialResilienceConfig,
+    ) -> Self {
         println!(
             "[SerialReader] 初始化，串口={}, 波特率={}",
             config.port_name, config.baud_rate
@@ -19,137 +85,411 @@ impl SerialReader {
         Self {
             config,
             data_queue,
+            channel_registry,
+            parser,
+            counters,
+            status,
+            resilience,
+            thread_tuning: ThreadTuningConfig::default(),
             stop_flag: Arc::new(AtomicBool::new(false)),
+            heartbeat: Heartbeat::new(),
+            writer: Arc::new(Mutex::new(None)),
+            worker: Mutex::new(None),
         }
     }
 
-    pub fn test_connection(&self) -> Result<(), String> {
+    /// 设置读取线程的优先级和CPU亲和性，在 `start` 之前调用
+    pub fn set_thread_tuning(&mut self, config: ThreadTuningConfig) {
+        self.thread_tuning = config;
+    }
+
+    /// 读取线程的心跳，每成功读到数据更新一次
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
+
+    pub fn test_connection(&self) -> Result<PortTestStatus, String> {
         println!("[SerialReader] 测试串口连接: {}", self.config.port_name);
-        serialport::new(&self.config.port_name, self.config.baud_rate)
+        match serialport::new(&self.config.port_name, self.config.baud_rate)
             .timeout(Duration::from_millis(1000))
             .open()
-            .map_err(|e| format!("无法打开串口: {}", e))?;
-        println!("[SerialReader] 串口连接正常");
-        Ok(())
+        {
+            Ok(_) => {
+                println!("[SerialReader] 串口连接正常");
+                Ok(PortTestStatus::Available)
+            }
+            Err(e) if Self::is_port_busy_error(&e, &self.config.port_name) => {
+                println!(
+                    "[SerialReader] 串口 {} 已被其他程序占用",
+                    self.config.port_name
+                );
+                Ok(PortTestStatus::Busy)
+            }
+            Err(e) => Err(format!("无法打开串口: {}", e)),
+        }
     }
 
-    pub fn send_data(&self, data: &str) -> Result<(), String> {
-        println!("[SerialReader] 向串口发送数据: {}", data);
+    /// 回环测试：向串口发送已知的字节序列并等待回显，测量往返时间和数据完整性
+    ///
+    /// 需要设备或适配器的收发引脚短接（回环），测试期间不能有其他程序占用串口。
+    pub fn run_loopback_test(&self) -> Result<LoopbackTestResult, String> {
+        println!("[SerialReader] 开始回环测试: {}", self.config.port_name);
         let mut port = serialport::new(&self.config.port_name, self.config.baud_rate)
-            .timeout(Duration::from_millis(1000))
+            .timeout(Duration::from_millis(100))
             .open()
             .map_err(|e| format!("无法打开串口: {}", e))?;
+        port.clear(ClearBuffer::All)
+            .map_err(|e| format!("清空串口缓冲区失败: {}", e))?;
 
-        port.write_all(data.as_bytes())
-            .map_err(|e| format!("发送数据失败: {}", e))?;
+        let mut result = LoopbackTestResult {
+            rounds: LOOPBACK_ROUNDS,
+            completed_rounds: 0,
+            timed_out_rounds: 0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            corrupted_bytes: 0,
+            min_round_trip_ms: None,
+            average_round_trip_ms: None,
+            max_round_trip_ms: None,
+        };
+        let mut round_trips = Vec::with_capacity(LOOPBACK_ROUNDS as usize);
+        let mut echo = [0u8; LOOPBACK_PATTERN_LEN];
 
-        println!("[SerialReader] 数据发送完成");
-        Ok(())
-    }
-
-    fn parse_data_line(line: &str) -> Option<VitalSigns> {
-        let mut ecg = None;
-        let mut spo2 = None;
-        let mut temp = None;
+        for round in 0..LOOPBACK_ROUNDS {
+            // 每轮的字节序列不同，避免把上一轮迟到的回显误当作本轮的
+            let pattern: Vec<u8> = (0..LOOPBACK_PATTERN_LEN)
+                .map(|i| (i as u32 * 7 + round * 31) as u8)
+                .collect();
+            let started = Instant::now();
+            port.write_all(&pattern)
+                .map_err(|e| format!("发送数据失败: {}", e))?;
+            result.bytes_sent += pattern.len() as u64;
 
-        for part in line.split(',') {
-            let kv: Vec<&str> = part.split('=').collect();
-            if kv.len() != 2 {
-                continue;
+            let mut received = 0;
+            while received < pattern.len() && started.elapsed() < LOOPBACK_TIMEOUT {
+                match port.read(&mut echo[received..]) {
+                    Ok(n) => received += n,
+                    Err(e) if e.kind() == ErrorKind::TimedOut => {}
+                    Err(e) => return Err(format!("读取回显失败: {}", e)),
+                }
             }
-            match kv[0].trim() {
-                "A" => ecg = kv[1].trim().parse().ok(),
-                "B" => spo2 = kv[1].trim().parse().ok(),
-                "C" => temp = kv[1].trim().parse().ok(),
-                _ => continue,
+            let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+            result.bytes_received += received as u64;
+            result.corrupted_bytes += pattern[..received]
+                .iter()
+                .zip(&echo[..received])
+                .filter(|(sent, got)| sent != got)
+                .count() as u64;
+            if received == pattern.len() {
+                result.completed_rounds += 1;
+                round_trips.push(elapsed_ms);
+            } else {
+                result.timed_out_rounds += 1;
+                // 丢弃本轮残留的回显，避免影响下一轮
+                let _ = port.clear(ClearBuffer::Input);
             }
         }
 
-        if let (Some(ecg), Some(spo2), Some(temp)) = (ecg, spo2, temp) {
-            Some(VitalSigns { 
-                ecg, 
-                spo2, 
-                temp, 
-                systolic: 0, // 默认值为0
-                diastolic: 0  // 默认值为0
-            })
-        } else {
-            None
+        if !round_trips.is_empty() {
+            result.min_round_trip_ms = round_trips.iter().copied().reduce(f64::min);
+            result.max_round_trip_ms = round_trips.iter().copied().reduce(f64::max);
+            result.average_round_trip_ms =
+                Some(round_trips.iter().sum::<f64>() / round_trips.len() as f64);
         }
+        println!(
+            "[SerialReader] 回环测试完成: 完成{}/{}轮, 错误字节{}",
+            result.completed_rounds, result.rounds, result.corrupted_bytes
+        );
+        Ok(result)
     }
 
-    pub fn start(&self) -> Result<(), String> {
-        self.test_connection()?;
+    /// 通过读取线程已打开的串口发送数据，不再另外打开串口
+    pub fn send_data(&self, data: &str) -> Result<(), String> {
+        println!("[SerialReader] 向串口发送数据: {}", data);
+        let mut writer = self.writer.lock().unwrap();
+        let port = writer.as_mut().ok_or_else(|| "串口未连接".to_string())?;
+
+        port.write_all(data.as_bytes())
+            .map_err(|e| format!("发送数据失败: {}", e))?;
 
+        println!("[SerialReader] 数据发送完成");
+        Ok(())
+    }
+
+    /// 打开串口并启动读取线程
+    ///
+    /// 串口只打开一次：打开成功即视为连接正常，读取线程和发送数据共用这一个句柄。
+    pub fn start(&self) -> Result<(), String> {
         println!(
             "[SerialReader] 启动串口读取线程: {}, 波特率={}",
             self.config.port_name, self.config.baud_rate
         );
         let port = serialport::new(&self.config.port_name, self.config.baud_rate)
-            .timeout(Duration::from_millis(3000))
+            .timeout(Duration::from_millis(self.resilience.read_timeout_ms))
             .open()
             .map_err(|e| format!("无法打开串口: {}", e))?;
+        let writer = port
+            .try_clone()
+            .map_err(|e| format!("无法复制串口句柄: {}", e))?;
+        *self.writer.lock().unwrap() = Some(writer);
 
-        let reader = BufReader::new(port);
+        let mut assembler = FrameAssembler::new(self.parser.clone());
         let stop_flag = self.stop_flag.clone();
         let data_queue = self.data_queue.clone();
         let port_name = self.config.port_name.clone();
+        let channel_registry = self.channel_registry.clone();
+        let heartbeat = self.heartbeat.clone();
+        let counters = self.counters.clone();
+        let writer_slot = self.writer.clone();
+        heartbeat.beat();
+
+        let config = self.config.clone();
+        let status = self.status.clone();
+        let resilience = self.resilience.clone();
+        let thread_tuning = self.thread_tuning.clone();
+        let initial_backoff = Duration::from_millis(resilience.initial_backoff_ms);
+        let max_backoff = Duration::from_millis(resilience.max_backoff_ms);
 
-        std::thread::spawn(move || {
+        let handle = std::thread::spawn(move || {
             println!("[SerialReader][线程] 读取线程已启动，端口={}", port_name);
-            let mut line = String::new();
-            let mut reader = reader;
+            thread_tuning::apply(&thread_tuning, ThreadRole::SerialReader);
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            let mut port = port;
             let mut consecutive_errors = 0;
-            const MAX_CONSECUTIVE_ERRORS: u32 = 5;
+            let mut backoff = initial_backoff;
+            // 上一帧的序号，用于发现丢帧
+            let mut last_sequence: Option<u64> = None;
 
             while !stop_flag.load(Ordering::Relaxed) {
-                line.clear();
-                match reader.read_line(&mut line) {
-                    Ok(0) => {
-                        println!("[SerialReader][线程] 检测到串口 EOF，线程退出");
-                        break;
-                    }
-                    Ok(_) => {
+                let (kind, message) = match port.read(&mut chunk) {
+                    Ok(0) => (SerialErrorKind::DeviceGone, "检测到串口 EOF".to_string()),
+                    Ok(n) => {
                         consecutive_errors = 0;
-                        // print!("[SerialReader][线程] 原始数据行: {}", line.trim_end());
-                        if let Some(vital_signs) = Self::parse_data_line(&line) {
-                            // println!(" -> 解析成功: {:?}", vital_signs);
-                            let mut queue = data_queue.lock().unwrap();
-                            if queue.len() >= 1000 {
-                                // println!("[SerialReader][线程] 队列已满，移除最早数据");
-                                queue.pop_front();
+                        backoff = initial_backoff;
+                        heartbeat.beat();
+                        counters.record_bytes(n);
+                        assembler.push(&chunk[..n]);
+                        while let Some(unit) = assembler.next_frame() {
+                            match unit {
+                                AssembledFrame::Frame(frame) => {
+                                    let parsed = {
+                                        let registry = channel_registry.lock().unwrap();
+                                        assembler.decode(&frame, &registry)
+                                    };
+                                    assembler.recycle(frame);
+                                    deliver_frame(
+                                        parsed,
+                                        &counters,
+                                        &mut last_sequence,
+                                        &data_queue,
+                                    );
+                                }
+                                AssembledFrame::Discarded(len) => {
+                                    counters.record_resync(len);
+                                    hot_log!("[SerialReader][线程] 丢弃{}字节无效数据", len);
+                                }
                             }
-                            queue.push_back(vital_signs);
-                            // println!("[SerialReader][线程] 当前队列长度: {}", queue.len());
-                        } else {
-                            println!(" -> 解析失败，无效数据行");
                         }
+                        continue;
                     }
-                    Err(e) => {
+                    Err(e) => (Self::classify_io_error(&e), e.to_string()),
+                };
+
+                counters.record_error(kind, &message);
+                let action = kind.recovery_action();
+                if kind != SerialErrorKind::Timeout {
+                    eprintln!(
+                        "[SerialReader][线程] 串口读取错误: {} (类别: {:?}, 恢复动作: {:?})",
+                        message, kind, action
+                    );
+                }
+
+                match action {
+                    RecoveryAction::Retry => {}
+                    RecoveryAction::Backoff => {
                         consecutive_errors += 1;
-                        eprintln!(
-                            "[SerialReader][线程] 串口读取错误: {} (连续错误: {})",
-                            e, consecutive_errors
-                        );
-                        if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
+                        if consecutive_errors >= resilience.max_consecutive_errors {
                             eprintln!(
                                 "[SerialReader][线程] 连续发生{}次错误，退出读取线程",
-                                MAX_CONSECUTIVE_ERRORS
+                                consecutive_errors
                             );
+                            *status.lock().unwrap() =
+                                SerialStatus::Error(format!("串口连续读取错误: {}", message));
                             break;
                         }
-                        std::thread::sleep(Duration::from_millis(1000));
+                        std::thread::sleep(backoff);
+                        backoff = backoff
+                            .mul_f64(resilience.backoff_multiplier)
+                            .min(max_backoff);
+                    }
+                    RecoveryAction::Reconnect => {
+                        // 断开期间旧句柄已失效，发送数据直接报告未连接
+                        *writer_slot.lock().unwrap() = None;
+                        match Self::reconnect(&config, &resilience, &stop_flag) {
+                            Ok(Some(new_port)) => {
+                                println!("[SerialReader][线程] 串口已重新连接");
+                                counters.record_reconnect();
+                                *writer_slot.lock().unwrap() = new_port.try_clone().ok();
+                                port = new_port;
+                                // 断开前残留的半帧无法与新数据拼接
+                                assembler.clear();
+                                last_sequence = None;
+                                consecutive_errors = 0;
+                                backoff = initial_backoff;
+                            }
+                            Ok(None) => break,
+                            Err(e) => {
+                                eprintln!("[SerialReader][线程] 重新连接失败，退出读取线程: {}", e);
+                                *status.lock().unwrap() = SerialStatus::Error(e);
+                                break;
+                            }
+                        }
+                    }
+                    RecoveryAction::SurfaceToUser => {
+                        *status.lock().unwrap() =
+                            SerialStatus::Error(format!("串口读取失败: {}", message));
+                        break;
                     }
                 }
             }
+            *writer_slot.lock().unwrap() = None;
             println!("[SerialReader][线程] 读取线程安全退出");
         });
+        *self.worker.lock().unwrap() = Some(handle);
 
         Ok(())
     }
 
-    pub fn stop(&self) {
+    /// 将I/O错误归类
+    fn classify_io_error(error: &std::io::Error) -> SerialErrorKind {
+        match error.kind() {
+            ErrorKind::TimedOut | ErrorKind::WouldBlock | ErrorKind::Interrupted => {
+                SerialErrorKind::Timeout
+            }
+            ErrorKind::PermissionDenied => SerialErrorKind::PermissionDenied,
+            ErrorKind::InvalidData => SerialErrorKind::Framing,
+            ErrorKind::NotFound
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+            | ErrorKind::UnexpectedEof
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted => SerialErrorKind::DeviceGone,
+            _ => match error.raw_os_error() {
+                Some(code) if Self::is_device_gone_os_error(code) => SerialErrorKind::DeviceGone,
+                _ => SerialErrorKind::Other,
+            },
+        }
+    }
+
+    /// 设备拔出时操作系统返回的错误码
+    #[cfg(unix)]
+    fn is_device_gone_os_error(code: i32) -> bool {
+        // EIO、ENXIO、ENODEV
+        matches!(code, 5 | 6 | 19)
+    }
+
+    /// 设备拔出时操作系统返回的错误码
+    #[cfg(windows)]
+    fn is_device_gone_os_error(code: i32) -> bool {
+        // ERROR_BAD_COMMAND、ERROR_OPERATION_ABORTED、ERROR_DEVICE_NOT_CONNECTED
+        matches!(code, 22 | 995 | 1167)
+    }
+
+    /// 设备拔出时操作系统返回的错误码
+    #[cfg(not(any(unix, windows)))]
+    fn is_device_gone_os_error(_code: i32) -> bool {
+        false
+    }
+
+    /// 打开串口的错误是否因为串口已被其他程序占用
+    #[cfg(unix)]
+    fn is_port_busy_error(error: &serialport::Error, _port_name: &str) -> bool {
+        // serialport 把 EBUSY 归为 Unknown，只能按错误描述识别
+        matches!(error.kind(), serialport::ErrorKind::Unknown)
+            && error.description == "Device or resource busy"
+    }
+
+    /// 打开串口的错误是否因为串口已被其他程序占用
+    #[cfg(windows)]
+    fn is_port_busy_error(error: &serialport::Error, port_name: &str) -> bool {
+        // serialport 把 ERROR_ACCESS_DENIED 和串口不存在一样归为 NoDevice，
+        // 串口仍在可用串口列表中说明是被其他程序打开
+        matches!(error.kind(), serialport::ErrorKind::NoDevice)
+            && serialport::available_ports()
+                .is_ok_and(|ports| ports.iter().any(|port| port.port_name == port_name))
+    }
+
+    /// 打开串口的错误是否因为串口已被其他程序占用
+    #[cfg(not(any(unix, windows)))]
+    fn is_port_busy_error(_error: &serialport::Error, _port_name: &str) -> bool {
+        false
+    }
+
+    /// 将打开串口时的错误归类
+    fn classify_open_error(error: &serialport::Error) -> SerialErrorKind {
+        match error.kind() {
+            serialport::ErrorKind::NoDevice => SerialErrorKind::DeviceGone,
+            serialport::ErrorKind::Io(ErrorKind::PermissionDenied) => {
+                SerialErrorKind::PermissionDenied
+            }
+            serialport::ErrorKind::Io(ErrorKind::NotFound) => SerialErrorKind::DeviceGone,
+            _ => SerialErrorKind::Other,
+        }
+    }
+
+    /// 设备断开后按固定间隔尝试重新打开串口
+    ///
+    /// # 返回值
+    /// 成功时返回新的读取器；收到停止信号时返回None；
+    /// 权限错误或超过最大尝试次数时返回错误信息
+    fn reconnect(
+        config: &SerialConfig,
+        resilience: &SerialResilienceConfig,
+        stop_flag: &AtomicBool,
+    ) -> Result<Option<Box<dyn SerialPort>>, String> {
+        for attempt in 1..=resilience.max_reconnect_attempts {
+            std::thread::sleep(Duration::from_millis(resilience.reconnect_interval_ms));
+            if stop_flag.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            println!(
+                "[SerialReader][线程] 尝试重新连接 {} (第{}次)",
+                config.port_name, attempt
+            );
+            match serialport::new(&config.port_name, config.baud_rate)
+                .timeout(Duration::from_millis(resilience.read_timeout_ms))
+                .open()
+            {
+                Ok(port) => return Ok(Some(port)),
+                Err(e) if Self::classify_open_error(&e) == SerialErrorKind::PermissionDenied => {
+                    return Err(format!("无法打开串口: {}", e));
+                }
+                Err(e) => eprintln!("[SerialReader][线程] 重新连接失败: {}", e),
+            }
+        }
+        Err(format!(
+            "串口 {} 已断开，{}次重新连接均失败",
+            config.port_name, resilience.max_reconnect_attempts
+        ))
+    }
+
+    /// 发出停止信号并等待读取线程退出
+    ///
+    /// 读取线程最长阻塞一个读取超时才会检查停止标志，等待时间为读取超时加 `STOP_GRACE`。
+    ///
+    /// # 返回值
+    /// 读取线程未按时退出时返回错误信息，此时线程留在后台，发现停止标志后自行退出
+    pub fn stop(&self) -> Result<(), String> {
         println!("[SerialReader] 停止信号已发出");
         self.stop_flag.store(true, Ordering::Relaxed);
+        *self.writer.lock().unwrap() = None;
+        match self.worker.lock().unwrap().take() {
+            Some(handle) => {
+                let timeout = Duration::from_millis(self.resilience.read_timeout_ms) + STOP_GRACE;
+                join_with_timeout(handle, timeout, "串口读取线程")
+            }
+            None => Ok(()),
+        }
     }
 }
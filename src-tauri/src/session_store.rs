@@ -0,0 +1,457 @@
+use crate::data_processor::{self, DataProcessor};
+use crate::ipc_schema;
+use crate::purge;
+use crate::types::{BeatRecord, SessionAnnotation, TrendBin};
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use tauri::Manager;
+
+/// 已保存会话的概要信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SessionMetadata {
+    /// 会话ID
+    pub session_id: String,
+    /// 会话所属患者的ID
+    #[serde(default)]
+    pub patient_id: Option<String>,
+    /// 重新处理得到的衍生会话所基于的原会话ID
+    pub derived_from: Option<String>,
+    /// 重新处理所用的原始数据录制文件
+    pub source_recording: Option<String>,
+    /// 保存时间（RFC 3339）
+    pub created_at: String,
+    /// 第一个采样的时间戳（毫秒）
+    pub start_timestamp: u64,
+    /// 最后一个采样的时间戳（毫秒）
+    pub end_timestamp: u64,
+    /// 处理的采样数
+    pub sample_count: u64,
+    /// 无法解析而被跳过的采样数
+    pub rejected_samples: u64,
+    /// 保存时本机时钟相对NTP服务器的偏差（毫秒），未开启时间同步时为None
+    #[serde(default)]
+    pub clock_offset_ms: Option<f64>,
+    /// 保存时关闭的通道，这些通道的趋势不保存也不导出
+    #[serde(default)]
+    pub disabled_channels: Vec<String>,
+}
+
+/// 已保存的会话，包含各项体征的趋势和事件标注
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct StoredSession {
+    pub metadata: SessionMetadata,
+    /// 按体征名存放的趋势聚合段（每段1分钟）
+    pub trends: BTreeMap<String, Vec<TrendBin>>,
+    /// 会话中的事件标注
+    pub annotations: Vec<SessionAnnotation>,
+    /// 心搏检测结果（时间戳、RR间期、分类、幅度）
+    #[serde(default)]
+    pub beats: Vec<BeatRecord>,
+}
+
+/// 会话对比的时间对齐方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionAlignment {
+    /// 按距会话开始的时长对齐
+    Elapsed,
+    /// 按本地时钟时间对齐（距会话开始当天零点的时长），用于对比不同夜晚的同一时段
+    TimeOfDay,
+}
+
+/// 对比序列中的一个点，两个会话在该时刻的趋势平均值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ComparisonPoint {
+    /// 对齐后的时间偏移（毫秒）
+    pub offset_ms: u64,
+    /// 会话A在该时刻的平均值，没有数据时为None
+    pub a: Option<f64>,
+    /// 会话B在该时刻的平均值，没有数据时为None
+    pub b: Option<f64>,
+}
+
+/// 一个会话中某项体征的汇总统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct VitalSummary {
+    pub min: f64,
+    pub max: f64,
+    /// 按数值数量加权的平均值
+    pub mean: f64,
+    /// 参与统计的数值数量
+    pub count: u64,
+}
+
+/// 两个会话某项体征的对比结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SessionComparison {
+    pub session_a: String,
+    pub session_b: String,
+    pub vital: String,
+    pub alignment: SessionAlignment,
+    /// 按对齐时间排列的对比序列（每点1分钟）
+    pub points: Vec<ComparisonPoint>,
+    pub summary_a: VitalSummary,
+    pub summary_b: VitalSummary,
+    /// 平均值之差（B减A）
+    pub mean_delta: f64,
+    /// 最小值之差（B减A）
+    pub min_delta: f64,
+    /// 最大值之差（B减A）
+    pub max_delta: f64,
+}
+
+/// 对齐基准时间戳（毫秒）
+fn alignment_origin(session: &StoredSession, alignment: SessionAlignment) -> u64 {
+    let start = session.metadata.start_timestamp;
+    match alignment {
+        SessionAlignment::Elapsed => start,
+        SessionAlignment::TimeOfDay => Local
+            .timestamp_millis_opt(start as i64)
+            .single()
+            .and_then(|time| time.date_naive().and_hms_opt(0, 0, 0))
+            .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+            .map_or(start, |midnight| midnight.timestamp_millis() as u64),
+    }
+}
+
+/// 汇总趋势聚合段
+fn summarize(bins: &[TrendBin]) -> Option<VitalSummary> {
+    let count: u64 = bins.iter().map(|bin| bin.count).sum();
+    if count == 0 {
+        return None;
+    }
+    let weighted: f64 = bins.iter().map(|bin| bin.mean * bin.count as f64).sum();
+    Some(VitalSummary {
+        min: bins.iter().map(|bin| bin.min).fold(f64::INFINITY, f64::min),
+        max: bins
+            .iter()
+            .map(|bin| bin.max)
+            .fold(f64::NEG_INFINITY, f64::max),
+        mean: weighted / count as f64,
+        count,
+    })
+}
+
+/// 对比两个会话中的同一项体征
+///
+/// # 参数
+/// * `a` - 会话A，通常为较早的会话（例如用药前）
+/// * `b` - 会话B
+/// * `vital` - 体征名，例如 `heart_rate`
+/// * `alignment` - 时间对齐方式
+///
+/// # 返回值
+/// 返回对齐后的趋势序列和汇总差值，任一会话没有该体征的数据时返回错误
+pub fn compare(
+    a: &StoredSession,
+    b: &StoredSession,
+    vital: &str,
+    alignment: SessionAlignment,
+) -> Result<SessionComparison, String> {
+    let bins_a = a.trends.get(vital).map(Vec::as_slice).unwrap_or_default();
+    let bins_b = b.trends.get(vital).map(Vec::as_slice).unwrap_or_default();
+    let missing = |session: &StoredSession| {
+        format!(
+            "会话 {} 中没有体征 {} 的数据",
+            session.metadata.session_id, vital
+        )
+    };
+    let summary_a = summarize(bins_a).ok_or_else(|| missing(a))?;
+    let summary_b = summarize(bins_b).ok_or_else(|| missing(b))?;
+
+    // 按对齐后的分钟合并两个会话的趋势
+    let mut merged: BTreeMap<u64, (Option<f64>, Option<f64>)> = BTreeMap::new();
+    let origin_a = alignment_origin(a, alignment);
+    for bin in bins_a {
+        let minute = bin.start_timestamp.saturating_sub(origin_a) / 60_000;
+        merged.entry(minute).or_default().0 = Some(bin.mean);
+    }
+    let origin_b = alignment_origin(b, alignment);
+    for bin in bins_b {
+        let minute = bin.start_timestamp.saturating_sub(origin_b) / 60_000;
+        merged.entry(minute).or_default().1 = Some(bin.mean);
+    }
+    let points = merged
+        .into_iter()
+        .map(|(minute, (a, b))| ComparisonPoint {
+            offset_ms: minute * 60_000,
+            a,
+            b,
+        })
+        .collect();
+
+    Ok(SessionComparison {
+        session_a: a.metadata.session_id.clone(),
+        session_b: b.metadata.session_id.clone(),
+        vital: vital.to_string(),
+        alignment,
+        points,
+        mean_delta: summary_b.mean - summary_a.mean,
+        min_delta: summary_b.min - summary_a.min,
+        max_delta: summary_b.max - summary_a.max,
+        summary_a,
+        summary_b,
+    })
+}
+
+/// 收集处理器当前会话的趋势和事件标注
+///
+/// 起止时间取自趋势聚合段的范围，重新处理时由调用方按录制时间覆盖。
+/// 关闭的通道的趋势不保存。
+pub fn capture(processor: &DataProcessor) -> StoredSession {
+    let disabled_channels = processor.get_disabled_channels();
+    let disabled_vitals: Vec<&str> = disabled_channels
+        .iter()
+        .flat_map(|channel| data_processor::channel_vitals(channel))
+        .collect();
+    let trends: BTreeMap<String, Vec<TrendBin>> = processor
+        .get_trend_vitals()
+        .into_iter()
+        .filter(|vital| !disabled_vitals.contains(&vital.as_str()))
+        .map(|vital| {
+            let bins = processor.get_trends(&vital, None, None);
+            (vital, bins)
+        })
+        .collect();
+    let bins = || trends.values().flatten();
+    let start_timestamp = bins().map(|bin| bin.start_timestamp).min().unwrap_or(0);
+    let end_timestamp = bins().map(|bin| bin.end_timestamp).max().unwrap_or(0);
+
+    StoredSession {
+        metadata: SessionMetadata {
+            session_id: processor.session_id().to_string(),
+            patient_id: None,
+            derived_from: None,
+            source_recording: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            start_timestamp,
+            end_timestamp,
+            sample_count: processor.total_processed(),
+            rejected_samples: 0,
+            clock_offset_ms: None,
+            disabled_channels,
+        },
+        trends,
+        annotations: processor.get_session_annotations(),
+        beats: processor.get_beats(None, None),
+    }
+}
+
+/// 导出记录，用于在清除患者数据时找到导出的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ExportRecord {
+    /// 导出的会话ID
+    pub session_id: String,
+    /// 会话所属患者的ID
+    pub patient_id: Option<String>,
+    /// 导出文件路径
+    pub path: String,
+    /// 导出时间（RFC 3339）
+    pub created_at: String,
+}
+
+/// 会话存储，每个会话保存为 `sessions` 目录下的一个JSON文件
+pub struct SessionStore {
+    data_dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("无法获取应用数据目录: {}", e))?;
+
+        let data_dir = app_data_dir.join("vital-signs").join("sessions");
+        if !data_dir.exists() {
+            fs::create_dir_all(&data_dir).map_err(|e| format!("创建会话目录失败: {}", e))?;
+        }
+
+        Ok(Self { data_dir })
+    }
+
+    fn export_log(&self) -> PathBuf {
+        self.data_dir.join("exports.jsonl")
+    }
+
+    fn load_exports(&self) -> Result<Vec<ExportRecord>, String> {
+        let path = self.export_log();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| format!("读取导出记录失败: {}", e))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| ipc_schema::from_stored_json(line).ok())
+            .collect())
+    }
+
+    /// 追加一条导出记录
+    pub fn record_export(&self, record: &ExportRecord) -> Result<(), String> {
+        let line =
+            serde_json::to_string(record).map_err(|e| format!("序列化导出记录失败: {}", e))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.export_log())
+            .map_err(|e| format!("打开导出记录失败: {}", e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("写入导出记录失败: {}", e))
+    }
+
+    fn session_file(&self, session_id: &str) -> Result<PathBuf, String> {
+        let valid = !session_id.is_empty()
+            && session_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(format!("无效的会话ID: {}", session_id));
+        }
+        Ok(self.data_dir.join(format!("{}.json", session_id)))
+    }
+
+    /// 保存会话，已存在同ID的会话时返回错误
+    pub fn save(&self, session: &StoredSession) -> Result<(), String> {
+        let path = self.session_file(&session.metadata.session_id)?;
+        if path.exists() {
+            return Err(format!("会话 {} 已存在", session.metadata.session_id));
+        }
+        let json = serde_json::to_string(session).map_err(|e| format!("序列化会话失败: {}", e))?;
+        fs::write(&path, json).map_err(|e| format!("写入会话文件失败: {}", e))
+    }
+
+    /// 读取会话
+    pub fn load(&self, session_id: &str) -> Result<StoredSession, String> {
+        let path = self.session_file(session_id)?;
+        if !path.exists() {
+            return Err(format!("会话不存在: {}", session_id));
+        }
+        let content = fs::read_to_string(&path).map_err(|e| format!("读取会话文件失败: {}", e))?;
+        ipc_schema::from_stored_json(&content).map_err(|e| format!("解析会话文件失败: {}", e))
+    }
+
+    /// 不可恢复地删除属于某患者的会话
+    ///
+    /// # 返回值
+    /// 返回被删除的会话ID
+    pub fn purge_sessions(&self, patient_id: &str) -> Result<Vec<String>, String> {
+        let mut removed = Vec::new();
+        for metadata in self.list()? {
+            if metadata.patient_id.as_deref() == Some(patient_id) {
+                purge::secure_delete(&self.session_file(&metadata.session_id)?)?;
+                removed.push(metadata.session_id);
+            }
+        }
+        Ok(removed)
+    }
+
+    /// 不可恢复地删除属于某患者的导出文件，并从导出记录中移除
+    ///
+    /// # 返回值
+    /// 返回被删除的导出文件路径，已不存在的文件不计入
+    pub fn purge_exports(&self, patient_id: &str) -> Result<Vec<String>, String> {
+        let (purged, kept): (Vec<ExportRecord>, Vec<ExportRecord>) = self
+            .load_exports()?
+            .into_iter()
+            .partition(|record| record.patient_id.as_deref() == Some(patient_id));
+
+        let mut removed = Vec::new();
+        for record in purged {
+            let path = PathBuf::from(&record.path);
+            if path.exists() {
+                purge::secure_delete(&path)?;
+                removed.push(record.path);
+            }
+        }
+
+        self.write_exports(&kept)?;
+        Ok(removed)
+    }
+
+    /// 重写导出记录
+    fn write_exports(&self, records: &[ExportRecord]) -> Result<(), String> {
+        let mut content = String::new();
+        for record in records {
+            let line =
+                serde_json::to_string(record).map_err(|e| format!("序列化导出记录失败: {}", e))?;
+            content.push_str(&line);
+            content.push('\n');
+        }
+        fs::write(self.export_log(), content).map_err(|e| format!("写入导出记录失败: {}", e))
+    }
+
+    /// 把属于某患者的会话改为属于另一个患者
+    ///
+    /// # 返回值
+    /// 返回涉及的会话ID，`dry_run` 为真时只查找不修改
+    pub fn relink_sessions(
+        &self,
+        from: &str,
+        to: &str,
+        dry_run: bool,
+    ) -> Result<Vec<String>, String> {
+        let mut relinked = Vec::new();
+        for metadata in self.list()? {
+            if metadata.patient_id.as_deref() != Some(from) {
+                continue;
+            }
+            if !dry_run {
+                let mut session = self.load(&metadata.session_id)?;
+                session.metadata.patient_id = Some(to.to_string());
+                let json = serde_json::to_string(&session)
+                    .map_err(|e| format!("序列化会话失败: {}", e))?;
+                fs::write(self.session_file(&metadata.session_id)?, json)
+                    .map_err(|e| format!("写入会话文件失败: {}", e))?;
+            }
+            relinked.push(metadata.session_id);
+        }
+        Ok(relinked)
+    }
+
+    /// 把属于某患者的导出记录改为属于另一个患者
+    ///
+    /// # 返回值
+    /// 返回涉及的记录数，`dry_run` 为真时只统计不修改
+    pub fn relink_exports(&self, from: &str, to: &str, dry_run: bool) -> Result<usize, String> {
+        let mut records = self.load_exports()?;
+        let mut count = 0;
+        for record in records
+            .iter_mut()
+            .filter(|record| record.patient_id.as_deref() == Some(from))
+        {
+            record.patient_id = Some(to.to_string());
+            count += 1;
+        }
+        if count > 0 && !dry_run {
+            self.write_exports(&records)?;
+        }
+        Ok(count)
+    }
+
+    /// 列出已保存的会话
+    ///
+    /// # 返回值
+    /// 返回按开始时间先后排列的会话概要，无法解析的文件被跳过
+    pub fn list(&self) -> Result<Vec<SessionMetadata>, String> {
+        let entries =
+            fs::read_dir(&self.data_dir).map_err(|e| format!("读取会话目录失败: {}", e))?;
+        let mut sessions: Vec<SessionMetadata> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .filter_map(|content| ipc_schema::from_stored_json::<StoredSession>(&content).ok())
+            .map(|session| session.metadata)
+            .collect();
+        sessions.sort_by_key(|metadata| metadata.start_timestamp);
+        Ok(sessions)
+    }
+}
@@ -0,0 +1,209 @@
+//! 共享内存输出模块
+//!
+//! 把最新的采样持续写入一个固定大小的环形缓冲文件，同机的分析程序把该文件映射到内存
+//! （mmap / MapViewOfFile）后即可以微秒级延迟读取体征，无需经过IPC。写入经过系统页缓存，
+//! 映射该文件的进程立即可见。
+//!
+//! 文件布局（小端序）：
+//! * 0..64字节为文件头：
+//!   * 0 `u32` 魔数 `0x4D485356`（"VSHM"），4 `u32` 版本号
+//!   * 8 `u32` 环形缓冲容量（采样数），12 `u32` 每个采样的字节数
+//!   * 16 `f64` 采样率（Hz）
+//!   * 24 `u64` 序号：已写入的采样总数，最新采样位于第 `(序号 - 1) % 容量` 个槽位
+//!   * 32 `u32` 每个采样的数值个数
+//! * 之后为 `容量` 个槽位，每个槽位为 `u64` 时间戳（毫秒）加 `CHANNELS` 中各项的 `f32` 数值，
+//!   设备未上报的体征为NaN
+//!
+//! 每个采样先写槽位再更新序号。读取方读序号、读槽位后再读一次序号，
+//! 序号前进超过容量时说明槽位已被覆盖。
+
+use crate::types::ProcessedVitalSigns;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io;
+
+/// 文件魔数 "VSHM"
+const MAGIC: u32 = 0x4D48_5356;
+
+/// 文件格式版本
+const VERSION: u32 = 1;
+
+/// 文件头长度（字节）
+const HEADER_LEN: u64 = 64;
+
+/// 序号在文件头中的偏移
+const SEQUENCE_OFFSET: u64 = 24;
+
+/// 每个采样的数值，依次写在时间戳之后
+pub const CHANNELS: [&str; 8] = [
+    "ecg_normalized",
+    "ecg_raw",
+    "heart_rate",
+    "blood_oxygen",
+    "body_temperature",
+    "respiration_rate",
+    "rr_interval",
+    "artifact",
+];
+
+/// 每个采样的字节数
+const SLOT_LEN: usize = 8 + CHANNELS.len() * 4;
+
+/// 共享内存输出配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SharedMemoryConfig {
+    /// 环形缓冲文件路径
+    pub path: String,
+    /// 容量（采样数）
+    #[serde(default = "default_capacity")]
+    pub capacity: u32,
+    /// 采样率（Hz），写入文件头供读取方换算
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate_hz: f64,
+}
+
+fn default_capacity() -> u32 {
+    4096
+}
+
+fn default_sample_rate() -> f64 {
+    250.0
+}
+
+/// 共享内存输出状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SharedMemoryStatus {
+    pub config: SharedMemoryConfig,
+    /// 每个采样的数值名称
+    pub channels: Vec<String>,
+    /// 已写入的采样总数
+    pub sequence: u64,
+    /// 写入失败次数
+    pub write_errors: u64,
+}
+
+/// 验证共享内存输出配置
+pub fn validate_config(config: &SharedMemoryConfig) -> Result<(), String> {
+    if config.path.trim().is_empty() {
+        return Err("共享内存文件路径不能为空".to_string());
+    }
+    if !(16..=1_048_576).contains(&config.capacity) {
+        return Err("共享内存容量必须在16到1048576个采样之间".to_string());
+    }
+    if !(1.0..=10_000.0).contains(&config.sample_rate_hz) {
+        return Err("采样率必须在1到10000Hz之间".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn write_at(file: &File, bytes: &[u8], offset: u64) -> io::Result<()> {
+    use std::os::unix::fs::FileExt;
+    file.write_all_at(bytes, offset)
+}
+
+#[cfg(windows)]
+fn write_at(file: &File, bytes: &[u8], offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    let mut written = 0;
+    while written < bytes.len() {
+        written += file.seek_write(&bytes[written..], offset + written as u64)?;
+    }
+    Ok(())
+}
+
+/// 共享内存环形缓冲写入器，由处理线程在每个采样后调用 `push`
+pub struct SharedMemoryWriter {
+    config: SharedMemoryConfig,
+    file: File,
+    sequence: u64,
+    write_errors: u64,
+}
+
+impl SharedMemoryWriter {
+    /// 创建（或覆盖）环形缓冲文件并写入文件头
+    pub fn new(config: SharedMemoryConfig) -> Result<Self, String> {
+        validate_config(&config)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&config.path)
+            .map_err(|e| format!("创建共享内存文件失败: {}", e))?;
+        file.set_len(HEADER_LEN + config.capacity as u64 * SLOT_LEN as u64)
+            .map_err(|e| format!("设置共享内存文件大小失败: {}", e))?;
+
+        let mut header = Vec::with_capacity(HEADER_LEN as usize);
+        header.extend_from_slice(&MAGIC.to_le_bytes());
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&config.capacity.to_le_bytes());
+        header.extend_from_slice(&(SLOT_LEN as u32).to_le_bytes());
+        header.extend_from_slice(&config.sample_rate_hz.to_le_bytes());
+        header.extend_from_slice(&0u64.to_le_bytes());
+        header.extend_from_slice(&(CHANNELS.len() as u32).to_le_bytes());
+        header.resize(HEADER_LEN as usize, 0);
+        write_at(&file, &header, 0).map_err(|e| format!("写入共享内存文件头失败: {}", e))?;
+
+        println!(
+            "[SharedMemory] 环形缓冲文件 {}，容量={}个采样",
+            config.path, config.capacity
+        );
+        Ok(Self {
+            config,
+            file,
+            sequence: 0,
+            write_errors: 0,
+        })
+    }
+
+    /// 写入一个采样
+    pub fn push(&mut self, processed: &ProcessedVitalSigns) {
+        let values = [
+            processed.ecg_normalized,
+            processed.ecg_raw as f64,
+            processed.heart_rate,
+            processed.blood_oxygen.unwrap_or(f64::NAN),
+            processed.body_temperature.unwrap_or(f64::NAN),
+            processed.respiration_rate.unwrap_or(f64::NAN),
+            processed.rr_interval,
+            if processed.artifact { 1.0 } else { 0.0 },
+        ];
+        let mut slot = [0u8; SLOT_LEN];
+        slot[..8].copy_from_slice(&processed.timestamp.to_le_bytes());
+        for (chunk, value) in slot[8..].chunks_exact_mut(4).zip(values) {
+            chunk.copy_from_slice(&(value as f32).to_le_bytes());
+        }
+
+        let index = self.sequence % self.config.capacity as u64;
+        let offset = HEADER_LEN + index * SLOT_LEN as u64;
+        let result = write_at(&self.file, &slot, offset).and_then(|_| {
+            write_at(
+                &self.file,
+                &(self.sequence + 1).to_le_bytes(),
+                SEQUENCE_OFFSET,
+            )
+        });
+        match result {
+            Ok(()) => self.sequence += 1,
+            Err(e) => {
+                if self.write_errors == 0 {
+                    eprintln!("[SharedMemory] 写入共享内存文件失败: {}", e);
+                }
+                self.write_errors += 1;
+            }
+        }
+    }
+
+    /// 获取输出状态
+    pub fn status(&self) -> SharedMemoryStatus {
+        SharedMemoryStatus {
+            config: self.config.clone(),
+            channels: CHANNELS.iter().map(|name| name.to_string()).collect(),
+            sequence: self.sequence,
+            write_errors: self.write_errors,
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! 波形快照模块
+//!
+//! 临床人员观察到异常事件时一键把最近几秒的心电波形（原始采样和LTTB压缩波形）连同当时的
+//! 体征数值写入文件存档。原始采样优先取自原始心电存档，存档未初始化时取自处理后数据队列，
+//! 此时可截取的时长受处理后数据队列容量限制，文件中记录实际截取的时间范围。
+
+use crate::data_processor::DataProcessor;
+use crate::ecg_archive::{EcgArchive, EcgSample};
+use crate::types::{BloodPressureReading, CurrentVitalValue, LttbDataPoint};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+
+/// 快照时长范围（秒）
+const DURATION_RANGE_SECS: (u64, u64) = (1, 60);
+
+/// 快照文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotFormat {
+    /// 包含波形和体征数值的JSON文件
+    Json,
+    /// 每行一个波形数据点的CSV文件，文件头以 `#` 注释行记录体征数值
+    Csv,
+}
+
+/// 快照时的体征数值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SnapshotNumerics {
+    pub heart_rate: CurrentVitalValue,
+    pub spo2: CurrentVitalValue,
+    pub temperature: CurrentVitalValue,
+    /// 呼吸频率（次/分），没有呼吸数据时为None
+    pub respiration_rate: Option<f64>,
+    /// 呼气末二氧化碳分压（mmHg），没有二氧化碳数据时为None
+    pub etco2: Option<f64>,
+    /// 最近一次无创血压测量
+    pub blood_pressure: Option<BloodPressureReading>,
+}
+
+/// 波形快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WaveformSnapshot {
+    /// 所属会话ID
+    pub session_id: String,
+    /// 快照时间（RFC 3339）
+    pub captured_at: String,
+    /// 请求的时长（秒）
+    pub requested_duration_secs: u64,
+    /// 实际截取的起始时间戳（毫秒）
+    pub start_timestamp: u64,
+    /// 实际截取的结束时间戳（毫秒）
+    pub end_timestamp: u64,
+    /// 原始ECG采样，按时间先后排列
+    pub raw: Vec<EcgSample>,
+    /// 快照时显示窗口的LTTB压缩波形
+    pub compressed: Vec<LttbDataPoint>,
+    pub numerics: SnapshotNumerics,
+}
+
+/// 截取最近一段时间的心电波形和当前体征数值
+///
+/// # 参数
+/// * `processor` - 正在运行的数据处理器
+/// * `archive` - 原始心电存档，未初始化时为None
+/// * `duration_secs` - 截取的时长（秒）
+pub fn capture(
+    processor: &DataProcessor,
+    archive: Option<&EcgArchive>,
+    duration_secs: u64,
+) -> Result<WaveformSnapshot, String> {
+    if !(DURATION_RANGE_SECS.0..=DURATION_RANGE_SECS.1).contains(&duration_secs) {
+        return Err(format!(
+            "快照时长必须在{}到{}秒之间",
+            DURATION_RANGE_SECS.0, DURATION_RANGE_SECS.1
+        ));
+    }
+    let latest = processor
+        .get_processed_data(1, None, None)
+        .pop()
+        .ok_or_else(|| "没有可用的心电数据".to_string())?;
+    let end = latest.timestamp;
+    let start = end.saturating_sub(duration_secs * 1000);
+
+    let raw = match archive {
+        Some(archive) => archive.range(start, end + 1, None),
+        None => {
+            let mut samples: Vec<EcgSample> = processor
+                .get_processed_data(usize::MAX, Some(start), Some(end))
+                .into_iter()
+                .map(|d| EcgSample {
+                    timestamp: d.timestamp,
+                    value: d.ecg_raw,
+                })
+                .collect();
+            samples.reverse();
+            samples
+        }
+    };
+    let positive = |value: &f64| *value > 0.0;
+
+    Ok(WaveformSnapshot {
+        session_id: processor.session_id().to_string(),
+        captured_at: chrono::Utc::now().to_rfc3339(),
+        requested_duration_secs: duration_secs,
+        start_timestamp: raw.first().map_or(end, |s| s.timestamp),
+        end_timestamp: end,
+        raw,
+        compressed: latest.ecg_lttb_compressed,
+        numerics: SnapshotNumerics {
+            heart_rate: processor.get_current_heart_rate(),
+            spo2: processor.get_current_spo2(),
+            temperature: processor.get_current_temperature(),
+            respiration_rate: latest.respiration_rate.filter(positive),
+            etco2: latest.etco2.filter(positive),
+            blood_pressure: processor.get_latest_blood_pressure(),
+        },
+    })
+}
+
+fn to_csv(snapshot: &WaveformSnapshot) -> String {
+    let mut csv = String::new();
+    let _ = writeln!(csv, "# session={}", snapshot.session_id);
+    let _ = writeln!(csv, "# captured_at={}", snapshot.captured_at);
+    let _ = writeln!(
+        csv,
+        "# start_timestamp={},end_timestamp={}",
+        snapshot.start_timestamp, snapshot.end_timestamp
+    );
+    let numerics = &snapshot.numerics;
+    for (name, value) in [
+        ("heart_rate", &numerics.heart_rate),
+        ("spo2", &numerics.spo2),
+        ("temperature", &numerics.temperature),
+    ] {
+        let _ = writeln!(
+            csv,
+            "# {}={},unit={},quality={:?}",
+            name,
+            value.value.map(|v| format!("{:.1}", v)).unwrap_or_default(),
+            value.unit,
+            value.quality
+        );
+    }
+    if let Some(rate) = numerics.respiration_rate {
+        let _ = writeln!(csv, "# respiration_rate={:.1}", rate);
+    }
+    if let Some(etco2) = numerics.etco2 {
+        let _ = writeln!(csv, "# etco2={:.1}", etco2);
+    }
+    if let Some(bp) = &numerics.blood_pressure {
+        let _ = writeln!(
+            csv,
+            "# blood_pressure={}/{},timestamp={}",
+            bp.systolic, bp.diastolic, bp.timestamp
+        );
+    }
+    csv.push_str("series,x,y\n");
+    for sample in &snapshot.raw {
+        let _ = writeln!(csv, "raw,{},{}", sample.timestamp, sample.value);
+    }
+    for point in &snapshot.compressed {
+        let _ = writeln!(csv, "compressed,{},{}", point.x, point.y);
+    }
+    csv
+}
+
+/// 把快照写入文件
+pub fn write(
+    snapshot: &WaveformSnapshot,
+    path: &str,
+    format: SnapshotFormat,
+) -> Result<(), String> {
+    if path.trim().is_empty() {
+        return Err("快照文件路径不能为空".to_string());
+    }
+    let content = match format {
+        SnapshotFormat::Json => serde_json::to_string_pretty(snapshot)
+            .map_err(|e| format!("序列化波形快照失败: {}", e))?,
+        SnapshotFormat::Csv => to_csv(snapshot),
+    };
+    fs::write(path, content).map_err(|e| format!("写入快照文件失败: {}", e))?;
+    println!(
+        "[Snapshot] 已保存 {} 个原始采样的波形快照到 {}，格式={:?}",
+        snapshot.raw.len(),
+        path,
+        format
+    );
+    Ok(())
+}
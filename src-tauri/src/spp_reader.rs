@@ -0,0 +1,452 @@
+//! 蓝牙SPP（RFCOMM）数据源模块
+//!
+//! Windows和macOS会为已配对的SPP设备创建虚拟串口，由 `serialport` 直接列出；
+//! Linux下通过BlueZ查询已配对且提供串口服务的设备，并直接建立RFCOMM连接，
+//! 无需事先用 `rfcomm bind` 创建 `/dev/rfcommN`。
+//!
+//! SPP设备在串口列表中的名称为 `bt:<蓝牙地址>`，可用 `bt:<蓝牙地址>@<通道>`
+//! 指定RFCOMM通道，省略时使用通道1（绝大多数SPP模块的默认通道）。
+
+use crate::channels::ChannelRegistry;
+use crate::framing::{deliver_frame, AssembledFrame, FrameAssembler, FrameParser};
+use crate::serial_manager::StreamCounters;
+use crate::types::{DataQueue, SerialErrorKind, SerialResilienceConfig, SerialStatus};
+use crate::watchdog::Heartbeat;
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// SPP设备在串口列表中的名称前缀
+const PORT_PREFIX: &str = "bt:";
+
+/// 未指定通道时使用的RFCOMM通道
+const DEFAULT_CHANNEL: u8 = 1;
+
+/// 每次读取的最大字节数
+const READ_CHUNK_SIZE: usize = 1024;
+
+/// 已配对的SPP设备
+#[derive(Debug, Clone)]
+pub struct SppDevice {
+    /// 蓝牙地址，例如 `00:11:22:33:44:55`
+    pub address: String,
+    /// 设备名称
+    pub name: String,
+}
+
+impl SppDevice {
+    /// 设备在串口列表中的名称
+    pub fn port_name(&self) -> String {
+        format!("{}{}", PORT_PREFIX, self.address)
+    }
+}
+
+/// SPP连接目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SppTarget {
+    /// 蓝牙地址（大端，按显示顺序）
+    address: [u8; 6],
+    /// RFCOMM通道
+    channel: u8,
+}
+
+/// 判断串口名称是否是SPP设备
+pub fn is_spp_port(port_name: &str) -> bool {
+    port_name.starts_with(PORT_PREFIX)
+}
+
+/// 解析SPP设备的串口名称
+///
+/// # 参数
+/// * `port_name` - `bt:<蓝牙地址>` 或 `bt:<蓝牙地址>@<通道>`
+pub fn parse_port_name(port_name: &str) -> Result<SppTarget, String> {
+    let target = port_name
+        .strip_prefix(PORT_PREFIX)
+        .ok_or_else(|| format!("{} 不是蓝牙SPP设备", port_name))?;
+    let (address_text, channel) = match target.split_once('@') {
+        Some((address, channel)) => (
+            address,
+            channel
+                .parse::<u8>()
+                .ok()
+                .filter(|c| (1..=30).contains(c))
+                .ok_or_else(|| format!("无效的RFCOMM通道: {}", channel))?,
+        ),
+        None => (target, DEFAULT_CHANNEL),
+    };
+
+    let parts: Vec<&str> = address_text.split(':').collect();
+    if parts.len() != 6 {
+        return Err(format!("无效的蓝牙地址: {}", address_text));
+    }
+    let mut address = [0u8; 6];
+    for (byte, part) in address.iter_mut().zip(parts) {
+        *byte = u8::from_str_radix(part, 16)
+            .map_err(|_| format!("无效的蓝牙地址: {}", address_text))?;
+    }
+    Ok(SppTarget { address, channel })
+}
+
+/// 列出已配对且提供串口服务（SPP）的蓝牙设备
+///
+/// 仅Linux需要，其他平台的SPP设备已作为虚拟串口列出，返回空列表。
+pub fn discover_paired_devices() -> Vec<SppDevice> {
+    platform::discover_paired_devices()
+}
+
+/// 测试能否建立SPP连接
+pub fn test_connection(port_name: &str) -> Result<(), String> {
+    let target = parse_port_name(port_name)?;
+    platform::connect(&target, Duration::from_millis(1000)).map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{SppDevice, SppTarget};
+    use std::fs::File;
+    use std::os::fd::{FromRawFd, OwnedFd};
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// 调用 `bluetoothctl` 的超时，蓝牙服务未运行时 `bluetoothctl` 会一直等待
+    const BLUETOOTHCTL_TIMEOUT: Duration = Duration::from_millis(2000);
+
+    /// 串口服务（Serial Port Profile）的UUID
+    const SPP_UUID: &str = "00001101-0000-1000-8000-00805f9b34fb";
+
+    /// BlueZ中RFCOMM协议的编号
+    const BTPROTO_RFCOMM: libc::c_int = 3;
+
+    /// RFCOMM套接字地址（`struct sockaddr_rc`）
+    #[repr(C)]
+    struct SockaddrRc {
+        rc_family: libc::sa_family_t,
+        /// 蓝牙地址，按小端顺序存放
+        rc_bdaddr: [u8; 6],
+        rc_channel: u8,
+    }
+
+    /// 运行 `bluetoothctl` 并返回标准输出，失败或超时时返回None
+    fn bluetoothctl(args: &[&str]) -> Option<String> {
+        let mut child = Command::new("bluetoothctl")
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let deadline = Instant::now() + BLUETOOTHCTL_TIMEOUT;
+        loop {
+            match child.try_wait() {
+                Ok(Some(_)) => break,
+                Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(20)),
+                _ => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return None;
+                }
+            }
+        }
+        child
+            .wait_with_output()
+            .ok()
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    pub fn discover_paired_devices() -> Vec<SppDevice> {
+        // BlueZ 5.65起使用 `devices Paired`，旧版本为 `paired-devices`
+        let Some(listing) = bluetoothctl(&["devices", "Paired"])
+            .filter(|output| output.contains("Device "))
+            .or_else(|| bluetoothctl(&["paired-devices"]))
+        else {
+            return Vec::new();
+        };
+
+        listing
+            .lines()
+            .filter_map(|line| {
+                let rest = line.trim().strip_prefix("Device ")?;
+                let (address, name) = rest.split_once(' ').unwrap_or((rest, rest));
+                Some(SppDevice {
+                    address: address.to_string(),
+                    name: name.trim().to_string(),
+                })
+            })
+            .filter(|device| {
+                bluetoothctl(&["info", &device.address])
+                    .is_some_and(|info| info.to_lowercase().contains(SPP_UUID))
+            })
+            .collect()
+    }
+
+    /// 建立RFCOMM连接
+    ///
+    /// # 参数
+    /// * `target` - 连接目标
+    /// * `read_timeout` - 读取超时
+    pub fn connect(target: &SppTarget, read_timeout: Duration) -> Result<File, String> {
+        // SAFETY: 创建套接字没有前置条件，返回值在下面检查
+        let fd = unsafe { libc::socket(libc::AF_BLUETOOTH, libc::SOCK_STREAM, BTPROTO_RFCOMM) };
+        if fd < 0 {
+            return Err(format!(
+                "无法创建蓝牙套接字: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        // SAFETY: fd是刚创建的有效套接字，所有权转移给OwnedFd，出错时自动关闭
+        let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let mut bdaddr = target.address;
+        bdaddr.reverse();
+        let address = SockaddrRc {
+            rc_family: libc::AF_BLUETOOTH as libc::sa_family_t,
+            rc_bdaddr: bdaddr,
+            rc_channel: target.channel,
+        };
+        // SAFETY: address是有效的sockaddr_rc，长度与结构体一致
+        let result = unsafe {
+            libc::connect(
+                fd,
+                &address as *const SockaddrRc as *const libc::sockaddr,
+                std::mem::size_of::<SockaddrRc>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(format!(
+                "无法连接蓝牙设备: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        let timeout = libc::timeval {
+            tv_sec: read_timeout.as_secs() as libc::time_t,
+            tv_usec: read_timeout.subsec_micros() as libc::suseconds_t,
+        };
+        // SAFETY: timeout是有效的timeval，长度与结构体一致
+        let result = unsafe {
+            libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &timeout as *const libc::timeval as *const libc::c_void,
+                std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(format!(
+                "设置读取超时失败: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(File::from(socket))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::{SppDevice, SppTarget};
+    use std::fs::File;
+    use std::time::Duration;
+
+    pub fn discover_paired_devices() -> Vec<SppDevice> {
+        Vec::new()
+    }
+
+    pub fn connect(_target: &SppTarget, _read_timeout: Duration) -> Result<File, String> {
+        Err("当前平台不支持直接连接蓝牙SPP设备，请使用系统为该设备创建的蓝牙串口".to_string())
+    }
+}
+
+pub struct SppReader {
+    port_name: String,
+    target: SppTarget,
+    data_queue: DataQueue,
+    channel_registry: Arc<Mutex<ChannelRegistry>>,
+    parser: Arc<dyn FrameParser>,
+    counters: Arc<StreamCounters>,
+    status: Arc<Mutex<SerialStatus>>,
+    resilience: SerialResilienceConfig,
+    /// 当前连接的写入端，用于发送数据，重新连接后替换
+    writer: Arc<Mutex<Option<File>>>,
+    stop_flag: Arc<AtomicBool>,
+    heartbeat: Heartbeat,
+}
+
+impl SppReader {
+    pub fn new(
+        port_name: &str,
+        data_queue: DataQueue,
+        channel_registry: Arc<Mutex<ChannelRegistry>>,
+        parser: Arc<dyn FrameParser>,
+        counters: Arc<StreamCounters>,
+        status: Arc<Mutex<SerialStatus>>,
+        resilience: SerialResilienceConfig,
+    ) -> Result<Self, String> {
+        let target = parse_port_name(port_name)?;
+        println!(
+            "[SppReader] 初始化，设备={}，RFCOMM通道={}",
+            port_name, target.channel
+        );
+        Ok(Self {
+            port_name: port_name.to_string(),
+            target,
+            data_queue,
+            channel_registry,
+            parser,
+            counters,
+            status,
+            resilience,
+            writer: Arc::new(Mutex::new(None)),
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            heartbeat: Heartbeat::new(),
+        })
+    }
+
+    /// 读取线程的心跳，每轮读取（包括超时）更新一次
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
+
+    /// 按错误恢复策略重新建立连接
+    ///
+    /// # 返回值
+    /// 重新连接成功时返回新连接，停止信号到达时返回None
+    fn reconnect(
+        port_name: &str,
+        target: &SppTarget,
+        resilience: &SerialResilienceConfig,
+        stop_flag: &AtomicBool,
+    ) -> Result<Option<File>, String> {
+        let read_timeout = Duration::from_millis(resilience.read_timeout_ms);
+        for attempt in 1..=resilience.max_reconnect_attempts {
+            thread::sleep(Duration::from_millis(resilience.reconnect_interval_ms));
+            if stop_flag.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            println!(
+                "[SppReader][线程] 尝试重新连接 {} (第{}次)",
+                port_name, attempt
+            );
+            match platform::connect(target, read_timeout) {
+                Ok(socket) => return Ok(Some(socket)),
+                Err(e) => eprintln!("[SppReader][线程] 重新连接失败: {}", e),
+            }
+        }
+        Err(format!(
+            "蓝牙设备 {} 已断开，{}次重新连接均失败",
+            port_name, resilience.max_reconnect_attempts
+        ))
+    }
+
+    pub fn start(&self) -> Result<(), String> {
+        let read_timeout = Duration::from_millis(self.resilience.read_timeout_ms);
+        let socket = platform::connect(&self.target, read_timeout)?;
+        let writer = socket
+            .try_clone()
+            .map_err(|e| format!("无法复制蓝牙连接: {}", e))?;
+        *self.writer.lock().unwrap() = Some(writer);
+        println!("[SppReader] 已连接蓝牙设备 {}", self.port_name);
+
+        let port_name = self.port_name.clone();
+        let target = self.target.clone();
+        let resilience = self.resilience.clone();
+        let mut assembler = FrameAssembler::new(self.parser.clone());
+        let stop_flag = self.stop_flag.clone();
+        let data_queue = self.data_queue.clone();
+        let channel_registry = self.channel_registry.clone();
+        let counters = self.counters.clone();
+        let status = self.status.clone();
+        let writer_slot = self.writer.clone();
+        let heartbeat = self.heartbeat.clone();
+        heartbeat.beat();
+
+        thread::spawn(move || {
+            let mut socket = socket;
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            // 上一帧的序号，用于统计丢帧
+            let mut last_sequence: Option<u64> = None;
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                heartbeat.beat();
+                let result = match socket.read(&mut chunk) {
+                    Ok(0) => Err("蓝牙设备关闭了连接".to_string()),
+                    Ok(len) => Ok(len),
+                    Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                        Ok(0)
+                    }
+                    Err(e) => Err(e.to_string()),
+                };
+
+                let len = match result {
+                    Ok(len) => len,
+                    Err(_) if stop_flag.load(Ordering::Relaxed) => break,
+                    Err(message) => {
+                        eprintln!("[SppReader][线程] 蓝牙连接中断: {}", message);
+                        counters.record_error(SerialErrorKind::DeviceGone, &message);
+                        writer_slot.lock().unwrap().take();
+                        match Self::reconnect(&port_name, &target, &resilience, &stop_flag) {
+                            Ok(Some(new_socket)) => {
+                                *writer_slot.lock().unwrap() = new_socket.try_clone().ok();
+                                socket = new_socket;
+                                counters.record_reconnect();
+                                // 新连接从帧边界重新同步
+                                assembler.clear();
+                                last_sequence = None;
+                                println!("[SppReader][线程] 蓝牙设备重新连接成功");
+                            }
+                            Ok(None) => break,
+                            Err(e) => {
+                                eprintln!("[SppReader][线程] {}", e);
+                                *status.lock().unwrap() = SerialStatus::Error(e);
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+                };
+                if len == 0 {
+                    continue;
+                }
+
+                counters.record_bytes(len);
+                assembler.push(&chunk[..len]);
+                while let Some(unit) = assembler.next_frame() {
+                    match unit {
+                        AssembledFrame::Frame(frame) => {
+                            let registry = channel_registry.lock().unwrap();
+                            let parsed = assembler.decode(&frame, &registry);
+                            drop(registry);
+                            assembler.recycle(frame);
+                            deliver_frame(parsed, &counters, &mut last_sequence, &data_queue);
+                        }
+                        AssembledFrame::Discarded(len) => counters.record_resync(len),
+                    }
+                }
+            }
+            println!("[SppReader][线程] 读取线程安全退出");
+        });
+
+        Ok(())
+    }
+
+    /// 向蓝牙设备发送数据
+    pub fn send_data(&self, data: &str) -> Result<(), String> {
+        let mut writer = self.writer.lock().unwrap();
+        let socket = writer
+            .as_mut()
+            .ok_or_else(|| "蓝牙设备未连接".to_string())?;
+        socket
+            .write_all(data.as_bytes())
+            .map_err(|e| format!("发送数据失败: {}", e))
+    }
+
+    pub fn stop(&self) {
+        println!("[SppReader] 停止信号已发出");
+        self.stop_flag.store(true, Ordering::Relaxed);
+        self.writer.lock().unwrap().take();
+    }
+}
@@ -0,0 +1,231 @@
+//! 模拟数据源压力测试模块
+//!
+//! 压力测试模式下模拟数据源不再以250 Hz生成真实心电，而是生成频率扫描、幅度大幅变化的
+//! 心电和呼吸波形，采样率从起始速率按台阶逐级升高到最高速率（最高可达数kHz），用于检验
+//! 原始数据队列、LTTB压缩和前端图表取数在高采样率下的表现。
+//!
+//! 每个台阶记录生成的采样数和数据处理器丢弃的采样数，报告第一次出现丢弃时的采样率。
+//! 丢弃数由看门狗每秒检查一次，计入检查时所在的台阶，台阶切换后约1秒内的丢弃可能
+//! 计入下一台阶，因此台阶时长不少于2秒。
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+use std::sync::Mutex;
+
+/// 采样率范围（Hz）
+const RATE_RANGE_HZ: (u32, u32) = (250, 10_000);
+
+/// 台阶时长范围（秒）
+const STEP_SECS_RANGE: (u64, u64) = (2, 60);
+
+/// 心电波形的基线，与内置心电录制的等电位线相当
+const ECG_BASELINE: f64 = 124_000.0;
+
+/// 心电波形的最大振幅
+const ECG_AMPLITUDE: f64 = 40_000.0;
+
+/// 呼吸波形的基线
+const RESP_BASELINE: f64 = 50_000.0;
+
+/// 呼吸波形的最大振幅
+const RESP_AMPLITUDE: f64 = 20_000.0;
+
+/// 频率扫描的周期（秒），每个周期内频率从下限线性升到上限
+const SWEEP_PERIOD_SECS: f64 = 10.0;
+
+/// 扫描频率下限（Hz）
+const SWEEP_MIN_HZ: f64 = 0.5;
+
+/// 扫描频率上限（Hz），采样率较低时不超过采样率的1/4
+const SWEEP_MAX_HZ: f64 = 150.0;
+
+/// 幅度包络的频率（Hz），振幅在最大振幅的10%~100%之间变化
+const ENVELOPE_HZ: f64 = 0.2;
+
+/// 压力测试配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct StressConfig {
+    /// 起始采样率（Hz）
+    pub start_rate_hz: u32,
+    /// 最高采样率（Hz），达到后保持该速率
+    pub max_rate_hz: u32,
+    /// 每个台阶提高的采样率（Hz），为0时始终以起始采样率运行
+    pub step_hz: u32,
+    /// 每个台阶的时长（秒）
+    pub step_secs: u64,
+}
+
+impl Default for StressConfig {
+    fn default() -> Self {
+        Self {
+            start_rate_hz: 250,
+            max_rate_hz: 5_000,
+            step_hz: 250,
+            step_secs: 5,
+        }
+    }
+}
+
+impl StressConfig {
+    /// 启动后经过 `elapsed_secs` 秒时的采样率（Hz）
+    pub fn rate_at(&self, elapsed_secs: u64) -> u32 {
+        let steps = (elapsed_secs / self.step_secs).min(u32::MAX as u64) as u32;
+        self.start_rate_hz
+            .saturating_add(self.step_hz.saturating_mul(steps))
+            .min(self.max_rate_hz)
+    }
+}
+
+/// 验证压力测试配置
+pub fn validate_config(config: &StressConfig) -> Result<(), String> {
+    for rate in [config.start_rate_hz, config.max_rate_hz] {
+        if !(RATE_RANGE_HZ.0..=RATE_RANGE_HZ.1).contains(&rate) {
+            return Err(format!(
+                "压力测试采样率必须在{}到{} Hz之间",
+                RATE_RANGE_HZ.0, RATE_RANGE_HZ.1
+            ));
+        }
+    }
+    if config.start_rate_hz > config.max_rate_hz {
+        return Err("起始采样率不能高于最高采样率".to_string());
+    }
+    if !(STEP_SECS_RANGE.0..=STEP_SECS_RANGE.1).contains(&config.step_secs) {
+        return Err(format!(
+            "台阶时长必须在{}到{}秒之间",
+            STEP_SECS_RANGE.0, STEP_SECS_RANGE.1
+        ));
+    }
+    Ok(())
+}
+
+/// 生成频率扫描、幅度变化的心电和呼吸波形
+#[derive(Debug, Default)]
+pub struct SweepGenerator {
+    /// 从启动起经过的时间（秒）
+    elapsed: f64,
+    /// 扫描信号的相位（弧度）
+    phase: f64,
+}
+
+impl SweepGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 按当前采样率生成下一个采样
+    ///
+    /// # 返回值
+    /// 返回（心电采样值, 呼吸采样值）
+    pub fn next_sample(&mut self, rate_hz: u32, rng: &mut impl Rng) -> (i32, i32) {
+        let rate = rate_hz.max(1) as f64;
+        let max_hz = SWEEP_MAX_HZ.min(rate / 4.0).max(SWEEP_MIN_HZ);
+        let sweep = (self.elapsed / SWEEP_PERIOD_SECS).fract();
+        let frequency = SWEEP_MIN_HZ + (max_hz - SWEEP_MIN_HZ) * sweep;
+        self.phase = (self.phase + TAU * frequency / rate) % TAU;
+        let envelope = 0.55 + 0.45 * (TAU * ENVELOPE_HZ * self.elapsed).sin();
+        self.elapsed += 1.0 / rate;
+
+        let noise: f64 = rng.gen_range(-0.05..0.05);
+        let ecg = ECG_BASELINE + ECG_AMPLITUDE * (envelope * self.phase.sin() + noise);
+        // 呼吸波形用方波，每个扫描周期内幅度从0跳变到最大，检验压缩对阶跃的保留
+        let resp = RESP_BASELINE + RESP_AMPLITUDE * sweep * self.phase.sin().signum();
+        (ecg.round() as i32, resp.round() as i32)
+    }
+}
+
+/// 一个采样率台阶的统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct StressStep {
+    /// 采样率（Hz）
+    pub rate_hz: u32,
+    /// 台阶开始时间戳（毫秒）
+    pub started_at: u64,
+    /// 生成的采样数
+    pub generated: u64,
+    /// 数据处理器丢弃的采样数，包括反压策略丢弃和原始数据队列溢出
+    pub dropped: u64,
+}
+
+/// 压力测试报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct StressReport {
+    /// 压力测试是否正在运行
+    pub active: bool,
+    /// 当前采样率（Hz），未运行时为None
+    pub current_rate_hz: Option<u32>,
+    /// 第一次出现采样丢弃时的采样率（Hz），尚未丢弃时为None
+    pub first_drop_rate_hz: Option<u32>,
+    /// 各台阶的统计，按时间先后排列
+    pub steps: Vec<StressStep>,
+}
+
+/// 记录压力测试各台阶的统计，每次连接模拟数据源时清空
+#[derive(Debug, Default)]
+pub struct StressMonitor {
+    report: Mutex<StressReport>,
+}
+
+impl StressMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 清空上次压力测试的统计
+    pub fn reset(&self) {
+        *self.report.lock().unwrap() = StressReport::default();
+    }
+
+    /// 开始一个新的采样率台阶
+    pub fn begin_step(&self, rate_hz: u32, started_at: u64) {
+        println!("[StressSim] 采样率提高到 {} Hz", rate_hz);
+        let mut report = self.report.lock().unwrap();
+        report.active = true;
+        report.current_rate_hz = Some(rate_hz);
+        report.steps.push(StressStep {
+            rate_hz,
+            started_at,
+            generated: 0,
+            dropped: 0,
+        });
+    }
+
+    /// 记录当前台阶生成的采样数
+    pub fn record_generated(&self, count: u64) {
+        if let Some(step) = self.report.lock().unwrap().steps.last_mut() {
+            step.generated += count;
+        }
+    }
+
+    /// 记录数据处理器丢弃的采样数，压力测试未运行时忽略
+    pub fn record_dropped(&self, count: u64) {
+        let mut report = self.report.lock().unwrap();
+        if !report.active {
+            return;
+        }
+        let Some(step) = report.steps.last_mut() else {
+            return;
+        };
+        step.dropped += count;
+        let rate_hz = step.rate_hz;
+        if report.first_drop_rate_hz.is_none() {
+            println!("[StressSim] 采样率达到 {} Hz 时开始丢弃采样", rate_hz);
+            report.first_drop_rate_hz = Some(rate_hz);
+        }
+    }
+
+    /// 压力测试结束，保留各台阶的统计
+    pub fn finish(&self) {
+        let mut report = self.report.lock().unwrap();
+        report.active = false;
+        report.current_rate_hz = None;
+    }
+
+    /// 获取压力测试报告
+    pub fn report(&self) -> StressReport {
+        self.report.lock().unwrap().clone()
+    }
+}
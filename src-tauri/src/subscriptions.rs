@@ -0,0 +1,221 @@
+//! 按体征订阅模块
+//!
+//! `vital-signs-batch` 事件推送完整的处理后采样（含LTTB压缩波形），只显示一两个数值的
+//! 小组件不需要这么多数据。订阅时指定需要的体征和推送频率，后台线程按各订阅的频率
+//! 取最新的处理后采样，只把订阅的体征推送到该订阅专属的事件
+//! `vital-subscription-<订阅ID>`，没有新采样时不推送。
+
+use crate::types::ProcessedVitalSigns;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 订阅事件名前缀，完整事件名为前缀加订阅ID
+pub const EVENT_PREFIX: &str = "vital-subscription-";
+
+/// 推送频率范围（Hz）
+const RATE_RANGE_HZ: (u32, u32) = (1, 60);
+
+/// 同时存在的订阅数上限
+const MAX_SUBSCRIPTIONS: usize = 32;
+
+/// 可订阅的内置体征，扩展通道按通道ID订阅
+pub const BUILTIN_VITALS: &[&str] = &[
+    "ecg",
+    "heart_rate",
+    "heart_rate_instant",
+    "rr_interval",
+    "spo2",
+    "spo2_raw",
+    "temperature",
+    "respiration",
+    "respiration_rate",
+    "apnea",
+    "co2",
+    "etco2",
+    "co2_respiration_rate",
+    "glucose",
+    "artifact",
+];
+
+/// 订阅信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SubscriptionInfo {
+    /// 订阅ID
+    pub id: u64,
+    /// 订阅的体征
+    pub vitals: Vec<String>,
+    /// 推送频率（Hz）
+    pub rate_hz: u32,
+    /// 推送事件名
+    pub event: String,
+}
+
+/// 推送给一个订阅的体征数值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct VitalUpdate {
+    /// 订阅ID
+    pub subscription_id: u64,
+    /// 采样时间戳（毫秒）
+    pub timestamp: u64,
+    /// 订阅的体征数值，本采样中没有该体征时为None
+    pub values: BTreeMap<String, Option<f64>>,
+}
+
+/// 一个订阅的推送进度
+#[derive(Debug)]
+struct Subscription {
+    vitals: Vec<String>,
+    rate_hz: u32,
+    /// 上次推送的时间（毫秒）
+    last_sent_ms: u64,
+    /// 上次推送的采样时间戳，用于跳过没有新采样的推送
+    last_sample: Option<u64>,
+}
+
+/// 订阅管理
+#[derive(Debug, Default)]
+pub struct SubscriptionManager {
+    next_id: u64,
+    subscriptions: BTreeMap<u64, Subscription>,
+}
+
+impl SubscriptionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 新建订阅
+    ///
+    /// # 参数
+    /// * `vitals` - 订阅的体征，内置体征见 `BUILTIN_VITALS`，扩展通道用通道ID
+    /// * `rate_hz` - 推送频率（Hz）
+    /// * `extension_channels` - 当前已注册的扩展通道ID
+    ///
+    /// # 返回值
+    /// 成功时返回订阅ID
+    pub fn subscribe(
+        &mut self,
+        vitals: Vec<String>,
+        rate_hz: u32,
+        extension_channels: &[String],
+    ) -> Result<u64, String> {
+        if vitals.is_empty() {
+            return Err("至少订阅一项体征".to_string());
+        }
+        if let Some(unknown) = vitals
+            .iter()
+            .find(|v| !BUILTIN_VITALS.contains(&v.as_str()) && !extension_channels.contains(v))
+        {
+            return Err(format!("未知的体征: {}", unknown));
+        }
+        if !(RATE_RANGE_HZ.0..=RATE_RANGE_HZ.1).contains(&rate_hz) {
+            return Err(format!(
+                "推送频率必须在{}到{}Hz之间",
+                RATE_RANGE_HZ.0, RATE_RANGE_HZ.1
+            ));
+        }
+        if self.subscriptions.len() >= MAX_SUBSCRIPTIONS {
+            return Err(format!("订阅数已达上限{}", MAX_SUBSCRIPTIONS));
+        }
+        self.next_id += 1;
+        let id = self.next_id;
+        let mut seen = BTreeSet::new();
+        let mut vitals = vitals;
+        vitals.retain(|vital| seen.insert(vital.clone()));
+        self.subscriptions.insert(
+            id,
+            Subscription {
+                vitals,
+                rate_hz,
+                last_sent_ms: 0,
+                last_sample: None,
+            },
+        );
+        println!("[Subscriptions] 新建订阅 {}，频率{}Hz", id, rate_hz);
+        Ok(id)
+    }
+
+    /// 取消订阅
+    pub fn unsubscribe(&mut self, id: u64) -> Result<(), String> {
+        self.subscriptions
+            .remove(&id)
+            .map(|_| println!("[Subscriptions] 取消订阅 {}", id))
+            .ok_or_else(|| format!("订阅不存在: {}", id))
+    }
+
+    /// 获取所有订阅
+    pub fn list(&self) -> Vec<SubscriptionInfo> {
+        self.subscriptions
+            .iter()
+            .map(|(id, s)| SubscriptionInfo {
+                id: *id,
+                vitals: s.vitals.clone(),
+                rate_hz: s.rate_hz,
+                event: event_name(*id),
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    /// 为到达推送时间且有新采样的订阅生成推送内容
+    ///
+    /// # 参数
+    /// * `latest` - 最新的处理后采样
+    /// * `now_ms` - 当前时间戳（毫秒）
+    pub fn poll(&mut self, latest: &ProcessedVitalSigns, now_ms: u64) -> Vec<VitalUpdate> {
+        let mut updates = Vec::new();
+        for (id, subscription) in &mut self.subscriptions {
+            let interval_ms = 1000 / subscription.rate_hz as u64;
+            if now_ms.saturating_sub(subscription.last_sent_ms) < interval_ms
+                || subscription.last_sample == Some(latest.timestamp)
+            {
+                continue;
+            }
+            subscription.last_sent_ms = now_ms;
+            subscription.last_sample = Some(latest.timestamp);
+            updates.push(VitalUpdate {
+                subscription_id: *id,
+                timestamp: latest.timestamp,
+                values: subscription
+                    .vitals
+                    .iter()
+                    .map(|vital| (vital.clone(), vital_value(latest, vital)))
+                    .collect(),
+            });
+        }
+        updates
+    }
+}
+
+/// 订阅的推送事件名
+pub fn event_name(id: u64) -> String {
+    format!("{}{}", EVENT_PREFIX, id)
+}
+
+/// 取处理后采样中某项体征的数值
+fn vital_value(sample: &ProcessedVitalSigns, vital: &str) -> Option<f64> {
+    let flag = |value: bool| if value { 1.0 } else { 0.0 };
+    match vital {
+        "ecg" => Some(sample.ecg_normalized),
+        "heart_rate" => Some(sample.heart_rate),
+        "heart_rate_instant" => Some(sample.heart_rate_instant),
+        "rr_interval" => Some(sample.rr_interval),
+        "spo2" => sample.blood_oxygen,
+        "spo2_raw" => sample.blood_oxygen_raw,
+        "temperature" => sample.body_temperature,
+        "respiration" => sample.respiration_raw.map(f64::from),
+        "respiration_rate" => sample.respiration_rate,
+        "apnea" => Some(flag(sample.apnea)),
+        "co2" => sample.co2_waveform,
+        "etco2" => sample.etco2,
+        "co2_respiration_rate" => sample.co2_respiration_rate,
+        "glucose" => sample.glucose,
+        "artifact" => Some(flag(sample.artifact)),
+        channel => sample.channels.get(channel).copied(),
+    }
+}
@@ -0,0 +1,532 @@
+//! 远程串口（ser2net / RFC2217）数据源模块
+//!
+//! 通过TCP连接由ser2net等串口服务器共享的远程串口。raw模式下TCP流即为串口字节流；
+//! RFC2217模式下串口数据承载在Telnet协议中，连接建立后通过COM-PORT-OPTION
+//! 子协商设置远程串口的波特率和数据格式（8N1），并从数据流中去除Telnet命令。
+
+use crate::channels::ChannelRegistry;
+use crate::framing::{self, deliver_frame, AssembledFrame, FrameAssembler};
+use crate::serial_manager::StreamCounters;
+use crate::types::{
+    DataQueue, SerialErrorKind, SerialResilienceConfig, SerialStatus, TcpSerialConfig,
+};
+use crate::watchdog::Heartbeat;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 每次从TCP连接读取的最大字节数
+const READ_CHUNK_SIZE: usize = 1024;
+
+/// 等待远程端确认波特率的超时
+const NEGOTIATION_TIMEOUT: Duration = Duration::from_millis(3000);
+
+/// Telnet命令字节（RFC854）
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+/// Telnet选项
+const OPT_BINARY: u8 = 0;
+const OPT_SUPPRESS_GO_AHEAD: u8 = 3;
+const OPT_COM_PORT: u8 = 44;
+
+/// COM-PORT-OPTION子命令（RFC2217），远程端的应答为命令值加100
+const SET_BAUDRATE: u8 = 1;
+const SET_DATASIZE: u8 = 2;
+const SET_PARITY: u8 = 3;
+const SET_STOPSIZE: u8 = 4;
+const SERVER_RESPONSE_OFFSET: u8 = 100;
+
+/// 验证远程串口配置
+pub fn validate_config(config: &TcpSerialConfig) -> Result<(), String> {
+    if config.host.trim().is_empty() {
+        return Err("远程主机地址不能为空".to_string());
+    }
+    if config.port == 0 {
+        return Err("远程端口必须大于0".to_string());
+    }
+    Ok(())
+}
+
+/// Telnet数据流中的协议事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TelnetEvent {
+    /// 选项协商（`DO`/`DONT`/`WILL`/`WONT` 及选项）
+    Negotiation { command: u8, option: u8 },
+    /// 子协商内容（不含 `IAC SB` 和 `IAC SE`）
+    Subnegotiation(Vec<u8>),
+}
+
+/// Telnet解码器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TelnetState {
+    Data,
+    Iac,
+    Negotiation(u8),
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+/// Telnet解码器，把TCP字节流拆分为串口数据和协议事件
+///
+/// 命令可能跨越多次读取，解码状态在两次读取之间保留。
+#[derive(Debug)]
+struct TelnetDecoder {
+    state: TelnetState,
+    subnegotiation: Vec<u8>,
+}
+
+impl TelnetDecoder {
+    fn new() -> Self {
+        Self {
+            state: TelnetState::Data,
+            subnegotiation: Vec::new(),
+        }
+    }
+
+    /// 解码一段字节
+    ///
+    /// # 参数
+    /// * `bytes` - 从TCP连接读取的字节
+    /// * `data` - 输出的串口数据
+    /// * `events` - 输出的协议事件
+    fn feed(&mut self, bytes: &[u8], data: &mut Vec<u8>, events: &mut Vec<TelnetEvent>) {
+        for &byte in bytes {
+            self.state = match (self.state, byte) {
+                (TelnetState::Data, IAC) => TelnetState::Iac,
+                (TelnetState::Data, _) => {
+                    data.push(byte);
+                    TelnetState::Data
+                }
+                // IAC IAC 为转义的0xFF数据字节
+                (TelnetState::Iac, IAC) => {
+                    data.push(IAC);
+                    TelnetState::Data
+                }
+                (TelnetState::Iac, DO | DONT | WILL | WONT) => TelnetState::Negotiation(byte),
+                (TelnetState::Iac, SB) => {
+                    self.subnegotiation.clear();
+                    TelnetState::Subnegotiation
+                }
+                // NOP、GA等不影响数据的命令直接忽略
+                (TelnetState::Iac, _) => TelnetState::Data,
+                (TelnetState::Negotiation(command), option) => {
+                    events.push(TelnetEvent::Negotiation { command, option });
+                    TelnetState::Data
+                }
+                (TelnetState::Subnegotiation, IAC) => TelnetState::SubnegotiationIac,
+                (TelnetState::Subnegotiation, _) => {
+                    self.subnegotiation.push(byte);
+                    TelnetState::Subnegotiation
+                }
+                (TelnetState::SubnegotiationIac, SE) => {
+                    events.push(TelnetEvent::Subnegotiation(std::mem::take(
+                        &mut self.subnegotiation,
+                    )));
+                    TelnetState::Data
+                }
+                (TelnetState::SubnegotiationIac, IAC) => {
+                    self.subnegotiation.push(IAC);
+                    TelnetState::Subnegotiation
+                }
+                // 不完整的子协商，丢弃
+                (TelnetState::SubnegotiationIac, _) => TelnetState::Data,
+            };
+        }
+    }
+}
+
+/// 一条RFC2217连接的会话状态
+struct Rfc2217Session {
+    decoder: TelnetDecoder,
+    /// 远程端确认的波特率，尚未确认时为None
+    confirmed_baud_rate: Option<u32>,
+    /// 远程端拒绝了COM-PORT-OPTION
+    refused: bool,
+}
+
+impl Rfc2217Session {
+    fn new() -> Self {
+        Self {
+            decoder: TelnetDecoder::new(),
+            confirmed_baud_rate: None,
+            refused: false,
+        }
+    }
+
+    /// 解码一段字节并应答远程端的选项协商
+    ///
+    /// # 返回值
+    /// 返回其中的串口数据
+    fn process(&mut self, bytes: &[u8], stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(bytes.len());
+        let mut events = Vec::new();
+        self.decoder.feed(bytes, &mut data, &mut events);
+
+        for event in events {
+            match event {
+                TelnetEvent::Negotiation { command, option } => {
+                    if let Some(reply) = Self::negotiation_reply(command, option) {
+                        stream.write_all(&reply)?;
+                    }
+                    if command == DONT && option == OPT_COM_PORT {
+                        self.refused = true;
+                    }
+                }
+                TelnetEvent::Subnegotiation(payload) => {
+                    if let [OPT_COM_PORT, command, b0, b1, b2, b3] = payload[..] {
+                        if command == SET_BAUDRATE + SERVER_RESPONSE_OFFSET {
+                            self.confirmed_baud_rate = Some(u32::from_be_bytes([b0, b1, b2, b3]));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(data)
+    }
+
+    /// 对远程端选项协商的应答
+    ///
+    /// 本端在连接时已主动请求所需的选项，因此对这些选项的请求和确认不再应答，
+    /// 其他选项一律拒绝，避免协商循环。
+    fn negotiation_reply(command: u8, option: u8) -> Option<[u8; 3]> {
+        match (command, option) {
+            (DO, OPT_BINARY | OPT_COM_PORT) => None,
+            (DO, _) => Some([IAC, WONT, option]),
+            (WILL, OPT_BINARY | OPT_SUPPRESS_GO_AHEAD) => None,
+            (WILL, _) => Some([IAC, DONT, option]),
+            _ => None,
+        }
+    }
+
+    /// 构造连接建立后发送的协商请求：选项协商及8N1和波特率设置
+    fn negotiation_request(baud_rate: u32) -> Vec<u8> {
+        let mut request = Vec::new();
+        for (command, option) in [
+            (WILL, OPT_COM_PORT),
+            (WILL, OPT_BINARY),
+            (DO, OPT_BINARY),
+            (DO, OPT_SUPPRESS_GO_AHEAD),
+        ] {
+            request.extend_from_slice(&[IAC, command, option]);
+        }
+        let mut push_command = |command: u8, value: &[u8]| {
+            request.extend_from_slice(&[IAC, SB, OPT_COM_PORT, command]);
+            for &byte in value {
+                request.push(byte);
+                if byte == IAC {
+                    request.push(IAC);
+                }
+            }
+            request.extend_from_slice(&[IAC, SE]);
+        };
+        push_command(SET_BAUDRATE, &baud_rate.to_be_bytes());
+        push_command(SET_DATASIZE, &[8]);
+        push_command(SET_PARITY, &[1]); // 1 = 无校验
+        push_command(SET_STOPSIZE, &[1]); // 1 = 1位停止位
+        request
+    }
+}
+
+/// 建立的连接：TCP连接、RFC2217会话（raw模式为None）和协商期间收到的串口数据
+type Connection = (TcpStream, Option<Rfc2217Session>, Vec<u8>);
+
+/// 转义发送给RFC2217远程端的数据中的0xFF字节
+fn escape_iac(data: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(data.len());
+    for &byte in data {
+        escaped.push(byte);
+        if byte == IAC {
+            escaped.push(IAC);
+        }
+    }
+    escaped
+}
+
+pub struct TcpReader {
+    config: TcpSerialConfig,
+    baud_rate: u32,
+    data_queue: DataQueue,
+    channel_registry: Arc<Mutex<ChannelRegistry>>,
+    counters: Arc<StreamCounters>,
+    status: Arc<Mutex<SerialStatus>>,
+    resilience: SerialResilienceConfig,
+    /// 当前连接的写入端，用于发送数据，重新连接后替换
+    writer: Arc<Mutex<Option<TcpStream>>>,
+    stop_flag: Arc<AtomicBool>,
+    heartbeat: Heartbeat,
+}
+
+impl TcpReader {
+    pub fn new(
+        config: TcpSerialConfig,
+        baud_rate: u32,
+        data_queue: DataQueue,
+        channel_registry: Arc<Mutex<ChannelRegistry>>,
+        counters: Arc<StreamCounters>,
+        status: Arc<Mutex<SerialStatus>>,
+        resilience: SerialResilienceConfig,
+    ) -> Self {
+        println!(
+            "[TcpReader] 初始化，远程串口={}:{}，RFC2217={}",
+            config.host, config.port, config.rfc2217
+        );
+        Self {
+            config,
+            baud_rate,
+            data_queue,
+            channel_registry,
+            counters,
+            status,
+            resilience,
+            writer: Arc::new(Mutex::new(None)),
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            heartbeat: Heartbeat::new(),
+        }
+    }
+
+    /// 读取线程的心跳，每轮读取（包括超时）更新一次
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
+
+    /// 建立TCP连接，RFC2217模式下协商远程串口参数
+    ///
+    /// # 返回值
+    /// 返回连接和RFC2217会话，以及协商期间收到的串口数据
+    fn open(
+        config: &TcpSerialConfig,
+        baud_rate: u32,
+        resilience: &SerialResilienceConfig,
+    ) -> Result<Connection, String> {
+        let address = format!("{}:{}", config.host, config.port);
+        let timeout = Duration::from_millis(resilience.read_timeout_ms);
+        let socket_addr = address
+            .to_socket_addrs()
+            .map_err(|e| format!("无法解析远程地址 {}: {}", address, e))?
+            .next()
+            .ok_or_else(|| format!("无法解析远程地址 {}", address))?;
+        let mut stream = TcpStream::connect_timeout(&socket_addr, timeout)
+            .map_err(|e| format!("无法连接远程串口 {}: {}", address, e))?;
+        stream
+            .set_read_timeout(Some(timeout))
+            .map_err(|e| format!("设置读取超时失败: {}", e))?;
+        // 体征数据帧很小，关闭Nagle算法以降低延迟
+        let _ = stream.set_nodelay(true);
+
+        if !config.rfc2217 {
+            return Ok((stream, None, Vec::new()));
+        }
+
+        let mut session = Rfc2217Session::new();
+        stream
+            .write_all(&Rfc2217Session::negotiation_request(baud_rate))
+            .map_err(|e| format!("发送RFC2217协商请求失败: {}", e))?;
+
+        // 等待远程端确认波特率，期间收到的串口数据保留
+        let mut pending = Vec::new();
+        let mut chunk = [0u8; READ_CHUNK_SIZE];
+        let deadline = Instant::now() + NEGOTIATION_TIMEOUT;
+        while session.confirmed_baud_rate.is_none() && Instant::now() < deadline {
+            match stream.read(&mut chunk) {
+                Ok(0) => return Err("远程端在RFC2217协商期间关闭了连接".to_string()),
+                Ok(len) => {
+                    let data = session
+                        .process(&chunk[..len], &mut stream)
+                        .map_err(|e| format!("RFC2217协商失败: {}", e))?;
+                    pending.extend_from_slice(&data);
+                }
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                Err(e) => return Err(format!("RFC2217协商失败: {}", e)),
+            }
+            if session.refused {
+                return Err(format!("远程端 {} 不支持RFC2217串口控制", address));
+            }
+        }
+
+        match session.confirmed_baud_rate {
+            Some(confirmed) if confirmed != baud_rate => {
+                println!(
+                    "[TcpReader] 远程端将波特率设置为{}（请求{}）",
+                    confirmed, baud_rate
+                );
+            }
+            Some(confirmed) => println!("[TcpReader] 远程串口波特率已设置为{}", confirmed),
+            None => println!("[TcpReader] 远程端未确认波特率，按请求值{}继续", baud_rate),
+        }
+        Ok((stream, Some(session), pending))
+    }
+
+    /// 按错误恢复策略重新建立连接
+    ///
+    /// # 返回值
+    /// 重新连接成功时返回新连接，停止信号到达时返回None
+    fn reconnect(
+        config: &TcpSerialConfig,
+        baud_rate: u32,
+        resilience: &SerialResilienceConfig,
+        stop_flag: &AtomicBool,
+    ) -> Result<Option<Connection>, String> {
+        for attempt in 1..=resilience.max_reconnect_attempts {
+            thread::sleep(Duration::from_millis(resilience.reconnect_interval_ms));
+            if stop_flag.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            println!(
+                "[TcpReader][线程] 尝试重新连接 {}:{} (第{}次)",
+                config.host, config.port, attempt
+            );
+            match Self::open(config, baud_rate, resilience) {
+                Ok(connection) => return Ok(Some(connection)),
+                Err(e) => eprintln!("[TcpReader][线程] 重新连接失败: {}", e),
+            }
+        }
+        Err(format!(
+            "远程串口 {}:{} 已断开，{}次重新连接均失败",
+            config.host, config.port, resilience.max_reconnect_attempts
+        ))
+    }
+
+    pub fn start(&self) -> Result<(), String> {
+        validate_config(&self.config)?;
+        let (stream, session, pending) =
+            Self::open(&self.config, self.baud_rate, &self.resilience)?;
+        let writer = stream
+            .try_clone()
+            .map_err(|e| format!("无法复制TCP连接: {}", e))?;
+        *self.writer.lock().unwrap() = Some(writer);
+        println!(
+            "[TcpReader] 已连接远程串口 {}:{}",
+            self.config.host, self.config.port
+        );
+
+        let config = self.config.clone();
+        let baud_rate = self.baud_rate;
+        let resilience = self.resilience.clone();
+        let mut assembler = FrameAssembler::new(framing::create_parser(config.parser));
+        let stop_flag = self.stop_flag.clone();
+        let data_queue = self.data_queue.clone();
+        let channel_registry = self.channel_registry.clone();
+        let counters = self.counters.clone();
+        let status = self.status.clone();
+        let writer_slot = self.writer.clone();
+        let heartbeat = self.heartbeat.clone();
+        heartbeat.beat();
+
+        thread::spawn(move || {
+            let mut stream = stream;
+            let mut session = session;
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            let mut received = pending;
+            // 上一帧的序号，用于统计丢帧
+            let mut last_sequence: Option<u64> = None;
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                heartbeat.beat();
+                let result = match stream.read(&mut chunk) {
+                    Ok(0) => Err("远程端关闭了连接".to_string()),
+                    Ok(len) => {
+                        counters.record_bytes(len);
+                        match session.as_mut() {
+                            Some(session) => session
+                                .process(&chunk[..len], &mut stream)
+                                .map(|data| received.extend_from_slice(&data))
+                                .map_err(|e| e.to_string()),
+                            None => {
+                                received.extend_from_slice(&chunk[..len]);
+                                Ok(())
+                            }
+                        }
+                    }
+                    Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                        Ok(())
+                    }
+                    Err(e) => Err(e.to_string()),
+                };
+
+                if let Err(message) = result {
+                    if stop_flag.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    eprintln!("[TcpReader][线程] 远程串口连接中断: {}", message);
+                    counters.record_error(SerialErrorKind::DeviceGone, &message);
+                    if let Some(writer) = writer_slot.lock().unwrap().take() {
+                        let _ = writer.shutdown(Shutdown::Both);
+                    }
+                    match Self::reconnect(&config, baud_rate, &resilience, &stop_flag) {
+                        Ok(Some((new_stream, new_session, new_pending))) => {
+                            *writer_slot.lock().unwrap() = new_stream.try_clone().ok();
+                            stream = new_stream;
+                            session = new_session;
+                            counters.record_reconnect();
+                            // 新连接从帧边界重新同步
+                            assembler.clear();
+                            received = new_pending;
+                            last_sequence = None;
+                            println!("[TcpReader][线程] 远程串口重新连接成功");
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            eprintln!("[TcpReader][线程] {}", e);
+                            *status.lock().unwrap() = SerialStatus::Error(e);
+                            break;
+                        }
+                    }
+                }
+
+                if received.is_empty() {
+                    continue;
+                }
+                assembler.push(&received);
+                received.clear();
+                while let Some(unit) = assembler.next_frame() {
+                    match unit {
+                        AssembledFrame::Frame(frame) => {
+                            let registry = channel_registry.lock().unwrap();
+                            let parsed = assembler.decode(&frame, &registry);
+                            drop(registry);
+                            assembler.recycle(frame);
+                            deliver_frame(parsed, &counters, &mut last_sequence, &data_queue);
+                        }
+                        AssembledFrame::Discarded(len) => counters.record_resync(len),
+                    }
+                }
+            }
+            println!("[TcpReader][线程] 读取线程安全退出");
+        });
+
+        Ok(())
+    }
+
+    /// 向远程串口发送数据
+    pub fn send_data(&self, data: &str) -> Result<(), String> {
+        let mut writer = self.writer.lock().unwrap();
+        let stream = writer
+            .as_mut()
+            .ok_or_else(|| "远程串口未连接".to_string())?;
+        let bytes = if self.config.rfc2217 {
+            escape_iac(data.as_bytes())
+        } else {
+            data.as_bytes().to_vec()
+        };
+        stream
+            .write_all(&bytes)
+            .map_err(|e| format!("发送数据失败: {}", e))
+    }
+
+    pub fn stop(&self) {
+        println!("[TcpReader] 停止信号已发出");
+        self.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(writer) = self.writer.lock().unwrap().take() {
+            let _ = writer.shutdown(Shutdown::Both);
+        }
+    }
+}
@@ -0,0 +1,495 @@
+//! 云端遥测模块
+//!
+//! 把体征和设备状态打包成批，通过MQTT over TLS（设备证书认证）上报到AWS IoT Core或
+//! Azure IoT Hub，用于远程患者的设备连接后端。每批先写入本地发件箱目录再上传，
+//! 收到服务端确认（QoS 1 PUBACK）后删除；离线期间的批次保留在发件箱中，
+//! 恢复连接后按时间顺序补发，发件箱超过容量时删除最早的批次。
+//! 重连时同一批次可能重复上传，服务端按 `batch_id` 去重。
+//!
+//! MQTT客户端需要用 `--features cloud` 编译；未启用时启动上报返回错误。
+
+use crate::types::ProcessedVitalSigns;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// 云平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloudProvider {
+    /// AWS IoT Core，客户端ID为物品名称
+    Aws,
+    /// Azure IoT Hub，客户端ID为设备ID，用户名为 `<hub>/<设备ID>/?api-version=...`
+    Azure,
+}
+
+/// 云端遥测配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TelemetryConfig {
+    pub provider: CloudProvider,
+    /// 服务端点主机名，例如 `xxxx-ats.iot.cn-north-1.amazonaws.com.cn` 或 `myhub.azure-devices.net`
+    pub endpoint: String,
+    /// 设备ID（AWS物品名称或Azure设备ID）
+    pub device_id: String,
+    /// 服务端CA证书（PEM）路径
+    pub ca_cert_path: String,
+    /// 设备证书（PEM）路径
+    pub cert_path: String,
+    /// 设备私钥（PEM）路径
+    pub key_path: String,
+    /// 上报主题，省略时AWS使用 `vitals/<设备ID>/telemetry`，Azure使用设备到云消息主题
+    #[serde(default)]
+    pub topic: Option<String>,
+    /// 患者ID，写入每批数据，省略时使用当前患者
+    #[serde(default)]
+    pub patient_id: Option<String>,
+    /// 批次中体征采样的间隔（毫秒）
+    #[serde(default = "default_sample_interval_ms")]
+    pub sample_interval_ms: u64,
+    /// 每批的时长（秒）
+    #[serde(default = "default_batch_secs")]
+    pub batch_secs: u64,
+    /// 发件箱最多保留的批次数
+    #[serde(default = "default_outbox_capacity")]
+    pub outbox_capacity: usize,
+}
+
+fn default_sample_interval_ms() -> u64 {
+    1000
+}
+
+fn default_batch_secs() -> u64 {
+    60
+}
+
+fn default_outbox_capacity() -> usize {
+    10_000
+}
+
+impl TelemetryConfig {
+    /// 上报主题
+    fn topic(&self) -> String {
+        self.topic.clone().unwrap_or_else(|| match self.provider {
+            CloudProvider::Aws => format!("vitals/{}/telemetry", self.device_id),
+            CloudProvider::Azure => format!("devices/{}/messages/events/", self.device_id),
+        })
+    }
+}
+
+/// 验证云端遥测配置
+pub fn validate_config(config: &TelemetryConfig) -> Result<(), String> {
+    if config.endpoint.trim().is_empty() {
+        return Err("服务端点不能为空".to_string());
+    }
+    if config.device_id.trim().is_empty() || config.device_id.contains(['/', '#', '+']) {
+        return Err("设备ID不能为空且不能包含/#+".to_string());
+    }
+    for path in [&config.ca_cert_path, &config.cert_path, &config.key_path] {
+        if !Path::new(path).is_file() {
+            return Err(format!("证书文件不存在: {}", path));
+        }
+    }
+    if !(100..=60_000).contains(&config.sample_interval_ms) {
+        return Err("采样间隔必须在100到60000毫秒之间".to_string());
+    }
+    if !(5..=3600).contains(&config.batch_secs) {
+        return Err("批次时长必须在5到3600秒之间".to_string());
+    }
+    if !(10..=1_000_000).contains(&config.outbox_capacity) {
+        return Err("发件箱容量必须在10到1000000批之间".to_string());
+    }
+    Ok(())
+}
+
+/// 批次中的一个体征采样
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetrySample {
+    pub timestamp: u64,
+    pub heart_rate: f64,
+    pub spo2: Option<f64>,
+    pub temperature: Option<f64>,
+    pub respiration_rate: Option<f64>,
+    pub artifact: bool,
+}
+
+/// 设备状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceHealth {
+    /// 上报启动以来的时长（秒）
+    pub uptime_secs: u64,
+    /// 本批时间内处理的采样数
+    pub samples: u64,
+    /// 本批时间内有伪差的采样比例
+    pub artifact_ratio: f64,
+    /// 批次生成时发件箱中等待上传的批次数
+    pub outbox_pending: usize,
+}
+
+/// 一批遥测数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryBatch {
+    /// 批次ID，重复上传时用于去重
+    pub batch_id: String,
+    pub device_id: String,
+    pub patient_id: Option<String>,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub samples: Vec<TelemetrySample>,
+    pub health: DeviceHealth,
+}
+
+/// 上传统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TelemetryStats {
+    /// 是否已连接到服务端
+    pub connected: bool,
+    /// 已确认上传的批次数
+    pub uploaded: u64,
+    /// 发件箱中等待上传的批次数
+    pub pending: usize,
+    /// 发件箱溢出删除的批次数
+    pub dropped: u64,
+    /// 最近一次连接或上传失败的原因
+    pub last_error: Option<String>,
+}
+
+/// 云端遥测状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryStatus {
+    pub config: TelemetryConfig,
+    pub topic: String,
+    #[serde(flatten)]
+    pub stats: TelemetryStats,
+}
+
+/// 本地发件箱，每批一个JSON文件，文件名按生成时间排序
+#[cfg_attr(not(feature = "cloud"), allow(dead_code))]
+struct Outbox {
+    dir: PathBuf,
+    capacity: usize,
+}
+
+impl Outbox {
+    fn new(dir: PathBuf, capacity: usize) -> Result<Self, String> {
+        fs::create_dir_all(&dir).map_err(|e| format!("创建遥测发件箱失败: {}", e))?;
+        Ok(Self { dir, capacity })
+    }
+
+    /// 按时间顺序列出待上传的批次文件
+    fn pending(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+
+    /// 写入一批数据，超过容量时删除最早的批次
+    ///
+    /// # 返回值
+    /// 返回删除的批次数
+    #[cfg_attr(not(feature = "cloud"), allow(dead_code))]
+    fn store(&self, batch: &TelemetryBatch) -> Result<u64, String> {
+        let json = serde_json::to_vec(batch).map_err(|e| format!("序列化遥测数据失败: {}", e))?;
+        let path = self.dir.join(format!("{}.json", batch.batch_id));
+        fs::write(&path, json).map_err(|e| format!("写入遥测发件箱失败: {}", e))?;
+        let pending = self.pending();
+        let excess = pending.len().saturating_sub(self.capacity);
+        for old in &pending[..excess] {
+            let _ = fs::remove_file(old);
+        }
+        Ok(excess as u64)
+    }
+}
+
+/// 云端遥测上报，由处理线程在每个采样后调用 `push`
+///
+/// 批次在处理线程中生成后交给上传线程写入发件箱；上报被丢弃时通道关闭，
+/// 上传线程随之退出，未上传的批次保留在发件箱中，下次启动时补发。
+pub struct TelemetryUplink {
+    config: TelemetryConfig,
+    sender: Sender<TelemetryBatch>,
+    stats: Arc<Mutex<TelemetryStats>>,
+    started_at: u64,
+    batch_start: Option<u64>,
+    last_sample: Option<u64>,
+    samples: Vec<TelemetrySample>,
+    processed: u64,
+    artifacts: u64,
+}
+
+impl TelemetryUplink {
+    /// 启动上传线程
+    ///
+    /// # 参数
+    /// * `config` - 遥测配置
+    /// * `outbox_dir` - 本地发件箱目录
+    pub fn new(config: TelemetryConfig, outbox_dir: PathBuf) -> Result<Self, String> {
+        validate_config(&config)?;
+        let outbox = Outbox::new(outbox_dir, config.outbox_capacity)?;
+        let stats = Arc::new(Mutex::new(TelemetryStats {
+            pending: outbox.pending().len(),
+            ..TelemetryStats::default()
+        }));
+        let (sender, receiver) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::sync_channel(1);
+        let thread_config = config.clone();
+        let thread_stats = stats.clone();
+        thread::spawn(move || {
+            run_uplink(thread_config, outbox, receiver, thread_stats, ready_sender)
+        });
+        ready
+            .recv()
+            .map_err(|_| "遥测上传线程意外退出".to_string())??;
+        println!(
+            "[Telemetry] 上报到 {:?} {}，主题={}",
+            config.provider,
+            config.endpoint,
+            config.topic()
+        );
+        Ok(Self {
+            config,
+            sender,
+            stats,
+            started_at: chrono::Utc::now().timestamp_millis().max(0) as u64,
+            batch_start: None,
+            last_sample: None,
+            samples: Vec::new(),
+            processed: 0,
+            artifacts: 0,
+        })
+    }
+
+    /// 处理一个采样，批次时长已满时生成一批数据
+    pub fn push(&mut self, processed: &ProcessedVitalSigns) {
+        let timestamp = processed.timestamp;
+        let batch_start = *self.batch_start.get_or_insert(timestamp);
+        self.processed += 1;
+        if processed.artifact {
+            self.artifacts += 1;
+        }
+        let due = self.last_sample.is_none_or(|last| {
+            timestamp < last || timestamp - last >= self.config.sample_interval_ms
+        });
+        if due {
+            self.last_sample = Some(timestamp);
+            self.samples.push(TelemetrySample {
+                timestamp,
+                heart_rate: processed.heart_rate,
+                spo2: processed.blood_oxygen,
+                temperature: processed.body_temperature,
+                respiration_rate: processed.respiration_rate,
+                artifact: processed.artifact,
+            });
+        }
+        if timestamp >= batch_start && timestamp - batch_start < self.config.batch_secs * 1000 {
+            return;
+        }
+
+        let batch = TelemetryBatch {
+            batch_id: format!(
+                "{}-{:013}-{:08x}",
+                self.config.device_id,
+                batch_start,
+                rand::random::<u32>()
+            ),
+            device_id: self.config.device_id.clone(),
+            patient_id: self.config.patient_id.clone(),
+            start_timestamp: batch_start,
+            end_timestamp: timestamp,
+            samples: std::mem::take(&mut self.samples),
+            health: DeviceHealth {
+                uptime_secs: timestamp.saturating_sub(self.started_at) / 1000,
+                samples: self.processed,
+                artifact_ratio: self.artifacts as f64 / self.processed as f64,
+                outbox_pending: self.stats.lock().unwrap().pending,
+            },
+        };
+        self.batch_start = None;
+        self.processed = 0;
+        self.artifacts = 0;
+        let _ = self.sender.send(batch);
+    }
+
+    /// 获取上报状态
+    pub fn status(&self) -> TelemetryStatus {
+        TelemetryStatus {
+            config: self.config.clone(),
+            topic: self.config.topic(),
+            stats: self.stats.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// 读取证书文件
+#[cfg(feature = "cloud")]
+fn read_pem(path: &str) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("读取证书文件 {} 失败: {}", path, e))
+}
+
+/// 上传线程：把新批次写入发件箱，连接服务端后按顺序上传发件箱中的批次
+#[cfg(feature = "cloud")]
+fn run_uplink(
+    config: TelemetryConfig,
+    outbox: Outbox,
+    batches: Receiver<TelemetryBatch>,
+    stats: Arc<Mutex<TelemetryStats>>,
+    ready: SyncSender<Result<(), String>>,
+) {
+    use rumqttc::{Client, Event, MqttOptions, Outgoing, Packet, QoS, Transport};
+    use std::collections::{HashMap, HashSet, VecDeque};
+    use std::sync::mpsc::TryRecvError;
+    use std::time::Duration;
+
+    /// MQTT over TLS的标准端口
+    const MQTT_TLS_PORT: u16 = 8883;
+    /// 同时等待确认的最大批次数
+    const MAX_IN_FLIGHT: usize = 8;
+    /// 断线后重连前的等待时长
+    const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+    let transport = (|| {
+        let ca = read_pem(&config.ca_cert_path)?;
+        let cert = read_pem(&config.cert_path)?;
+        let key = read_pem(&config.key_path)?;
+        Ok::<_, String>(Transport::tls(ca, Some((cert, key)), None))
+    })();
+    let transport = match transport {
+        Ok(transport) => {
+            let _ = ready.send(Ok(()));
+            transport
+        }
+        Err(e) => {
+            let _ = ready.send(Err(e));
+            return;
+        }
+    };
+
+    let mut options = MqttOptions::new(&config.device_id, &config.endpoint, MQTT_TLS_PORT);
+    options.set_keep_alive(Duration::from_secs(60));
+    options.set_transport(transport);
+    if config.provider == CloudProvider::Azure {
+        let username = format!(
+            "{}/{}/?api-version=2021-04-12",
+            config.endpoint, config.device_id
+        );
+        options.set_credentials(username, "");
+    }
+    let topic = config.topic();
+    let (client, mut connection) = Client::new(options, MAX_IN_FLIGHT * 2);
+
+    let mut connected = false;
+    // 已提交发布、等待分配报文ID的批次，按提交顺序排列
+    let mut submitted: VecDeque<PathBuf> = VecDeque::new();
+    // 等待确认的批次，键为报文ID
+    let mut awaiting: HashMap<u16, PathBuf> = HashMap::new();
+    loop {
+        let mut dropped = 0;
+        loop {
+            match batches.try_recv() {
+                Ok(batch) => match outbox.store(&batch) {
+                    Ok(removed) => dropped += removed,
+                    Err(e) => eprintln!("[Telemetry][线程] {}", e),
+                },
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    println!("[Telemetry][线程] 上传线程安全退出");
+                    let _ = client.disconnect();
+                    return;
+                }
+            }
+        }
+
+        if connected && submitted.len() + awaiting.len() < MAX_IN_FLIGHT {
+            let busy: HashSet<&PathBuf> = submitted.iter().chain(awaiting.values()).collect();
+            let next: Vec<PathBuf> = outbox
+                .pending()
+                .into_iter()
+                .filter(|path| !busy.contains(path))
+                .take(MAX_IN_FLIGHT - submitted.len() - awaiting.len())
+                .collect();
+            for path in next {
+                let Ok(payload) = fs::read(&path) else {
+                    continue;
+                };
+                if client
+                    .try_publish(&topic, QoS::AtLeastOnce, false, payload)
+                    .is_err()
+                {
+                    break;
+                }
+                submitted.push_back(path);
+            }
+        }
+
+        let mut error = None;
+        let mut uploaded = 0;
+        match connection.recv_timeout(Duration::from_millis(200)) {
+            Ok(Ok(Event::Incoming(Packet::ConnAck(_)))) => {
+                println!("[Telemetry][线程] 已连接到 {}", config.endpoint);
+                connected = true;
+            }
+            Ok(Ok(Event::Outgoing(Outgoing::Publish(pkid)))) => {
+                if let Some(path) = submitted.pop_front() {
+                    awaiting.insert(pkid, path);
+                }
+            }
+            Ok(Ok(Event::Incoming(Packet::PubAck(ack)))) => {
+                if let Some(path) = awaiting.remove(&ack.pkid) {
+                    let _ = fs::remove_file(path);
+                    uploaded += 1;
+                }
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => error = Some(e.to_string()),
+            Err(_) => {}
+        }
+
+        let failed = error.is_some();
+        let mut stats = stats.lock().unwrap();
+        stats.uploaded += uploaded;
+        stats.dropped += dropped;
+        if let Some(e) = error {
+            if connected || stats.last_error.is_none() {
+                eprintln!("[Telemetry][线程] 连接断开: {}", e);
+            }
+            stats.last_error = Some(e);
+            connected = false;
+            // 未确认的批次仍在发件箱中，重连后重新上传
+            submitted.clear();
+            awaiting.clear();
+        }
+        stats.connected = connected;
+        stats.pending = outbox.pending().len();
+        drop(stats);
+        // 断线后等待一段时间再重连，期间新批次留在通道中
+        if failed {
+            thread::sleep(RECONNECT_DELAY);
+        }
+    }
+}
+
+/// 未启用云端遥测支持时无法连接
+#[cfg(not(feature = "cloud"))]
+fn run_uplink(
+    _config: TelemetryConfig,
+    _outbox: Outbox,
+    _batches: Receiver<TelemetryBatch>,
+    _stats: Arc<Mutex<TelemetryStats>>,
+    ready: SyncSender<Result<(), String>>,
+) {
+    let _ = ready.send(Err(
+        "当前版本未包含云端遥测支持，请使用 --features cloud 重新编译".to_string(),
+    ));
+}
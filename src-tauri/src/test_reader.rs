@@ -1,45 +1,97 @@
-use crate::types::{DataQueue, VitalSigns};
+use crate::arrhythmia_sim::{BeatGenerator, GroundTruth, GroundTruthBeat, SimulatorConfig};
+use crate::serial_manager::StreamCounters;
+use crate::stress_sim::{StressConfig, StressMonitor, SweepGenerator};
+use crate::types::{CuffStatus, DataQueue, NibpMeasurement, VitalSigns};
+use crate::watchdog::Heartbeat;
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::thread;
 use rand::Rng;
 
 
-const ECG_DATA: &[i32] = &[
+pub(crate) const ECG_DATA: &[i32] = &[
 127486, 127609, 127665, 127603, 127388, 127038, 126610, 126197, 125875, 125662, 125508, 125304, 124943, 124385, 123691, 123003, 122491, 122262, 122294, 122444, 122509, 122346, 121957, 121514, 121269, 121406, 121889, 122424, 122559, 121918, 120486, 118772, 117763, 118621, 122218, 128678, 137128, 145811, 152553, 155438, 153470, 146936, 137350, 126982, 118142, 112487, 110594, 111932, 115211, 118926, 121888, 123539, 123970, 123694, 123315, 123234, 123528, 124007, 124380, 124440, 124169, 123721, 123324, 123150, 123242, 123501, 123768, 123902, 123858, 123689, 123515, 123441, 123518, 123718, 123966, 124183, 124332, 124429, 124527, 124682, 124920, 125231, 125570, 125892, 126172, 126409, 126632, 126864, 127114, 127370, 127596, 127759, 127837, 127831, 127762, 127657, 127538, 127412, 127274, 127111, 126909, 126668, 126399, 126113, 125829, 125558, 125305, 125071, 124855, 124661, 124496, 124369, 124288, 124258, 124278, 124338, 124429, 124534, 124633, 124711, 124756, 124759, 124722, 124657, 124580, 124513, 124474, 124470, 124502, 124556, 124616, 124662, 124690, 124693, 124681, 124665, 124654, 124651, 124657, 124662, 124660, 124645, 124617, 124579, 124536, 124494, 124453, 124413, 124369, 124324, 124279, 124243, 124235, 124282, 124408, 124629, 124948, 125339, 125765, 126177, 126535, 126820, 127036, 127210, 127368, 127519, 127637, 127675, 127580, 127329, 126950, 126513, 126114, 125820, 125632, 125481, 125252, 124848, 124247, 123530, 122859, 122395, 122227, 122301, 122453, 122482, 122266, 121852, 121433, 121266, 121493, 122023, 122514, 122503, 121676, 120120, 118466, 117770, 119176, 123427, 130435, 139110, 147551, 153580, 155436, 152388, 145011, 135043, 124824, 116575, 111736, 110629, 112518, 116016, 119649, 122350, 123711, 123942, 123603, 123270, 123275, 123626, 124099, 124408, 124389, 124067, 123623, 123276, 123173, 123316, 123584, 123819, 123897, 123806, 123622, 123470, 123441, 123566, 123793, 124040, 124233, 124353, 124431, 124533, 124711, 124984, 125324, 125679, 125997, 126259,
 ];
 
 pub struct TestReader {
     data_queue: DataQueue,
+    counters: Arc<StreamCounters>,
+    config: SimulatorConfig,
+    ground_truth: Arc<GroundTruth>,
+    stress_monitor: Arc<StressMonitor>,
     stop_flag: Arc<AtomicBool>,
+    heartbeat: Heartbeat,
 }
 
 impl TestReader {
-    pub fn new(data_queue: DataQueue) -> Self {
-        println!("[TestReader] 初始化测试数据生成器（ECG 来自常量数组）");
+    pub fn new(
+        data_queue: DataQueue,
+        counters: Arc<StreamCounters>,
+        config: SimulatorConfig,
+        ground_truth: Arc<GroundTruth>,
+        stress_monitor: Arc<StressMonitor>,
+    ) -> Self {
+        match (&config.stress, config.scenario) {
+            (Some(stress), _) => println!(
+                "[TestReader] 初始化测试数据生成器（压力测试 {}~{} Hz）",
+                stress.start_rate_hz, stress.max_rate_hz
+            ),
+            (None, Some(scenario)) => println!(
+                "[TestReader] 初始化测试数据生成器（心律失常场景 {:?}）",
+                scenario
+            ),
+            (None, None) => println!("[TestReader] 初始化测试数据生成器（ECG 来自常量数组）"),
+        }
         Self {
             data_queue,
+            counters,
+            config,
+            ground_truth,
+            stress_monitor,
             stop_flag: Arc::new(AtomicBool::new(false)),
+            heartbeat: Heartbeat::new(),
         }
     }
 
+    /// 生成线程的心跳，每生成一个采样更新一次
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
+
     pub fn start(&self) -> Result<(), String> {
+        if let Some(stress) = self.config.stress.clone() {
+            return self.start_stress(stress);
+        }
         println!("[TestReader] 启动测试数据生成线程");
 
         let stop_flag = self.stop_flag.clone();
         let data_queue = self.data_queue.clone();
+        let heartbeat = self.heartbeat.clone();
+        let counters = self.counters.clone();
+        let mut generator = self.config.scenario.map(BeatGenerator::new);
+        let ground_truth = self.ground_truth.clone();
+        heartbeat.beat();
 
         thread::spawn(move || {
             println!("[TestReader][线程] 生成线程已启动 (250 Hz)");
 
             let mut rng = rand::thread_rng();
             let mut ecg_idx: usize = 0;
+            let mut sample_idx: u64 = 0;
 
             while !stop_flag.load(Ordering::SeqCst) {
                 // ---------- 1. 取 ECG 数据 ----------
-                let ecg = ECG_DATA[ecg_idx];
-                ecg_idx = (ecg_idx + 1) % ECG_DATA.len(); // 读到末尾就回到 0
+                // 选择了心律失常场景时按场景生成，R波采样的类别记为标注
+                let (ecg, beat) = match generator.as_mut() {
+                    Some(generator) => generator.next_sample(&mut rng),
+                    None => {
+                        let ecg = ECG_DATA[ecg_idx];
+                        ecg_idx = (ecg_idx + 1) % ECG_DATA.len(); // 读到末尾就回到 0
+                        (ecg, None)
+                    }
+                };
 
                 // ---------- 2. 生成其它生命体征 ----------
                 let spo2_float: f32 = rng.gen_range(95.0..=100.0);
@@ -48,26 +100,62 @@ impl TestReader {
                 let temp_float: f32 = rng.gen_range(45.0..=46.5);   // 正常体温
                 let temp: i32 = (temp_float * 10.0).round() as i32; // 36.8℃→368
 
-                let systolic = rng.gen_range(110..140);
-                let diastolic = rng.gen_range(70..90);
+                // 无创血压：启动时及之后每60秒完成一次测量
+                let blood_pressure = if sample_idx.is_multiple_of(15_000) {
+                    Some(NibpMeasurement {
+                        systolic: rng.gen_range(110..140),
+                        diastolic: rng.gen_range(70..90),
+                        cuff_status: CuffStatus::Ok,
+                    })
+                } else {
+                    None
+                };
+
+                // 呼吸波形：15次/分 (0.25Hz) 正弦 + 少量噪声
+                let phase = sample_idx as f64 / 250.0 * 0.25 * std::f64::consts::TAU;
+                let resp = 50_000 + (phase.sin() * 2_000.0) as i32 + rng.gen_range(-50..50);
+
+                // 二氧化碳波形：与呼吸同步，吸气相接近0，呼气相上升至约38mmHg平台
+                let cycle = (phase / std::f64::consts::TAU).fract();
+                let co2_mmhg = if cycle < 0.4 {
+                    0.5
+                } else {
+                    38.0 * (1.0 - (-(cycle - 0.4) * 30.0).exp())
+                };
+                let co2 = (co2_mmhg * 10.0).round() as i32 + rng.gen_range(-3..3);
+
+                // 血糖：每60秒上报一次测量结果
+                let glucose = if sample_idx % 15_000 == 14_999 {
+                    Some((rng.gen_range(4.5..7.5) * 10.0_f64).round() / 10.0)
+                } else {
+                    None
+                };
+                sample_idx += 1;
 
                 let vital_signs = VitalSigns {
                     ecg,
-                    spo2,
-                    temp,
-                    systolic,
-                    diastolic,
+                    spo2: Some(spo2),
+                    temp: Some(temp),
+                    blood_pressure,
+                    resp: Some(resp),
+                    co2: Some(co2),
+                    glucose,
+                    channels: BTreeMap::new(),
+                    received_at: chrono::Utc::now().timestamp_millis() as u64,
                 };
 
-                // ---------- 3. 推入队列 (带简单截断) ----------
-                {
-                    let mut q = data_queue.lock().unwrap();
-                    if q.len() >= 1_000 {
-                        q.pop_front();
-                    }
-                    q.push_back(vital_signs);
+                if let Some(classification) = beat {
+                    ground_truth.record(GroundTruthBeat {
+                        timestamp: vital_signs.received_at,
+                        classification,
+                    });
                 }
 
+                // ---------- 3. 推入队列 (队列满时丢弃) ----------
+                counters.record_accepted(&vital_signs);
+                data_queue.push(vital_signs);
+                heartbeat.beat();
+
                 // ---------- 4. 休眠 4 ms → 250 Hz ----------
                 thread::sleep(Duration::from_millis(4));
             }
@@ -78,6 +166,72 @@ impl TestReader {
         Ok(())
     }
 
+    /// 启动压力测试生成线程：每毫秒补齐按当前采样率应生成的采样，台阶时长到后提高采样率
+    fn start_stress(&self, stress: StressConfig) -> Result<(), String> {
+        println!("[TestReader] 启动压力测试生成线程");
+
+        let stop_flag = self.stop_flag.clone();
+        let data_queue = self.data_queue.clone();
+        let heartbeat = self.heartbeat.clone();
+        let counters = self.counters.clone();
+        let monitor = self.stress_monitor.clone();
+        heartbeat.beat();
+
+        thread::spawn(move || {
+            println!(
+                "[TestReader][线程] 压力测试线程已启动 ({}~{} Hz)",
+                stress.start_rate_hz, stress.max_rate_hz
+            );
+
+            let mut rng = rand::thread_rng();
+            let mut generator = SweepGenerator::new();
+            let started = Instant::now();
+            let mut rate_hz = 0;
+            let mut step_started = started;
+            let mut step_generated: u64 = 0;
+
+            while !stop_flag.load(Ordering::SeqCst) {
+                let rate = stress.rate_at(started.elapsed().as_secs());
+                if rate != rate_hz {
+                    rate_hz = rate;
+                    step_started = Instant::now();
+                    step_generated = 0;
+                    monitor.begin_step(rate_hz, chrono::Utc::now().timestamp_millis() as u64);
+                }
+
+                // 补齐从台阶开始到现在按当前采样率应生成的采样
+                let due = (step_started.elapsed().as_secs_f64() * rate_hz as f64) as u64;
+                let received_at = chrono::Utc::now().timestamp_millis() as u64;
+                for _ in step_generated..due {
+                    let (ecg, resp) = generator.next_sample(rate_hz, &mut rng);
+                    let vital_signs = VitalSigns {
+                        ecg,
+                        spo2: None,
+                        temp: None,
+                        blood_pressure: None,
+                        resp: Some(resp),
+                        co2: None,
+                        glucose: None,
+                        channels: BTreeMap::new(),
+                        received_at,
+                    };
+                    counters.record_accepted(&vital_signs);
+                    data_queue.push(vital_signs);
+                }
+                monitor.record_generated(due.saturating_sub(step_generated));
+                step_generated = step_generated.max(due);
+                heartbeat.beat();
+
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            monitor.finish();
+            println!("[TestReader][线程] 已收到停止信号，安全退出");
+        });
+
+        Ok(())
+    }
+
     pub fn stop(&self) {
         println!("[TestReader] 停止测试数据生成");
         self.stop_flag.store(true, Ordering::SeqCst);
@@ -0,0 +1,95 @@
+//! 线程优先级和CPU亲和性模块
+//!
+//! 低配床旁机上界面渲染或导出报告会抢占CPU，串口读取线程和数据处理线程来不及取数时
+//! 会丢采样。开启后这两个线程在启动时提高自身的调度优先级，并可各自绑定到指定的CPU核。
+//! 目前只在Linux上生效，提高优先级需要CAP_SYS_NICE权限；设置失败只记录警告，不影响采集。
+
+use crate::types::ThreadTuningConfig;
+
+/// 提高优先级时使用的nice值
+#[cfg(target_os = "linux")]
+const RAISED_NICENESS: i32 = -10;
+
+/// 需要调整的线程
+#[derive(Debug, Clone, Copy)]
+pub enum ThreadRole {
+    /// 串口读取线程
+    SerialReader,
+    /// 数据处理线程
+    Processor,
+}
+
+impl ThreadRole {
+    fn label(self) -> &'static str {
+        match self {
+            ThreadRole::SerialReader => "串口读取线程",
+            ThreadRole::Processor => "数据处理线程",
+        }
+    }
+}
+
+/// 验证线程调度配置
+pub fn validate_config(config: &ThreadTuningConfig) -> Result<(), String> {
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    for core in [config.reader_core, config.processor_core].into_iter().flatten() {
+        if core >= cores {
+            return Err(format!("CPU核编号必须小于{}", cores));
+        }
+    }
+    Ok(())
+}
+
+/// 按配置调整当前线程的优先级和CPU亲和性，在线程启动时调用
+pub fn apply(config: &ThreadTuningConfig, role: ThreadRole) {
+    let core = match role {
+        ThreadRole::SerialReader => config.reader_core,
+        ThreadRole::Processor => config.processor_core,
+    };
+    if config.raise_priority {
+        match raise_priority() {
+            Ok(()) => println!("[ThreadTuning] {}已提高优先级", role.label()),
+            Err(e) => eprintln!("[ThreadTuning] {}提高优先级失败: {}", role.label(), e),
+        }
+    }
+    if let Some(core) = core {
+        match pin_to_core(core) {
+            Ok(()) => println!("[ThreadTuning] {}已绑定到CPU核 {}", role.label(), core),
+            Err(e) => eprintln!("[ThreadTuning] {}绑定CPU核失败: {}", role.label(), e),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn raise_priority() -> Result<(), String> {
+    // Linux下线程有各自的nice值，以线程ID调用setpriority只影响当前线程
+    let tid = unsafe { libc::syscall(libc::SYS_gettid) } as libc::id_t;
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid, RAISED_NICENESS) } != 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn raise_priority() -> Result<(), String> {
+    Err("当前平台不支持".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn pin_to_core(core: usize) -> Result<(), String> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        // pid为0表示当前线程
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_to_core(_core: usize) -> Result<(), String> {
+    Err("当前平台不支持".to_string())
+}
@@ -0,0 +1,169 @@
+//! 提示性阈值模块
+//!
+//! 与报警无关，体征越过配置的分界值（例如心率区间）时记录一个轻量的事件，
+//! 供前端在趋势图上标注区间变化。事件保存在内存中，可按时间范围查询，
+//! 新事件同时进入待推送列表，由看门狗线程每秒取出推送。
+
+use crate::types::{CrossingDirection, ThresholdConfig, ThresholdCrossing};
+use std::collections::{BTreeMap, VecDeque};
+
+/// 最多保留的事件数量
+const EVENT_CAPACITY: usize = 2048;
+
+/// 默认的提示性阈值：心率60和100次/分
+pub fn default_thresholds() -> Vec<ThresholdConfig> {
+    vec![ThresholdConfig {
+        vital: "heart_rate".to_string(),
+        levels: vec![60.0, 100.0],
+        hysteresis: 2.0,
+    }]
+}
+
+/// 验证阈值配置
+pub fn validate_thresholds(configs: &[ThresholdConfig]) -> Result<(), String> {
+    for config in configs {
+        if config.levels.is_empty() {
+            return Err(format!("{} 的分界值不能为空", config.vital));
+        }
+        if config.levels.iter().any(|level| !level.is_finite()) {
+            return Err(format!("{} 的分界值无效", config.vital));
+        }
+        if config.levels.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(format!("{} 的分界值必须从小到大排列", config.vital));
+        }
+        if !config.hysteresis.is_finite() || config.hysteresis < 0.0 {
+            return Err(format!("{} 的回差不能为负数", config.vital));
+        }
+    }
+    Ok(())
+}
+
+/// 计算数值所在的区间序号，离开当前区间需要越过分界值超过回差
+fn zone_of(levels: &[f64], value: f64, current: Option<usize>, hysteresis: f64) -> usize {
+    let count_below = |offset: f64| {
+        levels
+            .iter()
+            .filter(|level| value >= **level + offset)
+            .count()
+    };
+    let raw = count_below(0.0);
+    match current {
+        Some(zone) if raw > zone => count_below(hysteresis).max(zone),
+        Some(zone) if raw < zone => count_below(-hysteresis).min(zone),
+        Some(zone) => zone,
+        None => raw,
+    }
+}
+
+/// 提示性阈值监测
+#[derive(Debug, Clone)]
+pub struct ThresholdMonitor {
+    /// 各体征的阈值配置，键为体征名
+    configs: BTreeMap<String, ThresholdConfig>,
+    /// 各体征当前所在的区间序号
+    zones: BTreeMap<String, usize>,
+    /// 按时间先后排列的事件
+    events: VecDeque<ThresholdCrossing>,
+    /// 尚未推送的事件
+    pending: Vec<ThresholdCrossing>,
+}
+
+impl Default for ThresholdMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThresholdMonitor {
+    pub fn new() -> Self {
+        let mut monitor = Self {
+            configs: BTreeMap::new(),
+            zones: BTreeMap::new(),
+            events: VecDeque::new(),
+            pending: Vec::new(),
+        };
+        monitor.set_configs(default_thresholds());
+        monitor
+    }
+
+    /// 替换阈值配置，各体征重新从下一个数值确定所在区间
+    pub fn set_configs(&mut self, configs: Vec<ThresholdConfig>) {
+        self.configs = configs
+            .into_iter()
+            .map(|config| (config.vital.clone(), config))
+            .collect();
+        self.zones.clear();
+    }
+
+    /// 获取阈值配置
+    pub fn configs(&self) -> Vec<ThresholdConfig> {
+        self.configs.values().cloned().collect()
+    }
+
+    /// 检查一个数值，进入新区间时记录事件
+    ///
+    /// # 参数
+    /// * `vital` - 体征名
+    /// * `value` - 数值
+    /// * `timestamp` - 时间戳（毫秒）
+    pub fn observe(&mut self, vital: &str, value: f64, timestamp: u64) {
+        let Some(config) = self.configs.get(vital) else {
+            return;
+        };
+        if !value.is_finite() {
+            return;
+        }
+        let current = self.zones.get(vital).copied();
+        let zone = zone_of(&config.levels, value, current, config.hysteresis);
+        match self.zones.get_mut(vital) {
+            Some(slot) => *slot = zone,
+            None => {
+                self.zones.insert(vital.to_string(), zone);
+            }
+        }
+        let Some(from_zone) = current.filter(|from| *from != zone) else {
+            return;
+        };
+
+        let (direction, threshold) = if zone > from_zone {
+            (CrossingDirection::Rising, config.levels[zone - 1])
+        } else {
+            (CrossingDirection::Falling, config.levels[zone])
+        };
+        let event = ThresholdCrossing {
+            vital: vital.to_string(),
+            timestamp,
+            value,
+            threshold,
+            direction,
+            from_zone,
+            to_zone: zone,
+        };
+        if self.events.len() >= EVENT_CAPACITY {
+            self.events.pop_front();
+        }
+        self.events.push_back(event.clone());
+        if self.pending.len() < EVENT_CAPACITY {
+            self.pending.push(event);
+        }
+    }
+
+    /// 查询时间范围内的事件
+    ///
+    /// # 参数
+    /// * `start` - 起始时间戳（毫秒，包含）
+    /// * `end` - 结束时间戳（毫秒，包含）
+    pub fn events(&self, start: Option<u64>, end: Option<u64>) -> Vec<ThresholdCrossing> {
+        self.events
+            .iter()
+            .filter(|e| start.is_none_or(|s| e.timestamp >= s))
+            .filter(|e| end.is_none_or(|t| e.timestamp <= t))
+            .cloned()
+            .collect()
+    }
+
+    /// 取出尚未推送的事件
+    pub fn take_pending(&mut self) -> Vec<ThresholdCrossing> {
+        std::mem::take(&mut self.pending)
+    }
+}
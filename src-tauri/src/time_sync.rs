@@ -0,0 +1,226 @@
+//! 时间同步状态模块
+//!
+//! 多台设备同时记录的研究需要按时间对齐各设备的数据。后台线程按配置的间隔向NTP服务器
+//! 发送SNTP请求，计算本机时钟相对服务器的偏差和往返时延，偏差超过阈值时发出警告。
+//! 这里只测量偏差，不修改系统时钟；保存会话时记录当时的偏差，供事后跨设备对齐。
+
+use serde::{Deserialize, Serialize};
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// NTP时间戳起点（1900年）与Unix时间起点之间的秒数
+const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
+/// SNTP报文长度
+const PACKET_LEN: usize = 48;
+
+/// 等待服务器响应的超时
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 查询间隔范围（秒）
+const INTERVAL_RANGE_SECS: (u64, u64) = (10, 86_400);
+
+/// 时间同步配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TimeSyncConfig {
+    /// 是否定期查询时钟偏差
+    pub enabled: bool,
+    /// NTP服务器地址，未指定端口时使用123
+    pub server: String,
+    /// 查询间隔（秒）
+    pub interval_secs: u64,
+    /// 偏差超过该值（毫秒）时发出警告
+    pub drift_threshold_ms: f64,
+}
+
+impl Default for TimeSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server: "pool.ntp.org".to_string(),
+            interval_secs: 300,
+            drift_threshold_ms: 100.0,
+        }
+    }
+}
+
+/// 时间同步状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TimeSyncStatus {
+    /// 最近一次查询的服务器
+    pub server: Option<String>,
+    /// 本机时钟相对服务器的偏差（毫秒），正数表示本机时钟偏慢，尚未查询成功时为None
+    pub offset_ms: Option<f64>,
+    /// 请求往返时延（毫秒）
+    pub round_trip_ms: Option<f64>,
+    /// 最近一次查询成功的时间（RFC 3339）
+    pub last_sync: Option<String>,
+    /// 最近一次查询失败的原因，查询成功后清除
+    pub last_error: Option<String>,
+    /// 偏差是否超过阈值
+    pub drift_exceeded: bool,
+}
+
+/// 时钟偏差超过阈值的警告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TimeSyncWarning {
+    pub server: String,
+    /// 本机时钟相对服务器的偏差（毫秒）
+    pub offset_ms: f64,
+    /// 警告阈值（毫秒）
+    pub threshold_ms: f64,
+    /// 时间戳（毫秒）
+    pub timestamp: u64,
+}
+
+/// 校验时间同步配置
+pub fn validate_config(config: &TimeSyncConfig) -> Result<(), String> {
+    if config.server.trim().is_empty() {
+        return Err("NTP服务器地址不能为空".to_string());
+    }
+    if !(INTERVAL_RANGE_SECS.0..=INTERVAL_RANGE_SECS.1).contains(&config.interval_secs) {
+        return Err(format!(
+            "查询间隔必须在{}到{}秒之间",
+            INTERVAL_RANGE_SECS.0, INTERVAL_RANGE_SECS.1
+        ));
+    }
+    if !config.drift_threshold_ms.is_finite() || config.drift_threshold_ms <= 0.0 {
+        return Err("偏差警告阈值必须大于0".to_string());
+    }
+    Ok(())
+}
+
+/// 时钟偏差监测，记录最近一次查询结果
+#[derive(Debug, Default)]
+pub struct TimeSync {
+    status: TimeSyncStatus,
+    /// 最近一次查询的时间戳（毫秒），不论成功与否
+    last_attempt_ms: Option<u64>,
+}
+
+impl TimeSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(&self) -> TimeSyncStatus {
+        self.status.clone()
+    }
+
+    /// 最近一次成功测得的偏差（毫秒）
+    pub fn offset_ms(&self) -> Option<f64> {
+        self.status.offset_ms
+    }
+
+    /// 按配置的间隔判断是否需要查询
+    pub fn is_due(&self, config: &TimeSyncConfig, now_ms: u64) -> bool {
+        config.enabled
+            && self
+                .last_attempt_ms
+                .is_none_or(|last| now_ms.saturating_sub(last) >= config.interval_secs * 1000)
+    }
+
+    /// 记录一次查询结果，偏差超过阈值时返回警告
+    pub fn record(
+        &mut self,
+        config: &TimeSyncConfig,
+        result: Result<(f64, f64), String>,
+        now_ms: u64,
+    ) -> Option<TimeSyncWarning> {
+        self.last_attempt_ms = Some(now_ms);
+        self.status.server = Some(config.server.clone());
+        let (offset_ms, round_trip_ms) = match result {
+            Ok(measurement) => measurement,
+            Err(e) => {
+                eprintln!("[TimeSync] 查询 {} 失败: {}", config.server, e);
+                self.status.last_error = Some(e);
+                return None;
+            }
+        };
+        self.status.offset_ms = Some(offset_ms);
+        self.status.round_trip_ms = Some(round_trip_ms);
+        self.status.last_sync = Some(chrono::Utc::now().to_rfc3339());
+        self.status.last_error = None;
+        self.status.drift_exceeded = offset_ms.abs() > config.drift_threshold_ms;
+        if !self.status.drift_exceeded {
+            return None;
+        }
+        Some(TimeSyncWarning {
+            server: config.server.clone(),
+            offset_ms,
+            threshold_ms: config.drift_threshold_ms,
+            timestamp: now_ms,
+        })
+    }
+}
+
+/// 向NTP服务器发送一次SNTP请求
+///
+/// # 返回值
+/// (本机时钟相对服务器的偏差 毫秒, 往返时延 毫秒)
+pub fn query_offset(server: &str) -> Result<(f64, f64), String> {
+    let address = if server.contains(':') {
+        server.to_string()
+    } else {
+        format!("{}:123", server)
+    };
+    let target = address
+        .to_socket_addrs()
+        .map_err(|e| format!("无法解析NTP服务器地址: {}", e))?
+        .next()
+        .ok_or_else(|| "无法解析NTP服务器地址".to_string())?;
+    let bind_addr = if target.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(bind_addr).map_err(|e| format!("创建套接字失败: {}", e))?;
+    socket
+        .set_read_timeout(Some(QUERY_TIMEOUT))
+        .map_err(|e| format!("设置超时失败: {}", e))?;
+
+    // LI=0，版本4，客户端模式
+    let mut request = [0u8; PACKET_LEN];
+    request[0] = 0x23;
+    let originate = ntp_now();
+    socket
+        .send_to(&request, target)
+        .map_err(|e| format!("发送请求失败: {}", e))?;
+    let mut response = [0u8; PACKET_LEN];
+    let (len, _) = socket
+        .recv_from(&mut response)
+        .map_err(|e| format!("等待响应失败: {}", e))?;
+    let destination = ntp_now();
+    if len < PACKET_LEN {
+        return Err("响应报文长度不足".to_string());
+    }
+    let mode = response[0] & 0x07;
+    let stratum = response[1];
+    if mode != 4 || stratum == 0 {
+        return Err("服务器拒绝请求或尚未同步".to_string());
+    }
+
+    let receive = read_timestamp(&response[32..40]);
+    let transmit = read_timestamp(&response[40..48]);
+    let offset = ((receive - originate) + (transmit - destination)) / 2.0;
+    let round_trip = (destination - originate) - (transmit - receive);
+    Ok((offset * 1000.0, round_trip.max(0.0) * 1000.0))
+}
+
+/// 当前时间的NTP时间戳（秒）
+fn ntp_now() -> f64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    (now.as_secs() + NTP_UNIX_OFFSET_SECS) as f64 + now.subsec_nanos() as f64 / 1e9
+}
+
+/// 解析64位NTP时间戳（秒）
+fn read_timestamp(bytes: &[u8]) -> f64 {
+    let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    seconds as f64 + fraction as f64 / 4_294_967_296.0
+}
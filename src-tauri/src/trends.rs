@@ -0,0 +1,408 @@
+//! 趋势聚合模块
+//!
+//! 将各项体征数值按固定时间段（默认1分钟）聚合为最小/最大/平均/最新值，
+//! 连续体征每个采样累加一次，血压等间歇性测量每次测量累加一次，
+//! 供趋势图和报告使用，而不需要保存全部原始采样。
+
+use crate::types::{
+    ActivityState, HeartRateHistogram, HeartRateZone, HistogramBucket, HourlySummary, RollingStats,
+    TrendBin, ZoneTime,
+};
+use std::collections::{BTreeMap, VecDeque};
+
+/// 一小时的毫秒数
+const HOUR_MS: u64 = 3_600_000;
+
+/// 滚动窗口中一秒内的数值汇总
+#[derive(Debug, Clone)]
+struct SecondBucket {
+    second: u64,
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: u64,
+}
+
+/// 单项体征的滚动窗口
+#[derive(Debug, Clone, Default)]
+struct RollingWindow {
+    /// 按秒汇总的数值，窗口很长时也只需保存窗口秒数个汇总
+    buckets: VecDeque<SecondBucket>,
+    last: f64,
+}
+
+/// 滚动统计：最近一段时间（默认5分钟）内各项体征的最小/最大/平均值
+///
+/// 窗口按最新数值的时间戳滑动，离线重新处理时同样适用。
+#[derive(Debug, Clone)]
+pub struct RollingStatistics {
+    /// 窗口时长（毫秒）
+    window_ms: u64,
+    windows: BTreeMap<String, RollingWindow>,
+}
+
+impl RollingStatistics {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms: window_ms.max(1000),
+            windows: BTreeMap::new(),
+        }
+    }
+
+    /// 窗口时长（毫秒）
+    pub fn window_ms(&self) -> u64 {
+        self.window_ms
+    }
+
+    /// 修改窗口时长，已有数值中超出新窗口的部分被丢弃
+    pub fn set_window_ms(&mut self, window_ms: u64) {
+        self.window_ms = window_ms.max(1000);
+        let window_secs = self.window_ms / 1000;
+        for window in self.windows.values_mut() {
+            if let Some(latest) = window.buckets.back().map(|b| b.second) {
+                while window
+                    .buckets
+                    .front()
+                    .is_some_and(|b| b.second + window_secs <= latest)
+                {
+                    window.buckets.pop_front();
+                }
+            }
+        }
+    }
+
+    /// 累加一个数值
+    ///
+    /// # 参数
+    /// * `vital` - 体征名
+    /// * `value` - 数值
+    /// * `timestamp` - 时间戳（毫秒）
+    pub fn add(&mut self, vital: &str, value: f64, timestamp: u64) {
+        if !value.is_finite() {
+            return;
+        }
+        let second = timestamp / 1000;
+        let window_secs = self.window_ms / 1000;
+        // 先按 `&str` 查找，只在第一次出现该体征时分配键
+        if !self.windows.contains_key(vital) {
+            self.windows
+                .insert(vital.to_string(), RollingWindow::default());
+        }
+        let window = self.windows.get_mut(vital).unwrap();
+        window.last = value;
+        match window.buckets.back_mut() {
+            Some(bucket) if bucket.second == second => {
+                bucket.min = bucket.min.min(value);
+                bucket.max = bucket.max.max(value);
+                bucket.sum += value;
+                bucket.count += 1;
+            }
+            _ => window.buckets.push_back(SecondBucket {
+                second,
+                min: value,
+                max: value,
+                sum: value,
+                count: 1,
+            }),
+        }
+        while window
+            .buckets
+            .front()
+            .is_some_and(|b| b.second + window_secs <= second)
+        {
+            window.buckets.pop_front();
+        }
+    }
+
+    /// 获取体征在窗口内的统计值，没有数值时返回None
+    pub fn get(&self, vital: &str) -> Option<RollingStats> {
+        let window = self.windows.get(vital)?;
+        let count: u64 = window.buckets.iter().map(|b| b.count).sum();
+        if count == 0 {
+            return None;
+        }
+        Some(RollingStats {
+            current: window.last,
+            min: window
+                .buckets
+                .iter()
+                .map(|b| b.min)
+                .fold(f64::INFINITY, f64::min),
+            max: window
+                .buckets
+                .iter()
+                .map(|b| b.max)
+                .fold(f64::NEG_INFINITY, f64::max),
+            mean: window.buckets.iter().map(|b| b.sum).sum::<f64>() / count as f64,
+            count,
+        })
+    }
+}
+
+/// 默认的心率区间
+pub fn default_heart_rate_zones() -> Vec<HeartRateZone> {
+    [
+        ("心动过缓", 0.0, 60.0),
+        ("正常", 60.0, 100.0),
+        ("心动过速", 100.0, 400.0),
+    ]
+    .into_iter()
+    .map(|(name, min, max)| HeartRateZone {
+        name: name.to_string(),
+        min,
+        max,
+    })
+    .collect()
+}
+
+/// 由心率趋势计算心率分布
+///
+/// 每个聚合段按平均值归入直方图柱和心率区间，按参与聚合的数值数量加权，
+/// 因此百分比即时间占比。
+///
+/// # 参数
+/// * `bins` - 心率趋势聚合段
+/// * `start` - 统计起始时间戳（毫秒）
+/// * `end` - 统计结束时间戳（毫秒）
+/// * `bin_width` - 直方图柱宽（次/分）
+/// * `zones` - 心率区间
+pub fn heart_rate_histogram(
+    bins: &[TrendBin],
+    start: u64,
+    end: u64,
+    bin_width: f64,
+    zones: Vec<HeartRateZone>,
+) -> HeartRateHistogram {
+    let total_count: u64 = bins.iter().map(|bin| bin.count).sum();
+    let percent = |count: u64| {
+        if total_count == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / total_count as f64
+        }
+    };
+
+    let mut counts: BTreeMap<i64, u64> = BTreeMap::new();
+    for bin in bins {
+        *counts
+            .entry((bin.mean / bin_width).floor() as i64)
+            .or_default() += bin.count;
+    }
+    let buckets = counts
+        .into_iter()
+        .map(|(index, count)| HistogramBucket {
+            lower: index as f64 * bin_width,
+            upper: (index + 1) as f64 * bin_width,
+            count,
+            percent: percent(count),
+        })
+        .collect();
+
+    let zones = zones
+        .into_iter()
+        .map(|zone| {
+            let count = bins
+                .iter()
+                .filter(|bin| bin.mean >= zone.min && bin.mean < zone.max)
+                .map(|bin| bin.count)
+                .sum();
+            ZoneTime {
+                zone,
+                count,
+                percent: percent(count),
+            }
+        })
+        .collect();
+
+    HeartRateHistogram {
+        start_timestamp: start,
+        end_timestamp: end,
+        bin_width,
+        total_count,
+        buckets,
+        zones,
+    }
+}
+
+/// 报警的体征是否对应趋势中的体征
+///
+/// 报警按测量项命名（例如 `blood_pressure`），趋势按具体数值命名（例如 `systolic`）。
+fn alarm_matches(alarm_vital: &str, trend_vital: &str) -> bool {
+    match alarm_vital {
+        "blood_pressure" => matches!(
+            trend_vital,
+            "systolic" | "diastolic" | "mean_arterial_pressure"
+        ),
+        "respiration" => trend_vital == "respiration_rate",
+        other => other == trend_vital,
+    }
+}
+
+/// 趋势聚合器
+#[derive(Debug, Clone)]
+pub struct TrendAggregator {
+    /// 每个聚合段的时长（毫秒）
+    bin_ms: u64,
+    /// 每项体征最多保留的聚合段数量
+    capacity: usize,
+    /// 各项体征的聚合段，键为体征名
+    series: BTreeMap<String, VecDeque<TrendBin>>,
+}
+
+impl TrendAggregator {
+    /// 创建趋势聚合器
+    ///
+    /// # 参数
+    /// * `bin_ms` - 每个聚合段的时长（毫秒）
+    /// * `capacity` - 每项体征最多保留的聚合段数量
+    pub fn new(bin_ms: u64, capacity: usize) -> Self {
+        Self {
+            bin_ms: bin_ms.max(1),
+            capacity: capacity.max(1),
+            series: BTreeMap::new(),
+        }
+    }
+
+    /// 累加一个数值
+    ///
+    /// # 参数
+    /// * `vital` - 体征名，例如 `heart_rate`
+    /// * `value` - 数值
+    /// * `timestamp` - 时间戳（毫秒）
+    pub fn add(&mut self, vital: &str, value: f64, timestamp: u64) {
+        if !value.is_finite() {
+            return;
+        }
+
+        let start = timestamp - timestamp % self.bin_ms;
+        if !self.series.contains_key(vital) {
+            self.series.insert(vital.to_string(), VecDeque::new());
+        }
+        let bins = self.series.get_mut(vital).unwrap();
+        match bins.back_mut() {
+            Some(bin) if bin.start_timestamp == start => {
+                bin.count += 1;
+                bin.min = bin.min.min(value);
+                bin.max = bin.max.max(value);
+                bin.mean += (value - bin.mean) / bin.count as f64;
+                bin.last = value;
+            }
+            _ => {
+                if bins.len() >= self.capacity {
+                    bins.pop_front();
+                }
+                bins.push_back(TrendBin {
+                    start_timestamp: start,
+                    end_timestamp: start + self.bin_ms,
+                    min: value,
+                    max: value,
+                    mean: value,
+                    last: value,
+                    count: 1,
+                    activity: None,
+                });
+            }
+        }
+    }
+
+    /// 获取体征在时间范围内的聚合段
+    ///
+    /// # 参数
+    /// * `vital` - 体征名
+    /// * `start` - 起始时间戳（毫秒，包含），与聚合段有重叠即返回
+    /// * `end` - 结束时间戳（毫秒，包含）
+    ///
+    /// # 返回值
+    /// 返回按时间先后排列的聚合段
+    pub fn get(&self, vital: &str, start: Option<u64>, end: Option<u64>) -> Vec<TrendBin> {
+        self.series
+            .get(vital)
+            .map(|bins| {
+                bins.iter()
+                    .filter(|b| start.is_none_or(|s| b.end_timestamp > s))
+                    .filter(|b| end.is_none_or(|e| b.start_timestamp <= e))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 为某个时间段内所有体征的聚合段标记休息/活动判定
+    ///
+    /// # 参数
+    /// * `start` - 聚合段起始时间戳（毫秒）
+    /// * `state` - 判定结果
+    pub fn tag_activity(&mut self, start: u64, state: ActivityState) {
+        for bins in self.series.values_mut() {
+            if let Some(bin) = bins.iter_mut().rev().find(|b| b.start_timestamp == start) {
+                bin.activity = Some(state);
+            }
+        }
+    }
+
+    /// 获取所有体征在时间范围内的每小时概览
+    ///
+    /// # 参数
+    /// * `start` - 起始时间戳（毫秒，包含）
+    /// * `end` - 结束时间戳（毫秒，包含）
+    /// * `alarms` - 时间范围内的报警，每项为体征名和报警开始时间戳
+    ///
+    /// # 返回值
+    /// 返回以体征名为键、按时间先后排列的每小时概览，没有数据的小时不返回
+    pub fn hourly_summary(
+        &self,
+        start: u64,
+        end: u64,
+        alarms: &[(String, u64)],
+    ) -> BTreeMap<String, Vec<HourlySummary>> {
+        let mut summary = BTreeMap::new();
+        for vital in self.series.keys() {
+            let mut hours: Vec<HourlySummary> = Vec::new();
+            for bin in self.get(vital, Some(start), Some(end)) {
+                let hour = bin.start_timestamp - bin.start_timestamp % HOUR_MS;
+                match hours.last_mut() {
+                    Some(summary) if summary.start_timestamp == hour => {
+                        let count = summary.count + bin.count;
+                        summary.min = summary.min.min(bin.min);
+                        summary.max = summary.max.max(bin.max);
+                        summary.mean = (summary.mean * summary.count as f64
+                            + bin.mean * bin.count as f64)
+                            / count as f64;
+                        summary.last = bin.last;
+                        summary.count = count;
+                    }
+                    _ => hours.push(HourlySummary {
+                        start_timestamp: hour,
+                        end_timestamp: hour + HOUR_MS,
+                        min: bin.min,
+                        max: bin.max,
+                        mean: bin.mean,
+                        last: bin.last,
+                        count: bin.count,
+                        alarm_count: 0,
+                    }),
+                }
+            }
+            for (alarm_vital, started_at) in alarms {
+                if !alarm_matches(alarm_vital, vital) {
+                    continue;
+                }
+                if let Some(hour) = hours
+                    .iter_mut()
+                    .find(|h| (h.start_timestamp..h.end_timestamp).contains(started_at))
+                {
+                    hour.alarm_count += 1;
+                }
+            }
+            if !hours.is_empty() {
+                summary.insert(vital.clone(), hours);
+            }
+        }
+        summary
+    }
+
+    /// 获取已有趋势数据的体征名
+    pub fn vitals(&self) -> Vec<String> {
+        self.series.keys().cloned().collect()
+    }
+}
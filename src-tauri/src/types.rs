@@ -1,33 +1,410 @@
+use crate::sample_ring::SampleRing;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::{Arc, Mutex};
 
 /// 数据源类型
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DataSourceType {
     /// 真实串口数据
     RealSerial,
     /// 测试模拟数据
     TestSimulation,
+    /// UDP（可为组播）网络数据
+    Udp,
+    /// 通过TCP连接的远程串口（ser2net / RFC2217）
+    RemoteSerial,
+    /// USB HID厂商协议设备
+    Hid,
+    /// 持续追加的文本或CSV记录文件
+    FileTail,
+    /// 回放已录制的原始数据文件
+    Playback,
+}
+
+/// 连接中切换数据源类型时推送的事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct DataSourceSwitchEvent {
+    /// 切换前的数据源类型
+    pub from: DataSourceType,
+    /// 切换后的数据源类型
+    pub to: DataSourceType,
+    /// 沿用的连接目标（串口名或地址）
+    pub port_name: String,
+    /// 新数据源是否已启动
+    pub success: bool,
+    /// 描述信息，启动失败时为失败原因
+    pub message: String,
+    /// 切换时间戳（毫秒）
+    pub timestamp: u64,
+}
+
+/// 帧解析器类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameParserKind {
+    /// 换行结尾的文本行协议
+    #[default]
+    Line,
+}
+
+/// UDP数据源配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct UdpConfig {
+    /// 本地绑定地址，例如 `0.0.0.0:5005`
+    pub bind_address: String,
+    /// 要加入的IPv4组播组，例如 `239.0.0.1`，单播或广播时为None
+    pub multicast_group: Option<String>,
+    /// 加入组播组使用的本地网卡地址，省略时由系统选择
+    #[serde(default)]
+    pub multicast_interface: Option<String>,
+    /// 数据报内容使用的帧解析器
+    #[serde(default)]
+    pub parser: FrameParserKind,
+}
+
+impl Default for UdpConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: "0.0.0.0:5005".to_string(),
+            multicast_group: None,
+            multicast_interface: None,
+            parser: FrameParserKind::Line,
+        }
+    }
+}
+
+/// 远程串口（ser2net / RFC2217）配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TcpSerialConfig {
+    /// 串口服务器的主机名或IP地址
+    pub host: String,
+    /// 串口服务器为该串口开放的TCP端口
+    pub port: u16,
+    /// 是否使用RFC2217协商远程串口的波特率，ser2net的raw模式为false，telnet模式为true
+    #[serde(default)]
+    pub rfc2217: bool,
+    /// 串口数据使用的帧解析器
+    #[serde(default)]
+    pub parser: FrameParserKind,
+}
+
+impl Default for TcpSerialConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 2000,
+            rfc2217: false,
+            parser: FrameParserKind::Line,
+        }
+    }
+}
+
+/// HID设备的数据报告类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HidReportMode {
+    /// 设备主动上报的输入报告
+    #[default]
+    Input,
+    /// 由主机定时读取的特征报告，设备应在每次被读取后清空已上报的数据
+    Feature,
+}
+
+/// USB HID数据源配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HidConfig {
+    /// 厂商ID
+    pub vendor_id: u16,
+    /// 产品ID
+    pub product_id: u16,
+    /// 设备序列号，同型号设备有多个时用于区分，省略时打开第一个匹配的设备
+    #[serde(default)]
+    pub serial_number: Option<String>,
+    /// 读取的报告类型
+    #[serde(default)]
+    pub report_mode: HidReportMode,
+    /// 报告ID，设备使用编号报告时必须设置，读取特征报告时省略视为0
+    #[serde(default)]
+    pub report_id: Option<u8>,
+    /// 报告数据的第一个字节是否为有效数据长度（与CP2110等HID串口桥相同），
+    /// 为false时去掉报告末尾的0填充
+    #[serde(default)]
+    pub length_prefixed: bool,
+    /// 读取特征报告的间隔（毫秒）
+    pub poll_interval_ms: u64,
+    /// 报告数据使用的帧解析器
+    #[serde(default)]
+    pub parser: FrameParserKind,
+}
+
+impl Default for HidConfig {
+    fn default() -> Self {
+        Self {
+            vendor_id: 0,
+            product_id: 0,
+            serial_number: None,
+            report_mode: HidReportMode::Input,
+            report_id: None,
+            length_prefixed: false,
+            poll_interval_ms: 10,
+            parser: FrameParserKind::Line,
+        }
+    }
+}
+
+/// 枚举到的HID设备
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HidDeviceInfo {
+    /// 厂商ID
+    pub vendor_id: u16,
+    /// 产品ID
+    pub product_id: u16,
+    /// 产品名称
+    pub product: Option<String>,
+    /// 厂商名称
+    pub manufacturer: Option<String>,
+    /// 序列号
+    pub serial_number: Option<String>,
+}
+
+/// 记录文件的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileTailFormat {
+    /// 与串口协议相同的文本行，例如 `A=123456,B=980,C=368`
+    #[default]
+    Line,
+    /// 分隔符分隔的表格，按列映射到通道
+    Csv,
+}
+
+/// 记录文件数据源配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct FileTailConfig {
+    /// 文件路径
+    pub path: String,
+    /// 文件格式
+    #[serde(default)]
+    pub format: FileTailFormat,
+    /// CSV列映射：通道ID到列（表头中的列名，或从0开始的列序号），
+    /// 必须包含 `ecg`、`spo2` 和 `temp`
+    #[serde(default)]
+    pub columns: BTreeMap<String, String>,
+    /// CSV第一行是否为表头
+    #[serde(default)]
+    pub has_header: bool,
+    /// CSV分隔符
+    pub delimiter: char,
+    /// 是否从文件开头读取已有内容，为false时只读取连接后追加的内容
+    #[serde(default)]
+    pub from_beginning: bool,
+    /// 检查文件新内容的间隔（毫秒）
+    pub poll_interval_ms: u64,
+}
+
+impl Default for FileTailConfig {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            format: FileTailFormat::Line,
+            columns: BTreeMap::new(),
+            has_header: false,
+            delimiter: ',',
+            from_beginning: false,
+            poll_interval_ms: 100,
+        }
+    }
+}
+
+/// 回放数据源配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct PlaybackConfig {
+    /// 录制文件路径，每行为一个串口协议采样，例如 `A=123456,B=980,C=368`
+    pub path: String,
+    /// 录制时的采样率（Hz），用于把行号换算为时间
+    pub sample_rate_hz: f64,
+    /// 回放速度倍数，1.0为实时
+    pub speed: f64,
+    /// 回放到末尾后是否从头开始
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+impl Default for PlaybackConfig {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            sample_rate_hz: 250.0,
+            speed: 1.0,
+            repeat: false,
+        }
+    }
+}
+
+/// 重新处理录制数据的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ReprocessConfig {
+    /// 原始数据录制文件路径，格式与回放数据源相同
+    pub recording_path: String,
+    /// 录制时的采样率（Hz），用于计算采样时间戳
+    pub sample_rate_hz: f64,
+    /// 第一个采样的时间戳（毫秒），省略时使用录制文件的修改时间倒推
+    #[serde(default)]
+    pub start_timestamp: Option<u64>,
+    /// 录制对应的原会话ID，记录在衍生会话中
+    #[serde(default)]
+    pub source_session_id: Option<String>,
+}
+
+/// 回放进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct PlaybackPosition {
+    /// 当前位置，距录制开始的毫秒数
+    pub position_ms: u64,
+    /// 录制总时长（毫秒）
+    pub duration_ms: u64,
+    /// 当前回放速度倍数
+    pub speed: f64,
+    /// 是否已回放到末尾
+    pub finished: bool,
 }
 
 /// 体征数据结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
 pub struct VitalSigns {
     /// 心电数据
     pub ecg: i32,
-    /// 血氧饱和度
-    pub spo2: i32,
-    /// 体温
-    pub temp: i32,
-    /// 收缩压(高压)
-    pub systolic: i32,
-    /// 舒张压(低压)
-    pub diastolic: i32,
+    /// 血氧饱和度，设备未上报该通道时为None
+    pub spo2: Option<i32>,
+    /// 体温，设备未上报该通道时为None
+    pub temp: Option<i32>,
+    /// 无创血压测量结果，仅在测量完成的采样中存在
+    pub blood_pressure: Option<NibpMeasurement>,
+    /// 阻抗呼吸波形，设备未上报该通道时为None
+    pub resp: Option<i32>,
+    /// 二氧化碳波形（0.1mmHg），设备未上报该通道时为None
+    pub co2: Option<i32>,
+    /// 血糖（mmol/L），间歇性测量，仅在设备上报新结果的采样中存在
+    pub glucose: Option<f64>,
+    /// 扩展通道数据，键为通道注册表中的通道ID
+    #[serde(default)]
+    pub channels: BTreeMap<String, f64>,
+    /// 采样放入原始数据队列的时间戳（毫秒），用于计算处理延迟，未知时为0
+    #[serde(default)]
+    pub received_at: u64,
+}
+
+impl VitalSigns {
+    /// 本采样中上报的通道ID（内置通道和扩展通道）
+    pub fn present_channels(&self) -> impl Iterator<Item = &str> {
+        [
+            ("ecg", true),
+            ("spo2", self.spo2.is_some()),
+            ("temp", self.temp.is_some()),
+            ("resp", self.resp.is_some()),
+            ("co2", self.co2.is_some()),
+            ("nibp", self.blood_pressure.is_some()),
+            ("glucose", self.glucose.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, present)| *present)
+        .map(|(id, _)| id)
+        .chain(self.channels.keys().map(String::as_str))
+    }
+}
+
+/// 通道类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChannelKind {
+    /// 连续波形
+    Waveform,
+    /// 数值
+    Numeric,
+}
+
+/// 处理流水线阶段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum PipelineStage {
+    /// 滑动平均滤波
+    MovingAverage { window: usize },
+    /// 一阶低通滤波，`alpha` 越小越平滑
+    LowPass { alpha: f64 },
+    /// 限幅
+    Clamp { min: f64, max: f64 },
+    /// 通道检测（心搏检测、呼吸检测、数值验证等）
+    Detection,
+    /// 衍生指标（仅心电通道：由心率和血压计算休克指数等风险指标），必须位于检测阶段之后
+    DerivedMetrics,
+    /// LTTB压缩（仅波形通道）
+    Compression,
+}
+
+/// 流水线中的一个阶段及其开关
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct PipelineStageConfig {
+    /// 阶段及其参数
+    pub stage: PipelineStage,
+    /// 是否启用
+    pub enabled: bool,
+}
+
+/// 单个通道的处理流水线配置，阶段按顺序执行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ChannelPipeline {
+    /// 通道ID
+    pub channel: String,
+    /// 有序的阶段列表
+    pub stages: Vec<PipelineStageConfig>,
+}
+
+/// 通道开关状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelStatus {
+    /// 打开
+    On,
+    /// 关闭
+    Off,
+}
+
+/// 通道描述（通道注册表中的元数据）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ChannelDescriptor {
+    /// 通道ID
+    pub id: String,
+    /// 显示名称
+    pub label: String,
+    /// 单位
+    pub unit: String,
+    /// 通道类型
+    pub kind: ChannelKind,
+    /// 采样率（Hz），间歇性测量为0
+    pub sample_rate_hz: f64,
+    /// 串口协议中的键，例如 `A=` 中的 `A`
+    pub protocol_key: String,
+    /// 是否为内置通道，内置通道解析到 `VitalSigns` 的固定字段
+    #[serde(default)]
+    pub builtin: bool,
 }
 
 /// LTTB数据点结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
 pub struct LttbDataPoint {
     /// 时间戳或索引
     pub x: f64,
@@ -37,6 +414,7 @@ pub struct LttbDataPoint {
 
 /// 处理后的体征数据（包含LTTB压缩数据）
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
 pub struct ProcessedVitalSigns {
     /// 原始心电数据
     pub ecg_raw: i32,
@@ -44,18 +422,1139 @@ pub struct ProcessedVitalSigns {
     pub ecg_normalized: f64,
     /// LTTB压缩后的ECG数据点
     pub ecg_lttb_compressed: Vec<LttbDataPoint>,
-    /// 处理后的体温
-    pub body_temperature: f64,
-    /// 血氧饱和度
-    pub blood_oxygen: f64,
-    /// 心率
+    /// 处理后的体温，设备未上报体温时为None
+    pub body_temperature: Option<f64>,
+    /// 血氧饱和度（经验证和滑动平均后的显示值），设备未上报或尚无有效读数时为None
+    pub blood_oxygen: Option<f64>,
+    /// 未经平均的血氧读数，本采样没有有效读数时为None
+    pub blood_oxygen_raw: Option<f64>,
+    /// 心率（按平均策略处理后的显示值）
     pub heart_rate: f64,
+    /// 瞬时心率（最近一次心搏计算的原始值）
+    pub heart_rate_instant: f64,
     /// RR间隔
     pub rr_interval: f64,
+    /// 原始呼吸波形值，设备未上报呼吸通道时为None
+    pub respiration_raw: Option<i32>,
+    /// 呼吸频率（次/分），设备未上报呼吸通道时为None
+    pub respiration_rate: Option<f64>,
+    /// 呼吸暂停标记
+    pub apnea: bool,
+    /// 二氧化碳波形值（mmHg），设备未上报二氧化碳通道时为None
+    pub co2_waveform: Option<f64>,
+    /// 呼气末二氧化碳分压EtCO2（mmHg），设备未上报二氧化碳通道时为None
+    pub etco2: Option<f64>,
+    /// 由二氧化碳波形计算的呼吸频率（次/分），设备未上报二氧化碳通道时为None
+    pub co2_respiration_rate: Option<f64>,
+    /// 本采样携带的血糖测量结果（mmol/L），无新测量时为None
+    pub glucose: Option<f64>,
+    /// 扩展通道处理结果，键为通道ID
+    pub channels: BTreeMap<String, f64>,
+    /// 衍生风险指标，心率或血压不可用时为None
+    pub derived_metrics: Option<DerivedMetrics>,
+    /// 伪差标记：ECG或血氧信号受运动/噪声干扰，此时心率和血氧保持上一有效值
+    pub artifact: bool,
     /// 时间戳
     pub timestamp: u64,
 }
 
+/// 体征当前值的质量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ValueQuality {
+    /// 读数有效且及时更新
+    Good,
+    /// 信号受伪差干扰，数值为伪差前的最后有效值
+    Artifact,
+    /// 超过有效期未收到新的读数
+    Stale,
+    /// 尚无有效读数
+    Unavailable,
+}
+
+/// 单项体征的当前值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct CurrentVitalValue {
+    /// 当前值，尚无有效读数时为None
+    pub value: Option<f64>,
+    /// 单位
+    pub unit: String,
+    /// 读数的采样时间戳（毫秒）
+    pub timestamp: Option<u64>,
+    /// 读数距今的时长（毫秒）
+    pub age_ms: Option<u64>,
+    /// 读数质量
+    pub quality: ValueQuality,
+}
+
+/// 无创血压袖带状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CuffStatus {
+    /// 测量成功
+    Ok,
+    /// 袖带松动或漏气
+    LooseCuff,
+    /// 测量过程中有运动干扰
+    MotionArtifact,
+    /// 袖带压力过高
+    Overpressure,
+    /// 测量超时
+    Timeout,
+    /// 其他错误
+    Error,
+}
+
+impl CuffStatus {
+    /// 由协议中的状态码转换，未知状态码视为错误
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            0 => CuffStatus::Ok,
+            1 => CuffStatus::LooseCuff,
+            2 => CuffStatus::MotionArtifact,
+            3 => CuffStatus::Overpressure,
+            4 => CuffStatus::Timeout,
+            _ => CuffStatus::Error,
+        }
+    }
+}
+
+/// 设备上报的一次无创血压测量
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct NibpMeasurement {
+    /// 收缩压（mmHg）
+    pub systolic: i32,
+    /// 舒张压（mmHg）
+    pub diastolic: i32,
+    /// 袖带状态
+    pub cuff_status: CuffStatus,
+}
+
+/// 无创血压测量记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct BloodPressureReading {
+    /// 收缩压（mmHg）
+    pub systolic: i32,
+    /// 舒张压（mmHg）
+    pub diastolic: i32,
+    /// 平均动脉压（mmHg）
+    pub mean_arterial_pressure: f64,
+    /// 袖带状态
+    pub cuff_status: CuffStatus,
+    /// 测量时间戳（毫秒）
+    pub timestamp: u64,
+}
+
+/// 会话标注类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnnotationKind {
+    /// 呼吸暂停事件
+    Apnea,
+    /// 系统时钟调整，采样时间轴保持连续
+    ClockAdjustment,
+    /// 手动重置处理状态（例如更换电极后），之后的归一化和统计重新开始
+    ProcessingReset,
+}
+
+/// 监护会话中的事件标注
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SessionAnnotation {
+    /// 所属会话ID
+    pub session_id: String,
+    /// 标注类型
+    pub kind: AnnotationKind,
+    /// 标注描述
+    pub message: String,
+    /// 事件开始时间戳（毫秒）
+    pub start_timestamp: u64,
+    /// 事件结束时间戳（毫秒）
+    pub end_timestamp: u64,
+    /// 事件持续时长（毫秒）
+    pub duration_ms: u64,
+}
+
+/// 由心率和血压计算的衍生风险指标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct DerivedMetrics {
+    /// 休克指数（心率/收缩压）
+    pub shock_index: f64,
+    /// 改良休克指数（心率/平均动脉压）
+    pub modified_shock_index: f64,
+    /// 心率收缩压乘积（心率×收缩压）
+    pub rate_pressure_product: f64,
+    /// 参与计算的血压测量时间戳（毫秒）
+    pub blood_pressure_timestamp: u64,
+}
+
+/// 体温报警配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TemperatureAlarmConfig {
+    /// 体温升至该值及以上触发发热报警（°C）
+    pub fever_trigger: f64,
+    /// 体温降至该值以下才解除发热报警（°C），与触发值之间形成滞回区间
+    pub fever_clear: f64,
+    /// 体温上升速率达到该值触发升温报警（°C/小时）
+    pub max_rise_per_hour: f64,
+    /// 计算上升速率的时间窗口（毫秒）
+    pub rise_window_ms: u64,
+}
+
+impl Default for TemperatureAlarmConfig {
+    fn default() -> Self {
+        Self {
+            fever_trigger: 38.0,
+            fever_clear: 37.6,
+            max_rise_per_hour: 1.0,
+            rise_window_ms: 30 * 60_000,
+        }
+    }
+}
+
+/// 衍生风险指标报警配置，阈值为None时不对该指标报警
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct DerivedAlarmConfig {
+    /// 休克指数高于等于该值报警
+    pub shock_index_high: Option<f64>,
+    /// 改良休克指数高于等于该值报警
+    pub modified_shock_index_high: Option<f64>,
+    /// 心率收缩压乘积高于等于该值报警
+    pub rate_pressure_product_high: Option<f64>,
+}
+
+impl Default for DerivedAlarmConfig {
+    fn default() -> Self {
+        Self {
+            shock_index_high: Some(1.0),
+            modified_shock_index_high: Some(1.3),
+            rate_pressure_product_high: None,
+        }
+    }
+}
+
+/// 提示性阈值配置，与报警无关，用于在趋势图上标注体征进入的区间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ThresholdConfig {
+    /// 体征名，例如 `heart_rate`
+    pub vital: String,
+    /// 区间分界值，按从小到大排列
+    pub levels: Vec<f64>,
+    /// 回差：越过分界值超过该幅度才视为进入新区间，避免数值在分界附近抖动时频繁产生事件
+    #[serde(default)]
+    pub hysteresis: f64,
+}
+
+/// 越过阈值的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrossingDirection {
+    Rising,
+    Falling,
+}
+
+/// 体征越过提示性阈值的事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ThresholdCrossing {
+    pub vital: String,
+    /// 时间戳（毫秒）
+    pub timestamp: u64,
+    /// 越过阈值时的数值
+    pub value: f64,
+    /// 最后越过的分界值
+    pub threshold: f64,
+    pub direction: CrossingDirection,
+    /// 原区间序号，0表示低于第一个分界值
+    pub from_zone: usize,
+    /// 新区间序号
+    pub to_zone: usize,
+}
+
+/// 趋势聚合段（一段时间内某项体征的统计值）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TrendBin {
+    /// 起始时间戳（毫秒，包含）
+    pub start_timestamp: u64,
+    /// 结束时间戳（毫秒，不包含）
+    pub end_timestamp: u64,
+    /// 最小值
+    pub min: f64,
+    /// 最大值
+    pub max: f64,
+    /// 平均值
+    pub mean: f64,
+    /// 最新值
+    pub last: f64,
+    /// 参与聚合的数值数量
+    pub count: u64,
+    /// 该时间段的休息/活动判定，尚未判定或无法判定时为None
+    #[serde(default)]
+    pub activity: Option<ActivityState>,
+}
+
+/// 休息/活动判定结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityState {
+    /// 休息或睡眠
+    Rest,
+    Active,
+}
+
+/// 休息/睡眠判定参数，三项条件都满足的时间段判定为休息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct RestDetectionConfig {
+    /// 平均心率不高于该值（次/分）
+    pub max_heart_rate: f64,
+    /// 心率最大值与最小值之差不超过该值（次/分）
+    pub max_heart_rate_range: f64,
+    /// 伪差采样占比不超过该值（0-1），伪差多说明患者在活动
+    pub max_artifact_rate: f64,
+}
+
+impl Default for RestDetectionConfig {
+    fn default() -> Self {
+        Self {
+            max_heart_rate: 90.0,
+            max_heart_rate_range: 12.0,
+            max_artifact_rate: 0.1,
+        }
+    }
+}
+
+/// 连续的休息或活动时间段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ActivityPeriod {
+    /// 起始时间戳（毫秒，包含）
+    pub start_timestamp: u64,
+    /// 结束时间戳（毫秒，不包含）
+    pub end_timestamp: u64,
+    pub state: ActivityState,
+}
+
+/// 一次血氧下降
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct Spo2Dip {
+    /// 所在趋势聚合段的起始时间戳（毫秒）
+    pub timestamp: u64,
+    /// 最低血氧（%）
+    pub nadir: f64,
+}
+
+/// 休息与活动时段分开统计的概要，用于夜间报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct RestSummary {
+    /// 判定为休息的分钟数
+    pub rest_minutes: u64,
+    /// 判定为活动的分钟数
+    pub active_minutes: u64,
+    /// 休息时段的平均心率
+    pub resting_heart_rate: Option<f64>,
+    /// 活动时段的平均心率
+    pub active_heart_rate: Option<f64>,
+    /// 休息时段的血氧平均值，作为判断血氧下降的基线
+    pub rest_spo2_baseline: Option<f64>,
+    /// 休息时段中最低血氧比基线低3%以上的时间段
+    pub rest_spo2_dips: Vec<Spo2Dip>,
+    /// 活动时段中最低血氧比基线低3%以上的时间段数量
+    pub active_spo2_dip_count: usize,
+    /// 按时间先后排列的休息和活动时间段
+    pub periods: Vec<ActivityPeriod>,
+}
+
+/// 心率区间，包含下限不包含上限
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HeartRateZone {
+    /// 区间名称，例如 `心动过缓`
+    pub name: String,
+    /// 下限（次/分，包含）
+    pub min: f64,
+    /// 上限（次/分，不包含）
+    pub max: f64,
+}
+
+/// 心率直方图的一个柱
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HistogramBucket {
+    /// 下限（次/分，包含）
+    pub lower: f64,
+    /// 上限（次/分，不包含）
+    pub upper: f64,
+    /// 落在该柱的心率数值数量
+    pub count: u64,
+    /// 占全部数值的百分比
+    pub percent: f64,
+}
+
+/// 心率在某个区间内的时间占比
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ZoneTime {
+    #[serde(flatten)]
+    pub zone: HeartRateZone,
+    /// 落在该区间的心率数值数量
+    pub count: u64,
+    /// 在该区间内的时间百分比
+    pub percent: f64,
+}
+
+/// 一段时间内的心率分布
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HeartRateHistogram {
+    /// 统计起始时间戳（毫秒）
+    pub start_timestamp: u64,
+    /// 统计结束时间戳（毫秒）
+    pub end_timestamp: u64,
+    /// 柱宽（次/分）
+    pub bin_width: f64,
+    /// 参与统计的心率数值数量
+    pub total_count: u64,
+    /// 按心率从低到高排列的直方图
+    pub buckets: Vec<HistogramBucket>,
+    /// 各心率区间的时间占比
+    pub zones: Vec<ZoneTime>,
+}
+
+/// 每小时的体征概览，由该小时内的趋势聚合段合并而成
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HourlySummary {
+    /// 小时起始时间戳（毫秒，包含）
+    pub start_timestamp: u64,
+    /// 小时结束时间戳（毫秒，不包含）
+    pub end_timestamp: u64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub last: f64,
+    /// 参与聚合的数值数量
+    pub count: u64,
+    /// 该小时内开始的相关报警数量
+    pub alarm_count: u32,
+}
+
+/// 报警优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlarmPriority {
+    /// 低优先级
+    Low,
+    /// 中优先级
+    Medium,
+    /// 高优先级
+    High,
+}
+
+/// 单个报警优先级的通知方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct PriorityNotification {
+    /// 是否发送系统通知
+    pub notify: bool,
+    /// 是否播放提示音
+    pub sound: bool,
+}
+
+/// 报警系统通知设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct NotificationSettings {
+    /// 是否启用系统通知
+    pub enabled: bool,
+    /// 低优先级报警的通知方式
+    pub low: PriorityNotification,
+    /// 中优先级报警的通知方式
+    pub medium: PriorityNotification,
+    /// 高优先级报警的通知方式
+    pub high: PriorityNotification,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            low: PriorityNotification {
+                notify: false,
+                sound: false,
+            },
+            medium: PriorityNotification {
+                notify: true,
+                sound: false,
+            },
+            high: PriorityNotification {
+                notify: true,
+                sound: true,
+            },
+        }
+    }
+}
+
+/// 报警
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct Alarm {
+    /// 报警ID
+    pub id: u64,
+    /// 报警条件标识，例如 `bp_hypertension`
+    pub condition: String,
+    /// 相关体征
+    pub vital: String,
+    /// 优先级
+    pub priority: AlarmPriority,
+    /// 报警描述
+    pub message: String,
+    /// 触发报警的数值
+    pub value: f64,
+    /// 报警阈值
+    pub limit: f64,
+    /// 激活时间戳（毫秒）
+    pub started_at: u64,
+    /// 是否已确认
+    pub acknowledged: bool,
+    /// 确认报警的操作员
+    pub acknowledged_by: Option<String>,
+    /// 确认时间戳（毫秒）
+    pub acknowledged_at: Option<u64>,
+    /// 是否处于静音中
+    pub silenced: bool,
+    /// 因长时间未确认而升级优先级的时间戳（毫秒），未升级时为None
+    pub escalated_at: Option<u64>,
+    /// 报警延迟（毫秒）：条件需持续该时长才激活
+    pub delay_ms: u64,
+}
+
+/// 报警限值方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitDirection {
+    /// 低于阈值报警
+    Low,
+    /// 高于阈值报警
+    High,
+}
+
+/// 某一优先级的报警阈值
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct AlarmLevel {
+    /// 优先级
+    pub priority: AlarmPriority,
+    /// 阈值，低限报警在数值低于该值时触发，高限报警在数值高于该值时触发
+    pub threshold: f64,
+}
+
+/// 单项体征某一方向的分级报警限值，例如血氧低于92为中优先级、低于85为高优先级
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct VitalAlarmLimits {
+    /// 体征名，例如 `spo2`、`heart_rate`
+    pub vital: String,
+    /// 限值方向
+    pub direction: LimitDirection,
+    /// 各优先级的阈值
+    pub levels: Vec<AlarmLevel>,
+}
+
+/// 报警升级配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct EscalationConfig {
+    /// 是否启用升级
+    pub enabled: bool,
+    /// 报警持续未确认超过该时长后优先级升高一级（毫秒）
+    pub unacknowledged_ms: u64,
+}
+
+impl Default for EscalationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            unacknowledged_ms: 2 * 60_000,
+        }
+    }
+}
+
+/// 运动期间非高优先级报警的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MotionSuppressionMode {
+    /// 延迟激活：报警条件需在运动期间额外持续一段时间才激活
+    Delay,
+    /// 降级：报警优先级降低一级
+    Downgrade,
+}
+
+/// 运动感知报警抑制配置
+///
+/// 检测到运动/伪差期间，中、低优先级报警按配置延迟或降级，高优先级报警不受影响。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct MotionSuppressionConfig {
+    /// 是否启用
+    pub enabled: bool,
+    pub mode: MotionSuppressionMode,
+    /// 延迟模式下在报警延迟之外额外等待的时长（毫秒）
+    pub extra_delay_ms: u64,
+}
+
+impl Default for MotionSuppressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: MotionSuppressionMode::Delay,
+            extra_delay_ms: 15_000,
+        }
+    }
+}
+
+/// 一次运动期间的报警抑制记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct AlarmSuppression {
+    /// 时间戳（毫秒）
+    pub timestamp: u64,
+    /// 报警条件标识
+    pub condition: String,
+    /// 报警描述
+    pub message: String,
+    /// 抑制前的优先级
+    pub priority: AlarmPriority,
+    /// 处理方式
+    pub mode: MotionSuppressionMode,
+}
+
+/// 血压报警配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct BpAlarmConfig {
+    /// 收缩压高于等于该值视为高血压（mmHg）
+    pub hypertension_systolic: i32,
+    /// 舒张压高于等于该值视为高血压（mmHg）
+    pub hypertension_diastolic: i32,
+    /// 收缩压低于等于该值视为低血压（mmHg）
+    pub hypotension_systolic: i32,
+    /// 平均动脉压低于等于该值视为低血压（mmHg）
+    pub hypotension_map: f64,
+    /// 连续多少次测量超出阈值才报警
+    pub sustained_readings: u32,
+}
+
+impl Default for BpAlarmConfig {
+    fn default() -> Self {
+        Self {
+            hypertension_systolic: 160,
+            hypertension_diastolic: 100,
+            hypotension_systolic: 90,
+            hypotension_map: 65.0,
+            sustained_readings: 2,
+        }
+    }
+}
+
+/// 间歇性测量的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MeasurementKind {
+    /// 血糖
+    Glucose,
+    /// 体位性生命体征测试，数值为1表示通过、0表示未通过
+    Orthostatic,
+    /// 心率恢复测试，数值为运动结束后1分钟的心率下降值
+    HeartRateRecovery,
+}
+
+/// 测量记录的结构化详情
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum MeasurementDetails {
+    /// 体位性生命体征测试结果
+    Orthostatic(OrthostaticResult),
+    /// 心率恢复测试结果
+    HeartRateRecovery(HrRecoveryResult),
+}
+
+/// 间歇性测量记录（非连续的单次测量结果）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct MeasurementRecord {
+    /// 测量类型
+    pub kind: MeasurementKind,
+    /// 测量值
+    pub value: f64,
+    /// 单位
+    pub unit: String,
+    /// 测量时间戳（毫秒）
+    pub timestamp: u64,
+    /// 结构化详情，单值测量为None
+    #[serde(default)]
+    pub details: Option<MeasurementDetails>,
+}
+
+/// 体位性测试中的体位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrthostaticPosition {
+    /// 卧位
+    Supine,
+    /// 坐位
+    Sitting,
+    /// 立位
+    Standing,
+}
+
+/// 体位性生命体征测试配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct OrthostaticConfig {
+    /// 卧位持续时间（毫秒）
+    pub supine_duration_ms: u64,
+    /// 坐位持续时间（毫秒）
+    pub sitting_duration_ms: u64,
+    /// 立位持续时间（毫秒）
+    pub standing_duration_ms: u64,
+    /// 心率采集窗口（毫秒），取每个体位末尾该时长内的平均心率
+    pub heart_rate_window_ms: u64,
+    /// 收缩压下降达到该值判定为未通过（mmHg）
+    pub max_systolic_drop: i32,
+    /// 舒张压下降达到该值判定为未通过（mmHg）
+    pub max_diastolic_drop: i32,
+    /// 心率上升达到该值判定为未通过（次/分）
+    pub max_heart_rate_rise: f64,
+}
+
+impl Default for OrthostaticConfig {
+    fn default() -> Self {
+        Self {
+            supine_duration_ms: 5 * 60_000,
+            sitting_duration_ms: 60_000,
+            standing_duration_ms: 3 * 60_000,
+            heart_rate_window_ms: 30_000,
+            max_systolic_drop: 20,
+            max_diastolic_drop: 10,
+            max_heart_rate_rise: 30.0,
+        }
+    }
+}
+
+/// 单个体位的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct OrthostaticPhaseResult {
+    /// 体位
+    pub position: OrthostaticPosition,
+    /// 体位末尾的平均心率，无有效心率时为None
+    pub heart_rate: Option<f64>,
+    /// 体位期间最近一次测得的收缩压，无测量时为None
+    pub systolic: Option<i32>,
+    /// 体位期间最近一次测得的舒张压，无测量时为None
+    pub diastolic: Option<i32>,
+    /// 记录时间戳（毫秒）
+    pub captured_at: u64,
+}
+
+/// 相对卧位的体位性变化量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct OrthostaticDelta {
+    /// 体位
+    pub position: OrthostaticPosition,
+    /// 心率变化（次/分）
+    pub heart_rate_change: Option<f64>,
+    /// 收缩压变化（mmHg），负值表示下降
+    pub systolic_change: Option<i32>,
+    /// 舒张压变化（mmHg），负值表示下降
+    pub diastolic_change: Option<i32>,
+}
+
+/// 体位性生命体征测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct OrthostaticResult {
+    /// 各体位的记录
+    pub phases: Vec<OrthostaticPhaseResult>,
+    /// 坐位、立位相对卧位的变化量
+    pub deltas: Vec<OrthostaticDelta>,
+    /// 收缩压下降是否在标准内，缺少血压数据时为None
+    pub systolic_passed: Option<bool>,
+    /// 舒张压下降是否在标准内，缺少血压数据时为None
+    pub diastolic_passed: Option<bool>,
+    /// 心率上升是否在标准内，缺少心率数据时为None
+    pub heart_rate_passed: Option<bool>,
+    /// 所有可判定的标准是否均通过
+    pub passed: bool,
+    /// 测试使用的配置
+    pub config: OrthostaticConfig,
+    /// 开始时间戳（毫秒）
+    pub started_at: u64,
+    /// 完成时间戳（毫秒）
+    pub completed_at: u64,
+}
+
+/// 体位性测试的当前状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct OrthostaticStatus {
+    /// 测试是否进行中
+    pub active: bool,
+    /// 当前体位，测试完成后为None
+    pub position: Option<OrthostaticPosition>,
+    /// 当前体位的剩余时间（毫秒）
+    pub phase_remaining_ms: u64,
+    /// 已完成体位的记录
+    pub phases: Vec<OrthostaticPhaseResult>,
+    /// 测试结果，完成后存在
+    pub result: Option<OrthostaticResult>,
+}
+
+/// 心率恢复测试配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HrRecoveryConfig {
+    /// 心率采集窗口（毫秒），运动结束和每个时间点取该时长内的平均心率
+    pub heart_rate_window_ms: u64,
+    /// 1分钟心率下降不超过该值判定为恢复异常（次/分）
+    pub abnormal_recovery_threshold: f64,
+}
+
+impl Default for HrRecoveryConfig {
+    fn default() -> Self {
+        Self {
+            heart_rate_window_ms: 10_000,
+            abnormal_recovery_threshold: 12.0,
+        }
+    }
+}
+
+/// 心率恢复测试的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HrRecoveryPhase {
+    /// 运动中，等待标记运动结束
+    Exercise,
+    /// 运动已结束，正在采集恢复心率
+    Recovery,
+    /// 测试完成
+    Complete,
+}
+
+/// 运动结束后某个时间点的心率
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HrRecoveryPoint {
+    /// 距运动结束的分钟数
+    pub minute: u32,
+    /// 该时间点前采集窗口内的平均心率，无有效心率时为None
+    pub heart_rate: Option<f64>,
+    /// 相对运动结束时心率的下降值（次/分）
+    pub recovery: Option<f64>,
+    /// 记录时间戳（毫秒）
+    pub captured_at: u64,
+}
+
+/// 心率恢复测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HrRecoveryResult {
+    /// 运动结束前采集窗口内的平均心率
+    pub exercise_heart_rate: Option<f64>,
+    /// 运动结束后1、2、3分钟的心率
+    pub points: Vec<HrRecoveryPoint>,
+    /// 1分钟心率下降是否不超过异常阈值，缺少心率数据时为None
+    pub abnormal: Option<bool>,
+    /// 测试使用的配置
+    pub config: HrRecoveryConfig,
+    /// 开始时间戳（毫秒）
+    pub started_at: u64,
+    /// 运动结束时间戳（毫秒）
+    pub exercise_ended_at: u64,
+    /// 完成时间戳（毫秒）
+    pub completed_at: u64,
+}
+
+/// 心率恢复测试的当前状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HrRecoveryStatus {
+    pub phase: HrRecoveryPhase,
+    /// 距运动结束的时长（毫秒），运动中为0
+    pub recovery_elapsed_ms: u64,
+    /// 运动结束前的平均心率，尚未标记运动结束时为None
+    pub exercise_heart_rate: Option<f64>,
+    /// 已记录的时间点
+    pub points: Vec<HrRecoveryPoint>,
+    /// 测试结果，完成后存在
+    pub result: Option<HrRecoveryResult>,
+}
+
+/// 六分钟步行试验配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WalkTestConfig {
+    /// 试验时长（毫秒），标准为6分钟
+    pub duration_ms: u64,
+    /// 每圈距离（米）
+    pub lap_length_m: f64,
+    /// 血氧最低值比基线低该值（%）以上判定为运动中血氧下降
+    pub desaturation_drop: f64,
+}
+
+impl Default for WalkTestConfig {
+    fn default() -> Self {
+        Self {
+            duration_ms: 6 * 60_000,
+            lap_length_m: 30.0,
+            desaturation_drop: 4.0,
+        }
+    }
+}
+
+/// Borg评分的记录时机
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorgTiming {
+    /// 试验前
+    Pre,
+    /// 试验后
+    Post,
+}
+
+/// Borg CR10 评分（0-10）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct BorgScore {
+    /// 呼吸困难程度
+    pub dyspnea: f64,
+    /// 疲劳程度
+    pub fatigue: f64,
+}
+
+/// 步行试验中的一圈
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WalkLap {
+    /// 圈序号，从1开始
+    pub number: u32,
+    /// 完成时间戳（毫秒）
+    pub timestamp: u64,
+    /// 距试验开始的时长（毫秒）
+    pub elapsed_ms: u64,
+    /// 本圈用时（毫秒）
+    pub split_ms: u64,
+}
+
+/// 步行试验中每秒一个的心率和血氧采样
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WalkSample {
+    /// 距试验开始的时长（毫秒）
+    pub elapsed_ms: u64,
+    pub heart_rate: Option<f64>,
+    pub spo2: Option<f64>,
+}
+
+/// 试验期间某项体征的统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WalkVitalSummary {
+    /// 试验开始时的数值
+    pub baseline: Option<f64>,
+    /// 试验结束时的数值
+    pub end: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    /// 最低值出现的距试验开始时长（毫秒）
+    pub min_elapsed_ms: Option<u64>,
+}
+
+/// 六分钟步行试验的标准化结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WalkTestSummary {
+    /// 试验ID
+    pub test_id: String,
+    /// 所属患者ID，保存时填写
+    pub patient_id: Option<String>,
+    /// 开始时间戳（毫秒）
+    pub started_at: u64,
+    /// 结束时间戳（毫秒）
+    pub ended_at: u64,
+    /// 实际步行时长（毫秒）
+    pub duration_ms: u64,
+    /// 提前结束的原因，完成全程时为None
+    pub stop_reason: Option<String>,
+    pub laps: Vec<WalkLap>,
+    /// 最后不足一圈的距离（米）
+    pub partial_distance_m: f64,
+    /// 总步行距离（米）
+    pub total_distance_m: f64,
+    pub heart_rate: WalkVitalSummary,
+    pub spo2: WalkVitalSummary,
+    /// 血氧低于90%的累计时长（毫秒）
+    pub spo2_below_90_ms: u64,
+    /// 是否出现运动中血氧下降（最低值比基线低配置的幅度以上）
+    pub desaturation: bool,
+    pub borg_pre: Option<BorgScore>,
+    pub borg_post: Option<BorgScore>,
+    /// 每秒一个的心率和血氧采样
+    pub samples: Vec<WalkSample>,
+    /// 试验使用的配置
+    pub config: WalkTestConfig,
+}
+
+/// 步行试验的当前状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WalkTestStatus {
+    /// 试验是否进行中
+    pub active: bool,
+    /// 已步行时长（毫秒）
+    pub elapsed_ms: u64,
+    /// 剩余时长（毫秒）
+    pub remaining_ms: u64,
+    pub laps: Vec<WalkLap>,
+    /// 目前为止的血氧最低值
+    pub spo2_nadir: Option<f64>,
+    /// 目前为止的最高心率
+    pub max_heart_rate: Option<f64>,
+    /// 试验结束后的结果，保存前可继续补充试验后的Borg评分
+    pub summary: Option<WalkTestSummary>,
+}
+
+/// RR间期记录（心搏间期序列中的一个点）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct RrIntervalPoint {
+    /// 心搏时间戳（毫秒）
+    pub timestamp: u64,
+    /// 与上一心搏的间期（毫秒）
+    pub rr_interval_ms: f64,
+}
+
+/// 心搏分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BeatClass {
+    Normal,
+    /// 起搏心搏：起搏脉冲后300ms内出现的R波
+    Paced,
+    /// 提前心搏：RR间期短于最近心搏RR间期中位数的80%
+    Premature,
+}
+
+/// 心搏检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct BeatRecord {
+    /// R波时间戳（毫秒）
+    pub timestamp: u64,
+    /// R波采样放入原始数据队列的时间戳（毫秒），与模拟数据源的标注心搏同一时间轴；
+    /// 没有接收时间的采样为0
+    #[serde(default)]
+    pub received_at: u64,
+    /// 与上一心搏的间期（毫秒）
+    pub rr_interval_ms: f64,
+    pub classification: BeatClass,
+    /// R波幅度（原始单位，相对动态阈值窗口的最小值）
+    pub amplitude: f64,
+}
+
+/// Poincaré散点图中的一个点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct PoincarePoint {
+    /// 当前RR间期（毫秒）
+    pub rr_n: f64,
+    /// 下一个RR间期（毫秒）
+    pub rr_n1: f64,
+}
+
+/// Poincaré散点图数据及椭圆参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct PoincarePlot {
+    /// 散点数据 (RRn, RRn+1)
+    pub points: Vec<PoincarePoint>,
+    /// 短轴标准差SD1（毫秒）
+    pub sd1: f64,
+    /// 长轴标准差SD2（毫秒）
+    pub sd2: f64,
+    /// SD1/SD2 比值
+    pub sd1_sd2_ratio: f64,
+    /// 椭圆中心X坐标（平均RR间期）
+    pub center_x: f64,
+    /// 椭圆中心Y坐标（平均RR间期）
+    pub center_y: f64,
+}
+
+/// HRV功率谱估计方法
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum SpectralMethod {
+    /// 重采样后使用Welch法
+    #[default]
+    Welch,
+    /// 直接在不等间隔序列上使用Lomb-Scargle周期图
+    LombScargle,
+}
+
+/// HRV频域分析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct HrvSpectrum {
+    /// 使用的功率谱估计方法
+    pub method: SpectralMethod,
+    /// 分析窗口长度（秒）
+    pub window_seconds: f64,
+    /// 参与计算的RR间期数量
+    pub beat_count: usize,
+    /// 极低频功率 0.0033-0.04Hz (ms²)
+    pub vlf_power: f64,
+    /// 低频功率 0.04-0.15Hz (ms²)
+    pub lf_power: f64,
+    /// 高频功率 0.15-0.4Hz (ms²)
+    pub hf_power: f64,
+    /// 总功率 (ms²)
+    pub total_power: f64,
+    /// LF/HF 比值
+    pub lf_hf_ratio: f64,
+    /// 归一化低频功率 (n.u.)
+    pub lf_nu: f64,
+    /// 归一化高频功率 (n.u.)
+    pub hf_nu: f64,
+    /// 功率谱频率点 (Hz)
+    pub frequencies: Vec<f64>,
+    /// 功率谱密度 (ms²/Hz)
+    pub psd: Vec<f64>,
+}
+
+/// 心率平均策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum HeartRateAveraging {
+    /// 不做平均，直接使用瞬时心率
+    Instant,
+    /// 最近N个心搏的中位数
+    MedianOfBeats(usize),
+    /// 时间窗口内的心率均值（窗口长度，毫秒）
+    TimeWindowMean(u64),
+}
+
+impl Default for HeartRateAveraging {
+    fn default() -> Self {
+        HeartRateAveraging::MedianOfBeats(8)
+    }
+}
+
 /// 心电数据处理状态
 #[derive(Debug, Clone)]
 pub struct EcgProcessingState {
@@ -63,12 +1562,34 @@ pub struct EcgProcessingState {
     pub ecg_point_min: f64,
     pub ecg_point_max_new: f64,
     pub ecg_point_min_new: f64,
+    /// 心搏检测参数
+    pub params: EcgProcessingParams,
     pub ecg_points: VecDeque<i32>,
     pub peak_interval_num: u32,
     pub counter: u32,
     pub ecg_data_original_list: Vec<i32>,
     pub last_heart_rate: f64,
     pub last_rr_interval: f64,
+    /// 平均后的心率
+    pub last_averaged_heart_rate: f64,
+    /// 最近心搏的心率记录 (时间戳, 瞬时心率)，用于平均计算
+    pub recent_heart_rates: VecDeque<(u64, f64)>,
+    /// RR间期序列（按时间先后排列）
+    pub rr_history: VecDeque<RrIntervalPoint>,
+    /// 心搏检测结果（按时间先后排列）
+    pub beats: VecDeque<BeatRecord>,
+    /// 伪差结束后跳过下一个心搏（其间期跨越伪差段，不可信）
+    pub skip_next_beat: bool,
+    /// 上一个采样放入原始数据队列的时间戳，即波峰检测窗口中间点的接收时间
+    pub last_received_at: u64,
+    /// 距上一个起搏脉冲的采样点数
+    pub samples_since_pace: Option<u32>,
+    /// 检测到的起搏脉冲总数
+    pub pace_spike_count: u64,
+    /// 起搏心搏数
+    pub paced_beats: u64,
+    /// 检测到的心搏总数
+    pub total_beats: u64,
 }
 
 /// LTTB处理状态
@@ -102,10 +1623,178 @@ pub struct TemperatureProcessingState {
     pub offset: f64,
     pub max_temp: f64,
     pub room_temperature: f64,
+    /// 最近的未校准读数（原始值/10），用于校准时取稳定读数
+    pub recent_raw_values: VecDeque<f64>,
+}
+
+/// 体温校准点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TemperatureCalibrationPoint {
+    /// 采集时的未校准读数
+    pub raw_value: f64,
+    /// 参考温度计读数（摄氏度）
+    pub reference_value: f64,
+}
+
+/// 体温校准结果（设备相关，保存在连接配置档案中）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct TemperatureCalibration {
+    /// 比例系数
+    pub scale_factor: f64,
+    /// 偏移量（摄氏度）
+    pub offset: f64,
+    /// 用于计算的校准点
+    pub points: Vec<TemperatureCalibrationPoint>,
+    /// 校准时间
+    pub calibrated_at: String,
+}
+
+/// 呼吸数据（供前端显示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct RespirationData {
+    /// 呼吸频率（次/分）
+    pub respiration_rate: f64,
+    /// 是否呼吸暂停
+    pub apnea: bool,
+    /// 最近一次呼吸的时间戳（毫秒）
+    pub last_breath_timestamp: Option<u64>,
+    /// LTTB压缩后的呼吸波形
+    pub waveform: Vec<LttbDataPoint>,
+}
+
+/// 呼吸波形处理状态
+#[derive(Debug, Clone)]
+pub struct RespirationProcessingState {
+    /// 无呼吸超过该时长判定为呼吸暂停（毫秒）
+    pub apnea_threshold_ms: u64,
+    /// 两次呼吸的最小间隔（毫秒），更短的视为噪声
+    pub min_breath_interval_ms: u64,
+    /// 是否已用首个采样初始化
+    pub initialized: bool,
+    /// 平滑后的呼吸波形值
+    pub smoothed: f64,
+    /// 波形基线
+    pub baseline: f64,
+    /// 波形幅度包络
+    pub envelope: f64,
+    /// 波形已低于下阈值，等待上穿
+    pub armed: bool,
+    /// 最近一次呼吸的时间戳（毫秒）
+    pub last_breath_timestamp: Option<u64>,
+    /// 最近的呼吸间隔（毫秒）
+    pub breath_intervals: VecDeque<f64>,
+    /// 当前呼吸频率（次/分）
+    pub respiration_rate: f64,
+    /// 当前是否呼吸暂停
+    pub apnea: bool,
+    /// 当前呼吸暂停的开始时间戳（即最后一次呼吸），未处于呼吸暂停时为None
+    pub apnea_since: Option<u64>,
+}
+
+/// 二氧化碳监测数据（供前端显示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct CapnographyData {
+    /// 呼气末二氧化碳分压EtCO2（mmHg）
+    pub etco2: f64,
+    /// 吸入二氧化碳分压FiCO2（mmHg）
+    pub fico2: f64,
+    /// 由二氧化碳波形计算的呼吸频率（次/分）
+    pub respiration_rate: f64,
+    /// 最近一次呼气开始的时间戳（毫秒）
+    pub last_breath_timestamp: Option<u64>,
+    /// LTTB压缩后的二氧化碳波形
+    pub waveform: Vec<LttbDataPoint>,
+}
+
+/// 二氧化碳波形处理状态
+#[derive(Debug, Clone)]
+pub struct CapnographyProcessingState {
+    /// 波形高于该值（mmHg）判定为进入呼气相
+    pub expiration_threshold: f64,
+    /// 波形低于该值（mmHg）判定为进入吸气相
+    pub inspiration_threshold: f64,
+    /// 两次呼吸的最小间隔（毫秒），更短的视为噪声
+    pub min_breath_interval_ms: u64,
+    /// 超过该时长无呼吸时频率和EtCO2归零（毫秒）
+    pub no_breath_timeout_ms: u64,
+    /// 当前是否处于呼气相
+    pub in_expiration: bool,
+    /// 当前呼气相内的最大值（mmHg）
+    pub expiration_peak: f64,
+    /// 当前吸气相内的最小值（mmHg）
+    pub inspiration_min: f64,
+    /// 最近一次呼气开始的时间戳（毫秒）
+    pub last_breath_timestamp: Option<u64>,
+    /// 最近的呼吸间隔（毫秒）
+    pub breath_intervals: VecDeque<f64>,
+    /// 呼气末二氧化碳分压（mmHg）
+    pub etco2: f64,
+    /// 吸入二氧化碳分压（mmHg）
+    pub fico2: f64,
+    /// 当前呼吸频率（次/分）
+    pub respiration_rate: f64,
+}
+
+/// 运动/伪差检测状态（同时负责区分起搏脉冲）
+#[derive(Debug, Clone)]
+pub struct ArtifactDetectionState {
+    /// 相邻ECG采样允许的最大变化量（原始值单位）
+    pub max_ecg_slope: f64,
+    /// 短时方差超过基线方差的倍数阈值
+    pub variance_ratio_threshold: f64,
+    /// 相邻血氧采样允许的最大跳变（百分比）
+    pub max_spo2_jump: f64,
+    /// 起搏脉冲的最小上升斜率（原始值单位/采样）
+    pub pace_slew_threshold: f64,
+    /// 检测到伪差后保持标记的采样点数
+    pub hold_samples: u32,
+    /// 前两个ECG采样值
+    pub prev_ecg: Option<i32>,
+    /// 上一个ECG采样值
+    pub last_ecg: Option<i32>,
+    /// 最近1秒的ECG采样窗口
+    pub ecg_window: VecDeque<f64>,
+    /// ECG基线方差（指数滑动平均，仅在无伪差时更新）
+    pub baseline_variance: f64,
+    /// ECG伪差标记剩余保持的采样点数
+    pub ecg_hold_remaining: u32,
+    /// 上一个血氧值
+    pub last_spo2: Option<f64>,
+    /// 血氧伪差标记剩余保持的采样点数
+    pub spo2_hold_remaining: u32,
+}
+
+/// 通道增益/反相/偏移设置
+///
+/// 在检测和归一化之前作用于原始采样：`输出 = 原始值 × 增益 × (反相 ? -1 : 1) + 偏移`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ChannelAdjustment {
+    /// 增益系数
+    pub gain: f64,
+    /// 是否反相
+    pub invert: bool,
+    /// 偏移量（原始值单位）
+    pub offset: f64,
+}
+
+impl Default for ChannelAdjustment {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            invert: false,
+            offset: 0.0,
+        }
+    }
 }
 
 /// 串口配置结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
 pub struct SerialConfig {
     /// 串口名称 (如 "COM1" 或 "/dev/ttyUSB0")
     pub port_name: String,
@@ -113,8 +1802,66 @@ pub struct SerialConfig {
     pub baud_rate: u32,
 }
 
+/// ECG心搏检测参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct EcgProcessingParams {
+    /// 波峰检测阈值，波峰高出最小值的幅度超过动态范围的该比例才视为R波（0~1）
+    pub peak_threshold: f64,
+    /// 动态范围（最大最小值）的更新间隔（采样点数）
+    pub threshold_update_interval: u32,
+    /// 原始数据缓冲区大小（采样点数）
+    pub raw_buffer_size: usize,
+    /// 保留的最近心搏心率记录数，用于心率平均
+    pub heart_rate_history_size: usize,
+}
+
+impl Default for EcgProcessingParams {
+    fn default() -> Self {
+        Self {
+            peak_threshold: 0.6,
+            threshold_update_interval: 300,
+            raw_buffer_size: 250,
+            heart_rate_history_size: 64,
+        }
+    }
+}
+
+/// 血氧验证和平均配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct Spo2Config {
+    /// 滑动平均的心搏数（4、8或16）
+    pub averaging_beats: usize,
+    /// 单次读数相对当前平均值允许的最大偏差（百分比），超过视为尖峰
+    pub max_spike: f64,
+}
+
+impl Default for Spo2Config {
+    fn default() -> Self {
+        Self {
+            averaging_beats: 8,
+            max_spike: 4.0,
+        }
+    }
+}
+
+/// 血氧处理状态
+#[derive(Debug, Clone)]
+pub struct Spo2ProcessingState {
+    /// 验证和平均配置
+    pub config: Spo2Config,
+    /// 参与平均的最近读数（每个心搏一个）
+    pub values: VecDeque<f64>,
+    /// 连续被判定为尖峰的读数数量
+    pub consecutive_spikes: u32,
+    /// 最近一次采样读数的时间戳（毫秒）
+    pub last_sample_timestamp: Option<u64>,
+}
+
 /// LTTB配置结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
 pub struct LttbConfig {
     /// 缓冲区大小（触发压缩的数据点数量）
     pub buffer_size: usize,
@@ -139,6 +1886,7 @@ impl Default for LttbConfig {
 
 /// ECG数据统计信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
 pub struct EcgStatistics {
     /// 当前心率
     pub current_heart_rate: f64,
@@ -154,10 +1902,39 @@ pub struct EcgStatistics {
     pub signal_quality: f64,
     /// 压缩效率 (压缩前/压缩后)
     pub compression_efficiency: f64,
+    /// 起搏心搏占比 (%)
+    pub paced_beat_percentage: f64,
+}
+
+/// 单项体征在滚动窗口内的统计值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct RollingStats {
+    /// 最新值
+    pub current: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// 窗口内的数值数量
+    pub count: u64,
+}
+
+/// 心率、血氧和体温的滚动统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct VitalStatistics {
+    /// 统计窗口时长（秒）
+    pub window_secs: u64,
+    /// 心率统计，窗口内没有有效心率时为None
+    pub heart_rate: Option<RollingStats>,
+    /// 血氧统计
+    pub spo2: Option<RollingStats>,
+    /// 体温统计
+    pub temperature: Option<RollingStats>,
 }
 
-/// 数据存储队列类型
-pub type DataQueue = Arc<Mutex<VecDeque<VitalSigns>>>;
+/// 数据存储队列类型，原始采样队列为读取线程到处理线程的单生产者单消费者环形队列
+pub type DataQueue = Arc<SampleRing>;
 pub type ProcessedDataQueue = Arc<Mutex<VecDeque<ProcessedVitalSigns>>>;
 
 /// 串口状态枚举
@@ -169,8 +1946,303 @@ pub enum SerialStatus {
     Error(String), // 包含错误信息
 }
 
-/// 数据处理状态枚举
+/// 串口错误类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SerialErrorKind {
+    /// 读取超时（设备暂时没有发送数据）
+    Timeout,
+    /// 没有访问串口的权限（串口被占用或权限不足）
+    PermissionDenied,
+    /// 帧错误（收到无法解码的字节，通常是波特率不匹配或线路干扰）
+    Framing,
+    /// 设备已断开（拔出USB转串口适配器等）
+    DeviceGone,
+    /// 其他I/O错误
+    Other,
+    /// 设备固件版本低于兼容性表中已知可用的最低版本
+    Firmware,
+}
+
+/// 串口错误的恢复动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// 立即重试读取
+    Retry,
+    /// 等待一段逐渐增长的时间后重试，连续失败过多时放弃
+    Backoff,
+    /// 关闭并重新打开串口
+    Reconnect,
+    /// 停止读取并把错误报告给用户
+    SurfaceToUser,
+}
+
+impl SerialErrorKind {
+    /// 获取该类错误对应的恢复动作
+    pub fn recovery_action(self) -> RecoveryAction {
+        match self {
+            SerialErrorKind::Timeout | SerialErrorKind::Framing => RecoveryAction::Retry,
+            SerialErrorKind::Other => RecoveryAction::Backoff,
+            SerialErrorKind::DeviceGone => RecoveryAction::Reconnect,
+            SerialErrorKind::PermissionDenied | SerialErrorKind::Firmware => {
+                RecoveryAction::SurfaceToUser
+            }
+        }
+    }
+}
+
+/// 串口错误恢复策略配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SerialResilienceConfig {
+    /// 读取超时（毫秒）
+    pub read_timeout_ms: u64,
+    /// 连续发生可退避错误的最大次数，超过后停止读取并报告错误
+    pub max_consecutive_errors: u32,
+    /// 首次退避等待时间（毫秒）
+    pub initial_backoff_ms: u64,
+    /// 每次退避后等待时间的增长倍数
+    pub backoff_multiplier: f64,
+    /// 退避等待时间上限（毫秒）
+    pub max_backoff_ms: u64,
+    /// 设备断开后重新连接的尝试间隔（毫秒）
+    pub reconnect_interval_ms: u64,
+    /// 设备断开后重新连接的最大尝试次数，为0时不重新连接
+    pub max_reconnect_attempts: u32,
+}
+
+impl Default for SerialResilienceConfig {
+    fn default() -> Self {
+        Self {
+            read_timeout_ms: 3000,
+            max_consecutive_errors: 5,
+            initial_backoff_ms: 500,
+            backoff_multiplier: 2.0,
+            max_backoff_ms: 8000,
+            reconnect_interval_ms: 2000,
+            max_reconnect_attempts: 10,
+        }
+    }
+}
+
+/// 串口读取线程和数据处理线程的调度配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ThreadTuningConfig {
+    /// 是否提高两个线程的调度优先级
+    pub raise_priority: bool,
+    /// 串口读取线程绑定的CPU核，为None时不绑定
+    pub reader_core: Option<usize>,
+    /// 数据处理线程绑定的CPU核，为None时不绑定
+    pub processor_core: Option<usize>,
+}
+
+/// 串口连接测试结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortTestStatus {
+    /// 串口可以正常打开
+    Available,
+    /// 串口正被当前连接或其他程序占用
+    Busy,
+}
+
+/// 串口回环测试结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct LoopbackTestResult {
+    /// 测试轮数
+    pub rounds: u32,
+    /// 在超时前收到完整回显的轮数
+    pub completed_rounds: u32,
+    /// 超时未收到完整回显的轮数
+    pub timed_out_rounds: u32,
+    /// 发送的字节总数
+    pub bytes_sent: u64,
+    /// 收到的回显字节总数
+    pub bytes_received: u64,
+    /// 回显内容与发送内容不一致的字节数
+    pub corrupted_bytes: u64,
+    /// 最小往返时间（毫秒），没有完成的轮次时为None
+    pub min_round_trip_ms: Option<f64>,
+    /// 平均往返时间（毫秒）
+    pub average_round_trip_ms: Option<f64>,
+    /// 最大往返时间（毫秒）
+    pub max_round_trip_ms: Option<f64>,
+}
+
+/// 处理性能测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ProcessingBenchmark {
+    /// 计入结果的采样数
+    pub samples: usize,
+    /// 处理耗时（毫秒）
+    pub elapsed_ms: f64,
+    /// 处理速率（采样/秒）
+    pub samples_per_second: f64,
+    /// 内存分配总次数
+    pub allocations: u64,
+    /// 平均每个采样的内存分配次数
+    pub allocations_per_sample: f64,
+    /// 平均每个采样分配的字节数
+    pub bytes_allocated_per_sample: f64,
+}
+
+/// 当前连接按类别统计的串口错误
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SerialErrorCounts {
+    /// 各类别的错误次数
+    pub counts: BTreeMap<SerialErrorKind, u64>,
+    /// 设备断开后成功重新连接的次数
+    pub reconnects: u64,
+}
+
+/// 串口链路的帧完整性统计，每次连接重新计数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct LinkStatistics {
+    /// 解码成功的帧数
+    pub frames_received: u64,
+    /// 格式错误或缺少必需字段的帧数
+    pub malformed_frames: u64,
+    /// 校验和不匹配的帧数
+    pub checksum_failures: u64,
+    /// 丢弃无效字节后重新同步的次数
+    pub resyncs: u64,
+    /// 重新同步时丢弃的字节数
+    pub discarded_bytes: u64,
+    /// 检测到帧序号不连续的次数
+    pub sequence_gaps: u64,
+    /// 按帧序号推算丢失的帧数
+    pub missed_frames: u64,
+}
+
+/// 当前数据源的连接统计，每次连接重新计数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct ConnectionStatistics {
+    /// 数据源类型
+    pub source_type: DataSourceType,
+    /// 当前连接的端口或地址，未连接时为None
+    pub port_name: Option<String>,
+    /// 连接持续时长（毫秒），未连接时为None
+    pub uptime_ms: Option<u64>,
+    /// 设备断开后成功重新连接的次数
+    pub reconnects: u64,
+    /// 解析成功的采样总数
+    pub samples_received: u64,
+    /// 无法解析的数据帧总数
+    pub parse_failures: u64,
+    /// 无法解析的数据帧占全部数据帧的比例（0~1）
+    pub parse_failure_rate: f64,
+    /// 最近时间窗口内的吞吐量（采样/秒）
+    pub samples_per_second: f64,
+    /// 最近时间窗口内的吞吐量（字节/秒）
+    pub bytes_per_second: f64,
+    /// 最近一次错误（不含读取超时）
+    pub last_error: Option<String>,
+    /// 最近一次错误的时间戳（毫秒）
+    pub last_error_at: Option<u64>,
+}
+
+/// 设备能力，连接后根据设备实际上报的通道判断
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct DeviceCapabilities {
+    /// 是否已收到足够的采样完成检测
+    pub discovery_complete: bool,
+    /// 本次连接收到的采样数
+    pub samples_observed: u64,
+    /// 设备上报过的通道ID
+    pub supported_channels: Vec<String>,
+    /// 检测完成时设备仍未上报的连续通道ID
+    pub unsupported_channels: Vec<String>,
+    /// 尚无法判断的通道ID：检测完成前未上报的通道，以及未上报过的间歇性测量通道
+    /// （无创血压、血糖只在测量完成时上报）
+    pub pending_channels: Vec<String>,
+}
+
+/// 数据流健康状态，反映连接后采样是否真正到达
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum StreamHealth {
+    /// 采样正常到达
+    StreamingOk,
+    /// 连接后尚未收到任何有效采样
+    NoData,
+    /// 已超过若干秒没有收到有效采样
+    Stalled { seconds: u64 },
+    /// 近期收到的数据行中无法解析的比例过高（0~1）
+    ParserRejectingData { rate: f64 },
+}
+
+/// 串口状态报告，在连接状态之外附带数据流健康状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SerialStatusReport {
+    /// 连接状态
+    #[serde(flatten)]
+    pub status: SerialStatus,
+    /// 数据流健康状态，未连接时为None
+    pub stream_health: Option<StreamHealth>,
+    /// 设备固件版本过低的警告，未连接或固件版本兼容时为None
+    #[serde(default)]
+    pub firmware_warning: Option<String>,
+}
+
+/// 看门狗监控的组件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchdogComponent {
+    /// 串口读取线程（包括测试数据生成线程）
+    SerialReader,
+    /// 数据处理线程
+    DataProcessor,
+}
+
+/// 看门狗配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WatchdogConfig {
+    /// 是否启用看门狗
+    pub enabled: bool,
+    /// 组件运行中超过该时长没有进展即视为停滞（秒）
+    pub stall_timeout_secs: u64,
+    /// 检测到停滞后是否自动重启该组件
+    pub auto_restart: bool,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            stall_timeout_secs: 10,
+            auto_restart: false,
+        }
+    }
+}
+
+/// 看门狗检测到组件停滞时推送的事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WatchdogEvent {
+    /// 停滞的组件
+    pub component: WatchdogComponent,
+    /// 距上次心跳的时长（秒）
+    pub stalled_secs: u64,
+    /// 是否已自动重启
+    pub restarted: bool,
+    /// 描述信息
+    pub message: String,
+    /// 检测时间戳（毫秒）
+    pub timestamp: u64,
+}
+
+/// 数据处理状态枚举
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ProcessingStatus {
     /// 空闲状态
     Idle,
@@ -184,6 +2256,7 @@ pub enum ProcessingStatus {
 
 /// 系统性能指标
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
 pub struct PerformanceMetrics {
     /// 数据处理速率 (点/秒)
     pub processing_rate: f64,
@@ -195,10 +2268,48 @@ pub struct PerformanceMetrics {
     pub queue_length: usize,
     /// 压缩后数据大小减少百分比
     pub compression_ratio_achieved: f64,
+    /// 串口采样到达速率（采样/秒）
+    pub samples_per_second: f64,
+    /// 串口字节接收速率（字节/秒）
+    pub bytes_per_second: f64,
+    /// 采样从进入原始数据队列到处理完成的平均延迟（毫秒）
+    pub average_latency_ms: f64,
+    /// 最近采样中的最大处理延迟（毫秒）
+    pub max_latency_ms: f64,
+    /// 处理器启动以来丢弃的采样数，包括反压策略丢弃和原始数据队列溢出
+    pub dropped_samples: u64,
+}
+
+/// 数据处理跟不上采样速率时的反压策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    /// 积压超过高水位时一次丢弃最早的采样，直接处理接近实时的数据
+    #[default]
+    DropOldest,
+    /// 积压期间每两个采样只处理一个，积压回落到低水位后恢复
+    Downsample,
+}
+
+/// 采样丢弃警告，看门狗每秒检查一次，有新的丢弃时推送
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct SampleDropWarning {
+    /// 当前反压策略
+    pub policy: BackpressurePolicy,
+    /// 上次警告以来丢弃的采样数
+    pub dropped: u64,
+    /// 处理器启动以来丢弃的采样数
+    pub total_dropped: u64,
+    /// 当前原始数据队列长度
+    pub queue_length: usize,
+    /// 时间戳（毫秒）
+    pub timestamp: u64,
 }
 
 /// 实时数据包装器
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
 pub struct RealtimeDataPacket {
     /// 处理后的体征数据
     pub vital_signs: ProcessedVitalSigns,
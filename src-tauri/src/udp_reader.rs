@@ -0,0 +1,171 @@
+//! UDP数据源模块
+//!
+//! 无线网桥把采样帧以UDP（通常为组播）发送，每个数据报包含一个或多个完整帧。
+//! 读取线程接收数据报并交给帧解析器解码，帧序号不连续时计入丢包统计。
+
+use crate::channels::ChannelRegistry;
+use crate::framing::{self, deliver_frame, AssembledFrame, FrameAssembler};
+use crate::serial_manager::StreamCounters;
+use crate::types::{DataQueue, SerialStatus, UdpConfig};
+use crate::watchdog::Heartbeat;
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// 接收超时，用于定期检查停止信号
+const RECV_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 单个数据报的最大长度（字节）
+const MAX_DATAGRAM_LEN: usize = 65_536;
+
+/// 验证UDP数据源配置
+pub fn validate_config(config: &UdpConfig) -> Result<(), String> {
+    config
+        .bind_address
+        .parse::<SocketAddr>()
+        .map_err(|_| format!("无效的绑定地址: {}", config.bind_address))?;
+    if let Some(group) = &config.multicast_group {
+        let group: Ipv4Addr = group
+            .parse()
+            .map_err(|_| format!("无效的组播地址: {}", group))?;
+        if !group.is_multicast() {
+            return Err(format!("{} 不是组播地址", group));
+        }
+    }
+    if let Some(interface) = &config.multicast_interface {
+        interface
+            .parse::<Ipv4Addr>()
+            .map_err(|_| format!("无效的网卡地址: {}", interface))?;
+    }
+    Ok(())
+}
+
+pub struct UdpReader {
+    config: UdpConfig,
+    data_queue: DataQueue,
+    channel_registry: Arc<Mutex<ChannelRegistry>>,
+    counters: Arc<StreamCounters>,
+    status: Arc<Mutex<SerialStatus>>,
+    stop_flag: Arc<AtomicBool>,
+    heartbeat: Heartbeat,
+}
+
+impl UdpReader {
+    pub fn new(
+        config: UdpConfig,
+        data_queue: DataQueue,
+        channel_registry: Arc<Mutex<ChannelRegistry>>,
+        counters: Arc<StreamCounters>,
+        status: Arc<Mutex<SerialStatus>>,
+    ) -> Self {
+        println!("[UdpReader] 初始化，绑定地址={}", config.bind_address);
+        Self {
+            config,
+            data_queue,
+            channel_registry,
+            counters,
+            status,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            heartbeat: Heartbeat::new(),
+        }
+    }
+
+    /// 接收线程的心跳，每轮接收（包括超时）更新一次
+    ///
+    /// 网桥没有发送数据时线程仍然正常运行，数据是否到达由数据流健康状态反映
+    pub fn heartbeat(&self) -> Heartbeat {
+        self.heartbeat.clone()
+    }
+
+    /// 绑定套接字并加入组播组
+    fn open_socket(&self) -> Result<UdpSocket, String> {
+        validate_config(&self.config)?;
+        let socket = UdpSocket::bind(&self.config.bind_address)
+            .map_err(|e| format!("无法绑定UDP地址 {}: {}", self.config.bind_address, e))?;
+        if let Some(group) = &self.config.multicast_group {
+            let group: Ipv4Addr = group.parse().map_err(|_| "无效的组播地址".to_string())?;
+            let interface = match &self.config.multicast_interface {
+                Some(interface) => interface
+                    .parse()
+                    .map_err(|_| "无效的网卡地址".to_string())?,
+                None => Ipv4Addr::UNSPECIFIED,
+            };
+            socket
+                .join_multicast_v4(&group, &interface)
+                .map_err(|e| format!("加入组播组 {} 失败: {}", group, e))?;
+            println!("[UdpReader] 已加入组播组 {}", group);
+        }
+        socket
+            .set_read_timeout(Some(RECV_TIMEOUT))
+            .map_err(|e| format!("设置接收超时失败: {}", e))?;
+        Ok(socket)
+    }
+
+    pub fn start(&self) -> Result<(), String> {
+        let socket = self.open_socket()?;
+        println!("[UdpReader] 启动接收线程: {}", self.config.bind_address);
+
+        let mut assembler = FrameAssembler::new(framing::create_parser(self.config.parser));
+        let stop_flag = self.stop_flag.clone();
+        let data_queue = self.data_queue.clone();
+        let channel_registry = self.channel_registry.clone();
+        let counters = self.counters.clone();
+        let status = self.status.clone();
+        let heartbeat = self.heartbeat.clone();
+        heartbeat.beat();
+
+        thread::spawn(move || {
+            let mut datagram = vec![0u8; MAX_DATAGRAM_LEN];
+            // 上一帧的序号，用于统计丢包
+            let mut last_sequence: Option<u64> = None;
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                heartbeat.beat();
+                let len = match socket.recv_from(&mut datagram) {
+                    Ok((len, _)) => len,
+                    Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                        continue;
+                    }
+                    Err(e) => {
+                        eprintln!("[UdpReader][线程] 接收失败，退出接收线程: {}", e);
+                        *status.lock().unwrap() =
+                            SerialStatus::Error(format!("UDP接收失败: {}", e));
+                        break;
+                    }
+                };
+                counters.record_bytes(len);
+
+                // 数据报边界即帧边界，末尾不完整的内容作为最后一帧
+                assembler.push(&datagram[..len]);
+                let mut frames = Vec::new();
+                while let Some(unit) = assembler.next_frame() {
+                    match unit {
+                        AssembledFrame::Frame(frame) => frames.push(frame),
+                        AssembledFrame::Discarded(len) => counters.record_resync(len),
+                    }
+                }
+                let remaining = assembler.take_remaining();
+                if !remaining.is_empty() {
+                    frames.push(remaining);
+                }
+
+                let registry = channel_registry.lock().unwrap();
+                for frame in frames {
+                    let parsed = assembler.decode(&frame, &registry);
+                    deliver_frame(parsed, &counters, &mut last_sequence, &data_queue);
+                }
+            }
+            println!("[UdpReader][线程] 接收线程安全退出");
+        });
+
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        println!("[UdpReader] 停止接收");
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
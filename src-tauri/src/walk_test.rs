@@ -0,0 +1,383 @@
+//! 六分钟步行试验模块
+//!
+//! 按配置的时长（标准为6分钟）计时，操作员在患者每走完一圈时标记，
+//! 期间连续记录心率和血氧并检测血氧最低值。试验前后可录入Borg评分，
+//! 试验结束后生成标准化结果，保存到患者记录并可导出为PDF报告。
+
+use crate::types::{
+    BorgScore, BorgTiming, WalkLap, WalkSample, WalkTestConfig, WalkTestStatus, WalkTestSummary,
+    WalkVitalSummary,
+};
+
+/// 血氧低于该值（%）的时间单独统计
+const LOW_SPO2_THRESHOLD: f64 = 90.0;
+
+/// 结果中心率和血氧采样的间隔（毫秒）
+const SAMPLE_INTERVAL_MS: u64 = 1000;
+
+/// 验证步行试验配置
+pub fn validate_config(config: &WalkTestConfig) -> Result<(), String> {
+    if !(60_000..=20 * 60_000).contains(&config.duration_ms) {
+        return Err("试验时长必须在1到20分钟之间".to_string());
+    }
+    if !config.lap_length_m.is_finite() || config.lap_length_m <= 0.0 {
+        return Err("每圈距离必须大于0".to_string());
+    }
+    if !config.desaturation_drop.is_finite() || config.desaturation_drop <= 0.0 {
+        return Err("血氧下降判定幅度必须大于0".to_string());
+    }
+    Ok(())
+}
+
+/// 验证Borg评分
+pub fn validate_borg(score: &BorgScore) -> Result<(), String> {
+    if ![score.dyspnea, score.fatigue]
+        .iter()
+        .all(|value| (0.0..=10.0).contains(value))
+    {
+        return Err("Borg评分必须在0到10之间".to_string());
+    }
+    Ok(())
+}
+
+/// 试验期间单项体征的统计
+#[derive(Debug, Clone, Default)]
+struct VitalTracker {
+    baseline: Option<f64>,
+    last: Option<f64>,
+    min: Option<(f64, u64)>,
+    max: Option<f64>,
+    sum: f64,
+    count: u64,
+}
+
+impl VitalTracker {
+    fn add(&mut self, value: f64, elapsed_ms: u64) {
+        self.baseline.get_or_insert(value);
+        self.last = Some(value);
+        if self.min.is_none_or(|(min, _)| value < min) {
+            self.min = Some((value, elapsed_ms));
+        }
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn summary(&self) -> WalkVitalSummary {
+        WalkVitalSummary {
+            baseline: self.baseline,
+            end: self.last,
+            min: self.min.map(|(value, _)| value),
+            max: self.max,
+            mean: (self.count > 0).then(|| self.sum / self.count as f64),
+            min_elapsed_ms: self.min.map(|(_, elapsed)| elapsed),
+        }
+    }
+}
+
+/// 一次步行试验的运行状态
+#[derive(Debug, Clone)]
+pub struct WalkTestSession {
+    config: WalkTestConfig,
+    test_id: String,
+    /// 试验开始时间戳，收到第一个采样时确定
+    started_at: Option<u64>,
+    /// 最近一个采样的时间戳
+    last_timestamp: u64,
+    /// 已标记、等待下一个采样记录时间的圈数
+    pending_laps: u32,
+    /// 已请求提前结束
+    stop_requested: bool,
+    stop_reason: Option<String>,
+    /// 结束时间戳，试验进行中为None
+    ended_at: Option<u64>,
+    laps: Vec<WalkLap>,
+    heart_rate: VitalTracker,
+    spo2: VitalTracker,
+    spo2_below_90_ms: u64,
+    samples: Vec<WalkSample>,
+    borg_pre: Option<BorgScore>,
+    borg_post: Option<BorgScore>,
+}
+
+impl WalkTestSession {
+    /// 创建试验，配置需先通过 `validate_config` 验证
+    pub fn new(config: WalkTestConfig) -> Self {
+        Self {
+            config,
+            test_id: chrono::Local::now()
+                .format("6MWT-%Y%m%d-%H%M%S")
+                .to_string(),
+            started_at: None,
+            last_timestamp: 0,
+            pending_laps: 0,
+            stop_requested: false,
+            stop_reason: None,
+            ended_at: None,
+            laps: Vec::new(),
+            heart_rate: VitalTracker::default(),
+            spo2: VitalTracker::default(),
+            spo2_below_90_ms: 0,
+            samples: Vec::new(),
+            borg_pre: None,
+            borg_post: None,
+        }
+    }
+
+    /// 试验是否已结束
+    pub fn is_complete(&self) -> bool {
+        self.ended_at.is_some()
+    }
+
+    /// 标记完成一圈
+    pub fn record_lap(&mut self) -> Result<(), String> {
+        if self.is_complete() {
+            return Err("步行试验已结束".to_string());
+        }
+        self.pending_laps += 1;
+        Ok(())
+    }
+
+    /// 提前结束试验
+    pub fn stop(&mut self, reason: &str) -> Result<(), String> {
+        if self.is_complete() {
+            return Err("步行试验已结束".to_string());
+        }
+        if reason.trim().is_empty() {
+            return Err("提前结束必须填写原因".to_string());
+        }
+        self.stop_requested = true;
+        self.stop_reason = Some(reason.trim().to_string());
+        Ok(())
+    }
+
+    /// 录入Borg评分，试验后的评分只能在试验结束后录入
+    pub fn record_borg(&mut self, timing: BorgTiming, score: BorgScore) -> Result<(), String> {
+        validate_borg(&score)?;
+        match timing {
+            BorgTiming::Pre => self.borg_pre = Some(score),
+            BorgTiming::Post if !self.is_complete() => {
+                return Err("试验结束后才能录入试验后的Borg评分".to_string());
+            }
+            BorgTiming::Post => self.borg_post = Some(score),
+        }
+        Ok(())
+    }
+
+    /// 处理一个采样
+    ///
+    /// # 参数
+    /// * `heart_rate` - 当前有效心率，无效时为None
+    /// * `spo2` - 当前有效血氧，无效时为None
+    /// * `timestamp` - 当前时间戳（毫秒）
+    ///
+    /// # 返回值
+    /// 试验在本采样结束时返回true
+    pub fn update(&mut self, heart_rate: Option<f64>, spo2: Option<f64>, timestamp: u64) -> bool {
+        if self.is_complete() {
+            return false;
+        }
+        let started_at = *self.started_at.get_or_insert(timestamp);
+        let elapsed_ms = timestamp.saturating_sub(started_at);
+        let interval_ms = if self.last_timestamp > 0 {
+            timestamp.saturating_sub(self.last_timestamp)
+        } else {
+            0
+        };
+        self.last_timestamp = timestamp;
+
+        for _ in 0..std::mem::take(&mut self.pending_laps) {
+            let previous = self.laps.last().map_or(0, |lap| lap.elapsed_ms);
+            self.laps.push(WalkLap {
+                number: self.laps.len() as u32 + 1,
+                timestamp,
+                elapsed_ms,
+                split_ms: elapsed_ms - previous,
+            });
+        }
+
+        let heart_rate = heart_rate.filter(|&hr| hr > 0.0);
+        let spo2 = spo2.filter(|&value| value > 0.0);
+        if let Some(hr) = heart_rate {
+            self.heart_rate.add(hr, elapsed_ms);
+        }
+        if let Some(value) = spo2 {
+            self.spo2.add(value, elapsed_ms);
+            if value < LOW_SPO2_THRESHOLD {
+                self.spo2_below_90_ms += interval_ms;
+            }
+        }
+        if self
+            .samples
+            .last()
+            .is_none_or(|s| elapsed_ms >= s.elapsed_ms + SAMPLE_INTERVAL_MS)
+        {
+            self.samples.push(WalkSample {
+                elapsed_ms,
+                heart_rate,
+                spo2,
+            });
+        }
+
+        if elapsed_ms < self.config.duration_ms && !self.stop_requested {
+            return false;
+        }
+        self.ended_at = Some(timestamp);
+        println!(
+            "[WalkTest] 步行试验结束，{}圈，用时{}秒",
+            self.laps.len(),
+            elapsed_ms / 1000
+        );
+        true
+    }
+
+    /// 生成试验结果，试验进行中返回None
+    ///
+    /// # 参数
+    /// * `partial_distance_m` - 最后不足一圈的距离（米）
+    pub fn summary(&self, partial_distance_m: f64) -> Option<WalkTestSummary> {
+        let ended_at = self.ended_at?;
+        let started_at = self.started_at.unwrap_or(ended_at);
+        let spo2 = self.spo2.summary();
+        let desaturation = spo2
+            .baseline
+            .zip(spo2.min)
+            .is_some_and(|(baseline, min)| baseline - min >= self.config.desaturation_drop);
+        Some(WalkTestSummary {
+            test_id: self.test_id.clone(),
+            patient_id: None,
+            started_at,
+            ended_at,
+            duration_ms: ended_at - started_at,
+            stop_reason: self.stop_reason.clone(),
+            laps: self.laps.clone(),
+            partial_distance_m,
+            total_distance_m: self.laps.len() as f64 * self.config.lap_length_m
+                + partial_distance_m,
+            heart_rate: self.heart_rate.summary(),
+            spo2,
+            spo2_below_90_ms: self.spo2_below_90_ms,
+            desaturation,
+            borg_pre: self.borg_pre,
+            borg_post: self.borg_post,
+            samples: self.samples.clone(),
+            config: self.config.clone(),
+        })
+    }
+
+    /// 获取试验的当前状态
+    pub fn status(&self) -> WalkTestStatus {
+        let elapsed_ms = match (self.started_at, self.ended_at) {
+            (Some(start), Some(end)) => end - start,
+            (Some(start), None) => self.last_timestamp.saturating_sub(start),
+            (None, _) => 0,
+        };
+        WalkTestStatus {
+            active: !self.is_complete(),
+            elapsed_ms,
+            remaining_ms: if self.is_complete() {
+                0
+            } else {
+                self.config.duration_ms.saturating_sub(elapsed_ms)
+            },
+            laps: self.laps.clone(),
+            spo2_nadir: self.spo2.min.map(|(value, _)| value),
+            max_heart_rate: self.heart_rate.max,
+            summary: self.summary(0.0),
+        }
+    }
+}
+
+/// 格式化可能缺失的数值
+fn format_value(value: Option<f64>, unit: &str) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.1} {}", v, unit))
+}
+
+/// 生成PDF报告的文字内容
+///
+/// PDF标准字体不含中文字形，报告使用英文。
+pub fn report_lines(summary: &WalkTestSummary) -> Vec<String> {
+    let time = |timestamp: u64| {
+        chrono::DateTime::from_timestamp_millis(timestamp as i64)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default()
+    };
+    let vital = |name: &str, v: &WalkVitalSummary, unit: &str| {
+        format!(
+            "{}: baseline {}, end {}, min {}, max {}, mean {}",
+            name,
+            format_value(v.baseline, unit),
+            format_value(v.end, unit),
+            format_value(v.min, unit),
+            format_value(v.max, unit),
+            format_value(v.mean, unit)
+        )
+    };
+    let borg = |score: Option<BorgScore>| {
+        score.map_or("-".to_string(), |s| {
+            format!("dyspnea {:.1}, fatigue {:.1}", s.dyspnea, s.fatigue)
+        })
+    };
+
+    let mut lines = vec![
+        format!("Test ID: {}", summary.test_id),
+        format!(
+            "Patient ID: {}",
+            summary.patient_id.as_deref().unwrap_or("-")
+        ),
+        format!("Started: {}", time(summary.started_at)),
+        format!("Ended: {}", time(summary.ended_at)),
+        format!(
+            "Walking time: {}:{:02}",
+            summary.duration_ms / 60_000,
+            summary.duration_ms / 1000 % 60
+        ),
+        format!(
+            "Stopped early: {}",
+            summary.stop_reason.as_deref().unwrap_or("no")
+        ),
+        String::new(),
+        format!(
+            "Distance: {:.1} m ({} laps x {:.1} m + {:.1} m)",
+            summary.total_distance_m,
+            summary.laps.len(),
+            summary.config.lap_length_m,
+            summary.partial_distance_m
+        ),
+        vital("Heart rate", &summary.heart_rate, "bpm"),
+        vital("SpO2", &summary.spo2, "%"),
+        format!(
+            "SpO2 nadir at: {}",
+            summary
+                .spo2
+                .min_elapsed_ms
+                .map_or("-".to_string(), |ms| format!("{:.0} s", ms as f64 / 1000.0))
+        ),
+        format!(
+            "Time with SpO2 < 90%: {:.0} s",
+            summary.spo2_below_90_ms as f64 / 1000.0
+        ),
+        format!(
+            "Exercise desaturation (drop >= {:.0}%): {}",
+            summary.config.desaturation_drop,
+            if summary.desaturation { "yes" } else { "no" }
+        ),
+        format!("Borg before: {}", borg(summary.borg_pre)),
+        format!("Borg after: {}", borg(summary.borg_post)),
+        String::new(),
+        "Laps:".to_string(),
+    ];
+    lines.extend(summary.laps.iter().map(|lap| {
+        format!(
+            "  #{:<3} at {:>6.1} s   split {:>5.1} s",
+            lap.number,
+            lap.elapsed_ms as f64 / 1000.0,
+            lap.split_ms as f64 / 1000.0
+        )
+    }));
+    lines
+}
@@ -0,0 +1,155 @@
+//! 线程看门狗模块
+//!
+//! 串口读取线程和数据处理线程在每次取得进展时更新各自的心跳时间戳，
+//! 看门狗定期检查心跳，组件运行中超过配置的时长没有心跳即判定为停滞。
+//! 每次停滞只报告一次，心跳恢复后重新开始监控。
+
+use crate::types::{WatchdogComponent, WatchdogConfig, WatchdogEvent};
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// 等待线程退出时检查的间隔
+const JOIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 验证看门狗配置
+pub fn validate_config(config: &WatchdogConfig) -> Result<(), String> {
+    if !(1..=300).contains(&config.stall_timeout_secs) {
+        return Err("停滞超时必须在1到300秒之间".to_string());
+    }
+    Ok(())
+}
+
+/// 当前时间戳（毫秒）
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// 从线程panic的负载中取出描述信息
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "未知错误".to_string()
+    }
+}
+
+/// 等待线程退出，超过 `timeout` 仍未退出时放弃等待，线程留在后台继续运行
+///
+/// # 参数
+/// * `handle` - 线程句柄
+/// * `timeout` - 最长等待时间
+/// * `name` - 线程名称，用于错误信息
+///
+/// # 返回值
+/// 线程正常退出时返回Ok；超时未退出或线程panic时返回错误信息
+pub fn join_with_timeout(
+    handle: JoinHandle<()>,
+    timeout: Duration,
+    name: &str,
+) -> Result<(), String> {
+    let deadline = Instant::now() + timeout;
+    while !handle.is_finished() {
+        if Instant::now() >= deadline {
+            return Err(format!("{}在{}毫秒内未退出", name, timeout.as_millis()));
+        }
+        thread::sleep(JOIN_POLL_INTERVAL);
+    }
+    handle
+        .join()
+        .map_err(|payload| format!("{}异常退出: {}", name, panic_message(payload.as_ref())))
+}
+
+/// 线程心跳，记录最近一次取得进展的时间戳（毫秒）
+#[derive(Debug, Clone)]
+pub struct Heartbeat(Arc<AtomicU64>);
+
+impl Heartbeat {
+    /// 创建心跳，初始时间为当前时间
+    pub fn new() -> Self {
+        Self(Arc::new(AtomicU64::new(now_millis())))
+    }
+
+    /// 记录一次进展
+    pub fn beat(&self) {
+        self.0.store(now_millis(), Ordering::Relaxed);
+    }
+
+    /// 最近一次进展的时间戳（毫秒）
+    pub fn last_beat(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 看门狗，跟踪各组件的停滞状态
+#[derive(Debug, Default)]
+pub struct Watchdog {
+    /// 已报告停滞的组件及其停滞时的心跳时间戳
+    stalled: BTreeMap<WatchdogComponent, u64>,
+}
+
+impl Watchdog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 检查一个组件的心跳
+    ///
+    /// # 参数
+    /// * `component` - 组件
+    /// * `heartbeat` - 组件的心跳，组件未运行时为None
+    /// * `config` - 看门狗配置
+    ///
+    /// # 返回值
+    /// 组件刚进入停滞时返回停滞事件，`restarted` 由调用方在重启后填写
+    pub fn check(
+        &mut self,
+        component: WatchdogComponent,
+        heartbeat: Option<&Heartbeat>,
+        config: &WatchdogConfig,
+    ) -> Option<WatchdogEvent> {
+        let Some(heartbeat) = heartbeat.filter(|_| config.enabled) else {
+            self.stalled.remove(&component);
+            return None;
+        };
+
+        let now = now_millis();
+        let last_beat = heartbeat.last_beat();
+        let stalled_secs = now.saturating_sub(last_beat) / 1000;
+        if stalled_secs < config.stall_timeout_secs {
+            self.stalled.remove(&component);
+            return None;
+        }
+        // 同一次停滞只报告一次
+        if self.stalled.get(&component) == Some(&last_beat) {
+            return None;
+        }
+        self.stalled.insert(component, last_beat);
+
+        let name = match component {
+            WatchdogComponent::SerialReader => "串口读取线程",
+            WatchdogComponent::DataProcessor => "数据处理线程",
+        };
+        Some(WatchdogEvent {
+            component,
+            stalled_secs,
+            restarted: false,
+            message: format!("{}已{}秒没有进展", name, stalled_secs),
+            timestamp: now,
+        })
+    }
+}
@@ -0,0 +1,449 @@
+//! Webhook通知模块
+//!
+//! 报警激活/解除和监护会话开始/结束时向配置的URL发送HTTP POST请求，
+//! 用于通知Slack、Teams或自定义服务。请求在后台线程中发送，失败时按指数退避重试，
+//! 每次投递的状态记录在投递日志中。
+//!
+//! 请求体默认为事件的JSON；配置了 `payload_template` 时使用模板，模板中的
+//! `{{event}}`、`{{timestamp}}`、`{{session_id}}`、`{{condition}}`、`{{vital}}`、
+//! `{{priority}}`、`{{message}}`、`{{value}}`、`{{limit}}` 被替换为转义后可直接放入
+//! JSON字符串的值，例如 `{"text": "{{message}}（{{value}}）"}`。
+
+use crate::alarms::AlarmListener;
+use crate::ipc_schema;
+use crate::types::Alarm;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+/// 单次请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 重试间隔上限
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// 投递日志保留的记录数
+const DELIVERY_LOG_CAPACITY: usize = 200;
+
+/// 触发Webhook的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    AlarmRaised,
+    AlarmCleared,
+    SessionStarted,
+    SessionStopped,
+}
+
+impl WebhookEvent {
+    fn name(self) -> &'static str {
+        match self {
+            WebhookEvent::AlarmRaised => "alarm_raised",
+            WebhookEvent::AlarmCleared => "alarm_cleared",
+            WebhookEvent::SessionStarted => "session_started",
+            WebhookEvent::SessionStopped => "session_stopped",
+        }
+    }
+}
+
+/// Webhook配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WebhookConfig {
+    /// Webhook ID，新增时留空自动生成
+    #[serde(default)]
+    pub id: String,
+    /// 显示名称
+    pub name: String,
+    /// 目标URL，必须为http或https
+    pub url: String,
+    /// 附加的请求头，例如认证令牌
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// 触发的事件
+    pub events: Vec<WebhookEvent>,
+    /// 请求体模板，省略时发送事件的JSON
+    #[serde(default)]
+    pub payload_template: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 失败后的最大重试次数
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+/// 投递状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// 等待发送或等待重试
+    Pending,
+    Delivered,
+    /// 重试次数用尽后仍失败
+    Failed,
+}
+
+/// 一次Webhook投递的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "legacy-field-names"), serde(rename_all = "camelCase"))]
+pub struct WebhookDelivery {
+    pub id: u64,
+    pub webhook_id: String,
+    pub event: WebhookEvent,
+    /// 事件时间戳（毫秒）
+    pub timestamp: u64,
+    /// 已尝试次数
+    pub attempts: u32,
+    pub status: DeliveryStatus,
+    /// 最近一次请求的HTTP状态码
+    pub http_status: Option<u16>,
+    /// 最近一次失败的原因
+    pub error: Option<String>,
+}
+
+/// 默认请求体
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    event: WebhookEvent,
+    timestamp: u64,
+    session_id: Option<&'a str>,
+    alarm: Option<&'a Alarm>,
+}
+
+/// 验证Webhook配置
+fn validate_webhook(webhook: &WebhookConfig) -> Result<(), String> {
+    if webhook.name.trim().is_empty() {
+        return Err("Webhook名称不能为空".to_string());
+    }
+    if !(webhook.url.starts_with("http://") || webhook.url.starts_with("https://")) {
+        return Err(format!(
+            "Webhook地址必须以http://或https://开头: {}",
+            webhook.url
+        ));
+    }
+    if webhook.events.is_empty() {
+        return Err(format!("Webhook {} 至少选择一个事件", webhook.name));
+    }
+    if webhook.max_retries > 10 {
+        return Err("最大重试次数不能超过10".to_string());
+    }
+    Ok(())
+}
+
+/// 转义为可放入JSON字符串的内容（不含两侧引号）
+pub(crate) fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// 按模板生成请求体
+fn render_template(template: &str, payload: &WebhookPayload) -> String {
+    let alarm = payload.alarm;
+    let values = [
+        ("event", payload.event.name().to_string()),
+        ("timestamp", payload.timestamp.to_string()),
+        (
+            "session_id",
+            payload.session_id.unwrap_or_default().to_string(),
+        ),
+        (
+            "condition",
+            alarm.map(|a| a.condition.clone()).unwrap_or_default(),
+        ),
+        ("vital", alarm.map(|a| a.vital.clone()).unwrap_or_default()),
+        (
+            "priority",
+            alarm
+                .map(|a| format!("{:?}", a.priority).to_lowercase())
+                .unwrap_or_default(),
+        ),
+        (
+            "message",
+            alarm.map(|a| a.message.clone()).unwrap_or_default(),
+        ),
+        (
+            "value",
+            alarm.map(|a| a.value.to_string()).unwrap_or_default(),
+        ),
+        (
+            "limit",
+            alarm.map(|a| a.limit.to_string()).unwrap_or_default(),
+        ),
+    ];
+    values
+        .iter()
+        .fold(template.to_string(), |body, (name, value)| {
+            body.replace(&format!("{{{{{}}}}}", name), &json_escape(value))
+        })
+}
+
+/// 待发送的请求
+struct DeliveryJob {
+    delivery_id: u64,
+    webhook: WebhookConfig,
+    body: String,
+    /// 下次发送的时间
+    due: Instant,
+}
+
+/// Webhook分发器
+///
+/// 配置保存在数据目录的 `webhooks.json` 中。`fire` 只生成请求并交给后台线程，
+/// 可以在数据处理线程中调用。
+pub struct WebhookDispatcher {
+    config_file: PathBuf,
+    webhooks: Mutex<Vec<WebhookConfig>>,
+    deliveries: Arc<Mutex<VecDeque<WebhookDelivery>>>,
+    sender: Sender<DeliveryJob>,
+    next_id: AtomicU64,
+}
+
+impl WebhookDispatcher {
+    pub fn new(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("无法获取应用数据目录: {}", e))?;
+
+        let data_dir = app_data_dir.join("vital-signs");
+        if !data_dir.exists() {
+            fs::create_dir_all(&data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+        }
+
+        let config_file = data_dir.join("webhooks.json");
+        let webhooks = if config_file.exists() {
+            let content = fs::read_to_string(&config_file)
+                .map_err(|e| format!("读取Webhook配置失败: {}", e))?;
+            ipc_schema::from_stored_json(&content)
+                .map_err(|e| format!("解析Webhook配置失败: {}", e))?
+        } else {
+            Vec::new()
+        };
+
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+        let deliveries = Arc::new(Mutex::new(VecDeque::new()));
+        let (sender, receiver) = mpsc::channel();
+        let thread_deliveries = deliveries.clone();
+        thread::spawn(move || run_deliveries(client, receiver, thread_deliveries));
+
+        Ok(Self {
+            config_file,
+            webhooks: Mutex::new(webhooks),
+            deliveries,
+            sender,
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    /// 替换全部Webhook配置，ID为空的配置自动生成ID
+    pub fn set_webhooks(&self, webhooks: Vec<WebhookConfig>) -> Result<Vec<WebhookConfig>, String> {
+        let mut webhooks = webhooks;
+        for webhook in &mut webhooks {
+            validate_webhook(webhook)?;
+            if webhook.id.is_empty() {
+                webhook.id = format!("{:016x}", rand::random::<u64>());
+            }
+        }
+        let json = serde_json::to_string_pretty(&webhooks)
+            .map_err(|e| format!("序列化Webhook配置失败: {}", e))?;
+        fs::write(&self.config_file, json).map_err(|e| format!("保存Webhook配置失败: {}", e))?;
+        *self.webhooks.lock().unwrap() = webhooks.clone();
+        println!("[Webhook] 已保存{}个Webhook", webhooks.len());
+        Ok(webhooks)
+    }
+
+    /// 获取全部Webhook配置
+    pub fn get_webhooks(&self) -> Vec<WebhookConfig> {
+        self.webhooks.lock().unwrap().clone()
+    }
+
+    /// 获取最近的投递记录，最新的在前
+    pub fn get_deliveries(&self, limit: usize) -> Vec<WebhookDelivery> {
+        let deliveries = self.deliveries.lock().unwrap();
+        deliveries.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// 触发事件，向订阅该事件的全部已启用Webhook发送请求
+    ///
+    /// # 参数
+    /// * `event` - 事件类型
+    /// * `session_id` - 相关的监护会话ID
+    /// * `alarm` - 报警事件的报警
+    pub fn fire(&self, event: WebhookEvent, session_id: Option<&str>, alarm: Option<&Alarm>) {
+        let payload = WebhookPayload {
+            event,
+            timestamp: chrono::Utc::now().timestamp_millis().max(0) as u64,
+            session_id,
+            alarm,
+        };
+        let webhooks = self.webhooks.lock().unwrap();
+        for webhook in webhooks
+            .iter()
+            .filter(|w| w.enabled && w.events.contains(&event))
+        {
+            let body = match &webhook.payload_template {
+                Some(template) => render_template(template, &payload),
+                None => match serde_json::to_string(&payload) {
+                    Ok(body) => body,
+                    Err(e) => {
+                        eprintln!("[Webhook] 序列化事件失败: {}", e);
+                        continue;
+                    }
+                },
+            };
+            let delivery_id = self.next_id.fetch_add(1, Ordering::Relaxed);
+            let mut deliveries = self.deliveries.lock().unwrap();
+            if deliveries.len() >= DELIVERY_LOG_CAPACITY {
+                deliveries.pop_front();
+            }
+            deliveries.push_back(WebhookDelivery {
+                id: delivery_id,
+                webhook_id: webhook.id.clone(),
+                event,
+                timestamp: payload.timestamp,
+                attempts: 0,
+                status: DeliveryStatus::Pending,
+                http_status: None,
+                error: None,
+            });
+            drop(deliveries);
+            let _ = self.sender.send(DeliveryJob {
+                delivery_id,
+                webhook: webhook.clone(),
+                body,
+                due: Instant::now(),
+            });
+        }
+    }
+}
+
+/// 发送一次请求，返回HTTP状态码或失败原因
+fn send_request(
+    client: &reqwest::blocking::Client,
+    job: &DeliveryJob,
+) -> (Option<u16>, Option<String>) {
+    let mut request = client.post(&job.webhook.url);
+    let has_content_type = job
+        .webhook
+        .headers
+        .keys()
+        .any(|name| name.eq_ignore_ascii_case("content-type"));
+    if !has_content_type {
+        request = request.header("Content-Type", "application/json");
+    }
+    for (name, value) in &job.webhook.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    match request.body(job.body.clone()).send() {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+        Ok(response) => (
+            Some(response.status().as_u16()),
+            Some(format!("服务端返回 {}", response.status())),
+        ),
+        Err(e) => (None, Some(e.to_string())),
+    }
+}
+
+/// 投递线程：发送请求，失败的请求按指数退避等待后重试
+fn run_deliveries(
+    client: reqwest::blocking::Client,
+    jobs: Receiver<DeliveryJob>,
+    deliveries: Arc<Mutex<VecDeque<WebhookDelivery>>>,
+) {
+    let mut retries: Vec<DeliveryJob> = Vec::new();
+    loop {
+        let now = Instant::now();
+        let timeout = retries
+            .iter()
+            .map(|job| job.due.saturating_duration_since(now))
+            .min()
+            .unwrap_or(Duration::from_secs(3600));
+        match jobs.recv_timeout(timeout) {
+            Ok(job) => retries.push(job),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        let now = Instant::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = retries.drain(..).partition(|job| job.due <= now);
+        retries = waiting;
+        for mut job in due {
+            let (http_status, error) = send_request(&client, &job);
+            let mut log = deliveries.lock().unwrap();
+            let Some(delivery) = log.iter_mut().find(|d| d.id == job.delivery_id) else {
+                continue;
+            };
+            delivery.attempts += 1;
+            delivery.http_status = http_status;
+            delivery.error = error.clone();
+            let Some(error) = error else {
+                delivery.status = DeliveryStatus::Delivered;
+                continue;
+            };
+            if delivery.attempts > job.webhook.max_retries {
+                delivery.status = DeliveryStatus::Failed;
+                eprintln!(
+                    "[Webhook] {} 投递失败，已重试{}次: {}",
+                    job.webhook.name, job.webhook.max_retries, error
+                );
+                continue;
+            }
+            let delay =
+                Duration::from_secs(1 << (delivery.attempts - 1).min(6)).min(MAX_RETRY_DELAY);
+            job.due = Instant::now() + delay;
+            retries.push(job);
+        }
+    }
+    println!("[Webhook][线程] 投递线程安全退出");
+}
+
+/// 把报警事件转发给Webhook分发器
+pub struct WebhookAlarmListener {
+    dispatcher: Arc<WebhookDispatcher>,
+    session_id: String,
+}
+
+impl WebhookAlarmListener {
+    pub fn new(dispatcher: Arc<WebhookDispatcher>, session_id: String) -> Self {
+        Self {
+            dispatcher,
+            session_id,
+        }
+    }
+}
+
+impl AlarmListener for WebhookAlarmListener {
+    fn on_alarm_raised(&mut self, alarm: &Alarm) {
+        self.dispatcher.fire(
+            WebhookEvent::AlarmRaised,
+            Some(&self.session_id),
+            Some(alarm),
+        );
+    }
+
+    fn on_alarm_cleared(&mut self, alarm: &Alarm) {
+        self.dispatcher.fire(
+            WebhookEvent::AlarmCleared,
+            Some(&self.session_id),
+            Some(alarm),
+        );
+    }
+}
@@ -20,10 +20,10 @@ interface AnalysisState {
 /* ---- 新增：后端数据结构 ---- */
 interface VitalSignsData {
   timestamp: string;
-  body_temperature: number;
-  blood_oxygen: number;
-  heart_rate: number;
-  rr_interval: number;
+  bodyTemperature: number | null; // 设备未上报时为null
+  bloodOxygen: number | null; // 设备未上报时为null
+  heartRate: number;
+  rrInterval: number;
   systolic?: number;
   diastolic?: number;
 }
@@ -76,22 +76,22 @@ const AIAnalysis: React.FC<AIAnalysisProps> = ({ className = '' }) => {
       // 若血压未返回，单独获取
       if (raw.systolic === undefined || raw.diastolic === undefined) {
         try {
-          const [sys, dia] = await invoke<[number, number]>('get_blood_pressure');
-          raw.systolic = sys;
-          raw.diastolic = dia;
+          const bp = await invoke<{ systolic: number; diastolic: number; timestamp: number }>('get_blood_pressure');
+          raw.systolic = bp.systolic;
+          raw.diastolic = bp.diastolic;
         } catch {
           /* 忽略血压获取失败 */
         }
       }
 
       return {
-        heartRate: raw.heart_rate,
+        heartRate: raw.heartRate,
         bloodPressure: {
           systolic: raw.systolic ?? 0,
           diastolic: raw.diastolic ?? 0
         },
-        temperature: raw.body_temperature.toFixed(1),
-        oxygenSaturation: raw.blood_oxygen,
+        temperature: raw.bodyTemperature?.toFixed(1) ?? "--",
+        oxygenSaturation: raw.bloodOxygen ?? "--",
         timestamp: raw.timestamp
       };
     } catch {
@@ -9,8 +9,8 @@ const BloodOxygen: React.FC = () => {
   const { data, isLoading, error } = useVitalSigns();
 
   // 格式化血氧值显示
-  const formatOxygenValue = (value: number): string => {
-    return `${value.toFixed(1)}%`;
+  const formatOxygenValue = (value: number | null): string => {
+    return value != null ? `${value.toFixed(1)}%` : "--";
   };
 
   return (
@@ -29,7 +29,7 @@ const BloodOxygen: React.FC = () => {
           <div className="text-2xl font-bold text-red-500">--</div>
         ) : data ? (
           <div className="text-2xl font-bold text-red-500">
-            {formatOxygenValue(data.blood_oxygen)}
+            {formatOxygenValue(data.bloodOxygen)}
           </div>
         ) : (
           <div className="text-2xl font-bold text-red-500">--</div>
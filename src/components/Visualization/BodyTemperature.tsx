@@ -9,8 +9,8 @@ const BodyTemperature: React.FC = () => {
   const { data, isLoading, error } = useVitalSigns();
 
   // 格式化体温值显示
-  const formatTemperatureValue = (value: number): string => {
-    return `${value.toFixed(1)}℃`;
+  const formatTemperatureValue = (value: number | null): string => {
+    return value != null ? `${value.toFixed(1)}℃` : "--";
   };
 
   return (
@@ -29,7 +29,7 @@ const BodyTemperature: React.FC = () => {
           <div className="text-2xl font-bold text-green-500">--</div>
         ) : data ? (
           <div className="text-2xl font-bold text-green-500">
-            {formatTemperatureValue(data.body_temperature)}
+            {formatTemperatureValue(data.bodyTemperature)}
           </div>
         ) : (
           <div className="text-2xl font-bold text-green-500">--</div>
@@ -31,12 +31,12 @@ const ECG_Data: React.FC = () => {
     const hasValidData = data && !isLoading && !error;
     
     if (hasValidData) {
-      const calculatedRRInterval = calculateRRInterval(data.heart_rate);
+      const calculatedRRInterval = calculateRRInterval(data.heartRate);
       
       return [
         {
           label: "心率",
-          value: formatValue(data.heart_rate, 0),
+          value: formatValue(data.heartRate, 0),
           unit: "bpm"
         },
         {
@@ -10,12 +10,12 @@ const linearScore = (v: number, low: number, high: number, k: number) => {
 
 const calcScore = (vital: ReturnType<typeof useVitalSigns>["data"]) => {
   if (!vital) return NaN;
-  const { body_temperature, blood_oxygen, heart_rate, rr_interval, systolic, diastolic } = vital;
+  const { bodyTemperature, bloodOxygen, heartRate, rrInterval, systolic, diastolic } = vital;
 
-  const temp = linearScore(body_temperature, 36.3, 37.0, 60);
-  const spo2 = linearScore(blood_oxygen, 96, 100, 10);
-  const hr   = linearScore(heart_rate, 60, 90, 3);
-  const rr   = linearScore(rr_interval, 650, 950, 0.15);
+  const temp = bodyTemperature != null ? linearScore(bodyTemperature, 36.3, 37.0, 60) : 100;
+  const spo2 = bloodOxygen != null ? linearScore(bloodOxygen, 96, 100, 10) : 100;
+  const hr   = linearScore(heartRate, 60, 90, 3);
+  const rr   = linearScore(rrInterval, 650, 950, 0.15);
   const bp   =
     systolic && diastolic
       ? (linearScore(systolic, 100, 135, 1.5) + linearScore(diastolic, 65, 85, 1.5)) / 2
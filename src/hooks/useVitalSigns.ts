@@ -4,10 +4,10 @@ import { invoke } from '@tauri-apps/api/core';
 // 定义生命体征数据接口
 interface VitalSignsData {
   timestamp: string;
-  body_temperature: number;
-  blood_oxygen: number;
-  heart_rate: number;
-  rr_interval: number;
+  bodyTemperature: number | null; // 设备未上报时为null
+  bloodOxygen: number | null; // 设备未上报时为null
+  heartRate: number;
+  rrInterval: number;
   // 添加血压数据字段
   systolic?: number; // 高压
   diastolic?: number; // 低压
@@ -47,10 +47,10 @@ export const useVitalSigns = (refreshInterval: number = 1000): UseVitalSignsRetu
           
           // 获取血压数据
           try {
-            const [systolic, diastolic] = await invoke<[number, number]>('get_blood_pressure');
+            const bp = await invoke<{ systolic: number; diastolic: number; timestamp: number }>('get_blood_pressure');
             // 将血压数据添加到生命体征数据中
-            vitalData.systolic = systolic;
-            vitalData.diastolic = diastolic;
+            vitalData.systolic = bp.systolic;
+            vitalData.diastolic = bp.diastolic;
           } catch (bpErr) {
             console.warn('获取血压数据失败:', bpErr);
             // 血压数据获取失败不影响其他数据的显示
@@ -2,6 +2,24 @@ import React, { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { Save, Trash2, User, Phone, Heart, AlertTriangle, Plus, X } from 'lucide-react';
 
+interface CodedEntry {
+  id: string;
+  codeSystem?: string | null;
+  code?: string | null;
+  display: string;
+  onset?: string | null;
+  severity?: 'mild' | 'moderate' | 'severe' | null;
+}
+
+interface Medication {
+  id: string;
+  codeSystem?: string | null;
+  code?: string | null;
+  display: string;
+  dose?: string | null;
+  startedOn?: string | null;
+}
+
 interface PatientInfo {
   name: string;
   gender: "男" | "女";
@@ -10,11 +28,12 @@ interface PatientInfo {
   weight: number;
   phone: string;
   address: string;
-  emergency_contact: string;
-  blood_type: string;
-  allergies: string[];
-  medical_history: string[];
-  last_checkup: string;
+  emergencyContact: string;
+  bloodType: string;
+  allergies: CodedEntry[];
+  medicalHistory: CodedEntry[];
+  medications: Medication[];
+  lastCheckup: string;
 }
 
 const PatientInfoConfig: React.FC = () => {
@@ -26,11 +45,12 @@ const PatientInfoConfig: React.FC = () => {
     weight: 0,
     phone: '',
     address: '',
-    emergency_contact: '',
-    blood_type: 'A',
+    emergencyContact: '',
+    bloodType: 'A',
     allergies: [],
-    medical_history: [],
-    last_checkup: ''
+    medicalHistory: [],
+    medications: [],
+    lastCheckup: ''
   });
   
   const [newAllergy, setNewAllergy] = useState('');
@@ -82,11 +102,12 @@ const PatientInfoConfig: React.FC = () => {
         weight: 0,
         phone: '',
         address: '',
-        emergency_contact: '',
-        blood_type: 'A',
+        emergencyContact: '',
+        bloodType: 'A',
         allergies: [],
-        medical_history: [],
-        last_checkup: ''
+        medicalHistory: [],
+        medications: [],
+        lastCheckup: ''
       });
       showMessage('success', '患者信息已删除');
     } catch (error) {
@@ -103,10 +124,11 @@ const PatientInfoConfig: React.FC = () => {
   };
 
   const addAllergy = () => {
-    if (newAllergy.trim() && !patientInfo.allergies.includes(newAllergy.trim())) {
+    const display = newAllergy.trim();
+    if (display && !patientInfo.allergies.some(entry => entry.display === display)) {
       setPatientInfo(prev => ({
         ...prev,
-        allergies: [...prev.allergies, newAllergy.trim()]
+        allergies: [...prev.allergies, { id: '', display }]
       }));
       setNewAllergy('');
     }
@@ -123,7 +145,7 @@ const PatientInfoConfig: React.FC = () => {
     if (newMedicalHistory.trim()) {
       setPatientInfo(prev => ({
         ...prev,
-        medical_history: [...prev.medical_history, newMedicalHistory.trim()]
+        medicalHistory: [...prev.medicalHistory, { id: '', display: newMedicalHistory.trim() }]
       }));
       setNewMedicalHistory('');
     }
@@ -132,7 +154,7 @@ const PatientInfoConfig: React.FC = () => {
   const removeMedicalHistory = (index: number) => {
     setPatientInfo(prev => ({
       ...prev,
-      medical_history: prev.medical_history.filter((_, i) => i !== index)
+      medicalHistory: prev.medicalHistory.filter((_, i) => i !== index)
     }));
   };
 
@@ -196,8 +218,8 @@ const PatientInfoConfig: React.FC = () => {
           <div>
             <label className="block text-sm font-medium text-gray-700 mb-2">血型</label>
             <select
-              value={patientInfo.blood_type}
-              onChange={(e) => setPatientInfo(prev => ({ ...prev, blood_type: e.target.value }))}
+              value={patientInfo.bloodType}
+              onChange={(e) => setPatientInfo(prev => ({ ...prev, bloodType: e.target.value }))}
               className="w-full px-3 py-2 bg-white border border-gray-300 rounded-lg text-gray-800 focus:ring-2 focus:ring-blue-500 focus:border-transparent"
             >
               <option value="A">A型</option>
@@ -271,8 +293,8 @@ const PatientInfoConfig: React.FC = () => {
             <label className="block text-sm font-medium text-gray-700 mb-2">紧急联系人</label>
             <input
               type="text"
-              value={patientInfo.emergency_contact}
-              onChange={(e) => setPatientInfo(prev => ({ ...prev, emergency_contact: e.target.value }))}
+              value={patientInfo.emergencyContact}
+              onChange={(e) => setPatientInfo(prev => ({ ...prev, emergencyContact: e.target.value }))}
               className="w-full px-3 py-2 bg-white border border-gray-300 rounded-lg text-gray-800 focus:ring-2 focus:ring-blue-500 focus:border-transparent"
               placeholder="紧急联系人姓名和电话"
             />
@@ -320,8 +342,8 @@ const PatientInfoConfig: React.FC = () => {
           </div>
           <div className="flex flex-wrap gap-2">
             {patientInfo.allergies.map((allergy, index) => (
-              <span key={index} className="bg-red-100 text-red-700 px-3 py-1 rounded-full text-sm flex items-center gap-2">
-                {allergy}
+              <span key={allergy.id || index} className="bg-red-100 text-red-700 px-3 py-1 rounded-full text-sm flex items-center gap-2">
+                {allergy.display}
                 <button
                   onClick={() => removeAllergy(index)}
                   className="hover:text-red-900"
@@ -354,9 +376,9 @@ const PatientInfoConfig: React.FC = () => {
             </button>
           </div>
           <div className="space-y-2">
-            {patientInfo.medical_history.map((history, index) => (
-              <div key={index} className="bg-yellow-100 text-yellow-800 px-3 py-2 rounded-lg text-sm flex items-center justify-between">
-                <span>{history}</span>
+            {patientInfo.medicalHistory.map((history, index) => (
+              <div key={history.id || index} className="bg-yellow-100 text-yellow-800 px-3 py-2 rounded-lg text-sm flex items-center justify-between">
+                <span>{history.display}</span>
                 <button
                   onClick={() => removeMedicalHistory(index)}
                   className="hover:text-yellow-900"
@@ -373,8 +395,8 @@ const PatientInfoConfig: React.FC = () => {
           <label className="block text-sm font-medium text-gray-700 mb-2">最后检查时间</label>
           <input
             type="datetime-local"
-            value={patientInfo.last_checkup}
-            onChange={(e) => setPatientInfo(prev => ({ ...prev, last_checkup: e.target.value }))}
+            value={patientInfo.lastCheckup}
+            onChange={(e) => setPatientInfo(prev => ({ ...prev, lastCheckup: e.target.value }))}
             className="w-full px-3 py-2 bg-white border border-gray-300 rounded-lg text-gray-800 focus:ring-2 focus:ring-blue-500 focus:border-transparent"
           />
         </div>
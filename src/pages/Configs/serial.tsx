@@ -11,9 +11,16 @@ interface SerialPort {
   description: string;
 }
 
+type StreamHealth =
+  | { type: 'StreamingOk' }
+  | { type: 'NoData' }
+  | { type: 'Stalled'; data: { seconds: number } }
+  | { type: 'ParserRejectingData'; data: { rate: number } };
+
 interface SerialStatus {
   type: 'Connected' | 'Disconnected' | 'Error';
   data?: string;
+  streamHealth?: StreamHealth | null;
 }
 
 export default function SerialConfig() {
@@ -80,10 +87,15 @@ export default function SerialConfig() {
     setTesting(true);
     appendLog(`准备测试串口连接: ${selectedPort} @ ${baudRate}`);
     try {
-      await invoke('test_serial_connection', {
+      const status = await invoke<string>('test_serial_connection', {
         portName: selectedPort,
         baudRate: parseInt(baudRate),
       });
+      if (status === 'busy') {
+        appendLog(`串口 ${selectedPort} 正在使用中`);
+        alert('串口正在使用中，当前连接正常');
+        return;
+      }
       appendLog('串口连接测试成功！');
       alert('串口连接测试成功！');
     } catch (error) {
@@ -100,7 +112,15 @@ export default function SerialConfig() {
       appendLog('真实模式下需要选择串口才能连接');
       return;
     }
-    appendLog(`尝试连接${dataSourceType === 'real' ? `串口: ${selectedPort} @ ${baudRate}` : '测试数据生成器'}`);
+    const sourceLabels: Record<string, string> = {
+      real: `串口: ${selectedPort} @ ${baudRate}`,
+      udp: 'UDP数据源',
+      tcp: `远程串口 @ ${baudRate}`,
+      hid: 'USB HID设备',
+      file: '记录文件',
+      playback: '录制回放',
+    };
+    appendLog(`尝试连接${sourceLabels[dataSourceType] ?? '测试数据生成器'}`);
     try {
       await invoke('connect_serial', {
         portName: selectedPort,
@@ -156,6 +176,26 @@ export default function SerialConfig() {
               <RadioGroupItem value="test" id="test" />
               <Label htmlFor="test">测试模拟数据</Label>
             </div>
+            <div className="flex items-center space-x-2">
+              <RadioGroupItem value="udp" id="udp" />
+              <Label htmlFor="udp">UDP网络数据</Label>
+            </div>
+            <div className="flex items-center space-x-2">
+              <RadioGroupItem value="tcp" id="tcp" />
+              <Label htmlFor="tcp">远程串口（ser2net）</Label>
+            </div>
+            <div className="flex items-center space-x-2">
+              <RadioGroupItem value="hid" id="hid" />
+              <Label htmlFor="hid">USB HID设备</Label>
+            </div>
+            <div className="flex items-center space-x-2">
+              <RadioGroupItem value="file" id="file" />
+              <Label htmlFor="file">记录文件</Label>
+            </div>
+            <div className="flex items-center space-x-2">
+              <RadioGroupItem value="playback" id="playback" />
+              <Label htmlFor="playback">录制回放</Label>
+            </div>
           </RadioGroup>
         </div>
 
@@ -18,11 +18,11 @@ interface PatientInfoData {
   weight: number;
   phone: string;
   address: string;
-  emergency_contact: string;
-  blood_type: string;
-  allergies: string[];
-  medical_history: string[];
-  last_checkup: string;
+  emergencyContact: string;
+  bloodType: string;
+  allergies: { display: string }[];
+  medicalHistory: { display: string }[];
+  lastCheckup: string;
 }
 
 const Home = () => {
@@ -83,11 +83,11 @@ const Home = () => {
               weight={patientData.weight}
               phone={patientData.phone}
               address={patientData.address}
-              emergencyContact={patientData.emergency_contact}
-              bloodType={patientData.blood_type}
-              allergies={patientData.allergies}
-              medicalHistory={patientData.medical_history}
-              lastCheckup={patientData.last_checkup}
+              emergencyContact={patientData.emergencyContact}
+              bloodType={patientData.bloodType}
+              allergies={patientData.allergies.map(entry => entry.display)}
+              medicalHistory={patientData.medicalHistory.map(entry => entry.display)}
+              lastCheckup={patientData.lastCheckup}
             />
           ) : (
             <PatientInfo />